@@ -0,0 +1,165 @@
+//! On-disk cache of parsed charts.
+//!
+//! Parsing a chart file on every selection and every play is wasted work, so
+//! the parsed notes, tempo changes and audio path are stored as `bincode` in
+//! `data/c/{hash}.c`. An entry is only used while the chart file keeps the
+//! size and modification time it had when it was parsed; otherwise the chart
+//! is parsed again and the entry replaced.
+
+use crate::models::engine::load_error::validate_chart;
+use crate::models::engine::{MapLoadError, NoteData, TimingData, load_map};
+use crate::models::formats::{ChartFormat, sm};
+use crate::models::settings::{BmsScratchMode, ChartRules, OsuConvertRules};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Base directory for cached charts.
+const CHART_DIR: &str = "data/c";
+
+/// Bumped whenever the parsers or `NoteData` change, to drop older entries.
+const CACHE_VERSION: u32 = 2;
+
+/// A parsed chart, as loaded by [`load_map`].
+pub type LoadedChart = (PathBuf, Vec<NoteData>, TimingData);
+
+/// Size and modification time (ns) of a chart file.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct SourceStamp {
+    len: u64,
+    modified_ns: u128,
+}
+
+impl SourceStamp {
+    fn of(chart_path: &Path) -> Option<Self> {
+        // `.sm` charts are addressed as `song.sm#N`.
+        let file = if ChartFormat::from_path(chart_path) == Some(ChartFormat::Sm) {
+            sm::split_chart_path(chart_path).0
+        } else {
+            chart_path.to_path_buf()
+        };
+        let metadata = fs::metadata(file).ok()?;
+        let modified_ns = metadata
+            .modified()
+            .ok()?
+            .duration_since(UNIX_EPOCH)
+            .ok()?
+            .as_nanos();
+        Some(Self {
+            len: metadata.len(),
+            modified_ns,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedChart {
+    version: u32,
+    source: SourceStamp,
+    /// BMS charts depend on the scratch lane setting.
+    bms_scratch: BmsScratchMode,
+    /// osu! charts depend on the mine and burst conversion rules.
+    osu_convert: OsuConvertRules,
+    audio_path: PathBuf,
+    notes: Vec<NoteData>,
+    timing: TimingData,
+}
+
+/// Get the path of the cache entry of a chart.
+pub fn chart_cache_path(hash: &str) -> PathBuf {
+    PathBuf::from(CHART_DIR).join(format!("{}.c", hash))
+}
+
+/// Loads the chart `hash` stored at `path`, from the cache when it is still
+/// valid. Otherwise the file is parsed with `rules` and the cache entry
+/// rewritten.
+pub fn load_chart(hash: &str, path: &Path, rules: ChartRules) -> Result<LoadedChart, MapLoadError> {
+    let source = SourceStamp::of(path);
+    let ChartRules {
+        bms_scratch,
+        osu_convert,
+    } = rules;
+
+    if let Some(source) = source
+        && let Some(cached) = read_entry(hash)
+        && cached.version == CACHE_VERSION
+        && cached.source == source
+        && cached.bms_scratch == bms_scratch
+        && cached.osu_convert == osu_convert
+    {
+        // The audio file may have been removed since the entry was written.
+        validate_chart(&cached.audio_path, &cached.notes)?;
+        return Ok((cached.audio_path, cached.notes, cached.timing));
+    }
+
+    let (audio_path, notes, timing) = load_map(path.to_path_buf(), rules)?;
+    let Some(source) = source else {
+        return Ok((audio_path, notes, timing));
+    };
+    let entry = CachedChart {
+        version: CACHE_VERSION,
+        source,
+        bms_scratch,
+        osu_convert,
+        audio_path,
+        notes,
+        timing,
+    };
+    if let Err(e) = write_entry(hash, &entry) {
+        log::warn!("CHART CACHE: Could not store {}: {}", hash, e);
+    }
+    Ok((entry.audio_path, entry.notes, entry.timing))
+}
+
+fn read_entry(hash: &str) -> Option<CachedChart> {
+    let bytes = fs::read(chart_cache_path(hash)).ok()?;
+    bincode::serde::decode_from_slice(&bytes, bincode::config::standard())
+        .map(|(entry, _)| entry)
+        .ok()
+}
+
+fn write_entry(hash: &str, entry: &CachedChart) -> std::io::Result<()> {
+    fs::create_dir_all(CHART_DIR)?;
+    let bytes = bincode::serde::encode_to_vec(entry, bincode::config::standard())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+    // Write then rename, so a crash never leaves a truncated entry behind.
+    let path = chart_cache_path(hash);
+    let partial = path.with_extension("c.part");
+    fs::write(&partial, bytes)?;
+    fs::rename(partial, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_round_trip() {
+        let entry = CachedChart {
+            version: CACHE_VERSION,
+            source: SourceStamp {
+                len: 42,
+                modified_ns: 1_700_000_000_000_000_000,
+            },
+            bms_scratch: BmsScratchMode::default(),
+            osu_convert: OsuConvertRules::default(),
+            audio_path: PathBuf::from("songs/set/audio.mp3"),
+            notes: vec![
+                NoteData::tap(1000.0, 0),
+                NoteData::hold(1500.0, 3, 250.0).with_keysound(Some("kick.wav".into())),
+            ],
+            timing: TimingData::default(),
+        };
+        let hash = "test_chart_cache_hash";
+
+        write_entry(hash, &entry).unwrap();
+        let loaded = read_entry(hash).unwrap();
+        assert_eq!(loaded.source, entry.source);
+        assert_eq!(loaded.notes.len(), 2);
+        assert!(loaded.notes[1].note_type.is_hold());
+        assert_eq!(loaded.notes[1].keysound.as_deref(), Some("kick.wav"));
+
+        fs::remove_file(chart_cache_path(hash)).unwrap();
+    }
+}
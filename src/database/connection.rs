@@ -1,18 +1,19 @@
 //! Database connection helpers built on top of sqlx/SQLite.
 
-use crate::database::models::{BeatmapRating, BeatmapWithRatings, Beatmapset};
-use crate::database::query;
+use crate::database::models::{
+    BeatmapRating, BeatmapWithRatings, Beatmapset, CourseWithCharts, GoalWithCharts, LaneStats,
+    MetadataEdit, Profile,
+};
+use crate::database::{migrations, query};
+use crate::difficulty::BeatmapSsr;
+use crate::models::course::CourseDraft;
+use crate::models::goal::{CourseStagePlay, GoalDraft};
+use crate::models::replay::ColumnStats;
 use crate::models::search::MenuSearchFilters;
 use sqlx::{SqlitePool, sqlite::SqliteConnectOptions};
 use std::path::{Path, PathBuf};
 
-const MIGRATION_CREATE_BEATMAPSET: &str = include_str!("migrations/001_create_beatmapset.sql");
-const MIGRATION_CREATE_BEATMAP: &str = include_str!("migrations/002_create_beatmap.sql");
-const MIGRATION_CREATE_REPLAY: &str = include_str!("migrations/003_create_replay.sql");
-const MIGRATION_CREATE_BEATMAP_RATING: &str =
-    include_str!("migrations/005_create_beatmap_rating.sql");
-const MIGRATION_REPLAY_FILE_STORAGE: &str = include_str!("migrations/006_replay_file_storage.sql");
-
+#[derive(Clone)]
 pub struct Database {
     pool: SqlitePool,
 }
@@ -52,33 +53,9 @@ impl Database {
         Ok(db)
     }
 
-    /// Creates missing tables by replaying the embedded migrations.
+    /// Upgrades the schema to the latest version (see `database::migrations`).
     async fn init_schema(&self) -> Result<(), sqlx::Error> {
-        for migration in [
-            MIGRATION_CREATE_BEATMAPSET,
-            MIGRATION_CREATE_BEATMAP,
-            MIGRATION_CREATE_REPLAY,
-            MIGRATION_CREATE_BEATMAP_RATING,
-        ] {
-            sqlx::query(migration).execute(&self.pool).await?;
-        }
-
-        // Conditional migration: Replay File Storage
-        // This migration drops the 'replay' table, so we MUST NOT run it
-        // if the table is already in the new format (has 'file_path' column).
-        let has_file_path: Option<i32> = sqlx::query_scalar(
-            "SELECT 1 FROM pragma_table_info('replay') WHERE name = 'file_path'",
-        )
-        .fetch_optional(&self.pool)
-        .await?;
-
-        if has_file_path.is_none() {
-            log::info!("DB: Applying migration MIGRATION_REPLAY_FILE_STORAGE");
-            sqlx::query(MIGRATION_REPLAY_FILE_STORAGE)
-                .execute(&self.pool)
-                .await?;
-        }
-
+        migrations::run(&self.pool).await?;
         Ok(())
     }
 
@@ -99,8 +76,53 @@ impl Database {
         image_path: Option<&str>,
         artist: Option<&str>,
         title: Option<&str>,
+        creator: Option<&str>,
     ) -> Result<i64, sqlx::Error> {
-        query::insert_beatmapset(&self.pool, path, image_path, artist, title).await
+        query::insert_beatmapset(
+            &mut *self.pool.acquire().await?,
+            path,
+            image_path,
+            artist,
+            title,
+            creator,
+        )
+        .await
+    }
+
+    /// Archives or restores a beatmapset.
+    pub async fn set_beatmapset_archived(
+        &self,
+        beatmapset_id: i64,
+        archived: bool,
+    ) -> Result<(), sqlx::Error> {
+        query::set_beatmapset_archived(&self.pool, beatmapset_id, archived).await
+    }
+
+    /// Stores or drops the player's metadata edit of a beatmapset.
+    pub async fn set_metadata_override(
+        &self,
+        beatmapset_id: i64,
+        metadata: Option<&MetadataEdit>,
+    ) -> Result<(), sqlx::Error> {
+        query::set_metadata_override(&self.pool, beatmapset_id, metadata).await
+    }
+
+    /// Flags a chart as failing to load, or clears the flag.
+    pub async fn set_beatmap_load_error(
+        &self,
+        hash: &str,
+        load_error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        query::set_beatmap_load_error(&self.pool, hash, load_error).await
+    }
+
+    /// Stores the dominant patterns of a chart.
+    pub async fn set_beatmap_pattern_tags(
+        &self,
+        hash: &str,
+        pattern_tags: &str,
+    ) -> Result<(), sqlx::Error> {
+        query::set_beatmap_pattern_tags(&self.pool, hash, pattern_tags).await
     }
 
     /// Inserts or updates a beatmap row.
@@ -113,9 +135,10 @@ impl Database {
         note_count: i32,
         duration_ms: i32,
         nps: f64,
+        key_count: i32,
     ) -> Result<String, sqlx::Error> {
         query::insert_beatmap(
-            &self.pool,
+            &mut *self.pool.acquire().await?,
             beatmapset_id,
             hash,
             path,
@@ -123,6 +146,7 @@ impl Database {
             note_count,
             duration_ms,
             nps,
+            key_count,
         )
         .await
     }
@@ -135,6 +159,32 @@ impl Database {
         query::get_ratings_for_beatmap(&self.pool, beatmap_hash).await
     }
 
+    /// Stores the rating of a beatmap for one calculator.
+    pub async fn insert_beatmap_rating(
+        &self,
+        beatmap_hash: &str,
+        name: &str,
+        ssr: &BeatmapSsr,
+    ) -> Result<(), sqlx::Error> {
+        query::insert_beatmap_rating(&self.pool, beatmap_hash, name, ssr).await
+    }
+
+    /// Hash and path of the charts a calculator has not rated yet.
+    pub async fn get_unrated_beatmaps(
+        &self,
+        name: &str,
+    ) -> Result<Vec<(String, String)>, sqlx::Error> {
+        query::get_unrated_beatmaps(&self.pool, name).await
+    }
+
+    /// Key counts and overall ratings of the library, plus its chart count.
+    pub async fn get_library_ratings(
+        &self,
+        name: &str,
+    ) -> Result<(Vec<(i32, f64)>, i64), sqlx::Error> {
+        query::get_library_ratings(&self.pool, name).await
+    }
+
     /// Fetches all ratings across every beatmap.
     pub async fn get_all_beatmap_ratings(&self) -> Result<Vec<BeatmapRating>, sqlx::Error> {
         query::get_all_beatmap_ratings(&self.pool).await
@@ -169,6 +219,7 @@ impl Database {
         max_combo: i32,
         rate: f64,
         data: &crate::models::replay::ReplayData,
+        profile_id: i64,
     ) -> Result<String, sqlx::Error> {
         query::insert_replay(
             &self.pool,
@@ -179,15 +230,165 @@ impl Database {
             max_combo,
             rate,
             data,
+            profile_id,
         )
         .await
     }
 
-    /// Retrieves all replays for a given beatmap hash.
+    /// Retrieves a profile's replays for a given beatmap hash.
     pub async fn get_replays_for_beatmap(
         &self,
         beatmap_hash: &str,
+        profile_id: i64,
+    ) -> Result<Vec<crate::database::models::Replay>, sqlx::Error> {
+        query::get_replays_for_beatmap(&self.pool, beatmap_hash, profile_id).await
+    }
+
+    /// Retrieves every replay of a profile.
+    pub async fn get_all_replays(
+        &self,
+        profile_id: i64,
     ) -> Result<Vec<crate::database::models::Replay>, sqlx::Error> {
-        query::get_replays_for_beatmap(&self.pool, beatmap_hash).await
+        query::get_all_replays(&self.pool, profile_id).await
+    }
+
+    /// Deletes replay rows by hash.
+    pub async fn delete_replays(&self, hashes: &[String]) -> Result<(), sqlx::Error> {
+        query::delete_replays(&self.pool, hashes).await
+    }
+
+    /// Compacts the database file.
+    pub async fn vacuum(&self) -> Result<(), sqlx::Error> {
+        query::vacuum(&self.pool).await
+    }
+
+    /// Records the hit window and practice flag of an older replay.
+    pub async fn set_replay_judge(
+        &self,
+        hash: &str,
+        judge: &str,
+        practice: bool,
+    ) -> Result<(), sqlx::Error> {
+        query::set_replay_judge(&self.pool, hash, judge, practice).await
+    }
+
+    /// Stores the per-column breakdown of a saved replay.
+    pub async fn insert_replay_columns(
+        &self,
+        replay_hash: &str,
+        columns: &[ColumnStats],
+    ) -> Result<(), sqlx::Error> {
+        query::insert_replay_columns(&self.pool, replay_hash, columns).await
+    }
+
+    /// Per-lane judgements and offsets of a profile's saved plays.
+    pub async fn get_lane_stats(&self, profile_id: i64) -> Result<Vec<LaneStats>, sqlx::Error> {
+        query::get_lane_stats(&self.pool, profile_id).await
+    }
+
+    /// Average rating of a profile's latest cleared scores with a calculator.
+    pub async fn get_recent_score_rating(
+        &self,
+        name: &str,
+        profile_id: i64,
+        min_accuracy: f64,
+        limit: i64,
+    ) -> Result<Option<f64>, sqlx::Error> {
+        query::get_recent_score_rating(&self.pool, name, profile_id, min_accuracy, limit).await
+    }
+
+    // ========================================================================
+    // GOAL METHODS
+    // ========================================================================
+
+    /// Persists a new goal and returns its id.
+    pub async fn insert_goal(
+        &self,
+        draft: &GoalDraft,
+        created_at: i64,
+        profile_id: i64,
+    ) -> Result<i64, sqlx::Error> {
+        query::insert_goal(&self.pool, draft, created_at, profile_id).await
+    }
+
+    /// Removes a goal and its progress.
+    pub async fn delete_goal(&self, goal_id: i64) -> Result<(), sqlx::Error> {
+        query::delete_goal(&self.pool, goal_id).await
+    }
+
+    /// Fetches every goal of a profile with its charts.
+    pub async fn get_all_goals(&self, profile_id: i64) -> Result<Vec<GoalWithCharts>, sqlx::Error> {
+        query::get_all_goals(&self.pool, profile_id).await
+    }
+
+    /// Completes the course-style goals a course run played back to back;
+    /// returns how many there were.
+    pub async fn record_course_goal_progress(
+        &self,
+        stages: &[CourseStagePlay],
+        rate: f64,
+        timestamp: i64,
+        profile_id: i64,
+    ) -> Result<u64, sqlx::Error> {
+        query::record_course_goal_progress(&self.pool, stages, rate, timestamp, profile_id).await
+    }
+
+    /// Updates goal progress after a play; returns how many goals were completed.
+    pub async fn record_goal_progress(
+        &self,
+        beatmap_hash: &str,
+        accuracy: f64,
+        score: i64,
+        rate: f64,
+        timestamp: i64,
+        profile_id: i64,
+    ) -> Result<u64, sqlx::Error> {
+        query::record_goal_progress(
+            &self.pool,
+            beatmap_hash,
+            accuracy,
+            score,
+            rate,
+            timestamp,
+            profile_id,
+        )
+        .await
+    }
+
+    // ========================================================================
+    // COURSE METHODS
+    // ========================================================================
+
+    /// Persists a new course and returns its id.
+    pub async fn insert_course(
+        &self,
+        draft: &CourseDraft,
+        created_at: i64,
+    ) -> Result<i64, sqlx::Error> {
+        query::insert_course(&self.pool, draft, created_at).await
+    }
+
+    /// Removes a course and its chart list.
+    pub async fn delete_course(&self, course_id: i64) -> Result<(), sqlx::Error> {
+        query::delete_course(&self.pool, course_id).await
+    }
+
+    /// Fetches every course with its charts.
+    pub async fn get_all_courses(&self) -> Result<Vec<CourseWithCharts>, sqlx::Error> {
+        query::get_all_courses(&self.pool).await
+    }
+
+    // ========================================================================
+    // PROFILE METHODS
+    // ========================================================================
+
+    /// Creates a profile and returns its id.
+    pub async fn insert_profile(&self, name: &str, created_at: i64) -> Result<i64, sqlx::Error> {
+        query::insert_profile(&self.pool, name, created_at).await
+    }
+
+    /// Fetches every profile.
+    pub async fn get_all_profiles(&self) -> Result<Vec<Profile>, sqlx::Error> {
+        query::get_all_profiles(&self.pool).await
     }
 }
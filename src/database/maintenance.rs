@@ -0,0 +1,138 @@
+//! Library maintenance: compacting the database and pruning old replays.
+//!
+//! Pruning runs in two steps: a preview counts the replays the rules would
+//! delete and the space they take, and the player confirms it before
+//! anything is removed. Personal bests are never pruned.
+
+use crate::database::models::Replay;
+use crate::models::personal_best::personal_bests;
+use crate::models::settings::ReplayPruneRules;
+use std::collections::HashMap;
+
+/// Maintenance command picked in the settings.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MaintenanceRequest {
+    /// Compact the database file.
+    Vacuum,
+    /// Count what a prune with these rules would delete.
+    PreviewPrune(ReplayPruneRules),
+    /// Delete the replays matching the rules (after a preview).
+    Prune(ReplayPruneRules),
+    /// Hide the last report.
+    Dismiss,
+}
+
+/// Progress or outcome of the last maintenance command.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MaintenanceReport {
+    Running,
+    /// Replays a prune would delete, waiting for confirmation.
+    PrunePreview {
+        rules: ReplayPruneRules,
+        replays: usize,
+        bytes: u64,
+    },
+    Pruned {
+        replays: usize,
+        bytes: u64,
+    },
+    Vacuumed {
+        before: u64,
+        after: u64,
+    },
+    Failed(String),
+}
+
+/// Replays matching `rules` as of `now` (unix seconds). The personal best of
+/// every chart, rate and judge is kept, and so are replays whose judge is
+/// not known yet, since they may be one.
+pub fn replays_to_prune<'a>(
+    replays: &'a [Replay],
+    rules: &ReplayPruneRules,
+    now: i64,
+) -> Vec<&'a Replay> {
+    if !rules.is_enabled() {
+        return Vec::new();
+    }
+    let mut by_chart: HashMap<&str, Vec<Replay>> = HashMap::new();
+    for replay in replays {
+        by_chart
+            .entry(replay.beatmap_hash.as_str())
+            .or_default()
+            .push(replay.clone());
+    }
+    let kept: Vec<String> = by_chart
+        .values()
+        .flat_map(|chart_replays| personal_bests(chart_replays))
+        .collect();
+
+    replays
+        .iter()
+        .filter(|r| r.practice || r.judge.is_some())
+        .filter(|r| !kept.contains(&r.hash))
+        .filter(|r| rules.matches(r.accuracy, r.timestamp, now))
+        .collect()
+}
+
+/// "1.5 MB" style size for the reports.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replay(hash: &str, chart: &str, accuracy: f64, timestamp: i64) -> Replay {
+        Replay {
+            hash: hash.to_string(),
+            beatmap_hash: chart.to_string(),
+            timestamp,
+            score: (accuracy * 10_000.0) as i32,
+            accuracy,
+            max_combo: 100,
+            rate: 1.0,
+            file_path: String::new(),
+            judge: Some("J4".to_string()),
+            practice: false,
+        }
+    }
+
+    #[test]
+    fn pruning_keeps_personal_bests_and_unknown_judges() {
+        let now = 100 * 86_400;
+        let mut unknown = replay("unknown", "a", 50.0, now);
+        unknown.judge = None;
+        let replays = vec![
+            replay("best_a", "a", 80.0, 0),
+            replay("low_a", "a", 70.0, now),
+            replay("old_a", "a", 75.0, now - 40 * 86_400),
+            replay("best_b", "b", 60.0, now),
+            unknown,
+        ];
+        let rules = ReplayPruneRules {
+            below_accuracy: 72.0,
+            older_than_days: 30,
+        };
+
+        let mut pruned: Vec<&str> = replays_to_prune(&replays, &rules, now)
+            .iter()
+            .map(|r| r.hash.as_str())
+            .collect();
+        pruned.sort();
+        assert_eq!(pruned, vec!["low_a", "old_a"]);
+        assert!(replays_to_prune(&replays, &ReplayPruneRules::default(), now).is_empty());
+        assert_eq!(format_size(1536), "1.5 KB");
+    }
+}
@@ -1,15 +1,34 @@
 //! Database manager handling background operations.
 
 use crate::database::connection::Database;
-use crate::database::models::{BeatmapWithRatings, Beatmapset, Replay};
-use crate::database::query::{clear_all, get_all_beatmapsets};
-use crate::database::scanner::scan_songs_directory;
+use crate::database::maintenance::{MaintenanceReport, MaintenanceRequest, replays_to_prune};
+use crate::database::models::{
+    BeatmapWithRatings, Beatmapset, CourseWithCharts, DEFAULT_PROFILE_ID, GoalWithCharts,
+    LibraryStats, MetadataEdit, Profile, Replay,
+};
+use crate::database::query::{
+    clear_all, get_all_beatmapsets, get_archived_paths, restore_archived_paths,
+};
+use crate::database::replay_storage;
+use crate::database::scanner::{self, import_beatmapset_folder, scan_songs_directory};
+use crate::database::watcher::SongsWatcher;
+use crate::difficulty::pattern_tags;
+use crate::models::course::CourseDraft;
+use crate::models::goal::{CourseStagePlay, GoalDraft};
+use crate::models::personal_best::judge_key;
 use crate::models::search::MenuSearchFilters;
+use crate::models::settings::ScanOptions;
+use crate::shared::locale::{tr, tr_args};
+use crate::shared::notice::Notice;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 
+/// Scores averaged for map recommendations: the latest ones, cleared.
+const RECOMMEND_SCORE_COUNT: i64 = 20;
+const RECOMMEND_MIN_ACCURACY: f64 = 90.0;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum DbStatus {
     Idle,
@@ -29,6 +48,30 @@ pub struct DbState {
     pub leaderboard: Vec<Replay>,
     pub leaderboard_hash: Option<String>,
     pub leaderboard_version: u64,
+    /// Bumped when a search starts or is superseded; a search only stores its
+    /// result while its generation is still the current one.
+    pub search_generation: u64,
+    pub goals: Vec<GoalWithCharts>,
+    pub goals_version: u64,
+    pub courses: Vec<CourseWithCharts>,
+    pub courses_version: u64,
+    pub profiles: Vec<Profile>,
+    pub profiles_version: u64,
+    pub library_stats: Option<LibraryStats>,
+    /// `(rated, to_rate)` while the library overview is rating missing charts.
+    pub library_progress: Option<(usize, usize)>,
+    pub library_version: u64,
+    /// Folder of a set imported from a dropped archive; song select jumps to
+    /// it once the new beatmap list is loaded.
+    pub focus_set_path: Option<String>,
+    /// Messages for the player (import results), shown as toasts.
+    pub notices: Vec<Notice>,
+    /// Average rating of the profile's recent scores, for recommendations.
+    pub recent_rating: Option<f64>,
+    pub recent_rating_version: u64,
+    /// Last library maintenance command, shown in the settings.
+    pub maintenance: Option<MaintenanceReport>,
+    pub maintenance_version: u64,
 }
 
 impl DbState {
@@ -41,6 +84,22 @@ impl DbState {
             leaderboard: Vec::new(),
             leaderboard_hash: None,
             leaderboard_version: 0,
+            search_generation: 0,
+            goals: Vec::new(),
+            goals_version: 0,
+            courses: Vec::new(),
+            courses_version: 0,
+            profiles: Vec::new(),
+            profiles_version: 0,
+            library_stats: None,
+            library_progress: None,
+            library_version: 0,
+            focus_set_path: None,
+            notices: Vec::new(),
+            recent_rating: None,
+            recent_rating_version: 0,
+            maintenance: None,
+            maintenance_version: 0,
         }
     }
 }
@@ -54,16 +113,69 @@ pub struct SaveReplayCommand {
     pub max_combo: i32,
     pub rate: f64,
     pub data: crate::models::replay::ReplayData,
+    /// Judgements and offsets per column, stored next to the replay.
+    pub columns: Vec<crate::models::replay::ColumnStats>,
 }
 
 #[derive(Debug)]
 pub enum DbCommand {
     Init,
     Load,
+    /// Full rescan: clears the library and re-hashes every chart.
     Rescan,
+    /// Only re-imports new or modified folders and drops removed ones.
+    IncrementalScan,
     Search(MenuSearchFilters),
     SaveReplay(SaveReplayCommand),
     FetchLeaderboard(String),
+    SetArchived {
+        beatmapset_id: i64,
+        archived: bool,
+        filters: MenuSearchFilters,
+    },
+    /// Edits the metadata of a set (`None` goes back to the scanned values).
+    SetMetadata {
+        beatmapset_id: i64,
+        metadata: Option<MetadataEdit>,
+        filters: MenuSearchFilters,
+    },
+    /// Flags a chart that failed to load (`None` clears the flag).
+    SetLoadError {
+        beatmap_hash: String,
+        load_error: Option<String>,
+        filters: MenuSearchFilters,
+    },
+    /// Stores the dominant patterns of a chart rated on demand.
+    SetPatternTags {
+        beatmap_hash: String,
+        pattern_tags: String,
+    },
+    CreateGoal(GoalDraft),
+    DeleteGoal(i64),
+    /// A course run ended: completes the course-style goals it played.
+    RecordCourseRun {
+        stages: Vec<CourseStagePlay>,
+        rate: f64,
+    },
+    CreateCourse(CourseDraft),
+    DeleteCourse(i64),
+    /// Switch the profile replays and goals are read from / written to.
+    SetProfile(i64),
+    CreateProfile(String),
+    /// Rate the unrated charts with a calculator, then build the library overview.
+    LoadLibraryStats(String),
+    /// Average the profile's recent score ratings with a calculator.
+    LoadRecentRating(String),
+    /// Import a single new beatmapset folder (e.g. a downloaded set).
+    ImportFolder(PathBuf),
+    /// Extract a dropped `.osz` / `.zip` into the songs folder and import it.
+    ImportArchive(PathBuf),
+    /// Replace the library folders, then bring the library in line with them.
+    SetLibraryRoots(Vec<PathBuf>),
+    /// Chart rules and thread count used by the next scans and imports.
+    SetScanOptions(ScanOptions),
+    /// Compact the database or prune replays.
+    Maintenance(MaintenanceRequest),
     Shutdown,
 }
 
@@ -71,24 +183,32 @@ pub struct DbManager {
     state: Arc<Mutex<DbState>>,
     command_sender: std::sync::mpsc::Sender<DbCommand>,
     _handle: thread::JoinHandle<()>,
+    /// Queues incremental scans when a library folder changes on disk.
+    watcher: Option<SongsWatcher>,
 }
 
 impl DbManager {
-    pub fn new(db_path: PathBuf, songs_path: PathBuf) -> Self {
+    /// `roots` are the library folders (at least one); the first one
+    /// receives imports. `scan` applies to scans and imports until replaced
+    /// with `set_scan_options`.
+    pub fn new(db_path: PathBuf, roots: Vec<PathBuf>, scan: ScanOptions) -> Self {
         let state = Arc::new(Mutex::new(DbState::new()));
         let (tx, rx) = std::sync::mpsc::channel();
 
+        let watcher = SongsWatcher::start(&roots, tx.clone());
+
         let state_clone = Arc::clone(&state);
         let handle = thread::spawn(move || {
             let rt = tokio::runtime::Runtime::new()
                 .expect("Failed to create tokio runtime for database thread");
-            rt.block_on(Self::db_thread(state_clone, rx, db_path, songs_path));
+            rt.block_on(Self::db_thread(state_clone, rx, db_path, roots, scan));
         });
 
         Self {
             state,
             command_sender: tx,
             _handle: handle,
+            watcher,
         }
     }
 
@@ -96,9 +216,16 @@ impl DbManager {
         state: Arc<Mutex<DbState>>,
         rx: std::sync::mpsc::Receiver<DbCommand>,
         db_path: PathBuf,
-        songs_path: PathBuf,
+        mut roots: Vec<PathBuf>,
+        mut scan: ScanOptions,
     ) {
         let mut db: Option<Database> = None;
+        // Search running in the background, aborted when superseded. Its
+        // generation keeps a result that still lands from overwriting newer data.
+        let mut search_task: Option<tokio::task::JoinHandle<()>> = None;
+        let mut profile_id = DEFAULT_PROFILE_ID;
+        // Library overview job (rates the whole library, can take minutes).
+        let mut library_task: Option<tokio::task::JoinHandle<()>> = None;
 
         loop {
             // Check commands without blocking the loop.
@@ -122,6 +249,12 @@ impl DbManager {
                             if db_path.exists() {
                                 Self::load_maps(&state, db.as_ref().unwrap()).await;
                             }
+                            Self::load_goals(&state, db.as_ref().unwrap(), profile_id).await;
+                            Self::load_profiles(&state, db.as_ref().unwrap()).await;
+                            Self::load_courses(&state, db.as_ref().unwrap()).await;
+                            // Pick up folders added or removed while the game was closed.
+                            Self::incremental_scan(&state, db.as_ref().unwrap(), &roots, scan)
+                                .await;
                         }
                         Err(e) => {
                             let mut s = state.lock().unwrap();
@@ -131,34 +264,252 @@ impl DbManager {
                     }
                 }
                 Ok(DbCommand::Load) => {
+                    Self::cancel_search(&state, &mut search_task);
                     if let Some(ref d) = db {
                         Self::load_maps(&state, d).await;
                     }
                 }
                 Ok(DbCommand::Rescan) => {
+                    Self::cancel_search(&state, &mut search_task);
+                    // The rescan wipes the beatmap and rating tables under it.
+                    Self::cancel_library_job(&state, &mut library_task);
+                    if let Some(ref d) = db {
+                        Self::rescan_maps(&state, d, &roots, scan).await;
+                    }
+                }
+                Ok(DbCommand::IncrementalScan) => {
                     if let Some(ref d) = db {
-                        Self::rescan_maps(&state, d, &songs_path).await;
+                        Self::cancel_search(&state, &mut search_task);
+                        Self::incremental_scan(&state, d, &roots, scan).await;
                     }
                 }
+                Ok(DbCommand::SetLibraryRoots(new_roots)) => {
+                    log::info!("DB: Library folders set to {:?}", new_roots);
+                    roots = new_roots;
+                    if let Some(ref d) = db {
+                        Self::cancel_search(&state, &mut search_task);
+                        Self::incremental_scan(&state, d, &roots, scan).await;
+                    }
+                }
+                Ok(DbCommand::SetScanOptions(options)) => {
+                    log::info!("DB: Scan options set to {:?}", options);
+                    scan = options;
+                }
                 Ok(DbCommand::Search(filters)) => {
+                    Self::cancel_search(&state, &mut search_task);
                     if let Some(ref d) = db {
-                        Self::search_maps(&state, d, filters).await;
+                        // The new list may not hold the chart of the shown
+                        // leaderboard; clear it now so a leaderboard fetched
+                        // while the search runs is kept.
+                        let generation = {
+                            let mut s = state.lock().unwrap();
+                            s.leaderboard.clear();
+                            s.leaderboard_hash = None;
+                            s.leaderboard_version = s.leaderboard_version.wrapping_add(1);
+                            s.search_generation
+                        };
+                        let state = Arc::clone(&state);
+                        let d = d.clone();
+                        search_task = Some(tokio::spawn(async move {
+                            Self::search_maps(&state, &d, filters, generation).await;
+                        }));
+                    }
+                }
+                Ok(DbCommand::Maintenance(request)) => {
+                    if let Some(ref d) = db {
+                        Self::run_maintenance(&state, d, &db_path, request, profile_id).await;
                     }
                 }
                 Ok(DbCommand::SaveReplay(payload)) => {
                     if let Some(ref d) = db {
                         log::info!("DB: Saving replay for beatmap {}", payload.beatmap_hash);
-                        Self::persist_replay(&state, d, payload).await;
+                        Self::persist_replay(&state, d, payload, profile_id).await;
                     } else {
                         log::error!("DB: Cannot save replay - database not initialized!");
                     }
                 }
                 Ok(DbCommand::FetchLeaderboard(hash)) => {
                     if let Some(ref d) = db {
-                        Self::load_leaderboard(&state, d, &hash).await;
+                        Self::load_leaderboard(&state, d, &hash, profile_id).await;
+                    }
+                }
+                Ok(DbCommand::SetArchived {
+                    beatmapset_id,
+                    archived,
+                    filters,
+                }) => {
+                    Self::cancel_search(&state, &mut search_task);
+                    if let Some(ref d) = db {
+                        if let Err(e) = d.set_beatmapset_archived(beatmapset_id, archived).await {
+                            log::error!(
+                                "DB: failed to update archive flag for set {}: {}",
+                                beatmapset_id,
+                                e
+                            );
+                        }
+                        // Refresh whichever view the menu is currently showing.
+                        if filters.is_active() {
+                            let generation = state.lock().unwrap().search_generation;
+                            Self::search_maps(&state, d, filters, generation).await;
+                        } else {
+                            Self::load_maps(&state, d).await;
+                        }
+                    }
+                }
+                Ok(DbCommand::SetMetadata {
+                    beatmapset_id,
+                    metadata,
+                    filters,
+                }) => {
+                    Self::cancel_search(&state, &mut search_task);
+                    if let Some(ref d) = db {
+                        if let Err(e) = d
+                            .set_metadata_override(beatmapset_id, metadata.as_ref())
+                            .await
+                        {
+                            log::error!(
+                                "DB: failed to edit the metadata of set {}: {}",
+                                beatmapset_id,
+                                e
+                            );
+                        }
+                        if filters.is_active() {
+                            let generation = state.lock().unwrap().search_generation;
+                            Self::search_maps(&state, d, filters, generation).await;
+                        } else {
+                            Self::load_maps(&state, d).await;
+                        }
+                    }
+                }
+                Ok(DbCommand::SetLoadError {
+                    beatmap_hash,
+                    load_error,
+                    filters,
+                }) => {
+                    Self::cancel_search(&state, &mut search_task);
+                    if let Some(ref d) = db {
+                        if let Err(e) = d
+                            .set_beatmap_load_error(&beatmap_hash, load_error.as_deref())
+                            .await
+                        {
+                            log::error!(
+                                "DB: failed to update load error of {}: {}",
+                                beatmap_hash,
+                                e
+                            );
+                        }
+                        if filters.is_active() {
+                            let generation = state.lock().unwrap().search_generation;
+                            Self::search_maps(&state, d, filters, generation).await;
+                        } else {
+                            Self::load_maps(&state, d).await;
+                        }
+                    }
+                }
+                Ok(DbCommand::SetPatternTags {
+                    beatmap_hash,
+                    pattern_tags,
+                }) => {
+                    if let Some(ref d) = db {
+                        Self::store_pattern_tags(&state, d, &beatmap_hash, &pattern_tags).await;
+                    }
+                }
+                Ok(DbCommand::CreateGoal(draft)) => {
+                    if let Some(ref d) = db {
+                        Self::persist_goal(&state, d, draft, profile_id).await;
+                    }
+                }
+                Ok(DbCommand::DeleteGoal(goal_id)) => {
+                    if let Some(ref d) = db {
+                        if let Err(e) = d.delete_goal(goal_id).await {
+                            log::error!("DB: failed to delete goal {}: {}", goal_id, e);
+                        }
+                        Self::load_goals(&state, d, profile_id).await;
+                    }
+                }
+                Ok(DbCommand::RecordCourseRun { stages, rate }) => {
+                    if let Some(ref d) = db {
+                        Self::track_course_goals(&state, d, &stages, rate, profile_id).await;
+                    }
+                }
+                Ok(DbCommand::CreateCourse(draft)) => {
+                    if let Some(ref d) = db {
+                        Self::persist_course(&state, d, draft).await;
+                    }
+                }
+                Ok(DbCommand::DeleteCourse(course_id)) => {
+                    if let Some(ref d) = db {
+                        if let Err(e) = d.delete_course(course_id).await {
+                            log::error!("DB: failed to delete course {}: {}", course_id, e);
+                        }
+                        Self::load_courses(&state, d).await;
+                    }
+                }
+                Ok(DbCommand::SetProfile(id)) => {
+                    profile_id = id;
+                    if let Some(ref d) = db {
+                        Self::load_goals(&state, d, profile_id).await;
+                    }
+                    // The shown scores belong to the previous profile.
+                    let mut s = state.lock().unwrap();
+                    s.leaderboard.clear();
+                    s.leaderboard_hash = None;
+                    s.leaderboard_version = s.leaderboard_version.wrapping_add(1);
+                }
+                Ok(DbCommand::CreateProfile(name)) => {
+                    if let Some(ref d) = db {
+                        Self::persist_profile(&state, d, &name).await;
+                    }
+                }
+                Ok(DbCommand::LoadLibraryStats(calculator_id)) => {
+                    Self::cancel_library_job(&state, &mut library_task);
+                    if let Some(ref d) = db {
+                        let state = Arc::clone(&state);
+                        let d = d.clone();
+                        library_task = Some(tokio::spawn(async move {
+                            Self::compute_library_stats(&state, &d, &calculator_id, profile_id)
+                                .await;
+                        }));
+                    }
+                }
+                Ok(DbCommand::LoadRecentRating(calculator_id)) => {
+                    if let Some(ref d) = db {
+                        Self::load_recent_rating(&state, d, &calculator_id, profile_id).await;
+                    }
+                }
+                Ok(DbCommand::ImportFolder(folder)) => {
+                    Self::cancel_search(&state, &mut search_task);
+                    if let Some(ref d) = db {
+                        let name = folder
+                            .file_name()
+                            .map(|n| n.to_string_lossy().into_owned())
+                            .unwrap_or_default();
+                        let notice = match import_beatmapset_folder(d, &folder, scan.rules).await {
+                            Ok(_) => Notice::success(tr_args(
+                                "notice.added_to_library",
+                                &[("name", &name)],
+                            )),
+                            Err(e) => {
+                                log::error!("DB: failed to import {:?}: {}", folder, e);
+                                Notice::error(tr_args(
+                                    "notice.import_failed",
+                                    &[("name", &name), ("error", &e)],
+                                ))
+                            }
+                        };
+                        state.lock().unwrap().notices.push(notice);
+                        Self::load_maps(&state, d).await;
+                    }
+                }
+                Ok(DbCommand::ImportArchive(archive)) => {
+                    Self::cancel_search(&state, &mut search_task);
+                    if let Some(ref d) = db {
+                        Self::import_dropped_archive(&state, d, &archive, &roots[0], scan).await;
                     }
                 }
                 Ok(DbCommand::Shutdown) => {
+                    Self::cancel_search(&state, &mut search_task);
+                    Self::cancel_library_job(&state, &mut library_task);
                     break;
                 }
                 Err(std::sync::mpsc::TryRecvError::Empty) => {
@@ -201,7 +552,13 @@ impl DbManager {
         }
     }
 
-    async fn rescan_maps(state: &Arc<Mutex<DbState>>, db: &Database, songs_path: &Path) {
+    async fn import_dropped_archive(
+        state: &Arc<Mutex<DbState>>,
+        db: &Database,
+        archive: &Path,
+        songs_path: &Path,
+        scan: ScanOptions,
+    ) {
         {
             let mut s = state.lock().unwrap();
             s.status = DbStatus::Scanning {
@@ -211,54 +568,187 @@ impl DbManager {
             s.error = None;
         }
 
-        // Clear the in-memory view first.
-        if let Err(e) = clear_all(db.pool()).await {
-            let mut s = state.lock().unwrap();
-            s.status = DbStatus::Error(format!("Error clearing database: {}", e));
-            s.error = Some(format!("{}", e));
-            return;
+        let mut progress = |current: usize, total: usize| {
+            state.lock().unwrap().status = DbStatus::Scanning { current, total };
+        };
+        let imported = scanner::import_archive(db, archive, songs_path, scan, &mut progress).await;
+
+        let archive_name = archive
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        match imported {
+            Ok(folder) => {
+                log::info!("DB: Imported {:?} into {:?}", archive, folder);
+                let mut s = state.lock().unwrap();
+                s.focus_set_path = folder.to_str().map(str::to_string);
+                s.notices.push(Notice::success(tr_args(
+                    "notice.imported",
+                    &[("name", &archive_name)],
+                )));
+            }
+            Err(e) => {
+                log::error!("DB: Failed to import {:?}: {}", archive, e);
+                let mut s = state.lock().unwrap();
+                s.status = DbStatus::Idle;
+                s.notices.push(Notice::error(tr_args(
+                    "notice.import_failed",
+                    &[("name", &archive_name), ("error", &e)],
+                )));
+                return;
+            }
         }
+        Self::load_maps(state, db).await;
+    }
 
-        // Run a full rescan (progress tracking is not exposed yet).
+    async fn rescan_maps(
+        state: &Arc<Mutex<DbState>>,
+        db: &Database,
+        roots: &[PathBuf],
+        scan: ScanOptions,
+    ) {
         {
             let mut s = state.lock().unwrap();
             s.status = DbStatus::Scanning {
                 current: 0,
-                total: 1,
+                total: 0,
             };
+            s.error = None;
         }
 
-        if let Err(e) = scan_songs_directory(db, songs_path).await {
+        // Archived flags live on beatmapset rows, so remember them across the clear.
+        let archived_paths = get_archived_paths(db.pool()).await.unwrap_or_else(|e| {
+            log::error!("DB: failed to read archived sets before rescan: {}", e);
+            Vec::new()
+        });
+
+        // Clear the in-memory view first.
+        if let Err(e) = clear_all(db.pool()).await {
             let mut s = state.lock().unwrap();
-            s.status = DbStatus::Error(format!("Scan error: {}", e));
+            s.status = DbStatus::Error(format!("Error clearing database: {}", e));
             s.error = Some(format!("{}", e));
             return;
         }
 
+        // Run a full rescan, one progress step per set folder.
+        let mut progress = |current: usize, total: usize| {
+            state.lock().unwrap().status = DbStatus::Scanning { current, total };
+        };
+        let merged = match scan_songs_directory(db, roots, scan, &mut progress).await {
+            Ok(merged) => merged,
+            Err(e) => {
+                let mut s = state.lock().unwrap();
+                s.status = DbStatus::Error(format!("Scan error: {}", e));
+                s.error = Some(format!("{}", e));
+                s.notices.push(Notice::error(tr_args(
+                    "notice.rescan_failed",
+                    &[("error", &e)],
+                )));
+                return;
+            }
+        };
+
+        if let Err(e) = restore_archived_paths(db.pool(), &archived_paths).await {
+            log::error!("DB: failed to restore archived sets after rescan: {}", e);
+        }
+
         // Recharger les maps
         Self::load_maps(state, db).await;
+        // Course charts are resolved by hash, so paths may have moved.
+        Self::load_courses(state, db).await;
+
+        let mut s = state.lock().unwrap();
+        let mut notice = tr_args("notice.rescan_done", &[("sets", &s.beatmapsets.len())]);
+        if merged > 0 {
+            log::info!("DB: Rescan merged {} duplicate charts", merged);
+            notice.push_str(&tr_args("notice.duplicates_merged", &[("count", &merged)]));
+        }
+        s.notices.push(Notice::success(notice));
+    }
+
+    async fn incremental_scan(
+        state: &Arc<Mutex<DbState>>,
+        db: &Database,
+        roots: &[PathBuf],
+        scan: ScanOptions,
+    ) {
+        let mut progress = |current: usize, total: usize| {
+            if total > 0 {
+                state.lock().unwrap().status = DbStatus::Scanning { current, total };
+            }
+        };
+        match scanner::incremental_scan(db, roots, scan, &mut progress).await {
+            Ok(summary) if summary.is_empty() => {}
+            Ok(summary) => {
+                log::info!("DB: Incremental scan {:?}", summary);
+                let mut notice = tr_args(
+                    "notice.library_updated",
+                    &[
+                        ("added", &summary.added),
+                        ("changed", &summary.updated),
+                        ("removed", &summary.removed),
+                    ],
+                );
+                if summary.merged > 0 {
+                    notice.push_str(&tr_args(
+                        "notice.duplicates_merged",
+                        &[("count", &summary.merged)],
+                    ));
+                }
+                state.lock().unwrap().notices.push(Notice::success(notice));
+                Self::load_maps(state, db).await;
+                Self::load_courses(state, db).await;
+            }
+            Err(e) => {
+                log::error!("DB: Incremental scan failed: {}", e);
+                let mut s = state.lock().unwrap();
+                s.status = DbStatus::Error(format!("Scan error: {}", e));
+                s.error = Some(format!("{}", e));
+                s.notices.push(Notice::error(tr_args(
+                    "notice.library_update_failed",
+                    &[("error", &e)],
+                )));
+            }
+        }
+    }
+
+    /// Aborts the in-flight search, if any. Its result is dropped before it
+    /// reaches the shared state.
+    /// Supersedes the running search, if any: its result is dropped even if
+    /// it completes before the abort takes effect.
+    fn cancel_search(state: &Arc<Mutex<DbState>>, task: &mut Option<tokio::task::JoinHandle<()>>) {
+        let mut s = state.lock().unwrap();
+        s.search_generation = s.search_generation.wrapping_add(1);
+        if let Some(handle) = task.take() {
+            handle.abort();
+        }
     }
 
-    async fn search_maps(state: &Arc<Mutex<DbState>>, db: &Database, filters: MenuSearchFilters) {
+    async fn search_maps(
+        state: &Arc<Mutex<DbState>>,
+        db: &Database,
+        filters: MenuSearchFilters,
+        generation: u64,
+    ) {
         {
             let mut s = state.lock().unwrap();
             s.status = DbStatus::Searching;
             s.error = None;
         }
 
-        match db.search_beatmapsets(&filters).await {
+        let result = db.search_beatmapsets(&filters).await;
+        let mut s = state.lock().unwrap();
+        if s.search_generation != generation {
+            return;
+        }
+        match result {
             Ok(beatmapsets) => {
-                let mut s = state.lock().unwrap();
                 s.beatmapsets = beatmapsets;
                 s.status = DbStatus::Idle;
                 s.error = None;
                 s.version = s.version.wrapping_add(1);
-                s.leaderboard.clear();
-                s.leaderboard_hash = None;
-                s.leaderboard_version = s.leaderboard_version.wrapping_add(1);
             }
             Err(e) => {
-                let mut s = state.lock().unwrap();
                 s.status = DbStatus::Error(format!("Search error: {}", e));
                 s.error = Some(format!("{}", e));
             }
@@ -269,6 +759,7 @@ impl DbManager {
         state: &Arc<Mutex<DbState>>,
         db: &Database,
         payload: SaveReplayCommand,
+        profile_id: i64,
     ) {
         match db
             .insert_replay(
@@ -279,12 +770,26 @@ impl DbManager {
                 payload.max_combo,
                 payload.rate,
                 &payload.data,
+                profile_id,
             )
             .await
         {
-            Ok(_) => {
+            Ok(hash) => {
                 log::info!("DB: Replay saved successfully for {}", payload.beatmap_hash);
-                Self::load_leaderboard(state, db, &payload.beatmap_hash).await;
+                if let Err(e) = db.insert_replay_columns(&hash, &payload.columns).await {
+                    log::error!("DB: failed to store column stats of {}: {}", hash, e);
+                }
+                state
+                    .lock()
+                    .unwrap()
+                    .notices
+                    .push(Notice::success(tr("notice.replay_saved")));
+                Self::load_leaderboard(state, db, &payload.beatmap_hash, profile_id).await;
+                // Practice runs restart from checkpoints and recovered runs
+                // stop midway, so neither counts towards goals.
+                if !payload.data.is_practice_mode && !payload.data.incomplete {
+                    Self::track_goals(state, db, &payload, profile_id).await;
+                }
             }
             Err(e) => {
                 log::error!(
@@ -292,13 +797,112 @@ impl DbManager {
                     payload.beatmap_hash,
                     e
                 );
+                state.lock().unwrap().notices.push(Notice::error(tr_args(
+                    "notice.replay_not_saved",
+                    &[("error", &e)],
+                )));
             }
         }
     }
 
-    async fn load_leaderboard(state: &Arc<Mutex<DbState>>, db: &Database, beatmap_hash: &str) {
-        match db.get_replays_for_beatmap(beatmap_hash).await {
-            Ok(replays) => {
+    fn set_maintenance(state: &Arc<Mutex<DbState>>, report: Option<MaintenanceReport>) {
+        let mut s = state.lock().unwrap();
+        s.maintenance = report;
+        s.maintenance_version = s.maintenance_version.wrapping_add(1);
+    }
+
+    async fn run_maintenance(
+        state: &Arc<Mutex<DbState>>,
+        db: &Database,
+        db_path: &Path,
+        request: MaintenanceRequest,
+        profile_id: i64,
+    ) {
+        let file_size = |path: &Path| std::fs::metadata(path).map_or(0, |m| m.len());
+        let report = match request {
+            MaintenanceRequest::Dismiss => None,
+            MaintenanceRequest::Vacuum => {
+                Self::set_maintenance(state, Some(MaintenanceReport::Running));
+                let before = file_size(db_path);
+                Some(match db.vacuum().await {
+                    Ok(()) => {
+                        let after = file_size(db_path);
+                        log::info!("DB: Vacuumed, {} -> {} bytes", before, after);
+                        MaintenanceReport::Vacuumed { before, after }
+                    }
+                    Err(e) => {
+                        log::error!("DB: Vacuum failed: {}", e);
+                        MaintenanceReport::Failed(format!("Vacuum failed: {}", e))
+                    }
+                })
+            }
+            MaintenanceRequest::PreviewPrune(rules) | MaintenanceRequest::Prune(rules) => {
+                Self::set_maintenance(state, Some(MaintenanceReport::Running));
+                let replays = match db.get_all_replays(profile_id).await {
+                    Ok(replays) => replays,
+                    Err(e) => {
+                        log::error!("DB: failed to list replays: {}", e);
+                        let report =
+                            MaintenanceReport::Failed(format!("Could not list replays: {}", e));
+                        Self::set_maintenance(state, Some(report));
+                        return;
+                    }
+                };
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map_or(0, |d| d.as_secs() as i64);
+                let pruned = replays_to_prune(&replays, &rules, now);
+                let bytes: u64 = pruned
+                    .iter()
+                    .map(|r| file_size(Path::new(&r.file_path)))
+                    .sum();
+
+                if matches!(request, MaintenanceRequest::PreviewPrune(_)) {
+                    Some(MaintenanceReport::PrunePreview {
+                        rules,
+                        replays: pruned.len(),
+                        bytes,
+                    })
+                } else {
+                    let hashes: Vec<String> = pruned.iter().map(|r| r.hash.clone()).collect();
+                    Some(match db.delete_replays(&hashes).await {
+                        Ok(()) => {
+                            for replay in &pruned {
+                                if let Err(e) = std::fs::remove_file(&replay.file_path) {
+                                    log::warn!("DB: could not delete {}: {}", replay.file_path, e);
+                                }
+                            }
+                            log::info!("DB: Pruned {} replays ({} bytes)", hashes.len(), bytes);
+                            // The shown leaderboard may list deleted replays.
+                            let shown = state.lock().unwrap().leaderboard_hash.clone();
+                            if let Some(hash) = shown {
+                                Self::load_leaderboard(state, db, &hash, profile_id).await;
+                            }
+                            MaintenanceReport::Pruned {
+                                replays: hashes.len(),
+                                bytes,
+                            }
+                        }
+                        Err(e) => {
+                            log::error!("DB: failed to prune replays: {}", e);
+                            MaintenanceReport::Failed(format!("Prune failed: {}", e))
+                        }
+                    })
+                }
+            }
+        };
+        Self::set_maintenance(state, report);
+    }
+
+    async fn load_leaderboard(
+        state: &Arc<Mutex<DbState>>,
+        db: &Database,
+        beatmap_hash: &str,
+        profile_id: i64,
+    ) {
+        match db.get_replays_for_beatmap(beatmap_hash, profile_id).await {
+            Ok(mut replays) => {
+                Self::backfill_replay_judges(db, &mut replays).await;
                 let mut s = state.lock().unwrap();
                 s.leaderboard = replays;
                 s.leaderboard_hash = Some(beatmap_hash.to_string());
@@ -310,6 +914,319 @@ impl DbManager {
         }
     }
 
+    /// Reads the hit window of replays saved before it was stored in the
+    /// database, so they can rank as personal bests.
+    async fn backfill_replay_judges(db: &Database, replays: &mut [Replay]) {
+        for replay in replays.iter_mut().filter(|r| r.judge.is_none()) {
+            let data = match replay_storage::load_replay_from_path(Path::new(&replay.file_path)) {
+                Ok(data) => data,
+                Err(e) => {
+                    log::warn!("DB: could not read replay {}: {}", replay.hash, e);
+                    continue;
+                }
+            };
+            let judge = judge_key(data.hit_window_mode, data.hit_window_value);
+            if let Err(e) = db
+                .set_replay_judge(&replay.hash, &judge, data.is_practice_mode)
+                .await
+            {
+                log::error!("DB: failed to store judge of replay {}: {}", replay.hash, e);
+                continue;
+            }
+            replay.judge = Some(judge);
+            replay.practice = data.is_practice_mode;
+        }
+    }
+
+    async fn load_goals(state: &Arc<Mutex<DbState>>, db: &Database, profile_id: i64) {
+        match db.get_all_goals(profile_id).await {
+            Ok(goals) => {
+                let mut s = state.lock().unwrap();
+                s.goals = goals;
+                s.goals_version = s.goals_version.wrapping_add(1);
+            }
+            Err(e) => {
+                log::error!("DB: failed to load goals: {}", e);
+            }
+        }
+    }
+
+    async fn persist_goal(
+        state: &Arc<Mutex<DbState>>,
+        db: &Database,
+        draft: GoalDraft,
+        profile_id: i64,
+    ) {
+        let created_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+
+        match db.insert_goal(&draft, created_at, profile_id).await {
+            Ok(id) => log::info!("DB: Goal '{}' created (id {})", draft.name, id),
+            Err(e) => log::error!("DB: failed to create goal '{}': {}", draft.name, e),
+        }
+        Self::load_goals(state, db, profile_id).await;
+    }
+
+    async fn track_goals(
+        state: &Arc<Mutex<DbState>>,
+        db: &Database,
+        payload: &SaveReplayCommand,
+        profile_id: i64,
+    ) {
+        match db
+            .record_goal_progress(
+                &payload.beatmap_hash,
+                payload.accuracy,
+                payload.score as i64,
+                payload.rate,
+                payload.timestamp,
+                profile_id,
+            )
+            .await
+        {
+            Ok(completed) => {
+                if completed > 0 {
+                    log::info!("DB: {} goal(s) completed", completed);
+                }
+                Self::load_goals(state, db, profile_id).await;
+            }
+            Err(e) => {
+                log::error!(
+                    "DB: failed to update goals for {}: {}",
+                    payload.beatmap_hash,
+                    e
+                );
+            }
+        }
+    }
+
+    async fn track_course_goals(
+        state: &Arc<Mutex<DbState>>,
+        db: &Database,
+        stages: &[CourseStagePlay],
+        rate: f64,
+        profile_id: i64,
+    ) {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+
+        match db
+            .record_course_goal_progress(stages, rate, timestamp, profile_id)
+            .await
+        {
+            Ok(completed) => {
+                if completed > 0 {
+                    log::info!("DB: {} course goal(s) completed", completed);
+                    Self::load_goals(state, db, profile_id).await;
+                }
+            }
+            Err(e) => log::error!("DB: failed to update course goals: {}", e),
+        }
+    }
+
+    async fn load_courses(state: &Arc<Mutex<DbState>>, db: &Database) {
+        match db.get_all_courses().await {
+            Ok(courses) => {
+                let mut s = state.lock().unwrap();
+                s.courses = courses;
+                s.courses_version = s.courses_version.wrapping_add(1);
+            }
+            Err(e) => {
+                log::error!("DB: failed to load courses: {}", e);
+            }
+        }
+    }
+
+    async fn persist_course(state: &Arc<Mutex<DbState>>, db: &Database, draft: CourseDraft) {
+        let created_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+
+        match db.insert_course(&draft, created_at).await {
+            Ok(id) => log::info!("DB: Course '{}' created (id {})", draft.name, id),
+            Err(e) => log::error!("DB: failed to create course '{}': {}", draft.name, e),
+        }
+        Self::load_courses(state, db).await;
+    }
+
+    async fn load_profiles(state: &Arc<Mutex<DbState>>, db: &Database) {
+        match db.get_all_profiles().await {
+            Ok(profiles) => {
+                let mut s = state.lock().unwrap();
+                s.profiles = profiles;
+                s.profiles_version = s.profiles_version.wrapping_add(1);
+            }
+            Err(e) => {
+                log::error!("DB: failed to load profiles: {}", e);
+            }
+        }
+    }
+
+    async fn persist_profile(state: &Arc<Mutex<DbState>>, db: &Database, name: &str) {
+        let created_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+
+        match db.insert_profile(name, created_at).await {
+            Ok(id) => log::info!("DB: Profile '{}' created (id {})", name, id),
+            Err(e) => log::error!("DB: failed to create profile '{}': {}", name, e),
+        }
+        Self::load_profiles(state, db).await;
+    }
+
+    async fn load_recent_rating(
+        state: &Arc<Mutex<DbState>>,
+        db: &Database,
+        calculator_id: &str,
+        profile_id: i64,
+    ) {
+        let rating = db
+            .get_recent_score_rating(
+                calculator_id,
+                profile_id,
+                RECOMMEND_MIN_ACCURACY,
+                RECOMMEND_SCORE_COUNT,
+            )
+            .await
+            .unwrap_or_else(|e| {
+                log::error!("DB: failed to load recent score rating: {}", e);
+                None
+            });
+        let mut s = state.lock().unwrap();
+        s.recent_rating = rating;
+        s.recent_rating_version = s.recent_rating_version.wrapping_add(1);
+    }
+
+    /// Stops a running library overview job.
+    fn cancel_library_job(
+        state: &Arc<Mutex<DbState>>,
+        task: &mut Option<tokio::task::JoinHandle<()>>,
+    ) {
+        if let Some(task) = task.take()
+            && !task.is_finished()
+        {
+            task.abort();
+            let mut s = state.lock().unwrap();
+            s.library_progress = None;
+            s.library_version = s.library_version.wrapping_add(1);
+        }
+    }
+
+    /// Rates every chart the calculator has not rated yet (persisting the
+    /// ratings), then buckets the library by rating and keymode, next to the
+    /// profile's per-lane judgements.
+    async fn compute_library_stats(
+        state: &Arc<Mutex<DbState>>,
+        db: &Database,
+        calculator_id: &str,
+        profile_id: i64,
+    ) {
+        let unrated = match db.get_unrated_beatmaps(calculator_id).await {
+            Ok(unrated) => unrated,
+            Err(e) => {
+                log::error!("DB: failed to list unrated charts: {}", e);
+                Vec::new()
+            }
+        };
+        let total = unrated.len();
+        log::info!(
+            "DB: Library overview: rating {} charts with {}",
+            total,
+            calculator_id
+        );
+
+        for (done, (hash, path)) in unrated.into_iter().enumerate() {
+            if done % 10 == 0 {
+                let mut s = state.lock().unwrap();
+                s.library_progress = Some((done, total));
+                s.library_version = s.library_version.wrapping_add(1);
+            }
+            let calculator = calculator_id.to_string();
+            let rating = tokio::task::spawn_blocking(move || {
+                let map = rosu_map::Beatmap::from_path(&path).map_err(|e| e.to_string())?;
+                crate::difficulty::calculate_on_demand(&map, &calculator, 1.0)
+                    .map_err(|e| e.to_string())
+            })
+            .await;
+            match rating {
+                Ok(Ok(ssr)) => {
+                    if let Err(e) = db.insert_beatmap_rating(&hash, calculator_id, &ssr).await {
+                        log::error!("DB: failed to store rating of {}: {}", hash, e);
+                    }
+                    if calculator_id == "etterna" {
+                        let tags = pattern_tags::to_column(&pattern_tags::classify(&ssr));
+                        Self::store_pattern_tags(state, db, &hash, &tags).await;
+                    }
+                }
+                Ok(Err(e)) => log::warn!("DB: could not rate {}: {}", hash, e),
+                Err(e) => log::error!("DB: rating task failed for {}: {}", hash, e),
+            }
+        }
+
+        let mut stats = match db.get_library_ratings(calculator_id).await {
+            Ok((ratings, total)) => Some(LibraryStats::from_ratings(
+                calculator_id,
+                Self::library_bucket_width(calculator_id),
+                &ratings,
+                total,
+            )),
+            Err(e) => {
+                log::error!("DB: failed to load library ratings: {}", e);
+                None
+            }
+        };
+        if let Some(stats) = stats.as_mut() {
+            match db.get_lane_stats(profile_id).await {
+                Ok(lanes) => stats.lanes = lanes,
+                Err(e) => log::error!("DB: failed to load lane stats: {}", e),
+            }
+        }
+        let mut s = state.lock().unwrap();
+        s.library_stats = stats;
+        s.library_progress = None;
+        s.library_version = s.library_version.wrapping_add(1);
+    }
+
+    /// Saves the pattern tags of a chart and patches the loaded list, without
+    /// bumping its version (which would reset the song select).
+    async fn store_pattern_tags(
+        state: &Arc<Mutex<DbState>>,
+        db: &Database,
+        beatmap_hash: &str,
+        tags: &str,
+    ) {
+        if let Err(e) = db.set_beatmap_pattern_tags(beatmap_hash, tags).await {
+            log::error!(
+                "DB: failed to store pattern tags of {}: {}",
+                beatmap_hash,
+                e
+            );
+            return;
+        }
+        let mut s = state.lock().unwrap();
+        for (_, beatmaps) in s.beatmapsets.iter_mut() {
+            for entry in beatmaps.iter_mut() {
+                if entry.beatmap.hash == beatmap_hash {
+                    entry.beatmap.pattern_tags = Some(tags.to_string());
+                }
+            }
+        }
+    }
+
+    /// Histogram bucket size: 2 MSD for Etterna, half a star for osu!.
+    fn library_bucket_width(calculator_id: &str) -> f64 {
+        match calculator_id {
+            "osu" => 0.5,
+            _ => 2.0,
+        }
+    }
+
     pub fn get_state(&self) -> Arc<Mutex<DbState>> {
         Arc::clone(&self.state)
     }
@@ -344,4 +1261,106 @@ impl DbManager {
     pub fn fetch_leaderboard(&self, beatmap_hash: &str) {
         let _ = self.send_command(DbCommand::FetchLeaderboard(beatmap_hash.to_string()));
     }
+
+    pub fn set_archived(&self, beatmapset_id: i64, archived: bool, filters: MenuSearchFilters) {
+        let _ = self.send_command(DbCommand::SetArchived {
+            beatmapset_id,
+            archived,
+            filters,
+        });
+    }
+
+    pub fn set_metadata(
+        &self,
+        beatmapset_id: i64,
+        metadata: Option<MetadataEdit>,
+        filters: MenuSearchFilters,
+    ) {
+        let _ = self.send_command(DbCommand::SetMetadata {
+            beatmapset_id,
+            metadata,
+            filters,
+        });
+    }
+
+    pub fn set_load_error(
+        &self,
+        beatmap_hash: String,
+        load_error: Option<String>,
+        filters: MenuSearchFilters,
+    ) {
+        let _ = self.send_command(DbCommand::SetLoadError {
+            beatmap_hash,
+            load_error,
+            filters,
+        });
+    }
+
+    pub fn set_pattern_tags(&self, beatmap_hash: String, pattern_tags: String) {
+        let _ = self.send_command(DbCommand::SetPatternTags {
+            beatmap_hash,
+            pattern_tags,
+        });
+    }
+
+    pub fn create_goal(&self, draft: GoalDraft) {
+        let _ = self.send_command(DbCommand::CreateGoal(draft));
+    }
+
+    pub fn delete_goal(&self, goal_id: i64) {
+        let _ = self.send_command(DbCommand::DeleteGoal(goal_id));
+    }
+
+    pub fn record_course_run(&self, stages: Vec<CourseStagePlay>, rate: f64) {
+        let _ = self.send_command(DbCommand::RecordCourseRun { stages, rate });
+    }
+
+    pub fn create_course(&self, draft: CourseDraft) {
+        let _ = self.send_command(DbCommand::CreateCourse(draft));
+    }
+
+    pub fn delete_course(&self, course_id: i64) {
+        let _ = self.send_command(DbCommand::DeleteCourse(course_id));
+    }
+
+    pub fn set_profile(&self, profile_id: i64) {
+        let _ = self.send_command(DbCommand::SetProfile(profile_id));
+    }
+
+    pub fn create_profile(&self, name: String) {
+        let _ = self.send_command(DbCommand::CreateProfile(name));
+    }
+
+    pub fn load_library_stats(&self, calculator_id: String) {
+        let _ = self.send_command(DbCommand::LoadLibraryStats(calculator_id));
+    }
+
+    pub fn fetch_recent_rating(&self, calculator_id: String) {
+        let _ = self.send_command(DbCommand::LoadRecentRating(calculator_id));
+    }
+
+    pub fn import_folder(&self, folder: PathBuf) {
+        let _ = self.send_command(DbCommand::ImportFolder(folder));
+    }
+
+    pub fn import_archive(&self, archive: PathBuf) {
+        let _ = self.send_command(DbCommand::ImportArchive(archive));
+    }
+
+    /// Runs a library maintenance command (vacuum, prune preview or prune).
+    pub fn maintenance(&self, request: MaintenanceRequest) {
+        let _ = self.send_command(DbCommand::Maintenance(request));
+    }
+
+    /// Switches the library folders and watches the new ones.
+    pub fn set_library_roots(&mut self, roots: Vec<PathBuf>) {
+        self.watcher = None;
+        self.watcher = SongsWatcher::start(&roots, self.command_sender.clone());
+        let _ = self.send_command(DbCommand::SetLibraryRoots(roots));
+    }
+
+    /// Sets the chart rules and thread count of the next scans and imports.
+    pub fn set_scan_options(&self, options: ScanOptions) {
+        let _ = self.send_command(DbCommand::SetScanOptions(options));
+    }
 }
@@ -0,0 +1,22 @@
+CREATE TABLE IF NOT EXISTS goal (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    target_accuracy REAL,
+    target_score INTEGER,
+    min_rate REAL NOT NULL DEFAULT 1.0,
+    created_at INTEGER NOT NULL,
+    completed_at INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS goal_chart (
+    goal_id INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    beatmap_hash TEXT NOT NULL,
+    best_accuracy REAL NOT NULL DEFAULT 0.0,
+    best_score INTEGER NOT NULL DEFAULT 0,
+    cleared INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (goal_id, position),
+    FOREIGN KEY (goal_id) REFERENCES goal(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_goal_chart_hash ON goal_chart(beatmap_hash);
@@ -0,0 +1,3 @@
+-- Migration: archived flag on beatmapset
+-- Archived sets stay on disk and in the DB but are hidden from the default song list.
+ALTER TABLE beatmapset ADD COLUMN archived INTEGER NOT NULL DEFAULT 0;
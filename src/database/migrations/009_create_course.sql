@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS course (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS course_chart (
+    course_id INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    beatmap_hash TEXT NOT NULL,
+    PRIMARY KEY (course_id, position),
+    FOREIGN KEY (course_id) REFERENCES course(id) ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+-- Migration: mapper name on beatmapset
+-- Existing rows stay NULL until the next rescan.
+ALTER TABLE beatmapset ADD COLUMN creator TEXT;
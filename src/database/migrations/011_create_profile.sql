@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS profile (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    created_at INTEGER NOT NULL
+);
+
+-- Scores set before profiles existed belong to this one.
+INSERT OR IGNORE INTO profile (id, name, created_at) VALUES (1, 'Default', 0);
@@ -0,0 +1,5 @@
+-- Migration: scope replays and goals to a profile
+-- Existing rows go to the default profile (id 1).
+ALTER TABLE replay ADD COLUMN profile_id INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE goal ADD COLUMN profile_id INTEGER NOT NULL DEFAULT 1;
+CREATE INDEX IF NOT EXISTS idx_replay_profile ON replay(profile_id, beatmap_hash);
@@ -0,0 +1,3 @@
+-- Migration: key count (keymode) of each chart
+-- Existing rows stay 0 (unknown) until the next rescan.
+ALTER TABLE beatmap ADD COLUMN key_count INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,3 @@
+-- Migration: modification time of a set folder when it was last imported
+-- NULL on existing rows, so the first incremental scan re-imports every set once.
+ALTER TABLE beatmapset ADD COLUMN scanned_mtime INTEGER;
@@ -0,0 +1,3 @@
+-- Migration: pack name of sets imported from an Etterna-style pack folder
+-- (songs/<pack>/<song>/). NULL for sets sitting directly under songs/.
+ALTER TABLE beatmapset ADD COLUMN pack TEXT;
@@ -0,0 +1,11 @@
+-- Migration: extra copies of a chart found in other sets.
+-- The beatmap row keeps the first copy; every other file with the same hash
+-- is recorded here so the library lists the chart once and scores stay shared.
+CREATE TABLE IF NOT EXISTS beatmap_source (
+    path TEXT PRIMARY KEY,
+    hash TEXT NOT NULL,
+    beatmapset_id INTEGER NOT NULL,
+    FOREIGN KEY (beatmapset_id) REFERENCES beatmapset(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_beatmap_source_hash ON beatmap_source(hash);
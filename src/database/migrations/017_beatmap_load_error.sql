@@ -0,0 +1,3 @@
+-- Migration: why a chart failed to load for play, NULL while it loads fine.
+-- Cleared whenever the chart is re-imported by a scan.
+ALTER TABLE beatmap ADD COLUMN load_error TEXT;
@@ -0,0 +1,3 @@
+-- Migration: dominant patterns of each chart (see difficulty::pattern_tags),
+-- NULL until the chart is rated with Etterna.
+ALTER TABLE beatmap ADD COLUMN pattern_tags TEXT;
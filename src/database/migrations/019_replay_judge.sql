@@ -0,0 +1,5 @@
+-- Migration: hit window (see models::personal_best::judge_key) and practice
+-- flag of each replay, to rank personal bests per rate and judge.
+-- Older replays keep a NULL judge until their file is read.
+ALTER TABLE replay ADD COLUMN judge TEXT;
+ALTER TABLE replay ADD COLUMN practice INTEGER NOT NULL DEFAULT 0;
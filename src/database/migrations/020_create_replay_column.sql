@@ -0,0 +1,19 @@
+-- Migration: judgement counts and mean offset of each column of a replay,
+-- so per-finger statistics don't need to read every replay file.
+-- Replays saved before this migration have no rows here.
+CREATE TABLE IF NOT EXISTS replay_column (
+    replay_hash TEXT NOT NULL,
+    column_index INTEGER NOT NULL,
+    marv INTEGER NOT NULL,
+    perfect INTEGER NOT NULL,
+    great INTEGER NOT NULL,
+    good INTEGER NOT NULL,
+    bad INTEGER NOT NULL,
+    miss INTEGER NOT NULL,
+    ghost_tap INTEGER NOT NULL,
+    -- Judged hits (misses excluded) the mean offset is taken over.
+    hits INTEGER NOT NULL,
+    mean_offset_ms REAL NOT NULL,
+    PRIMARY KEY (replay_hash, column_index),
+    FOREIGN KEY (replay_hash) REFERENCES replay(hash) ON DELETE CASCADE
+);
@@ -0,0 +1,11 @@
+-- Migration: metadata edited by the player from song select.
+-- One row per chart of the edited set, keyed by chart hash rather than set id,
+-- so the edit finds its set again after a rescan re-creates it. The chart
+-- files are never touched; NULL columns keep the scanned value.
+CREATE TABLE IF NOT EXISTS metadata_override (
+    beatmap_hash TEXT PRIMARY KEY,
+    title TEXT,
+    artist TEXT,
+    tags TEXT,
+    source TEXT
+);
@@ -0,0 +1,5 @@
+-- Migration: title and artist in their original script (osu! TitleUnicode,
+-- StepMania #TITLE next to #TITLETRANSLIT, Malody titleorg). NULL when the
+-- files only give one version. Existing rows stay NULL until the next rescan.
+ALTER TABLE beatmapset ADD COLUMN title_unicode TEXT;
+ALTER TABLE beatmapset ADD COLUMN artist_unicode TEXT;
@@ -0,0 +1,272 @@
+//! Versioned schema migrations.
+//!
+//! Every SQL script of this folder is listed in [`MIGRATIONS`] under the
+//! number of its file name. The `schema_version` table records which versions
+//! a database has applied; on startup [`run`] applies the missing ones in
+//! order, each in its own transaction. A schema change is a new script
+//! appended to the list, never an edit of an existing one.
+//!
+//! Databases created before versioning have an empty `schema_version`: the
+//! versions they already have are detected once from their tables and columns.
+
+use sqlx::SqlitePool;
+use std::collections::BTreeSet;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// One schema change.
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    sql: &'static str,
+}
+
+const fn migration(version: i64, name: &'static str, sql: &'static str) -> Migration {
+    Migration { version, name, sql }
+}
+
+/// Every migration, in the order they are applied.
+pub const MIGRATIONS: &[Migration] = &[
+    migration(
+        1,
+        "create_beatmapset",
+        include_str!("001_create_beatmapset.sql"),
+    ),
+    migration(2, "create_beatmap", include_str!("002_create_beatmap.sql")),
+    migration(3, "create_replay", include_str!("003_create_replay.sql")),
+    migration(
+        5,
+        "create_beatmap_rating",
+        include_str!("005_create_beatmap_rating.sql"),
+    ),
+    migration(
+        6,
+        "replay_file_storage",
+        include_str!("006_replay_file_storage.sql"),
+    ),
+    migration(7, "create_goal", include_str!("007_create_goal.sql")),
+    migration(
+        8,
+        "beatmapset_archived",
+        include_str!("008_beatmapset_archived.sql"),
+    ),
+    migration(9, "create_course", include_str!("009_create_course.sql")),
+    migration(
+        10,
+        "beatmapset_creator",
+        include_str!("010_beatmapset_creator.sql"),
+    ),
+    migration(11, "create_profile", include_str!("011_create_profile.sql")),
+    migration(
+        12,
+        "profile_scoping",
+        include_str!("012_profile_scoping.sql"),
+    ),
+    migration(
+        13,
+        "beatmap_key_count",
+        include_str!("013_beatmap_key_count.sql"),
+    ),
+    migration(
+        14,
+        "beatmapset_scanned_mtime",
+        include_str!("014_beatmapset_scanned_mtime.sql"),
+    ),
+    migration(
+        15,
+        "beatmapset_pack",
+        include_str!("015_beatmapset_pack.sql"),
+    ),
+    migration(
+        16,
+        "create_beatmap_source",
+        include_str!("016_create_beatmap_source.sql"),
+    ),
+    migration(
+        17,
+        "beatmap_load_error",
+        include_str!("017_beatmap_load_error.sql"),
+    ),
+    migration(
+        18,
+        "beatmap_pattern_tags",
+        include_str!("018_beatmap_pattern_tags.sql"),
+    ),
+    migration(19, "replay_judge", include_str!("019_replay_judge.sql")),
+    migration(
+        20,
+        "create_replay_column",
+        include_str!("020_create_replay_column.sql"),
+    ),
+    migration(
+        21,
+        "create_metadata_override",
+        include_str!("021_create_metadata_override.sql"),
+    ),
+    migration(
+        22,
+        "beatmapset_unicode_metadata",
+        include_str!("022_beatmapset_unicode_metadata.sql"),
+    ),
+];
+
+/// Latest schema version known to this build.
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+/// Brings the schema up to [`latest_version`]. Returns the versions applied.
+pub async fn run(pool: &SqlitePool) -> Result<Vec<i64>, sqlx::Error> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            applied_at INTEGER NOT NULL
+        )",
+    )
+    .execute(pool)
+    .await?;
+
+    let mut applied: BTreeSet<i64> = sqlx::query_scalar("SELECT version FROM schema_version")
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .collect();
+    if applied.is_empty() {
+        applied = detect_unversioned(pool).await?;
+        for migration in MIGRATIONS.iter().filter(|m| applied.contains(&m.version)) {
+            record(pool, migration).await?;
+        }
+        if !applied.is_empty() {
+            log::info!(
+                "DB: Unversioned database, detected schema version {}",
+                applied.last().copied().unwrap_or(0)
+            );
+        }
+    }
+
+    if let Some(&newest) = applied.last()
+        && newest > latest_version()
+    {
+        log::warn!(
+            "DB: Schema version {} is newer than this build ({})",
+            newest,
+            latest_version()
+        );
+    }
+
+    let mut ran = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|m| !applied.contains(&m.version)) {
+        log::info!(
+            "DB: Applying migration {:03}_{}",
+            migration.version,
+            migration.name
+        );
+        let mut tx = pool.begin().await?;
+        sqlx::query(migration.sql).execute(&mut *tx).await?;
+        record(&mut *tx, migration).await?;
+        tx.commit().await?;
+        ran.push(migration.version);
+    }
+    Ok(ran)
+}
+
+async fn record<'e, E>(executor: E, migration: &Migration) -> Result<(), sqlx::Error>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Sqlite>,
+{
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    sqlx::query("INSERT INTO schema_version (version, name, applied_at) VALUES (?1, ?2, ?3)")
+        .bind(migration.version)
+        .bind(migration.name)
+        .bind(now)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// Versions already present in a database created before `schema_version`
+/// existed (empty for a new database).
+async fn detect_unversioned(pool: &SqlitePool) -> Result<BTreeSet<i64>, sqlx::Error> {
+    let has_table = |table: &'static str| async move {
+        sqlx::query_scalar::<_, i32>(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+        )
+        .bind(table)
+        .fetch_optional(pool)
+        .await
+        .map(|row| row.is_some())
+    };
+    let has_column = |table: &'static str, column: &'static str| async move {
+        sqlx::query_scalar::<_, i32>("SELECT 1 FROM pragma_table_info(?1) WHERE name = ?2")
+            .bind(table)
+            .bind(column)
+            .fetch_optional(pool)
+            .await
+            .map(|row| row.is_some())
+    };
+
+    let checks = [
+        (1, has_table("beatmapset").await?),
+        (2, has_table("beatmap").await?),
+        (3, has_table("replay").await?),
+        (5, has_table("beatmap_rating").await?),
+        (6, has_column("replay", "file_path").await?),
+        (7, has_table("goal").await?),
+        (8, has_column("beatmapset", "archived").await?),
+        (9, has_table("course").await?),
+        (10, has_column("beatmapset", "creator").await?),
+        (11, has_table("profile").await?),
+        (12, has_column("replay", "profile_id").await?),
+        (13, has_column("beatmap", "key_count").await?),
+        (14, has_column("beatmapset", "scanned_mtime").await?),
+        (15, has_column("beatmapset", "pack").await?),
+        (16, has_table("beatmap_source").await?),
+        (17, has_column("beatmap", "load_error").await?),
+        (18, has_column("beatmap", "pattern_tags").await?),
+        (19, has_column("replay", "judge").await?),
+        (20, has_table("replay_column").await?),
+        (21, has_table("metadata_override").await?),
+        (22, has_column("beatmapset", "title_unicode").await?),
+    ];
+    Ok(checks
+        .into_iter()
+        .filter_map(|(version, present)| present.then_some(version))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn memory_pool() -> SqlitePool {
+        // One connection, since each connection gets its own in-memory database.
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn new_database_applies_everything_once() {
+        let pool = memory_pool().await;
+        let ran = run(&pool).await.unwrap();
+        assert_eq!(ran.len(), MIGRATIONS.len());
+        assert!(run(&pool).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn unversioned_database_only_gets_missing_migrations() {
+        let pool = memory_pool().await;
+        for migration in MIGRATIONS.iter().filter(|m| m.version <= 13) {
+            sqlx::query(migration.sql).execute(&pool).await.unwrap();
+        }
+
+        let ran = run(&pool).await.unwrap();
+        assert_eq!(ran, vec![14, 15, 16, 17, 18, 19, 20, 21, 22]);
+    }
+}
@@ -1,9 +1,14 @@
+pub mod chart_storage;
 pub mod connection;
+pub mod maintenance;
 pub mod manager;
+pub mod migrations;
 pub mod models;
 pub mod query;
 pub mod replay_storage;
 pub mod scanner;
+pub mod thumbnail_cache;
+pub mod watcher;
 
 pub use connection::Database;
 pub use manager::{DbManager, DbStatus, SaveReplayCommand};
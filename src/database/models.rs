@@ -1,5 +1,8 @@
 //! Data structures mirroring the SQLite tables.
 
+use crate::difficulty::PatternTag;
+use crate::difficulty::pattern_tags;
+use crate::models::stats::HitStats;
 use sqlx::FromRow;
 
 #[derive(Debug, Clone, FromRow)]
@@ -9,6 +12,48 @@ pub struct Beatmapset {
     pub image_path: Option<String>,
     pub artist: Option<String>,
     pub title: Option<String>,
+    pub artist_unicode: Option<String>, // Original script, when the files give both
+    pub title_unicode: Option<String>,  // Original script, when the files give both
+    pub creator: Option<String>,        // Mapper name
+    pub archived: bool,                 // Hidden from the default song list
+    pub pack: Option<String>,           // Pack folder the set was found in
+    pub tags: Option<String>,           // Player-edited only, never scanned
+    pub source: Option<String>,         // Player-edited only, never scanned
+    pub edited: bool,                   // Title/artist/tags/source edited by the player
+}
+
+impl Beatmapset {
+    /// Title to show: the original script one when `unicode` is preferred
+    /// and known, the romanized one otherwise.
+    pub fn display_title(&self, unicode: bool) -> Option<&str> {
+        preferred(unicode, &self.title_unicode, &self.title)
+    }
+
+    /// Artist to show, like [`Beatmapset::display_title`].
+    pub fn display_artist(&self, unicode: bool) -> Option<&str> {
+        preferred(unicode, &self.artist_unicode, &self.artist)
+    }
+}
+
+fn preferred<'a>(
+    unicode: bool,
+    original: &'a Option<String>,
+    romanized: &'a Option<String>,
+) -> Option<&'a str> {
+    original
+        .as_deref()
+        .filter(|_| unicode)
+        .or(romanized.as_deref())
+}
+
+/// Metadata of a set as edited by the player. Empty fields fall back to the
+/// values read from the chart files.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetadataEdit {
+    pub title: String,
+    pub artist: String,
+    pub tags: String,
+    pub source: String,
 }
 
 #[derive(Debug, Clone, FromRow)]
@@ -20,6 +65,18 @@ pub struct Beatmap {
     pub note_count: i32,
     pub duration_ms: i32,
     pub nps: f64,
+    pub load_error: Option<String>, // Why the chart failed to load, if it did
+    pub pattern_tags: Option<String>, // Dominant patterns, NULL until rated
+}
+
+impl Beatmap {
+    /// Dominant patterns of the chart, empty until it is rated.
+    pub fn tags(&self) -> Vec<PatternTag> {
+        self.pattern_tags
+            .as_deref()
+            .map(pattern_tags::from_column)
+            .unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Clone, FromRow)]
@@ -92,6 +149,165 @@ pub struct Replay {
     pub score: i32,
     pub accuracy: f64,
     pub max_combo: i32,
-    pub rate: f64,         // Playback rate (1.0 = normal, 1.5 = 1.5x, etc.)
-    pub file_path: String, // Path to Brotli-compressed replay file (data/r/{hash}.r)
+    pub rate: f64,             // Playback rate (1.0 = normal, 1.5 = 1.5x, etc.)
+    pub file_path: String,     // Path to Brotli-compressed replay file (data/r/{hash}.r)
+    pub judge: Option<String>, // Hit window key, NULL until known for older replays
+    pub practice: bool,        // Practice run (never a personal best)
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Goal {
+    pub id: i64,
+    pub name: String,
+    pub target_accuracy: Option<f64>,
+    pub target_score: Option<i64>,
+    pub min_rate: f64,
+    pub completed_at: Option<i64>, // Unix timestamp, None while in progress
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct GoalChart {
+    pub goal_id: i64,
+    pub position: i64, // Order inside a course-style goal
+    pub beatmap_hash: String,
+    pub best_accuracy: f64,
+    pub best_score: i64,
+    pub cleared: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct GoalWithCharts {
+    pub goal: Goal,
+    pub charts: Vec<GoalChart>,
+}
+
+impl GoalWithCharts {
+    pub fn is_completed(&self) -> bool {
+        self.goal.completed_at.is_some()
+    }
+
+    /// Number of charts meeting the target.
+    pub fn cleared_count(&self) -> usize {
+        self.charts.iter().filter(|c| c.cleared).count()
+    }
+
+    /// Completion ratio in `[0, 1]`.
+    pub fn progress(&self) -> f32 {
+        if self.charts.is_empty() {
+            return 0.0;
+        }
+        self.cleared_count() as f32 / self.charts.len() as f32
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Course {
+    pub id: i64,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct CourseChart {
+    pub course_id: i64,
+    pub beatmap_hash: String,
+    pub path: Option<String>, // None when the chart is no longer in the library
+    pub title: Option<String>,
+    pub difficulty_name: Option<String>,
+}
+
+impl CourseChart {
+    /// "Title [Difficulty]", falling back to the hash prefix for missing charts.
+    pub fn label(&self) -> String {
+        match (&self.title, &self.difficulty_name) {
+            (Some(title), Some(diff)) => format!("{} [{}]", title, diff),
+            (Some(title), None) => title.clone(),
+            _ => self.beatmap_hash.chars().take(8).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CourseWithCharts {
+    pub course: Course,
+    pub charts: Vec<CourseChart>,
+}
+
+impl CourseWithCharts {
+    /// A course can only be started when every chart is still on disk.
+    pub fn is_playable(&self) -> bool {
+        !self.charts.is_empty() && self.charts.iter().all(|c| c.path.is_some())
+    }
+}
+
+/// Profile owning the data recorded before profiles existed.
+pub const DEFAULT_PROFILE_ID: i64 = 1;
+
+/// A player sharing this install; replays and goals belong to one.
+#[derive(Debug, Clone, FromRow)]
+pub struct Profile {
+    pub id: i64,
+    pub name: String,
+}
+
+/// Chart counts of the library per difficulty bucket and keymode.
+#[derive(Debug, Clone, Default)]
+pub struct LibraryStats {
+    /// Calculator the ratings come from ("etterna", "osu").
+    pub calculator_id: String,
+    /// Rating span covered by one bucket.
+    pub bucket_width: f64,
+    /// Chart count per bucket, for each key count (0 = unknown, i.e. scanned
+    /// before key counts were stored), sorted by key count.
+    pub key_modes: Vec<(i32, Vec<u32>)>,
+    /// Non-archived charts without a rating (formats the calculators don't support).
+    pub unrated: u32,
+    /// Judgements of each lane over the active profile's plays, sorted by
+    /// keymode then lane.
+    pub lanes: Vec<LaneStats>,
+}
+
+/// Judgements and mean offset of one lane of a keymode, summed over every
+/// saved play.
+#[derive(Debug, Clone, Default)]
+pub struct LaneStats {
+    pub key_count: i32,
+    pub column: usize,
+    pub hit_stats: HitStats,
+    /// Mean offset in ms (negative = early).
+    pub mean_offset_ms: f64,
+}
+
+impl LibraryStats {
+    /// Buckets `(key_count, overall)` rows; `total` is the library's chart count.
+    pub fn from_ratings(
+        calculator_id: &str,
+        bucket_width: f64,
+        ratings: &[(i32, f64)],
+        total: i64,
+    ) -> Self {
+        let mut key_modes: Vec<(i32, Vec<u32>)> = Vec::new();
+        for &(key_count, overall) in ratings {
+            let bucket = (overall.max(0.0) / bucket_width) as usize;
+            let counts = match key_modes.iter().position(|(k, _)| *k == key_count) {
+                Some(idx) => &mut key_modes[idx].1,
+                None => {
+                    key_modes.push((key_count, Vec::new()));
+                    &mut key_modes.last_mut().expect("just pushed").1
+                }
+            };
+            if counts.len() <= bucket {
+                counts.resize(bucket + 1, 0);
+            }
+            counts[bucket] += 1;
+        }
+        key_modes.sort_by_key(|(k, _)| *k);
+
+        Self {
+            calculator_id: calculator_id.to_string(),
+            bucket_width,
+            key_modes,
+            unrated: (total.max(0) as usize).saturating_sub(ratings.len()) as u32,
+            lanes: Vec::new(),
+        }
+    }
 }
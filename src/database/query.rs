@@ -2,9 +2,18 @@
 
 #![allow(clippy::too_many_arguments)]
 
-use crate::database::models::{Beatmap, BeatmapRating, BeatmapWithRatings, Beatmapset, Replay};
+use crate::database::models::{
+    Beatmap, BeatmapRating, BeatmapWithRatings, Beatmapset, Course, CourseChart, CourseWithCharts,
+    Goal, GoalChart, GoalWithCharts, LaneStats, MetadataEdit, Profile, Replay,
+};
+use crate::difficulty::BeatmapSsr;
+use crate::models::course::CourseDraft;
+use crate::models::goal::{CourseStagePlay, GoalDraft, run_meets_goal};
+use crate::models::personal_best::judge_key;
+use crate::models::replay::ColumnStats;
 use crate::models::search::MenuSearchFilters;
-use sqlx::SqlitePool;
+use crate::models::stats::HitStats;
+use sqlx::{SqliteConnection, SqlitePool};
 use std::collections::HashMap;
 
 /// Clears beatmap tables (used during rescans).
@@ -15,6 +24,9 @@ pub async fn clear_all(pool: &SqlitePool) -> Result<(), sqlx::Error> {
         .execute(pool)
         .await?;
     // Replays are preserved - they are valuable user data!
+    sqlx::query("DELETE FROM beatmap_source")
+        .execute(pool)
+        .await?;
     sqlx::query("DELETE FROM beatmap").execute(pool).await?;
     sqlx::query("DELETE FROM beatmapset").execute(pool).await?;
     Ok(())
@@ -22,51 +34,288 @@ pub async fn clear_all(pool: &SqlitePool) -> Result<(), sqlx::Error> {
 
 /// Inserts or updates a beatmapset record.
 pub async fn insert_beatmapset(
-    pool: &SqlitePool,
+    conn: &mut SqliteConnection,
     path: &str,
     image_path: Option<&str>,
     artist: Option<&str>,
     title: Option<&str>,
+    creator: Option<&str>,
 ) -> Result<i64, sqlx::Error> {
     // Check whether the beatmapset already exists.
     let existing: Option<i64> = sqlx::query_scalar("SELECT id FROM beatmapset WHERE path = ?1")
         .bind(path)
-        .fetch_optional(pool)
+        .fetch_optional(&mut *conn)
         .await?;
 
     match existing {
         Some(id) => {
             // Update existing row.
             sqlx::query(
-                "UPDATE beatmapset SET image_path = ?1, artist = ?2, title = ?3 WHERE id = ?4",
+                "UPDATE beatmapset SET image_path = ?1, artist = ?2, title = ?3, creator = ?4 WHERE id = ?5",
             )
             .bind(image_path)
             .bind(artist)
             .bind(title)
+            .bind(creator)
             .bind(id)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
             Ok(id)
         }
         None => {
             // Insert a new row.
             let result = sqlx::query(
-                "INSERT INTO beatmapset (path, image_path, artist, title) VALUES (?1, ?2, ?3, ?4)",
+                "INSERT INTO beatmapset (path, image_path, artist, title, creator) VALUES (?1, ?2, ?3, ?4, ?5)",
             )
             .bind(path)
             .bind(image_path)
             .bind(artist)
             .bind(title)
-            .execute(pool)
+            .bind(creator)
+            .execute(&mut *conn)
             .await?;
             Ok(result.last_insert_rowid())
         }
     }
 }
 
+/// Sets or clears the archived flag of a beatmapset.
+pub async fn set_beatmapset_archived(
+    pool: &SqlitePool,
+    beatmapset_id: i64,
+    archived: bool,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE beatmapset SET archived = ?1 WHERE id = ?2")
+        .bind(archived)
+        .bind(beatmapset_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Stores the player's metadata edit of a set, or drops it with `None`. The
+/// edit is written for every chart of the set, so it follows the charts if
+/// a rescan gives the set a new id.
+pub async fn set_metadata_override(
+    pool: &SqlitePool,
+    beatmapset_id: i64,
+    metadata: Option<&MetadataEdit>,
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        "DELETE FROM metadata_override WHERE beatmap_hash IN (SELECT hash FROM beatmap WHERE beatmapset_id = ?1)",
+    )
+    .bind(beatmapset_id)
+    .execute(&mut *tx)
+    .await?;
+    if let Some(metadata) = metadata {
+        let field = |value: &str| {
+            let value = value.trim();
+            (!value.is_empty()).then(|| value.to_string())
+        };
+        sqlx::query(
+            "INSERT INTO metadata_override (beatmap_hash, title, artist, tags, source) SELECT hash, ?1, ?2, ?3, ?4 FROM beatmap WHERE beatmapset_id = ?5",
+        )
+        .bind(field(&metadata.title))
+        .bind(field(&metadata.artist))
+        .bind(field(&metadata.tags))
+        .bind(field(&metadata.source))
+        .bind(beatmapset_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Records why a chart failed to load, or clears it once it loads again.
+pub async fn set_beatmap_load_error(
+    pool: &SqlitePool,
+    hash: &str,
+    load_error: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE beatmap SET load_error = ?1 WHERE hash = ?2")
+        .bind(load_error)
+        .bind(hash)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Stores the dominant patterns of a chart (see `difficulty::pattern_tags`).
+pub async fn set_beatmap_pattern_tags(
+    pool: &SqlitePool,
+    hash: &str,
+    pattern_tags: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE beatmap SET pattern_tags = ?1 WHERE hash = ?2")
+        .bind(pattern_tags)
+        .bind(hash)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Returns the folder paths of every archived beatmapset.
+pub async fn get_archived_paths(pool: &SqlitePool) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar("SELECT path FROM beatmapset WHERE archived = 1")
+        .fetch_all(pool)
+        .await
+}
+
+/// Re-applies the archived flag by folder path (used after a rescan).
+pub async fn restore_archived_paths(
+    pool: &SqlitePool,
+    paths: &[String],
+) -> Result<(), sqlx::Error> {
+    for path in paths {
+        sqlx::query("UPDATE beatmapset SET archived = 1 WHERE path = ?1")
+            .bind(path)
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Folder path and last imported mtime of every beatmapset, for incremental scans.
+pub async fn get_scanned_beatmapsets(
+    pool: &SqlitePool,
+) -> Result<Vec<(i64, String, Option<i64>)>, sqlx::Error> {
+    sqlx::query_as("SELECT id, path, scanned_mtime FROM beatmapset")
+        .fetch_all(pool)
+        .await
+}
+
+/// Records the folder mtime a beatmapset was imported at.
+pub async fn set_beatmapset_scanned_mtime(
+    conn: &mut SqliteConnection,
+    beatmapset_id: i64,
+    mtime: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE beatmapset SET scanned_mtime = ?1 WHERE id = ?2")
+        .bind(mtime)
+        .bind(beatmapset_id)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+/// Records the pack folder a beatmapset was found in (`None` outside packs).
+pub async fn set_beatmapset_pack(
+    conn: &mut SqliteConnection,
+    beatmapset_id: i64,
+    pack: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE beatmapset SET pack = ?1 WHERE id = ?2")
+        .bind(pack)
+        .bind(beatmapset_id)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+/// Stores the title and artist of a set in their original script, when the
+/// chart files give one next to the romanized version.
+pub async fn set_beatmapset_unicode_metadata(
+    conn: &mut SqliteConnection,
+    beatmapset_id: i64,
+    title_unicode: Option<&str>,
+    artist_unicode: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE beatmapset SET title_unicode = ?1, artist_unicode = ?2 WHERE id = ?3")
+        .bind(title_unicode)
+        .bind(artist_unicode)
+        .bind(beatmapset_id)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+/// Removes the charts of a beatmapset (before it is re-imported), or the
+/// whole set when `keep_set` is false. Like `clear_all`, replays are kept.
+///
+/// Charts that also have a copy in another set are moved to that copy
+/// instead of being removed.
+pub async fn clear_beatmapset(
+    pool: &SqlitePool,
+    beatmapset_id: i64,
+    keep_set: bool,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM beatmap_source WHERE beatmapset_id = ?1")
+        .bind(beatmapset_id)
+        .execute(pool)
+        .await?;
+    let copies: Vec<(String, String, i64)> = sqlx::query_as(
+        "SELECT s.hash, MIN(s.path), s.beatmapset_id FROM beatmap_source s JOIN beatmap b ON b.hash = s.hash WHERE b.beatmapset_id = ?1 GROUP BY s.hash",
+    )
+    .bind(beatmapset_id)
+    .fetch_all(pool)
+    .await?;
+    for (hash, path, owner) in copies {
+        sqlx::query("DELETE FROM beatmap_source WHERE path = ?1")
+            .bind(&path)
+            .execute(pool)
+            .await?;
+        sqlx::query("UPDATE beatmap SET path = ?1, beatmapset_id = ?2 WHERE hash = ?3")
+            .bind(&path)
+            .bind(owner)
+            .bind(&hash)
+            .execute(pool)
+            .await?;
+    }
+
+    sqlx::query(
+        "DELETE FROM beatmap_rating WHERE beatmap_hash IN (SELECT hash FROM beatmap WHERE beatmapset_id = ?1)",
+    )
+    .bind(beatmapset_id)
+    .execute(pool)
+    .await?;
+    sqlx::query("DELETE FROM beatmap WHERE beatmapset_id = ?1")
+        .bind(beatmapset_id)
+        .execute(pool)
+        .await?;
+    if !keep_set {
+        sqlx::query("DELETE FROM beatmapset WHERE id = ?1")
+            .bind(beatmapset_id)
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Records `path` as another copy of the chart `hash` when that chart was
+/// already imported from a different set. Returns `false` (nothing recorded)
+/// if the chart is new or belongs to `beatmapset_id`, in which case it goes
+/// through `insert_beatmap` as usual.
+pub async fn merge_duplicate_beatmap(
+    conn: &mut SqliteConnection,
+    beatmapset_id: i64,
+    hash: &str,
+    path: &str,
+) -> Result<bool, sqlx::Error> {
+    let owner: Option<i64> =
+        sqlx::query_scalar("SELECT beatmapset_id FROM beatmap WHERE hash = ?1")
+            .bind(hash)
+            .fetch_optional(&mut *conn)
+            .await?;
+    if owner.is_none_or(|owner| owner == beatmapset_id) {
+        return Ok(false);
+    }
+
+    sqlx::query(
+        "INSERT OR REPLACE INTO beatmap_source (path, hash, beatmapset_id) VALUES (?1, ?2, ?3)",
+    )
+    .bind(path)
+    .bind(hash)
+    .bind(beatmapset_id)
+    .execute(&mut *conn)
+    .await?;
+    Ok(true)
+}
+
 /// Inserts or updates a beatmap record.
 pub async fn insert_beatmap(
-    pool: &SqlitePool,
+    conn: &mut SqliteConnection,
     beatmapset_id: i64,
     hash: &str,
     path: &str,
@@ -74,18 +323,19 @@ pub async fn insert_beatmap(
     note_count: i32,
     duration_ms: i32,
     nps: f64,
+    key_count: i32,
 ) -> Result<String, sqlx::Error> {
     // Check whether a beatmap already exists for the given hash.
     let existing: Option<String> = sqlx::query_scalar("SELECT hash FROM beatmap WHERE hash = ?1")
         .bind(hash)
-        .fetch_optional(pool)
+        .fetch_optional(&mut *conn)
         .await?;
 
     match existing {
         Some(existing_hash) => {
             // Update the existing row.
             sqlx::query(
-                "UPDATE beatmap SET beatmapset_id = ?1, path = ?2, difficulty_name = ?3, note_count = ?4, duration_ms = ?5, nps = ?6 WHERE hash = ?7"
+                "UPDATE beatmap SET beatmapset_id = ?1, path = ?2, difficulty_name = ?3, note_count = ?4, duration_ms = ?5, nps = ?6, key_count = ?7, load_error = NULL WHERE hash = ?8"
             )
             .bind(beatmapset_id)
             .bind(path)
@@ -93,15 +343,16 @@ pub async fn insert_beatmap(
             .bind(note_count)
             .bind(duration_ms)
             .bind(nps)
+            .bind(key_count)
             .bind(&existing_hash)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
             Ok(existing_hash)
         }
         None => {
             // Insert a new row.
             sqlx::query(
-                "INSERT INTO beatmap (hash, beatmapset_id, path, difficulty_name, note_count, duration_ms, nps) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
+                "INSERT INTO beatmap (hash, beatmapset_id, path, difficulty_name, note_count, duration_ms, nps, key_count) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"
             )
             .bind(hash)
             .bind(beatmapset_id)
@@ -110,7 +361,8 @@ pub async fn insert_beatmap(
             .bind(note_count)
             .bind(duration_ms)
             .bind(nps)
-            .execute(pool)
+            .bind(key_count)
+            .execute(&mut *conn)
             .await?;
             Ok(hash.to_string())
         }
@@ -132,6 +384,71 @@ pub async fn get_ratings_for_beatmap(
     Ok(ratings)
 }
 
+/// Stores (or replaces) the rating of a beatmap for one calculator.
+pub async fn insert_beatmap_rating(
+    pool: &SqlitePool,
+    beatmap_hash: &str,
+    name: &str,
+    ssr: &BeatmapSsr,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT OR REPLACE INTO beatmap_rating (beatmap_hash, name, overall, stream, jumpstream, handstream, stamina, jackspeed, chordjack, technical)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+    )
+    .bind(beatmap_hash)
+    .bind(name)
+    .bind(ssr.overall)
+    .bind(ssr.stream)
+    .bind(ssr.jumpstream)
+    .bind(ssr.handstream)
+    .bind(ssr.stamina)
+    .bind(ssr.jackspeed)
+    .bind(ssr.chordjack)
+    .bind(ssr.technical)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Hash and path of the osu! charts that have no rating yet for a calculator
+/// (other formats are not rated).
+pub async fn get_unrated_beatmaps(
+    pool: &SqlitePool,
+    name: &str,
+) -> Result<Vec<(String, String)>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT b.hash, b.path FROM beatmap b
+         LEFT JOIN beatmap_rating br ON br.beatmap_hash = b.hash AND br.name = ?1
+         WHERE br.id IS NULL AND LOWER(b.path) LIKE '%.osu'",
+    )
+    .bind(name)
+    .fetch_all(pool)
+    .await
+}
+
+/// Key count and overall rating of every rated, non-archived chart, plus the
+/// total number of non-archived charts.
+pub async fn get_library_ratings(
+    pool: &SqlitePool,
+    name: &str,
+) -> Result<(Vec<(i32, f64)>, i64), sqlx::Error> {
+    let rated: Vec<(i32, f64)> = sqlx::query_as(
+        "SELECT b.key_count, br.overall FROM beatmap b
+         JOIN beatmapset bs ON bs.id = b.beatmapset_id
+         JOIN beatmap_rating br ON br.beatmap_hash = b.hash AND br.name = ?1
+         WHERE bs.archived = 0",
+    )
+    .bind(name)
+    .fetch_all(pool)
+    .await?;
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM beatmap b JOIN beatmapset bs ON bs.id = b.beatmapset_id WHERE bs.archived = 0",
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok((rated, total))
+}
+
 /// Retrieves all ratings across the database.
 pub async fn get_all_beatmap_ratings(pool: &SqlitePool) -> Result<Vec<BeatmapRating>, sqlx::Error> {
     let ratings: Vec<BeatmapRating> = sqlx::query_as(
@@ -142,15 +459,23 @@ pub async fn get_all_beatmap_ratings(pool: &SqlitePool) -> Result<Vec<BeatmapRat
     Ok(ratings)
 }
 
-/// Retrieves every beatmapset together with its beatmaps/ratings.
+/// Columns of a [`Beatmapset`] with the player's metadata edit applied over
+/// the scanned values (an edited title or artist also replaces the original
+/// script one). Expects the set as `bs`, joined with [`METADATA_OVERRIDE_JOIN`].
+const BEATMAPSET_COLUMNS: &str = "bs.id, bs.path, bs.image_path, COALESCE(mo.artist, bs.artist) AS artist, COALESCE(mo.title, bs.title) AS title, IIF(mo.artist IS NULL, bs.artist_unicode, NULL) AS artist_unicode, IIF(mo.title IS NULL, bs.title_unicode, NULL) AS title_unicode, bs.creator, bs.archived, bs.pack, mo.tags, mo.source, mo.beatmap_hash IS NOT NULL AS edited";
+
+/// Joins the metadata edit of the set `bs`, found through any of its charts.
+const METADATA_OVERRIDE_JOIN: &str = "LEFT JOIN metadata_override mo ON mo.beatmap_hash = (SELECT o.beatmap_hash FROM metadata_override o JOIN beatmap ob ON ob.hash = o.beatmap_hash WHERE ob.beatmapset_id = bs.id LIMIT 1)";
+
+/// Retrieves every beatmapset together with its beatmaps/ratings. Sets whose
+/// charts were all merged into copies from other sets are left out.
 pub async fn get_all_beatmapsets(
     pool: &SqlitePool,
 ) -> Result<Vec<(Beatmapset, Vec<BeatmapWithRatings>)>, sqlx::Error> {
-    let beatmapsets: Vec<Beatmapset> = sqlx::query_as(
-        "SELECT id, path, image_path, artist, title FROM beatmapset ORDER BY artist, title",
-    )
-    .fetch_all(pool)
-    .await?;
+    let sql = format!(
+        "SELECT {BEATMAPSET_COLUMNS} FROM beatmapset bs {METADATA_OVERRIDE_JOIN} WHERE bs.archived = 0 AND EXISTS (SELECT 1 FROM beatmap b WHERE b.beatmapset_id = bs.id) ORDER BY artist, title",
+    );
+    let beatmapsets: Vec<Beatmapset> = sqlx::query_as(&sql).fetch_all(pool).await?;
 
     let ratings = get_all_beatmap_ratings(pool).await?;
     let mut ratings_map: HashMap<String, Vec<BeatmapRating>> = HashMap::new();
@@ -164,7 +489,7 @@ pub async fn get_all_beatmapsets(
     let mut result = Vec::new();
     for beatmapset in beatmapsets {
         let beatmaps: Vec<Beatmap> = sqlx::query_as(
-            "SELECT hash, beatmapset_id, path, difficulty_name, note_count, duration_ms, nps FROM beatmap WHERE beatmapset_id = ?1 ORDER BY difficulty_name"
+            "SELECT hash, beatmapset_id, path, difficulty_name, note_count, duration_ms, nps, load_error, pattern_tags FROM beatmap WHERE beatmapset_id = ?1 ORDER BY difficulty_name"
         )
         .bind(beatmapset.id)
         .fetch_all(pool)
@@ -216,20 +541,51 @@ pub async fn search_beatmapsets(
 
     let sql = format!(
         r#"
-        SELECT DISTINCT bs.id, bs.path, bs.image_path, bs.artist, bs.title
+        SELECT DISTINCT {columns}
         FROM beatmapset bs
         JOIN beatmap b ON b.beatmapset_id = bs.id
         LEFT JOIN beatmap_rating br ON br.beatmap_hash = b.hash AND LOWER(br.name) = LOWER(?3)
+        {metadata_join}
         WHERE
-            (?1 = '' OR LOWER(bs.title) LIKE ?2 OR LOWER(bs.artist) LIKE ?2 OR LOWER(IFNULL(b.difficulty_name, '')) LIKE ?2)
+            (?1 = ''
+                OR LOWER(COALESCE(mo.title, bs.title)) LIKE ?2
+                OR LOWER(COALESCE(mo.artist, bs.artist)) LIKE ?2
+                OR LOWER(IFNULL(bs.title_unicode, '')) LIKE ?2
+                OR LOWER(IFNULL(bs.artist_unicode, '')) LIKE ?2
+                OR LOWER(IFNULL(mo.tags, '')) LIKE ?2
+                OR LOWER(IFNULL(mo.source, '')) LIKE ?2
+                OR LOWER(IFNULL(b.difficulty_name, '')) LIKE ?2)
             AND (?4 = 0 OR IFNULL(br.{col}, 0) >= ?5)
             AND (?6 = 0 OR IFNULL(br.{col}, 0) <= ?7)
             AND (?8 = 0 OR b.duration_ms >= ?9)
             AND (?10 = 0 OR b.duration_ms <= ?11)
-        ORDER BY bs.artist, bs.title
+            AND bs.archived = ?12
+            AND (?13 IS NULL OR bs.creator = ?13)
+            AND (?14 IS NULL OR bs.pack = ?14)
+            AND (?15 = 0 OR b.load_error IS NULL)
+            {tags}
+        ORDER BY {order}
         LIMIT 500
         "#,
-        col = rating_column
+        columns = BEATMAPSET_COLUMNS,
+        metadata_join = METADATA_OVERRIDE_JOIN,
+        col = rating_column,
+        // Tag ids are fixed identifiers, safe to inline.
+        tags = filters
+            .pattern_tags
+            .iter()
+            .map(|tag| format!(
+                "AND INSTR(',' || IFNULL(b.pattern_tags, '') || ',', ',{},') > 0",
+                tag.id()
+            ))
+            .collect::<Vec<_>>()
+            .join(" "),
+        order = if filters.group_by_pack {
+            // Sets outside any pack come last.
+            "bs.pack IS NULL, bs.pack COLLATE NOCASE, artist, title"
+        } else {
+            "artist, title"
+        }
     );
 
     let beatmapsets: Vec<Beatmapset> = sqlx::query_as(&sql)
@@ -244,6 +600,10 @@ pub async fn search_beatmapsets(
         .bind(min_duration_ms)
         .bind(max_duration_active)
         .bind(max_duration_ms)
+        .bind(filters.show_archived)
+        .bind(filters.creator.as_deref())
+        .bind(filters.pack.as_deref())
+        .bind(filters.hide_broken)
         .fetch_all(pool)
         .await?;
 
@@ -251,9 +611,10 @@ pub async fn search_beatmapsets(
 
     for beatmapset in beatmapsets {
         let beatmaps: Vec<Beatmap> = sqlx::query_as(
-            "SELECT hash, beatmapset_id, path, difficulty_name, note_count, duration_ms, nps FROM beatmap WHERE beatmapset_id = ?1 ORDER BY difficulty_name",
+            "SELECT hash, beatmapset_id, path, difficulty_name, note_count, duration_ms, nps, load_error, pattern_tags FROM beatmap WHERE beatmapset_id = ?1 AND (?2 = 0 OR load_error IS NULL) ORDER BY difficulty_name",
         )
         .bind(beatmapset.id)
+        .bind(filters.hide_broken)
         .fetch_all(pool)
         .await?;
 
@@ -283,6 +644,7 @@ pub async fn insert_replay(
     max_combo: i32,
     rate: f64,
     data: &crate::models::replay::ReplayData,
+    profile_id: i64,
 ) -> Result<String, sqlx::Error> {
     // Generate deterministic hash
     // We serialize data to simple JSON string just for the hash entropy
@@ -303,7 +665,7 @@ pub async fn insert_replay(
 
     // Insert into database with file_path
     sqlx::query(
-        "INSERT INTO replay (hash, beatmap_hash, timestamp, score, accuracy, max_combo, rate, file_path) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"
+        "INSERT INTO replay (hash, beatmap_hash, timestamp, score, accuracy, max_combo, rate, file_path, profile_id, judge, practice) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)"
     )
     .bind(&hash)
     .bind(beatmap_hash)
@@ -313,21 +675,575 @@ pub async fn insert_replay(
     .bind(max_combo)
     .bind(rate)
     .bind(&file_path)
+    .bind(profile_id)
+    .bind(judge_key(data.hit_window_mode, data.hit_window_value))
+    // Incomplete runs stay out of personal bests and ratings like practice.
+    .bind(data.is_practice_mode || data.incomplete)
     .execute(pool)
     .await?;
     Ok(hash)
 }
 
-/// Retrieves all replays for a beatmap, sorted by rate then accuracy (best first).
+/// Retrieves a profile's replays for a beatmap, sorted by rate then accuracy
+/// (best first). Enough of them to find the personal best of every rate and
+/// judge played.
 pub async fn get_replays_for_beatmap(
     pool: &SqlitePool,
     beatmap_hash: &str,
+    profile_id: i64,
 ) -> Result<Vec<Replay>, sqlx::Error> {
     let replays: Vec<Replay> = sqlx::query_as(
-        "SELECT hash, beatmap_hash, timestamp, score, accuracy, max_combo, rate, file_path FROM replay WHERE beatmap_hash = ?1 ORDER BY rate DESC, accuracy DESC, timestamp DESC LIMIT 10"
+        "SELECT hash, beatmap_hash, timestamp, score, accuracy, max_combo, rate, file_path, judge, practice FROM replay WHERE beatmap_hash = ?1 AND profile_id = ?2 ORDER BY rate DESC, accuracy DESC, timestamp DESC LIMIT 100"
     )
     .bind(beatmap_hash)
+    .bind(profile_id)
     .fetch_all(pool)
     .await?;
     Ok(replays)
 }
+
+/// Retrieves every replay of a profile, for library maintenance.
+pub async fn get_all_replays(
+    pool: &SqlitePool,
+    profile_id: i64,
+) -> Result<Vec<Replay>, sqlx::Error> {
+    sqlx::query_as(
+        "SELECT hash, beatmap_hash, timestamp, score, accuracy, max_combo, rate, file_path, judge, practice FROM replay WHERE profile_id = ?1",
+    )
+    .bind(profile_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Deletes replays and their column stats (the files are left to the caller).
+pub async fn delete_replays(pool: &SqlitePool, hashes: &[String]) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    for hash in hashes {
+        sqlx::query("DELETE FROM replay_column WHERE replay_hash = ?1")
+            .bind(hash)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM replay WHERE hash = ?1")
+            .bind(hash)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await
+}
+
+/// Rebuilds the database file, giving the space of deleted rows back.
+pub async fn vacuum(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query("VACUUM").execute(pool).await?;
+    Ok(())
+}
+
+/// Records the hit window and practice flag of a replay saved before they
+/// were stored.
+pub async fn set_replay_judge(
+    pool: &SqlitePool,
+    hash: &str,
+    judge: &str,
+    practice: bool,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE replay SET judge = ?1, practice = ?2 WHERE hash = ?3")
+        .bind(judge)
+        .bind(practice)
+        .bind(hash)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Stores the per-column breakdown of a saved replay.
+pub async fn insert_replay_columns(
+    pool: &SqlitePool,
+    replay_hash: &str,
+    columns: &[ColumnStats],
+) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    for column in columns {
+        let s = &column.hit_stats;
+        sqlx::query(
+            "INSERT OR REPLACE INTO replay_column (replay_hash, column_index, marv, perfect, great, good, bad, miss, ghost_tap, hits, mean_offset_ms) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        )
+        .bind(replay_hash)
+        .bind(column.column as i64)
+        .bind(s.marv)
+        .bind(s.perfect)
+        .bind(s.great)
+        .bind(s.good)
+        .bind(s.bad)
+        .bind(s.miss)
+        .bind(s.ghost_tap)
+        .bind(column.deviation.count as i64)
+        .bind(column.deviation.mean_ms)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await
+}
+
+/// Judgements and mean offset of each lane over a profile's saved plays
+/// (practice excluded), per keymode.
+pub async fn get_lane_stats(
+    pool: &SqlitePool,
+    profile_id: i64,
+) -> Result<Vec<LaneStats>, sqlx::Error> {
+    type Row = (i32, i64, i64, i64, i64, i64, i64, i64, i64, i64, f64);
+    let rows: Vec<Row> = sqlx::query_as(
+        "SELECT b.key_count, rc.column_index, SUM(rc.marv), SUM(rc.perfect), SUM(rc.great),
+                SUM(rc.good), SUM(rc.bad), SUM(rc.miss), SUM(rc.ghost_tap), SUM(rc.hits),
+                SUM(rc.mean_offset_ms * rc.hits)
+         FROM replay_column rc
+         JOIN replay r ON r.hash = rc.replay_hash
+         JOIN beatmap b ON b.hash = r.beatmap_hash
+         WHERE r.profile_id = ?1 AND r.practice = 0
+         GROUP BY b.key_count, rc.column_index
+         ORDER BY b.key_count, rc.column_index",
+    )
+    .bind(profile_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(
+                key_count,
+                column,
+                marv,
+                perfect,
+                great,
+                good,
+                bad,
+                miss,
+                ghost_tap,
+                hits,
+                offsets,
+            )| {
+                LaneStats {
+                    key_count,
+                    column: column.max(0) as usize,
+                    hit_stats: HitStats {
+                        marv: marv as u32,
+                        perfect: perfect as u32,
+                        great: great as u32,
+                        good: good as u32,
+                        bad: bad as u32,
+                        miss: miss as u32,
+                        ghost_tap: ghost_tap as u32,
+                        columns: Vec::new(),
+                    },
+                    mean_offset_ms: if hits > 0 { offsets / hits as f64 } else { 0.0 },
+                }
+            },
+        )
+        .collect())
+}
+
+/// Average rating of a profile's latest cleared scores (at least
+/// `min_accuracy`), each worth the chart's 1.0x overall times its rate.
+/// `None` when none of them is on a chart the calculator rated.
+pub async fn get_recent_score_rating(
+    pool: &SqlitePool,
+    name: &str,
+    profile_id: i64,
+    min_accuracy: f64,
+    limit: i64,
+) -> Result<Option<f64>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT AVG(overall * rate) FROM (
+            SELECT br.overall, r.rate FROM replay r
+            JOIN beatmap_rating br ON br.beatmap_hash = r.beatmap_hash AND br.name = ?1
+            WHERE r.profile_id = ?2 AND r.accuracy >= ?3
+            ORDER BY r.timestamp DESC LIMIT ?4
+        )",
+    )
+    .bind(name)
+    .bind(profile_id)
+    .bind(min_accuracy)
+    .bind(limit)
+    .fetch_one(pool)
+    .await
+}
+
+// ============================================================================
+// GOAL QUERIES
+// ============================================================================
+
+/// Inserts a goal and its charts, returning the new goal id.
+pub async fn insert_goal(
+    pool: &SqlitePool,
+    draft: &GoalDraft,
+    created_at: i64,
+    profile_id: i64,
+) -> Result<i64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let result = sqlx::query(
+        "INSERT INTO goal (name, target_accuracy, target_score, min_rate, created_at, profile_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )
+    .bind(&draft.name)
+    .bind(draft.target_accuracy)
+    .bind(draft.target_score)
+    .bind(draft.min_rate)
+    .bind(created_at)
+    .bind(profile_id)
+    .execute(&mut *tx)
+    .await?;
+    let goal_id = result.last_insert_rowid();
+
+    for (position, hash) in draft.beatmap_hashes.iter().enumerate() {
+        sqlx::query("INSERT INTO goal_chart (goal_id, position, beatmap_hash) VALUES (?1, ?2, ?3)")
+            .bind(goal_id)
+            .bind(position as i64)
+            .bind(hash)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+    Ok(goal_id)
+}
+
+/// Deletes a goal together with its tracked charts.
+pub async fn delete_goal(pool: &SqlitePool, goal_id: i64) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM goal_chart WHERE goal_id = ?1")
+        .bind(goal_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM goal WHERE id = ?1")
+        .bind(goal_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await
+}
+
+/// Retrieves every goal of a profile with its charts, in-progress goals first.
+pub async fn get_all_goals(
+    pool: &SqlitePool,
+    profile_id: i64,
+) -> Result<Vec<GoalWithCharts>, sqlx::Error> {
+    let goals: Vec<Goal> = sqlx::query_as(
+        "SELECT id, name, target_accuracy, target_score, min_rate, completed_at FROM goal WHERE profile_id = ?1 ORDER BY completed_at IS NOT NULL, created_at DESC",
+    )
+    .bind(profile_id)
+    .fetch_all(pool)
+    .await?;
+
+    let charts: Vec<GoalChart> = sqlx::query_as(
+        "SELECT goal_id, position, beatmap_hash, best_accuracy, best_score, cleared FROM goal_chart ORDER BY goal_id, position",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut charts_map: HashMap<i64, Vec<GoalChart>> = HashMap::new();
+    for chart in charts {
+        charts_map.entry(chart.goal_id).or_default().push(chart);
+    }
+
+    Ok(goals
+        .into_iter()
+        .map(|goal| {
+            let charts = charts_map.remove(&goal.id).unwrap_or_default();
+            GoalWithCharts { goal, charts }
+        })
+        .collect())
+}
+
+/// Records a play against every open goal of the profile tracking this chart.
+/// Course-style goals only keep the best results: they are cleared by
+/// [`record_course_goal_progress`].
+///
+/// Returns the number of goals that became completed with this play.
+pub async fn record_goal_progress(
+    pool: &SqlitePool,
+    beatmap_hash: &str,
+    accuracy: f64,
+    score: i64,
+    rate: f64,
+    timestamp: i64,
+    profile_id: i64,
+) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    // Rates are stored as floats; allow a tiny epsilon so 1.0 == 1.0.
+    sqlx::query(
+        r#"
+        UPDATE goal_chart
+        SET best_accuracy = MAX(best_accuracy, ?2), best_score = MAX(best_score, ?3)
+        WHERE beatmap_hash = ?1
+          AND goal_id IN (
+            SELECT id FROM goal
+            WHERE completed_at IS NULL AND min_rate <= ?4 + 0.0001 AND profile_id = ?5
+          )
+        "#,
+    )
+    .bind(beatmap_hash)
+    .bind(accuracy)
+    .bind(score)
+    .bind(rate)
+    .bind(profile_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        UPDATE goal_chart
+        SET cleared = 1
+        WHERE cleared = 0
+          AND beatmap_hash = ?1
+          AND (SELECT COUNT(*) FROM goal_chart c WHERE c.goal_id = goal_chart.goal_id) = 1
+          AND EXISTS (
+            SELECT 1 FROM goal g
+            WHERE g.id = goal_chart.goal_id
+              AND g.completed_at IS NULL
+              AND (g.target_accuracy IS NULL OR goal_chart.best_accuracy >= g.target_accuracy)
+              AND (g.target_score IS NULL OR goal_chart.best_score >= g.target_score)
+          )
+        "#,
+    )
+    .bind(beatmap_hash)
+    .execute(&mut *tx)
+    .await?;
+
+    let completed = sqlx::query(
+        r#"
+        UPDATE goal
+        SET completed_at = ?1
+        WHERE completed_at IS NULL
+          AND EXISTS (SELECT 1 FROM goal_chart gc WHERE gc.goal_id = goal.id)
+          AND NOT EXISTS (SELECT 1 FROM goal_chart gc WHERE gc.goal_id = goal.id AND gc.cleared = 0)
+        "#,
+    )
+    .bind(timestamp)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(completed.rows_affected())
+}
+
+/// Completes the open course-style goals of the profile whose charts a
+/// finished course run played back to back at `rate` or faster.
+///
+/// Returns the number of goals completed by the run.
+pub async fn record_course_goal_progress(
+    pool: &SqlitePool,
+    stages: &[CourseStagePlay],
+    rate: f64,
+    timestamp: i64,
+    profile_id: i64,
+) -> Result<u64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let goals: Vec<Goal> = sqlx::query_as(
+        "SELECT id, name, target_accuracy, target_score, min_rate, completed_at FROM goal WHERE completed_at IS NULL AND profile_id = ?1 AND min_rate <= ?2 + 0.0001",
+    )
+    .bind(profile_id)
+    .bind(rate)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut completed = 0;
+    for goal in goals {
+        let charts: Vec<String> = sqlx::query_scalar(
+            "SELECT beatmap_hash FROM goal_chart WHERE goal_id = ?1 ORDER BY position",
+        )
+        .bind(goal.id)
+        .fetch_all(&mut *tx)
+        .await?;
+        if charts.len() < 2
+            || !run_meets_goal(stages, &charts, goal.target_accuracy, goal.target_score)
+        {
+            continue;
+        }
+
+        sqlx::query("UPDATE goal_chart SET cleared = 1 WHERE goal_id = ?1")
+            .bind(goal.id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("UPDATE goal SET completed_at = ?2 WHERE id = ?1")
+            .bind(goal.id)
+            .bind(timestamp)
+            .execute(&mut *tx)
+            .await?;
+        completed += 1;
+    }
+
+    tx.commit().await?;
+    Ok(completed)
+}
+
+/// Inserts a course and its ordered charts, returning the new course id.
+pub async fn insert_course(
+    pool: &SqlitePool,
+    draft: &CourseDraft,
+    created_at: i64,
+) -> Result<i64, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    let result = sqlx::query("INSERT INTO course (name, created_at) VALUES (?1, ?2)")
+        .bind(&draft.name)
+        .bind(created_at)
+        .execute(&mut *tx)
+        .await?;
+    let course_id = result.last_insert_rowid();
+
+    for (position, hash) in draft.beatmap_hashes.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO course_chart (course_id, position, beatmap_hash) VALUES (?1, ?2, ?3)",
+        )
+        .bind(course_id)
+        .bind(position as i64)
+        .bind(hash)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(course_id)
+}
+
+/// Deletes a course together with its chart list.
+pub async fn delete_course(pool: &SqlitePool, course_id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM course_chart WHERE course_id = ?1")
+        .bind(course_id)
+        .execute(pool)
+        .await?;
+    sqlx::query("DELETE FROM course WHERE id = ?1")
+        .bind(course_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Retrieves every course with its charts resolved against the library.
+pub async fn get_all_courses(pool: &SqlitePool) -> Result<Vec<CourseWithCharts>, sqlx::Error> {
+    let courses: Vec<Course> =
+        sqlx::query_as("SELECT id, name FROM course ORDER BY created_at DESC")
+            .fetch_all(pool)
+            .await?;
+
+    let charts: Vec<CourseChart> = sqlx::query_as(
+        r#"
+        SELECT cc.course_id, cc.beatmap_hash, b.path, bs.title, b.difficulty_name
+        FROM course_chart cc
+        LEFT JOIN beatmap b ON b.hash = cc.beatmap_hash
+        LEFT JOIN beatmapset bs ON bs.id = b.beatmapset_id
+        ORDER BY cc.course_id, cc.position
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut charts_map: HashMap<i64, Vec<CourseChart>> = HashMap::new();
+    for chart in charts {
+        charts_map.entry(chart.course_id).or_default().push(chart);
+    }
+
+    Ok(courses
+        .into_iter()
+        .map(|course| {
+            let charts = charts_map.remove(&course.id).unwrap_or_default();
+            CourseWithCharts { course, charts }
+        })
+        .collect())
+}
+
+// ============================================================================
+// PROFILE QUERIES
+// ============================================================================
+
+/// Creates a profile, returning its id.
+pub async fn insert_profile(
+    pool: &SqlitePool,
+    name: &str,
+    created_at: i64,
+) -> Result<i64, sqlx::Error> {
+    let result = sqlx::query("INSERT INTO profile (name, created_at) VALUES (?1, ?2)")
+        .bind(name)
+        .bind(created_at)
+        .execute(pool)
+        .await?;
+    Ok(result.last_insert_rowid())
+}
+
+/// Retrieves every profile, oldest first.
+pub async fn get_all_profiles(pool: &SqlitePool) -> Result<Vec<Profile>, sqlx::Error> {
+    sqlx::query_as("SELECT id, name FROM profile ORDER BY id")
+        .fetch_all(pool)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::migrations;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn scan_set(pool: &SqlitePool) -> i64 {
+        let mut conn = pool.acquire().await.unwrap();
+        let id = insert_beatmapset(
+            &mut conn,
+            "songs/set",
+            None,
+            Some("Artist"),
+            Some("Title"),
+            None,
+        )
+        .await
+        .unwrap();
+        insert_beatmap(
+            &mut conn,
+            id,
+            "hash",
+            "songs/set/a.osu",
+            None,
+            100,
+            60_000,
+            1.6,
+            4,
+        )
+        .await
+        .unwrap();
+        id
+    }
+
+    #[tokio::test]
+    async fn metadata_edit_is_searchable_and_survives_a_rescan() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        migrations::run(&pool).await.unwrap();
+        let id = scan_set(&pool).await;
+        let edit = MetadataEdit {
+            title: "Fixed title".to_string(),
+            source: "Some game".to_string(),
+            ..Default::default()
+        };
+        set_metadata_override(&pool, id, Some(&edit)).await.unwrap();
+
+        clear_all(&pool).await.unwrap();
+        scan_set(&pool).await;
+        let sets = get_all_beatmapsets(&pool).await.unwrap();
+        let set = &sets[0].0;
+        assert_eq!(set.title.as_deref(), Some("Fixed title"));
+        assert_eq!(set.artist.as_deref(), Some("Artist"));
+        assert!(set.edited);
+
+        let filters = MenuSearchFilters {
+            query: "some game".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(search_beatmapsets(&pool, &filters).await.unwrap().len(), 1);
+
+        set_metadata_override(&pool, set.id, None).await.unwrap();
+        let sets = get_all_beatmapsets(&pool).await.unwrap();
+        assert_eq!(sets[0].0.title.as_deref(), Some("Title"));
+        assert!(!sets[0].0.edited);
+    }
+}
@@ -2,134 +2,730 @@
 //!
 //! This scanner has been optimized to only extract basic metadata during import.
 //! Difficulty ratings are now calculated on-demand when a map is selected.
+//!
+//! `.osu` charts are imported through `rosu_map`; BMS, Malody and StepMania
+//! charts go through the converters in `models::formats`.
+//!
+//! The library is made of one or more root folders (`songs/` by default,
+//! see `SettingsState::library_roots`). Sets sit either directly under a
+//! root or one level deeper inside an Etterna-style pack folder
+//! (`songs/<pack>/<song>/chart.sm`), in which case the pack name is stored
+//! on the set.
 
 use crate::database::connection::Database;
-use crate::database::query::insert_beatmap;
+use crate::database::query::{
+    clear_beatmapset, get_scanned_beatmapsets, insert_beatmap, insert_beatmapset,
+    merge_duplicate_beatmap, set_beatmapset_pack, set_beatmapset_scanned_mtime,
+    set_beatmapset_unicode_metadata,
+};
+use crate::database::thumbnail_cache;
 use crate::difficulty;
+use crate::models::engine::NoteData;
+use crate::models::formats::{ChartFormat, bms, malody, sm};
+use crate::models::settings::{BmsScratchMode, ChartRules, ScanOptions};
 use md5::Context;
+use sqlx::{Acquire, SqliteConnection};
+use std::collections::HashMap;
 use std::fs;
-use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Set folders stored per transaction during a scan.
+const BATCH_SIZE: usize = 64;
 
-/// Scans the `songs/` directory and fills the database.
+/// Scans the library roots and fills the database. Returns how many charts
+/// were merged into an identical chart from another set.
+/// `progress(done, total)` counts set folders.
 ///
 /// Note: This scanner now only extracts basic metadata (hash, notes, duration, nps).
 /// Difficulty ratings are NOT calculated here - they are computed on-demand
 /// when the user selects a beatmap in the song select menu.
 pub async fn scan_songs_directory(
     db: &Database,
-    songs_path: &Path,
-) -> Result<(), Box<dyn std::error::Error>> {
-    if !songs_path.exists() {
-        eprintln!("The songs/ directory does not exist");
-        return Ok(());
+    roots: &[PathBuf],
+    options: ScanOptions,
+    progress: &mut dyn FnMut(usize, usize),
+) -> Result<usize, Box<dyn std::error::Error>> {
+    import_sets(db, collect_library_sets(roots)?, options, progress).await
+}
+
+/// What an incremental scan changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScanSummary {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+    /// Charts found identical (same hash) to a chart of another set.
+    pub merged: usize,
+}
+
+impl ScanSummary {
+    pub fn is_empty(&self) -> bool {
+        self.added + self.updated + self.removed == 0
     }
+}
 
-    // Walk every sub-folder under songs/.
-    let entries = fs::read_dir(songs_path)?;
+/// Brings the database in line with the library roots without a full rescan.
+///
+/// Folders whose mtime differs from the one recorded at import time are
+/// re-imported (only their charts are re-hashed), new folders are imported
+/// and sets whose folder is gone are removed, as are the sets of roots no
+/// longer in `roots`. Sets of a root that is configured but missing (an
+/// unplugged drive) are kept. `progress(done, total)` counts the folders
+/// being (re)imported.
+pub async fn incremental_scan(
+    db: &Database,
+    roots: &[PathBuf],
+    options: ScanOptions,
+    progress: &mut dyn FnMut(usize, usize),
+) -> Result<ScanSummary, Box<dyn std::error::Error>> {
+    let mut summary = ScanSummary::default();
+    let missing_roots: Vec<&PathBuf> = roots.iter().filter(|root| !root.is_dir()).collect();
 
-    for entry in entries {
-        let entry = entry?;
-        let path = entry.path();
+    let mut known: HashMap<String, (i64, Option<i64>)> = get_scanned_beatmapsets(db.pool())
+        .await?
+        .into_iter()
+        .map(|(id, path, mtime)| (path, (id, mtime)))
+        .collect();
 
-        if !path.is_dir() {
+    let mut pending = Vec::new();
+    for set in collect_library_sets(roots)? {
+        let Some(path_str) = set.path.to_str() else {
             continue;
+        };
+
+        let existing = known.remove(path_str);
+        let mtime = folder_mtime(&set.path, &set.chart_files);
+        match existing {
+            Some((_, Some(scanned))) if scanned == mtime => {}
+            Some((id, _)) => pending.push((set, Some(id))),
+            None => pending.push((set, None)),
         }
+    }
 
-        let osu_files = match collect_osu_files(&path) {
-            Some(files) if !files.is_empty() => files,
-            _ => continue,
-        };
+    // Whatever is left has no folder (or no chart) on disk anymore.
+    for (path, (id, _)) in known {
+        if missing_roots
+            .iter()
+            .any(|root| Path::new(&path).starts_with(root))
+        {
+            continue;
+        }
+        log::info!("SCANNER: {} was removed from the library", path);
+        clear_beatmapset(db.pool(), id, false).await?;
+        summary.removed += 1;
+    }
+
+    let mut sets = Vec::with_capacity(pending.len());
+    for (set, existing) in pending {
+        if let Some(id) = existing {
+            // Chart hashes change when a file is edited; drop the old rows
+            // so the unique path constraint does not reject the new ones.
+            clear_beatmapset(db.pool(), id, true).await?;
+            summary.updated += 1;
+        } else {
+            summary.added += 1;
+        }
+        sets.push(set);
+    }
+
+    summary.merged = import_sets(db, sets, options, progress).await?;
+
+    Ok(summary)
+}
+
+/// Imports a single beatmapset folder (e.g. a freshly downloaded set)
+/// without clearing or rescanning the rest of the library.
+pub async fn import_beatmapset_folder(
+    db: &Database,
+    folder: &Path,
+    rules: ChartRules,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match collect_chart_files(folder) {
+        Some(files) if !files.is_empty() => {
+            process_beatmapset(db, folder, &files, None, rules, &mut |_, _| {}).await?;
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Extracts a `.osz` / `.zip` archive into `songs_path` and imports the new
+/// beatmapset. `progress(done, total)` is called after each chart file.
+/// Returns the folder the set was extracted to.
+///
+/// Archives holding a single top-level folder (a zipped song folder) are
+/// flattened so the charts sit directly under `songs/<name>/`. A zipped pack
+/// is kept as `songs/<pack>/<song>/` and every song of it is imported, with
+/// `progress` counting songs instead of charts.
+pub async fn import_archive(
+    db: &Database,
+    archive: &Path,
+    songs_path: &Path,
+    options: ScanOptions,
+    progress: &mut dyn FnMut(usize, usize),
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let stem = archive
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or("archive has no file name")?;
 
-        if let Err(e) = process_beatmapset(db, &path, &osu_files).await {
-            eprintln!("Error processing beatmapset {:?}: {}", path, e);
+    // Extract next to the library first, then move the set into place.
+    let staging = songs_path.join(format!(".import-{}", stem));
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    extract_archive(archive, &staging)?;
+
+    let (root, name) = match single_subfolder(&staging) {
+        Some(inner) if collect_chart_files(&staging).is_none_or(|f| f.is_empty()) => {
+            let name = inner.file_name().map(|n| n.to_os_string());
+            (inner, name)
         }
+        _ => (staging.clone(), Some(stem.into())),
+    };
+    let folder = songs_path.join(name.ok_or("archive has no file name")?);
+    if folder.exists() {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(format!("{:?} is already in the library", folder).into());
+    }
+    let moved = fs::rename(&root, &folder);
+    let _ = fs::remove_dir_all(&staging);
+    moved?;
+
+    if let Some(chart_files) = collect_chart_files(&folder).filter(|f| !f.is_empty()) {
+        process_beatmapset(db, &folder, &chart_files, None, options.rules, progress).await?;
+        return Ok(folder);
+    }
+
+    let songs = pack_songs(&folder);
+    if songs.is_empty() {
+        let _ = fs::remove_dir_all(&folder);
+        return Err("no playable chart in the archive".into());
     }
+    let first_song = songs[0].0.clone();
+    let pack = folder
+        .file_name()
+        .and_then(|n| n.to_str())
+        .map(str::to_string);
+    let sets = songs
+        .into_iter()
+        .map(|(path, chart_files)| SetFolder {
+            path,
+            chart_files,
+            pack: pack.clone(),
+        })
+        .collect();
+    import_sets(db, sets, options, progress).await?;
+    // Focus the first song of the pack.
+    Ok(first_song)
+}
+
+/// Extracts a zip archive (`.osz` included) into `folder`, which is created if needed.
+pub fn extract_archive(archive: &Path, folder: &Path) -> Result<(), String> {
+    let file = fs::File::open(archive).map_err(|e| e.to_string())?;
+    let mut zip = zip::ZipArchive::new(file).map_err(|e| format!("Invalid archive: {}", e))?;
+    fs::create_dir_all(folder).map_err(|e| e.to_string())?;
+    zip.extract(folder).map_err(|e| e.to_string())
+}
+
+/// The only entry of `path`, if it is a directory: an archive that wraps its
+/// content in one top-level folder.
+pub fn single_subfolder(path: &Path) -> Option<PathBuf> {
+    let mut entries = fs::read_dir(path).ok()?.filter_map(|e| e.ok());
+    let first = entries.next()?.path();
+    (entries.next().is_none() && first.is_dir()).then_some(first)
+}
 
-    Ok(())
+/// A beatmapset folder found under a library root.
+struct SetFolder {
+    path: PathBuf,
+    chart_files: Vec<PathBuf>,
+    /// Name of the pack folder holding the set, if any.
+    pack: Option<String>,
 }
 
-fn collect_osu_files(path: &Path) -> Option<Vec<PathBuf>> {
+/// Every beatmapset folder of the roots that exist.
+fn collect_library_sets(roots: &[PathBuf]) -> std::io::Result<Vec<SetFolder>> {
+    let mut sets = Vec::new();
+    for root in roots {
+        if root.is_dir() {
+            sets.extend(collect_set_folders(root)?);
+        } else {
+            log::warn!("SCANNER: Library folder {:?} does not exist", root);
+        }
+    }
+    Ok(sets)
+}
+
+/// Every beatmapset folder under a library root.
+///
+/// A folder holding charts is a set; a folder without charts is a pack whose
+/// sub-folders are the sets. Hidden folders include the staging folders of
+/// archive imports and are skipped.
+fn collect_set_folders(songs_path: &Path) -> std::io::Result<Vec<SetFolder>> {
+    let mut sets = Vec::new();
+    for entry in fs::read_dir(songs_path)? {
+        let path = entry?.path();
+        if is_hidden(&path) || !path.is_dir() {
+            continue;
+        }
+        match collect_chart_files(&path) {
+            Some(chart_files) if !chart_files.is_empty() => sets.push(SetFolder {
+                path,
+                chart_files,
+                pack: None,
+            }),
+            _ => {
+                let pack = path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .map(str::to_string);
+                sets.extend(
+                    pack_songs(&path)
+                        .into_iter()
+                        .map(|(path, chart_files)| SetFolder {
+                            path,
+                            chart_files,
+                            pack: pack.clone(),
+                        }),
+                );
+            }
+        }
+    }
+    Ok(sets)
+}
+
+/// Song folders of a pack folder, with their charts.
+fn pack_songs(pack: &Path) -> Vec<(PathBuf, Vec<PathBuf>)> {
+    let Ok(entries) = fs::read_dir(pack) else {
+        return Vec::new();
+    };
+    let mut songs: Vec<_> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| !is_hidden(p) && p.is_dir())
+        .filter_map(|p| {
+            let files = collect_chart_files(&p).filter(|f| !f.is_empty())?;
+            Some((p, files))
+        })
+        .collect();
+    songs.sort();
+    songs
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_none_or(|n| n.starts_with('.'))
+}
+
+fn collect_chart_files(path: &Path) -> Option<Vec<PathBuf>> {
     let entries = fs::read_dir(path).ok()?;
-    let files = entries
+    let mut files = entries
         .filter_map(|e| e.ok())
         .map(|e| e.path())
-        .filter(|p| p.extension().and_then(|s| s.to_str()) == Some("osu"))
+        .filter(|p| ChartFormat::from_path(p).is_some())
         .collect::<Vec<_>>();
+    // .osu first so that mixed folders take their metadata from osu!.
+    files.sort_by_key(|p| ChartFormat::from_path(p) != Some(ChartFormat::Osu));
     Some(files)
 }
 
+/// Imports one set folder: parses it, then stores it in a single transaction.
 async fn process_beatmapset(
     db: &Database,
     folder: &Path,
-    osu_files: &[PathBuf],
-) -> Result<(), Box<dyn std::error::Error>> {
-    let Some(first_osu) = osu_files.first() else {
-        return Ok(());
-    };
+    chart_files: &[PathBuf],
+    pack: Option<&str>,
+    rules: ChartRules,
+    progress: &mut dyn FnMut(usize, usize),
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let set = parse_set(folder, chart_files, pack, rules, progress).map_err(|e| e.to_string())?;
+    let mut tx = db.pool().begin().await?;
+    let merged = store_set(&mut tx, &set).await?;
+    tx.commit().await?;
+    Ok(merged)
+}
 
-    let map = rosu_map::Beatmap::from_path(first_osu)?;
-    let title = map.title.clone();
-    let artist = map.artist.clone();
-    let image_path = if map.background_file.is_empty() {
-        None
-    } else {
-        find_background_image(folder, Some(map.background_file.as_str()))
-    };
+/// Imports many set folders. Parsing and hashing run on `options.workers`
+/// threads (0 = one per core) while this task stores the parsed sets as they arrive,
+/// [`BATCH_SIZE`] sets per transaction. `progress(done, total)` counts folders.
+async fn import_sets(
+    db: &Database,
+    sets: Vec<SetFolder>,
+    options: ScanOptions,
+    progress: &mut dyn FnMut(usize, usize),
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let total = sets.len();
+    progress(0, total);
+    if total == 0 {
+        return Ok(0);
+    }
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(options.workers)
+        .thread_name(|i| format!("scanner-{}", i))
+        .build()?;
+    let (results_tx, mut results) = tokio::sync::mpsc::unbounded_channel();
+    for set in sets {
+        let results_tx = results_tx.clone();
+        pool.spawn(move || {
+            let parsed = parse_set(
+                &set.path,
+                &set.chart_files,
+                set.pack.as_deref(),
+                options.rules,
+                &mut |_, _| {},
+            );
+            let _ = results_tx.send((set.path, parsed));
+        });
+    }
+    // The pool winds down on its own once the queued folders are done.
+    drop(pool);
+    drop(results_tx);
+
+    let mut merged = 0;
+    let mut done = 0;
+    let mut tx = db.pool().begin().await?;
+    while let Some((folder, parsed)) = results.recv().await {
+        match parsed {
+            Ok(set) => {
+                // A savepoint per set, so one bad set does not undo the batch.
+                let mut savepoint = tx.begin().await?;
+                match store_set(&mut savepoint, &set).await {
+                    Ok(count) => {
+                        savepoint.commit().await?;
+                        merged += count;
+                    }
+                    Err(e) => eprintln!("Error storing beatmapset {:?}: {}", folder, e),
+                }
+            }
+            Err(e) => eprintln!("Error processing beatmapset {:?}: {}", folder, e),
+        }
 
-    let Some(path_str) = folder.to_str() else {
-        return Ok(());
+        done += 1;
+        progress(done, total);
+        if done % BATCH_SIZE == 0 {
+            tx.commit().await?;
+            tx = db.pool().begin().await?;
+        }
+    }
+    tx.commit().await?;
+
+    Ok(merged)
+}
+
+/// Error of a folder parsed on a scanner thread.
+type ParseError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A set folder read from disk, ready to be stored.
+struct ParsedSet {
+    path: String,
+    pack: Option<String>,
+    title: String,
+    artist: String,
+    /// Original script title and artist, when they differ from the others.
+    title_unicode: Option<String>,
+    artist_unicode: Option<String>,
+    creator: String,
+    image_path: Option<String>,
+    mtime: i64,
+    charts: Vec<ParsedChart>,
+}
+
+/// Basic metadata of a chart (difficulty ratings are computed on demand).
+struct ParsedChart {
+    hash: String,
+    path: String,
+    difficulty_name: String,
+    note_count: i32,
+    duration_ms: i32,
+    nps: f64,
+    key_count: i32,
+}
+
+/// Reads the metadata of a set and of each of its charts. Charts that fail
+/// to parse are logged and skipped. `progress(done, total)` counts charts.
+fn parse_set(
+    folder: &Path,
+    chart_files: &[PathBuf],
+    pack: Option<&str>,
+    rules: ChartRules,
+    progress: &mut dyn FnMut(usize, usize),
+) -> Result<ParsedSet, ParseError> {
+    let first_chart = chart_files.first().ok_or("no chart in the folder")?;
+    let path = folder.to_str().ok_or("folder path is not valid UTF-8")?;
+
+    let (title, artist, creator, background, unicode) = match ChartFormat::from_path(first_chart) {
+        Some(ChartFormat::Bms) => {
+            let chart = bms::parse_bms(first_chart, rules.bms_scratch)?;
+            let unicode = (None, None);
+            (
+                chart.title,
+                chart.artist,
+                String::new(),
+                chart.stage_file,
+                unicode,
+            )
+        }
+        Some(ChartFormat::Malody) => {
+            let chart = malody::parse_mc(first_chart)?;
+            let unicode = (chart.title_unicode, chart.artist_unicode);
+            (
+                chart.title,
+                chart.artist,
+                chart.creator,
+                chart.background,
+                unicode,
+            )
+        }
+        Some(ChartFormat::Sm) => {
+            let file = sm::parse_sm(first_chart)?;
+            let unicode = (file.title_unicode, file.artist_unicode);
+            (
+                file.title,
+                file.artist,
+                file.creator,
+                file.background,
+                unicode,
+            )
+        }
+        _ => {
+            let map = rosu_map::Beatmap::from_path(first_chart)?;
+            let background = (!map.background_file.is_empty()).then_some(map.background_file);
+            let unicode = (Some(map.title_unicode), Some(map.artist_unicode));
+            (map.title, map.artist, map.creator, background, unicode)
+        }
     };
+    let title_unicode = original_script(unicode.0, &title);
+    let artist_unicode = original_script(unicode.1, &artist);
 
-    let beatmapset_id = db
-        .insert_beatmapset(
-            path_str,
-            image_path.as_deref(),
-            Some(artist.as_str()),
-            Some(title.as_str()),
-        )
-        .await?;
+    let mut charts = Vec::with_capacity(chart_files.len());
+    for (done, chart_file) in chart_files.iter().enumerate() {
+        let format = ChartFormat::from_path(chart_file);
+        if format.is_some_and(ChartFormat::is_experimental) {
+            log::warn!(
+                "SCANNER: Importing {:?} with experimental support",
+                chart_file
+            );
+        }
 
-    for osu_file in osu_files {
-        if let Err(e) = process_osu_file(db, beatmapset_id, osu_file).await {
-            eprintln!("Error processing {:?}: {}", osu_file, e);
+        let result = match format {
+            Some(ChartFormat::Bms) => parse_bms_file(chart_file, rules.bms_scratch),
+            Some(ChartFormat::Malody) => parse_malody_file(chart_file),
+            Some(ChartFormat::Sm) => parse_sm_file(chart_file),
+            _ => parse_osu_file(chart_file),
+        };
+        match result {
+            Ok(parsed) => charts.extend(parsed),
+            Err(e) => eprintln!("Error processing {:?}: {}", chart_file, e),
         }
+        progress(done + 1, chart_files.len());
+    }
+
+    let image_path = find_background_image(folder, background.as_deref());
+    if let Some(image) = &image_path
+        && let Err(e) = thumbnail_cache::ensure_thumbnail(image)
+    {
+        log::warn!("SCANNER: No thumbnail for {:?}: {}", folder, e);
     }
 
-    Ok(())
+    Ok(ParsedSet {
+        path: path.to_string(),
+        pack: pack.map(str::to_string),
+        title,
+        artist,
+        title_unicode,
+        artist_unicode,
+        creator,
+        image_path,
+        mtime: folder_mtime(folder, chart_files),
+        charts,
+    })
 }
 
-async fn process_osu_file(
-    db: &Database,
-    beatmapset_id: i64,
-    osu_file: &PathBuf,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let hash = calculate_file_hash(osu_file)?;
-    let bm = rosu_map::Beatmap::from_path(osu_file)?;
+/// Keeps the original script version of a name only when the files give
+/// one that differs from the romanized name.
+fn original_script(original: Option<String>, romanized: &str) -> Option<String> {
+    original
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty() && name != romanized.trim())
+}
 
-    // Extract basic info WITHOUT calculating difficulty
-    let basic_info = difficulty::extract_basic_info(&bm)?;
-    let difficulty_name = bm.version.clone();
+/// Writes a parsed set and its charts. Returns how many charts were merged
+/// into an identical chart from another set instead of being inserted.
+async fn store_set(conn: &mut SqliteConnection, set: &ParsedSet) -> Result<usize, sqlx::Error> {
+    let beatmapset_id = insert_beatmapset(
+        &mut *conn,
+        &set.path,
+        set.image_path.as_deref(),
+        Some(set.artist.as_str()),
+        Some(set.title.as_str()),
+        (!set.creator.is_empty()).then_some(set.creator.as_str()),
+    )
+    .await?;
+    set_beatmapset_scanned_mtime(&mut *conn, beatmapset_id, set.mtime).await?;
+    set_beatmapset_pack(&mut *conn, beatmapset_id, set.pack.as_deref()).await?;
+    set_beatmapset_unicode_metadata(
+        &mut *conn,
+        beatmapset_id,
+        set.title_unicode.as_deref(),
+        set.artist_unicode.as_deref(),
+    )
+    .await?;
 
-    if let Some(osu_str) = osu_file.to_str() {
+    let mut merged = 0;
+    for chart in &set.charts {
+        if merge_duplicate_beatmap(&mut *conn, beatmapset_id, &chart.hash, &chart.path).await? {
+            merged += 1;
+            continue;
+        }
         insert_beatmap(
-            db.pool(),
+            &mut *conn,
             beatmapset_id,
-            &hash,
-            osu_str,
-            Some(&difficulty_name),
-            basic_info.note_count,
-            basic_info.duration_ms,
-            basic_info.nps,
+            &chart.hash,
+            &chart.path,
+            Some(&chart.difficulty_name),
+            chart.note_count,
+            chart.duration_ms,
+            chart.nps,
+            chart.key_count,
         )
         .await?;
+    }
+    if merged > 0 {
+        log::info!(
+            "SCANNER: {} chart(s) of {:?} merged into copies from other sets",
+            merged,
+            set.path
+        );
+    }
+
+    Ok(merged)
+}
+
+fn path_string(path: &Path) -> Result<String, ParseError> {
+    Ok(path
+        .to_str()
+        .ok_or("chart path is not valid UTF-8")?
+        .to_string())
+}
+
+fn parse_osu_file(osu_file: &Path) -> Result<Vec<ParsedChart>, ParseError> {
+    let hash = calculate_file_hash(osu_file)?;
+    let bm = rosu_map::Beatmap::from_path(osu_file)?;
+
+    // Extract basic info WITHOUT calculating difficulty
+    let basic_info = difficulty::extract_basic_info(&bm).map_err(|e| e.to_string())?;
+
+    Ok(vec![ParsedChart {
+        hash,
+        path: path_string(osu_file)?,
+        difficulty_name: bm.version.clone(),
+        note_count: basic_info.note_count,
+        duration_ms: basic_info.duration_ms,
+        nps: basic_info.nps,
+        // osu!mania stores the key count as the circle size.
+        key_count: bm.circle_size.round() as i32,
+    }])
+}
 
-        // NOTE: We no longer calculate ratings here!
-        // Ratings are computed on-demand when the user selects a beatmap.
-        // This dramatically speeds up the scan process.
+/// Reads a BMS chart (experimental: no keysound playback, no difficulty ratings).
+fn parse_bms_file(
+    bms_file: &Path,
+    bms_scratch: BmsScratchMode,
+) -> Result<Vec<ParsedChart>, ParseError> {
+    let chart = bms::parse_bms(bms_file, bms_scratch)?;
+    if chart.audio_path.is_none() {
+        return Err("no background audio (keysound-only BMS charts are not supported)".into());
     }
 
-    Ok(())
+    let (note_count, duration_ms, nps) = chart_stats(&chart.notes);
+    Ok(vec![ParsedChart {
+        hash: calculate_file_hash(bms_file)?,
+        path: path_string(bms_file)?,
+        difficulty_name: chart.difficulty_name(),
+        note_count,
+        duration_ms,
+        nps,
+        key_count: chart.key_count as i32,
+    }])
+}
+
+/// Reads a Malody key-mode chart (no difficulty ratings).
+fn parse_malody_file(mc_file: &Path) -> Result<Vec<ParsedChart>, ParseError> {
+    let chart = malody::parse_mc(mc_file)?;
+    if !chart.audio_path.exists() {
+        return Err(format!("song audio {:?} not found", chart.audio_path).into());
+    }
+
+    let (note_count, duration_ms, nps) = chart_stats(&chart.notes);
+    Ok(vec![ParsedChart {
+        hash: calculate_file_hash(mc_file)?,
+        path: path_string(mc_file)?,
+        difficulty_name: chart.difficulty_name(),
+        note_count,
+        duration_ms,
+        nps,
+        key_count: chart.key_count as i32,
+    }])
+}
+
+/// Reads every chart of a StepMania `.sm` file, each becoming its own
+/// beatmap (no difficulty ratings). Each chart hashes the file plus its index.
+fn parse_sm_file(sm_file: &Path) -> Result<Vec<ParsedChart>, ParseError> {
+    let file = sm::parse_sm(sm_file)?;
+    if !file.audio_path.exists() {
+        return Err(format!("song audio {:?} not found", file.audio_path).into());
+    }
+
+    let bytes = fs::read(sm_file)?;
+    let mut charts = Vec::with_capacity(file.charts.len());
+    for (index, chart) in file.charts.iter().enumerate() {
+        let mut context = Context::new();
+        context.consume(&bytes);
+        context.consume(format!("#{}", index));
+        let (note_count, duration_ms, nps) = chart_stats(&chart.notes);
+        charts.push(ParsedChart {
+            hash: format!("{:x}", context.finalize()),
+            path: path_string(&sm::chart_path(sm_file, index))?,
+            difficulty_name: chart.difficulty_name(),
+            note_count,
+            duration_ms,
+            nps,
+            key_count: chart.key_count as i32,
+        });
+    }
+
+    Ok(charts)
+}
+
+/// Note count, duration (ms) and NPS of a converted chart, fake notes excluded.
+fn chart_stats(notes: &[NoteData]) -> (i32, i32, f64) {
+    let judged: Vec<_> = notes.iter().filter(|n| n.is_judged()).collect();
+    let first = judged.first().map(|n| n.timestamp_ms).unwrap_or(0.0);
+    let last = judged.iter().map(|n| n.end_time_ms()).fold(first, f64::max);
+    let duration = (last - first).max(0.0);
+    let nps = if duration > 0.0 {
+        judged.len() as f64 / (duration / 1000.0)
+    } else {
+        0.0
+    };
+    (judged.len() as i32, duration as i32, nps)
+}
+
+/// Latest modification time (seconds) of a set folder and its charts.
+/// The folder's own mtime changes when files are added or removed, the
+/// charts' when they are edited in place.
+fn folder_mtime(folder: &Path, chart_files: &[PathBuf]) -> i64 {
+    std::iter::once(folder)
+        .chain(chart_files.iter().map(PathBuf::as_path))
+        .filter_map(|p| fs::metadata(p).and_then(|m| m.modified()).ok())
+        .filter_map(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .max()
+        .unwrap_or(0)
 }
 
 fn find_background_image(beatmapset_path: &Path, filename: Option<&str>) -> Option<String> {
@@ -143,14 +739,14 @@ fn find_background_image(beatmapset_path: &Path, filename: Option<&str>) -> Opti
     })
 }
 
-/// Computes the MD5 hash for an `.osu` chart file.
+/// Computes the MD5 hash for a chart file.
+///
+/// Hashes raw bytes, since BMS files are usually not valid UTF-8.
 fn calculate_file_hash(file_path: &Path) -> Result<String, std::io::Error> {
-    let mut file = fs::File::open(file_path)?;
-    let mut buffer = String::new();
-    file.read_to_string(&mut buffer)?;
+    let buffer = fs::read(file_path)?;
 
     let mut context = Context::new();
-    context.consume(buffer.as_bytes());
+    context.consume(&buffer);
     let result = context.finalize();
     let hash_string = format!("{:x}", result);
 
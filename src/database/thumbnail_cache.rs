@@ -0,0 +1,77 @@
+//! On-disk cache of song list thumbnails.
+//!
+//! Set backgrounds are often full-size JPEGs of several megabytes, far too
+//! heavy to decode for every card of the song list. The scanner writes a
+//! small copy of each background to `data/t/{md5 of the path}.jpg`; a
+//! thumbnail is rebuilt when its background is newer than it.
+
+use image::imageops::FilterType;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Base directory for thumbnails.
+const THUMBNAIL_DIR: &str = "data/t";
+
+/// Thumbnail size in pixels (16:9, twice the size drawn on a card).
+pub const THUMBNAIL_WIDTH: u32 = 256;
+pub const THUMBNAIL_HEIGHT: u32 = 144;
+
+/// Get the path of the thumbnail of a background image.
+pub fn thumbnail_path(image_path: &str) -> PathBuf {
+    PathBuf::from(THUMBNAIL_DIR).join(format!("{:x}.jpg", md5::compute(image_path)))
+}
+
+/// Returns the thumbnail of `image_path`, creating or refreshing it first
+/// when needed.
+pub fn ensure_thumbnail(image_path: &str) -> Result<PathBuf, String> {
+    let path = thumbnail_path(image_path);
+    if is_fresh(&path, Path::new(image_path)) {
+        return Ok(path);
+    }
+
+    let image = image::open(image_path).map_err(|e| format!("{}: {}", image_path, e))?;
+    // JPEG has no alpha channel.
+    let thumbnail = image
+        .resize_to_fill(THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT, FilterType::Triangle)
+        .to_rgb8();
+    fs::create_dir_all(THUMBNAIL_DIR).map_err(|e| e.to_string())?;
+    thumbnail
+        .save(&path)
+        .map_err(|e| format!("{:?}: {}", path, e))?;
+    Ok(path)
+}
+
+/// Whether the thumbnail exists and is not older than its source.
+fn is_fresh(thumbnail: &Path, source: &Path) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+    match (modified(thumbnail), modified(source)) {
+        (Some(thumbnail), Some(source)) => thumbnail >= source,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thumbnails_are_cropped_and_reused() {
+        let source = std::env::temp_dir().join("rvsrg_thumbnail_source.png");
+        image::RgbImage::from_pixel(400, 400, image::Rgb([200, 40, 40]))
+            .save(&source)
+            .unwrap();
+        let source = source.to_str().unwrap();
+
+        let path = ensure_thumbnail(source).unwrap();
+        assert_eq!(
+            image::image_dimensions(&path).unwrap(),
+            (THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT)
+        );
+        let written = fs::metadata(&path).unwrap().modified().unwrap();
+        assert_eq!(ensure_thumbnail(source).unwrap(), path);
+        assert_eq!(fs::metadata(&path).unwrap().modified().unwrap(), written);
+
+        fs::remove_file(path).unwrap();
+        fs::remove_file(source).unwrap();
+    }
+}
@@ -0,0 +1,79 @@
+//! Watches the library folders and asks the database thread for an
+//! incremental scan when folders are added, removed or edited.
+
+use crate::database::manager::DbCommand;
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{DebounceEventResult, Debouncer, new_debouncer};
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+
+/// Quiet period before a scan, so copying a whole folder triggers one scan.
+const DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// Keeps the watcher alive; dropping it stops watching.
+pub struct SongsWatcher {
+    _debouncer: Debouncer<RecommendedWatcher>,
+}
+
+impl SongsWatcher {
+    /// Starts watching the library folders. Returns `None` (and logs) when
+    /// the platform watcher cannot be created or no folder can be watched;
+    /// F8 rescans still work then.
+    pub fn start(library_roots: &[PathBuf], commands: Sender<DbCommand>) -> Option<Self> {
+        // Event paths may come back canonicalized, so match against both forms.
+        let roots: Vec<PathBuf> = library_roots
+            .iter()
+            .flat_map(|root| {
+                [
+                    root.clone(),
+                    root.canonicalize().unwrap_or_else(|_| root.clone()),
+                ]
+            })
+            .collect();
+        let handler = move |result: DebounceEventResult| match result {
+            Ok(events) => {
+                if events.iter().any(|e| is_library_change(&roots, &e.path)) {
+                    let _ = commands.send(DbCommand::IncrementalScan);
+                }
+            }
+            Err(e) => log::warn!("WATCHER: {}", e),
+        };
+
+        let mut debouncer = match new_debouncer(DEBOUNCE, handler) {
+            Ok(debouncer) => debouncer,
+            Err(e) => {
+                log::error!("WATCHER: Could not create the songs watcher: {}", e);
+                return None;
+            }
+        };
+        let mut watching = false;
+        for root in library_roots {
+            match debouncer.watcher().watch(root, RecursiveMode::Recursive) {
+                Ok(()) => {
+                    log::info!("WATCHER: Watching {:?}", root);
+                    watching = true;
+                }
+                Err(e) => log::error!("WATCHER: Could not watch {:?}: {}", root, e),
+            }
+        }
+        if !watching {
+            return None;
+        }
+
+        Some(Self {
+            _debouncer: debouncer,
+        })
+    }
+}
+
+/// Ignores hidden entries (archive import staging folders among them).
+fn is_library_change(roots: &[PathBuf], path: &Path) -> bool {
+    let Some(relative) = roots.iter().find_map(|root| path.strip_prefix(root).ok()) else {
+        return true;
+    };
+    !relative.components().any(|c| match c {
+        Component::Normal(name) => name.to_string_lossy().starts_with('.'),
+        _ => false,
+    })
+}
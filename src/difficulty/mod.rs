@@ -1,6 +1,7 @@
 //! Difficulty calculation module.
 //!
-//! This module provides difficulty calculation using Etterna (MinaCalc) and osu! (rosu-pp).
+//! This module provides difficulty calculation using Etterna (MinaCalc) and osu! (rosu-pp),
+//! plus community calculators scripted in Rhai (see [`script`]).
 //!
 //! ## Usage
 //!
@@ -9,10 +10,13 @@
 
 pub mod builtin;
 pub mod calculator;
+pub mod pattern_tags;
+pub mod script;
 
 // Re-export commonly used types
 pub use builtin::{EtternaCalculator, OsuCalculator};
 pub use calculator::CalcError;
+pub use pattern_tags::PatternTag;
 
 use minacalc_rs::Calc;
 use rosu_map::Beatmap;
@@ -186,9 +190,11 @@ pub fn calculate_on_demand(
             let etterna_ssr = EtternaCalculator::calculate_from_beatmap(map, rate)?;
             OsuCalculator::calculate_from_beatmap(map, &etterna_ssr, rate)
         }
-        _ => Err(CalcError::Other(format!(
-            "Unknown calculator: {}",
-            calculator_id
-        ))),
+        _ => script::calculate(map, calculator_id, rate).unwrap_or_else(|| {
+            Err(CalcError::Other(format!(
+                "Unknown calculator: {}",
+                calculator_id
+            )))
+        }),
     }
 }
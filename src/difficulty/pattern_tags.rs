@@ -0,0 +1,141 @@
+//! Pattern tags derived from the MinaCalc skillsets.
+//!
+//! Like Etterna's song wheel, a chart is tagged with its dominant skillsets:
+//! the strongest one plus those rated close to it. Tags are stored in the
+//! `beatmap.pattern_tags` column as a comma-separated list of [`PatternTag::id`]
+//! (an empty string for a chart rated without any clear pattern).
+
+use crate::difficulty::BeatmapSsr;
+
+/// A skillset is a tag when it rates at least this fraction of the top one.
+const TAG_THRESHOLD: f64 = 0.93;
+/// Tags kept per chart.
+const MAX_TAGS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PatternTag {
+    Stream,
+    Jumpstream,
+    Handstream,
+    Stamina,
+    Jackspeed,
+    Chordjack,
+    Technical,
+}
+
+impl PatternTag {
+    pub const ALL: [PatternTag; 7] = [
+        PatternTag::Stream,
+        PatternTag::Jumpstream,
+        PatternTag::Handstream,
+        PatternTag::Stamina,
+        PatternTag::Jackspeed,
+        PatternTag::Chordjack,
+        PatternTag::Technical,
+    ];
+
+    /// Name stored in the database.
+    pub fn id(self) -> &'static str {
+        match self {
+            PatternTag::Stream => "stream",
+            PatternTag::Jumpstream => "js",
+            PatternTag::Handstream => "hs",
+            PatternTag::Stamina => "stamina",
+            PatternTag::Jackspeed => "jack",
+            PatternTag::Chordjack => "cj",
+            PatternTag::Technical => "tech",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|tag| tag.id() == id)
+    }
+
+    /// Short label drawn on the chips.
+    pub fn label(self) -> &'static str {
+        match self {
+            PatternTag::Stream => "Stream",
+            PatternTag::Jumpstream => "JS",
+            PatternTag::Handstream => "HS",
+            PatternTag::Stamina => "Stamina",
+            PatternTag::Jackspeed => "Jack",
+            PatternTag::Chordjack => "CJ",
+            PatternTag::Technical => "Tech",
+        }
+    }
+
+    fn rating(self, ssr: &BeatmapSsr) -> f64 {
+        match self {
+            PatternTag::Stream => ssr.stream,
+            PatternTag::Jumpstream => ssr.jumpstream,
+            PatternTag::Handstream => ssr.handstream,
+            PatternTag::Stamina => ssr.stamina,
+            PatternTag::Jackspeed => ssr.jackspeed,
+            PatternTag::Chordjack => ssr.chordjack,
+            PatternTag::Technical => ssr.technical,
+        }
+    }
+}
+
+/// Dominant patterns of a chart from its Etterna ratings, strongest first.
+pub fn classify(ssr: &BeatmapSsr) -> Vec<PatternTag> {
+    let mut rated: Vec<(PatternTag, f64)> = PatternTag::ALL
+        .into_iter()
+        .map(|tag| (tag, tag.rating(ssr)))
+        .filter(|(_, rating)| rating.is_finite() && *rating > 0.0)
+        .collect();
+    rated.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let Some(&(_, top)) = rated.first() else {
+        return Vec::new();
+    };
+    rated
+        .into_iter()
+        .take_while(|(_, rating)| *rating >= top * TAG_THRESHOLD)
+        .take(MAX_TAGS)
+        .map(|(tag, _)| tag)
+        .collect()
+}
+
+/// Column value for a list of tags.
+pub fn to_column(tags: &[PatternTag]) -> String {
+    tags.iter()
+        .map(|tag| tag.id())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Tags of a column value; unknown names are skipped.
+pub fn from_column(column: &str) -> Vec<PatternTag> {
+    column.split(',').filter_map(PatternTag::from_id).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_the_skillsets_close_to_the_top_one() {
+        let ssr = BeatmapSsr {
+            overall: 24.0,
+            stream: 20.0,
+            jumpstream: 24.0,
+            handstream: 23.0,
+            stamina: 21.0,
+            jackspeed: 12.0,
+            chordjack: 18.0,
+            technical: 22.5,
+        };
+        let tags = classify(&ssr);
+        assert_eq!(
+            tags,
+            vec![
+                PatternTag::Jumpstream,
+                PatternTag::Handstream,
+                PatternTag::Technical
+            ]
+        );
+        assert_eq!(to_column(&tags), "js,hs,tech");
+        assert_eq!(from_column("js,hs,tech"), tags);
+        assert!(classify(&BeatmapSsr::default()).is_empty());
+    }
+}
@@ -0,0 +1,189 @@
+//! Community calculators written as Rhai scripts.
+//!
+//! Every `calculators/*.rhai` file found at startup becomes a calculator
+//! named after the file. A script defines
+//!
+//! ```rhai
+//! fn rate(notes, rate, keys) {
+//!     // notes: [#{ time, end_time, column, hold }, ...] sorted by time (ms at 1.0x)
+//!     #{ overall: 12.3, stream: 10.0 }
+//! }
+//! ```
+//!
+//! and may define `fn name() { "Display name" }`. The returned map holds any
+//! of the SSR skillsets; missing ones are 0.
+
+use crate::difficulty::{BeatmapSsr, CalcError};
+use rhai::{AST, Array, Dynamic, Engine, Map, Scope};
+use rosu_map::Beatmap;
+use rosu_map::section::hit_objects::HitObjectKind;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Folder scanned for calculator scripts.
+const SCRIPT_DIR: &str = "calculators";
+
+/// Built-in calculator ids a script cannot take.
+const RESERVED_IDS: [&str; 2] = ["etterna", "osu"];
+
+/// Guards against scripts that never return.
+const MAX_OPERATIONS: u64 = 50_000_000;
+
+pub struct ScriptCalculator {
+    /// File stem, used as the calculator id.
+    pub id: String,
+    pub display_name: String,
+    ast: AST,
+}
+
+struct Scripts {
+    engine: Engine,
+    calculators: Vec<ScriptCalculator>,
+}
+
+static SCRIPTS: OnceLock<Scripts> = OnceLock::new();
+
+fn scripts() -> &'static Scripts {
+    SCRIPTS.get_or_init(|| {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        let calculators = load_dir(&engine, Path::new(SCRIPT_DIR));
+        Scripts {
+            engine,
+            calculators,
+        }
+    })
+}
+
+/// Loads the calculator scripts (once) and returns them.
+pub fn script_calculators() -> &'static [ScriptCalculator] {
+    &scripts().calculators
+}
+
+fn load_dir(engine: &Engine, dir: &Path) -> Vec<ScriptCalculator> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<_> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rhai"))
+        .collect();
+    paths.sort();
+
+    let mut calculators = Vec::new();
+    for path in paths {
+        match load_script(engine, &path) {
+            Ok(calculator) => {
+                log::info!(
+                    "CALC: Loaded script calculator '{}' from {:?}",
+                    calculator.id,
+                    path
+                );
+                calculators.push(calculator);
+            }
+            Err(e) => log::error!("CALC: Skipping {:?}: {}", path, e),
+        }
+    }
+    calculators
+}
+
+fn load_script(engine: &Engine, path: &Path) -> Result<ScriptCalculator, String> {
+    let id = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or("invalid file name")?
+        .to_string();
+    if RESERVED_IDS.contains(&id.as_str()) {
+        return Err(format!("'{}' is a built-in calculator", id));
+    }
+    let ast = engine
+        .compile_file(path.to_path_buf())
+        .map_err(|e| e.to_string())?;
+    if !ast.iter_functions().any(|f| f.name == "rate") {
+        return Err("no `rate(notes, rate, keys)` function".to_string());
+    }
+    let display_name = if ast.iter_functions().any(|f| f.name == "name") {
+        engine
+            .call_fn::<String>(&mut Scope::new(), &ast, "name", ())
+            .map_err(|e| e.to_string())?
+    } else {
+        id.clone()
+    };
+    Ok(ScriptCalculator {
+        id,
+        display_name,
+        ast,
+    })
+}
+
+/// Rates `map` with the script calculator `id`, if there is one.
+pub fn calculate(map: &Beatmap, id: &str, rate: f64) -> Option<Result<BeatmapSsr, CalcError>> {
+    let scripts = scripts();
+    let calculator = scripts.calculators.iter().find(|c| c.id == id)?;
+    let keys = map.circle_size.round().max(1.0) as i64;
+
+    let result = scripts
+        .engine
+        .call_fn::<Dynamic>(
+            &mut Scope::new(),
+            &calculator.ast,
+            "rate",
+            (note_array(map, keys), rate, keys),
+        )
+        .map_err(|e| CalcError::CalculationFailed(format!("{}: {}", id, e)))
+        .and_then(|value| {
+            let ratings = value.try_cast::<Map>().ok_or_else(|| {
+                CalcError::CalculationFailed(format!("{}: `rate` must return a map", id))
+            })?;
+            Ok(ssr_from_map(&ratings))
+        });
+    Some(result)
+}
+
+/// The chart as script values, sorted by time.
+fn note_array(map: &Beatmap, keys: i64) -> Array {
+    let column = |x: f32| ((x / 512.0 * keys as f32).floor() as i64).clamp(0, keys - 1);
+    let mut notes: Vec<(f64, Map)> = map
+        .hit_objects
+        .iter()
+        .filter_map(|object| {
+            let (x, end_time, hold) = match &object.kind {
+                HitObjectKind::Circle(circle) => (circle.pos.x, object.start_time, false),
+                HitObjectKind::Hold(hold) => (hold.pos_x, object.start_time + hold.duration, true),
+                _ => return None,
+            };
+            let mut note = Map::new();
+            note.insert("time".into(), Dynamic::from_float(object.start_time));
+            note.insert("end_time".into(), Dynamic::from_float(end_time));
+            note.insert("column".into(), Dynamic::from_int(column(x)));
+            note.insert("hold".into(), Dynamic::from_bool(hold));
+            Some((object.start_time, note))
+        })
+        .collect();
+    notes.sort_by(|a, b| a.0.total_cmp(&b.0));
+    notes.into_iter().map(|(_, note)| note.into()).collect()
+}
+
+fn ssr_from_map(ratings: &Map) -> BeatmapSsr {
+    let value = |key: &str| {
+        ratings
+            .get(key)
+            .and_then(|v| {
+                v.as_float()
+                    .ok()
+                    .or_else(|| v.as_int().ok().map(|i| i as f64))
+            })
+            .unwrap_or(0.0)
+    };
+    BeatmapSsr {
+        overall: value("overall"),
+        stream: value("stream"),
+        jumpstream: value("jumpstream"),
+        handstream: value("handstream"),
+        stamina: value("stamina"),
+        jackspeed: value("jackspeed"),
+        chordjack: value("chordjack"),
+        technical: value("technical"),
+    }
+}
@@ -0,0 +1,134 @@
+//! Beatmap mirror client: search and `.osz` download.
+//!
+//! The mirror is expected to expose the osu! API v2 layout that the public
+//! mirrors share: `GET {base}/api/v2/search?q=..&mode=3` returns a list of
+//! beatmapsets and `GET {base}/d/{id}` serves the `.osz` archive.
+
+use serde::Deserialize;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// osu! mode id of mania.
+const MANIA_MODE: &str = "3";
+/// Progress is reported every this many bytes.
+const PROGRESS_STEP: u64 = 256 * 1024;
+
+/// A beatmapset returned by a mirror search.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MirrorSet {
+    pub id: i64,
+    #[serde(default)]
+    pub artist: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub creator: String,
+    #[serde(default, rename = "beatmaps")]
+    pub difficulties: Vec<MirrorDifficulty>,
+}
+
+/// One difficulty of a `MirrorSet`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MirrorDifficulty {
+    #[serde(default)]
+    pub version: String,
+    #[serde(default, rename = "difficulty_rating")]
+    pub stars: f64,
+    /// Key count for mania charts.
+    #[serde(default, rename = "cs")]
+    pub keys: f64,
+    #[serde(default)]
+    pub mode: String,
+}
+
+impl MirrorSet {
+    /// "Artist - Title", as shown in the download list.
+    pub fn label(&self) -> String {
+        format!("{} - {}", self.artist, self.title)
+    }
+
+    /// Mania difficulties only (converts are not playable here).
+    pub fn mania_difficulties(&self) -> impl Iterator<Item = &MirrorDifficulty> {
+        self.difficulties
+            .iter()
+            .filter(|d| d.mode.is_empty() || d.mode == "mania")
+    }
+}
+
+fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout_connect(Duration::from_secs(10))
+        .timeout_read(Duration::from_secs(30))
+        .user_agent(concat!("rvsrg/", env!("CARGO_PKG_VERSION")))
+        .build()
+}
+
+/// Searches the mirror for mania beatmapsets matching `query`.
+pub fn search(base_url: &str, query: &str) -> Result<Vec<MirrorSet>, String> {
+    let url = format!("{}/api/v2/search", base_url.trim_end_matches('/'));
+    let body = agent()
+        .get(&url)
+        .query("q", query)
+        .query("mode", MANIA_MODE)
+        .call()
+        .map_err(|e| e.to_string())?
+        .into_string()
+        .map_err(|e| e.to_string())?;
+    serde_json::from_str(&body).map_err(|e| format!("Unexpected mirror response: {}", e))
+}
+
+/// Downloads the `.osz` of `set_id` to `dest`. `progress` receives the bytes
+/// received so far and the total size when the mirror sends it.
+pub fn download(
+    base_url: &str,
+    set_id: i64,
+    dest: &Path,
+    mut progress: impl FnMut(u64, Option<u64>),
+) -> Result<(), String> {
+    let url = format!("{}/d/{}", base_url.trim_end_matches('/'), set_id);
+    let response = agent().get(&url).call().map_err(|e| e.to_string())?;
+    let total = response
+        .header("Content-Length")
+        .and_then(|len| len.parse::<u64>().ok());
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let mut file = File::create(dest).map_err(|e| e.to_string())?;
+    let mut reader = response.into_reader();
+    let mut buffer = [0u8; 64 * 1024];
+    let mut received = 0u64;
+    let mut last_report = 0u64;
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.to_string()),
+        };
+        file.write_all(&buffer[..read]).map_err(|e| e.to_string())?;
+        received += read as u64;
+        if received - last_report >= PROGRESS_STEP {
+            last_report = received;
+            progress(received, total);
+        }
+    }
+    progress(received, total);
+    Ok(())
+}
+
+/// Folder name under `songs/` for a downloaded set, with the characters
+/// file systems reject replaced.
+pub fn folder_name(set: &MirrorSet) -> PathBuf {
+    let name: String = format!("{} {}", set.id, set.label())
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    PathBuf::from(name.trim_end_matches(['.', ' ']))
+}
@@ -0,0 +1,244 @@
+//! Optional beatmap downloader ("Get more songs").
+//!
+//! Searches a beatmap mirror, downloads `.osz` archives into a temporary
+//! folder and extracts them into the first library folder (`songs/` by
+//! default). Like the database, it runs on its
+//! own thread and exposes a shared state that the logic thread mirrors into
+//! the menu. Extracted folders are queued in `DownloadState::imported`; the
+//! logic thread hands them to the database for an incremental import.
+//!
+//! The downloader is disabled when `SettingsState::mirror_url` is empty. The
+//! logic thread passes the mirror and the import folder with each command.
+
+pub mod mirror;
+
+use crate::database::scanner::extract_archive;
+use mirror::MirrorSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Progress of one download.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JobStatus {
+    Downloading {
+        received: u64,
+        total: Option<u64>,
+    },
+    Extracting,
+    Done,
+    /// The folder already exists under `songs/`.
+    AlreadyInstalled,
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct DownloadJob {
+    pub set_id: i64,
+    pub label: String,
+    pub status: JobStatus,
+}
+
+/// State shared between the downloader and the logic thread.
+#[derive(Debug, Clone, Default)]
+pub struct DownloadState {
+    pub searching: bool,
+    pub search_error: Option<String>,
+    pub results: Vec<MirrorSet>,
+    pub jobs: Vec<DownloadJob>,
+    /// Extracted folders not yet imported into the database.
+    pub imported: Vec<PathBuf>,
+    /// Bumped on every change, for the menu sync.
+    pub version: u64,
+}
+
+pub enum DownloadCommand {
+    Search {
+        mirror_url: String,
+        query: String,
+    },
+    Download {
+        mirror_url: String,
+        songs_path: PathBuf,
+        set: MirrorSet,
+    },
+}
+
+pub struct Downloader {
+    state: Arc<Mutex<DownloadState>>,
+    command_sender: Sender<DownloadCommand>,
+}
+
+impl Downloader {
+    pub fn new() -> Self {
+        let state = Arc::new(Mutex::new(DownloadState::default()));
+        let (tx, rx) = channel();
+
+        let state_clone = Arc::clone(&state);
+        thread::Builder::new()
+            .name("Downloader Thread".to_string())
+            .spawn(move || Self::worker(state_clone, rx))
+            .expect("Failed to spawn Downloader thread");
+
+        Self {
+            state,
+            command_sender: tx,
+        }
+    }
+
+    pub fn get_state(&self) -> Arc<Mutex<DownloadState>> {
+        Arc::clone(&self.state)
+    }
+
+    pub fn search(&self, mirror_url: &str, query: String) {
+        let _ = self.command_sender.send(DownloadCommand::Search {
+            mirror_url: mirror_url.to_string(),
+            query,
+        });
+    }
+
+    /// Downloads `set` and extracts it into `songs_path`.
+    pub fn download(&self, mirror_url: &str, songs_path: PathBuf, set: MirrorSet) {
+        let _ = self.command_sender.send(DownloadCommand::Download {
+            mirror_url: mirror_url.to_string(),
+            songs_path,
+            set,
+        });
+    }
+
+    /// Takes the folders extracted since the last call.
+    pub fn take_imported(&self) -> Vec<PathBuf> {
+        match self.state.try_lock() {
+            Ok(mut s) => std::mem::take(&mut s.imported),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    fn worker(state: Arc<Mutex<DownloadState>>, rx: Receiver<DownloadCommand>) {
+        log::info!("DOWNLOADER: Thread started");
+        while let Ok(command) = rx.recv() {
+            let mirror_url = match &command {
+                DownloadCommand::Search { mirror_url, .. }
+                | DownloadCommand::Download { mirror_url, .. } => mirror_url.clone(),
+            };
+            if mirror_url.trim().is_empty() {
+                update(&state, |s| {
+                    s.search_error = Some("No beatmap mirror configured.".to_string());
+                });
+                continue;
+            }
+
+            match command {
+                DownloadCommand::Search { query, .. } => {
+                    update(&state, |s| {
+                        s.searching = true;
+                        s.search_error = None;
+                    });
+                    let result = mirror::search(&mirror_url, &query);
+                    update(&state, |s| {
+                        s.searching = false;
+                        match result {
+                            Ok(results) => s.results = results,
+                            Err(e) => {
+                                log::error!("DOWNLOADER: Search failed: {}", e);
+                                s.search_error = Some(e);
+                            }
+                        }
+                    });
+                }
+                DownloadCommand::Download {
+                    songs_path, set, ..
+                } => {
+                    let already_queued = {
+                        let s = state.lock().unwrap();
+                        s.jobs.iter().any(|job| {
+                            job.set_id == set.id && !matches!(job.status, JobStatus::Failed(_))
+                        })
+                    };
+                    if already_queued {
+                        continue;
+                    }
+                    update(&state, |s| {
+                        s.jobs.push(DownloadJob {
+                            set_id: set.id,
+                            label: set.label(),
+                            status: JobStatus::Downloading {
+                                received: 0,
+                                total: None,
+                            },
+                        });
+                    });
+
+                    // Each download gets its own thread so searches stay responsive.
+                    let state = Arc::clone(&state);
+                    thread::spawn(move || {
+                        let status = run_download(&state, &mirror_url, &set, &songs_path);
+                        set_job_status(&state, set.id, status);
+                    });
+                }
+            }
+        }
+        log::info!("DOWNLOADER: Thread stopped");
+    }
+}
+
+impl Default for Downloader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn update(state: &Arc<Mutex<DownloadState>>, f: impl FnOnce(&mut DownloadState)) {
+    let mut s = state.lock().unwrap();
+    f(&mut s);
+    s.version = s.version.wrapping_add(1);
+}
+
+fn set_job_status(state: &Arc<Mutex<DownloadState>>, set_id: i64, status: JobStatus) {
+    update(state, |s| {
+        // Latest job for the set (an earlier one may have failed).
+        if let Some(job) = s.jobs.iter_mut().rev().find(|job| job.set_id == set_id) {
+            job.status = status;
+        }
+    });
+}
+
+/// Downloads, extracts and queues one set for import. Returns the final status.
+fn run_download(
+    state: &Arc<Mutex<DownloadState>>,
+    mirror_url: &str,
+    set: &MirrorSet,
+    songs_path: &std::path::Path,
+) -> JobStatus {
+    let folder = songs_path.join(mirror::folder_name(set));
+    if folder.exists() {
+        return JobStatus::AlreadyInstalled;
+    }
+
+    let archive = std::env::temp_dir()
+        .join("rvsrg-downloads")
+        .join(format!("{}.osz", set.id));
+    log::info!("DOWNLOADER: Downloading set {} ({})", set.id, set.label());
+    if let Err(e) = mirror::download(mirror_url, set.id, &archive, |received, total| {
+        set_job_status(state, set.id, JobStatus::Downloading { received, total });
+    }) {
+        log::error!("DOWNLOADER: Download of set {} failed: {}", set.id, e);
+        let _ = fs::remove_file(&archive);
+        return JobStatus::Failed(e);
+    }
+
+    set_job_status(state, set.id, JobStatus::Extracting);
+    let extracted = extract_archive(&archive, &folder);
+    let _ = fs::remove_file(&archive);
+    if let Err(e) = extracted {
+        log::error!("DOWNLOADER: Extraction of set {} failed: {}", set.id, e);
+        let _ = fs::remove_dir_all(&folder);
+        return JobStatus::Failed(e);
+    }
+
+    log::info!("DOWNLOADER: Set {} extracted to {:?}", set.id, folder);
+    update(state, |s| s.imported.push(folder));
+    JobStatus::Done
+}
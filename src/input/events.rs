@@ -3,17 +3,40 @@
 //! This module defines all input-related structures used for communication
 //! between the window, input thread, and game logic.
 
+use crate::database::maintenance::MaintenanceRequest;
+use crate::database::models::MetadataEdit;
+use crate::models::course::CourseDraft;
+use crate::models::goal::GoalDraft;
 use crate::models::search::MenuSearchFilters;
+use crate::models::settings::{LaneCover, TournamentSettings, VolumeChannel, Volumes};
 use std::collections::HashMap;
+use std::time::Instant;
 use winit::event::{ElementState, KeyEvent, WindowEvent};
 use winit::keyboard::{KeyCode, PhysicalKey};
 
-/// A raw keyboard input event from the window.
+/// Physical origin of a raw input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputSource {
+    /// A keyboard key.
+    Key(KeyCode),
+    /// A mouse click on a column's receptor (optional, see settings).
+    Receptor(usize),
+    /// A gamepad button (any connected gamepad).
+    Pad(gilrs::Button),
+}
+
+impl From<KeyCode> for InputSource {
+    fn from(code: KeyCode) -> Self {
+        InputSource::Key(code)
+    }
+}
+
+/// A raw input event from the window.
 #[derive(Debug, Clone, Copy)]
 pub struct RawInputEvent {
-    /// The physical key code.
-    pub keycode: KeyCode,
-    /// Whether the key was pressed or released.
+    /// The key or receptor the input comes from.
+    pub source: InputSource,
+    /// Whether the input was pressed or released.
     pub state: ElementState,
 }
 
@@ -34,7 +57,7 @@ impl RawInputEvent {
         } = event
         {
             Some(Self {
-                keycode: *keycode,
+                source: InputSource::Key(*keycode),
                 state: *state,
             })
         } else {
@@ -77,11 +100,19 @@ impl std::fmt::Display for EditMode {
 #[derive(Debug, Clone, PartialEq)]
 pub enum GameAction {
     // Gameplay
-    /// Key press on a column.
-    Hit { column: usize },
-    /// Key release on a column.
-    Release { column: usize },
-    /// Restart the current map.
+    /// Key press on a column. `timestamp` is when the input thread received
+    /// it (`None` for synthesized inputs), so the press is judged at that
+    /// instant rather than at the logic tick that processes it.
+    Hit {
+        column: usize,
+        timestamp: Option<Instant>,
+    },
+    /// Key release on a column, timestamped like `Hit`.
+    Release {
+        column: usize,
+        timestamp: Option<Instant>,
+    },
+    /// Restart the current map, or retry it from the results.
     Restart,
 
     // Practice Mode (in-game)
@@ -93,6 +124,16 @@ pub enum GameAction {
     // Menu
     /// Launch the game in practice mode (F3).
     LaunchPractice,
+    /// Let the engine play the chart itself (F7, song select and editor).
+    ToggleAutoplay,
+    /// Jump to a random map of the song list (F4).
+    RandomMap,
+    /// Jump to a map rated near the player's recent scores (F6).
+    RecommendMap,
+    /// Race a leaderboard replay on its chart (`None` stops racing).
+    SetGhost(Option<crate::models::ghost::GhostChoice>),
+    /// Start a versus race against a leaderboard replay.
+    StartVersus(crate::models::ghost::VersusChoice),
 
     // System / UI
     /// Toggle pause state.
@@ -117,10 +158,24 @@ pub enum GameAction {
     TabPrev,
     /// Toggle settings panel.
     ToggleSettings,
-    /// Update master volume.
-    UpdateVolume(f32),
+    /// Update the master, music and effects volumes.
+    UpdateVolume(Volumes),
+    /// Raise or lower one volume by `delta` (quick-adjust keys).
+    AdjustVolume { channel: VolumeChannel, delta: f32 },
+    /// Update the scroll speed (ms), applied to the running chart too.
+    UpdateScrollSpeed(f64),
+    /// Update the per-keymode scroll speeds (key count -> ms).
+    UpdateKeymodeScrollSpeeds(std::collections::HashMap<String, f64>),
+    /// Update the sudden / hidden lane cover heights.
+    UpdateLaneCover(LaneCover),
     /// Reload keybinds from disk.
     ReloadKeybinds,
+    /// Turn Discord Rich Presence on or off.
+    SetDiscordPresence(bool),
+    /// Show titles and artists in their original script (or romanized).
+    SetUnicodeMetadata(bool),
+    /// Apply changed tournament mode settings.
+    UpdateTournament(TournamentSettings),
 
     // Editor
     /// Toggle editor mode.
@@ -135,8 +190,33 @@ pub enum GameAction {
     // Database
     /// Trigger a full beatmap rescan.
     Rescan,
+    /// Import a `.osz` / `.zip` archive dropped on the window.
+    ImportArchive(std::path::PathBuf),
+    /// Library folders changed in the settings.
+    SetLibraryRoots(Vec<std::path::PathBuf>),
     /// Apply search filters.
     ApplySearch(MenuSearchFilters),
+    /// Archive (hide) or restore a beatmapset.
+    SetArchived { beatmapset_id: i64, archived: bool },
+    /// Edit the metadata of a beatmapset (`None` restores the file values).
+    EditMetadata {
+        beatmapset_id: i64,
+        metadata: Option<MetadataEdit>,
+    },
+    /// Create a new goal from the goals tab.
+    CreateGoal(GoalDraft),
+    /// Delete a goal by id.
+    DeleteGoal(i64),
+    /// Create a new course from the courses tab.
+    CreateCourse(CourseDraft),
+    /// Delete a course by id.
+    DeleteCourse(i64),
+    /// Start playing a course by id.
+    StartCourse(i64),
+    /// Save the play interrupted by a crash as an incomplete score.
+    RecoverAutosave,
+    /// Forget the play interrupted by a crash.
+    DiscardAutosave,
 
     // Difficulty
     /// Set the active difficulty calculator.
@@ -146,6 +226,28 @@ pub enum GameAction {
         mode: crate::models::settings::HitWindowMode,
         value: f64,
     },
+    /// Change the leaderboard filters / sorting (persisted).
+    UpdateLeaderboardOptions(crate::models::settings::LeaderboardOptions),
+    /// Load a saved gameplay preset by name (persisted).
+    ApplyGameplayPreset(String),
+
+    // Profiles
+    /// Make a profile active (settings already saved by the settings window).
+    SwitchProfile(i64),
+    /// Create a new profile with the given name.
+    CreateProfile(String),
+
+    // Library overview
+    /// Rate the library with the active calculator and rebuild its histogram.
+    LoadLibraryStats,
+    /// Compact the database or prune replays, from the Library settings.
+    LibraryMaintenance(MaintenanceRequest),
+
+    // Beatmap downloads
+    /// Search the beatmap mirror.
+    SearchMirror(String),
+    /// Download a beatmapset from the last mirror search, by set id.
+    DownloadBeatmapset(i64),
 
     // Result screen
     /// Navigate to result screen with data.
@@ -154,6 +256,8 @@ pub enum GameAction {
     // Debug
     /// Launch a debug map with all note types for testing.
     LaunchDebugMap,
+    /// Show/hide the latency and timing debug overlay (F9).
+    ToggleDebugOverlay,
 }
 
 /// Commands sent to the input thread.
@@ -161,4 +265,6 @@ pub enum GameAction {
 pub enum InputCommand {
     /// Reload keybind configuration.
     ReloadKeybinds(HashMap<String, Vec<String>>),
+    /// Reload gamepad button configuration.
+    ReloadPadBinds(HashMap<String, Vec<String>>),
 }
@@ -0,0 +1,122 @@
+//! Gamepad support (via gilrs).
+//!
+//! A dedicated thread waits for gamepad events and forwards button presses
+//! and releases to the input thread as `RawInputEvent`s, exactly like the
+//! window does for keys. Buttons are stored in the settings as labels
+//! ("PadSouth", "PadDPadLeft", ...).
+
+use crate::input::events::{InputSource, RawInputEvent};
+use crate::system::bus::SystemBus;
+use gilrs::{Button, EventType, Gilrs};
+use std::thread::{self, JoinHandle};
+use winit::event::ElementState;
+
+/// Settings label of a gamepad button.
+pub fn button_label(button: Button) -> Option<&'static str> {
+    let label = match button {
+        Button::South => "PadSouth",
+        Button::East => "PadEast",
+        Button::North => "PadNorth",
+        Button::West => "PadWest",
+        Button::C => "PadC",
+        Button::Z => "PadZ",
+        Button::LeftTrigger => "PadLeftTrigger",
+        Button::LeftTrigger2 => "PadLeftTrigger2",
+        Button::RightTrigger => "PadRightTrigger",
+        Button::RightTrigger2 => "PadRightTrigger2",
+        Button::Select => "PadSelect",
+        Button::Start => "PadStart",
+        Button::Mode => "PadMode",
+        Button::LeftThumb => "PadLeftThumb",
+        Button::RightThumb => "PadRightThumb",
+        Button::DPadUp => "PadDPadUp",
+        Button::DPadDown => "PadDPadDown",
+        Button::DPadLeft => "PadDPadLeft",
+        Button::DPadRight => "PadDPadRight",
+        Button::Unknown => return None,
+    };
+    Some(label)
+}
+
+/// Parses a label written by `button_label`.
+pub fn parse_button(s: &str) -> Option<Button> {
+    const BUTTONS: [Button; 19] = [
+        Button::South,
+        Button::East,
+        Button::North,
+        Button::West,
+        Button::C,
+        Button::Z,
+        Button::LeftTrigger,
+        Button::LeftTrigger2,
+        Button::RightTrigger,
+        Button::RightTrigger2,
+        Button::Select,
+        Button::Start,
+        Button::Mode,
+        Button::LeftThumb,
+        Button::RightThumb,
+        Button::DPadUp,
+        Button::DPadDown,
+        Button::DPadLeft,
+        Button::DPadRight,
+    ];
+    BUTTONS
+        .into_iter()
+        .find(|&button| button_label(button) == Some(s))
+}
+
+/// Spawns the gamepad thread. Does nothing but log if gamepads are
+/// unavailable on this system.
+pub fn start_thread(bus: SystemBus) -> JoinHandle<()> {
+    thread::Builder::new()
+        .name("Gamepad Thread".to_string())
+        .spawn(move || {
+            let mut gilrs = match Gilrs::new() {
+                Ok(gilrs) => gilrs,
+                Err(e) => {
+                    log::warn!("GAMEPAD: Unavailable, gamepad input disabled: {}", e);
+                    return;
+                }
+            };
+            for (_, pad) in gilrs.gamepads() {
+                log::info!("GAMEPAD: Found {}", pad.name());
+            }
+
+            while let Some(event) = gilrs.next_event_blocking(None) {
+                let (button, state) = match event.event {
+                    EventType::ButtonPressed(button, _) => (button, ElementState::Pressed),
+                    EventType::ButtonReleased(button, _) => (button, ElementState::Released),
+                    EventType::Connected => {
+                        log::info!("GAMEPAD: {} connected", gilrs.gamepad(event.id).name());
+                        continue;
+                    }
+                    EventType::Disconnected => {
+                        log::info!("GAMEPAD: Gamepad {} disconnected", event.id);
+                        continue;
+                    }
+                    _ => continue,
+                };
+                if button == Button::Unknown {
+                    continue;
+                }
+
+                if state == ElementState::Pressed {
+                    bus.stamp_key_press();
+                    if let Some(label) = button_label(button) {
+                        let _ = bus.pad_press_tx.send(label);
+                    }
+                }
+                let raw = RawInputEvent {
+                    source: InputSource::Pad(button),
+                    state,
+                };
+                if bus.raw_input_tx.send(raw).is_err() {
+                    break;
+                }
+            }
+
+            log::info!("GAMEPAD: Thread stopped");
+        })
+        .expect("Failed to spawn Gamepad thread")
+}
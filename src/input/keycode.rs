@@ -59,6 +59,7 @@ pub fn parse_keycode(s: &str) -> Option<KeyCode> {
         "BracketRight" => Some(KeyCode::BracketRight),
         "Minus" => Some(KeyCode::Minus),
         "Equal" => Some(KeyCode::Equal),
+        "Backquote" => Some(KeyCode::Backquote),
         _ => None,
     }
 }
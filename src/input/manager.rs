@@ -1,13 +1,47 @@
-use super::events::{EditorTarget, GameAction, RawInputEvent};
+use super::events::{EditorTarget, GameAction, InputSource, RawInputEvent};
+use super::gamepad::parse_button;
 use super::keycode::parse_keycode;
 use crate::models::engine::constants::NUM_COLUMNS;
-use crate::models::settings::SettingsState;
+use crate::models::settings::{SettingsState, VolumeChannel};
 use std::collections::{HashMap, HashSet};
+use std::time::Instant;
 use winit::event::ElementState;
 use winit::keyboard::KeyCode;
 
+/// Volume change of one Ctrl + arrow / page key press.
+const VOLUME_STEP: f32 = 0.05;
+
+/// Keys and buttons with a fixed global action. Binding one of them to a
+/// column shadows that action, so the keybind editor flags it.
+pub const RESERVED_BINDS: [(&str, &str); 24] = [
+    ("Escape", "Back"),
+    ("Enter", "Confirm"),
+    ("F2", "Editor"),
+    ("F3", "Practice"),
+    ("F4", "Random map"),
+    ("F5", "Retry"),
+    ("Backquote", "Retry"),
+    ("F6", "Recommended map"),
+    ("F7", "Autoplay"),
+    ("F8", "Rescan"),
+    ("F9", "Debug overlay"),
+    ("F10", "Debug map"),
+    ("F12", "Screenshot"),
+    ("BracketLeft", "Practice checkpoint"),
+    ("BracketRight", "Practice retry"),
+    ("ArrowUp", "Navigation / Lane cover"),
+    ("ArrowDown", "Navigation / Lane cover"),
+    ("ArrowLeft", "Navigation / Lane cover"),
+    ("ArrowRight", "Navigation / Lane cover"),
+    ("PageUp", "Rate down"),
+    ("PageDown", "Rate up"),
+    ("KeyO", "Settings"),
+    ("PadStart", "Confirm"),
+    ("PadSelect", "Back"),
+];
+
 pub struct InputManager {
-    bindings: HashMap<KeyCode, GameAction>,
+    bindings: HashMap<InputSource, GameAction>,
     ctrl_left: bool,
     ctrl_right: bool,
     suppressed_keys: HashSet<KeyCode>,
@@ -15,6 +49,15 @@ pub struct InputManager {
 
 impl InputManager {
     pub fn new() -> Self {
+        let mut manager = Self::with_default_bindings();
+        let settings = SettingsState::load();
+        manager.reload_keybinds(&settings.keybinds);
+        manager.reload_pad_binds(&settings.pad_keybinds);
+        manager
+    }
+
+    /// Manager with the built-in bindings only, without the saved keybinds.
+    pub fn with_default_bindings() -> Self {
         let mut manager = Self {
             bindings: HashMap::new(),
             ctrl_left: false,
@@ -22,54 +65,75 @@ impl InputManager {
             suppressed_keys: HashSet::new(),
         };
         manager.load_default_bindings();
-        let settings = SettingsState::load();
-        manager.reload_keybinds(&settings.keybinds);
         manager
     }
 
-    pub fn process(&mut self, event: RawInputEvent) -> Option<GameAction> {
-        match event.keycode {
-            KeyCode::ControlLeft => {
-                self.ctrl_left = event.state == ElementState::Pressed;
-                return None;
+    /// Maps a raw event to its action. `received_at` stamps column presses
+    /// and releases.
+    pub fn process(&mut self, event: RawInputEvent, received_at: Instant) -> Option<GameAction> {
+        if let InputSource::Key(keycode) = event.source {
+            match keycode {
+                KeyCode::ControlLeft => {
+                    self.ctrl_left = event.state == ElementState::Pressed;
+                    return None;
+                }
+                KeyCode::ControlRight => {
+                    self.ctrl_right = event.state == ElementState::Pressed;
+                    return None;
+                }
+                _ => {}
             }
-            KeyCode::ControlRight => {
-                self.ctrl_right = event.state == ElementState::Pressed;
+
+            if self.suppressed_keys.contains(&keycode) {
+                if event.state == ElementState::Released {
+                    self.suppressed_keys.remove(&keycode);
+                }
                 return None;
             }
-            _ => {}
-        }
 
-        if self.suppressed_keys.contains(&event.keycode) {
-            if event.state == ElementState::Released {
-                self.suppressed_keys.remove(&event.keycode);
+            if event.state == ElementState::Pressed && (self.ctrl_left || self.ctrl_right) {
+                let volume = |channel, delta| Some(GameAction::AdjustVolume { channel, delta });
+                let shortcut = match keycode {
+                    KeyCode::KeyO => Some(GameAction::ToggleSettings),
+                    KeyCode::KeyR => Some(GameAction::Restart),
+                    KeyCode::ArrowUp => volume(VolumeChannel::Master, VOLUME_STEP),
+                    KeyCode::ArrowDown => volume(VolumeChannel::Master, -VOLUME_STEP),
+                    KeyCode::ArrowRight => volume(VolumeChannel::Music, VOLUME_STEP),
+                    KeyCode::ArrowLeft => volume(VolumeChannel::Music, -VOLUME_STEP),
+                    KeyCode::PageUp => volume(VolumeChannel::Effects, VOLUME_STEP),
+                    KeyCode::PageDown => volume(VolumeChannel::Effects, -VOLUME_STEP),
+                    _ => None,
+                };
+                if shortcut.is_some() {
+                    self.suppressed_keys.insert(keycode);
+                    return shortcut;
+                }
             }
-            return None;
-        }
-
-        if event.state == ElementState::Pressed
-            && event.keycode == KeyCode::KeyO
-            && (self.ctrl_left || self.ctrl_right)
-        {
-            self.suppressed_keys.insert(KeyCode::KeyO);
-            return Some(GameAction::ToggleSettings);
         }
 
-        if let Some(base_action) = self.bindings.get(&event.keycode) {
+        if let Some(base_action) = self.bindings.get(&event.source) {
             match (event.state, base_action.clone()) {
-                (ElementState::Pressed, GameAction::Hit { column }) => {
-                    Some(GameAction::Hit { column })
-                }
-                (ElementState::Released, GameAction::Hit { column }) => {
-                    Some(GameAction::Release { column })
+                (ElementState::Pressed, GameAction::Hit { column, .. }) => Some(GameAction::Hit {
+                    column,
+                    timestamp: Some(received_at),
+                }),
+                (ElementState::Released, GameAction::Hit { column, .. }) => {
+                    Some(GameAction::Release {
+                        column,
+                        timestamp: Some(received_at),
+                    })
                 }
                 (ElementState::Pressed, action) => Some(action),
                 _ => None,
             }
         } else {
-            match (event.state, event.keycode) {
-                (ElementState::Pressed, KeyCode::Escape) => Some(GameAction::Back),
-                (ElementState::Pressed, KeyCode::Enter) => Some(GameAction::Confirm),
+            match (event.state, event.source) {
+                (ElementState::Pressed, InputSource::Key(KeyCode::Escape)) => {
+                    Some(GameAction::Back)
+                }
+                (ElementState::Pressed, InputSource::Key(KeyCode::Enter)) => {
+                    Some(GameAction::Confirm)
+                }
                 _ => None,
             }
         }
@@ -95,91 +159,200 @@ impl InputManager {
             return;
         }
 
-        let to_remove: Vec<KeyCode> = self
+        // Only key bindings are remapped; receptor clicks stay on their column.
+        let to_remove: Vec<InputSource> = self
             .bindings
             .iter()
-            .filter_map(|(code, action)| matches!(action, GameAction::Hit { .. }).then_some(*code))
+            .filter_map(|(source, action)| {
+                (matches!(source, InputSource::Key(_)) && matches!(action, GameAction::Hit { .. }))
+                    .then_some(*source)
+            })
             .collect();
-        for code in to_remove {
-            self.bindings.remove(&code);
+        for source in to_remove {
+            self.bindings.remove(&source);
         }
 
         for (idx, code) in parsed {
-            self.bindings.insert(code, GameAction::Hit { column: idx });
+            self.bindings.insert(
+                InputSource::Key(code),
+                GameAction::Hit {
+                    column: idx,
+                    timestamp: None,
+                },
+            );
+        }
+    }
+
+    /// Same as `reload_keybinds`, for the gamepad button bindings.
+    pub fn reload_pad_binds(&mut self, pad_binds: &HashMap<String, Vec<String>>) {
+        let key = NUM_COLUMNS.to_string();
+        let Some(entries) = pad_binds.get(&key) else {
+            return;
+        };
+
+        let parsed: Vec<(usize, gilrs::Button)> = entries
+            .iter()
+            .take(NUM_COLUMNS)
+            .enumerate()
+            .filter_map(|(idx, label)| parse_button(label).map(|button| (idx, button)))
+            .collect();
+        if parsed.is_empty() {
+            return;
+        }
+
+        self.bindings.retain(|source, action| {
+            !(matches!(source, InputSource::Pad(_)) && matches!(action, GameAction::Hit { .. }))
+        });
+        for (idx, button) in parsed {
+            self.bindings.insert(
+                InputSource::Pad(button),
+                GameAction::Hit {
+                    column: idx,
+                    timestamp: None,
+                },
+            );
         }
     }
 
     fn load_default_bindings(&mut self) {
         // Gameplay 4K
+        self.bindings.insert(
+            KeyCode::KeyD.into(),
+            GameAction::Hit {
+                column: 0,
+                timestamp: None,
+            },
+        );
+        self.bindings.insert(
+            KeyCode::KeyF.into(),
+            GameAction::Hit {
+                column: 1,
+                timestamp: None,
+            },
+        );
+        self.bindings.insert(
+            KeyCode::KeyJ.into(),
+            GameAction::Hit {
+                column: 2,
+                timestamp: None,
+            },
+        );
+        self.bindings.insert(
+            KeyCode::KeyK.into(),
+            GameAction::Hit {
+                column: 3,
+                timestamp: None,
+            },
+        );
         self.bindings
-            .insert(KeyCode::KeyD, GameAction::Hit { column: 0 });
+            .insert(KeyCode::F5.into(), GameAction::Restart);
         self.bindings
-            .insert(KeyCode::KeyF, GameAction::Hit { column: 1 });
+            .insert(KeyCode::Backquote.into(), GameAction::Restart); // Ctrl+R works too
+
+        // Mouse: a click on a receptor hits its column.
+        for column in 0..NUM_COLUMNS {
+            self.bindings.insert(
+                InputSource::Receptor(column),
+                GameAction::Hit {
+                    column,
+                    timestamp: None,
+                },
+            );
+        }
+
+        // Gamepad menu buttons
         self.bindings
-            .insert(KeyCode::KeyJ, GameAction::Hit { column: 2 });
+            .insert(InputSource::Pad(gilrs::Button::Start), GameAction::Confirm);
         self.bindings
-            .insert(KeyCode::KeyK, GameAction::Hit { column: 3 });
-        self.bindings.insert(KeyCode::F5, GameAction::Restart);
+            .insert(InputSource::Pad(gilrs::Button::Select), GameAction::Back);
 
         // Practice Mode
         self.bindings
-            .insert(KeyCode::F3, GameAction::LaunchPractice); // Menu: launch practice
-        self.bindings
-            .insert(KeyCode::BracketLeft, GameAction::PracticeCheckpoint); // In-game: checkpoint
+            .insert(KeyCode::F3.into(), GameAction::LaunchPractice); // Menu: launch practice
         self.bindings
-            .insert(KeyCode::BracketRight, GameAction::PracticeRetry); // In-game: retry
-
-        // UI navigation (mirrored inside the editor).
+            .insert(KeyCode::BracketLeft.into(), GameAction::PracticeCheckpoint); // In-game: checkpoint
         self.bindings
-            .insert(KeyCode::ArrowUp, GameAction::Navigation { x: 0, y: -1 });
+            .insert(KeyCode::BracketRight.into(), GameAction::PracticeRetry); // In-game: retry
         self.bindings
-            .insert(KeyCode::ArrowDown, GameAction::Navigation { x: 0, y: 1 });
+            .insert(KeyCode::F7.into(), GameAction::ToggleAutoplay); // Menu / editor: autoplay
         self.bindings
-            .insert(KeyCode::ArrowLeft, GameAction::Navigation { x: -1, y: 0 });
+            .insert(KeyCode::F4.into(), GameAction::RandomMap); // Menu: random map
         self.bindings
-            .insert(KeyCode::ArrowRight, GameAction::Navigation { x: 1, y: 0 });
+            .insert(KeyCode::F6.into(), GameAction::RecommendMap); // Menu: recommended map
+
+        // UI navigation (mirrored inside the editor).
+        self.bindings.insert(
+            KeyCode::ArrowUp.into(),
+            GameAction::Navigation { x: 0, y: -1 },
+        );
+        self.bindings.insert(
+            KeyCode::ArrowDown.into(),
+            GameAction::Navigation { x: 0, y: 1 },
+        );
+        self.bindings.insert(
+            KeyCode::ArrowLeft.into(),
+            GameAction::Navigation { x: -1, y: 0 },
+        );
+        self.bindings.insert(
+            KeyCode::ArrowRight.into(),
+            GameAction::Navigation { x: 1, y: 0 },
+        );
 
         // Tab / settings controls.
-        self.bindings.insert(KeyCode::PageUp, GameAction::TabPrev);
-        self.bindings.insert(KeyCode::PageDown, GameAction::TabNext);
         self.bindings
-            .insert(KeyCode::KeyO, GameAction::ToggleSettings);
+            .insert(KeyCode::PageUp.into(), GameAction::TabPrev);
+        self.bindings
+            .insert(KeyCode::PageDown.into(), GameAction::TabNext);
+        self.bindings
+            .insert(KeyCode::KeyO.into(), GameAction::ToggleSettings);
 
         // System / DB
         self.bindings
-            .insert(KeyCode::KeyE, GameAction::ToggleEditor); // F2 ou E
-        self.bindings.insert(KeyCode::F2, GameAction::ToggleEditor);
-        self.bindings.insert(KeyCode::F8, GameAction::Rescan);
+            .insert(KeyCode::KeyE.into(), GameAction::ToggleEditor); // F2 ou E
+        self.bindings
+            .insert(KeyCode::F2.into(), GameAction::ToggleEditor);
+        self.bindings.insert(KeyCode::F8.into(), GameAction::Rescan);
 
         // Editor Selection Shortcuts
-        self.bindings
-            .insert(KeyCode::KeyW, GameAction::EditorSelect(EditorTarget::Notes));
         self.bindings.insert(
-            KeyCode::KeyX,
+            KeyCode::KeyW.into(),
+            GameAction::EditorSelect(EditorTarget::Notes),
+        );
+        self.bindings.insert(
+            KeyCode::KeyX.into(),
             GameAction::EditorSelect(EditorTarget::Receptors),
         );
-        self.bindings
-            .insert(KeyCode::KeyC, GameAction::EditorSelect(EditorTarget::Combo));
-        self.bindings
-            .insert(KeyCode::KeyV, GameAction::EditorSelect(EditorTarget::Score));
         self.bindings.insert(
-            KeyCode::KeyB,
+            KeyCode::KeyC.into(),
+            GameAction::EditorSelect(EditorTarget::Combo),
+        );
+        self.bindings.insert(
+            KeyCode::KeyV.into(),
+            GameAction::EditorSelect(EditorTarget::Score),
+        );
+        self.bindings.insert(
+            KeyCode::KeyB.into(),
             GameAction::EditorSelect(EditorTarget::Accuracy),
         );
         self.bindings.insert(
-            KeyCode::KeyN,
+            KeyCode::KeyN.into(),
             GameAction::EditorSelect(EditorTarget::Judgement),
         );
         self.bindings.insert(
-            KeyCode::KeyK,
+            KeyCode::KeyK.into(),
             GameAction::EditorSelect(EditorTarget::HitBar),
         );
+        self.bindings.insert(
+            KeyCode::KeyL.into(),
+            GameAction::EditorSelect(EditorTarget::Lanes),
+        );
         self.bindings
-            .insert(KeyCode::KeyL, GameAction::EditorSelect(EditorTarget::Lanes));
-        self.bindings.insert(KeyCode::KeyS, GameAction::EditorSave);
+            .insert(KeyCode::KeyS.into(), GameAction::EditorSave);
 
         // Debug
         self.bindings
-            .insert(KeyCode::F10, GameAction::LaunchDebugMap);
+            .insert(KeyCode::F10.into(), GameAction::LaunchDebugMap);
+        self.bindings
+            .insert(KeyCode::F9.into(), GameAction::ToggleDebugOverlay);
     }
 }
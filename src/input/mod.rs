@@ -1,4 +1,5 @@
 pub mod events;
+pub mod gamepad;
 pub mod keycode;
 pub mod manager;
 
@@ -6,9 +7,10 @@ use crate::input::events::InputCommand;
 use crate::input::manager::InputManager;
 use crate::system::bus::SystemBus;
 use crossbeam_channel::select;
-use std::thread;
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
 
-pub fn start_thread(bus: SystemBus, mut manager: InputManager) {
+pub fn start_thread(bus: SystemBus, mut manager: InputManager) -> JoinHandle<()> {
     thread::Builder::new()
         .name("Input Thread".to_string())
         .spawn(move || {
@@ -21,7 +23,9 @@ pub fn start_thread(bus: SystemBus, mut manager: InputManager) {
                     recv(bus.raw_input_rx) -> raw => {
                         match raw {
                             Ok(raw_event) => {
-                                if let Some(action) = manager.process(raw_event)
+                                // Horodaté dès la réception, avant tout traitement
+                                let received_at = Instant::now();
+                                if let Some(action) = manager.process(raw_event, received_at)
                                     && let Err(e) = bus.action_tx.send(action) {
                                         log::error!("INPUT: Failed to send action (Logic thread died?): {}", e);
                                         break;
@@ -33,6 +37,7 @@ pub fn start_thread(bus: SystemBus, mut manager: InputManager) {
                     recv(bus.input_cmd_rx) -> cmd => {
                         match cmd {
                             Ok(InputCommand::ReloadKeybinds(map)) => manager.reload_keybinds(&map),
+                            Ok(InputCommand::ReloadPadBinds(map)) => manager.reload_pad_binds(&map),
                             Err(_) => break,
                         }
                     }
@@ -41,5 +46,5 @@ pub fn start_thread(bus: SystemBus, mut manager: InputManager) {
 
             log::info!("INPUT: Thread stopped");
         })
-        .expect("Failed to spawn Input thread");
+        .expect("Failed to spawn Input thread")
 }
@@ -3,11 +3,13 @@
 //! This module provides a thread-safe interface for controlling audio playback
 //! without blocking the main game loop.
 
+use crate::models::settings::Volumes;
+use crate::shared::audio_clock::AudioClock;
 use crate::system::bus::{AudioCommand, SystemBus};
 use crossbeam_channel::Sender;
 use std::path::Path;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
 
 /// Wrapper for sending commands to the audio thread.
 ///
@@ -16,9 +18,7 @@ use std::sync::atomic::{AtomicU64, Ordering};
 /// ensuring non-blocking audio control from the game logic thread.
 pub struct AudioManager {
     cmd_tx: Sender<AudioCommand>,
-    position: Arc<AtomicU64>,
-    sample_rate: Arc<AtomicU64>,
-    channels: Arc<AtomicU64>,
+    clock: Arc<AudioClock>,
     current_speed: f32,
 }
 
@@ -27,9 +27,7 @@ impl AudioManager {
     pub fn new(bus: &SystemBus) -> Self {
         Self {
             cmd_tx: bus.audio_cmd_tx.clone(),
-            position: bus.audio_position.clone(),
-            sample_rate: bus.audio_sample_rate.clone(),
-            channels: bus.audio_channels.clone(),
+            clock: bus.audio_clock.clone(),
             current_speed: 1.0,
         }
     }
@@ -62,36 +60,31 @@ impl AudioManager {
         let _ = self.cmd_tx.send(AudioCommand::SetSpeed { speed });
     }
 
-    /// Sets the master volume (0.0 to 1.0).
-    pub fn set_volume(&mut self, volume: f32) {
-        let _ = self.cmd_tx.send(AudioCommand::SetVolume { volume });
+    /// Chooses whether rate changes keep the pitch (time stretch) or shift it.
+    pub fn set_time_stretch(&mut self, enabled: bool) {
+        let _ = self.cmd_tx.send(AudioCommand::SetTimeStretch { enabled });
+    }
+
+    /// Sets the master, music and sound effect volumes.
+    pub fn set_volume(&mut self, volumes: Volumes) {
+        let _ = self.cmd_tx.send(AudioCommand::SetVolume(volumes));
     }
 
     /// Seeks to a position in seconds.
     ///
     /// This operation is non-blocking; the audio thread handles the seek asynchronously.
     pub fn seek(&mut self, position_seconds: f32) {
+        self.clock.begin_seek();
         let _ = self.cmd_tx.send(AudioCommand::Seek {
             position_secs: position_seconds,
         });
     }
 
-    /// Returns the current playback position in seconds.
-    ///
-    /// The position is calculated from the sample count shared atomically
-    /// with the audio thread.
-    pub fn get_position_seconds(&self) -> f64 {
-        let samples = self.position.load(Ordering::Relaxed) as f64;
-        let sample_rate = self.sample_rate.load(Ordering::Relaxed).max(1) as f64;
-        let channels = self.channels.load(Ordering::Relaxed).max(1) as f64;
-
-        samples / (sample_rate * channels)
-    }
-
-    /// Returns whether a seek operation is in progress.
+    /// Returns the position heard at `now` in milliseconds, or `None` while
+    /// the music is not playing (not started yet, paused or seeking).
     ///
-    /// Currently always returns `false` as seeks are handled asynchronously.
-    pub fn is_seeking(&self) -> bool {
-        false
+    /// Read from the clock the audio thread publishes, without waiting on it.
+    pub fn audible_ms(&self, now: Instant) -> Option<f64> {
+        self.clock.audible_ms(now)
     }
 }
@@ -2,114 +2,169 @@
 //!
 //! This prevents audio loading/seeking from blocking the game logic thread.
 
+use crate::logic::output::Output;
+use crate::logic::time_stretch::{SharedRate, TimeStretch};
+use crate::models::settings::Volumes;
+use crate::shared::audio_clock::AudioClock;
 use crate::system::bus::{AudioCommand, SystemBus};
-use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source};
+use rodio::source::Buffered;
+use rodio::{Decoder, Sink, Source};
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::sync::atomic::Ordering;
-use std::thread;
+use std::thread::{self, JoinHandle};
 use std::time::Duration;
 
+/// Volumes until the game state sends the saved ones.
+const FULL_VOLUME: Volumes = Volumes {
+    master: 1.0,
+    music: 1.0,
+    effects: 1.0,
+};
+
 struct AudioWorker {
-    _stream: Option<OutputStream>,
-    stream_handle: Option<OutputStreamHandle>,
+    output: Option<Output>,
     sink: Option<Sink>,
     current_path: Option<PathBuf>,
     speed: f32,
-    volume: f32,
+    /// Speed as seen by the time-stretch source (updated live).
+    stretch_rate: SharedRate,
+    /// Pitch-preserving rate changes instead of resampling.
+    time_stretch: bool,
+    volumes: Volumes,
     sample_rate: u32,
     channels: u16,
-    position_counter: Arc<std::sync::atomic::AtomicU64>,
+    clock: Arc<AudioClock>,
     /// True if audio is available, false for silent mode
     has_audio: bool,
+    /// Decoded UI sounds, played straight on the output so they overlap
+    /// the music and each other.
+    ui_sounds: HashMap<PathBuf, Buffered<Decoder<BufReader<File>>>>,
 }
 
 impl AudioWorker {
     fn new(bus: &SystemBus) -> Self {
-        match OutputStream::try_default() {
-            Ok((_stream, stream_handle)) => {
+        let output = match Output::open(bus.audio_clock.clone()) {
+            Ok(output) => {
                 log::info!("AUDIO: Device found, audio enabled");
-                Self {
-                    _stream: Some(_stream),
-                    stream_handle: Some(stream_handle),
-                    sink: None,
-                    current_path: None,
-                    speed: 1.0,
-                    volume: 1.0,
-                    sample_rate: 44100,
-                    channels: 2,
-                    position_counter: bus.audio_position.clone(),
-                    has_audio: true,
-                }
+                Some(output)
             }
             Err(e) => {
                 log::warn!(
                     "AUDIO: No audio device found ({}), running in silent mode",
                     e
                 );
-                Self {
-                    _stream: None,
-                    stream_handle: None,
-                    sink: None,
-                    current_path: None,
-                    speed: 1.0,
-                    volume: 1.0,
-                    sample_rate: 44100,
-                    channels: 2,
-                    position_counter: bus.audio_position.clone(),
-                    has_audio: false,
-                }
+                None
             }
+        };
+        Self {
+            has_audio: output.is_some(),
+            output,
+            sink: None,
+            current_path: None,
+            speed: 1.0,
+            stretch_rate: SharedRate::new(1.0),
+            time_stretch: false,
+            volumes: FULL_VOLUME,
+            sample_rate: 44100,
+            channels: 2,
+            clock: bus.audio_clock.clone(),
+            ui_sounds: HashMap::new(),
         }
     }
 
-    fn handle_command(&mut self, cmd: AudioCommand, bus: &SystemBus) {
+    fn handle_command(&mut self, cmd: AudioCommand) {
         match cmd {
             AudioCommand::Load { path } => {
-                self.load_music(&path, bus);
+                self.load_music(&path);
             }
             AudioCommand::Play => {
                 if let Some(sink) = &self.sink {
+                    // Before the sink starts, so its first block restarts the clock.
+                    self.clock.set_playing(true);
                     sink.play();
                 }
             }
             AudioCommand::Pause => {
                 if let Some(sink) = &self.sink {
+                    self.clock.set_playing(false);
                     sink.pause();
                 }
             }
             AudioCommand::Stop => {
+                self.clock.set_playing(false);
                 if let Some(sink) = self.sink.take() {
                     sink.stop();
                 }
-                self.position_counter.store(0, Ordering::Relaxed);
+                self.clock.restart_at(0);
             }
             AudioCommand::Seek { position_secs } => {
-                self.seek_to(position_secs, bus);
+                self.seek_to(position_secs);
+                self.clock.end_seek();
             }
             AudioCommand::SetSpeed { speed } => {
                 self.speed = speed;
+                self.stretch_rate.set(speed);
+                self.clock.set_speed(speed);
                 if let Some(sink) = &self.sink {
-                    sink.set_speed(speed);
+                    sink.set_speed(self.sink_speed());
+                }
+            }
+            AudioCommand::SetTimeStretch { enabled } => {
+                if self.time_stretch != enabled {
+                    self.time_stretch = enabled;
+                    // The source chain differs between modes: rebuild it in place.
+                    if self.sink.is_some() {
+                        let position = self.clock.position_secs() as f32;
+                        self.seek_to(position);
+                    }
                 }
             }
-            AudioCommand::SetVolume { volume } => {
-                self.volume = volume;
+            AudioCommand::SetVolume(volumes) => {
+                self.volumes = volumes;
                 if let Some(sink) = &self.sink {
-                    sink.set_volume(volume);
+                    sink.set_volume(volumes.music_gain());
                 }
             }
+            AudioCommand::PlaySound { path } => {
+                self.play_sound(&path);
+            }
         }
     }
 
-    fn load_music(&mut self, path: &Path, bus: &SystemBus) {
+    fn play_sound(&mut self, path: &Path) {
+        let Some(output) = &self.output else {
+            return;
+        };
+        if !self.ui_sounds.contains_key(path) {
+            let decoder = File::open(path)
+                .map_err(|e| e.to_string())
+                .and_then(|file| Decoder::new(BufReader::new(file)).map_err(|e| e.to_string()));
+            match decoder {
+                Ok(decoder) => {
+                    self.ui_sounds
+                        .insert(path.to_path_buf(), decoder.buffered());
+                }
+                Err(e) => {
+                    log::warn!("AUDIO: Cannot load sound {:?}: {}", path, e);
+                    return;
+                }
+            }
+        }
+        let sound = self.ui_sounds[path]
+            .clone()
+            .amplify(self.volumes.effects_gain());
+        output.mixer.add(sound.convert_samples());
+    }
+
+    fn load_music(&mut self, path: &Path) {
         self.current_path = Some(path.to_path_buf());
-        self.load_from_position(0.0, bus);
+        self.load_from_position(0.0);
     }
 
-    fn load_from_position(&mut self, position_secs: f32, bus: &SystemBus) {
+    fn load_from_position(&mut self, position_secs: f32) {
         // Skip if no audio device available
         if !self.has_audio {
             return;
@@ -138,48 +193,62 @@ impl AudioWorker {
         self.channels = source.channels();
 
         // Update shared state
-        bus.audio_sample_rate
-            .store(self.sample_rate as u64, Ordering::Relaxed);
-        bus.audio_channels
-            .store(self.channels as u64, Ordering::Relaxed);
+        self.clock.set_format(self.sample_rate, self.channels);
 
         let skip_duration = Duration::from_secs_f32(position_secs.max(0.0));
         let skipped_samples =
             (position_secs.max(0.0) as f64 * self.sample_rate as f64 * self.channels as f64) as u64;
 
-        self.position_counter
-            .store(skipped_samples, Ordering::Relaxed);
+        let generation = self.clock.restart_at(skipped_samples);
 
         let source_skipped = source.skip_duration(skip_duration);
 
-        let monitor = AudioMonitor {
-            inner: source_skipped,
-            position_counter: self.position_counter.clone(),
-        };
-
-        let Some(stream_handle) = &self.stream_handle else {
+        let Some(output) = &self.output else {
             return;
         };
 
-        let Ok(sink) = Sink::try_new(stream_handle) else {
-            log::error!("AUDIO: Failed to create sink");
-            return;
-        };
-        sink.set_speed(self.speed);
-        sink.set_volume(self.volume);
-        sink.append(monitor);
+        let (sink, queue) = Sink::new_idle();
+        output.mixer.add(queue);
+        sink.set_speed(self.sink_speed());
+        sink.set_volume(self.volumes.music_gain());
+        if self.time_stretch {
+            // Count after stretching so the position follows what is audible,
+            // not the stretcher's read-ahead.
+            sink.append(AudioMonitor::new(
+                TimeStretch::new(source_skipped, self.stretch_rate.clone()),
+                self.clock.clone(),
+                generation,
+                skipped_samples,
+                Some(self.stretch_rate.clone()),
+            ));
+        } else {
+            sink.append(AudioMonitor::new(
+                source_skipped,
+                self.clock.clone(),
+                generation,
+                skipped_samples,
+                None,
+            ));
+        }
         sink.pause();
+        self.clock.set_playing(false);
 
         self.sink = Some(sink);
         log::info!("AUDIO: Loaded from {:.1}s", position_secs);
     }
 
-    fn seek_to(&mut self, position_secs: f32, bus: &SystemBus) {
+    /// Speed applied by the sink itself (the stretcher handles it otherwise).
+    fn sink_speed(&self) -> f32 {
+        if self.time_stretch { 1.0 } else { self.speed }
+    }
+
+    fn seek_to(&mut self, position_secs: f32) {
         let was_playing = self.sink.as_ref().map(|s| !s.is_paused()).unwrap_or(false);
 
-        self.load_from_position(position_secs, bus);
+        self.load_from_position(position_secs);
 
         if was_playing && let Some(sink) = &self.sink {
+            self.clock.set_playing(true);
             sink.play();
         }
 
@@ -187,9 +256,41 @@ impl AudioWorker {
     }
 }
 
+/// Counts the source samples the device pulls and publishes the blocks
+/// they come in to the audio clock.
 struct AudioMonitor<I> {
     inner: I,
-    position_counter: Arc<std::sync::atomic::AtomicU64>,
+    clock: Arc<AudioClock>,
+    generation: u64,
+    /// Source samples pulled so far, interleaved.
+    position: u64,
+    /// When set, each played sample stands for `rate` source samples
+    /// (time-stretched output).
+    rate: Option<SharedRate>,
+    /// Fractional source samples not yet added to the counter.
+    pending: f64,
+    /// Buffer pull the last block was published for.
+    last_pull_ns: u64,
+}
+
+impl<I> AudioMonitor<I> {
+    fn new(
+        inner: I,
+        clock: Arc<AudioClock>,
+        generation: u64,
+        position: u64,
+        rate: Option<SharedRate>,
+    ) -> Self {
+        Self {
+            inner,
+            clock,
+            generation,
+            position,
+            rate,
+            pending: 0.0,
+            last_pull_ns: 0,
+        }
+    }
 }
 
 impl<I> Iterator for AudioMonitor<I>
@@ -200,7 +301,26 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         let item = self.inner.next();
         if item.is_some() {
-            self.position_counter.fetch_add(1, Ordering::Relaxed);
+            // First sample since the output callback pulled a new buffer:
+            // it starts the block.
+            let pull_ns = self.clock.last_pull_ns();
+            if pull_ns != self.last_pull_ns {
+                self.last_pull_ns = pull_ns;
+                self.clock
+                    .start_block(self.generation, self.position, pull_ns);
+            }
+
+            match &self.rate {
+                Some(rate) => {
+                    self.pending += rate.get() as f64;
+                    let whole = self.pending.floor();
+                    self.pending -= whole;
+                    self.position += whole as u64;
+                }
+                None => {
+                    self.position += 1;
+                }
+            }
         }
         item
     }
@@ -226,7 +346,7 @@ where
 }
 
 /// Starts the dedicated audio thread.
-pub fn start_audio_thread(bus: SystemBus) {
+pub fn start_audio_thread(bus: SystemBus) -> JoinHandle<()> {
     thread::Builder::new()
         .name("Audio Thread".to_string())
         .spawn(move || {
@@ -235,10 +355,45 @@ pub fn start_audio_thread(bus: SystemBus) {
             let mut worker = AudioWorker::new(&bus);
 
             while let Ok(cmd) = bus.audio_cmd_rx.recv() {
-                worker.handle_command(cmd, &bus);
+                worker.handle_command(cmd);
             }
 
             log::info!("AUDIO: Thread stopped");
         })
-        .expect("Failed to spawn Audio thread");
+        .expect("Failed to spawn Audio thread")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rodio::source::SineWave;
+    use std::time::Instant;
+
+    #[test]
+    fn clock_keeps_advancing_when_buffers_are_pulled_back_to_back() {
+        let clock = Arc::new(AudioClock::new());
+        clock.set_format(48_000, 1);
+        let generation = clock.restart_at(0);
+        clock.set_playing(true);
+        let mut monitor =
+            AudioMonitor::new(SineWave::new(440.0), clock.clone(), generation, 0, None);
+
+        // 10 ms buffers, every sample pulled right after the previous one.
+        let start = Instant::now();
+        let mut last = f64::MIN;
+        for block in 0..50u64 {
+            let pulled_at = start + Duration::from_millis(block * 10);
+            clock.device_pull(pulled_at);
+            for _ in 0..480 {
+                monitor.next();
+            }
+            let audible = clock
+                .audible_ms(pulled_at + Duration::from_millis(5))
+                .unwrap();
+            assert!(audible > last, "clock stuck at {} ms", audible);
+            last = audible;
+        }
+        // Block 49 starts at 490 ms, heard one 10 ms period later.
+        assert!((last - 485.0).abs() < 1e-6, "{}", last);
+    }
 }
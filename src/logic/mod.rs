@@ -5,8 +5,11 @@
 
 pub mod audio;
 pub mod audio_thread;
+pub mod output;
+pub mod time_stretch;
 
 use crate::database::DbManager;
+use crate::downloader::Downloader;
 use crate::state::GlobalState;
 use crate::system::bus::{SystemBus, SystemEvent};
 use std::thread;
@@ -22,10 +25,7 @@ const TPS: u64 = 200;
 /// 2. Handles system events (resize, quit, etc.)
 /// 3. Updates game state at a fixed rate
 /// 4. Sends render snapshots to the render thread
-pub fn start_thread(bus: SystemBus, db_manager: DbManager) {
-    // Start the dedicated audio thread
-    audio_thread::start_audio_thread(bus.clone());
-
+pub fn start_thread(bus: SystemBus, db_manager: DbManager, downloader: Downloader) {
     thread::Builder::new()
         .name("Logic Thread".to_string())
         .spawn(move || {
@@ -36,7 +36,7 @@ pub fn start_thread(bus: SystemBus, db_manager: DbManager) {
             db_manager.load();
 
             let input_cmd_tx = bus.input_cmd_tx.clone();
-            let mut state = GlobalState::new(db_manager, input_cmd_tx, bus.clone());
+            let mut state = GlobalState::new(db_manager, downloader, input_cmd_tx, bus.clone());
 
             let mut accumulator = Duration::new(0, 0);
             let mut last_time = Instant::now();
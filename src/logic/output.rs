@@ -0,0 +1,69 @@
+//! Output stream of the audio thread.
+//!
+//! rodio's own stream hides the device callback, which the audio clock
+//! needs: the stream is opened here instead, and its callback stamps the
+//! clock once per buffer before filling it from a rodio mixer. The music
+//! sink and the UI sounds are added to that mixer.
+
+use crate::shared::audio_clock::AudioClock;
+use rodio::cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use rodio::cpal::{self, FromSample, SizedSample};
+use rodio::dynamic_mixer::{self, DynamicMixer, DynamicMixerController};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Open stream on the default output device.
+pub struct Output {
+    pub mixer: Arc<DynamicMixerController<f32>>,
+    _stream: cpal::Stream,
+}
+
+impl Output {
+    pub fn open(clock: Arc<AudioClock>) -> Result<Self, String> {
+        let device = cpal::default_host()
+            .default_output_device()
+            .ok_or("no output device")?;
+        let config = device.default_output_config().map_err(|e| e.to_string())?;
+        let (mixer, source) =
+            dynamic_mixer::mixer::<f32>(config.channels(), config.sample_rate().0);
+
+        let stream_config = config.config();
+        let stream = match config.sample_format() {
+            cpal::SampleFormat::F32 => build::<f32>(&device, &stream_config, source, clock),
+            cpal::SampleFormat::I16 => build::<i16>(&device, &stream_config, source, clock),
+            cpal::SampleFormat::I32 => build::<i32>(&device, &stream_config, source, clock),
+            cpal::SampleFormat::U16 => build::<u16>(&device, &stream_config, source, clock),
+            format => Err(format!("unsupported sample format {}", format)),
+        }?;
+        stream.play().map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            mixer,
+            _stream: stream,
+        })
+    }
+}
+
+fn build<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    mut source: DynamicMixer<f32>,
+    clock: Arc<AudioClock>,
+) -> Result<cpal::Stream, String>
+where
+    T: SizedSample + FromSample<f32>,
+{
+    device
+        .build_output_stream::<T, _, _>(
+            config,
+            move |data: &mut [T], _| {
+                clock.device_pull(Instant::now());
+                for sample in data.iter_mut() {
+                    *sample = source.next().map_or(T::EQUILIBRIUM, T::from_sample);
+                }
+            },
+            |e| log::error!("AUDIO: Output stream error: {}", e),
+            None,
+        )
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,238 @@
+//! Pitch-preserving time stretch for rate changes (WSOLA).
+//!
+//! Waveform-similarity overlap-add: the output is built from Hann-windowed
+//! grains taken from the input at `rate` times the output hop, each grain being
+//! shifted by a few milliseconds to line up with the previous one. This keeps
+//! the original pitch, at the cost of mild smearing on transients.
+
+use rodio::Source;
+use rodio::cpal::{FromSample, Sample as _};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+/// Grain length in seconds.
+const GRAIN_SECS: f64 = 0.04;
+/// How far a grain may be shifted to match the previous one, in seconds.
+const TOLERANCE_SECS: f64 = 0.006;
+/// Only every Nth frame is used when comparing waveforms (cheaper search).
+const SEARCH_STRIDE: usize = 4;
+
+/// Playback rate shared with a [`TimeStretch`] source, stored as `f32` bits so
+/// it can be changed while the sink is playing.
+#[derive(Clone, Debug)]
+pub struct SharedRate(Arc<AtomicU32>);
+
+impl SharedRate {
+    pub fn new(rate: f32) -> Self {
+        Self(Arc::new(AtomicU32::new(rate.to_bits())))
+    }
+
+    pub fn set(&self, rate: f32) {
+        self.0.store(rate.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+}
+
+/// Source adapter that changes tempo without changing pitch.
+pub struct TimeStretch<I> {
+    inner: I,
+    rate: SharedRate,
+    channels: usize,
+    sample_rate: u32,
+    grain: usize,
+    hop: usize,
+    tolerance: usize,
+    window: Vec<f32>,
+    /// Interleaved input not yet discarded; `input[0]` is frame `input_base`.
+    input: VecDeque<f32>,
+    input_base: usize,
+    input_done: bool,
+    /// Ideal (unshifted) input frame of the next grain.
+    ideal_pos: f64,
+    /// Input frame where the previous grain started.
+    prev_start: Option<usize>,
+    /// Second half of the previous windowed grain, waiting to be overlapped.
+    overlap: Vec<f32>,
+    output: VecDeque<f32>,
+}
+
+impl<I> TimeStretch<I>
+where
+    I: Source,
+    I::Item: rodio::Sample,
+    f32: FromSample<I::Item>,
+{
+    pub fn new(inner: I, rate: SharedRate) -> Self {
+        let channels = inner.channels().max(1) as usize;
+        let sample_rate = inner.sample_rate();
+        let hop = ((sample_rate as f64 * GRAIN_SECS / 2.0) as usize).max(16);
+        let grain = hop * 2;
+        let tolerance = (sample_rate as f64 * TOLERANCE_SECS) as usize;
+
+        let window = (0..grain)
+            .map(|i| {
+                let phase = i as f32 / grain as f32;
+                0.5 - 0.5 * (2.0 * std::f32::consts::PI * phase).cos()
+            })
+            .collect();
+
+        Self {
+            inner,
+            rate,
+            channels,
+            sample_rate,
+            grain,
+            hop,
+            tolerance,
+            window,
+            input: VecDeque::new(),
+            input_base: 0,
+            input_done: false,
+            ideal_pos: 0.0,
+            prev_start: None,
+            overlap: vec![0.0; hop * channels],
+            output: VecDeque::new(),
+        }
+    }
+
+    /// Pulls input until `frame` (exclusive) is buffered or the source ends.
+    fn fill_to(&mut self, frame: usize) {
+        while !self.input_done && self.input_base + self.input.len() / self.channels < frame {
+            match self.inner.next() {
+                Some(sample) => self.input.push_back(sample.to_sample::<f32>()),
+                None => self.input_done = true,
+            }
+        }
+    }
+
+    /// Mono value of an input frame (0 if not buffered).
+    fn mono(&self, frame: usize) -> f32 {
+        let Some(offset) = frame.checked_sub(self.input_base) else {
+            return 0.0;
+        };
+        let start = offset * self.channels;
+        (start..start + self.channels)
+            .filter_map(|i| self.input.get(i))
+            .sum::<f32>()
+    }
+
+    /// Picks the grain start near `ideal` that best continues the previous grain.
+    fn best_start(&self, ideal: usize) -> usize {
+        let Some(prev) = self.prev_start else {
+            return ideal;
+        };
+        let natural = prev + self.hop;
+        let min = ideal.saturating_sub(self.tolerance).max(self.input_base);
+        let max = ideal + self.tolerance;
+
+        let mut best = ideal.max(min);
+        let mut best_score = f32::MIN;
+        for candidate in min..=max {
+            let mut dot = 0.0;
+            let mut energy = 1e-9;
+            for i in (0..self.hop).step_by(SEARCH_STRIDE) {
+                let a = self.mono(candidate + i);
+                dot += a * self.mono(natural + i);
+                energy += a * a;
+            }
+            let score = dot / energy.sqrt();
+            if score > best_score {
+                best_score = score;
+                best = candidate;
+            }
+        }
+        best
+    }
+
+    /// Produces the next `hop` frames of output. Returns false once drained.
+    fn process_grain(&mut self) -> bool {
+        let rate = self.rate.get().clamp(0.25, 4.0) as f64;
+        let ideal = self.ideal_pos.round() as usize;
+
+        self.fill_to(ideal + self.tolerance + self.grain);
+        let available = self.input_base + self.input.len() / self.channels;
+        if self.input_done && ideal >= available {
+            // Flush the tail of the last grain once.
+            if self.overlap.iter().any(|s| *s != 0.0) {
+                self.output.extend(self.overlap.drain(..));
+                return true;
+            }
+            return false;
+        }
+
+        let start = self.best_start(ideal);
+        for i in 0..self.grain {
+            let w = self.window[i];
+            for c in 0..self.channels {
+                let sample = (start + i)
+                    .checked_sub(self.input_base)
+                    .and_then(|f| self.input.get(f * self.channels + c))
+                    .copied()
+                    .unwrap_or(0.0)
+                    * w;
+                if i < self.hop {
+                    self.output
+                        .push_back(self.overlap[i * self.channels + c] + sample);
+                } else {
+                    self.overlap[(i - self.hop) * self.channels + c] = sample;
+                }
+            }
+        }
+
+        self.prev_start = Some(start);
+        self.ideal_pos += self.hop as f64 * rate;
+
+        // Drop input that no future grain or comparison can reach.
+        let keep_from =
+            (start + self.hop).min((self.ideal_pos as usize).saturating_sub(self.tolerance));
+        let drop = keep_from
+            .saturating_sub(self.input_base)
+            .min(self.input.len() / self.channels);
+        self.input.drain(..drop * self.channels);
+        self.input_base += drop;
+        true
+    }
+}
+
+impl<I> Iterator for TimeStretch<I>
+where
+    I: Source,
+    I::Item: rodio::Sample,
+    f32: FromSample<I::Item>,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        while self.output.is_empty() {
+            if !self.process_grain() {
+                return None;
+            }
+        }
+        self.output.pop_front()
+    }
+}
+
+impl<I> Source for TimeStretch<I>
+where
+    I: Source,
+    I::Item: rodio::Sample,
+    f32: FromSample<I::Item>,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+    fn channels(&self) -> u16 {
+        self.channels as u16
+    }
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
@@ -9,6 +9,8 @@
 //! - **Logic thread**: Game state at 200 TPS with fixed timestep
 //! - **Input thread**: Keyboard input processing and keybind mapping
 //! - **Audio thread**: Dedicated audio playback with pitch shifting
+//! - **Downloader thread**: Optional beatmap mirror search and downloads
+//! - **Supervisor thread**: Restarts the input, gamepad and audio threads if they panic
 //!
 //! Communication between threads uses lock-free channels via [`SystemBus`].
 
@@ -19,14 +21,19 @@ mod system;
 
 mod database;
 mod difficulty;
+mod downloader;
 mod models;
+mod presence;
 mod shaders;
 mod shared;
 mod state;
 mod views;
 
 use crate::database::DbManager;
+use crate::downloader::Downloader;
+use crate::models::settings::SettingsState;
 use crate::system::bus::SystemBus;
+use crate::system::supervisor::Supervisor;
 use std::path::PathBuf;
 
 /// Application entry point.
@@ -45,21 +52,35 @@ fn main() {
     // Create the central communication hub
     let bus = SystemBus::new();
 
-    let input_bus = bus.clone();
     let logic_bus = bus.clone();
     let render_bus = bus.clone();
 
     // Initialize database manager
     let db_path = PathBuf::from("main.db");
-    let songs_path = PathBuf::from("songs");
-    let db_manager = DbManager::new(db_path, songs_path);
+    let settings = SettingsState::load();
+    let db_manager = DbManager::new(db_path, settings.song_roots(), settings.scan_options());
+    let downloader = Downloader::new();
+
+    // Community calculators, listed by the song select
+    difficulty::script::script_calculators();
 
-    // Initialize input manager
-    let input_manager = input::manager::InputManager::new();
+    // Spawn worker threads; the supervised ones are restarted if they panic
+    let mut supervisor = Supervisor::new(bus.clone());
+    let audio_bus = bus.clone();
+    supervisor.watch("Audio", move || {
+        logic::audio_thread::start_audio_thread(audio_bus.clone())
+    });
+    let input_bus = bus.clone();
+    supervisor.watch("Input", move || {
+        input::start_thread(input_bus.clone(), input::manager::InputManager::new())
+    });
+    let gamepad_bus = bus.clone();
+    supervisor.watch("Gamepad", move || {
+        input::gamepad::start_thread(gamepad_bus.clone())
+    });
+    supervisor.start();
 
-    // Spawn worker threads
-    input::start_thread(input_bus, input_manager);
-    logic::start_thread(logic_bus, db_manager);
+    logic::start_thread(logic_bus, db_manager, downloader);
 
     // Run the render loop (blocking)
     render::app::App::run(render_bus);
@@ -0,0 +1,100 @@
+//! Crash-safe copy of the play in progress.
+//!
+//! While a chart is played, the logic thread writes the inputs so far to
+//! `data/autosave.json` every few seconds. The copy goes through a temporary
+//! file and a rename, so a crash in the middle of a write keeps the previous
+//! one. A play that ends (finished, quit or closed) removes the file; one
+//! found at startup belongs to a play that crashed, and can be saved as an
+//! incomplete score.
+
+use crate::models::replay::ReplayData;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+const AUTOSAVE_PATH: &str = "data/autosave.json";
+const AUTOSAVE_TMP_PATH: &str = "data/autosave.json.tmp";
+
+/// Time between two writes of the play in progress.
+pub const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Inputs of an unfinished play.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Autosave {
+    pub beatmap_hash: String,
+    /// When the copy was written (unix seconds).
+    pub timestamp: i64,
+    /// Chart time the play had reached, in ms.
+    pub reached_ms: f64,
+    pub replay: ReplayData,
+}
+
+impl Autosave {
+    pub fn write(&self) -> Result<(), String> {
+        Self::write_to(self, Path::new(AUTOSAVE_PATH), Path::new(AUTOSAVE_TMP_PATH))
+    }
+
+    /// The copy left by a play that did not end, if any.
+    pub fn load() -> Option<Self> {
+        Self::load_from(Path::new(AUTOSAVE_PATH))
+    }
+
+    /// Forgets the play in progress.
+    pub fn remove() {
+        if let Err(e) = fs::remove_file(AUTOSAVE_PATH)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            log::warn!("REPLAY: Could not remove the autosave: {}", e);
+        }
+    }
+
+    fn write_to(&self, path: &Path, tmp_path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(tmp_path, json).map_err(|e| e.to_string())?;
+        fs::rename(tmp_path, path).map_err(|e| e.to_string())
+    }
+
+    fn load_from(path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+        match serde_json::from_str(&content) {
+            Ok(autosave) => Some(autosave),
+            Err(e) => {
+                log::warn!("REPLAY: Ignoring unreadable autosave {:?}: {}", path, e);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::settings::HitWindowMode;
+
+    #[test]
+    fn autosave_round_trips_through_its_file() {
+        let dir = std::env::temp_dir().join("rvsrg_autosave_test");
+        let (path, tmp_path) = (dir.join("autosave.json"), dir.join("autosave.json.tmp"));
+        let mut replay = ReplayData::new(1.2, HitWindowMode::EtternaJudge, 4.0);
+        replay.add_press(1000.0, 2);
+        replay.add_release(1080.0, 2);
+        let autosave = Autosave {
+            beatmap_hash: "abc".to_string(),
+            timestamp: 1_700_000_000,
+            reached_ms: 1500.0,
+            replay,
+        };
+
+        autosave.write_to(&path, &tmp_path).unwrap();
+        assert!(!tmp_path.exists());
+        assert_eq!(Autosave::load_from(&path), Some(autosave));
+
+        fs::write(&path, "{ truncated").unwrap();
+        assert_eq!(Autosave::load_from(&path), None);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
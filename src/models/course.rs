@@ -0,0 +1,32 @@
+//! Courses: ordered chart lists played back to back with shared score and HP.
+
+use crate::models::stats::Judgement;
+
+/// Health at the start of a course.
+pub const COURSE_START_HEALTH: f64 = 1.0;
+
+/// A course as entered in the menu, before it is persisted.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CourseDraft {
+    pub name: String,
+    pub beatmap_hashes: Vec<String>,
+}
+
+impl CourseDraft {
+    /// A course needs at least two charts, otherwise it is just a regular play.
+    pub fn is_valid(&self) -> bool {
+        self.beatmap_hashes.len() >= 2
+    }
+}
+
+/// HP change applied for a judgement while playing a course.
+pub fn health_delta(judgement: Judgement) -> f64 {
+    match judgement {
+        Judgement::Marv | Judgement::Perfect => 0.008,
+        Judgement::Great => 0.004,
+        Judgement::Good => 0.0,
+        Judgement::Bad => -0.04,
+        Judgement::Miss => -0.08,
+        Judgement::GhostTap => 0.0,
+    }
+}
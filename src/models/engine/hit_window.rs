@@ -1,9 +1,11 @@
 //! Hit window timing configuration.
 
+use crate::models::settings::HitWindowMode;
 use crate::models::stats::Judgement;
+use serde::{Deserialize, Serialize};
 
 /// Hit window timing thresholds in milliseconds.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct HitWindow {
     pub marv_ms: f64,
     pub perfect_ms: f64,
@@ -66,6 +68,41 @@ impl HitWindow {
         }
     }
 
+    /// Fenêtres d'un mode de jugement (`value` = OD ou niveau de judge).
+    pub fn from_mode(mode: HitWindowMode, value: f64) -> Self {
+        match mode {
+            HitWindowMode::OsuOD => Self::from_osu_od(value),
+            HitWindowMode::EtternaJudge => Self::from_etterna_judge(value as u8),
+            HitWindowMode::Custom(windows) => windows,
+        }
+    }
+
+    /// Vérifie que les fenêtres sont positives et strictement croissantes,
+    /// de Marvelous jusqu'à Miss.
+    pub fn validate(&self) -> Result<(), String> {
+        let windows = [
+            ("Marvelous", self.marv_ms),
+            ("Perfect", self.perfect_ms),
+            ("Great", self.great_ms),
+            ("Good", self.good_ms),
+            ("Bad", self.bad_ms),
+            ("Miss", self.miss_ms),
+        ];
+        if let Some((name, _)) = windows.iter().find(|(_, ms)| ms.is_nan() || *ms <= 0.0) {
+            return Err(format!("{} window must be above 0 ms", name));
+        }
+        for pair in windows.windows(2) {
+            let ((inner, inner_ms), (outer, outer_ms)) = (pair[0], pair[1]);
+            if outer_ms <= inner_ms {
+                return Err(format!(
+                    "{} window ({} ms) must be wider than {} ({} ms)",
+                    outer, outer_ms, inner, inner_ms
+                ));
+            }
+        }
+        Ok(())
+    }
+
     /// Constructeur utilitaire pour des valeurs custom complètes
     pub fn from_custom(marv: f64, perf: f64, great: f64, good: f64, bad: f64, miss: f64) -> Self {
         Self {
@@ -102,3 +139,29 @@ impl HitWindow {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::settings::SettingsState;
+
+    #[test]
+    fn custom_windows_must_widen_and_survive_settings() {
+        assert!(HitWindow::new().validate().is_ok());
+        let overlapping = HitWindow::from_custom(20.0, 50.0, 50.0, 100.0, 150.0, 200.0);
+        assert!(overlapping.validate().is_err());
+        assert!(
+            HitWindow::from_custom(0.0, 50.0, 65.0, 100.0, 150.0, 200.0)
+                .validate()
+                .is_err()
+        );
+
+        let windows = HitWindow::from_custom(10.0, 30.0, 60.0, 90.0, 120.0, 180.0);
+        let mut settings = SettingsState::new();
+        settings.hit_window_mode = HitWindowMode::Custom(windows);
+        let toml = toml::to_string_pretty(&settings).unwrap();
+        let read: SettingsState = toml::from_str(&toml).unwrap();
+        assert_eq!(read.hit_window_mode, HitWindowMode::Custom(windows));
+        assert_eq!(HitWindow::from_mode(read.hit_window_mode, 0.0), windows);
+    }
+}
@@ -1,6 +1,35 @@
+/// Rectangle `[u, v, width, height]` covering a whole texture.
+pub const FULL_UV: [f32; 4] = [0.0, 0.0, 1.0, 1.0];
+
+/// `clip_y` of sprites drawn whole.
+pub const NO_CLIP: f32 = f32::MIN;
+
 #[repr(C)]
 #[derive(Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct InstanceRaw {
     pub offset: [f32; 2],
     pub scale: [f32; 2],
+    /// Part of the texture drawn on the quad, `[u, v, width, height]`.
+    pub uv: [f32; 4],
+    /// Opacity the sprite is drawn with.
+    pub alpha: f32,
+    /// Times the texture repeats down the quad, from its top edge; 0
+    /// stretches it once over the quad.
+    pub tile: f32,
+    /// Clip-space height under which nothing of the quad is drawn.
+    pub clip_y: f32,
+}
+
+impl InstanceRaw {
+    /// Quad showing the whole texture.
+    pub fn new(offset: [f32; 2], scale: [f32; 2]) -> Self {
+        Self {
+            offset,
+            scale,
+            uv: FULL_UV,
+            alpha: 1.0,
+            tile: 0.0,
+            clip_y: NO_CLIP,
+        }
+    }
 }
@@ -0,0 +1,98 @@
+//! Why a chart could not be loaded for play.
+
+use crate::models::engine::constants::NUM_COLUMNS;
+use crate::models::engine::note::NoteData;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MapLoadError {
+    /// The chart file itself is gone.
+    ChartMissing(PathBuf),
+    /// The chart points to an audio file that does not exist.
+    AudioMissing(PathBuf),
+    /// The chart uses more columns than the engine plays.
+    UnsupportedKeyMode(usize),
+    /// The chart has nothing to play.
+    NoNotes,
+    /// The chart file could not be parsed.
+    Corrupt(String),
+}
+
+impl std::fmt::Display for MapLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MapLoadError::ChartMissing(path) => {
+                write!(f, "Chart file missing ({})", file_name(path))
+            }
+            MapLoadError::AudioMissing(path) => {
+                write!(f, "Audio file missing ({})", file_name(path))
+            }
+            MapLoadError::UnsupportedKeyMode(keys) => {
+                write!(
+                    f,
+                    "Unsupported keymode: {}K (only {}K is playable)",
+                    keys, NUM_COLUMNS
+                )
+            }
+            MapLoadError::NoNotes => write!(f, "Chart has no notes"),
+            MapLoadError::Corrupt(msg) => write!(f, "Corrupt chart: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for MapLoadError {}
+
+/// The format parsers report their failures as text.
+impl From<String> for MapLoadError {
+    fn from(msg: String) -> Self {
+        MapLoadError::Corrupt(msg)
+    }
+}
+
+/// Checks that a parsed chart can be played.
+pub fn validate_chart(audio_path: &Path, notes: &[NoteData]) -> Result<(), MapLoadError> {
+    if notes.is_empty() {
+        return Err(MapLoadError::NoNotes);
+    }
+    let keys = notes.iter().map(|note| note.column + 1).max().unwrap_or(0);
+    if keys > NUM_COLUMNS {
+        return Err(MapLoadError::UnsupportedKeyMode(keys));
+    }
+    if !audio_path.is_file() {
+        return Err(MapLoadError::AudioMissing(audio_path.to_path_buf()));
+    }
+    Ok(())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_charts_wider_than_the_playfield() {
+        let notes = vec![NoteData::tap(0.0, 0), NoteData::tap(100.0, NUM_COLUMNS + 2)];
+        assert_eq!(
+            validate_chart(Path::new("missing.ogg"), &notes),
+            Err(MapLoadError::UnsupportedKeyMode(NUM_COLUMNS + 3))
+        );
+    }
+
+    #[test]
+    fn reports_missing_audio_last() {
+        let notes = vec![NoteData::tap(0.0, 0)];
+        assert_eq!(
+            validate_chart(Path::new("missing.ogg"), &notes),
+            Err(MapLoadError::AudioMissing(PathBuf::from("missing.ogg")))
+        );
+        assert_eq!(
+            validate_chart(Path::new("missing.ogg"), &[]),
+            Err(MapLoadError::NoNotes)
+        );
+    }
+}
@@ -2,14 +2,18 @@ pub mod constants;
 //pub mod game;
 pub mod hit_window;
 pub mod instance;
+pub mod load_error;
 pub mod note;
 pub mod pixel_system;
 pub mod playfield;
+pub mod timing;
 
 pub use constants::*;
 //pub use game::GameEngine;
 pub use hit_window::HitWindow;
-pub use instance::InstanceRaw;
-pub use note::{NoteData, NoteType, load_map, load_map_safe};
+pub use instance::{InstanceRaw, NO_CLIP};
+pub use load_error::MapLoadError;
+pub use note::{NoteData, NoteType, load_map};
 pub use pixel_system::PixelSystem;
 pub use playfield::PlayfieldConfig;
+pub use timing::{ScrollMap, TimingData};
@@ -1,10 +1,17 @@
 //! Structures et fonctions de chargement de charts osu!mania.
 
+use crate::models::engine::load_error::{MapLoadError, validate_chart};
+use crate::models::engine::timing::TimingData;
+use crate::models::formats::{ChartFormat, bms, malody, sm};
+use crate::models::settings::{ChartRules, OsuConvertRules, OsuHitsound};
+use rosu_map::section::hit_objects::hit_samples::{HitSampleInfoName, HitSoundType};
 use rosu_map::section::hit_objects::{HitObject, HitObjectKind};
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// Type of note in a rhythm game chart.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum NoteType {
     /// Simple tap note - press and release.
     Tap,
@@ -117,7 +124,7 @@ impl NoteType {
 }
 
 /// A single note in a rhythm game chart.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct NoteData {
     /// When the note should be hit (in milliseconds).
     pub timestamp_ms: f64,
@@ -127,6 +134,10 @@ pub struct NoteData {
     pub hit: bool,
     /// The type of note (tap, hold, mine, burst) with its state.
     pub note_type: NoteType,
+    /// Decorative note: drawn like any other note but never judged.
+    pub fake: bool,
+    /// Keysound sample file (relative to the chart folder), if the format provides one.
+    pub keysound: Option<Arc<str>>,
 }
 
 impl NoteData {
@@ -137,6 +148,8 @@ impl NoteData {
             column,
             hit: false,
             note_type: NoteType::Tap,
+            fake: false,
+            keysound: None,
         }
     }
 
@@ -147,6 +160,8 @@ impl NoteData {
             column,
             hit: false,
             note_type: NoteType::new_hold(duration_ms),
+            fake: false,
+            keysound: None,
         }
     }
 
@@ -157,6 +172,8 @@ impl NoteData {
             column,
             hit: false,
             note_type: NoteType::Mine,
+            fake: false,
+            keysound: None,
         }
     }
 
@@ -167,9 +184,23 @@ impl NoteData {
             column,
             hit: false,
             note_type: NoteType::new_burst(duration_ms, required_hits),
+            fake: false,
+            keysound: None,
         }
     }
 
+    /// Marks this note as fake (decorative only).
+    pub fn into_fake(mut self) -> Self {
+        self.fake = true;
+        self
+    }
+
+    /// Attaches a keysound sample to this note.
+    pub fn with_keysound(mut self, keysound: Option<Arc<str>>) -> Self {
+        self.keysound = keysound;
+        self
+    }
+
     /// Returns the end time of this note.
     /// For holds/bursts: start + duration. For others: same as start.
     pub fn end_time_ms(&self) -> f64 {
@@ -201,9 +232,14 @@ impl NoteData {
         self.note_type.duration()
     }
 
+    /// Returns true if this note takes part in judging (i.e. is not fake).
+    pub fn is_judged(&self) -> bool {
+        !self.fake
+    }
+
     /// Returns true if this note should be hit.
     pub fn should_hit(&self) -> bool {
-        self.note_type.should_hit()
+        !self.fake && self.note_type.should_hit()
     }
 
     /// Returns the number of hits required for this note.
@@ -226,10 +262,34 @@ impl NoteData {
     }
 }
 
-/// Charge une map depuis un fichier .osu.
-/// Retourne le chemin audio et la liste des notes, ou une erreur si le chargement échoue.
-pub fn load_map(path: PathBuf) -> Result<(PathBuf, Vec<NoteData>), String> {
-    let map = rosu_map::Beatmap::from_path(&path)
+/// Charge une map depuis un fichier .osu (ou .bms / .mc / .sm, via `formats`).
+/// Retourne le chemin audio, la liste des notes et les changements de BPM,
+/// ou la raison pour laquelle la map n'est pas jouable.
+pub fn load_map(
+    path: PathBuf,
+    rules: ChartRules,
+) -> Result<(PathBuf, Vec<NoteData>, TimingData), MapLoadError> {
+    if !path.is_file() {
+        return Err(MapLoadError::ChartMissing(path));
+    }
+    let (audio_path, notes, timing) = parse_map(&path, rules)?;
+    validate_chart(&audio_path, &notes)?;
+    Ok((audio_path, notes, timing))
+}
+
+/// Lit les notes d'un chart, quel que soit son format.
+fn parse_map(
+    path: &Path,
+    rules: ChartRules,
+) -> Result<(PathBuf, Vec<NoteData>, TimingData), String> {
+    match ChartFormat::from_path(path) {
+        Some(ChartFormat::Bms) => return bms::load_map(path, rules.bms_scratch),
+        Some(ChartFormat::Malody) => return malody::load_map(path),
+        Some(ChartFormat::Sm) => return sm::load_map(path),
+        _ => {}
+    }
+
+    let map = rosu_map::Beatmap::from_path(path)
         .map_err(|e| format!("Failed to load beatmap {:?}: {}", path, e))?;
 
     let audio_path = path
@@ -238,48 +298,72 @@ pub fn load_map(path: PathBuf) -> Result<(PathBuf, Vec<NoteData>), String> {
         .join(&map.audio_file);
 
     let key_count = map.circle_size as u8;
+    let timing = TimingData::from_osu(&map);
 
     let mut notes = Vec::new();
     for hit_object in map.hit_objects {
         if let Some(note) = parse_hit_object(&hit_object, key_count) {
-            notes.push(note);
+            notes.push(convert_note(note, &hit_object, &rules.osu_convert));
         }
     }
 
-    Ok((audio_path, notes))
+    Ok((audio_path, notes, timing))
 }
 
-/// Charge une map depuis un fichier .osu, version safe qui retourne Option.
-/// Utilisé pour le cache où on ne veut pas panic.
-pub fn load_map_safe(path: &PathBuf) -> Option<(PathBuf, Vec<NoteData>)> {
-    let map = rosu_map::Beatmap::from_path(path).ok()?;
-    let audio_path = path.parent()?.join(&map.audio_file);
-    let key_count = map.circle_size as u8;
-
-    let mut notes = Vec::new();
-    for hit_object in map.hit_objects {
-        if let Some(note) = parse_hit_object(&hit_object, key_count) {
-            notes.push(note);
-        }
+/// Applique les règles de conversion osu! : un tap portant le hitsound des
+/// mines devient une mine, un hold portant celui des bursts devient un burst.
+pub fn convert_note(note: NoteData, hit_object: &HitObject, rules: &OsuConvertRules) -> NoteData {
+    if !rules.is_enabled() {
+        return note;
+    }
+    let hitsound = HitSoundType::from(hit_object.samples.as_slice());
+    let has = |marker: OsuHitsound| match marker {
+        OsuHitsound::None => false,
+        OsuHitsound::Whistle => hitsound.has_flag(HitSoundType::WHISTLE),
+        OsuHitsound::Finish => hitsound.has_flag(HitSoundType::FINISH),
+        OsuHitsound::Clap => hitsound.has_flag(HitSoundType::CLAP),
+    };
+
+    if note.is_tap() && has(rules.mine_hitsound) {
+        // Une mine ne joue pas de keysound.
+        NoteData::mine(note.timestamp_ms, note.column)
+    } else if note.is_hold() && has(rules.burst_hitsound) {
+        let duration = note.hold_duration_ms();
+        let required_hits = rules.burst_required_hits(duration);
+        NoteData::burst(note.timestamp_ms, note.column, duration, required_hits)
+            .with_keysound(note.keysound)
+    } else {
+        note
     }
-
-    Some((audio_path, notes))
 }
 
 /// Parse un HitObject osu! et retourne une NoteData.
 pub fn parse_hit_object(hit_object: &HitObject, key_count: u8) -> Option<NoteData> {
-    match &hit_object.kind {
+    let note = match &hit_object.kind {
         HitObjectKind::Circle(circle) => {
             let column = x_to_column_generic(circle.pos.x as i32, key_count)?;
-            Some(NoteData::tap(hit_object.start_time, column))
+            NoteData::tap(hit_object.start_time, column)
         }
         HitObjectKind::Hold(hold) => {
             // For holds, use the end_info position which contains the x coordinate
             let column = x_to_column_generic(hold.pos_x as i32, key_count)?;
-            Some(NoteData::hold(hit_object.start_time, column, hold.duration))
+            NoteData::hold(hit_object.start_time, column, hold.duration)
         }
-        _ => None, // On ignore les sliders, spinners, etc.
-    }
+        _ => return None, // On ignore les sliders, spinners, etc.
+    };
+
+    Some(note.with_keysound(keysound_of(hit_object)))
+}
+
+/// Récupère le sample custom (keysound) d'un HitObject, s'il en a un.
+fn keysound_of(hit_object: &HitObject) -> Option<Arc<str>> {
+    hit_object
+        .samples
+        .iter()
+        .find_map(|sample| match &sample.name {
+            HitSampleInfoName::File(file) if !file.is_empty() => Some(Arc::from(file.as_str())),
+            _ => None,
+        })
 }
 
 /// Parse un HitObject osu! et retourne l'index de colonne si c'est un cercle valide.
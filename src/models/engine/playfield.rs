@@ -11,6 +11,14 @@ pub struct PlayfieldConfig {
     pub receptor_spacing_pixels: f32,
     pub x_offset_pixels: f32,
     pub y_offset_pixels: f32,
+    /// Opacity of tap notes, hold / burst ends and mines.
+    pub note_opacity: f32,
+    /// Opacity of hold and burst bodies.
+    pub hold_body_opacity: f32,
+    pub receptor_opacity: f32,
+    /// Body images stretched over the note instead of repeated.
+    pub stretch_hold_body: bool,
+    pub stretch_burst_body: bool,
 }
 
 impl PlayfieldConfig {
@@ -24,6 +32,11 @@ impl PlayfieldConfig {
             receptor_spacing_pixels: 0.0,
             x_offset_pixels: 0.0,
             y_offset_pixels: 0.0,
+            note_opacity: 1.0,
+            hold_body_opacity: 1.0,
+            receptor_opacity: 1.0,
+            stretch_hold_body: false,
+            stretch_burst_body: false,
         }
     }
     pub fn decrease_note_size(&mut self) {
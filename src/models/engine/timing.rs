@@ -0,0 +1,216 @@
+//! Tempo data of a chart and the scroll position mapping derived from it.
+//!
+//! `TimingData` keeps the BPM and scroll velocity (SV) changes parsed from the
+//! chart file. `ScrollMap` turns them into a "scroll position" per timestamp,
+//! which the playfield uses instead of raw time when scroll speed follows the
+//! BPM (mmod) and/or the chart's SV.
+
+use rosu_map::Beatmap;
+use serde::{Deserialize, Serialize};
+
+/// A tempo change at a given time. A BPM of 0 freezes the scroll (BMS stops).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BpmChange {
+    pub time_ms: f64,
+    pub bpm: f64,
+}
+
+/// A scroll velocity multiplier starting at a given time (osu! inherited points).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SvChange {
+    pub time_ms: f64,
+    pub multiplier: f64,
+}
+
+/// BPM and SV changes of a chart, each sorted by time.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TimingData {
+    pub bpm_changes: Vec<BpmChange>,
+    pub sv_changes: Vec<SvChange>,
+}
+
+impl TimingData {
+    /// Reads the timing points of an osu! beatmap: uninherited (red) points
+    /// give the BPM, inherited (green) points the SV.
+    pub fn from_osu(map: &Beatmap) -> Self {
+        let bpm_changes = map
+            .control_points
+            .timing_points
+            .iter()
+            .filter(|tp| tp.beat_len > 0.0)
+            .map(|tp| BpmChange {
+                time_ms: tp.time,
+                bpm: 60_000.0 / tp.beat_len,
+            })
+            .collect();
+        // rosu_map stores the mania scroll multiplier of every point on the
+        // effect points (1.0 for red lines, which reset the SV).
+        let sv_changes = map
+            .control_points
+            .effect_points
+            .iter()
+            .map(|ep| SvChange {
+                time_ms: ep.time,
+                multiplier: ep.scroll_speed,
+            })
+            .collect();
+        Self {
+            bpm_changes,
+            sv_changes,
+        }
+    }
+
+    /// BPM that lasts the longest between the first change and `end_ms`.
+    /// Used as the reference tempo for BPM-scaled scrolling.
+    pub fn dominant_bpm(&self, end_ms: f64) -> Option<f64> {
+        let mut durations: Vec<(f64, f64)> = Vec::new();
+        for (i, change) in self.bpm_changes.iter().enumerate() {
+            if change.bpm <= 0.0 {
+                continue;
+            }
+            let next = self
+                .bpm_changes
+                .get(i + 1)
+                .map_or(end_ms, |c| c.time_ms.min(end_ms));
+            let length = (next - change.time_ms).max(0.0);
+            match durations
+                .iter_mut()
+                .find(|(bpm, _)| (bpm - change.bpm).abs() < 0.001)
+            {
+                Some((_, total)) => *total += length,
+                None => durations.push((change.bpm, length)),
+            }
+        }
+        durations
+            .into_iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(bpm, _)| bpm)
+    }
+}
+
+/// Section of constant scroll velocity.
+#[derive(Debug, Clone, Copy)]
+struct ScrollSegment {
+    time_ms: f64,
+    /// Scroll position at `time_ms`.
+    position: f64,
+    /// Scroll position units per millisecond.
+    speed: f64,
+}
+
+/// Piecewise-linear mapping from chart time to scroll position.
+///
+/// Positions are in "reference milliseconds": at speed 1.0 a note covers the
+/// playfield in exactly the configured scroll time.
+#[derive(Debug, Clone)]
+pub struct ScrollMap {
+    segments: Vec<ScrollSegment>,
+}
+
+impl ScrollMap {
+    /// Builds the mapping from the chart's timing.
+    ///
+    /// With `reference_bpm`, velocity is proportional to the BPM (mmod);
+    /// with `use_sv`, it is also multiplied by the SV. Before the first change
+    /// the initial velocity is extended backwards, so the pre-roll scrolls normally.
+    pub fn new(timing: &TimingData, reference_bpm: Option<f64>, use_sv: bool) -> Self {
+        /// Which factor of the velocity an event changes.
+        enum Factor {
+            Bpm(f64),
+            Sv(f64),
+        }
+
+        let mut events: Vec<(f64, Factor)> = Vec::new();
+        if let Some(reference) = reference_bpm {
+            events.extend(
+                timing
+                    .bpm_changes
+                    .iter()
+                    .map(|c| (c.time_ms, Factor::Bpm(c.bpm.max(0.0) / reference))),
+            );
+        }
+        if use_sv {
+            events.extend(
+                timing
+                    .sv_changes
+                    .iter()
+                    .map(|c| (c.time_ms, Factor::Sv(c.multiplier.max(0.0)))),
+            );
+        }
+        events.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut bpm_factor = events
+            .iter()
+            .find_map(|(_, f)| match f {
+                Factor::Bpm(v) => Some(*v),
+                Factor::Sv(_) => None,
+            })
+            .unwrap_or(1.0);
+        let mut sv = 1.0;
+
+        let Some(&(start, _)) = events.first() else {
+            return Self {
+                segments: Vec::new(),
+            };
+        };
+        let mut segments = vec![ScrollSegment {
+            time_ms: start,
+            position: start,
+            speed: bpm_factor * sv,
+        }];
+        for (time_ms, factor) in events {
+            match factor {
+                Factor::Bpm(v) => bpm_factor = v,
+                Factor::Sv(v) => sv = v,
+            }
+            let last = segments.last().expect("segments start non-empty");
+            let position = last.position + (time_ms - last.time_ms) * last.speed;
+            segments.push(ScrollSegment {
+                time_ms,
+                position,
+                speed: bpm_factor * sv,
+            });
+        }
+        Self { segments }
+    }
+
+    /// Scroll position of `time_ms`.
+    pub fn position_at(&self, time_ms: f64) -> f64 {
+        let idx = self
+            .segments
+            .partition_point(|s| s.time_ms <= time_ms)
+            .saturating_sub(1);
+        match self.segments.get(idx) {
+            Some(s) => s.position + (time_ms - s.time_ms) * s.speed,
+            None => time_ms,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_speed_up_after_an_sv_change() {
+        let timing = TimingData {
+            bpm_changes: vec![BpmChange {
+                time_ms: 0.0,
+                bpm: 120.0,
+            }],
+            sv_changes: vec![SvChange {
+                time_ms: 1000.0,
+                multiplier: 2.0,
+            }],
+        };
+
+        let map = ScrollMap::new(&timing, None, true);
+        assert_eq!(map.position_at(500.0), 500.0);
+        assert_eq!(map.position_at(1000.0), 1000.0);
+        assert_eq!(map.position_at(1500.0), 2000.0);
+
+        // Without SV the scroll follows the time.
+        let map = ScrollMap::new(&timing, None, false);
+        assert_eq!(map.position_at(1500.0), 1500.0);
+    }
+}
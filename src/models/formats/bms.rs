@@ -0,0 +1,450 @@
+//! Experimental BMS import (`.bms` / `.bme` / `.bml`).
+//!
+//! Converts single-player 5K/7K + scratch charts into the internal note model.
+//! Keysounds are kept as note metadata only: playback uses the first sample of
+//! the BGM channel as the song audio, which works for the common "keysound-less"
+//! conversions that ship the whole song as a single sample.
+//!
+//! Supported: BPM changes (`#BPM`, channels 03/08), stops (channel 09), measure
+//! lengths (channel 02), long notes (channels 5x and `#LNOBJ`), landmines (Dx).
+//! `#RANDOM` blocks always take the `#IF 1` branch.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::models::engine::NoteData;
+use crate::models::engine::timing::{BpmChange, TimingData};
+use crate::models::settings::BmsScratchMode;
+
+/// Default tempo when the chart does not declare `#BPM`.
+const DEFAULT_BPM: f64 = 130.0;
+
+/// Audio extensions tried when a referenced sample is missing on disk
+/// (BMS packages often reference `.wav` but ship `.ogg`).
+const SAMPLE_EXTENSIONS: [&str; 4] = ["ogg", "wav", "mp3", "flac"];
+
+/// A BMS chart converted to the internal model.
+#[derive(Debug, Clone)]
+pub struct BmsChart {
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub artist: String,
+    pub play_level: Option<u32>,
+    /// `#DIFFICULTY` (1 = BEGINNER .. 5 = INSANE).
+    pub difficulty: Option<u8>,
+    /// Background image (`#STAGEFILE`), relative to the chart folder.
+    pub stage_file: Option<String>,
+    /// Song audio (first BGM sample), if it exists on disk.
+    pub audio_path: Option<PathBuf>,
+    /// Key lanes in the chart (5 or 7), scratch excluded.
+    pub key_count: usize,
+    /// Notes sorted by time, shifted so that 0 ms is the start of the song audio.
+    pub notes: Vec<NoteData>,
+    /// Tempo changes on the same timeline as `notes`; stops are 0 BPM sections.
+    pub timing: TimingData,
+}
+
+impl BmsChart {
+    /// Difficulty label shown in song select, e.g. "HYPER 10".
+    pub fn difficulty_name(&self) -> String {
+        let name = match self.difficulty {
+            Some(1) => "BEGINNER".to_string(),
+            Some(2) => "NORMAL".to_string(),
+            Some(3) => "HYPER".to_string(),
+            Some(4) => "ANOTHER".to_string(),
+            Some(5) => "INSANE".to_string(),
+            _ => self
+                .subtitle
+                .clone()
+                .unwrap_or_else(|| format!("{}K", self.key_count)),
+        };
+        match self.play_level {
+            Some(level) => format!("{} {}", name, level),
+            None => name,
+        }
+    }
+}
+
+/// Loads a BMS chart for gameplay: returns the audio path, the notes and the tempo changes.
+pub fn load_map(
+    path: &Path,
+    scratch: BmsScratchMode,
+) -> Result<(PathBuf, Vec<NoteData>, TimingData), String> {
+    let chart = parse_bms(path, scratch)?;
+    let audio_path = chart.audio_path.ok_or_else(|| {
+        format!(
+            "BMS chart {:?} has no background audio (keysound-only charts are not supported)",
+            path
+        )
+    })?;
+    Ok((audio_path, chart.notes, chart.timing))
+}
+
+/// Where a channel's objects go on the playfield.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lane {
+    /// Key lane, 0-indexed from the left.
+    Key(usize),
+    Scratch,
+}
+
+/// Maps the second character of a player-1 channel to its lane.
+fn lane_of(channel: &[u8]) -> Option<Lane> {
+    match channel.get(1)? {
+        b'1' => Some(Lane::Key(0)),
+        b'2' => Some(Lane::Key(1)),
+        b'3' => Some(Lane::Key(2)),
+        b'4' => Some(Lane::Key(3)),
+        b'5' => Some(Lane::Key(4)),
+        b'6' => Some(Lane::Scratch),
+        b'8' => Some(Lane::Key(5)),
+        b'9' => Some(Lane::Key(6)),
+        _ => None, // 7 = foot pedal
+    }
+}
+
+/// One object from a `#mmmcc:` line.
+struct RawObject {
+    measure: u32,
+    /// Position inside the measure, in `[0, 1)`.
+    fraction: f64,
+    channel: [u8; 2],
+    value: String,
+}
+
+/// Tempo change or stop, positioned in beats from the start of the chart.
+enum TimingEvent {
+    Bpm(f64),
+    /// Stop length in beats.
+    Stop(f64),
+}
+
+/// Tempo section used to convert beats to milliseconds.
+struct Segment {
+    beat: f64,
+    ms: f64,
+    bpm: f64,
+}
+
+/// Parses a BMS file and converts it to notes.
+pub fn parse_bms(path: &Path, scratch: BmsScratchMode) -> Result<BmsChart, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    // Most BMS files are Shift-JIS; only the ASCII parts matter for parsing.
+    let text = String::from_utf8_lossy(&bytes);
+    let folder = path
+        .parent()
+        .ok_or_else(|| format!("Invalid path (no parent): {:?}", path))?;
+
+    let mut header: HashMap<String, String> = HashMap::new();
+    let mut wavs: HashMap<String, String> = HashMap::new();
+    let mut bpms: HashMap<String, f64> = HashMap::new();
+    let mut stops: HashMap<String, f64> = HashMap::new();
+    let mut measure_lengths: HashMap<u32, f64> = HashMap::new();
+    let mut objects: Vec<RawObject> = Vec::new();
+    let mut skipping = false;
+
+    for line in text.lines() {
+        let Some(body) = line.trim().strip_prefix('#') else {
+            continue;
+        };
+
+        let (key, value) = match body.find(|c: char| c.is_whitespace()) {
+            Some(i) => (body[..i].to_ascii_uppercase(), body[i..].trim()),
+            None => (body.to_ascii_uppercase(), ""),
+        };
+
+        // Control flow: always follow the first branch of random blocks.
+        match key.as_str() {
+            "IF" => {
+                skipping = value != "1";
+                continue;
+            }
+            "ELSEIF" | "ELSE" => {
+                skipping = true;
+                continue;
+            }
+            "ENDIF" => {
+                skipping = false;
+                continue;
+            }
+            "RANDOM" | "SETRANDOM" | "ENDRANDOM" => continue,
+            _ => {}
+        }
+        if skipping {
+            continue;
+        }
+
+        if let Some((measure, channel, data)) = parse_channel_line(body) {
+            if &channel == b"02" {
+                if let Ok(length) = data.parse::<f64>()
+                    && length > 0.0
+                {
+                    measure_lengths.insert(measure, length);
+                }
+                continue;
+            }
+
+            let pairs: Vec<&[u8]> = data.as_bytes().chunks_exact(2).collect();
+            let count = pairs.len();
+            for (i, pair) in pairs.into_iter().enumerate() {
+                if pair == b"00" {
+                    continue;
+                }
+                objects.push(RawObject {
+                    measure,
+                    fraction: i as f64 / count as f64,
+                    channel,
+                    value: String::from_utf8_lossy(pair).to_ascii_uppercase(),
+                });
+            }
+            continue;
+        }
+
+        if key.len() == 5 && key.starts_with("WAV") {
+            wavs.insert(key[3..].to_string(), value.to_string());
+        } else if key.len() == 5 && key.starts_with("BPM") {
+            if let Ok(bpm) = value.parse::<f64>() {
+                bpms.insert(key[3..].to_string(), bpm);
+            }
+        } else if key.len() == 6 && key.starts_with("STOP") {
+            if let Ok(stop) = value.parse::<f64>() {
+                stops.insert(key[4..].to_string(), stop);
+            }
+        } else {
+            header.insert(key, value.to_string());
+        }
+    }
+
+    let initial_bpm = header
+        .get("BPM")
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|bpm| *bpm > 0.0)
+        .unwrap_or(DEFAULT_BPM);
+
+    // Beat at which each measure starts (4 beats per measure of length 1.0).
+    let last_measure = objects.iter().map(|o| o.measure).max().unwrap_or(0);
+    let measure_beats = |m: u32| 4.0 * measure_lengths.get(&m).copied().unwrap_or(1.0);
+    let mut measure_start = Vec::with_capacity(last_measure as usize + 2);
+    let mut beat = 0.0;
+    for m in 0..=last_measure + 1 {
+        measure_start.push(beat);
+        beat += measure_beats(m);
+    }
+    let beat_of =
+        |o: &RawObject| measure_start[o.measure as usize] + o.fraction * measure_beats(o.measure);
+
+    // Tempo map
+    let mut events: Vec<(f64, TimingEvent)> = Vec::new();
+    for o in &objects {
+        match &o.channel {
+            b"03" => {
+                if let Ok(bpm) = u32::from_str_radix(&o.value, 16) {
+                    events.push((beat_of(o), TimingEvent::Bpm(bpm as f64)));
+                }
+            }
+            b"08" => {
+                if let Some(&bpm) = bpms.get(&o.value) {
+                    events.push((beat_of(o), TimingEvent::Bpm(bpm)));
+                }
+            }
+            b"09" => {
+                // Stops are expressed in 1/192 of a 4/4 measure.
+                if let Some(&stop) = stops.get(&o.value) {
+                    events.push((beat_of(o), TimingEvent::Stop(stop / 48.0)));
+                }
+            }
+            _ => {}
+        }
+    }
+    // Tempo changes apply before stops placed on the same beat.
+    events.sort_by(|a, b| {
+        a.0.total_cmp(&b.0).then_with(|| {
+            matches!(a.1, TimingEvent::Stop(_)).cmp(&matches!(b.1, TimingEvent::Stop(_)))
+        })
+    });
+
+    let mut segments = vec![Segment {
+        beat: 0.0,
+        ms: 0.0,
+        bpm: initial_bpm,
+    }];
+    let mut bpm_changes = vec![BpmChange {
+        time_ms: 0.0,
+        bpm: initial_bpm,
+    }];
+    for (beat, event) in events {
+        let last = segments.last().expect("segments start non-empty");
+        let ms = last.ms + (beat - last.beat) * 60_000.0 / last.bpm;
+        let segment = match event {
+            TimingEvent::Bpm(bpm) if bpm > 0.0 => Segment { beat, ms, bpm },
+            TimingEvent::Bpm(_) => continue,
+            TimingEvent::Stop(beats) => {
+                bpm_changes.push(BpmChange {
+                    time_ms: ms,
+                    bpm: 0.0,
+                });
+                Segment {
+                    beat,
+                    ms: ms + beats * 60_000.0 / last.bpm,
+                    bpm: last.bpm,
+                }
+            }
+        };
+        bpm_changes.push(BpmChange {
+            time_ms: segment.ms,
+            bpm: segment.bpm,
+        });
+        segments.push(segment);
+    }
+    // Objects on the exact beat of a stop happen before it.
+    let ms_at = |beat: f64| {
+        let idx = segments
+            .partition_point(|s| s.beat < beat)
+            .saturating_sub(1);
+        let s = &segments[idx];
+        s.ms + (beat - s.beat) * 60_000.0 / s.bpm
+    };
+
+    // Layout: 7 keys if the 6th/7th lanes are used, 5 otherwise.
+    let key_count = if objects
+        .iter()
+        .filter(|o| matches!(o.channel[0], b'1' | b'5' | b'D'))
+        .any(|o| matches!(lane_of(&o.channel), Some(Lane::Key(5 | 6))))
+    {
+        7
+    } else {
+        5
+    };
+    let column_of = |lane: Lane| match (lane, scratch) {
+        (Lane::Key(k), BmsScratchMode::Left) => Some(k + 1),
+        (Lane::Key(k), _) => Some(k),
+        (Lane::Scratch, BmsScratchMode::Left) => Some(0),
+        (Lane::Scratch, BmsScratchMode::Right) => Some(key_count),
+        (Lane::Scratch, BmsScratchMode::Drop) => None,
+    };
+    let keysound = |value: &str| wavs.get(value).map(|name| Arc::<str>::from(name.as_str()));
+
+    let mut timed: Vec<(f64, &RawObject)> =
+        objects.iter().map(|o| (ms_at(beat_of(o)), o)).collect();
+    timed.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let lnobj = header.get("LNOBJ").map(|v| v.to_ascii_uppercase());
+    let mut notes: Vec<NoteData> = Vec::new();
+    // Index in `notes` of the last note per column (for #LNOBJ) and open LN starts (channel 5x).
+    let mut last_note: HashMap<usize, usize> = HashMap::new();
+    let mut open_ln: HashMap<usize, (f64, String)> = HashMap::new();
+    let mut bgm_start: Option<(f64, &str)> = None;
+
+    for (time, o) in &timed {
+        let time = *time;
+        if &o.channel == b"01" {
+            if bgm_start.is_none() {
+                bgm_start = Some((time, o.value.as_str()));
+            }
+            continue;
+        }
+
+        let Some(column) = lane_of(&o.channel).and_then(column_of) else {
+            continue;
+        };
+
+        match o.channel[0] {
+            b'1' => {
+                if lnobj.as_deref() == Some(o.value.as_str()) {
+                    if let Some(&idx) = last_note.get(&column) {
+                        let start = &notes[idx];
+                        let hold =
+                            NoteData::hold(start.timestamp_ms, column, time - start.timestamp_ms)
+                                .with_keysound(start.keysound.clone());
+                        notes[idx] = hold;
+                    }
+                    continue;
+                }
+                last_note.insert(column, notes.len());
+                notes.push(NoteData::tap(time, column).with_keysound(keysound(&o.value)));
+            }
+            b'5' => match open_ln.remove(&column) {
+                Some((start, value)) => {
+                    notes.push(
+                        NoteData::hold(start, column, time - start).with_keysound(keysound(&value)),
+                    );
+                }
+                None => {
+                    open_ln.insert(column, (time, o.value.clone()));
+                }
+            },
+            b'D' => notes.push(NoteData::mine(time, column)),
+            _ => {} // Invisible notes (3x) only trigger keysounds.
+        }
+    }
+
+    if notes.is_empty() {
+        return Err(format!("No playable notes found in {:?}", path));
+    }
+
+    // Song audio starts with the first BGM sample: make that time 0.
+    let (offset_ms, audio_path) = match bgm_start {
+        Some((time, value)) => (
+            time,
+            wavs.get(value)
+                .and_then(|name| resolve_sample(folder, name)),
+        ),
+        None => (0.0, None),
+    };
+    for note in &mut notes {
+        note.timestamp_ms -= offset_ms;
+    }
+    for change in &mut bpm_changes {
+        change.time_ms -= offset_ms;
+    }
+    notes.sort_by(|a, b| a.timestamp_ms.total_cmp(&b.timestamp_ms));
+
+    let non_empty = |key: &str| header.get(key).filter(|v| !v.is_empty()).cloned();
+    Ok(BmsChart {
+        title: non_empty("TITLE").unwrap_or_else(|| {
+            path.file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default()
+                .to_string()
+        }),
+        subtitle: non_empty("SUBTITLE"),
+        artist: non_empty("ARTIST").unwrap_or_default(),
+        play_level: header.get("PLAYLEVEL").and_then(|v| v.parse().ok()),
+        difficulty: header.get("DIFFICULTY").and_then(|v| v.parse().ok()),
+        stage_file: non_empty("STAGEFILE"),
+        audio_path,
+        key_count,
+        notes,
+        timing: TimingData {
+            bpm_changes,
+            sv_changes: Vec::new(),
+        },
+    })
+}
+
+/// Splits `mmmcc:data` into (measure, channel, data).
+fn parse_channel_line(body: &str) -> Option<(u32, [u8; 2], &str)> {
+    let (head, data) = body.split_once(':')?;
+    let head = head.as_bytes();
+    if head.len() != 5 || !head[..3].iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let measure = std::str::from_utf8(&head[..3]).ok()?.parse().ok()?;
+    let channel = [head[3].to_ascii_uppercase(), head[4].to_ascii_uppercase()];
+    Some((measure, channel, data.trim()))
+}
+
+/// Finds a sample on disk, trying the usual audio extensions if the exact
+/// file is missing.
+fn resolve_sample(folder: &Path, name: &str) -> Option<PathBuf> {
+    let exact = folder.join(name);
+    if exact.exists() {
+        return Some(exact);
+    }
+    SAMPLE_EXTENSIONS
+        .iter()
+        .map(|ext| exact.with_extension(ext))
+        .find(|candidate| candidate.exists())
+}
@@ -0,0 +1,311 @@
+//! Malody `.mc` import (key mode only).
+//!
+//! `.mc` files are JSON. Positions are beats written as `[beat, numerator,
+//! denominator]`; the `time` list holds the BPM changes and `effect` the scroll
+//! speed (SV) changes. The song audio is the
+//! note carrying a `sound` field (type 1); its `offset` (ms) is subtracted from
+//! every beat time: audio time = beat time - offset.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::models::engine::NoteData;
+use crate::models::engine::timing::{BpmChange, SvChange, TimingData};
+
+/// `meta.mode` value of key-mode charts.
+const MODE_KEY: u32 = 0;
+
+#[derive(Deserialize)]
+struct McFile {
+    meta: McMeta,
+    #[serde(default)]
+    time: Vec<McTime>,
+    #[serde(default)]
+    effect: Vec<McEffect>,
+    #[serde(default)]
+    note: Vec<McNote>,
+}
+
+#[derive(Deserialize)]
+struct McMeta {
+    #[serde(default)]
+    creator: String,
+    #[serde(default)]
+    background: String,
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    mode: u32,
+    #[serde(default)]
+    song: McSong,
+    mode_ext: Option<McModeExt>,
+}
+
+#[derive(Deserialize, Default)]
+struct McSong {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    artist: String,
+    /// Title in its original script.
+    #[serde(default)]
+    titleorg: String,
+    #[serde(default)]
+    artistorg: String,
+}
+
+#[derive(Deserialize)]
+struct McModeExt {
+    column: usize,
+}
+
+#[derive(Deserialize)]
+struct McTime {
+    beat: [u32; 3],
+    bpm: f64,
+}
+
+#[derive(Deserialize)]
+struct McEffect {
+    beat: [u32; 3],
+    scroll: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct McNote {
+    beat: [u32; 3],
+    endbeat: Option<[u32; 3]>,
+    column: Option<usize>,
+    sound: Option<String>,
+    #[serde(default)]
+    offset: f64,
+}
+
+/// A Malody chart converted to the internal model.
+#[derive(Debug, Clone)]
+pub struct MalodyChart {
+    pub title: String,
+    pub artist: String,
+    /// Original script title (`song.titleorg`), if the chart has one.
+    pub title_unicode: Option<String>,
+    pub artist_unicode: Option<String>,
+    pub creator: String,
+    /// Difficulty name (`meta.version`).
+    pub version: String,
+    /// Background image, relative to the chart folder.
+    pub background: Option<String>,
+    /// Song audio referenced by the sound note.
+    pub audio_path: PathBuf,
+    pub key_count: usize,
+    /// Notes sorted by time, on the audio timeline.
+    pub notes: Vec<NoteData>,
+    /// Tempo changes on the same timeline as `notes`.
+    pub timing: TimingData,
+}
+
+impl MalodyChart {
+    /// Difficulty label shown in song select, e.g. "4K Hard".
+    pub fn difficulty_name(&self) -> String {
+        if self.version.is_empty() {
+            format!("{}K", self.key_count)
+        } else {
+            self.version.clone()
+        }
+    }
+}
+
+/// Loads a Malody chart for gameplay: returns the audio path, the notes and the tempo changes.
+pub fn load_map(path: &Path) -> Result<(PathBuf, Vec<NoteData>, TimingData), String> {
+    let chart = parse_mc(path)?;
+    Ok((chart.audio_path, chart.notes, chart.timing))
+}
+
+/// Converts `[beat, numerator, denominator]` to a fractional beat.
+fn beat_of(beat: [u32; 3]) -> f64 {
+    let [whole, num, den] = beat;
+    if den == 0 {
+        whole as f64
+    } else {
+        whole as f64 + num as f64 / den as f64
+    }
+}
+
+/// Parses a `.mc` file and converts it to notes.
+pub fn parse_mc(path: &Path) -> Result<MalodyChart, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    let file: McFile = serde_json::from_str(&text)
+        .map_err(|e| format!("Invalid Malody chart {:?}: {}", path, e))?;
+    let folder = path
+        .parent()
+        .ok_or_else(|| format!("Invalid path (no parent): {:?}", path))?;
+
+    if file.meta.mode != MODE_KEY {
+        return Err(format!(
+            "Malody chart {:?} is not a key-mode chart (mode {})",
+            path, file.meta.mode
+        ));
+    }
+    let key_count = file
+        .meta
+        .mode_ext
+        .as_ref()
+        .map(|ext| ext.column)
+        .filter(|&c| c > 0)
+        .ok_or_else(|| format!("Malody chart {:?} has no column count", path))?;
+
+    // Tempo sections as (beat, ms, bpm).
+    let mut times: Vec<&McTime> = file.time.iter().filter(|t| t.bpm > 0.0).collect();
+    times.sort_by(|a, b| beat_of(a.beat).total_cmp(&beat_of(b.beat)));
+    let first_bpm = times
+        .first()
+        .map(|t| t.bpm)
+        .ok_or_else(|| format!("Malody chart {:?} has no BPM", path))?;
+    let mut segments: Vec<(f64, f64, f64)> = vec![(0.0, 0.0, first_bpm)];
+    for t in times {
+        let beat = beat_of(t.beat);
+        let &(last_beat, last_ms, last_bpm) = segments.last().expect("segments start non-empty");
+        if beat <= last_beat {
+            // Change on the same beat (usually the first one at beat 0).
+            if let Some(last) = segments.last_mut() {
+                last.2 = t.bpm;
+            }
+            continue;
+        }
+        segments.push((
+            beat,
+            last_ms + (beat - last_beat) * 60_000.0 / last_bpm,
+            t.bpm,
+        ));
+    }
+    let ms_at = |beat: f64| {
+        let idx = segments.partition_point(|s| s.0 <= beat).saturating_sub(1);
+        let (seg_beat, seg_ms, bpm) = segments[idx];
+        seg_ms + (beat - seg_beat) * 60_000.0 / bpm
+    };
+
+    let sound = file
+        .note
+        .iter()
+        .find(|n| n.sound.as_deref().is_some_and(|s| !s.is_empty()))
+        .ok_or_else(|| format!("Malody chart {:?} has no song audio", path))?;
+    let audio_path = folder.join(sound.sound.as_deref().unwrap_or_default());
+    let offset_ms = ms_at(beat_of(sound.beat)) + sound.offset;
+
+    let mut notes: Vec<NoteData> = file
+        .note
+        .iter()
+        .filter(|n| n.sound.is_none())
+        .filter_map(|n| {
+            let column = n.column.filter(|&c| c < key_count)?;
+            let start = ms_at(beat_of(n.beat)) - offset_ms;
+            Some(match n.endbeat {
+                Some(end) => {
+                    let end = ms_at(beat_of(end)) - offset_ms;
+                    NoteData::hold(start, column, (end - start).max(0.0))
+                }
+                None => NoteData::tap(start, column),
+            })
+        })
+        .collect();
+    if notes.is_empty() {
+        return Err(format!("No playable notes found in {:?}", path));
+    }
+    notes.sort_by(|a, b| a.timestamp_ms.total_cmp(&b.timestamp_ms));
+
+    let bpm_changes = segments
+        .iter()
+        .map(|&(_, ms, bpm)| BpmChange {
+            time_ms: ms - offset_ms,
+            bpm,
+        })
+        .collect();
+    let mut sv_changes: Vec<SvChange> = file
+        .effect
+        .iter()
+        .filter_map(|e| {
+            Some(SvChange {
+                time_ms: ms_at(beat_of(e.beat)) - offset_ms,
+                multiplier: e.scroll?,
+            })
+        })
+        .collect();
+    sv_changes.sort_by(|a, b| a.time_ms.total_cmp(&b.time_ms));
+
+    let meta = file.meta;
+    Ok(MalodyChart {
+        title: if meta.song.title.is_empty() {
+            path.file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default()
+                .to_string()
+        } else {
+            meta.song.title
+        },
+        artist: meta.song.artist,
+        title_unicode: (!meta.song.titleorg.is_empty()).then_some(meta.song.titleorg),
+        artist_unicode: (!meta.song.artistorg.is_empty()).then_some(meta.song.artistorg),
+        creator: meta.creator,
+        version: meta.version,
+        background: (!meta.background.is_empty()).then_some(meta.background),
+        audio_path,
+        key_count,
+        notes,
+        timing: TimingData {
+            bpm_changes,
+            sv_changes,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notes_follow_bpm_changes_and_the_song_offset() {
+        let path = std::env::temp_dir().join("rvsrg_malody_test.mc");
+        fs::write(
+            &path,
+            r#"{
+                "meta": {"version": "4K Hard", "mode": 0, "mode_ext": {"column": 4},
+                         "song": {"title": "Test", "artist": "Someone"}},
+                "time": [{"beat": [4, 0, 1], "bpm": 240}, {"beat": [0, 0, 1], "bpm": 120}],
+                "effect": [{"beat": [4, 0, 1], "scroll": 2.0}],
+                "note": [
+                    {"beat": [2, 0, 1], "column": 0},
+                    {"beat": [4, 1, 2], "column": 1},
+                    {"beat": [5, 0, 1], "endbeat": [6, 0, 1], "column": 2},
+                    {"beat": [3, 0, 1], "column": 7},
+                    {"beat": [0, 0, 1], "sound": "song.ogg", "offset": 50, "type": 1}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let chart = parse_mc(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(chart.key_count, 4);
+        assert_eq!(chart.difficulty_name(), "4K Hard");
+        assert_eq!(chart.audio_path.file_name().unwrap(), "song.ogg");
+        // 500 ms per beat up to beat 4, 250 ms after; 50 ms of song offset.
+        let times: Vec<(f64, usize)> = chart
+            .notes
+            .iter()
+            .map(|n| (n.timestamp_ms, n.column))
+            .collect();
+        assert_eq!(times, vec![(950.0, 0), (2075.0, 1), (2200.0, 2)]);
+        assert_eq!(chart.notes[2].hold_duration_ms(), 250.0);
+
+        let bpms: Vec<(f64, f64)> = chart
+            .timing
+            .bpm_changes
+            .iter()
+            .map(|c| (c.time_ms, c.bpm))
+            .collect();
+        assert_eq!(bpms, vec![(-50.0, 120.0), (1950.0, 240.0)]);
+        assert_eq!(chart.timing.sv_changes[0].time_ms, 1950.0);
+        assert_eq!(chart.timing.sv_changes[0].multiplier, 2.0);
+    }
+}
@@ -0,0 +1,43 @@
+//! Chart file formats understood by the scanner and the chart loader.
+//!
+//! osu!mania charts are parsed through `rosu_map` (see `models::engine::note`);
+//! this module holds the converters for the other formats.
+
+pub mod bms;
+pub mod malody;
+pub mod sm;
+
+use std::path::Path;
+
+/// Chart file format, detected from the file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartFormat {
+    /// osu!mania `.osu`.
+    Osu,
+    /// Be-Music Source `.bms` / `.bme` / `.bml` (experimental).
+    Bms,
+    /// Malody key-mode `.mc`.
+    Malody,
+    /// StepMania / Etterna `.sm`, one beatmap per chart (`song.sm#<index>`).
+    Sm,
+}
+
+impl ChartFormat {
+    /// Detects the format of a chart file, `None` if it is not a chart.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        // `.sm` charts are stored with their index after the extension.
+        match ext.split('#').next().unwrap_or_default() {
+            "osu" => Some(Self::Osu),
+            "bms" | "bme" | "bml" => Some(Self::Bms),
+            "mc" => Some(Self::Malody),
+            "sm" => Some(Self::Sm),
+            _ => None,
+        }
+    }
+
+    /// Whether support for this format is still experimental.
+    pub fn is_experimental(self) -> bool {
+        matches!(self, Self::Bms)
+    }
+}
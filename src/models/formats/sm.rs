@@ -0,0 +1,382 @@
+//! StepMania / Etterna `.sm` import.
+//!
+//! A `.sm` file holds the song metadata, the tempo (`#BPMS`, `#STOPS`) and
+//! one `#NOTES` block per chart. Each chart is imported as its own beatmap,
+//! stored under the path `song.sm#<index>` (see [`chart_path`]). Rolls are
+//! read as holds, lifts as taps, `M` as mines and `F` as fake notes.
+//!
+//! `#OFFSET` (seconds) is the time of beat 0 subtracted from the audio time:
+//! audio time = beat time - offset.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::models::engine::NoteData;
+use crate::models::engine::timing::{BpmChange, TimingData};
+
+/// A `.sm` file converted to the internal model.
+#[derive(Debug, Clone)]
+pub struct SmFile {
+    /// Romanized title (`#TITLETRANSLIT` when set, else `#TITLE`).
+    pub title: String,
+    pub artist: String,
+    /// `#TITLE` when a `#TITLETRANSLIT` replaces it in `title`.
+    pub title_unicode: Option<String>,
+    /// `#ARTIST` when a `#ARTISTTRANSLIT` replaces it in `artist`.
+    pub artist_unicode: Option<String>,
+    /// `#CREDIT`, or the author of the first chart.
+    pub creator: String,
+    /// Background image, relative to the song folder.
+    pub background: Option<String>,
+    /// Song audio (`#MUSIC`).
+    pub audio_path: PathBuf,
+    /// Tempo changes shared by every chart; stops are 0 BPM sections.
+    pub timing: TimingData,
+    /// Charts with a supported steps type, in file order.
+    pub charts: Vec<SmChart>,
+}
+
+/// One `#NOTES` block.
+#[derive(Debug, Clone)]
+pub struct SmChart {
+    /// Difficulty slot, e.g. "Challenge".
+    pub difficulty: String,
+    pub meter: u32,
+    pub key_count: usize,
+    /// Notes sorted by time, on the audio timeline.
+    pub notes: Vec<NoteData>,
+}
+
+impl SmChart {
+    /// Difficulty label shown in song select, e.g. "Challenge 12".
+    pub fn difficulty_name(&self) -> String {
+        if self.difficulty.is_empty() {
+            format!("{}K {}", self.key_count, self.meter)
+        } else {
+            format!("{} {}", self.difficulty, self.meter)
+        }
+    }
+}
+
+/// Path stored for the `index`-th chart of a `.sm` file: `song.sm#2`.
+pub fn chart_path(file: &Path, index: usize) -> PathBuf {
+    let mut path = file.as_os_str().to_os_string();
+    path.push(format!("#{}", index));
+    PathBuf::from(path)
+}
+
+/// Splits a path built by [`chart_path`] into the `.sm` file and the chart
+/// index. A plain `.sm` path is its first chart.
+pub fn split_chart_path(path: &Path) -> (PathBuf, usize) {
+    if let Some((file, index)) = path.to_str().and_then(|s| s.rsplit_once('#'))
+        && let Ok(index) = index.parse()
+    {
+        return (PathBuf::from(file), index);
+    }
+    (path.to_path_buf(), 0)
+}
+
+/// Loads one chart for gameplay: returns the audio path, the notes and the tempo changes.
+pub fn load_map(path: &Path) -> Result<(PathBuf, Vec<NoteData>, TimingData), String> {
+    let (file, index) = split_chart_path(path);
+    let sm = parse_sm(&file)?;
+    let chart = sm
+        .charts
+        .into_iter()
+        .nth(index)
+        .ok_or_else(|| format!("{:?} has no chart #{}", file, index))?;
+    Ok((sm.audio_path, chart.notes, sm.timing))
+}
+
+/// Key count of a `#NOTES` steps type, `None` for unsupported ones.
+fn key_count_of(steps_type: &str) -> Option<usize> {
+    match steps_type.trim().to_ascii_lowercase().as_str() {
+        "dance-threepanel" => Some(3),
+        "dance-single" => Some(4),
+        "pump-single" => Some(5),
+        "dance-solo" | "pump-halfdouble" => Some(6),
+        "kb7-single" => Some(7),
+        "dance-double" | "dance-couple" => Some(8),
+        "pump-double" => Some(10),
+        _ => None,
+    }
+}
+
+/// `#KEY:VALUE;` tags of the file, keys upper-cased, comments removed.
+fn tags(text: &str) -> Vec<(String, String)> {
+    let clean = text
+        .lines()
+        .map(|line| line.split("//").next().unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut tags = Vec::new();
+    let mut rest = clean.as_str();
+    while let Some(start) = rest.find('#') {
+        rest = &rest[start + 1..];
+        let end = rest.find(';').unwrap_or(rest.len());
+        if let Some((key, value)) = rest[..end].split_once(':') {
+            tags.push((key.trim().to_ascii_uppercase(), value.to_string()));
+        }
+        rest = &rest[(end + 1).min(rest.len())..];
+    }
+    tags
+}
+
+/// Parses a `beat=value,beat=value` list.
+fn beat_values(value: &str) -> Vec<(f64, f64)> {
+    value
+        .split(',')
+        .filter_map(|pair| {
+            let (beat, value) = pair.split_once('=')?;
+            Some((beat.trim().parse().ok()?, value.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+/// Tempo change or stop, positioned in beats from the start of the chart.
+enum TimingEvent {
+    Bpm(f64),
+    /// Stop length in seconds.
+    Stop(f64),
+}
+
+/// Tempo section used to convert beats to milliseconds.
+struct Segment {
+    beat: f64,
+    ms: f64,
+    bpm: f64,
+}
+
+/// Parses a `.sm` file and converts every supported chart.
+pub fn parse_sm(path: &Path) -> Result<SmFile, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    let text = String::from_utf8_lossy(&bytes);
+    let folder = path
+        .parent()
+        .ok_or_else(|| format!("Invalid path (no parent): {:?}", path))?;
+
+    let tags = tags(&text);
+    let tag = |key: &str| {
+        tags.iter()
+            .find(|(k, v)| k == key && !v.trim().is_empty())
+            .map(|(_, v)| v.trim().to_string())
+    };
+
+    let music = tag("MUSIC").ok_or_else(|| format!("{:?} has no #MUSIC", path))?;
+    let offset_ms = tag("OFFSET")
+        .and_then(|v| v.parse::<f64>().ok())
+        .unwrap_or(0.0)
+        * 1000.0;
+
+    let bpms: Vec<(f64, f64)> = tag("BPMS")
+        .map(|v| beat_values(&v))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|&(_, bpm)| bpm > 0.0)
+        .collect();
+    let initial_bpm = bpms
+        .first()
+        .map(|&(_, bpm)| bpm)
+        .ok_or_else(|| format!("{:?} has no BPM", path))?;
+    let stops = tag("STOPS").map(|v| beat_values(&v)).unwrap_or_default();
+    let mut events: Vec<(f64, TimingEvent)> = bpms
+        .into_iter()
+        .map(|(beat, bpm)| (beat, TimingEvent::Bpm(bpm)))
+        .chain(
+            stops
+                .into_iter()
+                .filter(|&(_, seconds)| seconds > 0.0)
+                .map(|(beat, seconds)| (beat, TimingEvent::Stop(seconds))),
+        )
+        .collect();
+    // Tempo changes apply before stops placed on the same beat.
+    events.sort_by(|a, b| {
+        a.0.total_cmp(&b.0).then_with(|| {
+            matches!(a.1, TimingEvent::Stop(_)).cmp(&matches!(b.1, TimingEvent::Stop(_)))
+        })
+    });
+
+    let mut segments = vec![Segment {
+        beat: 0.0,
+        ms: -offset_ms,
+        bpm: initial_bpm,
+    }];
+    let mut bpm_changes = vec![BpmChange {
+        time_ms: -offset_ms,
+        bpm: initial_bpm,
+    }];
+    for (beat, event) in events {
+        let last = segments.last().expect("segments start non-empty");
+        let beat = beat.max(last.beat);
+        let ms = last.ms + (beat - last.beat) * 60_000.0 / last.bpm;
+        let segment = match event {
+            TimingEvent::Bpm(bpm) => Segment { beat, ms, bpm },
+            TimingEvent::Stop(seconds) => {
+                bpm_changes.push(BpmChange {
+                    time_ms: ms,
+                    bpm: 0.0,
+                });
+                Segment {
+                    beat,
+                    ms: ms + seconds * 1000.0,
+                    bpm: last.bpm,
+                }
+            }
+        };
+        bpm_changes.push(BpmChange {
+            time_ms: segment.ms,
+            bpm: segment.bpm,
+        });
+        segments.push(segment);
+    }
+    // Notes on the exact beat of a stop happen before it.
+    let ms_at = |beat: f64| {
+        let idx = segments
+            .partition_point(|s| s.beat < beat)
+            .saturating_sub(1);
+        let s = &segments[idx];
+        s.ms + (beat - s.beat) * 60_000.0 / s.bpm
+    };
+
+    let mut charts = Vec::new();
+    let mut first_author = None;
+    for (_, value) in tags.iter().filter(|(k, _)| k == "NOTES") {
+        let fields: Vec<&str> = value.splitn(6, ':').collect();
+        let [steps_type, author, difficulty, meter, _radar, data] = fields[..] else {
+            continue;
+        };
+        let Some(key_count) = key_count_of(steps_type) else {
+            continue;
+        };
+        if first_author.is_none() && !author.trim().is_empty() {
+            first_author = Some(author.trim().to_string());
+        }
+
+        let notes = parse_notes(data, key_count, &ms_at);
+        if notes.is_empty() {
+            continue;
+        }
+        charts.push(SmChart {
+            difficulty: difficulty.trim().to_string(),
+            meter: meter.trim().parse().unwrap_or(0),
+            key_count,
+            notes,
+        });
+    }
+    if charts.is_empty() {
+        return Err(format!("No playable chart found in {:?}", path));
+    }
+
+    let title = tag("TITLE").unwrap_or_else(|| {
+        path.file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string()
+    });
+    let artist = tag("ARTIST").unwrap_or_default();
+    // A transliteration is the romanized name; #TITLE / #ARTIST then keep
+    // the original script.
+    let (title, title_unicode) = match tag("TITLETRANSLIT") {
+        Some(translit) => (translit, Some(title)),
+        None => (title, None),
+    };
+    let (artist, artist_unicode) = match tag("ARTISTTRANSLIT") {
+        Some(translit) => (translit, Some(artist)),
+        None => (artist, None),
+    };
+
+    Ok(SmFile {
+        title,
+        artist,
+        title_unicode,
+        artist_unicode,
+        creator: tag("CREDIT").or(first_author).unwrap_or_default(),
+        background: tag("BACKGROUND"),
+        audio_path: folder.join(music),
+        timing: TimingData {
+            bpm_changes,
+            sv_changes: Vec::new(),
+        },
+        charts,
+    })
+}
+
+/// Converts the measures of a `#NOTES` block. Each measure is split evenly
+/// into its rows; holds left open at the end of the chart become taps.
+fn parse_notes(data: &str, key_count: usize, ms_at: &impl Fn(f64) -> f64) -> Vec<NoteData> {
+    let mut notes = Vec::new();
+    let mut open_holds: Vec<Option<f64>> = vec![None; key_count];
+
+    for (measure, rows) in data.split(',').enumerate() {
+        let rows: Vec<&str> = rows
+            .lines()
+            .map(str::trim)
+            .filter(|row| !row.is_empty())
+            .collect();
+        for (r, row) in rows.iter().enumerate() {
+            let beat = measure as f64 * 4.0 + 4.0 * r as f64 / rows.len() as f64;
+            let time = ms_at(beat);
+            for (column, c) in row.chars().take(key_count).enumerate() {
+                match c {
+                    '1' | 'L' | 'l' => notes.push(NoteData::tap(time, column)),
+                    '2' | '4' => open_holds[column] = Some(time),
+                    '3' => {
+                        if let Some(start) = open_holds[column].take() {
+                            notes.push(NoteData::hold(start, column, (time - start).max(0.0)));
+                        }
+                    }
+                    'M' | 'm' => notes.push(NoteData::mine(time, column)),
+                    'F' | 'f' => notes.push(NoteData::tap(time, column).into_fake()),
+                    _ => {}
+                }
+            }
+        }
+    }
+    for (column, start) in open_holds.into_iter().enumerate() {
+        if let Some(start) = start {
+            notes.push(NoteData::tap(start, column));
+        }
+    }
+
+    notes.sort_by(|a, b| a.timestamp_ms.total_cmp(&b.timestamp_ms));
+    notes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chart_paths_round_trip() {
+        let file = Path::new("songs/Pack/Song/song.sm");
+        assert_eq!(
+            split_chart_path(&chart_path(file, 3)),
+            (file.to_path_buf(), 3)
+        );
+        assert_eq!(split_chart_path(file), (file.to_path_buf(), 0));
+    }
+
+    #[test]
+    fn holds_and_stops_are_timed_from_the_offset() {
+        let path = std::env::temp_dir().join("rvsrg_sm_test.sm");
+        fs::write(
+            &path,
+            "#TITLE:Test;\n#MUSIC:song.ogg;\n#OFFSET:-0.5;\n#BPMS:0.000=120.000;\n\
+             #STOPS:1.000=0.250;\n#NOTES:\n dance-single:\n:\n Hard:\n 9:\n 0,0,0,0,0:\n\
+             1000\n0100\n2000\n3000\n;\n",
+        )
+        .unwrap();
+        let file = parse_sm(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        let chart = &file.charts[0];
+        assert_eq!(chart.difficulty_name(), "Hard 9");
+        assert_eq!(chart.notes.len(), 3);
+        assert_eq!(chart.notes[0].timestamp_ms, 500.0);
+        // Beat 1 is the stop's beat: the note comes before the pause.
+        assert_eq!(chart.notes[1].timestamp_ms, 1000.0);
+        assert_eq!(chart.notes[2].timestamp_ms, 1750.0);
+        assert_eq!(chart.notes[2].hold_duration_ms(), 500.0);
+    }
+}
@@ -0,0 +1,105 @@
+//! Replay ghost: a previous play raced against during gameplay.
+//!
+//! The ghost replay is simulated once against the chart, then its judgements
+//! are replayed in time order as the audio clock advances, so the running
+//! score, accuracy and combo of the ghost are known at any point of the map.
+
+use crate::models::engine::NoteData;
+use crate::models::judge::Tally;
+use crate::models::replay::{ReplayData, simulate_replay};
+use crate::models::stats::Judgement;
+
+/// Replay picked on the leaderboard to race against.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GhostChoice {
+    pub beatmap_hash: String,
+    pub replay_data: ReplayData,
+    /// Final accuracy of the replay, for the song select label.
+    pub accuracy: f64,
+}
+
+/// Replays picked on the leaderboard for a versus race on split playfields.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersusChoice {
+    pub beatmap_hash: String,
+    /// Replay on the right playfield.
+    pub opponent: ReplayData,
+    /// Replay on the left playfield; `None` when the player plays live.
+    pub player: Option<ReplayData>,
+}
+
+/// Live comparison with the ghost, for the HUD.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GhostDelta {
+    /// Player score minus ghost score.
+    pub score: i64,
+    /// Player accuracy minus ghost accuracy, in percent.
+    pub accuracy: f64,
+    /// Player combo minus ghost combo.
+    pub combo: i64,
+}
+
+/// Ghost state, advanced tick by tick with the audio clock.
+#[derive(Debug, Clone)]
+pub struct GhostRun {
+    /// `(time, judgement)` sorted by the time the judgement happened.
+    events: Vec<(f64, Judgement)>,
+    cursor: usize,
+    /// Ghost score, combo and stats at the cursor.
+    pub tally: Tally,
+}
+
+impl GhostRun {
+    /// Simulates `replay` on `chart` with the hit window it was played with.
+    pub fn new(replay: &ReplayData, chart: &[NoteData]) -> Self {
+        let hit_window = replay.build_hit_window();
+        let result = simulate_replay(replay, chart, &hit_window);
+
+        let mut events: Vec<(f64, Judgement)> = result
+            .hit_timings
+            .iter()
+            .map(|t| {
+                // Misses happen when the window closes, hits at the input time.
+                let time = if t.judgement == Judgement::Miss {
+                    t.note_timestamp_ms + hit_window.miss_ms
+                } else {
+                    t.note_timestamp_ms - t.timing_ms
+                };
+                (time, t.judgement)
+            })
+            .collect();
+        events.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        Self {
+            events,
+            cursor: 0,
+            tally: Tally::new(),
+        }
+    }
+
+    /// Applies every ghost judgement up to `time_ms`. Seeking backwards
+    /// (practice retry) replays the ghost from the start.
+    pub fn advance(&mut self, time_ms: f64) {
+        if self.cursor > 0 && self.events[self.cursor - 1].0 > time_ms {
+            self.cursor = 0;
+            self.tally = Tally::new();
+        }
+        while let Some(&(time, judgement)) = self.events.get(self.cursor) {
+            if time > time_ms {
+                break;
+            }
+            self.tally.apply(judgement);
+            self.cursor += 1;
+        }
+    }
+
+    /// Compares a live run against the ghost at the current cursor.
+    pub fn delta(&self, player: &Tally) -> GhostDelta {
+        GhostDelta {
+            score: player.score as i64 - self.tally.score as i64,
+            accuracy: player.hit_stats.calculate_accuracy()
+                - self.tally.hit_stats.calculate_accuracy(),
+            combo: player.combo as i64 - self.tally.combo as i64,
+        }
+    }
+}
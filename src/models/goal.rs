@@ -0,0 +1,110 @@
+//! User-defined goals ("dans") tracked against played charts.
+
+/// A goal as entered in the menu, before it is persisted.
+///
+/// A single chart makes a plain goal; several charts make a course-style
+/// goal, completed by a course run playing them back to back, in order,
+/// each meeting the target.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalDraft {
+    pub name: String,
+    pub target_accuracy: Option<f64>,
+    pub target_score: Option<i64>,
+    pub min_rate: f64,
+    pub beatmap_hashes: Vec<String>,
+}
+
+impl GoalDraft {
+    pub fn new() -> Self {
+        Self {
+            name: String::new(),
+            target_accuracy: Some(96.0),
+            target_score: None,
+            min_rate: 1.0,
+            beatmap_hashes: Vec::new(),
+        }
+    }
+
+    /// A goal needs at least one chart and one target to be trackable.
+    pub fn is_valid(&self) -> bool {
+        !self.beatmap_hashes.is_empty()
+            && (self.target_accuracy.is_some() || self.target_score.is_some())
+    }
+}
+
+impl Default for GoalDraft {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A stage of a finished course run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CourseStagePlay {
+    pub beatmap_hash: String,
+    pub accuracy: f64,
+    pub score: i64,
+    /// The stage was played to the end with HP left.
+    pub passed: bool,
+}
+
+/// Whether `stages` play `charts` back to back, in order, each passed and
+/// meeting the targets.
+pub fn run_meets_goal(
+    stages: &[CourseStagePlay],
+    charts: &[String],
+    target_accuracy: Option<f64>,
+    target_score: Option<i64>,
+) -> bool {
+    let meets = |stage: &CourseStagePlay, hash: &String| {
+        stage.passed
+            && stage.beatmap_hash == *hash
+            && target_accuracy.is_none_or(|target| stage.accuracy >= target)
+            && target_score.is_none_or(|target| stage.score >= target)
+    };
+    !charts.is_empty()
+        && stages.windows(charts.len()).any(|window| {
+            window
+                .iter()
+                .zip(charts)
+                .all(|(stage, hash)| meets(stage, hash))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stage(hash: &str, accuracy: f64, passed: bool) -> CourseStagePlay {
+        CourseStagePlay {
+            beatmap_hash: hash.to_string(),
+            accuracy,
+            score: 0,
+            passed,
+        }
+    }
+
+    #[test]
+    fn course_goals_need_the_charts_back_to_back() {
+        let charts = vec!["a".to_string(), "b".to_string()];
+        let run = [
+            stage("x", 99.0, true),
+            stage("a", 97.0, true),
+            stage("b", 96.5, true),
+        ];
+        assert!(run_meets_goal(&run, &charts, Some(96.0), None));
+        assert!(!run_meets_goal(&run, &charts, Some(97.0), None));
+
+        // Out of order, split by another chart, or failed.
+        let reversed = [stage("b", 99.0, true), stage("a", 99.0, true)];
+        assert!(!run_meets_goal(&reversed, &charts, Some(96.0), None));
+        let split = [
+            stage("a", 99.0, true),
+            stage("x", 99.0, true),
+            stage("b", 99.0, true),
+        ];
+        assert!(!run_meets_goal(&split, &charts, Some(96.0), None));
+        let failed = [stage("a", 99.0, true), stage("b", 99.0, false)];
+        assert!(!run_meets_goal(&failed, &charts, Some(96.0), None));
+    }
+}
@@ -0,0 +1,481 @@
+//! Judging core: which note a press hits, and how judgements add up.
+//!
+//! Pure logic with no rendering, audio or file access. The game engine, the
+//! replay simulation and the replay ghost all judge through this module, so
+//! tools and tests calling [`simulate`] get the exact in-game results.
+
+use crate::models::engine::{HitWindow, NoteData, NoteType};
+use crate::models::replay::{GhostTap, HitTiming, ReplayInput, ReplayResult};
+use crate::models::stats::{HitStats, Judgement};
+
+/// Score points awarded for a judgement.
+pub fn points(judgement: Judgement) -> u32 {
+    match judgement {
+        Judgement::Marv | Judgement::Perfect => 300,
+        Judgement::Great => 200,
+        Judgement::Good => 100,
+        Judgement::Bad => 50,
+        Judgement::Miss | Judgement::GhostTap => 0,
+    }
+}
+
+/// Note hit by a press on `column` at `time_ms`: the closest note of that
+/// column within the miss window, among the notes from `start` on for which
+/// `is_open` holds.
+pub fn find_note(
+    chart: &[NoteData],
+    start: usize,
+    column: usize,
+    time_ms: f64,
+    hit_window: &HitWindow,
+    is_open: impl Fn(usize) -> bool,
+) -> Option<usize> {
+    let search_limit = time_ms + hit_window.miss_ms;
+    let mut best: Option<(usize, f64)> = None;
+
+    for (i, note) in chart.iter().enumerate().skip(start) {
+        if note.timestamp_ms > search_limit {
+            break;
+        }
+        if note.column == column && is_open(i) {
+            let diff = (note.timestamp_ms - time_ms).abs();
+            if diff <= hit_window.miss_ms && best.is_none_or(|(_, best_diff)| diff < best_diff) {
+                best = Some((i, diff));
+            }
+        }
+    }
+    best.map(|(i, _)| i)
+}
+
+/// What a press did to the note it reached.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Press {
+    /// A tap, or the hit completing a burst: counted now.
+    Hit(Judgement),
+    /// A hold was grabbed. It is counted when it ends: this is the timing of
+    /// the press, only shown.
+    HoldStart(Judgement),
+    /// A mine went off, counted as a miss.
+    Mine,
+    /// A burst hit short of the required count.
+    BurstStep,
+}
+
+/// Applies a press at `time_ms` to `note`, the one [`find_note`] picked.
+pub fn press(note: &mut NoteData, time_ms: f64, hit_window: &HitWindow) -> Press {
+    let (judgement, _) = hit_window.judge(note.timestamp_ms - time_ms);
+    match &mut note.note_type {
+        NoteType::Tap => {
+            note.hit = true;
+            Press::Hit(judgement)
+        }
+        NoteType::Hold {
+            start_time,
+            is_held,
+            ..
+        } => {
+            *start_time = Some(time_ms);
+            *is_held = true;
+            Press::HoldStart(judgement)
+        }
+        NoteType::Mine => {
+            note.hit = true;
+            Press::Mine
+        }
+        NoteType::Burst {
+            current_hits,
+            required_hits,
+            ..
+        } => {
+            *current_hits = current_hits.saturating_add(1);
+            if *current_hits >= *required_hits {
+                note.hit = true;
+                Press::Hit(judgement)
+            } else {
+                Press::BurstStep
+            }
+        }
+    }
+}
+
+/// Ends the hold held on `column`, among the notes from `start` on, by a
+/// release at `time_ms`. Returns the note and its judgement, given by the
+/// share of the hold that was held.
+pub fn release(
+    chart: &mut [NoteData],
+    start: usize,
+    column: usize,
+    time_ms: f64,
+) -> Option<(usize, Judgement)> {
+    for (i, note) in chart.iter_mut().enumerate().skip(start) {
+        if note.column != column || note.hit {
+            continue;
+        }
+        if let NoteType::Hold {
+            duration_ms,
+            start_time: Some(held_from),
+            is_held,
+        } = &mut note.note_type
+        {
+            if !*is_held {
+                continue;
+            }
+            *is_held = false;
+            note.hit = true;
+            let hold_ratio = (time_ms - *held_from) / *duration_ms;
+            return Some((i, hold_judgement(hold_ratio)));
+        }
+    }
+    None
+}
+
+fn hold_judgement(hold_ratio: f64) -> Judgement {
+    if hold_ratio >= 0.9 {
+        Judgement::Marv
+    } else if hold_ratio >= 0.8 {
+        Judgement::Perfect
+    } else if hold_ratio >= 0.6 {
+        Judgement::Great
+    } else if hold_ratio >= 0.4 {
+        Judgement::Good
+    } else if hold_ratio >= 0.2 {
+        Judgement::Bad
+    } else {
+        Judgement::Miss
+    }
+}
+
+/// Judgement of a burst that ended with `hits` of its `required` hits.
+fn burst_judgement(hits: u8, required: u8) -> Judgement {
+    let ratio = hits as f64 / required as f64;
+    if ratio >= 0.8 {
+        Judgement::Great
+    } else if ratio >= 0.5 {
+        Judgement::Good
+    } else if ratio > 0.0 {
+        Judgement::Bad
+    } else {
+        Judgement::Miss
+    }
+}
+
+/// Resolves the notes from `head` on whose time is up at `time_ms`: taps
+/// and holds never pressed are missed, holds held to the end and bursts
+/// that ran out are judged, and mines let through are done. `judged` gets
+/// the index and judgement of each. Returns the new head, the first note
+/// still open.
+///
+/// Fake notes are never judged: they stay open until they pass, without
+/// holding back the real notes behind them.
+pub fn sweep(
+    chart: &mut [NoteData],
+    head: usize,
+    time_ms: f64,
+    hit_window: &HitWindow,
+    mut judged: impl FnMut(usize, Judgement),
+) -> usize {
+    let miss_ms = hit_window.miss_ms;
+    let mut new_head = head;
+    // Index of the note being examined; runs ahead of `new_head` while a
+    // fake note is still scrolling past the receptors.
+    let mut idx = head;
+    let mut pending_fake = false;
+
+    while idx < chart.len() {
+        let note = &mut chart[idx];
+        if !note.hit {
+            let note_timestamp = note.timestamp_ms;
+            let note_end_time = note.end_time_ms();
+
+            if note.fake {
+                if time_ms <= note_end_time {
+                    pending_fake = true;
+                    idx += 1;
+                    continue;
+                }
+                note.hit = true;
+            } else {
+                match &mut note.note_type {
+                    NoteType::Tap => {
+                        if time_ms <= note_timestamp + miss_ms {
+                            break;
+                        }
+                        note.hit = true;
+                        judged(idx, Judgement::Miss);
+                    }
+
+                    NoteType::Hold {
+                        is_held,
+                        start_time,
+                        ..
+                    } => {
+                        if *is_held {
+                            // Still held: the note stays open until its end.
+                            if time_ms < note_end_time {
+                                break;
+                            }
+                            note.hit = true;
+                            *is_held = false;
+                            judged(idx, Judgement::Marv);
+                        } else if start_time.is_none() && time_ms > note_timestamp + miss_ms {
+                            note.hit = true;
+                            judged(idx, Judgement::Miss);
+                        } else {
+                            break;
+                        }
+                    }
+
+                    NoteType::Mine => {
+                        if time_ms <= note_timestamp + miss_ms {
+                            break;
+                        }
+                        // Mines that pass are not judged.
+                        note.hit = true;
+                    }
+
+                    NoteType::Burst {
+                        duration_ms,
+                        required_hits,
+                        current_hits,
+                    } => {
+                        if time_ms <= note_timestamp + *duration_ms {
+                            break;
+                        }
+                        note.hit = true;
+                        if *current_hits < *required_hits {
+                            judged(idx, burst_judgement(*current_hits, *required_hits));
+                        }
+                    }
+                }
+            }
+        }
+
+        idx += 1;
+        if !pending_fake {
+            new_head = idx;
+        }
+    }
+    new_head
+}
+
+/// Running score, combo and stats of a play.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tally {
+    pub hit_stats: HitStats,
+    pub score: u32,
+    pub combo: u32,
+    pub max_combo: u32,
+    /// Notes judged so far, ghost taps excluded.
+    pub notes_passed: u32,
+}
+
+impl Tally {
+    pub fn new() -> Self {
+        Self {
+            hit_stats: HitStats::new(),
+            score: 0,
+            combo: 0,
+            max_combo: 0,
+            notes_passed: 0,
+        }
+    }
+
+    /// Counts one judgement. Ghost taps neither break nor extend the combo.
+    pub fn apply(&mut self, judgement: Judgement) {
+        self.hit_stats.record(judgement);
+        self.count(judgement);
+    }
+
+    /// Counts one judgement given on `column`, also in the lane counts.
+    pub fn apply_column(&mut self, column: usize, judgement: Judgement) {
+        self.hit_stats.record_column(column, judgement);
+        self.count(judgement);
+    }
+
+    fn count(&mut self, judgement: Judgement) {
+        match judgement {
+            Judgement::GhostTap => {}
+            Judgement::Miss => {
+                self.combo = 0;
+                self.notes_passed += 1;
+            }
+            _ => {
+                self.combo += 1;
+                self.max_combo = self.max_combo.max(self.combo);
+                self.notes_passed += 1;
+                self.score += points(judgement);
+            }
+        }
+    }
+}
+
+impl Default for Tally {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Replays raw `inputs` on `chart` and judges every note, with the same
+/// rules as the game engine.
+///
+/// Notes that no input reached are missed.
+pub fn simulate(
+    chart: &[NoteData],
+    inputs: &[ReplayInput],
+    hit_window: &HitWindow,
+) -> ReplayResult {
+    let mut result = ReplayResult::new();
+    let mut tally = Tally::new();
+
+    // The chart may come from a play: its notes start over.
+    let mut notes: Vec<NoteData> = chart
+        .iter()
+        .map(|note| {
+            let mut note = note.clone();
+            note.hit = false;
+            note.note_type.reset();
+            note
+        })
+        .collect();
+    let mut head_index: usize = 0;
+    // Judged notes in order, with the press offset when a press judged them.
+    let mut judged: Vec<(usize, Judgement, Option<f64>)> = Vec::new();
+
+    for input in inputs {
+        let (input_column, is_press) = input.unpack();
+        let input_timestamp_ms = input.timestamp_ms as f64;
+
+        // Notes whose time is up before this input are resolved first.
+        head_index = sweep(
+            &mut notes,
+            head_index,
+            input_timestamp_ms,
+            hit_window,
+            |idx, judgement| judged.push((idx, judgement, None)),
+        );
+
+        if !is_press {
+            if let Some((idx, judgement)) =
+                release(&mut notes, head_index, input_column, input_timestamp_ms)
+            {
+                judged.push((idx, judgement, None));
+            }
+        } else if let Some(idx) = find_note(
+            &notes,
+            head_index,
+            input_column,
+            input_timestamp_ms,
+            hit_window,
+            |i| !notes[i].hit && notes[i].is_judged(),
+        ) {
+            let diff = notes[idx].timestamp_ms - input_timestamp_ms; // Signed: negative = early
+            match press(&mut notes[idx], input_timestamp_ms, hit_window) {
+                Press::Hit(judgement) => judged.push((idx, judgement, Some(diff))),
+                Press::Mine => judged.push((idx, Judgement::Miss, None)),
+                Press::HoldStart(_) | Press::BurstStep => {}
+            }
+        } else {
+            tally.apply(Judgement::GhostTap);
+            result.ghost_taps.push(GhostTap {
+                timestamp_ms: input.timestamp_ms,
+                column: input_column as u8,
+            });
+        }
+    }
+
+    // Whatever is left after the last input runs out.
+    sweep(
+        &mut notes,
+        head_index,
+        f64::INFINITY,
+        hit_window,
+        |idx, judgement| judged.push((idx, judgement, None)),
+    );
+
+    for (idx, judgement, diff) in judged {
+        tally.apply(judgement);
+        let note = &notes[idx];
+        result.hit_timings.push(HitTiming {
+            note_index: idx,
+            timing_ms: timing_of(note, judgement, diff, hit_window),
+            judgement,
+            note_timestamp_ms: note.timestamp_ms,
+            column: note.column,
+        });
+    }
+
+    result.accuracy = tally.hit_stats.calculate_accuracy();
+    result.hit_stats = tally.hit_stats;
+    result.score = tally.score;
+    result.max_combo = tally.max_combo;
+    result
+}
+
+/// Offset stored for a judgement: the press offset when there is one (the
+/// press that grabbed a hold for its end), the miss window for a miss.
+fn timing_of(
+    note: &NoteData,
+    judgement: Judgement,
+    diff: Option<f64>,
+    hit_window: &HitWindow,
+) -> f64 {
+    if let Some(diff) = diff {
+        return diff;
+    }
+    match (&note.note_type, judgement) {
+        (_, Judgement::Miss) => hit_window.miss_ms,
+        (
+            NoteType::Hold {
+                start_time: Some(start),
+                ..
+            },
+            _,
+        ) => note.timestamp_ms - start,
+        _ => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::replay::ReplayData;
+    use crate::models::settings::HitWindowMode;
+
+    fn chart() -> Vec<NoteData> {
+        (0..4)
+            .map(|i| NoteData::tap(1000.0 + i as f64 * 250.0, i))
+            .collect()
+    }
+
+    #[test]
+    fn exact_presses_are_all_marvelous() {
+        let chart = chart();
+        let mut replay = ReplayData::new(1.0, HitWindowMode::OsuOD, 8.0);
+        for note in &chart {
+            replay.add_press(note.timestamp_ms, note.column);
+            replay.add_release(note.timestamp_ms + 40.0, note.column);
+        }
+
+        let result = simulate(&chart, &replay.inputs, &HitWindow::new());
+        assert_eq!(result.hit_stats.marv, 4);
+        assert_eq!(result.max_combo, 4);
+        assert_eq!(result.score, 1200);
+    }
+
+    #[test]
+    fn wrong_column_is_a_ghost_tap_and_the_note_is_missed() {
+        let chart = vec![NoteData::tap(1000.0, 0)];
+        let mut replay = ReplayData::new(1.0, HitWindowMode::OsuOD, 8.0);
+        replay.add_press(1000.0, 1);
+
+        let result = simulate(&chart, &replay.inputs, &HitWindow::new());
+        assert_eq!(result.hit_stats.ghost_tap, 1);
+        assert_eq!(result.hit_stats.miss, 1);
+        assert_eq!(result.score, 0);
+
+        let columns = result.column_stats();
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns[0].hit_stats.miss, 1);
+        assert_eq!(columns[1].hit_stats.ghost_tap, 1);
+    }
+}
@@ -1,6 +1,15 @@
+pub mod autosave;
+pub mod course;
 pub mod engine;
+pub mod formats;
+pub mod ghost;
+pub mod goal;
+pub mod judge;
+pub mod personal_best;
+pub mod rejudge;
 pub mod replay;
 pub mod search;
 pub mod settings;
 pub mod skin;
 pub mod stats;
+pub mod tournament;
@@ -0,0 +1,143 @@
+//! Personal bests, ranked per rate and judge.
+//!
+//! A score only competes with the scores set on the same chart at the same
+//! rate and hit window: a J4 best at 1.0x says nothing about J7 or 1.1x.
+//! Practice runs never count, and neither do replays whose judge is not
+//! known yet (saved before it was stored, until their file is read).
+
+use crate::database::models::Replay;
+use crate::models::settings::HitWindowMode;
+use std::collections::HashSet;
+
+/// Identifies a hit window in the `replay.judge` column: `J4`, `OD8.5`, or
+/// the windows themselves for custom ones.
+pub fn judge_key(mode: HitWindowMode, value: f64) -> String {
+    match mode {
+        HitWindowMode::EtternaJudge => format!("J{}", value),
+        HitWindowMode::OsuOD => format!("OD{}", value),
+        HitWindowMode::Custom(windows) => format!(
+            "C{}/{}/{}/{}/{}/{}",
+            windows.marv_ms,
+            windows.perfect_ms,
+            windows.great_ms,
+            windows.good_ms,
+            windows.bad_ms,
+            windows.miss_ms
+        ),
+    }
+}
+
+fn same_rate(a: f64, b: f64) -> bool {
+    (a - b).abs() < 0.001
+}
+
+/// Whether `a` ranks above `b`: higher score, then higher accuracy.
+fn beats(a: &Replay, b: &Replay) -> bool {
+    (a.score, a.accuracy) > (b.score, b.accuracy)
+}
+
+/// Best score among `replays` at `rate` with the hit window `judge`.
+pub fn best_for<'a>(replays: &'a [Replay], rate: f64, judge: &str) -> Option<&'a Replay> {
+    replays
+        .iter()
+        .filter(|r| !r.practice && same_rate(r.rate, rate) && r.judge.as_deref() == Some(judge))
+        .fold(None, |best: Option<&Replay>, r| match best {
+            Some(best) if !beats(r, best) => Some(best),
+            _ => Some(r),
+        })
+}
+
+/// Hashes of the replays that are the personal best of their rate and judge.
+pub fn personal_bests(replays: &[Replay]) -> HashSet<String> {
+    replays
+        .iter()
+        .filter_map(|r| {
+            let judge = r.judge.as_deref()?;
+            best_for(replays, r.rate, judge).map(|best| best.hash.clone())
+        })
+        .collect()
+}
+
+/// How a new play compares to the previous best at its rate and judge.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PbResult {
+    /// Nothing set at this rate and judge before.
+    First,
+    /// The play beat the previous best by these amounts.
+    Improved { score: i64, accuracy: f64 },
+    /// The play fell short of the best by these (negative) amounts.
+    Behind { score: i64, accuracy: f64 },
+}
+
+impl PbResult {
+    pub fn compare(previous: Option<&Replay>, score: u32, accuracy: f64) -> Self {
+        let Some(previous) = previous else {
+            return PbResult::First;
+        };
+        let score_delta = score as i64 - previous.score as i64;
+        let accuracy_delta = accuracy - previous.accuracy;
+        if (score_delta, accuracy_delta) > (0, 0.0) {
+            PbResult::Improved {
+                score: score_delta,
+                accuracy: accuracy_delta,
+            }
+        } else {
+            PbResult::Behind {
+                score: score_delta,
+                accuracy: accuracy_delta,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replay(hash: &str, rate: f64, judge: &str, score: i32, practice: bool) -> Replay {
+        Replay {
+            hash: hash.to_string(),
+            beatmap_hash: "chart".to_string(),
+            timestamp: 0,
+            score,
+            accuracy: score as f64 / 10_000.0,
+            max_combo: 0,
+            rate,
+            file_path: String::new(),
+            judge: Some(judge.to_string()),
+            practice,
+        }
+    }
+
+    #[test]
+    fn bests_are_kept_per_rate_and_judge() {
+        let replays = vec![
+            replay("a", 1.0, "J4", 900_000, false),
+            replay("b", 1.0, "J4", 950_000, false),
+            replay("c", 1.0, "J7", 800_000, false),
+            replay("d", 1.1, "J4", 700_000, false),
+            replay("e", 1.0, "J4", 990_000, true),
+        ];
+        let bests = personal_bests(&replays);
+        let mut bests: Vec<_> = bests.iter().map(String::as_str).collect();
+        bests.sort_unstable();
+        assert_eq!(bests, vec!["b", "c", "d"]);
+
+        let previous = best_for(&replays, 1.0, "J4");
+        assert_eq!(
+            PbResult::compare(previous, 960_000, 96.0),
+            PbResult::Improved {
+                score: 10_000,
+                accuracy: 1.0
+            }
+        );
+        assert!(matches!(
+            PbResult::compare(previous, 940_000, 94.0),
+            PbResult::Behind { score: -10_000, .. }
+        ));
+        assert_eq!(
+            PbResult::compare(best_for(&replays, 1.2, "J4"), 1, 0.0),
+            PbResult::First
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! Leaderboard replays re-judged at the current hit window.
+//!
+//! Scores set at another judge are not comparable as stored, so their
+//! replays are simulated again against the chart with the window the
+//! leaderboard is displayed at. Simulations run on a worker thread and the
+//! results are cached per score and window, so the song select only pays
+//! for them once.
+
+use crate::models::engine::NoteData;
+use crate::models::engine::hit_window::HitWindow;
+use crate::models::replay::{ReplayData, ReplayResult, simulate_replay};
+use crossbeam_channel::{Receiver, Sender, unbounded};
+use std::collections::HashMap;
+use std::thread;
+
+/// A leaderboard score: beatmap hash and timestamp.
+pub type ScoreId = (String, i64);
+
+/// Results kept before the cache starts over.
+const MAX_CACHED: usize = 256;
+
+/// Replays of one chart to simulate at one hit window.
+struct Job {
+    chart: Vec<NoteData>,
+    hit_window: HitWindow,
+    replays: Vec<(ScoreId, ReplayData)>,
+}
+
+type Rejudged = (ScoreId, HitWindow, ReplayResult);
+
+pub struct ReplayRejudger {
+    jobs: Sender<Job>,
+    results: Receiver<Rejudged>,
+    done: HashMap<ScoreId, (HitWindow, ReplayResult)>,
+    /// Window each queued score is being simulated at.
+    pending: HashMap<ScoreId, HitWindow>,
+}
+
+impl ReplayRejudger {
+    pub fn new() -> Self {
+        let (jobs, job_rx) = unbounded::<Job>();
+        let (result_tx, results) = unbounded();
+        let spawned = thread::Builder::new()
+            .name("Rejudge Thread".to_string())
+            .spawn(move || {
+                for job in job_rx {
+                    for (id, replay) in job.replays {
+                        let result = simulate_replay(&replay, &job.chart, &job.hit_window);
+                        if result_tx.send((id, job.hit_window, result)).is_err() {
+                            return;
+                        }
+                    }
+                }
+            });
+        if let Err(e) = spawned {
+            log::error!("LEADERBOARD: Could not start the rejudge thread: {}", e);
+        }
+        Self {
+            jobs,
+            results,
+            done: HashMap::new(),
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Collects the simulations finished since the last call.
+    pub fn poll(&mut self) {
+        for (id, hit_window, result) in self.results.try_iter() {
+            if self.pending.get(&id) == Some(&hit_window) {
+                self.pending.remove(&id);
+            }
+            if self.done.len() >= MAX_CACHED {
+                self.done.clear();
+            }
+            self.done.insert(id, (hit_window, result));
+        }
+    }
+
+    /// Queues the replays not simulated at `hit_window` yet.
+    pub fn request<'a>(
+        &mut self,
+        chart: &[NoteData],
+        hit_window: HitWindow,
+        replays: impl IntoIterator<Item = (ScoreId, &'a ReplayData)>,
+    ) {
+        let missing: Vec<(ScoreId, ReplayData)> = replays
+            .into_iter()
+            .filter(|(id, _)| {
+                self.get(id, &hit_window).is_none() && self.pending.get(id) != Some(&hit_window)
+            })
+            .map(|(id, replay)| (id, replay.clone()))
+            .collect();
+        if missing.is_empty() {
+            return;
+        }
+        for (id, _) in &missing {
+            self.pending.insert(id.clone(), hit_window);
+        }
+        let job = Job {
+            chart: chart.to_vec(),
+            hit_window,
+            replays: missing,
+        };
+        if self.jobs.send(job).is_err() {
+            log::error!("LEADERBOARD: Rejudge thread is gone");
+        }
+    }
+
+    /// Result of a score simulated at `hit_window`, once available.
+    pub fn get(&self, id: &ScoreId, hit_window: &HitWindow) -> Option<&ReplayResult> {
+        self.done
+            .get(id)
+            .filter(|(window, _)| window == hit_window)
+            .map(|(_, result)| result)
+    }
+
+    /// Whether simulations are still running.
+    pub fn is_busy(&self) -> bool {
+        !self.pending.is_empty()
+    }
+}
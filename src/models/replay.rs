@@ -5,6 +5,7 @@
 
 use crate::models::engine::NoteData;
 use crate::models::engine::hit_window::HitWindow;
+use crate::models::judge;
 use crate::models::settings::HitWindowMode;
 use crate::models::stats::{HitStats, Judgement};
 use serde::{Deserialize, Serialize};
@@ -57,6 +58,10 @@ pub struct ReplayData {
     /// Maximum 1 checkpoint every 15 seconds.
     #[serde(default)]
     pub checkpoints: Vec<f64>,
+    /// Recovered from a play interrupted by a crash, judged up to where it
+    /// stopped. Kept out of personal bests like practice runs.
+    #[serde(default)]
+    pub incomplete: bool,
 }
 
 /// Minimum interval between checkpoints (in ms).
@@ -73,6 +78,7 @@ impl ReplayData {
             hit_window_value,
             is_practice_mode: false,
             checkpoints: Vec::new(),
+            incomplete: false,
         }
     }
 
@@ -143,12 +149,7 @@ impl ReplayData {
 
     /// Rebuilds the hit window from saved parameters.
     pub fn build_hit_window(&self) -> HitWindow {
-        match self.hit_window_mode {
-            HitWindowMode::OsuOD => HitWindow::from_osu_od(self.hit_window_value),
-            HitWindowMode::EtternaJudge => {
-                HitWindow::from_etterna_judge(self.hit_window_value as u8)
-            }
-        }
+        HitWindow::from_mode(self.hit_window_mode, self.hit_window_value)
     }
 }
 
@@ -162,6 +163,7 @@ impl Default for ReplayData {
             hit_window_value: 5.0,
             is_practice_mode: false,
             checkpoints: Vec::new(),
+            incomplete: false,
         }
     }
 }
@@ -209,6 +211,9 @@ pub struct HitTiming {
     pub judgement: Judgement,
     /// Timestamp of the note in the map.
     pub note_timestamp_ms: f64,
+    /// Column of the note.
+    #[serde(default)]
+    pub column: usize,
 }
 
 /// Ghost tap (press without a corresponding note).
@@ -250,160 +255,157 @@ impl ReplayResult {
     }
 }
 
-impl Default for ReplayResult {
-    fn default() -> Self {
-        Self::new()
-    }
+/// Summary of hit offsets (misses excluded).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeviationStats {
+    /// Number of judged hits.
+    pub count: usize,
+    /// Mean offset in ms (input minus note time, negative = early).
+    pub mean_ms: f64,
+    /// Standard deviation of the offset in ms.
+    pub std_dev_ms: f64,
+    /// Hits pressed before the note.
+    pub early: usize,
+    /// Hits pressed after the note.
+    pub late: usize,
 }
 
-/// Simulates a replay on a chart with the given hit window.
-///
-/// This function replays recorded inputs on the map to deterministically
-/// recalculate all statistics.
-pub fn simulate_replay(
-    replay_data: &ReplayData,
-    chart: &[NoteData],
-    hit_window: &HitWindow,
-) -> ReplayResult {
-    let mut result = ReplayResult::new();
-    let mut combo: u32 = 0;
-
-    // Track hit notes (index -> hit)
-    let mut note_hit: Vec<bool> = vec![false; chart.len()];
-
-    // Head index to optimize search
-    let mut head_index: usize = 0;
-
-    for input in &replay_data.inputs {
-        let (input_column, is_press) = input.unpack();
-        let input_timestamp_ms = input.timestamp_ms as f64;
-
-        // Before processing this input, check for missed notes
-        while head_index < chart.len() {
-            if note_hit[head_index] {
-                head_index += 1;
-                continue;
-            }
-
-            let note = &chart[head_index];
-            let miss_deadline = note.timestamp_ms + hit_window.miss_ms;
-
-            if input_timestamp_ms > miss_deadline {
-                // Miss!
-                note_hit[head_index] = true;
-                result.hit_stats.miss += 1;
-                combo = 0;
-
-                result.hit_timings.push(HitTiming {
-                    note_index: head_index,
-                    timing_ms: hit_window.miss_ms,
-                    judgement: Judgement::Miss,
-                    note_timestamp_ms: note.timestamp_ms,
-                });
-
-                head_index += 1;
-            } else {
-                break;
-            }
-        }
-
-        // Only process presses (releases are ignored for scoring)
-        if !is_press {
-            continue;
+impl DeviationStats {
+    /// Share of early hits in `[0, 1]`.
+    pub fn early_ratio(&self) -> f64 {
+        let total = self.early + self.late;
+        if total == 0 {
+            return 0.5;
         }
+        self.early as f64 / total as f64
+    }
+}
 
-        // Find the best note to hit in this column
-        let current_time = input_timestamp_ms;
-        let mut best_match: Option<(usize, f64)> = None;
-        let search_limit = current_time + hit_window.miss_ms;
-
-        for i in head_index..chart.len() {
-            let note = &chart[i];
+/// Per-column judgement breakdown.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnStats {
+    pub column: usize,
+    pub hit_stats: HitStats,
+    pub deviation: DeviationStats,
+}
 
-            if note.timestamp_ms > search_limit {
-                break;
-            }
+impl ReplayResult {
+    /// Offset statistics over every judged hit.
+    pub fn deviation_stats(&self) -> DeviationStats {
+        deviation_of(self.hit_timings.iter())
+    }
 
-            if note.column == input_column && !note_hit[i] {
-                let diff = (note.timestamp_ms - current_time).abs();
-                if diff <= hit_window.miss_ms
-                    && best_match.is_none_or(|(_, best_diff)| diff < best_diff)
-                {
-                    best_match = Some((i, diff));
+    /// Judgement counts (ghost taps included) and offsets per column, sorted
+    /// by column.
+    pub fn column_stats(&self) -> Vec<ColumnStats> {
+        let columns = self
+            .hit_timings
+            .iter()
+            .map(|h| h.column + 1)
+            .chain(self.ghost_taps.iter().map(|t| t.column as usize + 1))
+            .max()
+            .unwrap_or(0);
+
+        (0..columns)
+            .map(|column| {
+                let timings = self.hit_timings.iter().filter(|h| h.column == column);
+                let mut hit_stats = HitStats::new();
+                for hit in timings.clone() {
+                    match hit.judgement {
+                        Judgement::Marv => hit_stats.marv += 1,
+                        Judgement::Perfect => hit_stats.perfect += 1,
+                        Judgement::Great => hit_stats.great += 1,
+                        Judgement::Good => hit_stats.good += 1,
+                        Judgement::Bad => hit_stats.bad += 1,
+                        Judgement::Miss => hit_stats.miss += 1,
+                        Judgement::GhostTap => hit_stats.ghost_tap += 1,
+                    }
                 }
-            }
-        }
+                hit_stats.ghost_tap += self
+                    .ghost_taps
+                    .iter()
+                    .filter(|t| t.column as usize == column)
+                    .count() as u32;
+                ColumnStats {
+                    column,
+                    hit_stats,
+                    deviation: deviation_of(timings),
+                }
+            })
+            .collect()
+    }
 
-        if let Some((idx, _)) = best_match {
-            let note = &chart[idx];
-            let diff = note.timestamp_ms - current_time; // Signed: negative = early
-            let (judgement, _) = hit_window.judge(diff);
+    /// Per-note judgement table as CSV, one row per note in chart order:
+    /// `note,time_ms,column,offset_ms,judgement`. Offsets are negative when
+    /// early; misses have no offset. Ghost taps are listed after the notes
+    /// with an empty note index.
+    pub fn judgement_csv(&self) -> String {
+        let mut timings: Vec<&HitTiming> = self.hit_timings.iter().collect();
+        timings.sort_by_key(|h| h.note_index);
+
+        let mut csv = String::from("note,time_ms,column,offset_ms,judgement\n");
+        for hit in timings {
+            let offset = match hit.judgement {
+                Judgement::Miss => String::new(),
+                _ => format!("{:.2}", -hit.timing_ms),
+            };
+            csv.push_str(&format!(
+                "{},{:.2},{},{},{:?}\n",
+                hit.note_index, hit.note_timestamp_ms, hit.column, offset, hit.judgement
+            ));
+        }
+        for tap in &self.ghost_taps {
+            csv.push_str(&format!(
+                ",{},{},,{:?}\n",
+                tap.timestamp_ms,
+                tap.column,
+                Judgement::GhostTap
+            ));
+        }
+        csv
+    }
+}
 
-            note_hit[idx] = true;
+fn deviation_of<'a>(timings: impl Iterator<Item = &'a HitTiming>) -> DeviationStats {
+    // `timing_ms` is note minus input, so flip it to get the usual offset sign.
+    let offsets: Vec<f64> = timings
+        .filter(|h| !matches!(h.judgement, Judgement::Miss | Judgement::GhostTap))
+        .map(|h| -h.timing_ms)
+        .collect();
 
-            // Apply judgement
-            match judgement {
-                Judgement::Miss => {
-                    result.hit_stats.miss += 1;
-                    combo = 0;
-                }
-                Judgement::GhostTap => {
-                    result.hit_stats.ghost_tap += 1;
-                }
-                _ => {
-                    match judgement {
-                        Judgement::Marv => result.hit_stats.marv += 1,
-                        Judgement::Perfect => result.hit_stats.perfect += 1,
-                        Judgement::Great => result.hit_stats.great += 1,
-                        Judgement::Good => result.hit_stats.good += 1,
-                        Judgement::Bad => result.hit_stats.bad += 1,
-                        _ => {}
-                    }
-                    combo += 1;
-                    result.max_combo = result.max_combo.max(combo);
-                    result.score += match judgement {
-                        Judgement::Marv | Judgement::Perfect => 300,
-                        Judgement::Great => 200,
-                        Judgement::Good => 100,
-                        Judgement::Bad => 50,
-                        _ => 0,
-                    };
-                }
-            }
-
-            result.hit_timings.push(HitTiming {
-                note_index: idx,
-                timing_ms: diff,
-                judgement,
-                note_timestamp_ms: note.timestamp_ms,
-            });
-        } else {
-            // Ghost tap - no corresponding note
-            result.hit_stats.ghost_tap += 1;
-            result.ghost_taps.push(GhostTap {
-                timestamp_ms: input_timestamp_ms as i32, // Stored as i32
-                column: input_column as u8,
-            });
-        }
+    if offsets.is_empty() {
+        return DeviationStats::default();
     }
 
-    // After all inputs, check remaining unhit notes (final misses)
-    for (idx, note) in chart.iter().enumerate() {
-        if !note_hit[idx] {
-            result.hit_stats.miss += 1;
-            result.hit_timings.push(HitTiming {
-                note_index: idx,
-                timing_ms: hit_window.miss_ms,
-                judgement: Judgement::Miss,
-                note_timestamp_ms: note.timestamp_ms,
-            });
-        }
+    let count = offsets.len();
+    let mean_ms = offsets.iter().sum::<f64>() / count as f64;
+    let variance = offsets.iter().map(|o| (o - mean_ms).powi(2)).sum::<f64>() / count as f64;
+
+    DeviationStats {
+        count,
+        mean_ms,
+        std_dev_ms: variance.sqrt(),
+        early: offsets.iter().filter(|&&o| o < 0.0).count(),
+        late: offsets.iter().filter(|&&o| o > 0.0).count(),
     }
+}
 
-    // Calculate final accuracy
-    result.accuracy = result.hit_stats.calculate_accuracy();
+impl Default for ReplayResult {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-    result
+/// Simulates a replay on a chart with the given hit window.
+///
+/// This function replays recorded inputs on the map to deterministically
+/// recalculate all statistics (see [`judge::simulate`]).
+pub fn simulate_replay(
+    replay_data: &ReplayData,
+    chart: &[NoteData],
+    hit_window: &HitWindow,
+) -> ReplayResult {
+    judge::simulate(chart, &replay_data.inputs, hit_window)
 }
 
 /// Re-judges a replay with a new hit window.
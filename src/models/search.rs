@@ -1,3 +1,5 @@
+use crate::difficulty::PatternTag;
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub enum RatingSource {
     #[default]
@@ -64,6 +66,18 @@ pub struct MenuSearchFilters {
     pub rating_metric: RatingMetric,
     pub min_duration_seconds: Option<f64>,
     pub max_duration_seconds: Option<f64>,
+    /// Shows archived beatmapsets instead of the regular library.
+    pub show_archived: bool,
+    /// Restricts results to a single mapper.
+    pub creator: Option<String>,
+    /// Restricts results to a single pack.
+    pub pack: Option<String>,
+    /// Orders results by pack, so each pack's sets are listed together.
+    pub group_by_pack: bool,
+    /// Leaves out the charts that failed to load.
+    pub hide_broken: bool,
+    /// Only charts tagged with every one of these patterns.
+    pub pattern_tags: Vec<PatternTag>,
 }
 
 impl MenuSearchFilters {
@@ -73,5 +87,11 @@ impl MenuSearchFilters {
             || self.max_rating.is_some()
             || self.min_duration_seconds.is_some()
             || self.max_duration_seconds.is_some()
+            || self.show_archived
+            || self.creator.is_some()
+            || self.pack.is_some()
+            || self.group_by_pack
+            || self.hide_broken
+            || !self.pattern_tags.is_empty()
     }
 }
@@ -3,9 +3,16 @@
 //! This module handles loading/saving settings from `settings.toml`
 //! and provides the configuration UI state.
 
+use crate::database::models::DEFAULT_PROFILE_ID;
+use crate::input::manager::RESERVED_BINDS;
+use crate::models::engine::hit_window::HitWindow;
+use crate::models::stats::JudgementPalette;
+use crate::models::tournament;
+use crate::shared::locale::{DEFAULT_LANGUAGE, tr};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
+use std::path::PathBuf;
 
 /// Hit window calculation mode.
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -14,6 +21,19 @@ pub enum HitWindowMode {
     OsuOD,
     /// Etterna/Quaver judge level based timing.
     EtternaJudge,
+    /// Windows set per judgement; the hit window value is unused.
+    Custom(HitWindow),
+}
+
+impl HitWindowMode {
+    /// Short label of the judge with its `value`, e.g. "J4" or "OD8.5".
+    pub fn label(self, value: f64) -> String {
+        match self {
+            HitWindowMode::EtternaJudge => format!("J{}", value as u8),
+            HitWindowMode::OsuOD => format!("OD{}", value),
+            HitWindowMode::Custom(_) => "Custom".to_string(),
+        }
+    }
 }
 
 /// Aspect ratio mode for the playfield.
@@ -27,24 +47,650 @@ pub enum AspectRatioMode {
     Ratio4_3,
 }
 
+/// How the audio reacts to rate changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RateMode {
+    /// Resample: faster rates raise the pitch (classic "nightcore" behaviour).
+    #[default]
+    Pitch,
+    /// Time-stretch: the tempo changes but the pitch is preserved.
+    Stretch,
+}
+
+impl RateMode {
+    /// Whether the audio thread should time-stretch.
+    pub fn is_stretch(self) -> bool {
+        self == RateMode::Stretch
+    }
+}
+
+/// How the scroll speed setting is interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ScrollSpeedMode {
+    /// Constant: notes always take the scroll speed (ms) to cross the playfield.
+    #[default]
+    CMod,
+    /// BPM-scaled: the scroll speed applies at the chart's main BPM, and notes
+    /// move faster or slower as the BPM changes.
+    MMod,
+}
+
+/// How frames are presented to the screen (swapchain present mode).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PresentModeSetting {
+    /// No VSync: lowest latency, may tear.
+    #[default]
+    Immediate,
+    /// No VSync and no tearing (triple buffering), when the driver has it.
+    Mailbox,
+    /// Synced to the display refresh rate.
+    VSync,
+}
+
+impl PresentModeSetting {
+    pub fn label(self) -> &'static str {
+        match self {
+            PresentModeSetting::Immediate => "Immediate (no VSync)",
+            PresentModeSetting::Mailbox => "Mailbox",
+            PresentModeSetting::VSync => "VSync",
+        }
+    }
+}
+
+/// Window mode of the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DisplayMode {
+    #[default]
+    Windowed,
+    /// Fullscreen window at the desktop resolution.
+    Borderless,
+    /// Exclusive fullscreen at `resolution`.
+    Exclusive,
+}
+
+impl DisplayMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            DisplayMode::Windowed => "Windowed",
+            DisplayMode::Borderless => "Borderless fullscreen",
+            DisplayMode::Exclusive => "Exclusive fullscreen",
+        }
+    }
+}
+
+/// Pages of the settings window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SettingsCategory {
+    #[default]
+    Gameplay,
+    Audio,
+    Video,
+    Input,
+    Skin,
+    Library,
+}
+
+impl SettingsCategory {
+    pub const ALL: [SettingsCategory; 6] = [
+        SettingsCategory::Gameplay,
+        SettingsCategory::Audio,
+        SettingsCategory::Video,
+        SettingsCategory::Input,
+        SettingsCategory::Skin,
+        SettingsCategory::Library,
+    ];
+
+    /// Name shown in the settings window, in the interface language.
+    pub fn label(self) -> String {
+        tr(match self {
+            SettingsCategory::Gameplay => "settings.category.gameplay",
+            SettingsCategory::Audio => "settings.category.audio",
+            SettingsCategory::Video => "settings.category.video",
+            SettingsCategory::Input => "settings.category.input",
+            SettingsCategory::Skin => "settings.category.skin",
+            SettingsCategory::Library => "settings.category.library",
+        })
+    }
+}
+
+/// Where the turntable lane of imported BMS charts goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BmsScratchMode {
+    /// Scratch becomes the leftmost column.
+    #[default]
+    Left,
+    /// Scratch becomes the rightmost column.
+    Right,
+    /// Scratch notes are removed.
+    Drop,
+}
+
+/// osu! hitsound used as a marker by [`OsuConvertRules`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OsuHitsound {
+    /// Rule disabled.
+    #[default]
+    None,
+    Whistle,
+    Finish,
+    Clap,
+}
+
+impl OsuHitsound {
+    pub const ALL: [OsuHitsound; 4] = [
+        OsuHitsound::None,
+        OsuHitsound::Whistle,
+        OsuHitsound::Finish,
+        OsuHitsound::Clap,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OsuHitsound::None => "Off",
+            OsuHitsound::Whistle => "Whistle",
+            OsuHitsound::Finish => "Finish",
+            OsuHitsound::Clap => "Clap",
+        }
+    }
+}
+
+fn default_burst_hit_interval_ms() -> u32 {
+    150
+}
+
+/// Rules turning notes of osu!mania charts, which have no mines nor bursts,
+/// into these mechanics from the hitsounds the mapper put on them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OsuConvertRules {
+    /// Taps with this hitsound become mines.
+    #[serde(default)]
+    pub mine_hitsound: OsuHitsound,
+    /// Holds with this hitsound become bursts.
+    #[serde(default)]
+    pub burst_hitsound: OsuHitsound,
+    /// A converted burst needs one hit per this many ms of its length
+    /// (at least two).
+    #[serde(default = "default_burst_hit_interval_ms")]
+    pub burst_hit_interval_ms: u32,
+}
+
+impl Default for OsuConvertRules {
+    fn default() -> Self {
+        Self {
+            mine_hitsound: OsuHitsound::None,
+            burst_hitsound: OsuHitsound::None,
+            burst_hit_interval_ms: default_burst_hit_interval_ms(),
+        }
+    }
+}
+
+impl OsuConvertRules {
+    /// Hits required by a burst lasting `duration_ms`.
+    pub fn burst_required_hits(&self, duration_ms: f64) -> u8 {
+        let interval = self.burst_hit_interval_ms.max(1) as f64;
+        (duration_ms / interval).ceil().clamp(2.0, u8::MAX as f64) as u8
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.mine_hitsound != OsuHitsound::None || self.burst_hitsound != OsuHitsound::None
+    }
+}
+
+/// Which replays the library maintenance prunes. A replay goes when it
+/// matches any enabled rule; personal bests are always kept.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct ReplayPruneRules {
+    /// Prune replays under this accuracy in percent (0 = off).
+    #[serde(default)]
+    pub below_accuracy: f64,
+    /// Prune replays older than this many days (0 = off).
+    #[serde(default)]
+    pub older_than_days: u32,
+}
+
+impl ReplayPruneRules {
+    pub fn is_enabled(&self) -> bool {
+        self.below_accuracy > 0.0 || self.older_than_days > 0
+    }
+
+    /// Whether a replay set at `timestamp` (unix seconds) matches a rule,
+    /// as of `now`.
+    pub fn matches(&self, accuracy: f64, timestamp: i64, now: i64) -> bool {
+        let too_low = self.below_accuracy > 0.0 && accuracy < self.below_accuracy;
+        let too_old = self.older_than_days > 0
+            && now.saturating_sub(timestamp) > self.older_than_days as i64 * 86_400;
+        too_low || too_old
+    }
+}
+
+/// Order of the scores in the song select leaderboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LeaderboardSort {
+    /// Highest score first.
+    #[default]
+    Score,
+    /// Highest accuracy first.
+    Accuracy,
+    /// Most recent first.
+    Date,
+}
+
+/// Which local scores the leaderboard shows, and in what order.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct LeaderboardOptions {
+    /// Only show scores set at the currently selected rate.
+    #[serde(default)]
+    pub current_rate_only: bool,
+    /// Only show scores set with the current hit window (judge / OD).
+    #[serde(default)]
+    pub current_judge_only: bool,
+    #[serde(default)]
+    pub sort: LeaderboardSort,
+}
+
+/// Curve of the song list's animated jumps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ScrollEasing {
+    Linear,
+    /// Fast start, slow landing.
+    #[default]
+    EaseOut,
+    /// Slow start and landing.
+    EaseInOut,
+}
+
+impl ScrollEasing {
+    pub fn label(self) -> &'static str {
+        match self {
+            ScrollEasing::Linear => "Linear",
+            ScrollEasing::EaseOut => "Ease out",
+            ScrollEasing::EaseInOut => "Ease in-out",
+        }
+    }
+
+    /// Progress of a jump at `t` (0.0 to 1.0) of its duration.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            ScrollEasing::Linear => t,
+            ScrollEasing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            ScrollEasing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+        }
+    }
+}
+
+/// How the song list follows the selection and the mouse wheel.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SongListScroll {
+    /// Animate jumps and let the wheel glide; off moves the list instantly.
+    #[serde(default = "default_smooth_scroll")]
+    pub smooth: bool,
+    /// Duration of a jump to the selected set, in milliseconds.
+    #[serde(default = "default_scroll_duration_ms")]
+    pub duration_ms: f32,
+    #[serde(default)]
+    pub easing: ScrollEasing,
+}
+
+fn default_smooth_scroll() -> bool {
+    true
+}
+
+fn default_scroll_duration_ms() -> f32 {
+    250.0
+}
+
+impl Default for SongListScroll {
+    fn default() -> Self {
+        Self {
+            smooth: default_smooth_scroll(),
+            duration_ms: default_scroll_duration_ms(),
+            easing: ScrollEasing::default(),
+        }
+    }
+}
+
+/// Sudden+ / hidden+ covers, as fractions of the playfield height (0 = off).
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct LaneCover {
+    /// Hides the top of the playfield, where notes appear.
+    #[serde(default)]
+    pub sudden: f32,
+    /// Hides the bottom of the playfield, just above the receptors.
+    #[serde(default)]
+    pub hidden: f32,
+}
+
+impl LaneCover {
+    /// Largest fraction a single cover can take.
+    pub const MAX: f32 = 0.9;
+    /// Change applied by one press of the adjust keys.
+    pub const STEP: f32 = 0.02;
+
+    /// Changes the cover heights, keeping at least a sliver of lane visible.
+    pub fn adjust(&mut self, sudden_delta: f32, hidden_delta: f32) {
+        self.sudden = (self.sudden + sudden_delta).clamp(0.0, Self::MAX);
+        self.hidden = (self.hidden + hidden_delta).clamp(0.0, Self::MAX);
+        let overflow = self.sudden + self.hidden - Self::MAX;
+        if overflow > 0.0 {
+            if sudden_delta > 0.0 {
+                self.hidden -= overflow;
+            } else {
+                self.sudden -= overflow;
+            }
+        }
+    }
+}
+
+/// Named set of gameplay settings, switched from song select.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameplayPreset {
+    pub name: String,
+    pub scroll_speed: f64,
+    #[serde(default)]
+    pub scroll_speeds: HashMap<String, f64>,
+    pub hit_window_mode: HitWindowMode,
+    pub hit_window_value: f64,
+    #[serde(default)]
+    pub lane_cover: LaneCover,
+    #[serde(default = "default_background_dim")]
+    pub background_dim: f32,
+}
+
+/// Name of the preset made from the settings of older versions.
+pub const DEFAULT_PRESET_NAME: &str = "Default";
+
+/// Lockout for running events on the client: the judge and the rates are
+/// fixed, practice, the editor and the leaderboard are unavailable, and every
+/// play is also written as a signed replay. The organizer locks it with a
+/// password, which is needed to leave it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TournamentSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Rates players can pick.
+    #[serde(default = "default_tournament_rates")]
+    pub rates: Vec<f64>,
+    /// Salt and SHA-256 of the organizer password.
+    #[serde(default)]
+    pub password_salt: String,
+    #[serde(default)]
+    pub password_hash: String,
+    /// Key replays are signed with, derived from the password. Never saved:
+    /// the organizer enters the password again after a restart.
+    #[serde(skip)]
+    pub signing_key: Option<Vec<u8>>,
+}
+
+fn default_tournament_rates() -> Vec<f64> {
+    vec![1.0]
+}
+
+impl Default for TournamentSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rates: default_tournament_rates(),
+            password_salt: String::new(),
+            password_hash: String::new(),
+            signing_key: None,
+        }
+    }
+}
+
+impl TournamentSettings {
+    /// Turns tournament mode on, locked with `password`.
+    pub fn lock(&mut self, password: &str) {
+        self.password_salt = tournament::new_salt();
+        self.password_hash = tournament::password_hash(&self.password_salt, password);
+        self.signing_key = Some(tournament::signing_key(password));
+        self.enabled = true;
+    }
+
+    fn password_matches(&self, password: &str) -> bool {
+        !self.password_hash.is_empty()
+            && tournament::password_hash(&self.password_salt, password) == self.password_hash
+    }
+
+    /// Turns tournament mode off if `password` is the organizer's.
+    pub fn unlock(&mut self, password: &str) -> bool {
+        if !self.password_matches(password) {
+            return false;
+        }
+        self.enabled = false;
+        self.password_salt.clear();
+        self.password_hash.clear();
+        self.signing_key = None;
+        true
+    }
+
+    /// Restores the signing key after a restart if `password` is the
+    /// organizer's.
+    pub fn resume(&mut self, password: &str) -> bool {
+        if !self.password_matches(password) {
+            return false;
+        }
+        self.signing_key = Some(tournament::signing_key(password));
+        true
+    }
+
+    /// The allowed rate closest to `rate`.
+    pub fn snap_rate(&self, rate: f64) -> f64 {
+        self.rates
+            .iter()
+            .copied()
+            .min_by(|a, b| (a - rate).abs().total_cmp(&(b - rate).abs()))
+            .unwrap_or(1.0)
+    }
+
+    /// The allowed rate after (`step > 0`) or before `rate`, or `rate` snapped
+    /// when there is none.
+    pub fn step_rate(&self, rate: f64, step: i32) -> f64 {
+        let current = self.snap_rate(rate);
+        let mut rates = self.rates.clone();
+        rates.sort_by(f64::total_cmp);
+        let index = rates.iter().position(|r| *r == current).unwrap_or(0);
+        let target = (index as i64 + step as i64).clamp(0, rates.len().max(1) as i64 - 1);
+        rates.get(target as usize).copied().unwrap_or(current)
+    }
+}
+
+/// One of the volume sliders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolumeChannel {
+    Master,
+    Music,
+    Effects,
+}
+
+impl VolumeChannel {
+    /// Name shown in the volume toasts, in the interface language.
+    pub fn label(self) -> String {
+        tr(match self {
+            VolumeChannel::Master => "settings.option.master_volume",
+            VolumeChannel::Music => "settings.option.music_volume",
+            VolumeChannel::Effects => "settings.option.effects_volume",
+        })
+    }
+}
+
+/// Volumes applied by the audio thread, each from 0.0 to 1.0. The music
+/// and the sound effects are both scaled by the master volume.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Volumes {
+    pub master: f32,
+    pub music: f32,
+    pub effects: f32,
+}
+
+impl Volumes {
+    /// Gain of the song.
+    pub fn music_gain(&self) -> f32 {
+        self.master * self.music
+    }
+
+    /// Gain of the skin sound effects.
+    pub fn effects_gain(&self) -> f32 {
+        self.master * self.effects
+    }
+}
+
 /// Persistent user settings.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SettingsState {
     /// Master volume (0.0 to 1.0).
     pub master_volume: f32,
+    /// Song volume (0.0 to 1.0), scaled by the master volume.
+    #[serde(default = "default_volume")]
+    pub music_volume: f32,
+    /// Skin sound effect volume (0.0 to 1.0), scaled by the master volume.
+    #[serde(default = "default_volume")]
+    pub effects_volume: f32,
     /// Scroll speed in milliseconds.
     pub scroll_speed: f64,
+    /// Scroll speed of some keymodes (key count -> ms); the others use
+    /// `scroll_speed`.
+    #[serde(default)]
+    pub scroll_speeds: HashMap<String, f64>,
+    /// Constant (cmod) or BPM-scaled (mmod) scroll.
+    #[serde(default)]
+    pub scroll_speed_mode: ScrollSpeedMode,
+    /// Ignore the chart's scroll velocity changes (osu! SV).
+    #[serde(default)]
+    pub disable_sv: bool,
+    /// Quitting a chart midway shows the results up to the quit point.
+    #[serde(default = "default_partial_results")]
+    pub partial_results: bool,
+    /// Clicking a receptor hits its column.
+    #[serde(default)]
+    pub mouse_input: bool,
     /// Hit window calculation mode.
     pub hit_window_mode: HitWindowMode,
     /// Hit window value (OD or judge level).
     pub hit_window_value: f64,
+    /// Windows edited for the custom mode, kept while another mode is active.
+    #[serde(default = "HitWindow::new")]
+    pub custom_hit_window: HitWindow,
     /// Aspect ratio mode.
     pub aspect_ratio_mode: AspectRatioMode,
     /// Current skin name.
     pub current_skin: String,
+    /// Pitch behaviour when playing at a rate other than 1.0x.
+    #[serde(default)]
+    pub rate_mode: RateMode,
+    /// Scratch lane placement for BMS charts (experimental import).
+    #[serde(default)]
+    pub bms_scratch: BmsScratchMode,
+    /// Mine and burst conversion of osu! charts (off by default).
+    #[serde(default)]
+    pub osu_convert: OsuConvertRules,
+    /// Threads parsing charts during a library scan (0 = one per CPU core).
+    #[serde(default)]
+    pub scan_workers: usize,
+    /// Rules of the "Prune replays" maintenance command.
+    #[serde(default)]
+    pub replay_prune: ReplayPruneRules,
+    /// Song select leaderboard filters and sorting.
+    #[serde(default)]
+    pub leaderboard: LeaderboardOptions,
+    /// Text copied by the result screen's "Copy result" button.
+    /// Placeholders: {map} {rate} {judge} {score} {acc} {combo} {judgements}.
+    #[serde(default = "default_share_template")]
+    pub share_template: String,
+    /// Darkening of the song background in gameplay (0.0 - 1.0).
+    #[serde(default = "default_background_dim")]
+    pub background_dim: f32,
+    /// Gaussian blur radius of the gameplay background (0 = off).
+    #[serde(default)]
+    pub background_blur: f32,
+    /// Windowed, borderless or exclusive fullscreen.
+    #[serde(default)]
+    pub display_mode: DisplayMode,
+    /// Monitor used by the fullscreen modes (index in the system list).
+    #[serde(default)]
+    pub monitor: usize,
+    /// Window size in windowed mode, video mode in exclusive fullscreen.
+    #[serde(default = "default_resolution")]
+    pub resolution: (u32, u32),
+    /// Swapchain present mode.
+    #[serde(default)]
+    pub present_mode: PresentModeSetting,
+    /// Frame limiter in the render loop (0 = uncapped).
+    #[serde(default)]
+    pub fps_cap: u32,
+    /// Experimental: encode every play to an mp4 in `recordings/` (needs
+    /// ffmpeg in the PATH).
+    #[serde(default)]
+    pub record_gameplay: bool,
+    /// Frame rate of the recordings.
+    #[serde(default = "default_record_fps")]
+    pub record_fps: u32,
+    /// Auto-playing mini playfield of the selected chart in song select.
+    #[serde(default = "default_chart_preview")]
+    pub chart_preview: bool,
+    /// Song list scrolling animation.
+    #[serde(default)]
+    pub song_list_scroll: SongListScroll,
+    /// Judgement flash, panel and hit bar colors laid over the skin's.
+    #[serde(default)]
+    pub judgement_palette: JudgementPalette,
+    /// Show titles and artists in their original script (e.g. Japanese)
+    /// instead of romanized, when the charts give both.
+    #[serde(default)]
+    pub unicode_metadata: bool,
+    /// Interface language, a file name of `locales/` (see `shared::locale`).
+    #[serde(default = "default_language")]
+    pub language: String,
+    /// Show what is being played on the player's Discord profile.
+    #[serde(default)]
+    pub discord_presence: bool,
+    /// Tournament lockout. Its judge is the hit window above, which cannot be
+    /// changed while it is enabled.
+    #[serde(default)]
+    pub tournament: TournamentSettings,
+    /// Base URL of the beatmap mirror used by "Get songs" (empty = disabled).
+    #[serde(default = "default_mirror_url")]
+    pub mirror_url: String,
+    /// Folders the library is scanned from (e.g. an osu! `Songs` folder).
+    /// Downloads and imported archives go to the first one.
+    #[serde(default = "default_library_roots")]
+    pub library_roots: Vec<PathBuf>,
+    /// Active player profile (scopes replays, goals and keybinds).
+    #[serde(default = "default_profile_id")]
+    pub profile_id: i64,
+
+    /// Lane cover heights of the active profile.
+    #[serde(default)]
+    pub lane_cover: LaneCover,
+    /// Lane covers of the inactive profiles, keyed by profile id.
+    #[serde(default)]
+    pub profile_lane_covers: HashMap<String, LaneCover>,
 
     /// Keybinds per key count (key = "4", "5", etc.).
     pub keybinds: HashMap<String, Vec<String>>,
+    /// Keybinds of the inactive profiles, keyed by profile id.
+    #[serde(default)]
+    pub profile_keybinds: HashMap<String, HashMap<String, Vec<String>>>,
+    /// Gamepad buttons per key count, same layout as `keybinds`.
+    #[serde(default = "SettingsState::default_pad_keybinds")]
+    pub pad_keybinds: HashMap<String, Vec<String>>,
+    /// Custom lane order per key count: entry `c` is the lane chart column `c`
+    /// is played on. Missing or invalid entries keep the chart's order.
+    #[serde(default)]
+    pub lane_orders: HashMap<String, Vec<usize>>,
+
+    /// Saved gameplay presets.
+    #[serde(default)]
+    pub presets: Vec<GameplayPreset>,
+    /// Preset last saved or loaded.
+    #[serde(default)]
+    pub active_preset: Option<String>,
 
     /// Whether settings panel is open (UI state, not persisted).
     #[serde(skip)]
@@ -58,24 +704,212 @@ pub struct SettingsState {
     /// Buffer for keys being captured during remapping.
     #[serde(skip)]
     pub remapping_buffer: Vec<String>,
+    /// Whether the capture targets gamepad buttons instead of keys.
+    #[serde(skip)]
+    pub remapping_pad: bool,
+    /// Single column being rebound (`None` = capture every column in order).
+    #[serde(skip)]
+    pub remapping_slot: Option<usize>,
+    /// Set when a capture or reset changed the bindings; the settings window
+    /// applies them right away.
+    #[serde(skip)]
+    pub keybinds_changed: bool,
+    /// Keymode shown by the library overview (`None` = all).
+    #[serde(skip)]
+    pub library_key_mode: Option<i32>,
+    /// Lane picked in the lane order editor, waiting for the one to swap it
+    /// with: `(key count, lane)`.
+    #[serde(skip)]
+    pub lane_swap_from: Option<(usize, usize)>,
+    /// Name typed in the "new profile" field.
+    #[serde(skip)]
+    pub new_profile_name: String,
+
+    #[serde(skip)]
+    pub new_preset_name: String,
+    /// Page shown by the settings window.
+    #[serde(skip)]
+    pub settings_category: SettingsCategory,
+    /// Settings window search text (searches every page).
+    #[serde(skip)]
+    pub settings_search: String,
+}
+
+/// Default beatmap mirror (osu! API v2 compatible search).
+pub const DEFAULT_MIRROR_URL: &str = "https://catboy.best";
+
+fn default_mirror_url() -> String {
+    DEFAULT_MIRROR_URL.to_string()
+}
+
+/// Library folder used when none is configured.
+pub const DEFAULT_SONGS_DIR: &str = "songs";
+
+fn default_library_roots() -> Vec<PathBuf> {
+    vec![PathBuf::from(DEFAULT_SONGS_DIR)]
+}
+
+fn default_background_dim() -> f32 {
+    0.8
+}
+
+fn default_resolution() -> (u32, u32) {
+    (1280, 720)
+}
+
+fn default_record_fps() -> u32 {
+    60
+}
+
+fn default_language() -> String {
+    DEFAULT_LANGUAGE.to_string()
+}
+
+fn default_chart_preview() -> bool {
+    true
+}
+
+fn default_partial_results() -> bool {
+    true
+}
+
+fn default_volume() -> f32 {
+    1.0
+}
+
+fn default_profile_id() -> i64 {
+    DEFAULT_PROFILE_ID
+}
+
+/// Default share text, one line for chat.
+pub const DEFAULT_SHARE_TEMPLATE: &str =
+    "{map} ({rate}x, {judge}) - {acc}% | {score} | {judgements}";
+
+fn default_share_template() -> String {
+    DEFAULT_SHARE_TEMPLATE.to_string()
+}
+
+/// Settings that change how chart files are read, handed to the loaders by
+/// their callers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ChartRules {
+    pub bms_scratch: BmsScratchMode,
+    pub osu_convert: OsuConvertRules,
+}
+
+/// Settings of a library scan, handed to the database thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ScanOptions {
+    pub rules: ChartRules,
+    /// Threads parsing set folders (0 = one per CPU core).
+    pub workers: usize,
 }
 
 impl SettingsState {
+    /// Rules the chart loaders apply.
+    pub fn chart_rules(&self) -> ChartRules {
+        ChartRules {
+            bms_scratch: self.bms_scratch,
+            osu_convert: self.osu_convert,
+        }
+    }
+
+    /// Rules and thread count of library scans.
+    pub fn scan_options(&self) -> ScanOptions {
+        ScanOptions {
+            rules: self.chart_rules(),
+            workers: self.scan_workers,
+        }
+    }
+
+    /// Master, music and effects volumes, for the audio thread.
+    pub fn volumes(&self) -> Volumes {
+        Volumes {
+            master: self.master_volume,
+            music: self.music_volume,
+            effects: self.effects_volume,
+        }
+    }
+
+    pub fn set_volumes(&mut self, volumes: Volumes) {
+        self.master_volume = volumes.master;
+        self.music_volume = volumes.music;
+        self.effects_volume = volumes.effects;
+    }
+
+    pub fn volume_mut(&mut self, channel: VolumeChannel) -> &mut f32 {
+        match channel {
+            VolumeChannel::Master => &mut self.master_volume,
+            VolumeChannel::Music => &mut self.music_volume,
+            VolumeChannel::Effects => &mut self.effects_volume,
+        }
+    }
+
     /// Creates default settings.
     pub fn new() -> Self {
         Self {
             master_volume: 0.5,
+            music_volume: default_volume(),
+            effects_volume: default_volume(),
             scroll_speed: 500.0,
+            scroll_speeds: HashMap::new(),
+            scroll_speed_mode: ScrollSpeedMode::default(),
+            disable_sv: false,
+            partial_results: true,
+            mouse_input: false,
             hit_window_mode: HitWindowMode::OsuOD,
             hit_window_value: 5.0,
+            custom_hit_window: HitWindow::new(),
             aspect_ratio_mode: AspectRatioMode::Auto,
             current_skin: "default".to_string(),
+            rate_mode: RateMode::default(),
+            bms_scratch: BmsScratchMode::default(),
+            osu_convert: OsuConvertRules::default(),
+            scan_workers: 0,
+            replay_prune: ReplayPruneRules::default(),
+            leaderboard: LeaderboardOptions::default(),
+            share_template: default_share_template(),
+            background_dim: default_background_dim(),
+            background_blur: 0.0,
+            display_mode: DisplayMode::default(),
+            monitor: 0,
+            resolution: default_resolution(),
+            present_mode: PresentModeSetting::default(),
+            fps_cap: 0,
+            record_gameplay: false,
+            record_fps: default_record_fps(),
+            chart_preview: default_chart_preview(),
+            song_list_scroll: SongListScroll::default(),
+            judgement_palette: JudgementPalette::default(),
+            unicode_metadata: false,
+            language: default_language(),
+            discord_presence: false,
+            tournament: TournamentSettings::default(),
+            mirror_url: default_mirror_url(),
+            library_roots: default_library_roots(),
+            profile_id: DEFAULT_PROFILE_ID,
+            lane_cover: LaneCover::default(),
+            profile_lane_covers: HashMap::new(),
             keybinds: Self::default_keybinds(),
+            profile_keybinds: HashMap::new(),
+            pad_keybinds: Self::default_pad_keybinds(),
+            lane_orders: HashMap::new(),
+            presets: Vec::new(),
+            active_preset: None,
 
             is_open: false,
             show_keybindings: false,
             remapping_column: None,
             remapping_buffer: Vec::new(),
+            remapping_pad: false,
+            remapping_slot: None,
+            keybinds_changed: false,
+            library_key_mode: None,
+            lane_swap_from: None,
+            new_profile_name: String::new(),
+            new_preset_name: String::new(),
+            settings_category: SettingsCategory::default(),
+            settings_search: String::new(),
         }
     }
 
@@ -87,15 +921,34 @@ impl SettingsState {
                 settings.show_keybindings = false;
                 settings.remapping_column = None;
                 settings.remapping_buffer = Vec::new();
+                settings.remapping_pad = false;
 
                 if settings.keybinds.is_empty() {
                     settings.keybinds = Self::default_keybinds();
                 }
+                if let HitWindowMode::Custom(windows) = settings.hit_window_mode
+                    && let Err(e) = windows.validate()
+                {
+                    eprintln!("Invalid custom hit windows ({e}), using OD 5.");
+                    settings.hit_window_mode = HitWindowMode::OsuOD;
+                    settings.hit_window_value = 5.0;
+                }
+                settings.ensure_presets();
                 return settings;
             }
             eprintln!("Failed to parse settings.toml, using defaults.");
         }
-        Self::new()
+        let mut settings = Self::new();
+        settings.ensure_presets();
+        settings
+    }
+
+    /// Settings from before presets existed become the "Default" preset.
+    fn ensure_presets(&mut self) {
+        if self.presets.is_empty() {
+            self.presets.push(self.capture_preset(DEFAULT_PRESET_NAME));
+            self.active_preset = Some(DEFAULT_PRESET_NAME.to_string());
+        }
     }
 
     /// Saves settings to `settings.toml`.
@@ -113,18 +966,188 @@ impl SettingsState {
     /// Resets keybinds to defaults.
     pub fn reset_keybinds(&mut self) {
         self.keybinds = Self::default_keybinds();
+        self.pad_keybinds = Self::default_pad_keybinds();
+        self.keybinds_changed = true;
+    }
+
+    /// Library folders to scan, without duplicates. Falls back to `songs/`
+    /// when the list is empty.
+    pub fn song_roots(&self) -> Vec<PathBuf> {
+        let mut roots: Vec<PathBuf> = Vec::new();
+        for root in &self.library_roots {
+            if !root.as_os_str().is_empty() && !roots.contains(root) {
+                roots.push(root.clone());
+            }
+        }
+        if roots.is_empty() {
+            roots = default_library_roots();
+        }
+        roots
+    }
+
+    /// Folder downloads and imported archives are extracted into.
+    pub fn import_root(&self) -> PathBuf {
+        self.song_roots().swap_remove(0)
+    }
+
+    /// Scroll speed (ms) for charts with `key_count` keys.
+    pub fn scroll_speed_for(&self, key_count: usize) -> f64 {
+        self.scroll_speeds
+            .get(&key_count.to_string())
+            .copied()
+            .unwrap_or(self.scroll_speed)
+    }
+
+    /// The current gameplay settings, as a preset named `name`.
+    pub fn capture_preset(&self, name: &str) -> GameplayPreset {
+        GameplayPreset {
+            name: name.to_string(),
+            scroll_speed: self.scroll_speed,
+            scroll_speeds: self.scroll_speeds.clone(),
+            hit_window_mode: self.hit_window_mode,
+            hit_window_value: self.hit_window_value,
+            lane_cover: self.lane_cover,
+            background_dim: self.background_dim,
+        }
+    }
+
+    /// Saves the current gameplay settings as `name`, replacing a preset of
+    /// the same name.
+    pub fn save_preset(&mut self, name: &str) {
+        let preset = self.capture_preset(name);
+        match self.presets.iter_mut().find(|p| p.name == name) {
+            Some(existing) => *existing = preset,
+            None => self.presets.push(preset),
+        }
+        self.active_preset = Some(name.to_string());
+    }
+
+    /// Loads the preset `name`. Returns `false` if there is none.
+    pub fn apply_preset(&mut self, name: &str) -> bool {
+        let Some(preset) = self.presets.iter().find(|p| p.name == name).cloned() else {
+            return false;
+        };
+        self.scroll_speed = preset.scroll_speed;
+        self.scroll_speeds = preset.scroll_speeds;
+        self.hit_window_mode = preset.hit_window_mode;
+        self.hit_window_value = preset.hit_window_value;
+        self.lane_cover = preset.lane_cover;
+        self.background_dim = preset.background_dim;
+        self.active_preset = Some(preset.name);
+        true
+    }
+
+    pub fn delete_preset(&mut self, name: &str) {
+        self.presets.retain(|p| p.name != name);
+        if self.active_preset.as_deref() == Some(name) {
+            self.active_preset = None;
+        }
+    }
+
+    /// Whether the settings drifted from the active preset since it was
+    /// loaded or saved.
+    pub fn preset_modified(&self) -> bool {
+        self.active_preset.as_deref().is_some_and(|name| {
+            self.presets
+                .iter()
+                .find(|p| p.name == name)
+                .is_none_or(|preset| *preset != self.capture_preset(name))
+        })
+    }
+
+    /// Lane of each chart column for `key_count` keys, when a custom order
+    /// other than the chart's own is set.
+    pub fn lane_order(&self, key_count: usize) -> Option<Vec<usize>> {
+        let order = self.lane_orders.get(&key_count.to_string())?;
+        let valid = order.len() == key_count && (0..key_count).all(|lane| order.contains(&lane));
+        let identity = order
+            .iter()
+            .enumerate()
+            .all(|(column, &lane)| column == lane);
+        (valid && !identity).then(|| order.clone())
+    }
+
+    /// Swaps the chart columns played on lanes `a` and `b` of a keymode.
+    pub fn swap_lanes(&mut self, key_count: usize, a: usize, b: usize) {
+        let key = key_count.to_string();
+        let mut order = self
+            .lane_order(key_count)
+            .unwrap_or_else(|| (0..key_count).collect());
+        let column_a = order.iter().position(|&lane| lane == a);
+        let column_b = order.iter().position(|&lane| lane == b);
+        if let (Some(column_a), Some(column_b)) = (column_a, column_b) {
+            order.swap(column_a, column_b);
+        }
+        if order
+            .iter()
+            .enumerate()
+            .all(|(column, &lane)| column == lane)
+        {
+            self.lane_orders.remove(&key);
+        } else {
+            self.lane_orders.insert(key, order);
+        }
+    }
+
+    /// Writes only the keybinds to `settings.toml`, leaving the other
+    /// settings on disk untouched (they are saved when the settings window closes).
+    pub fn save_keybinds(&self) {
+        let mut disk = Self::load();
+        disk.keybinds = self.keybinds.clone();
+        disk.pad_keybinds = self.pad_keybinds.clone();
+        disk.save();
+    }
+
+    /// Makes `id` the active profile: the current keybinds and lane cover are
+    /// stored under the previous profile and the new profile's own are restored.
+    pub fn switch_profile(&mut self, id: i64) {
+        if id == self.profile_id {
+            return;
+        }
+        let previous = std::mem::take(&mut self.keybinds);
+        self.profile_keybinds
+            .insert(self.profile_id.to_string(), previous);
+        self.keybinds = self
+            .profile_keybinds
+            .remove(&id.to_string())
+            .filter(|binds| !binds.is_empty())
+            .unwrap_or_else(Self::default_keybinds);
+        self.profile_lane_covers
+            .insert(self.profile_id.to_string(), self.lane_cover);
+        self.lane_cover = self
+            .profile_lane_covers
+            .remove(&id.to_string())
+            .unwrap_or_default();
+        self.profile_id = id;
+        self.cancel_keybind_capture();
     }
 
     /// Begins capturing keybinds for a specific column count.
     pub fn begin_keybind_capture(&mut self, columns: usize) {
         self.remapping_column = Some(columns);
         self.remapping_buffer.clear();
+        self.remapping_pad = false;
+        self.remapping_slot = None;
+    }
+
+    /// Begins capturing a single key (or button) for one column of a keymode.
+    pub fn begin_slot_capture(&mut self, columns: usize, slot: usize, pad: bool) {
+        self.begin_keybind_capture(columns);
+        self.remapping_pad = pad;
+        self.remapping_slot = Some(slot);
+    }
+
+    /// Begins capturing gamepad buttons for a specific column count.
+    pub fn begin_pad_capture(&mut self, columns: usize) {
+        self.begin_keybind_capture(columns);
+        self.remapping_pad = true;
     }
 
     /// Cancels the current keybind capture.
     pub fn cancel_keybind_capture(&mut self) {
         self.remapping_column = None;
         self.remapping_buffer.clear();
+        self.remapping_slot = None;
     }
 
     /// Adds a key to the capture buffer during remapping.
@@ -133,17 +1156,92 @@ impl SettingsState {
             return;
         };
 
+        if let Some(slot) = self.remapping_slot {
+            let binds = if self.remapping_pad {
+                &mut self.pad_keybinds
+            } else {
+                &mut self.keybinds
+            };
+            let entry = binds.entry(target_columns.to_string()).or_default();
+            if entry.len() <= slot {
+                entry.resize(slot + 1, String::new());
+            }
+            entry[slot] = key_label;
+            self.cancel_keybind_capture();
+            self.keybinds_changed = true;
+            return;
+        }
+
         if !self.remapping_buffer.contains(&key_label) {
             self.remapping_buffer.push(key_label);
         }
 
         if self.remapping_buffer.len() >= target_columns {
             let column_key = target_columns.to_string();
-            self.keybinds
-                .insert(column_key, self.remapping_buffer.clone());
+            let binds = if self.remapping_pad {
+                &mut self.pad_keybinds
+            } else {
+                &mut self.keybinds
+            };
+            binds.insert(column_key, self.remapping_buffer.clone());
             self.remapping_buffer.clear();
             self.remapping_column = None;
+            self.keybinds_changed = true;
+        }
+    }
+
+    /// Lists binding problems, one line each: a key bound to several columns
+    /// of the same keymode, or a column key shadowing a global shortcut.
+    pub fn keybind_conflicts(&self) -> Vec<String> {
+        let mut conflicts = Vec::new();
+        for (binds, device) in [(&self.keybinds, ""), (&self.pad_keybinds, " pad")] {
+            let mut modes: Vec<_> = binds.iter().collect();
+            modes.sort_by_key(|(mode, _)| mode.parse::<usize>().unwrap_or(0));
+            for (mode, labels) in modes {
+                for (column, label) in labels.iter().enumerate() {
+                    if label.is_empty() {
+                        continue;
+                    }
+                    if let Some(other) = labels[..column].iter().position(|l| l == label) {
+                        conflicts.push(format!(
+                            "{}K{}: {} is bound to columns {} and {}",
+                            mode,
+                            device,
+                            label,
+                            other + 1,
+                            column + 1
+                        ));
+                    }
+                    if let Some((_, action)) = RESERVED_BINDS.iter().find(|(key, _)| key == label) {
+                        conflicts.push(format!(
+                            "{}K{}: {} (column {}) overrides {}",
+                            mode,
+                            device,
+                            label,
+                            column + 1,
+                            action
+                        ));
+                    }
+                }
+            }
         }
+        conflicts
+    }
+
+    /// Returns the default gamepad buttons (4K only: d-pad left/down, then
+    /// the two bottom face buttons).
+    fn default_pad_keybinds() -> HashMap<String, Vec<String>> {
+        let mut map = HashMap::new();
+        map.insert(
+            "4".to_string(),
+            vec![
+                "PadDPadLeft".to_string(),
+                "PadDPadDown".to_string(),
+                "PadSouth".to_string(),
+                "PadEast".to_string(),
+            ],
+        );
+        map
     }
 
     /// Returns the default keybinds for 4K, 5K, 6K, and 7K.
@@ -20,6 +20,11 @@ impl Vec2Conf {
 /// RGBA color type
 pub type Color = [f32; 4];
 
+/// Fully opaque, the default opacity of playfield and HUD elements.
+pub fn default_opacity() -> f32 {
+    1.0
+}
+
 /// Common color constants
 pub mod colors {
     use super::Color;
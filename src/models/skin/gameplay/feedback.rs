@@ -0,0 +1,78 @@
+//! Miss feedback: playfield shake and vignette flash.
+
+use crate::models::skin::common::Color;
+use serde::{Deserialize, Serialize};
+
+fn default_min_combo() -> u32 {
+    20
+}
+fn default_shake_intensity() -> f32 {
+    8.0
+}
+fn default_shake_duration_ms() -> f32 {
+    150.0
+}
+fn default_vignette_color() -> Color {
+    [1.0, 0.0, 0.0, 0.45]
+}
+fn default_vignette_duration_ms() -> f32 {
+    300.0
+}
+
+/// What sets the effects off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FeedbackTrigger {
+    /// Every miss.
+    Miss,
+    /// Losing a combo of at least `min_combo`.
+    #[default]
+    ComboBreak,
+}
+
+/// Effects played on a miss or a combo break. Both are off by default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedbackConfig {
+    #[serde(default)]
+    pub trigger: FeedbackTrigger,
+
+    /// Smallest combo whose break triggers the effects.
+    #[serde(default = "default_min_combo")]
+    pub min_combo: u32,
+
+    /// Shakes the notes and receptors.
+    #[serde(default)]
+    pub shake: bool,
+
+    /// Largest shake offset, in pixels at 1080p.
+    #[serde(default = "default_shake_intensity")]
+    pub shake_intensity: f32,
+
+    #[serde(default = "default_shake_duration_ms")]
+    pub shake_duration_ms: f32,
+
+    /// Darkens the screen edges with `vignette_color`.
+    #[serde(default)]
+    pub vignette: bool,
+
+    /// Color at the very edge; its alpha is the strength of the flash.
+    #[serde(default = "default_vignette_color")]
+    pub vignette_color: Color,
+
+    #[serde(default = "default_vignette_duration_ms")]
+    pub vignette_duration_ms: f32,
+}
+
+impl Default for FeedbackConfig {
+    fn default() -> Self {
+        Self {
+            trigger: FeedbackTrigger::default(),
+            min_combo: default_min_combo(),
+            shake: false,
+            shake_intensity: default_shake_intensity(),
+            shake_duration_ms: default_shake_duration_ms(),
+            vignette: false,
+            vignette_color: default_vignette_color(),
+            vignette_duration_ms: default_vignette_duration_ms(),
+        }
+    }
+}
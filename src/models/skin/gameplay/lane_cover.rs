@@ -0,0 +1,24 @@
+//! Lane cover (sudden+ / hidden+) configuration.
+
+use crate::models::skin::common::Color;
+use serde::{Deserialize, Serialize};
+
+fn default_color() -> Color {
+    [0.0, 0.0, 0.0, 1.0]
+}
+
+/// Look of the covers hiding the top (sudden) or bottom (hidden) of the
+/// playfield. Their height is a player setting, not part of the skin.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaneCoverConfig {
+    #[serde(default = "default_color")]
+    pub color: Color,
+}
+
+impl Default for LaneCoverConfig {
+    fn default() -> Self {
+        Self {
+            color: default_color(),
+        }
+    }
+}
@@ -1,11 +1,15 @@
 //! Gameplay module containing playfield, notes, and receptor configurations.
 
+pub mod feedback;
 pub mod key_modes;
+pub mod lane_cover;
 pub mod notes;
 pub mod playfield;
 pub mod receptors;
 
+pub use feedback::FeedbackConfig;
 pub use key_modes::KeyModeConfig;
+pub use lane_cover::LaneCoverConfig;
 pub use notes::NotesDefaults;
 pub use playfield::PlayfieldConfig;
 pub use receptors::ReceptorDefaults;
@@ -23,4 +27,10 @@ pub struct GameplayDefaults {
 
     #[serde(default)]
     pub receptors: ReceptorDefaults,
+
+    #[serde(default)]
+    pub lane_cover: LaneCoverConfig,
+
+    #[serde(default)]
+    pub feedback: FeedbackConfig,
 }
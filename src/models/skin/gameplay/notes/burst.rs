@@ -12,6 +12,15 @@ fn default_body_size() -> Vec2Conf {
 fn default_end_size() -> Vec2Conf {
     Vec2Conf { x: 90.0, y: 30.0 }
 }
+fn default_show_counter() -> bool {
+    true
+}
+fn default_counter_scale() -> f32 {
+    32.0
+}
+fn default_counter_color() -> Color {
+    [1.0, 1.0, 1.0, 1.0]
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BurstConfig {
@@ -24,13 +33,32 @@ pub struct BurstConfig {
     #[serde(default = "default_end_size")]
     pub end_size: Vec2Conf,
 
-    /// Image for burst body (stretched)
+    /// Image for burst body, repeated down its length
     #[serde(default)]
     pub body_image: Option<String>,
 
+    /// Stretch the body image over the whole burst instead of repeating it
+    #[serde(default)]
+    pub stretch_body: bool,
+
     /// Image for burst end (tail)
     #[serde(default)]
     pub end_image: Option<String>,
+
+    /// Show the hits still needed over the head of the burst
+    #[serde(default = "default_show_counter")]
+    pub show_counter: bool,
+
+    /// Font size of the hit counter
+    #[serde(default = "default_counter_scale")]
+    pub counter_scale: f32,
+
+    #[serde(default = "default_counter_color")]
+    pub counter_color: Color,
+
+    /// Offset of the hit counter from the center of the head, in pixels
+    #[serde(default)]
+    pub counter_offset: Vec2Conf,
 }
 
 impl Default for BurstConfig {
@@ -40,7 +68,12 @@ impl Default for BurstConfig {
             body_size: default_body_size(),
             end_size: default_end_size(),
             body_image: None,
+            stretch_body: false,
             end_image: None,
+            show_counter: default_show_counter(),
+            counter_scale: default_counter_scale(),
+            counter_color: default_counter_color(),
+            counter_offset: Vec2Conf::default(),
         }
     }
 }
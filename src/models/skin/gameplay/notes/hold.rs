@@ -25,10 +25,14 @@ pub struct HoldConfig {
     #[serde(default = "default_end_size")]
     pub end_size: Vec2Conf,
 
-    /// Image for hold body (stretched vertically)
+    /// Image for hold body, repeated down its length
     #[serde(default)]
     pub body_image: Option<String>,
 
+    /// Stretch the body image over the whole hold instead of repeating it
+    #[serde(default)]
+    pub stretch_body: bool,
+
     /// Image for hold end (tail)
     #[serde(default)]
     pub end_image: Option<String>,
@@ -41,6 +45,7 @@ impl Default for HoldConfig {
             body_width: default_body_width(),
             end_size: default_end_size(),
             body_image: None,
+            stretch_body: false,
             end_image: None,
         }
     }
@@ -1,6 +1,6 @@
 //! Playfield configuration.
 
-use crate::models::skin::common::Vec2Conf;
+use crate::models::skin::common::{Vec2Conf, default_opacity};
 use serde::{Deserialize, Serialize};
 
 fn default_position() -> Vec2Conf {
@@ -45,6 +45,18 @@ pub struct PlayfieldConfig {
     /// Optional background image for the playfield lane
     #[serde(default)]
     pub lane_image: Option<String>,
+
+    /// Opacity of tap notes, hold / burst ends and mines (0 - 1)
+    #[serde(default = "default_opacity")]
+    pub note_opacity: f32,
+
+    /// Opacity of hold and burst bodies (0 - 1)
+    #[serde(default = "default_opacity")]
+    pub hold_body_opacity: f32,
+
+    /// Opacity of the receptors (0 - 1)
+    #[serde(default = "default_opacity")]
+    pub receptor_opacity: f32,
 }
 
 impl Default for PlayfieldConfig {
@@ -57,6 +69,9 @@ impl Default for PlayfieldConfig {
             receptor_size: default_receptor_size(),
             hit_position_y: default_hit_position_y(),
             lane_image: None,
+            note_opacity: default_opacity(),
+            hold_body_opacity: default_opacity(),
+            receptor_opacity: default_opacity(),
         }
     }
 }
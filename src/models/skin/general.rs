@@ -1,6 +1,7 @@
 //! General skin metadata.
 
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
 /// General skin information (name, author, version, font)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,6 +11,118 @@ pub struct SkinGeneral {
     pub author: String,
     #[serde(default)]
     pub font: Option<String>,
+    /// Features the skin relies on (`[capabilities]` table).
+    #[serde(default)]
+    pub capabilities: SkinCapabilities,
+    /// Optional sound effects (`[sounds]` table).
+    #[serde(default)]
+    pub sounds: SkinSounds,
+}
+
+/// UI sound effects a skin can provide. Paths are relative to the skin
+/// folder; missing entries stay silent.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SkinSounds {
+    pub menu_move: Option<String>,
+    pub menu_confirm: Option<String>,
+    /// Course stage cleared.
+    pub section_pass: Option<String>,
+    /// Course stage failed.
+    pub section_fail: Option<String>,
+    /// Result screen reached.
+    pub applause: Option<String>,
+}
+
+/// Moments that trigger a skin sound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UiSound {
+    MenuMove,
+    MenuConfirm,
+    SectionPass,
+    SectionFail,
+    Applause,
+}
+
+impl SkinSounds {
+    /// File to play for `sound`, resolved against the skin folder.
+    pub fn resolve(&self, base_path: &Path, sound: UiSound) -> Option<PathBuf> {
+        let name = match sound {
+            UiSound::MenuMove => &self.menu_move,
+            UiSound::MenuConfirm => &self.menu_confirm,
+            UiSound::SectionPass => &self.section_pass,
+            UiSound::SectionFail => &self.section_fail,
+            UiSound::Applause => &self.applause,
+        };
+        name.as_ref()
+            .map(|name| base_path.join(name))
+            .filter(|path| path.exists())
+    }
+}
+
+/// Animated elements (frame sequences) are not rendered by this build.
+const ENGINE_SUPPORTS_ANIMATIONS: bool = false;
+/// Skin-provided WGSL shaders are not loaded by this build.
+const ENGINE_SUPPORTS_CUSTOM_SHADERS: bool = false;
+
+/// What a skin declares it needs from the engine. Unsupported features are
+/// skipped with a warning instead of failing the skin; `menus` and `hud`
+/// tell the editor which panels are relevant.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SkinCapabilities {
+    pub uses_animations: bool,
+    pub uses_custom_shaders: bool,
+    /// Oldest game version the skin works with ("0.2.0").
+    pub min_engine_version: Option<String>,
+    /// The skin styles the menus (song select, result screen).
+    pub menus: bool,
+    /// The skin styles the gameplay HUD.
+    pub hud: bool,
+}
+
+impl Default for SkinCapabilities {
+    fn default() -> Self {
+        Self {
+            uses_animations: false,
+            uses_custom_shaders: false,
+            min_engine_version: None,
+            menus: true,
+            hud: true,
+        }
+    }
+}
+
+impl SkinCapabilities {
+    /// One warning per declared feature this build cannot honour.
+    pub fn unsupported(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.uses_animations && !ENGINE_SUPPORTS_ANIMATIONS {
+            warnings.push("animations are not supported, static images are used".to_string());
+        }
+        if self.uses_custom_shaders && !ENGINE_SUPPORTS_CUSTOM_SHADERS {
+            warnings.push("custom shaders are not supported, default shaders are used".to_string());
+        }
+        if let Some(required) = &self.min_engine_version
+            && parse_version(required) > parse_version(env!("CARGO_PKG_VERSION"))
+        {
+            warnings.push(format!(
+                "made for version {} (this is {}), some elements may be missing",
+                required,
+                env!("CARGO_PKG_VERSION")
+            ));
+        }
+        warnings
+    }
+}
+
+/// "1.2.3" -> [1, 2, 3]; missing or invalid parts count as 0.
+fn parse_version(version: &str) -> [u32; 3] {
+    let mut parts = [0; 3];
+    for (part, value) in parts.iter_mut().zip(version.trim().split('.')) {
+        *part = value.parse().unwrap_or(0);
+    }
+    parts
 }
 
 impl Default for SkinGeneral {
@@ -19,6 +132,8 @@ impl Default for SkinGeneral {
             version: "1.0".to_string(),
             author: "System".to_string(),
             font: Some("font.ttf".to_string()),
+            capabilities: SkinCapabilities::default(),
+            sounds: SkinSounds::default(),
         }
     }
 }
@@ -1,6 +1,8 @@
 //! Accuracy display configuration.
 
-use crate::models::skin::common::{Color, Vec2Conf};
+use crate::models::skin::common::{Color, Vec2Conf, default_opacity};
+use crate::models::skin::hud::DigitAlign;
+use crate::models::skin::hud::anchor::{Anchor, PositionUnits};
 use serde::{Deserialize, Serialize};
 
 fn default_position() -> Vec2Conf {
@@ -15,6 +17,9 @@ fn default_size() -> Vec2Conf {
 fn default_color() -> Color {
     [1.0, 1.0, 1.0, 1.0]
 } // White
+fn default_digit_align() -> DigitAlign {
+    DigitAlign::Left
+}
 fn default_scale() -> f32 {
     20.0
 }
@@ -24,6 +29,14 @@ pub struct AccuracyConfig {
     #[serde(default = "default_position")]
     pub position: Vec2Conf,
 
+    /// Point `position` is measured from (see [`Anchor`]).
+    #[serde(default)]
+    pub anchor: Anchor,
+
+    /// Unit of `position`: pixels, or percent of the screen size.
+    #[serde(default)]
+    pub units: PositionUnits,
+
     #[serde(default = "default_size")]
     pub size: Vec2Conf,
 
@@ -41,6 +54,18 @@ pub struct AccuracyConfig {
     #[serde(default = "default_format")]
     pub format: String,
 
+    /// Gap between digit sprites in pixels, negative to overlap them.
+    #[serde(default)]
+    pub digit_spacing: f32,
+
+    /// Side of the digit sprites the position anchors.
+    #[serde(default = "default_digit_align")]
+    pub digit_align: DigitAlign,
+
+    /// Alpha multiplier of the element, 0 (hidden) to 1 (opaque).
+    #[serde(default = "default_opacity")]
+    pub opacity: f32,
+
     #[serde(default = "default_true")]
     pub visible: bool,
 }
@@ -56,11 +81,16 @@ impl Default for AccuracyConfig {
     fn default() -> Self {
         Self {
             position: default_position(),
+            anchor: Anchor::default(),
+            units: PositionUnits::default(),
             size: default_size(),
             color: default_color(),
             scale: default_scale(),
             image: None,
             format: default_format(),
+            digit_spacing: 0.0,
+            digit_align: default_digit_align(),
+            opacity: default_opacity(),
             visible: true,
         }
     }
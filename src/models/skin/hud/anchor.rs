@@ -0,0 +1,187 @@
+//! Relative placement of HUD elements.
+//!
+//! By default an element's `position` is in pixels from the top-left corner
+//! of the screen. An `anchor` makes it an offset from another point of the
+//! screen or of the playfield, and `units = "percent"` measures that offset
+//! in percent of the screen size, so one skin fits every resolution:
+//!
+//! ```toml
+//! [nps]
+//! anchor = "playfield-right"
+//! position = { x = 20.0, y = 50.0 }
+//!
+//! [score]
+//! anchor = "top-right"
+//! units = "percent"
+//! position = { x = -2.0, y = 3.0 }
+//! ```
+
+use crate::models::skin::common::Vec2Conf;
+use serde::{Deserialize, Serialize};
+
+/// Point an element's position is measured from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+    /// Left edge of the playfield, top of the screen.
+    PlayfieldLeft,
+    /// Middle of the playfield, top of the screen.
+    PlayfieldCenter,
+    /// Right edge of the playfield, top of the screen.
+    PlayfieldRight,
+}
+
+impl Anchor {
+    pub const ALL: [Anchor; 12] = [
+        Anchor::TopLeft,
+        Anchor::Top,
+        Anchor::TopRight,
+        Anchor::Left,
+        Anchor::Center,
+        Anchor::Right,
+        Anchor::BottomLeft,
+        Anchor::Bottom,
+        Anchor::BottomRight,
+        Anchor::PlayfieldLeft,
+        Anchor::PlayfieldCenter,
+        Anchor::PlayfieldRight,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Anchor::TopLeft => "Top left",
+            Anchor::Top => "Top",
+            Anchor::TopRight => "Top right",
+            Anchor::Left => "Left",
+            Anchor::Center => "Center",
+            Anchor::Right => "Right",
+            Anchor::BottomLeft => "Bottom left",
+            Anchor::Bottom => "Bottom",
+            Anchor::BottomRight => "Bottom right",
+            Anchor::PlayfieldLeft => "Playfield left",
+            Anchor::PlayfieldCenter => "Playfield center",
+            Anchor::PlayfieldRight => "Playfield right",
+        }
+    }
+}
+
+/// Unit of an element's offset from its anchor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PositionUnits {
+    #[default]
+    Pixels,
+    /// Percent of the screen width (x) and height (y).
+    Percent,
+}
+
+/// Screen and playfield geometry the anchors resolve against.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HudLayout {
+    pub screen_width: f32,
+    pub screen_height: f32,
+    pub playfield_left: f32,
+    pub playfield_right: f32,
+}
+
+impl HudLayout {
+    /// `playfield_x` is the horizontal center of the playfield.
+    pub fn new(
+        screen_width: f32,
+        screen_height: f32,
+        playfield_x: f32,
+        playfield_width: f32,
+    ) -> Self {
+        Self {
+            screen_width,
+            screen_height,
+            playfield_left: playfield_x - playfield_width / 2.0,
+            playfield_right: playfield_x + playfield_width / 2.0,
+        }
+    }
+
+    /// Screen position in pixels of an element placed at `position` from
+    /// `anchor`.
+    pub fn resolve(&self, position: Vec2Conf, anchor: Anchor, units: PositionUnits) -> Vec2Conf {
+        let (w, h) = (self.screen_width, self.screen_height);
+        let origin = match anchor {
+            Anchor::TopLeft => (0.0, 0.0),
+            Anchor::Top => (w / 2.0, 0.0),
+            Anchor::TopRight => (w, 0.0),
+            Anchor::Left => (0.0, h / 2.0),
+            Anchor::Center => (w / 2.0, h / 2.0),
+            Anchor::Right => (w, h / 2.0),
+            Anchor::BottomLeft => (0.0, h),
+            Anchor::Bottom => (w / 2.0, h),
+            Anchor::BottomRight => (w, h),
+            Anchor::PlayfieldLeft => (self.playfield_left, 0.0),
+            Anchor::PlayfieldCenter => ((self.playfield_left + self.playfield_right) / 2.0, 0.0),
+            Anchor::PlayfieldRight => (self.playfield_right, 0.0),
+        };
+        let (dx, dy) = self.offset_to_pixels(units, position.x, position.y);
+        Vec2Conf::new(origin.0 + dx, origin.1 + dy)
+    }
+
+    /// Converts an offset in `units` to pixels.
+    pub fn offset_to_pixels(&self, units: PositionUnits, x: f32, y: f32) -> (f32, f32) {
+        match units {
+            PositionUnits::Pixels => (x, y),
+            PositionUnits::Percent => (
+                x * self.screen_width / 100.0,
+                y * self.screen_height / 100.0,
+            ),
+        }
+    }
+
+    /// Converts an offset in pixels to `units`, e.g. to move an element by a
+    /// mouse drag.
+    pub fn pixels_to_offset(&self, units: PositionUnits, x: f32, y: f32) -> (f32, f32) {
+        match units {
+            PositionUnits::Pixels => (x, y),
+            PositionUnits::Percent => (
+                x * 100.0 / self.screen_width.max(1.0),
+                y * 100.0 / self.screen_height.max(1.0),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn anchors_follow_the_screen_and_the_playfield() {
+        let layout = HudLayout::new(2560.0, 1440.0, 1280.0, 400.0);
+        let offset = Vec2Conf::new(20.0, 50.0);
+        let at = |anchor, units| {
+            let p = layout.resolve(offset, anchor, units);
+            (p.x, p.y)
+        };
+
+        assert_eq!(at(Anchor::TopLeft, PositionUnits::Pixels), (20.0, 50.0));
+        assert_eq!(
+            at(Anchor::PlayfieldRight, PositionUnits::Pixels),
+            (1500.0, 50.0)
+        );
+        assert_eq!(
+            at(Anchor::BottomRight, PositionUnits::Pixels),
+            (2580.0, 1490.0)
+        );
+        assert_eq!(at(Anchor::Center, PositionUnits::Percent), (1792.0, 1440.0));
+        assert_eq!(
+            layout.pixels_to_offset(PositionUnits::Percent, 512.0, 720.0),
+            (20.0, 50.0)
+        );
+    }
+}
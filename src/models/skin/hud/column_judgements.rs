@@ -0,0 +1,61 @@
+//! Per-column judgement counts configuration
+
+use crate::models::skin::common::{Color, Vec2Conf, default_opacity};
+use crate::models::skin::hud::anchor::{Anchor, PositionUnits};
+use serde::{Deserialize, Serialize};
+
+fn default_position() -> Vec2Conf {
+    Vec2Conf { x: 40.0, y: 420.0 }
+}
+fn default_scale() -> f32 {
+    16.0
+}
+fn default_header_color() -> Color {
+    [0.8, 0.8, 0.8, 1.0]
+}
+
+/// Mini table of the judgement counts of each lane, updated live: one
+/// column per lane, one row per judgement (ghost taps excluded), in the
+/// colors of the judgement panel. Off by default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnJudgementsConfig {
+    /// Top-left corner of the table.
+    #[serde(default = "default_position")]
+    pub position: Vec2Conf,
+
+    /// Point `position` is measured from (see [`Anchor`]).
+    #[serde(default)]
+    pub anchor: Anchor,
+
+    /// Unit of `position`: pixels, or percent of the screen size.
+    #[serde(default)]
+    pub units: PositionUnits,
+
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+
+    /// Color of the lane numbers above the table.
+    #[serde(default = "default_header_color")]
+    pub header_color: Color,
+
+    /// Alpha multiplier of the element, 0 (hidden) to 1 (opaque).
+    #[serde(default = "default_opacity")]
+    pub opacity: f32,
+
+    #[serde(default)]
+    pub visible: bool,
+}
+
+impl Default for ColumnJudgementsConfig {
+    fn default() -> Self {
+        Self {
+            position: default_position(),
+            anchor: Anchor::default(),
+            units: PositionUnits::default(),
+            scale: default_scale(),
+            header_color: default_header_color(),
+            opacity: default_opacity(),
+            visible: false,
+        }
+    }
+}
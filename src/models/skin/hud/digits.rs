@@ -0,0 +1,35 @@
+//! Number sprites for the score, combo and accuracy.
+//!
+//! A skin with `score-0.png` .. `score-9.png` in its folder draws these
+//! numbers with the images instead of the font. `score-dot.png`,
+//! `score-comma.png`, `score-percent.png` and `score-x.png` are optional;
+//! characters without an image are left out.
+
+use serde::{Deserialize, Serialize};
+
+/// Characters a skin can provide an image for.
+pub const DIGIT_GLYPHS: [char; 14] = [
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', '.', ',', '%', 'x',
+];
+
+/// File name of the image of a character, e.g. `score-7.png`.
+pub fn digit_file_name(glyph: char) -> Option<String> {
+    let name = match glyph {
+        '0'..='9' => glyph.to_string(),
+        '.' => "dot".to_string(),
+        ',' => "comma".to_string(),
+        '%' => "percent".to_string(),
+        'x' => "x".to_string(),
+        _ => return None,
+    };
+    Some(format!("score-{}.png", name))
+}
+
+/// Side of the number the element position anchors.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq)]
+pub enum DigitAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
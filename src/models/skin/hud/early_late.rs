@@ -0,0 +1,89 @@
+//! Early / late trend indicator configuration
+
+use crate::models::skin::common::{Color, Vec2Conf, default_opacity};
+use crate::models::skin::hud::anchor::{Anchor, PositionUnits};
+use serde::{Deserialize, Serialize};
+
+fn default_position() -> Vec2Conf {
+    Vec2Conf { x: 640.0, y: 360.0 }
+}
+fn default_samples() -> usize {
+    20
+}
+fn default_early_color() -> Color {
+    [0.4, 0.7, 1.0, 1.0]
+}
+fn default_late_color() -> Color {
+    [1.0, 0.6, 0.3, 1.0]
+}
+fn default_scale() -> f32 {
+    20.0
+}
+fn default_format() -> String {
+    "{arrow} {offset}".to_string()
+}
+
+/// Whether recent hits trend early or late: the mean offset of the last
+/// `samples` judged hits, drawn under the judgement flash. `{arrow}` becomes
+/// an arrow pointing towards the side the player drifts to, `{offset}` the
+/// mean offset in ms.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EarlyLateConfig {
+    /// Center of the text.
+    #[serde(default = "default_position")]
+    pub position: Vec2Conf,
+
+    /// Point `position` is measured from (see [`Anchor`]).
+    #[serde(default)]
+    pub anchor: Anchor,
+
+    /// Unit of `position`: pixels, or percent of the screen size.
+    #[serde(default)]
+    pub units: PositionUnits,
+
+    /// Number of recent hits averaged.
+    #[serde(default = "default_samples")]
+    pub samples: usize,
+
+    /// Color while hits trend early.
+    #[serde(default = "default_early_color")]
+    pub early_color: Color,
+
+    /// Color while hits trend late.
+    #[serde(default = "default_late_color")]
+    pub late_color: Color,
+
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+
+    #[serde(default = "default_format")]
+    pub format: String,
+
+    /// Alpha multiplier of the element, 0 (hidden) to 1 (opaque).
+    #[serde(default = "default_opacity")]
+    pub opacity: f32,
+
+    #[serde(default = "default_visible")]
+    pub visible: bool,
+}
+
+fn default_visible() -> bool {
+    true
+}
+
+impl Default for EarlyLateConfig {
+    fn default() -> Self {
+        Self {
+            position: default_position(),
+            anchor: Anchor::default(),
+            units: PositionUnits::default(),
+            samples: default_samples(),
+            early_color: default_early_color(),
+            late_color: default_late_color(),
+            scale: default_scale(),
+            format: default_format(),
+            opacity: default_opacity(),
+            visible: true,
+        }
+    }
+}
@@ -1,6 +1,7 @@
 //! Hit bar (error bar) display configuration.
 
-use crate::models::skin::common::{Color, Vec2Conf};
+use crate::models::skin::common::{Color, Vec2Conf, default_opacity};
+use crate::models::skin::hud::anchor::{Anchor, PositionUnits};
 use serde::{Deserialize, Serialize};
 
 fn default_position() -> Vec2Conf {
@@ -18,12 +19,29 @@ fn default_indicator_color() -> Color {
 fn default_scale() -> f32 {
     20.0
 }
+fn default_max_ticks() -> usize {
+    10
+}
+fn default_tick_width() -> f32 {
+    2.0
+}
+fn default_average_window() -> usize {
+    20
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HitBarConfig {
     #[serde(default = "default_position")]
     pub position: Vec2Conf,
 
+    /// Point `position` is measured from (see [`Anchor`]).
+    #[serde(default)]
+    pub anchor: Anchor,
+
+    /// Unit of `position`: pixels, or percent of the screen size.
+    #[serde(default)]
+    pub units: PositionUnits,
+
     #[serde(default = "default_size")]
     pub size: Vec2Conf,
 
@@ -36,6 +54,35 @@ pub struct HitBarConfig {
     #[serde(default = "default_scale")]
     pub scale: f32,
 
+    /// Ticks shown at once; the oldest goes when a new hit comes in.
+    #[serde(default = "default_max_ticks")]
+    pub max_ticks: usize,
+
+    /// Time a tick stays on the bar in ms, 0 to keep it until pushed out.
+    #[serde(default)]
+    pub tick_lifetime_ms: f32,
+
+    /// Fade out over the end of the tick lifetime, in ms.
+    #[serde(default)]
+    pub tick_fade_ms: f32,
+
+    /// Width of the ticks and center line in pixels.
+    #[serde(default = "default_tick_width")]
+    pub tick_width: f32,
+
+    /// Marker below the bar at the moving average of the hit offsets.
+    #[serde(default)]
+    pub average_marker: bool,
+
+    /// Number of hits the average marker follows.
+    #[serde(default = "default_average_window")]
+    pub average_window: usize,
+
+    /// Early hits on the right and late hits on the left, instead of the
+    /// other way around.
+    #[serde(default)]
+    pub mirror: bool,
+
     /// Optional background image for hit bar
     #[serde(default)]
     pub background_image: Option<String>,
@@ -44,6 +91,10 @@ pub struct HitBarConfig {
     #[serde(default)]
     pub indicator_image: Option<String>,
 
+    /// Alpha multiplier of the element, 0 (hidden) to 1 (opaque).
+    #[serde(default = "default_opacity")]
+    pub opacity: f32,
+
     #[serde(default = "default_true")]
     pub visible: bool,
 }
@@ -56,12 +107,22 @@ impl Default for HitBarConfig {
     fn default() -> Self {
         Self {
             position: default_position(),
+            anchor: Anchor::default(),
+            units: PositionUnits::default(),
             size: default_size(),
             bar_color: default_bar_color(),
             indicator_color: default_indicator_color(),
             scale: default_scale(),
+            max_ticks: default_max_ticks(),
+            tick_lifetime_ms: 0.0,
+            tick_fade_ms: 0.0,
+            tick_width: default_tick_width(),
+            average_marker: false,
+            average_window: default_average_window(),
+            mirror: false,
             background_image: None,
             indicator_image: None,
+            opacity: default_opacity(),
             visible: true,
         }
     }
@@ -1,5 +1,6 @@
 //! Ghost tap judgement flash configuration.
 
+use super::FlashAnimation;
 use crate::models::skin::common::{Color, Vec2Conf};
 use serde::{Deserialize, Serialize};
 
@@ -27,6 +28,10 @@ pub struct JudgementFlashGhostTap {
     #[serde(default)]
     pub image: Option<String>,
 
+    /// Scale and fade of the flash
+    #[serde(default)]
+    pub animation: FlashAnimation,
+
     #[serde(default = "default_position")]
     pub position: Vec2Conf,
 
@@ -47,6 +52,7 @@ impl Default for JudgementFlashGhostTap {
             label: default_label(),
             color: default_color(),
             image: None,
+            animation: FlashAnimation::default(),
             position: default_position(),
             size: default_size(),
             visible: true,
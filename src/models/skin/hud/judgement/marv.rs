@@ -1,5 +1,6 @@
 //! Marvelous judgement flash configuration.
 
+use super::FlashAnimation;
 use crate::models::skin::common::{Color, Vec2Conf};
 use serde::{Deserialize, Serialize};
 
@@ -28,6 +29,10 @@ pub struct JudgementFlashMarv {
     #[serde(default)]
     pub image: Option<String>,
 
+    /// Scale and fade of the flash
+    #[serde(default)]
+    pub animation: FlashAnimation,
+
     #[serde(default = "default_position")]
     pub position: Vec2Conf,
 
@@ -48,6 +53,7 @@ impl Default for JudgementFlashMarv {
             label: default_label(),
             color: default_color(),
             image: None,
+            animation: FlashAnimation::default(),
             position: default_position(),
             size: default_size(),
             visible: true,
@@ -21,6 +21,9 @@ pub use miss::JudgementFlashMiss;
 pub use panel::JudgementPanelConfig;
 pub use perfect::JudgementFlashPerfect;
 
+use crate::models::skin::common::{Vec2Conf, default_opacity};
+use crate::models::skin::hud::anchor::{Anchor, PositionUnits};
+use crate::models::stats::Judgement;
 use serde::{Deserialize, Serialize};
 
 /// Labels for judgement text display
@@ -49,8 +52,60 @@ impl Default for JudgementLabels {
     }
 }
 
+fn default_flash_scale() -> f32 {
+    1.0
+}
+
+/// How a judgement flash appears and fades out. Applies to the image, and to
+/// the text when the flash has no image.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct FlashAnimation {
+    /// Size multiplier of the image (its size is the flash `size`).
+    #[serde(default = "default_flash_scale")]
+    pub scale: f32,
+
+    /// Scale the flash pops in at, easing to `scale` during the first
+    /// [`FlashAnimation::POP_MS`].
+    #[serde(default = "default_flash_scale")]
+    pub pop: f32,
+
+    /// Time for the flash to fade out, in ms. 0 keeps it until the next
+    /// judgement.
+    #[serde(default)]
+    pub fade_ms: f32,
+}
+
+impl FlashAnimation {
+    pub const POP_MS: f32 = 80.0;
+
+    /// Scale and opacity `elapsed_ms` after the judgement, `None` once faded out.
+    pub fn at(&self, elapsed_ms: f32) -> Option<(f32, f32)> {
+        let alpha = if self.fade_ms > 0.0 {
+            1.0 - elapsed_ms / self.fade_ms
+        } else {
+            1.0
+        };
+        if alpha <= 0.0 {
+            return None;
+        }
+        let pop = (elapsed_ms / Self::POP_MS).min(1.0);
+        let scale = self.scale * (self.pop + (1.0 - self.pop) * pop);
+        Some((scale, alpha))
+    }
+}
+
+impl Default for FlashAnimation {
+    fn default() -> Self {
+        Self {
+            scale: default_flash_scale(),
+            pop: default_flash_scale(),
+            fade_ms: 0.0,
+        }
+    }
+}
+
 /// Complete set of all judgement flash configurations (the centered flash when hitting notes)
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JudgementFlashSet {
     #[serde(default)]
     pub marv: JudgementFlashMarv,
@@ -77,9 +132,76 @@ pub struct JudgementFlashSet {
     /// - = early hit, + = late hit
     #[serde(default)]
     pub show_timing: bool,
+
+    /// Alpha multiplier of the flash, 0 (hidden) to 1 (opaque).
+    #[serde(default = "default_opacity")]
+    pub opacity: f32,
+
+    /// Point the positions of every flash are measured from.
+    #[serde(default)]
+    pub anchor: Anchor,
+
+    /// Unit of the flash positions: pixels, or percent of the screen size.
+    #[serde(default)]
+    pub units: PositionUnits,
+}
+
+impl Default for JudgementFlashSet {
+    fn default() -> Self {
+        Self {
+            marv: JudgementFlashMarv::default(),
+            perfect: JudgementFlashPerfect::default(),
+            great: JudgementFlashGreat::default(),
+            good: JudgementFlashGood::default(),
+            bad: JudgementFlashBad::default(),
+            miss: JudgementFlashMiss::default(),
+            ghost_tap: JudgementFlashGhostTap::default(),
+            show_timing: false,
+            opacity: default_opacity(),
+            anchor: Anchor::default(),
+            units: PositionUnits::default(),
+        }
+    }
 }
 
 impl JudgementFlashSet {
+    /// Image and animation of the flash shown for `judgement`.
+    pub fn flash(&self, judgement: Judgement) -> (Option<&String>, Vec2Conf, FlashAnimation) {
+        match judgement {
+            Judgement::Marv => (
+                self.marv.image.as_ref(),
+                self.marv.size,
+                self.marv.animation,
+            ),
+            Judgement::Perfect => (
+                self.perfect.image.as_ref(),
+                self.perfect.size,
+                self.perfect.animation,
+            ),
+            Judgement::Great => (
+                self.great.image.as_ref(),
+                self.great.size,
+                self.great.animation,
+            ),
+            Judgement::Good => (
+                self.good.image.as_ref(),
+                self.good.size,
+                self.good.animation,
+            ),
+            Judgement::Bad => (self.bad.image.as_ref(), self.bad.size, self.bad.animation),
+            Judgement::Miss => (
+                self.miss.image.as_ref(),
+                self.miss.size,
+                self.miss.animation,
+            ),
+            Judgement::GhostTap => (
+                self.ghost_tap.image.as_ref(),
+                self.ghost_tap.size,
+                self.ghost_tap.animation,
+            ),
+        }
+    }
+
     /// Get labels from the flash set
     pub fn labels(&self) -> JudgementLabels {
         JudgementLabels {
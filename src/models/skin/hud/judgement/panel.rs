@@ -1,7 +1,8 @@
 //! Judgement Panel configuration - Separate from Judgement Flash
 //! This is the stats display that shows counts (Marvelous: 100, Perfect: 50, etc.)
 
-use crate::models::skin::common::{Color, Vec2Conf};
+use crate::models::skin::common::{Color, Vec2Conf, default_opacity};
+use crate::models::skin::hud::anchor::{Anchor, PositionUnits};
 use serde::{Deserialize, Serialize};
 
 fn default_position() -> Vec2Conf {
@@ -42,12 +43,24 @@ pub struct JudgementPanelConfig {
     #[serde(default = "default_position")]
     pub position: Vec2Conf,
 
+    /// Point `position` is measured from (see [`Anchor`]).
+    #[serde(default)]
+    pub anchor: Anchor,
+
+    /// Unit of `position`: pixels, or percent of the screen size.
+    #[serde(default)]
+    pub units: PositionUnits,
+
     #[serde(default = "default_size")]
     pub size: Vec2Conf,
 
     #[serde(default = "default_text_scale")]
     pub text_scale: f32,
 
+    /// Alpha multiplier of the element, 0 (hidden) to 1 (opaque).
+    #[serde(default = "default_opacity")]
+    pub opacity: f32,
+
     #[serde(default)]
     pub visible: bool,
 
@@ -78,8 +91,11 @@ impl Default for JudgementPanelConfig {
     fn default() -> Self {
         Self {
             position: default_position(),
+            anchor: Anchor::default(),
+            units: PositionUnits::default(),
             size: default_size(),
             text_scale: default_text_scale(),
+            opacity: default_opacity(),
             visible: true,
             marv_color: default_marv_color(),
             perfect_color: default_perfect_color(),
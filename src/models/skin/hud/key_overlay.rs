@@ -0,0 +1,102 @@
+//! Key overlay configuration
+
+use crate::models::skin::common::{Color, Vec2Conf, default_opacity};
+use crate::models::skin::hud::anchor::{Anchor, PositionUnits};
+use serde::{Deserialize, Serialize};
+
+fn default_position() -> Vec2Conf {
+    Vec2Conf { x: 40.0, y: 640.0 }
+}
+fn default_anchor() -> Anchor {
+    Anchor::PlayfieldRight
+}
+fn default_key_size() -> Vec2Conf {
+    Vec2Conf { x: 36.0, y: 36.0 }
+}
+fn default_spacing() -> f32 {
+    6.0
+}
+fn default_idle_color() -> Color {
+    [1.0, 1.0, 1.0, 0.15]
+}
+fn default_pressed_color() -> Color {
+    [1.0, 1.0, 1.0, 0.85]
+}
+fn default_text_color() -> Color {
+    [1.0, 1.0, 1.0, 1.0]
+}
+fn default_scale() -> f32 {
+    20.0
+}
+fn default_format() -> String {
+    "{kps} KPS (max {peak})".to_string()
+}
+
+/// Stream overlay of the keys: one square per lane, lit while its key is
+/// held, and a keys-per-second meter under them. In the meter `{kps}` is
+/// replaced by the presses of the last second and `{peak}` by the highest
+/// value since the start of the play. Off by default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyOverlayConfig {
+    /// Top-left corner of the first key.
+    #[serde(default = "default_position")]
+    pub position: Vec2Conf,
+
+    /// Point `position` is measured from (see [`Anchor`]).
+    #[serde(default = "default_anchor")]
+    pub anchor: Anchor,
+
+    /// Unit of `position`: pixels, or percent of the screen size.
+    #[serde(default)]
+    pub units: PositionUnits,
+
+    /// Size of a key square, in pixels.
+    #[serde(default = "default_key_size")]
+    pub key_size: Vec2Conf,
+
+    /// Gap between two keys, in pixels.
+    #[serde(default = "default_spacing")]
+    pub spacing: f32,
+
+    #[serde(default = "default_idle_color")]
+    pub idle_color: Color,
+
+    #[serde(default = "default_pressed_color")]
+    pub pressed_color: Color,
+
+    /// Color of the keys-per-second meter.
+    #[serde(default = "default_text_color")]
+    pub text_color: Color,
+
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+
+    #[serde(default = "default_format")]
+    pub format: String,
+
+    /// Alpha multiplier of the element, 0 (hidden) to 1 (opaque).
+    #[serde(default = "default_opacity")]
+    pub opacity: f32,
+
+    #[serde(default)]
+    pub visible: bool,
+}
+
+impl Default for KeyOverlayConfig {
+    fn default() -> Self {
+        Self {
+            position: default_position(),
+            anchor: default_anchor(),
+            units: PositionUnits::default(),
+            key_size: default_key_size(),
+            spacing: default_spacing(),
+            idle_color: default_idle_color(),
+            pressed_color: default_pressed_color(),
+            text_color: default_text_color(),
+            scale: default_scale(),
+            format: default_format(),
+            opacity: default_opacity(),
+            visible: false,
+        }
+    }
+}
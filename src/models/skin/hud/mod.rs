@@ -1,25 +1,40 @@
 //! HUD (Heads-Up Display) module for gameplay UI elements.
 
 pub mod accuracy;
+pub mod anchor;
+pub mod column_judgements;
 pub mod combo;
+pub mod digits;
+pub mod early_late;
 pub mod hit_bar;
 pub mod judgement;
+pub mod key_overlay;
 pub mod notes_remaining;
 pub mod nps;
+pub mod pb_delta;
 pub mod score;
 pub mod scroll_speed;
 pub mod time_left;
+pub mod widget;
 
 pub use accuracy::AccuracyConfig;
+pub use anchor::{Anchor, HudLayout, PositionUnits};
+pub use column_judgements::ColumnJudgementsConfig;
 pub use combo::ComboConfig;
+pub use digits::{DIGIT_GLYPHS, DigitAlign, digit_file_name};
+pub use early_late::EarlyLateConfig;
 pub use hit_bar::HitBarConfig;
-pub use judgement::{JudgementFlashSet, JudgementLabels, JudgementPanelConfig};
+pub use judgement::{FlashAnimation, JudgementFlashSet, JudgementLabels, JudgementPanelConfig};
+pub use key_overlay::KeyOverlayConfig;
 pub use notes_remaining::NotesRemainingConfig;
 pub use nps::NpsConfig;
+pub use pb_delta::PbDeltaConfig;
 pub use score::ScoreConfig;
 pub use scroll_speed::ScrollSpeedConfig;
 pub use time_left::{TimeDisplayMode, TimeLeftConfig};
+pub use widget::ScriptWidgetConfig;
 
+use crate::models::skin::common::Vec2Conf;
 use serde::{Deserialize, Serialize};
 
 /// Complete HUD configuration for gameplay
@@ -60,4 +75,122 @@ pub struct HudConfig {
     /// Time left / Progress display (bar, circle, or text)
     #[serde(default)]
     pub time_left: TimeLeftConfig,
+
+    /// Delta with the personal best replay on the same map and rate
+    #[serde(default)]
+    pub pb_delta: PbDeltaConfig,
+
+    /// Whether recent hits trend early or late
+    #[serde(default)]
+    pub early_late: EarlyLateConfig,
+
+    /// Judgement counts of each lane
+    #[serde(default)]
+    pub column_judgements: ColumnJudgementsConfig,
+
+    /// Held keys and keys per second, for streams
+    #[serde(default)]
+    pub key_overlay: KeyOverlayConfig,
+
+    /// Widgets drawn by skin scripts
+    #[serde(default)]
+    pub widgets: Vec<ScriptWidgetConfig>,
+}
+
+impl HudConfig {
+    /// Copy of the configuration with every position resolved to screen
+    /// pixels from the top-left corner, as the HUD components expect.
+    pub fn resolved(&self, layout: &HudLayout) -> HudConfig {
+        let mut hud = self.clone();
+        let place = |position: &mut Vec2Conf, anchor: &mut Anchor, units: &mut PositionUnits| {
+            *position = layout.resolve(*position, *anchor, *units);
+            *anchor = Anchor::TopLeft;
+            *units = PositionUnits::Pixels;
+        };
+        place(
+            &mut hud.score.position,
+            &mut hud.score.anchor,
+            &mut hud.score.units,
+        );
+        place(
+            &mut hud.combo.position,
+            &mut hud.combo.anchor,
+            &mut hud.combo.units,
+        );
+        place(
+            &mut hud.accuracy.position,
+            &mut hud.accuracy.anchor,
+            &mut hud.accuracy.units,
+        );
+        place(
+            &mut hud.nps.position,
+            &mut hud.nps.anchor,
+            &mut hud.nps.units,
+        );
+        place(
+            &mut hud.hit_bar.position,
+            &mut hud.hit_bar.anchor,
+            &mut hud.hit_bar.units,
+        );
+        place(
+            &mut hud.judgement_panel.position,
+            &mut hud.judgement_panel.anchor,
+            &mut hud.judgement_panel.units,
+        );
+        place(
+            &mut hud.notes_remaining.position,
+            &mut hud.notes_remaining.anchor,
+            &mut hud.notes_remaining.units,
+        );
+        place(
+            &mut hud.scroll_speed.position,
+            &mut hud.scroll_speed.anchor,
+            &mut hud.scroll_speed.units,
+        );
+        place(
+            &mut hud.time_left.position,
+            &mut hud.time_left.anchor,
+            &mut hud.time_left.units,
+        );
+        place(
+            &mut hud.pb_delta.position,
+            &mut hud.pb_delta.anchor,
+            &mut hud.pb_delta.units,
+        );
+        place(
+            &mut hud.early_late.position,
+            &mut hud.early_late.anchor,
+            &mut hud.early_late.units,
+        );
+        place(
+            &mut hud.column_judgements.position,
+            &mut hud.column_judgements.anchor,
+            &mut hud.column_judgements.units,
+        );
+        place(
+            &mut hud.key_overlay.position,
+            &mut hud.key_overlay.anchor,
+            &mut hud.key_overlay.units,
+        );
+
+        let flash = &mut hud.judgement;
+        for position in [
+            &mut flash.marv.position,
+            &mut flash.perfect.position,
+            &mut flash.great.position,
+            &mut flash.good.position,
+            &mut flash.bad.position,
+            &mut flash.miss.position,
+            &mut flash.ghost_tap.position,
+        ] {
+            *position = layout.resolve(*position, flash.anchor, flash.units);
+        }
+        flash.anchor = Anchor::TopLeft;
+        flash.units = PositionUnits::Pixels;
+
+        for widget in &mut hud.widgets {
+            place(&mut widget.position, &mut widget.anchor, &mut widget.units);
+        }
+        hud
+    }
 }
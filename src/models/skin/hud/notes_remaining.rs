@@ -1,6 +1,7 @@
 //! Notes Remaining display configuration
 
-use crate::models::skin::common::{Color, Vec2Conf};
+use crate::models::skin::common::{Color, Vec2Conf, default_opacity};
+use crate::models::skin::hud::anchor::{Anchor, PositionUnits};
 use serde::{Deserialize, Serialize};
 
 fn default_position() -> Vec2Conf {
@@ -24,6 +25,14 @@ pub struct NotesRemainingConfig {
     #[serde(default = "default_position")]
     pub position: Vec2Conf,
 
+    /// Point `position` is measured from (see [`Anchor`]).
+    #[serde(default)]
+    pub anchor: Anchor,
+
+    /// Unit of `position`: pixels, or percent of the screen size.
+    #[serde(default)]
+    pub units: PositionUnits,
+
     #[serde(default = "default_size")]
     pub size: Vec2Conf,
 
@@ -39,6 +48,10 @@ pub struct NotesRemainingConfig {
     #[serde(default)]
     pub image: Option<String>,
 
+    /// Alpha multiplier of the element, 0 (hidden) to 1 (opaque).
+    #[serde(default = "default_opacity")]
+    pub opacity: f32,
+
     #[serde(default = "default_visible")]
     pub visible: bool,
 }
@@ -51,11 +64,14 @@ impl Default for NotesRemainingConfig {
     fn default() -> Self {
         Self {
             position: default_position(),
+            anchor: Anchor::default(),
+            units: PositionUnits::default(),
             size: default_size(),
             color: default_color(),
             scale: default_scale(),
             format: default_format(),
             image: None,
+            opacity: default_opacity(),
             visible: true,
         }
     }
@@ -1,6 +1,7 @@
 //! NPS (Notes Per Second) display configuration.
 
-use crate::models::skin::common::{Color, Vec2Conf};
+use crate::models::skin::common::{Color, Vec2Conf, default_opacity};
+use crate::models::skin::hud::anchor::{Anchor, PositionUnits};
 use serde::{Deserialize, Serialize};
 
 fn default_position() -> Vec2Conf {
@@ -21,6 +22,14 @@ pub struct NpsConfig {
     #[serde(default = "default_position")]
     pub position: Vec2Conf,
 
+    /// Point `position` is measured from (see [`Anchor`]).
+    #[serde(default)]
+    pub anchor: Anchor,
+
+    /// Unit of `position`: pixels, or percent of the screen size.
+    #[serde(default)]
+    pub units: PositionUnits,
+
     #[serde(default = "default_size")]
     pub size: Vec2Conf,
 
@@ -38,6 +47,10 @@ pub struct NpsConfig {
     #[serde(default = "default_format")]
     pub format: String,
 
+    /// Alpha multiplier of the element, 0 (hidden) to 1 (opaque).
+    #[serde(default = "default_opacity")]
+    pub opacity: f32,
+
     #[serde(default = "default_true")]
     pub visible: bool,
 }
@@ -53,11 +66,14 @@ impl Default for NpsConfig {
     fn default() -> Self {
         Self {
             position: default_position(),
+            anchor: Anchor::default(),
+            units: PositionUnits::default(),
             size: default_size(),
             color: default_color(),
             scale: default_scale(),
             image: None,
             format: default_format(),
+            opacity: default_opacity(),
             visible: true,
         }
     }
@@ -0,0 +1,78 @@
+//! Personal best delta display configuration
+
+use crate::models::skin::common::{Color, Vec2Conf, default_opacity};
+use crate::models::skin::hud::anchor::{Anchor, PositionUnits};
+use serde::{Deserialize, Serialize};
+
+fn default_position() -> Vec2Conf {
+    Vec2Conf { x: 50.0, y: 560.0 }
+}
+fn default_ahead_color() -> Color {
+    [0.4, 1.0, 0.6, 1.0]
+}
+fn default_behind_color() -> Color {
+    [1.0, 0.45, 0.45, 1.0]
+}
+fn default_scale() -> f32 {
+    16.0
+}
+fn default_format() -> String {
+    "PB {score} ({acc})".to_string()
+}
+
+/// Live score / accuracy difference with the best local replay on the same
+/// map and rate. `{score}` and `{acc}` are replaced with signed deltas.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PbDeltaConfig {
+    #[serde(default = "default_position")]
+    pub position: Vec2Conf,
+
+    /// Point `position` is measured from (see [`Anchor`]).
+    #[serde(default)]
+    pub anchor: Anchor,
+
+    /// Unit of `position`: pixels, or percent of the screen size.
+    #[serde(default)]
+    pub units: PositionUnits,
+
+    /// Color while ahead of (or even with) the personal best.
+    #[serde(default = "default_ahead_color")]
+    pub ahead_color: Color,
+
+    /// Color while behind the personal best.
+    #[serde(default = "default_behind_color")]
+    pub behind_color: Color,
+
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+
+    #[serde(default = "default_format")]
+    pub format: String,
+
+    /// Alpha multiplier of the element, 0 (hidden) to 1 (opaque).
+    #[serde(default = "default_opacity")]
+    pub opacity: f32,
+
+    #[serde(default = "default_visible")]
+    pub visible: bool,
+}
+
+fn default_visible() -> bool {
+    true
+}
+
+impl Default for PbDeltaConfig {
+    fn default() -> Self {
+        Self {
+            position: default_position(),
+            anchor: Anchor::default(),
+            units: PositionUnits::default(),
+            ahead_color: default_ahead_color(),
+            behind_color: default_behind_color(),
+            scale: default_scale(),
+            format: default_format(),
+            opacity: default_opacity(),
+            visible: true,
+        }
+    }
+}
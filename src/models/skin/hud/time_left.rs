@@ -1,7 +1,8 @@
 //! Time Left / Progress display configuration
 //! Supports multiple display modes: Bar, Circle (watch-like), or Text (minutes:seconds)
 
-use crate::models::skin::common::{Color, Vec2Conf};
+use crate::models::skin::common::{Color, Vec2Conf, default_opacity};
+use crate::models::skin::hud::anchor::{Anchor, PositionUnits};
 use serde::{Deserialize, Serialize};
 
 /// Display mode for time remaining
@@ -49,6 +50,14 @@ pub struct TimeLeftConfig {
     #[serde(default = "default_position")]
     pub position: Vec2Conf,
 
+    /// Point `position` is measured from (see [`Anchor`]).
+    #[serde(default)]
+    pub anchor: Anchor,
+
+    /// Unit of `position`: pixels, or percent of the screen size.
+    #[serde(default)]
+    pub units: PositionUnits,
+
     #[serde(default = "default_size")]
     pub size: Vec2Conf,
 
@@ -100,6 +109,10 @@ pub struct TimeLeftConfig {
     #[serde(default)]
     pub circle_image: Option<String>,
 
+    /// Alpha multiplier of the element, 0 (hidden) to 1 (opaque).
+    #[serde(default = "default_opacity")]
+    pub opacity: f32,
+
     #[serde(default = "default_visible")]
     pub visible: bool,
 }
@@ -116,6 +129,8 @@ impl Default for TimeLeftConfig {
     fn default() -> Self {
         Self {
             position: default_position(),
+            anchor: Anchor::default(),
+            units: PositionUnits::default(),
             size: default_size(),
             mode: TimeDisplayMode::default(),
             progress_color: default_progress_color(),
@@ -129,6 +144,7 @@ impl Default for TimeLeftConfig {
             background_image: None,
             progress_image: None,
             circle_image: None,
+            opacity: default_opacity(),
             visible: true,
         }
     }
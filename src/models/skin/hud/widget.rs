@@ -0,0 +1,67 @@
+//! Scripted HUD widgets configuration.
+//!
+//! A skin lists its widgets in `hud.toml`:
+//!
+//! ```toml
+//! [[widgets]]
+//! script = "widgets/notes_per_second_left.rhai"
+//! position = { x = 50.0, y = 600.0 }
+//! ```
+//!
+//! The script path is relative to the skin folder. See
+//! `views::components::gameplay::script_widget` for the script API.
+
+use crate::models::skin::common::{Color, Vec2Conf};
+use crate::models::skin::hud::anchor::{Anchor, PositionUnits};
+use serde::{Deserialize, Serialize};
+
+fn default_position() -> Vec2Conf {
+    Vec2Conf { x: 50.0, y: 600.0 }
+}
+fn default_size() -> Vec2Conf {
+    Vec2Conf { x: 200.0, y: 8.0 }
+}
+fn default_color() -> Color {
+    [1.0, 1.0, 1.0, 1.0]
+}
+fn default_scale() -> f32 {
+    16.0
+}
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptWidgetConfig {
+    /// Rhai script, relative to the skin folder.
+    pub script: String,
+
+    #[serde(default = "default_position")]
+    pub position: Vec2Conf,
+
+    /// Point `position` is measured from (see [`Anchor`]).
+    #[serde(default)]
+    pub anchor: Anchor,
+
+    /// Unit of `position`: pixels, or percent of the screen size.
+    #[serde(default)]
+    pub units: PositionUnits,
+
+    /// Size of the bar drawn by `fill`, below the text.
+    #[serde(default = "default_size")]
+    pub size: Vec2Conf,
+
+    /// Color used when the script has no `color` function or returns `()`.
+    #[serde(default = "default_color")]
+    pub color: Color,
+
+    /// Color of the empty part of the bar, if any.
+    #[serde(default)]
+    pub background: Option<Color>,
+
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+
+    #[serde(default = "default_true")]
+    pub visible: bool,
+}
@@ -27,6 +27,12 @@ fn default_text_secondary() -> Color {
 fn default_text_muted() -> Color {
     [0.50, 0.50, 0.55, 1.0]
 }
+fn default_success() -> Color {
+    [0.40, 0.85, 0.45, 1.0]
+}
+fn default_error() -> Color {
+    [0.95, 0.35, 0.35, 1.0]
+}
 
 /// Default panel color theme
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,6 +60,14 @@ pub struct PanelStyleConfig {
 
     #[serde(default = "default_text_muted")]
     pub text_muted: Color,
+
+    /// Border of the toasts reporting a success.
+    #[serde(default = "default_success")]
+    pub success: Color,
+
+    /// Border of the toasts reporting an error.
+    #[serde(default = "default_error")]
+    pub error: Color,
 }
 
 impl Default for PanelStyleConfig {
@@ -67,6 +81,8 @@ impl Default for PanelStyleConfig {
             text_primary: default_text_primary(),
             text_secondary: default_text_secondary(),
             text_muted: default_text_muted(),
+            success: default_success(),
+            error: default_error(),
         }
     }
 }
@@ -1,5 +1,6 @@
 //! Rating colors configuration for difficulty ratings.
 
+use crate::difficulty::PatternTag;
 use crate::models::skin::common::Color;
 use serde::{Deserialize, Serialize};
 
@@ -62,3 +63,18 @@ impl Default for RatingColorsConfig {
         }
     }
 }
+
+impl RatingColorsConfig {
+    /// Color of a pattern tag chip, the one of its skillset.
+    pub fn tag_color(&self, tag: PatternTag) -> Color {
+        match tag {
+            PatternTag::Stream => self.stream,
+            PatternTag::Jumpstream => self.jumpstream,
+            PatternTag::Handstream => self.handstream,
+            PatternTag::Stamina => self.stamina,
+            PatternTag::Jackspeed => self.jackspeed,
+            PatternTag::Chordjack => self.chordjack,
+            PatternTag::Technical => self.technical,
+        }
+    }
+}
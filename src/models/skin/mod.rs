@@ -10,6 +10,8 @@ pub mod gameplay;
 pub mod general;
 pub mod hud;
 pub mod menus;
+pub mod package;
+pub mod theme;
 
 pub use common::{
     /*Color,*/ Vec2Conf, check_file,
@@ -17,10 +19,12 @@ pub use common::{
 };
 pub use editor::EditorConfig;
 pub use gameplay::{/*BurstConfig,*/ GameplayDefaults, /*HoldConfig,*/ KeyModeConfig};
-pub use general::SkinGeneral;
+pub use general::{SkinGeneral, SkinSounds, UiSound};
 pub use hud::{HudConfig, JudgementLabels};
 pub use menus::MenusConfig;
+pub use theme::{EguiStyleConfig, ThemeConfig, ThemeScope};
 
+use crate::models::stats::Judgement;
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -34,6 +38,8 @@ pub struct Skin {
     pub gameplay: GameplayDefaults,
     pub menus: MenusConfig,
     pub editor: EditorConfig,
+    /// egui style per screen (optional `theme.toml`).
+    pub theme: ThemeConfig,
 
     /// Per-keymode configurations (4K, 5K, 6K, 7K, etc.)
     pub key_modes: HashMap<usize, KeyModeConfig>,
@@ -51,6 +57,7 @@ impl Default for Skin {
             gameplay: GameplayDefaults::default(),
             menus: MenusConfig::default(),
             editor: EditorConfig::default(),
+            theme: ThemeConfig::default(),
             key_modes: HashMap::new(),
             background: None,
         }
@@ -89,6 +96,13 @@ impl Skin {
         // Load editor config (if exists)
         let editor: EditorConfig = load_toml(&conf_path.join("editor.toml")).unwrap_or_default();
 
+        // Load egui theme (if exists)
+        let theme: ThemeConfig = load_toml(&conf_path.join("theme.toml")).unwrap_or_default();
+
+        for warning in general.capabilities.unsupported() {
+            log::warn!("SKIN: {}: {}", general.name, warning);
+        }
+
         Ok(Self {
             base_path: base_path.clone(),
             general,
@@ -96,6 +110,7 @@ impl Skin {
             gameplay,
             menus,
             editor,
+            theme,
             key_modes: HashMap::new(),
             background: check_file(&base_path, "background.png"),
         })
@@ -110,6 +125,10 @@ impl Skin {
             fs::create_dir_all(&conf_path).map_err(|e| e.to_string())?;
         }
 
+        let general_path = conf_path.join("general.toml");
+        let general_content = toml::to_string_pretty(&self.general).map_err(|e| e.to_string())?;
+        fs::write(general_path, general_content).map_err(|e| e.to_string())?;
+
         let hud_path = conf_path.join("hud.toml");
         let hud_content = toml::to_string_pretty(&self.hud).map_err(|e| e.to_string())?;
         fs::write(hud_path, hud_content).map_err(|e| e.to_string())?;
@@ -325,6 +344,17 @@ impl Skin {
         self.general.font.as_ref().map(|f| self.base_path.join(f))
     }
 
+    /// Get the image replacing the flash text of a judgement, if any
+    pub fn get_judgement_image(&self, judgement: Judgement) -> Option<PathBuf> {
+        let (image, _, _) = self.hud.judgement.flash(judgement);
+        image.map(|name| self.base_path.join(name))
+    }
+
+    /// Image of a number character (`score-0.png` ..), if the skin has one.
+    pub fn get_digit_image(&self, glyph: char) -> Option<PathBuf> {
+        check_file(&self.base_path, &hud::digit_file_name(glyph)?)
+    }
+
     /// Get judgement labels from skin
     pub fn get_judgement_labels(&self) -> JudgementLabels {
         self.hud.judgement.labels()
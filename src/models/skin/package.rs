@@ -0,0 +1,178 @@
+//! `.rvskin` packages: a skin folder in a single zip archive.
+//!
+//! Imports are extracted into a hidden staging folder of `skins/` and only
+//! moved next to the other skins once their `conf/` files parse, so a broken
+//! archive never shows up in the skin list.
+
+use super::{
+    EditorConfig, GameplayDefaults, HudConfig, KeyModeConfig, MenusConfig, SkinGeneral,
+    ThemeConfig, load_toml,
+};
+use crate::database::scanner::{extract_archive, single_subfolder};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+
+/// File extension of skin packages.
+pub const EXTENSION: &str = "rvskin";
+
+/// Whether `path` looks like a skin package.
+pub fn is_package(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(EXTENSION))
+}
+
+/// Writes every file of `skin_dir` into a compressed archive at `archive`.
+pub fn export_skin(skin_dir: &Path, archive: &Path) -> Result<(), String> {
+    let file = fs::File::create(archive).map_err(|e| e.to_string())?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    let mut pending = vec![skin_dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir).map_err(|e| e.to_string())? {
+            let path = entry.map_err(|e| e.to_string())?.path();
+            if path.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            let name = archive_name(skin_dir, &path)?;
+            let bytes = fs::read(&path).map_err(|e| e.to_string())?;
+            zip.start_file(name, options).map_err(|e| e.to_string())?;
+            zip.write_all(&bytes).map_err(|e| e.to_string())?;
+        }
+    }
+
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Entry name of `path` inside the archive, `/`-separated.
+fn archive_name(skin_dir: &Path, path: &Path) -> Result<String, String> {
+    let relative = path.strip_prefix(skin_dir).map_err(|e| e.to_string())?;
+    let parts: Vec<_> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect();
+    Ok(parts.join("/"))
+}
+
+/// Extracts `archive` into `skins_dir` and returns the name of the new skin.
+///
+/// The skin is named after the archive; a number is appended when a skin of
+/// that name already exists.
+pub fn import_skin(archive: &Path, skins_dir: &Path) -> Result<String, String> {
+    let stem = archive
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .map(|s| s.trim().trim_start_matches('.'))
+        .filter(|s| !s.is_empty())
+        .ok_or("Invalid archive name")?;
+
+    let staging = skins_dir.join(format!(".import-{}", stem));
+    if staging.exists() {
+        fs::remove_dir_all(&staging).map_err(|e| e.to_string())?;
+    }
+    let result = extract_and_install(archive, &staging, skins_dir, stem);
+    let _ = fs::remove_dir_all(&staging);
+    result
+}
+
+fn extract_and_install(
+    archive: &Path,
+    staging: &Path,
+    skins_dir: &Path,
+    stem: &str,
+) -> Result<String, String> {
+    extract_archive(archive, staging)?;
+
+    // Archives made by zipping the skin folder itself have it as only entry.
+    let root = if staging.join("conf").is_dir() {
+        staging.to_path_buf()
+    } else {
+        single_subfolder(staging).ok_or("No conf/ folder in the archive")?
+    };
+    validate_conf(&root.join("conf"))?;
+
+    let name = free_name(skins_dir, stem);
+    fs::rename(&root, skins_dir.join(&name)).map_err(|e| e.to_string())?;
+    Ok(name)
+}
+
+/// Checks that the skin configuration files present in `conf` parse.
+fn validate_conf(conf: &Path) -> Result<(), String> {
+    if !conf.is_dir() {
+        return Err("No conf/ folder in the archive".to_string());
+    }
+
+    fn check<T: serde::de::DeserializeOwned>(conf: &Path, file: &str) -> Result<(), String> {
+        let path = conf.join(file);
+        if path.exists() {
+            load_toml::<T>(&path).map_err(|e| format!("conf/{}: {}", file, e))?;
+        }
+        Ok(())
+    }
+    check::<SkinGeneral>(conf, "general.toml")?;
+    check::<HudConfig>(conf, "hud.toml")?;
+    check::<GameplayDefaults>(conf, "gameplay.toml")?;
+    check::<MenusConfig>(conf, "menus.toml")?;
+    check::<EditorConfig>(conf, "editor.toml")?;
+    check::<ThemeConfig>(conf, "theme.toml")?;
+
+    for entry in fs::read_dir(conf).map_err(|e| e.to_string())?.flatten() {
+        let file = entry.file_name().to_string_lossy().to_string();
+        let is_key_mode = file
+            .strip_suffix("k.toml")
+            .is_some_and(|keys| !keys.is_empty() && keys.chars().all(|c| c.is_ascii_digit()));
+        if is_key_mode {
+            check::<KeyModeConfig>(conf, &file)?;
+        }
+    }
+    Ok(())
+}
+
+/// `name`, or `name-2`, `name-3`... if a skin already uses it.
+fn free_name(skins_dir: &Path, name: &str) -> String {
+    if !skins_dir.join(name).exists() {
+        return name.to_string();
+    }
+    (2..)
+        .map(|n| format!("{}-{}", name, n))
+        .find(|candidate| !skins_dir.join(candidate).exists())
+        .unwrap_or_else(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exported_skins_import_under_a_free_name() {
+        let root = std::env::temp_dir().join("rvsrg_skin_package_test");
+        let _ = fs::remove_dir_all(&root);
+        let skin_dir = root.join("skins/mine");
+        fs::create_dir_all(skin_dir.join("conf")).unwrap();
+        let general = toml::to_string(&SkinGeneral::default()).unwrap();
+        fs::write(skin_dir.join("conf/general.toml"), general).unwrap();
+        fs::write(skin_dir.join("note.png"), [1u8, 2, 3]).unwrap();
+
+        let archive = root.join("mine.rvskin");
+        export_skin(&skin_dir, &archive).unwrap();
+        let name = import_skin(&archive, &root.join("skins")).unwrap();
+        assert_eq!(name, "mine-2");
+        assert_eq!(
+            fs::read(root.join("skins/mine-2/note.png")).unwrap(),
+            [1, 2, 3]
+        );
+        assert!(!root.join("skins/.import-mine").exists());
+
+        fs::write(skin_dir.join("conf/hud.toml"), "score = 3").unwrap();
+        export_skin(&skin_dir, &archive).unwrap();
+        assert!(import_skin(&archive, &root.join("skins")).is_err());
+        assert!(!root.join("skins/mine-3").exists());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
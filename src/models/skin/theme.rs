@@ -0,0 +1,95 @@
+//! egui theme configuration (`conf/theme.toml`).
+//!
+//! `[base]` applies to every screen; `[menu]`, `[result]`, `[editor]` and
+//! `[gameplay]` override it for a single screen. Every field is optional:
+//! anything left out keeps egui's default look.
+
+use crate::models::skin::common::Color;
+use serde::{Deserialize, Serialize};
+
+/// Screen a theme section applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemeScope {
+    Menu,
+    Result,
+    Editor,
+    Gameplay,
+}
+
+/// egui visual parameters. `None` keeps the value inherited from `[base]`
+/// (or egui's default).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EguiStyleConfig {
+    /// Corner radius of windows, menus and widgets (pixels).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rounding: Option<f32>,
+    /// Selection, active widget and link color.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accent: Option<Color>,
+    /// Side/central panel background.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub panel_fill: Option<Color>,
+    /// Window and popup background.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_fill: Option<Color>,
+    /// Window border color.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_stroke: Option<Color>,
+    /// Background of text edits, scroll bars and striped rows.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extreme_bg: Option<Color>,
+    /// Button / slider background at rest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub widget_fill: Option<Color>,
+    /// Button / slider background under the mouse.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub widget_hover_fill: Option<Color>,
+    /// Text color override for all widgets.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<Color>,
+}
+
+impl EguiStyleConfig {
+    /// Returns `self` with the fields left unset taken from `base`.
+    pub fn or(&self, base: &Self) -> Self {
+        Self {
+            rounding: self.rounding.or(base.rounding),
+            accent: self.accent.or(base.accent),
+            panel_fill: self.panel_fill.or(base.panel_fill),
+            window_fill: self.window_fill.or(base.window_fill),
+            window_stroke: self.window_stroke.or(base.window_stroke),
+            extreme_bg: self.extreme_bg.or(base.extreme_bg),
+            widget_fill: self.widget_fill.or(base.widget_fill),
+            widget_hover_fill: self.widget_hover_fill.or(base.widget_hover_fill),
+            text: self.text.or(base.text),
+        }
+    }
+}
+
+/// Contents of `theme.toml`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ThemeConfig {
+    #[serde(default)]
+    pub base: EguiStyleConfig,
+    #[serde(default)]
+    pub menu: EguiStyleConfig,
+    #[serde(default)]
+    pub result: EguiStyleConfig,
+    #[serde(default)]
+    pub editor: EguiStyleConfig,
+    #[serde(default)]
+    pub gameplay: EguiStyleConfig,
+}
+
+impl ThemeConfig {
+    /// Effective style parameters for a screen (`[base]` + its own section).
+    pub fn resolve(&self, scope: ThemeScope) -> EguiStyleConfig {
+        let section = match scope {
+            ThemeScope::Menu => &self.menu,
+            ThemeScope::Result => &self.result,
+            ThemeScope::Editor => &self.editor,
+            ThemeScope::Gameplay => &self.gameplay,
+        };
+        section.or(&self.base)
+    }
+}
@@ -28,6 +28,40 @@ impl JudgementColors {
             ghost_tap: [0.5, 0.5, 0.5, 1.0], // Gray
         }
     }
+
+    /// Color of one judgement.
+    pub fn get(&self, judgement: Judgement) -> [f32; 4] {
+        match judgement {
+            Judgement::Marv => self.marv,
+            Judgement::Perfect => self.perfect,
+            Judgement::Great => self.great,
+            Judgement::Good => self.good,
+            Judgement::Bad => self.bad,
+            Judgement::Miss => self.miss,
+            Judgement::GhostTap => self.ghost_tap,
+        }
+    }
+
+    /// These colors with `palette` laid over them. The opacity of each
+    /// color is kept, so faded skin elements stay faded.
+    pub fn with_palette(&self, palette: JudgementPalette) -> Self {
+        let Some(rgb) = palette.colors() else {
+            return self.clone();
+        };
+        let layer = |color: [f32; 4], judgement: Judgement| {
+            let [r, g, b] = rgb[judgement as usize];
+            [r, g, b, color[3]]
+        };
+        Self {
+            marv: layer(self.marv, Judgement::Marv),
+            perfect: layer(self.perfect, Judgement::Perfect),
+            great: layer(self.great, Judgement::Great),
+            good: layer(self.good, Judgement::Good),
+            bad: layer(self.bad, Judgement::Bad),
+            miss: layer(self.miss, Judgement::Miss),
+            ghost_tap: layer(self.ghost_tap, Judgement::GhostTap),
+        }
+    }
 }
 
 impl Default for JudgementColors {
@@ -36,6 +70,90 @@ impl Default for JudgementColors {
     }
 }
 
+/// Judgement colors the player can pick over the skin's ones, for color
+/// vision deficiencies or plainer contrast. Each palette tells neighbouring
+/// judgements apart by lightness as well as hue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum JudgementPalette {
+    /// Colors of the skin.
+    #[default]
+    Skin,
+    HighContrast,
+    /// Red-green, weak green.
+    Deuteranopia,
+    /// Red-green, weak red.
+    Protanopia,
+    /// Blue-yellow.
+    Tritanopia,
+}
+
+impl JudgementPalette {
+    pub const ALL: [JudgementPalette; 5] = [
+        JudgementPalette::Skin,
+        JudgementPalette::HighContrast,
+        JudgementPalette::Deuteranopia,
+        JudgementPalette::Protanopia,
+        JudgementPalette::Tritanopia,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            JudgementPalette::Skin => "Skin colors",
+            JudgementPalette::HighContrast => "High contrast",
+            JudgementPalette::Deuteranopia => "Deuteranopia",
+            JudgementPalette::Protanopia => "Protanopia",
+            JudgementPalette::Tritanopia => "Tritanopia",
+        }
+    }
+
+    /// RGB of each judgement in [`Judgement::ALL`] order, `None` to keep
+    /// the skin's.
+    fn colors(self) -> Option<[[f32; 3]; 7]> {
+        match self {
+            JudgementPalette::Skin => None,
+            JudgementPalette::HighContrast => Some([
+                [1.0, 1.0, 1.0],    // White
+                [1.0, 0.86, 0.0],   // Yellow
+                [0.0, 0.9, 0.35],   // Green
+                [0.2, 0.55, 1.0],   // Blue
+                [1.0, 0.35, 0.85],  // Magenta
+                [1.0, 0.1, 0.1],    // Red
+                [0.55, 0.55, 0.55], // Gray
+            ]),
+            // Okabe-Ito colors, kept apart on the blue-orange axis.
+            JudgementPalette::Deuteranopia => Some([
+                [0.95, 0.95, 0.95], // White
+                [0.34, 0.71, 0.91], // Sky blue
+                [0.94, 0.89, 0.26], // Yellow
+                [0.0, 0.45, 0.7],   // Blue
+                [0.9, 0.62, 0.0],   // Orange
+                [0.84, 0.37, 0.0],  // Vermillion
+                [0.5, 0.5, 0.5],    // Gray
+            ]),
+            // Reds look dark without red cones, so the miss is a bright orange.
+            JudgementPalette::Protanopia => Some([
+                [0.95, 0.95, 0.95], // White
+                [0.34, 0.71, 0.91], // Sky blue
+                [0.94, 0.89, 0.26], // Yellow
+                [0.0, 0.45, 0.7],   // Blue
+                [0.8, 0.47, 0.65],  // Reddish purple
+                [1.0, 0.6, 0.0],    // Orange
+                [0.5, 0.5, 0.5],    // Gray
+            ]),
+            // Kept apart on the red-cyan axis.
+            JudgementPalette::Tritanopia => Some([
+                [0.95, 0.95, 0.95], // White
+                [0.0, 0.8, 0.85],   // Cyan
+                [1.0, 0.55, 0.7],   // Pink
+                [0.0, 0.5, 0.5],    // Teal
+                [0.6, 0.3, 0.35],   // Brown
+                [0.9, 0.1, 0.1],    // Red
+                [0.5, 0.5, 0.5],    // Gray
+            ]),
+        }
+    }
+}
+
 /// Hit judgement types from best to worst.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum Judgement {
@@ -55,6 +173,19 @@ pub enum Judgement {
     GhostTap,
 }
 
+impl Judgement {
+    /// Every judgement, best first.
+    pub const ALL: [Judgement; 7] = [
+        Judgement::Marv,
+        Judgement::Perfect,
+        Judgement::Great,
+        Judgement::Good,
+        Judgement::Bad,
+        Judgement::Miss,
+        Judgement::GhostTap,
+    ];
+}
+
 /// Accumulated hit statistics for a play session.
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct HitStats {
@@ -65,6 +196,10 @@ pub struct HitStats {
     pub bad: u32,
     pub miss: u32,
     pub ghost_tap: u32,
+    /// Counts per lane in `Judgement::ALL` order, kept during play (empty
+    /// for simulated replays).
+    #[serde(default)]
+    pub columns: Vec<[u32; 7]>,
 }
 
 impl HitStats {
@@ -78,6 +213,31 @@ impl HitStats {
             bad: 0,
             miss: 0,
             ghost_tap: 0,
+            columns: Vec::new(),
+        }
+    }
+
+    /// Counts one judgement in its bucket.
+    pub fn record(&mut self, judgement: Judgement) {
+        match judgement {
+            Judgement::Marv => self.marv += 1,
+            Judgement::Perfect => self.perfect += 1,
+            Judgement::Great => self.great += 1,
+            Judgement::Good => self.good += 1,
+            Judgement::Bad => self.bad += 1,
+            Judgement::Miss => self.miss += 1,
+            Judgement::GhostTap => self.ghost_tap += 1,
+        }
+    }
+
+    /// Counts one judgement in its bucket and in the counts of `column`.
+    pub fn record_column(&mut self, column: usize, judgement: Judgement) {
+        self.record(judgement);
+        if self.columns.len() <= column {
+            self.columns.resize(column + 1, [0; 7]);
+        }
+        if let Some(index) = Judgement::ALL.iter().position(|&j| j == judgement) {
+            self.columns[column][index] += 1;
         }
     }
 
@@ -111,3 +271,84 @@ impl Default for HitStats {
         Self::new()
     }
 }
+
+/// Overall audio sync health of a play, derived from [`DriftStats`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyncQuality {
+    Good,
+    Fair,
+    Poor,
+}
+
+/// Audio clock drift measured during a play, for diagnosing driver issues:
+/// how far each tick's audible position lands from the previous one
+/// advanced by the tick time.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DriftStats {
+    /// Number of drift measurements taken.
+    pub samples: u32,
+    /// Sum of absolute drift in ms (for the mean).
+    pub total_abs_drift_ms: f64,
+    /// Largest absolute drift seen in ms.
+    pub max_abs_drift_ms: f64,
+    /// Most recent drift measurement in ms (signed).
+    pub last_drift_ms: f64,
+}
+
+impl DriftStats {
+    /// Records one drift measurement (audio device time minus tick clock).
+    pub fn record(&mut self, drift_ms: f64) {
+        let abs = drift_ms.abs();
+        self.samples += 1;
+        self.total_abs_drift_ms += abs;
+        self.max_abs_drift_ms = self.max_abs_drift_ms.max(abs);
+        self.last_drift_ms = drift_ms;
+    }
+
+    /// Mean absolute drift in ms.
+    pub fn mean_abs_drift_ms(&self) -> f64 {
+        if self.samples == 0 {
+            return 0.0;
+        }
+        self.total_abs_drift_ms / self.samples as f64
+    }
+
+    /// Folds another play's measurements into this one.
+    pub fn merge(&mut self, other: &DriftStats) {
+        self.samples += other.samples;
+        self.total_abs_drift_ms += other.total_abs_drift_ms;
+        self.max_abs_drift_ms = self.max_abs_drift_ms.max(other.max_abs_drift_ms);
+        self.last_drift_ms = other.last_drift_ms;
+    }
+
+    /// Rough rating: a single jump shows on the notes (a stalled output),
+    /// so the largest drift weighs the most.
+    pub fn quality(&self) -> SyncQuality {
+        let mean = self.mean_abs_drift_ms();
+        if self.max_abs_drift_ms < 20.0 && mean < 3.0 {
+            SyncQuality::Good
+        } else if self.max_abs_drift_ms < 80.0 && mean < 8.0 {
+            SyncQuality::Fair
+        } else {
+            SyncQuality::Poor
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palette_replaces_hues_but_keeps_opacity() {
+        let mut skin = JudgementColors::new();
+        skin.miss[3] = 0.5;
+
+        let skin_only = skin.with_palette(JudgementPalette::Skin);
+        assert_eq!(skin_only.miss, skin.miss);
+
+        let recolored = skin.with_palette(JudgementPalette::Deuteranopia);
+        assert_eq!(recolored.miss, [0.84, 0.37, 0.0, 0.5]);
+        assert_eq!(recolored.get(Judgement::Marv), [0.95, 0.95, 0.95, 1.0]);
+    }
+}
@@ -0,0 +1,175 @@
+//! Signed replays written in tournament mode.
+//!
+//! Every play is saved to `tournament/` as a JSON payload preceded by its
+//! HMAC-SHA256, keyed with a key derived from the organizer password. Results handed in after
+//! an event can then be checked against the replay that produced them, and a
+//! replay edited by hand no longer verifies.
+
+use crate::models::replay::ReplayData;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File extension of signed replays.
+pub const EXTENSION: &str = "rvreplay";
+
+/// Folder where signed replays are written.
+const SIGNED_REPLAY_DIR: &str = "tournament";
+
+/// PBKDF2 rounds for the password hash and the signing key, slow enough that
+/// a copied `settings.toml` cannot be brute-forced cheaply. Unoptimized test
+/// builds would take minutes with the real count.
+const KDF_ROUNDS: u32 = if cfg!(test) { 1_000 } else { 600_000 };
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// A finished play, as submitted to the organizer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedReplay {
+    pub beatmap_hash: String,
+    /// Unix time (s) the play ended.
+    pub timestamp: i64,
+    pub score: i32,
+    pub accuracy: f64,
+    pub max_combo: i32,
+    /// Inputs, rate and judge of the play.
+    pub replay: ReplayData,
+}
+
+/// Whether `path` looks like a signed replay.
+pub fn is_signed_replay(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(EXTENSION))
+}
+
+impl SignedReplay {
+    /// Writes the replay signed with `key` and returns its path.
+    pub fn save(&self, key: &[u8]) -> Result<PathBuf, String> {
+        fs::create_dir_all(SIGNED_REPLAY_DIR).map_err(|e| e.to_string())?;
+        let short_hash: String = self.beatmap_hash.chars().take(8).collect();
+        let path = PathBuf::from(SIGNED_REPLAY_DIR)
+            .join(format!("{}-{}.{}", self.timestamp, short_hash, EXTENSION));
+        fs::write(&path, self.signed_bytes(key)?).map_err(|e| e.to_string())?;
+        Ok(path)
+    }
+
+    /// The signature line followed by the JSON payload.
+    fn signed_bytes(&self, key: &[u8]) -> Result<Vec<u8>, String> {
+        let payload = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        let mut mac = new_mac(key);
+        mac.update(payload.as_bytes());
+        let signature = to_hex(&mac.finalize().into_bytes());
+        Ok(format!("{}\n{}", signature, payload).into_bytes())
+    }
+
+    /// Reads a signed replay, failing if it was not signed with `key`.
+    pub fn verify(path: &Path, key: &[u8]) -> Result<Self, String> {
+        let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::verify_bytes(&content, key)
+    }
+
+    fn verify_bytes(content: &str, key: &[u8]) -> Result<Self, String> {
+        let (signature, payload) = content.split_once('\n').ok_or("Not a signed replay")?;
+        // The payload is checked as written: re-serializing it could differ.
+        let signature = from_hex(signature.trim()).ok_or("Malformed signature")?;
+        let mut mac = new_mac(key);
+        mac.update(payload.as_bytes());
+        mac.verify_slice(&signature)
+            .map_err(|_| "Signature mismatch".to_string())?;
+        serde_json::from_str(payload).map_err(|e| format!("Invalid replay: {}", e))
+    }
+}
+
+/// Derives the signing key from the organizer password. Only the password
+/// hash is saved: the key lives in memory while tournament mode is on.
+///
+/// The salt is fixed so the organizer gets the same key on their own machine
+/// when checking replays.
+pub fn signing_key(password: &str) -> Vec<u8> {
+    derive(password, b"rvsrg replay signing").to_vec()
+}
+
+/// Random salt for a new organizer password, as hex.
+pub fn new_salt() -> String {
+    to_hex(&rand::random::<[u8; 16]>())
+}
+
+/// Salted PBKDF2 hash of the organizer password, as hex.
+pub fn password_hash(salt: &str, password: &str) -> String {
+    to_hex(&derive(password, salt.as_bytes()))
+}
+
+fn derive(password: &str, salt: &[u8]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, KDF_ROUNDS, &mut out);
+    out
+}
+
+fn new_mac(key: &[u8]) -> HmacSha256 {
+    HmacSha256::new_from_slice(key).expect("HMAC takes keys of any length")
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::settings::{HitWindowMode, TournamentSettings};
+
+    #[test]
+    fn signatures_follow_the_key_and_content() {
+        let replay = SignedReplay {
+            beatmap_hash: "abc".to_string(),
+            timestamp: 1,
+            score: 900_000,
+            accuracy: 97.5,
+            max_combo: 321,
+            replay: ReplayData::new(1.1, HitWindowMode::EtternaJudge, 4.0),
+        };
+        let key = signing_key("organizer");
+        let signed = String::from_utf8(replay.signed_bytes(&key).unwrap()).unwrap();
+        let read = SignedReplay::verify_bytes(&signed, &key).unwrap();
+        assert_eq!(read.score, 900_000);
+        assert!(SignedReplay::verify_bytes(&signed, &signing_key("player")).is_err());
+        let edited = signed.replace("900000", "999999");
+        assert!(SignedReplay::verify_bytes(&edited, &key).is_err());
+    }
+
+    #[test]
+    fn only_the_organizer_password_leaves_tournament_mode() {
+        let mut tournament = TournamentSettings::default();
+        tournament.lock("organizer");
+        assert!(tournament.enabled);
+        assert_eq!(tournament.signing_key, Some(signing_key("organizer")));
+
+        // After a restart the key is gone until the password is entered.
+        let saved: TournamentSettings =
+            toml::from_str(&toml::to_string(&tournament).unwrap()).unwrap();
+        assert_eq!(saved.signing_key, None);
+        let mut tournament = saved;
+        assert!(!tournament.resume("player"));
+        assert!(tournament.resume("organizer"));
+        assert_eq!(tournament.signing_key, Some(signing_key("organizer")));
+
+        assert!(!tournament.unlock("player"));
+        assert!(tournament.enabled);
+        assert!(tournament.unlock("organizer"));
+        assert!(!tournament.enabled);
+        assert_eq!(tournament.signing_key, None);
+    }
+}
@@ -0,0 +1,122 @@
+//! Minimal client of the Discord IPC socket: the handshake and
+//! `SET_ACTIVITY` commands, which is all Rich Presence needs.
+//!
+//! Frames are a little-endian opcode and length followed by a JSON payload.
+
+use super::Activity;
+use serde_json::{Value, json};
+use std::io::{self, Read, Write};
+
+const OP_HANDSHAKE: u32 = 0;
+const OP_FRAME: u32 = 1;
+const OP_CLOSE: u32 = 2;
+
+/// Discord listens on the first free of `discord-ipc-0` to `discord-ipc-9`.
+const PIPE_COUNT: u32 = 10;
+
+trait Pipe: Read + Write + Send {}
+impl<T: Read + Write + Send> Pipe for T {}
+
+pub struct DiscordIpc {
+    pipe: Box<dyn Pipe>,
+    nonce: u64,
+}
+
+impl DiscordIpc {
+    /// Connects to the local Discord client as application `client_id`.
+    pub fn connect(client_id: &str) -> io::Result<Self> {
+        let mut last_error = io::Error::new(io::ErrorKind::NotFound, "Discord is not running");
+        for index in 0..PIPE_COUNT {
+            let pipe = match open_pipe(index) {
+                Ok(pipe) => pipe,
+                Err(e) => {
+                    last_error = e;
+                    continue;
+                }
+            };
+            let mut ipc = Self { pipe, nonce: 0 };
+            ipc.send(OP_HANDSHAKE, &json!({ "v": 1, "client_id": client_id }))?;
+            ipc.receive()?;
+            return Ok(ipc);
+        }
+        Err(last_error)
+    }
+
+    /// Shows `activity` on the player's profile, or clears it.
+    pub fn set_activity(&mut self, activity: Option<&Activity>) -> io::Result<()> {
+        self.nonce += 1;
+        let payload = json!({
+            "cmd": "SET_ACTIVITY",
+            "args": {
+                "pid": std::process::id(),
+                "activity": activity.map(activity_json),
+            },
+            "nonce": self.nonce.to_string(),
+        });
+        self.send(OP_FRAME, &payload)?;
+        let response = self.receive()?;
+        if response["evt"] == "ERROR" {
+            let message = response["data"]["message"]
+                .as_str()
+                .unwrap_or("unknown error");
+            return Err(io::Error::other(message.to_string()));
+        }
+        Ok(())
+    }
+
+    fn send(&mut self, opcode: u32, payload: &Value) -> io::Result<()> {
+        let body = payload.to_string();
+        let mut frame = Vec::with_capacity(8 + body.len());
+        frame.extend_from_slice(&opcode.to_le_bytes());
+        frame.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        frame.extend_from_slice(body.as_bytes());
+        self.pipe.write_all(&frame)?;
+        self.pipe.flush()
+    }
+
+    fn receive(&mut self) -> io::Result<Value> {
+        let mut header = [0u8; 8];
+        self.pipe.read_exact(&mut header)?;
+        let opcode = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+        let len = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        let mut body = vec![0u8; len];
+        self.pipe.read_exact(&mut body)?;
+        if opcode == OP_CLOSE {
+            return Err(io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                String::from_utf8_lossy(&body).to_string(),
+            ));
+        }
+        serde_json::from_slice(&body).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+fn activity_json(activity: &Activity) -> Value {
+    let mut value = json!({ "details": activity.details });
+    if let Some(state) = &activity.state {
+        value["state"] = json!(state);
+    }
+    if let Some(start) = activity.started_at {
+        value["timestamps"] = json!({ "start": start });
+    }
+    value
+}
+
+#[cfg(unix)]
+fn open_pipe(index: u32) -> io::Result<Box<dyn Pipe>> {
+    let dir = ["XDG_RUNTIME_DIR", "TMPDIR", "TMP", "TEMP"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok())
+        .unwrap_or_else(|| "/tmp".to_string());
+    let path = std::path::Path::new(&dir).join(format!("discord-ipc-{}", index));
+    Ok(Box::new(std::os::unix::net::UnixStream::connect(path)?))
+}
+
+#[cfg(windows)]
+fn open_pipe(index: u32) -> io::Result<Box<dyn Pipe>> {
+    let pipe = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(format!(r"\\.\pipe\discord-ipc-{}", index))?;
+    Ok(Box::new(pipe))
+}
@@ -0,0 +1,120 @@
+//! Optional Discord Rich Presence.
+//!
+//! The logic thread describes what the player is doing as an [`Activity`]
+//! whenever the game state changes; a presence thread keeps the connection
+//! to the local Discord client and publishes it. Nothing is sent unless
+//! `SettingsState::discord_presence` is on, Discord is running, and the build
+//! has a Discord application id (`RVSRG_DISCORD_CLIENT_ID` at compile time).
+
+pub mod discord;
+
+use discord::DiscordIpc;
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender, channel};
+use std::thread;
+use std::time::Duration;
+
+/// Discord application the presence is published as.
+const CLIENT_ID: Option<&str> = option_env!("RVSRG_DISCORD_CLIENT_ID");
+
+/// Delay before trying to reach Discord again.
+const RETRY_DELAY: Duration = Duration::from_secs(15);
+
+/// What the player is doing, as shown on their Discord profile.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Activity {
+    /// First line, e.g. the chart being played.
+    pub details: String,
+    /// Second line, e.g. "Playing" or the result accuracy.
+    pub state: Option<String>,
+    /// Unix time (s) shown as elapsed time.
+    pub started_at: Option<u64>,
+}
+
+pub struct Presence {
+    /// `None` when the build has no application id.
+    sender: Option<Sender<Option<Activity>>>,
+}
+
+impl Presence {
+    pub fn new() -> Self {
+        let Some(client_id) = CLIENT_ID.filter(|id| !id.is_empty()) else {
+            log::info!("PRESENCE: No Discord application id in this build, disabled");
+            return Self { sender: None };
+        };
+        let (tx, rx) = channel();
+        let spawned = thread::Builder::new()
+            .name("Presence Thread".to_string())
+            .spawn(move || worker(client_id, rx));
+        if let Err(e) = spawned {
+            log::error!("PRESENCE: Could not start the presence thread: {}", e);
+            return Self { sender: None };
+        }
+        Self { sender: Some(tx) }
+    }
+
+    /// Whether this build can publish a presence at all.
+    pub fn available() -> bool {
+        CLIENT_ID.is_some_and(|id| !id.is_empty())
+    }
+
+    /// Publishes `activity`, or clears the presence with `None`.
+    pub fn set(&self, activity: Option<Activity>) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(activity);
+        }
+    }
+}
+
+fn worker(client_id: &str, rx: Receiver<Option<Activity>>) {
+    let mut client: Option<DiscordIpc> = None;
+    let mut current: Option<Activity> = None;
+    // Whether Discord still has to be told about `current`.
+    let mut pending = false;
+
+    loop {
+        match rx.recv_timeout(RETRY_DELAY) {
+            Ok(activity) => {
+                current = activity;
+                pending = true;
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        // Only the latest state matters.
+        for activity in rx.try_iter() {
+            current = activity;
+            pending = true;
+        }
+        if !pending {
+            continue;
+        }
+
+        if client.is_none() {
+            if current.is_none() {
+                // Nothing shown, nothing to clear.
+                pending = false;
+                continue;
+            }
+            match DiscordIpc::connect(client_id) {
+                Ok(connected) => {
+                    log::info!("PRESENCE: Connected to Discord");
+                    client = Some(connected);
+                }
+                Err(e) => {
+                    log::debug!("PRESENCE: Discord unavailable: {}", e);
+                    continue;
+                }
+            }
+        }
+        if let Some(ipc) = client.as_mut() {
+            match ipc.set_activity(current.as_ref()) {
+                Ok(()) => pending = false,
+                Err(e) => {
+                    log::warn!("PRESENCE: Lost Discord connection: {}", e);
+                    client = None;
+                }
+            }
+        }
+    }
+    log::info!("PRESENCE: Thread stopped");
+}
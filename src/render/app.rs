@@ -5,20 +5,35 @@
 
 use std::sync::Arc;
 use winit::application::ApplicationHandler;
-use winit::event::WindowEvent;
+use winit::dpi::PhysicalPosition;
+use winit::event::{ElementState, MouseButton, WindowEvent};
 use winit::event_loop::ActiveEventLoop;
-use winit::keyboard::PhysicalKey;
-use winit::window::{Window, WindowId};
+use winit::keyboard::{KeyCode, PhysicalKey};
+use winit::window::{Icon, Window, WindowId};
 
-use crate::input::events::RawInputEvent;
+use crate::input::events::{GameAction, InputSource, RawInputEvent};
+use crate::models::settings::{DisplayMode, SettingsState};
+use crate::models::skin::package;
+use crate::models::tournament;
+use crate::render::display::apply_display_mode;
 use crate::render::renderer::Renderer;
 use crate::system::bus::{SystemBus, SystemEvent};
 
+/// Title of the window when nothing is in progress.
+const WINDOW_TITLE: &str = "rVsrg 2.0";
+
+/// Window and taskbar icon, embedded in the binary.
+const ICON_PNG: &[u8] = include_bytes!("../../assets/icon.png");
+
 /// Main application struct handling window events.
 pub struct App {
     bus: SystemBus,
     window: Option<Arc<Window>>,
     renderer: Option<Renderer>,
+    /// Last known cursor position, for receptor clicks.
+    cursor: PhysicalPosition<f64>,
+    /// Receptor column held by each mouse button, released on button up.
+    held_receptors: Vec<(MouseButton, usize)>,
 }
 
 impl App {
@@ -28,6 +43,8 @@ impl App {
             bus,
             window: None,
             renderer: None,
+            cursor: PhysicalPosition::new(0.0, 0.0),
+            held_receptors: Vec::new(),
         }
     }
 
@@ -45,11 +62,18 @@ impl ApplicationHandler for App {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
         if self.window.is_none() {
             log::info!("RENDER: Creating window...");
+            let settings = SettingsState::load();
+            crate::shared::locale::set_language(&settings.language);
+            let (width, height) = settings.resolution;
             let win_attr = winit::window::Window::default_attributes()
-                .with_title("rVsrg 2.0")
-                .with_inner_size(winit::dpi::LogicalSize::new(1280.0, 720.0));
+                .with_title(WINDOW_TITLE)
+                .with_window_icon(window_icon())
+                .with_inner_size(winit::dpi::PhysicalSize::new(width, height));
 
             let window = Arc::new(event_loop.create_window(win_attr).unwrap());
+            if settings.display_mode != DisplayMode::Windowed {
+                apply_display_mode(&window, &settings);
+            }
             self.window = Some(window.clone());
 
             log::info!("RENDER: Initializing WGPU...");
@@ -80,13 +104,75 @@ impl ApplicationHandler for App {
                 if let PhysicalKey::Code(keycode) = key_event.physical_key
                     && !key_event.repeat
                 {
+                    if key_event.state == ElementState::Pressed {
+                        self.bus.stamp_key_press();
+                        if keycode == KeyCode::F12
+                            && let Some(renderer) = self.renderer.as_mut()
+                        {
+                            renderer.screenshots.request();
+                        }
+                    }
                     let raw_event = RawInputEvent {
-                        keycode,
+                        source: InputSource::Key(keycode),
                         state: key_event.state,
                     };
                     let _ = self.bus.raw_input_tx.send(raw_event);
                 }
             }
+            WindowEvent::CursorMoved { position, .. } => {
+                self.cursor = position;
+            }
+            WindowEvent::MouseInput { state, button, .. } => {
+                // The release goes to the column the press started on, even if
+                // the cursor moved away in between.
+                let column = match state {
+                    ElementState::Pressed => self
+                        .renderer
+                        .as_ref()
+                        .and_then(|r| r.receptor_column_at(self.cursor))
+                        .inspect(|&column| self.held_receptors.push((button, column))),
+                    ElementState::Released => self
+                        .held_receptors
+                        .iter()
+                        .position(|&(b, _)| b == button)
+                        .map(|idx| self.held_receptors.swap_remove(idx).1),
+                };
+                if let Some(column) = column {
+                    if state == ElementState::Pressed {
+                        self.bus.stamp_key_press();
+                    }
+                    let _ = self.bus.raw_input_tx.send(RawInputEvent {
+                        source: InputSource::Receptor(column),
+                        state,
+                    });
+                }
+            }
+            WindowEvent::DroppedFile(path) => {
+                let is_archive = path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| {
+                        ext.eq_ignore_ascii_case("osz") || ext.eq_ignore_ascii_case("zip")
+                    });
+                if is_archive {
+                    log::info!("RENDER: Archive dropped: {:?}", path);
+                    let _ = self.bus.action_tx.send(GameAction::ImportArchive(path));
+                } else if package::is_package(&path) {
+                    if let Some(renderer) = self.renderer.as_mut() {
+                        Renderer::import_skin_package(&path, &mut renderer.toasts);
+                    }
+                } else if tournament::is_signed_replay(&path) {
+                    if let Some(renderer) = self.renderer.as_mut() {
+                        renderer.verify_signed_replay(&path);
+                    }
+                } else if let Some(renderer) = self.renderer.as_mut()
+                    && !renderer.is_editing_skin()
+                {
+                    renderer
+                        .toasts
+                        .push("Only .osz, .zip and .rvskin archives can be imported.".to_string());
+                }
+            }
             WindowEvent::CloseRequested => {
                 log::info!("RENDER: Close requested");
                 let _ = self.bus.sys_tx.send(SystemEvent::Quit);
@@ -103,6 +189,18 @@ impl ApplicationHandler for App {
             }
             WindowEvent::RedrawRequested => {
                 if let Some(window) = self.window.as_ref() {
+                    if let Some(renderer) = self.renderer.as_mut() {
+                        for label in self.bus.pad_press_rx.try_iter() {
+                            renderer.capture_pad_button(label);
+                        }
+                        for message in self.bus.toast_rx.try_iter() {
+                            renderer.toasts.push(message);
+                        }
+                    }
+                    if let Some(progress) = self.bus.scan_progress_rx.try_iter().last() {
+                        window.set_title(&window_title(progress));
+                    }
+
                     // Update state from logic thread
                     if let Some(snapshot) = self.bus.render_rx.try_iter().last()
                         && let Some(renderer) = self.renderer.as_mut()
@@ -133,6 +231,7 @@ impl ApplicationHandler for App {
                             #[allow(unreachable_patterns)]
                             Err(e) => log::error!("Render error: {e:?}"),
                         }
+                        renderer.limit_frame_rate();
                     }
                     window.request_redraw();
                 }
@@ -141,3 +240,46 @@ impl ApplicationHandler for App {
         }
     }
 }
+
+/// Window title, with the progress of a running library scan or import.
+fn window_title(scan_progress: Option<(usize, usize)>) -> String {
+    match scan_progress {
+        Some((done, total)) if total > 0 => format!(
+            "{} - Scanning {}% ({}/{})",
+            WINDOW_TITLE,
+            done * 100 / total,
+            done,
+            total
+        ),
+        _ => WINDOW_TITLE.to_string(),
+    }
+}
+
+fn window_icon() -> Option<Icon> {
+    let image = match image::load_from_memory(ICON_PNG) {
+        Ok(image) => image.to_rgba8(),
+        Err(e) => {
+            log::warn!("RENDER: Could not decode the window icon: {}", e);
+            return None;
+        }
+    };
+    let (width, height) = image.dimensions();
+    Icon::from_rgba(image.into_raw(), width, height)
+        .inspect_err(|e| log::warn!("RENDER: Invalid window icon: {}", e))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn title_shows_the_scan_progress() {
+        assert_eq!(window_title(None), WINDOW_TITLE);
+        assert_eq!(
+            window_title(Some((50, 200))),
+            "rVsrg 2.0 - Scanning 25% (50/200)"
+        );
+        assert!(window_icon().is_some());
+    }
+}
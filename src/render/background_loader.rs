@@ -0,0 +1,114 @@
+//! Song backgrounds decoded off the render thread.
+//!
+//! Decoding a large JPEG/PNG takes long enough to drop frames, so paths are
+//! sent to a few worker threads that return RGBA buffers over a channel. The
+//! render thread only uploads finished images. Recently decoded images are
+//! kept in a small LRU cache, so going back and forth in song select does not
+//! decode the same files again.
+
+use crossbeam_channel::{Receiver, Sender, unbounded};
+use image::RgbaImage;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::thread;
+
+/// Decoding threads.
+const WORKERS: usize = 2;
+/// Decoded images kept around (a 1080p background is about 8 MB).
+const CACHE_SIZE: usize = 8;
+
+/// State of a requested image.
+pub enum Decoded {
+    Ready(Arc<RgbaImage>),
+    Pending,
+    Failed,
+}
+
+pub struct BackgroundLoader {
+    requests: Sender<String>,
+    results: Receiver<(String, Option<RgbaImage>)>,
+    /// Least recently used first.
+    cache: Vec<(String, Arc<RgbaImage>)>,
+    in_flight: HashSet<String>,
+    failed: HashSet<String>,
+}
+
+impl BackgroundLoader {
+    pub fn new() -> Self {
+        let (requests, jobs) = unbounded::<String>();
+        let (done, results) = unbounded();
+        for i in 0..WORKERS {
+            let jobs = jobs.clone();
+            let done = done.clone();
+            let spawned = thread::Builder::new()
+                .name(format!("background-decode-{}", i))
+                .spawn(move || {
+                    for path in jobs.iter() {
+                        let image = match image::open(&path) {
+                            Ok(image) => Some(image.to_rgba8()),
+                            Err(e) => {
+                                log::warn!("Failed to load texture {:?}: {}", path, e);
+                                None
+                            }
+                        };
+                        if done.send((path, image)).is_err() {
+                            break;
+                        }
+                    }
+                });
+            if let Err(e) = spawned {
+                log::error!("RENDER: Could not start background decoder: {}", e);
+            }
+        }
+
+        Self {
+            requests,
+            results,
+            cache: Vec::new(),
+            in_flight: HashSet::new(),
+            failed: HashSet::new(),
+        }
+    }
+
+    /// Starts decoding `path` unless it is cached, in flight or known broken.
+    pub fn request(&mut self, path: &str) {
+        if self.cache.iter().any(|(p, _)| p == path)
+            || self.in_flight.contains(path)
+            || self.failed.contains(path)
+        {
+            return;
+        }
+        if self.requests.send(path.to_string()).is_ok() {
+            self.in_flight.insert(path.to_string());
+        }
+    }
+
+    /// Collects finished images, then reports on `path`.
+    pub fn poll(&mut self, path: &str) -> Decoded {
+        for (done, image) in self.results.try_iter() {
+            self.in_flight.remove(&done);
+            match image {
+                Some(image) => {
+                    self.cache.push((done, Arc::new(image)));
+                    if self.cache.len() > CACHE_SIZE {
+                        self.cache.remove(0);
+                    }
+                }
+                None => {
+                    self.failed.insert(done);
+                }
+            }
+        }
+
+        if let Some(index) = self.cache.iter().position(|(p, _)| p == path) {
+            let entry = self.cache.remove(index);
+            let image = entry.1.clone();
+            self.cache.push(entry);
+            Decoded::Ready(image)
+        } else if self.failed.contains(path) {
+            Decoded::Failed
+        } else {
+            Decoded::Pending
+        }
+    }
+}
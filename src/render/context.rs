@@ -1,3 +1,4 @@
+use crate::models::settings::PresentModeSetting;
 use std::sync::Arc;
 use winit::window::Window;
 
@@ -8,6 +9,8 @@ pub struct RenderContext {
     pub config: wgpu::SurfaceConfiguration,
     pub size: winit::dpi::PhysicalSize<u32>,
     pub window: Arc<Window>,
+    /// Present modes the surface supports.
+    present_modes: Vec<wgpu::PresentMode>,
 }
 
 impl RenderContext {
@@ -44,28 +47,18 @@ impl RenderContext {
             .find(|f| f.is_srgb())
             .unwrap_or(surface_caps.formats[0]);
 
-        // SÉLECTION INTELLIGENTE DU MODE DE PRÉSENTATION (FPS DÉBRIDÉS)
-        // 1. Immediate : Pas de VSync, FPS max, risque de tearing (déchirement). C'est ce qu'on veut pour la perf pure.
-        // 2. Mailbox : Pas de VSync, FPS max, pas de tearing (Triple Buffering). Excellent si dispo.
-        // 3. Fifo : VSync activée (Cap à 60/144Hz). Fallback obligatoire.
-        let present_mode = surface_caps
-            .present_modes
-            .iter()
-            .copied()
-            .find(|&mode| mode == wgpu::PresentMode::Immediate) // Priorité absolu au mode "No VSync"
-            .or_else(|| {
-                surface_caps
-                    .present_modes
-                    .iter()
-                    .copied()
-                    .find(|&mode| mode == wgpu::PresentMode::Mailbox)
-            })
-            .unwrap_or(wgpu::PresentMode::Fifo);
+        // Immediate (FPS débridés) par défaut ; le choix du joueur est appliqué
+        // ensuite par `set_present_mode`.
+        let present_mode =
+            pick_present_mode(&surface_caps.present_modes, PresentModeSetting::Immediate);
 
         log::info!("RENDER: Selected Present Mode: {:?}", present_mode);
 
+        // COPY_SRC permet les captures d'écran (F12), si la surface le supporte.
+        let usage = wgpu::TextureUsages::RENDER_ATTACHMENT
+            | (surface_caps.usages & wgpu::TextureUsages::COPY_SRC);
         let config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            usage,
             format: texture_format,
             width: size.width,
             height: size.height,
@@ -84,6 +77,17 @@ impl RenderContext {
             config,
             size,
             window,
+            present_modes: surface_caps.present_modes,
+        }
+    }
+
+    /// Reconfigures the surface if `setting` resolves to another present mode.
+    pub fn set_present_mode(&mut self, setting: PresentModeSetting) {
+        let present_mode = pick_present_mode(&self.present_modes, setting);
+        if present_mode != self.config.present_mode {
+            log::info!("RENDER: Present mode changed to {:?}", present_mode);
+            self.config.present_mode = present_mode;
+            self.surface.configure(&self.device, &self.config);
         }
     }
 
@@ -96,3 +100,25 @@ impl RenderContext {
         }
     }
 }
+
+/// Resolves the setting against what the surface supports:
+/// 1. Immediate : pas de VSync, FPS max, risque de tearing.
+/// 2. Mailbox : pas de VSync, pas de tearing (triple buffering).
+/// 3. Fifo : VSync, toujours disponible (fallback obligatoire).
+fn pick_present_mode(
+    supported: &[wgpu::PresentMode],
+    setting: PresentModeSetting,
+) -> wgpu::PresentMode {
+    let preference: &[wgpu::PresentMode] = match setting {
+        PresentModeSetting::Immediate => {
+            &[wgpu::PresentMode::Immediate, wgpu::PresentMode::Mailbox]
+        }
+        PresentModeSetting::Mailbox => &[wgpu::PresentMode::Mailbox, wgpu::PresentMode::Immediate],
+        PresentModeSetting::VSync => &[],
+    };
+    preference
+        .iter()
+        .copied()
+        .find(|mode| supported.contains(mode))
+        .unwrap_or(wgpu::PresentMode::Fifo)
+}
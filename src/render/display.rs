@@ -0,0 +1,94 @@
+//! Window mode switching (windowed, borderless, exclusive fullscreen).
+//!
+//! Switching only touches the window: the resize event that follows
+//! reconfigures the surface and recalculates the `PixelSystem` and the HUD
+//! layout, like a manual resize.
+
+use crate::models::settings::{DisplayMode, SettingsState};
+use crate::views::settings::MonitorInfo;
+use winit::dpi::PhysicalSize;
+use winit::window::{Fullscreen, Window};
+
+/// Display settings last applied to the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AppliedDisplay {
+    mode: DisplayMode,
+    monitor: usize,
+    resolution: (u32, u32),
+}
+
+impl AppliedDisplay {
+    pub fn from_settings(settings: &SettingsState) -> Self {
+        Self {
+            mode: settings.display_mode,
+            monitor: settings.monitor,
+            resolution: settings.resolution,
+        }
+    }
+}
+
+/// Connected monitors and their resolutions, for the settings window.
+pub fn list_monitors(window: &Window) -> Vec<MonitorInfo> {
+    window
+        .available_monitors()
+        .enumerate()
+        .map(|(index, monitor)| {
+            let mut resolutions: Vec<(u32, u32)> = monitor
+                .video_modes()
+                .map(|mode| (mode.size().width, mode.size().height))
+                .collect();
+            resolutions.sort_unstable_by(|a, b| b.cmp(a));
+            resolutions.dedup();
+            MonitorInfo {
+                name: monitor
+                    .name()
+                    .unwrap_or_else(|| format!("Monitor {}", index + 1)),
+                resolutions,
+            }
+        })
+        .collect()
+}
+
+/// Puts the window in the mode described by `settings`.
+pub fn apply_display_mode(window: &Window, settings: &SettingsState) {
+    let monitor = window
+        .available_monitors()
+        .nth(settings.monitor)
+        .or_else(|| window.current_monitor());
+    let (width, height) = settings.resolution;
+
+    match settings.display_mode {
+        DisplayMode::Windowed => {
+            window.set_fullscreen(None);
+            let _ = window.request_inner_size(PhysicalSize::new(width, height));
+        }
+        DisplayMode::Borderless => window.set_fullscreen(Some(Fullscreen::Borderless(monitor))),
+        DisplayMode::Exclusive => {
+            // Highest refresh rate available at the chosen resolution.
+            let video_mode = monitor.as_ref().and_then(|monitor| {
+                monitor
+                    .video_modes()
+                    .filter(|mode| mode.size() == PhysicalSize::new(width, height))
+                    .max_by_key(|mode| (mode.refresh_rate_millihertz(), mode.bit_depth()))
+            });
+            match video_mode {
+                Some(video_mode) => window.set_fullscreen(Some(Fullscreen::Exclusive(video_mode))),
+                None => {
+                    log::warn!(
+                        "RENDER: No {}x{} video mode on this monitor, using borderless",
+                        width,
+                        height
+                    );
+                    window.set_fullscreen(Some(Fullscreen::Borderless(monitor)));
+                }
+            }
+        }
+    }
+    log::info!(
+        "RENDER: Display mode {:?} ({}x{}, monitor {})",
+        settings.display_mode,
+        width,
+        height,
+        settings.monitor
+    );
+}
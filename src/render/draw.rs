@@ -1,4 +1,5 @@
 use crate::render::context::RenderContext;
+use crate::render::gameplay_background::BackgroundSource;
 use crate::render::resources::RenderResources;
 use crate::shared::snapshot::{GameplaySnapshot, RenderState};
 use crate::views::context::GameplayRenderContext;
@@ -13,24 +14,31 @@ pub fn draw_game(
     fps: f64,
 ) {
     match state {
-        RenderState::InGame(snapshot) => {
-            encoder.begin_render_pass(&RenderPassDescriptor {
-                label: Some("Gameplay Clear"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view,
-                    resolve_target: None,
-                    ops: Operations {
-                        load: LoadOp::Clear(Color::BLACK),
-                        store: wgpu::StoreOp::Store,
-                    },
-                    depth_slice: None,
-                })],
-                depth_stencil_attachment: None,
-                timestamp_writes: None,
-                occlusion_query_set: None,
-            });
-            draw_gameplay(ctx, res, encoder, view, snapshot, fps);
-        }
+        RenderState::InGame(snapshot) => match &snapshot.versus {
+            // Both sides write the same note buffers, so the left one is
+            // submitted on its own before the right one is recorded.
+            Some(opponent) => {
+                let mut left = ctx
+                    .device
+                    .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                        label: Some("Versus Left Encoder"),
+                    });
+                let quarter = ctx.config.width as f32 / 4.0;
+                let x_offset = res
+                    .gameplay_view
+                    .playfield_component()
+                    .config
+                    .x_offset_pixels;
+                set_playfield_offset(res, x_offset - quarter);
+                draw_in_game(ctx, res, &mut left, view, snapshot, fps);
+                ctx.queue.submit([left.finish()]);
+
+                set_playfield_offset(res, x_offset + quarter);
+                draw_opponent(ctx, res, encoder, view, opponent, fps);
+                set_playfield_offset(res, x_offset);
+            }
+            None => draw_in_game(ctx, res, encoder, view, snapshot, fps),
+        },
         RenderState::Editor(snapshot) => {
             encoder.begin_render_pass(&RenderPassDescriptor {
                 label: Some("Editor Clear"),
@@ -75,6 +83,84 @@ pub fn draw_game(
     }
 }
 
+/// Background and playfield of a run.
+fn draw_in_game(
+    ctx: &RenderContext,
+    res: &mut RenderResources,
+    encoder: &mut CommandEncoder,
+    view: &TextureView,
+    snapshot: &GameplaySnapshot,
+    fps: f64,
+) {
+    if let (Some(path), Some((bg_view, width, height))) =
+        (&res.current_background_path, &res.background_texture)
+    {
+        let source = BackgroundSource {
+            path,
+            view: bg_view,
+            width: *width,
+            height: *height,
+        };
+        res.gameplay_background.prepare(
+            &ctx.device,
+            &ctx.queue,
+            &source,
+            res.settings.background_blur,
+        );
+    }
+    res.gameplay_background
+        .draw(&ctx.queue, encoder, view, res.settings.background_dim);
+    // The covers are moved in game by the logic thread; keep the copy
+    // the settings window edits (and saves) in sync, unless it is open.
+    if !res.settings.is_open {
+        res.settings.lane_cover = snapshot.lane_cover;
+    }
+    draw_gameplay(ctx, res, encoder, view, snapshot, fps);
+}
+
+fn set_playfield_offset(res: &mut RenderResources, x_offset_pixels: f32) {
+    res.gameplay_view
+        .playfield_component_mut()
+        .config
+        .x_offset_pixels = x_offset_pixels;
+}
+
+/// Right playfield of a versus race.
+fn draw_opponent(
+    ctx: &RenderContext,
+    res: &mut RenderResources,
+    encoder: &mut CommandEncoder,
+    view: &TextureView,
+    snapshot: &GameplaySnapshot,
+    fps: f64,
+) {
+    let mut view_ctx = GameplayRenderContext {
+        device: &ctx.device,
+        queue: &ctx.queue,
+        text_brush: &mut res.text_brush,
+        render_pipeline: &res.render_pipeline,
+        progress_pipeline: &res.progress_pipeline,
+        quad_pipeline: &res.quad_pipeline,
+        instance_buffer: &res.instance_buffer,
+        receptor_buffer: &res.receptor_buffer,
+        progress_buffer: &res.progress_buffer,
+        quad_buffer: &res.quad_buffer,
+        sprite_buffer: &res.sprite_buffer,
+        sprite_atlas: &res.sprite_atlas,
+        miss_feedback: &res.miss_feedback,
+        view,
+        pixel_system: &res.pixel_system,
+        lane_cover_color: res.skin.gameplay.lane_cover.color,
+        screen_width: ctx.config.width as f32,
+        screen_height: ctx.config.height as f32,
+        fps,
+        master_volume: 1.0,
+    };
+    let _ = res
+        .gameplay_view
+        .render_opponent(&mut view_ctx, encoder, snapshot, "Opponent");
+}
+
 fn draw_background(
     _ctx: &RenderContext,
     res: &RenderResources,
@@ -127,32 +213,41 @@ pub fn draw_gameplay(
     snapshot: &GameplaySnapshot,
     fps: f64,
 ) {
+    res.miss_feedback.update(
+        &ctx.queue,
+        snapshot,
+        &res.skin.gameplay.feedback,
+        ctx.config.width as f32,
+        ctx.config.height as f32,
+    );
+
     let mut view_ctx = GameplayRenderContext {
         device: &ctx.device,
         queue: &ctx.queue,
         text_brush: &mut res.text_brush,
         render_pipeline: &res.render_pipeline,
         progress_pipeline: &res.progress_pipeline,
+        quad_pipeline: &res.quad_pipeline,
         instance_buffer: &res.instance_buffer,
         receptor_buffer: &res.receptor_buffer,
         progress_buffer: &res.progress_buffer,
-        note_bind_groups: &res.note_bind_groups,
-        receptor_bind_groups: &res.receptor_bind_groups,
-        receptor_pressed_bind_groups: &res.receptor_pressed_bind_groups,
-        mine_bind_group: res.mine_bind_group.as_ref(),
-        hold_body_bind_group: res.hold_body_bind_group.as_ref(),
-        hold_end_bind_group: res.hold_end_bind_group.as_ref(),
-        burst_body_bind_group: res.burst_body_bind_group.as_ref(),
-        burst_end_bind_group: res.burst_end_bind_group.as_ref(),
+        quad_buffer: &res.quad_buffer,
+        sprite_buffer: &res.sprite_buffer,
+        sprite_atlas: &res.sprite_atlas,
+        miss_feedback: &res.miss_feedback,
         view,
         pixel_system: &res.pixel_system,
+        lane_cover_color: res.skin.gameplay.lane_cover.color,
         screen_width: ctx.config.width as f32,
         screen_height: ctx.config.height as f32,
         fps,
         master_volume: 1.0,
     };
 
-    // Get colors from new skin structure
+    // Couleurs du skin, sous la palette choisie par le joueur
+    let palette = res.settings.judgement_palette;
+    res.judgements_panel.set_palette(palette);
+    res.hit_bar.set_palette(palette);
     let judgement = &res.skin.hud.judgement;
     let colors = crate::models::stats::JudgementColors {
         marv: judgement.marv.color,
@@ -162,7 +257,8 @@ pub fn draw_gameplay(
         bad: judgement.bad.color,
         miss: judgement.miss.color,
         ghost_tap: judgement.ghost_tap.color,
-    };
+    }
+    .with_palette(palette);
 
     // Get labels from new skin structure
     let labels = res.skin.get_judgement_labels();
@@ -181,7 +277,14 @@ pub fn draw_gameplay(
         &mut res.notes_remaining_display,
         &mut res.scroll_speed_display,
         &mut res.time_left_display,
+        &mut res.pb_delta_display,
+        &mut res.early_late_display,
+        &mut res.column_judgements_display,
+        &mut res.key_overlay_display,
+        &mut res.script_widgets,
         &colors,
         &labels,
+        &res.skin.hud,
+        &res.skin.gameplay.notes.burst,
     );
 }
@@ -0,0 +1,290 @@
+//! Miss feedback of the skin: the notes and receptors shake, and the screen
+//! edges flash, when the player misses or breaks a combo.
+//!
+//! Both go through a uniform of the main sprite shader: an offset added to
+//! every note and receptor, and the color of a vignette drawn over the
+//! playfield. HUD sprites and the versus opponent bind a zeroed copy, so
+//! they stay still.
+
+use crate::models::skin::gameplay::FeedbackConfig;
+use crate::models::skin::gameplay::feedback::FeedbackTrigger;
+use crate::shaders::constants::MAIN_SHADER_SRC;
+use crate::shared::snapshot::GameplaySnapshot;
+use std::time::Instant;
+use wgpu::{BindGroup, BindGroupLayout, Buffer, Device, Queue, RenderPipeline, TextureFormat};
+
+/// Size of the shader's `Feedback` struct: the offset padded to 16 bytes,
+/// then the vignette color.
+const UNIFORM_SIZE: u64 = 32;
+
+/// Counters of the play compared from one frame to the next.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PlayCounters {
+    combo: u32,
+    misses: u32,
+    /// Judged notes, ghost taps aside.
+    judged: u32,
+}
+
+impl PlayCounters {
+    fn of(snapshot: &GameplaySnapshot) -> Self {
+        let stats = &snapshot.hit_stats;
+        Self {
+            combo: snapshot.combo,
+            misses: stats.miss,
+            judged: stats.marv + stats.perfect + stats.great + stats.good + stats.bad + stats.miss,
+        }
+    }
+}
+
+pub struct MissFeedback {
+    vignette_pipeline: RenderPipeline,
+    buffer: Buffer,
+    bind_group: BindGroup,
+    /// Zeroed uniform, for what must not shake.
+    still_bind_group: BindGroup,
+    last: Option<PlayCounters>,
+    started: Option<Instant>,
+    vignette_visible: bool,
+}
+
+/// Layout of the uniform, group 1 of the main sprite pipeline.
+pub fn create_bind_group_layout(device: &Device) -> BindGroupLayout {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Miss Feedback Layout"),
+        entries: &[wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }],
+    })
+}
+
+impl MissFeedback {
+    pub fn new(
+        device: &Device,
+        texture_layout: &BindGroupLayout,
+        layout: &BindGroupLayout,
+        format: TextureFormat,
+    ) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Miss Feedback Shader"),
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(MAIN_SHADER_SRC)),
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Miss Feedback Pipeline Layout"),
+            bind_group_layouts: &[texture_layout, layout],
+            push_constant_ranges: &[],
+        });
+        let vignette_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Miss Vignette Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_vignette"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_vignette"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        let (buffer, bind_group) = uniform_buffer(device, layout, "Miss Feedback");
+        // Never written: buffers start zeroed.
+        let (_, still_bind_group) = uniform_buffer(device, layout, "Miss Feedback (still)");
+
+        Self {
+            vignette_pipeline,
+            buffer,
+            bind_group,
+            still_bind_group,
+            last: None,
+            started: None,
+            vignette_visible: false,
+        }
+    }
+
+    /// Starts the effects on a new miss and writes this frame's offset and
+    /// vignette color.
+    pub fn update(
+        &mut self,
+        queue: &Queue,
+        snapshot: &GameplaySnapshot,
+        config: &FeedbackConfig,
+        screen_width: f32,
+        screen_height: f32,
+    ) {
+        let now = PlayCounters::of(snapshot);
+        if let Some(before) = self.last.replace(now)
+            && (config.shake || config.vignette)
+            && is_triggered(config, &before, &now)
+        {
+            self.started = Some(Instant::now());
+        }
+
+        let elapsed_ms = self
+            .started
+            .map(|started| started.elapsed().as_secs_f32() * 1000.0);
+        let shake = elapsed_ms
+            .filter(|_| config.shake)
+            .map_or(0.0, |elapsed| fade(elapsed, config.shake_duration_ms));
+        let flash = elapsed_ms
+            .filter(|_| config.vignette)
+            .map_or(0.0, |elapsed| fade(elapsed, config.vignette_duration_ms));
+        if shake == 0.0 && flash == 0.0 {
+            self.started = None;
+        }
+
+        let (x, y) = match elapsed_ms {
+            Some(elapsed) if shake > 0.0 => {
+                let amplitude = config.shake_intensity * shake * screen_height / 1080.0;
+                (
+                    (elapsed * 0.11).sin() * amplitude,
+                    (elapsed * 0.17).cos() * amplitude * 0.5,
+                )
+            }
+            _ => (0.0, 0.0),
+        };
+        let [r, g, b, a] = config.vignette_color;
+        self.vignette_visible = flash > 0.0 && a > 0.0;
+        let uniform: [f32; 8] = [
+            x * 2.0 / screen_width.max(1.0),
+            -y * 2.0 / screen_height.max(1.0),
+            0.0,
+            0.0,
+            r,
+            g,
+            b,
+            a * flash,
+        ];
+        queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&uniform));
+    }
+
+    /// Uniform of the shaking notes and receptors.
+    pub fn playfield_bind_group(&self) -> &BindGroup {
+        &self.bind_group
+    }
+
+    /// Uniform of what stays still (HUD, versus opponent).
+    pub fn still_bind_group(&self) -> &BindGroup {
+        &self.still_bind_group
+    }
+
+    /// Edge flash, drawn right after the playfield, whose atlas is still
+    /// bound to group 0.
+    pub fn draw_vignette(&self, render_pass: &mut wgpu::RenderPass<'_>) {
+        if !self.vignette_visible {
+            return;
+        }
+        render_pass.set_pipeline(&self.vignette_pipeline);
+        render_pass.set_bind_group(1, &self.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+/// Whether the play went from `before` to `now` through a miss the skin
+/// reacts to. Fewer judged notes than before means a restart or a rewind,
+/// not a miss.
+fn is_triggered(config: &FeedbackConfig, before: &PlayCounters, now: &PlayCounters) -> bool {
+    if now.judged < before.judged {
+        return false;
+    }
+    match config.trigger {
+        FeedbackTrigger::Miss => now.misses > before.misses,
+        FeedbackTrigger::ComboBreak => {
+            now.combo < before.combo && before.combo >= config.min_combo.max(1)
+        }
+    }
+}
+
+/// Strength of an effect `elapsed_ms` into it, from 1 down to 0.
+fn fade(elapsed_ms: f32, duration_ms: f32) -> f32 {
+    if duration_ms <= 0.0 {
+        return 0.0;
+    }
+    (1.0 - elapsed_ms / duration_ms).clamp(0.0, 1.0)
+}
+
+fn uniform_buffer(device: &Device, layout: &BindGroupLayout, label: &str) -> (Buffer, BindGroup) {
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some(label),
+        size: UNIFORM_SIZE,
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some(label),
+        layout,
+        entries: &[wgpu::BindGroupEntry {
+            binding: 0,
+            resource: buffer.as_entire_binding(),
+        }],
+    });
+    (buffer, bind_group)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counters(combo: u32, misses: u32, judged: u32) -> PlayCounters {
+        PlayCounters {
+            combo,
+            misses,
+            judged,
+        }
+    }
+
+    #[test]
+    fn triggers_on_misses_or_long_combo_breaks_but_not_restarts() {
+        let mut config = FeedbackConfig::default();
+        assert!(is_triggered(
+            &config,
+            &counters(30, 0, 30),
+            &counters(0, 1, 31)
+        ));
+        assert!(!is_triggered(
+            &config,
+            &counters(10, 0, 10),
+            &counters(0, 1, 11)
+        ));
+        assert!(!is_triggered(
+            &config,
+            &counters(300, 2, 310),
+            &counters(0, 0, 0)
+        ));
+
+        config.trigger = FeedbackTrigger::Miss;
+        assert!(is_triggered(
+            &config,
+            &counters(0, 1, 11),
+            &counters(0, 2, 12)
+        ));
+        assert!(!is_triggered(
+            &config,
+            &counters(5, 1, 11),
+            &counters(6, 1, 12)
+        ));
+
+        assert_eq!(fade(75.0, 150.0), 0.5);
+        assert_eq!(fade(200.0, 150.0), 0.0);
+    }
+}
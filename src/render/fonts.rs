@@ -0,0 +1,223 @@
+//! Fallback fonts for the interface and gameplay text.
+//!
+//! egui's default fonts and the skin fonts usually cover Latin only, so
+//! Japanese, Chinese or Korean titles would show as empty boxes. The fonts
+//! bundled in `assets/fonts/` are appended after them or, when there are
+//! none, one system font per script if the player's OS has it. egui takes
+//! each glyph from the first font of the chain that has it; the wgpu text
+//! brush has no such chain, so its text is split into runs per font first.
+
+use egui::{FontData, FontDefinitions, FontFamily};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use wgpu_text::glyph_brush::ab_glyph::{Font, FontArc};
+use wgpu_text::glyph_brush::{FontId, Section, Text};
+
+/// Fonts shipped with the game, read in file name order.
+const BUNDLED_FONTS_DIR: &str = "assets/fonts";
+
+/// System fonts tried when nothing is bundled, first found per script.
+const SYSTEM_FONTS: &[(&str, &[&str])] = &[
+    (
+        "CJK",
+        &[
+            "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+            "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+            "/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc",
+            "/usr/share/fonts/truetype/droid/DroidSansFallbackFull.ttf",
+            "C:\\Windows\\Fonts\\YuGothM.ttc",
+            "C:\\Windows\\Fonts\\meiryo.ttc",
+            "C:\\Windows\\Fonts\\msgothic.ttc",
+            "/System/Library/Fonts/ヒラギノ角ゴシック W3.ttc",
+            "/System/Library/Fonts/Hiragino Sans GB.ttc",
+        ],
+    ),
+    (
+        "Hangul",
+        &[
+            "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+            "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+            "/usr/share/fonts/truetype/nanum/NanumGothic.ttf",
+            "C:\\Windows\\Fonts\\malgun.ttf",
+            "/System/Library/Fonts/AppleSDGothicNeo.ttc",
+        ],
+    ),
+];
+
+/// Fallback fonts as `(name, bytes)`, read once and shared by egui and
+/// every text brush built afterwards (the CJK ones weigh megabytes).
+fn fallback_fonts() -> &'static [(String, &'static [u8])] {
+    static FONTS: OnceLock<Vec<(String, &'static [u8])>> = OnceLock::new();
+    FONTS.get_or_init(|| {
+        let paths = fallback_paths(Path::new(BUNDLED_FONTS_DIR));
+        if paths.is_empty() {
+            log::warn!("RENDER: No CJK fallback font found, such titles will show as boxes");
+        }
+        paths
+            .into_iter()
+            .filter_map(|path| match std::fs::read(&path) {
+                Ok(bytes) => {
+                    log::info!("RENDER: Fallback font {:?}", path);
+                    let bytes: &'static [u8] = Box::leak(bytes.into_boxed_slice());
+                    Some((path.to_string_lossy().into_owned(), bytes))
+                }
+                Err(e) => {
+                    log::warn!("RENDER: Could not read fallback font {:?}: {}", path, e);
+                    None
+                }
+            })
+            .collect()
+    })
+}
+
+/// Appends the fallback fonts to egui's proportional and monospace families.
+pub fn install(ctx: &egui::Context) {
+    let fallbacks = fallback_fonts();
+    if fallbacks.is_empty() {
+        return;
+    }
+
+    let mut fonts = FontDefinitions::default();
+    for (name, bytes) in fallbacks {
+        fonts
+            .font_data
+            .insert(name.clone(), Arc::new(FontData::from_static(bytes)));
+        for family in [FontFamily::Proportional, FontFamily::Monospace] {
+            fonts.families.entry(family).or_default().push(name.clone());
+        }
+    }
+    ctx.set_fonts(fonts);
+}
+
+/// Fallback fonts for a text brush, to list after its main font.
+pub fn brush_fallbacks() -> Vec<FontArc> {
+    fallback_fonts()
+        .iter()
+        .filter_map(|(name, bytes)| match FontArc::try_from_slice(bytes) {
+            Ok(font) => Some(font),
+            Err(e) => {
+                log::warn!("RENDER: Fallback font {} unusable for text: {}", name, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Splits the text of `sections` into runs drawn with the first font of
+/// `fonts` that has their glyphs, the brush's main font (id 0) first.
+pub fn with_fallback<'a>(mut sections: Vec<Section<'a>>, fonts: &[FontArc]) -> Vec<Section<'a>> {
+    if fonts.len() < 2 {
+        return sections;
+    }
+    for section in &mut sections {
+        // The main font is assumed to cover ASCII: most HUD text is numbers.
+        if section.text.iter().all(|text| text.text.is_ascii()) {
+            continue;
+        }
+        section.text = section
+            .text
+            .iter()
+            .flat_map(|text| split_runs(*text, fonts))
+            .collect();
+    }
+    sections
+}
+
+fn split_runs<'a>(text: Text<'a>, fonts: &[FontArc]) -> Vec<Text<'a>> {
+    let font_for = |c: char| {
+        if c.is_whitespace() || fonts[text.font_id.0].glyph_id(c).0 != 0 {
+            return text.font_id;
+        }
+        fonts
+            .iter()
+            .position(|font| font.glyph_id(c).0 != 0)
+            .map_or(text.font_id, FontId)
+    };
+
+    let mut runs = Vec::new();
+    let mut start = 0;
+    let mut current = text.font_id;
+    for (index, c) in text.text.char_indices() {
+        let font = font_for(c);
+        if font != current && index > start {
+            runs.push(Text {
+                text: &text.text[start..index],
+                font_id: current,
+                ..text
+            });
+            start = index;
+        }
+        current = font;
+    }
+    runs.push(Text {
+        text: &text.text[start..],
+        font_id: current,
+        ..text
+    });
+    runs
+}
+
+/// Bundled fonts if there are any, else the first system font of each script.
+fn fallback_paths(bundled_dir: &Path) -> Vec<PathBuf> {
+    let mut bundled: Vec<PathBuf> = std::fs::read_dir(bundled_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| is_font(path))
+        .collect();
+    if !bundled.is_empty() {
+        bundled.sort();
+        return bundled;
+    }
+
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for (_, candidates) in SYSTEM_FONTS {
+        if let Some(path) = candidates.iter().map(PathBuf::from).find(|p| p.is_file())
+            && !paths.contains(&path)
+        {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+fn is_font(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| matches!(ext.to_ascii_lowercase().as_str(), "ttf" | "otf" | "ttc"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_fonts_come_sorted_and_skip_other_files() {
+        let dir = std::env::temp_dir().join("rvsrg_fonts_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["b.otf", "a.TTF", "README.md"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+
+        let paths = fallback_paths(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(paths, vec![dir.join("a.TTF"), dir.join("b.otf")]);
+    }
+
+    #[test]
+    fn cjk_text_goes_to_the_bundled_fallback() {
+        let main = FontArc::try_from_vec(std::fs::read("assets/font.ttf").unwrap()).unwrap();
+        let cjk = std::fs::read("assets/fonts/LXGWWenKai-Regular.ttf").unwrap();
+        let fonts = vec![main, FontArc::try_from_vec(cjk).unwrap()];
+
+        let sections = vec![Section::default().add_text(Text::new("Title: 夜に駆ける 2"))];
+        let sections = with_fallback(sections, &fonts);
+        let runs: Vec<_> = sections[0]
+            .text
+            .iter()
+            .map(|text| (text.text, text.font_id.0))
+            .collect();
+        assert_eq!(runs, vec![("Title: ", 0), ("夜に駆ける", 1), (" 2", 0)]);
+    }
+}
@@ -0,0 +1,277 @@
+//! In-game background: the song background, dimmed and optionally blurred.
+//!
+//! The blur is a separable gaussian (horizontal then vertical pass, at half
+//! resolution) run once whenever the background or the radius changes; each
+//! frame only draws the cached result with the dim applied, so the dim can be
+//! changed live for free.
+
+use crate::render::utils::{create_bind_group_layout, create_sampler};
+use crate::shaders::constants::GAMEPLAY_BACKGROUND_SHADER_SRC;
+use wgpu::{
+    BindGroup, BindGroupLayout, Buffer, CommandEncoder, Device, Queue, RenderPipeline, Sampler,
+    TextureFormat, TextureView,
+};
+
+/// Format of the intermediate blur textures (same as loaded images).
+const BLUR_FORMAT: TextureFormat = TextureFormat::Rgba8UnormSrgb;
+/// Taps on each side of the center in the blur shader.
+const BLUR_TAPS: f32 = 4.0;
+
+/// Song background currently loaded by `RenderResources`.
+pub struct BackgroundSource<'a> {
+    pub path: &'a str,
+    pub view: &'a TextureView,
+    pub width: u32,
+    pub height: u32,
+}
+
+pub struct GameplayBackground {
+    blur_pipeline: RenderPipeline,
+    dim_pipeline: RenderPipeline,
+    texture_layout: BindGroupLayout,
+    params_layout: BindGroupLayout,
+    sampler: Sampler,
+    dim_buffer: Buffer,
+    dim_bind_group: BindGroup,
+    /// Background path and blur radius the cached bind group was built for.
+    key: Option<(String, u32)>,
+    /// Texture drawn each frame (blurred copy, or the source when radius is 0).
+    bind_group: Option<BindGroup>,
+}
+
+impl GameplayBackground {
+    pub fn new(device: &Device, surface_format: TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Gameplay BG Shader"),
+            source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(
+                GAMEPLAY_BACKGROUND_SHADER_SRC,
+            )),
+        });
+        let texture_layout = create_bind_group_layout(device);
+        let params_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Gameplay BG Params Layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Gameplay BG Layout"),
+            bind_group_layouts: &[&texture_layout, &params_layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = |label, entry_point, format| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some("vs_main"),
+                    buffers: &[],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some(entry_point),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+                cache: None,
+            })
+        };
+        let blur_pipeline = pipeline("Gameplay BG Blur Pipeline", "fs_blur", BLUR_FORMAT);
+        let dim_pipeline = pipeline("Gameplay BG Dim Pipeline", "fs_dim", surface_format);
+
+        let (dim_buffer, dim_bind_group) = params_buffer(device, &params_layout, "Gameplay BG Dim");
+
+        Self {
+            blur_pipeline,
+            dim_pipeline,
+            texture_layout,
+            params_layout,
+            sampler: create_sampler(device),
+            dim_buffer,
+            dim_bind_group,
+            key: None,
+            bind_group: None,
+        }
+    }
+
+    /// Re-blurs the background if it or the radius changed since last frame.
+    pub fn prepare(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        source: &BackgroundSource<'_>,
+        blur_radius: f32,
+    ) {
+        let radius = blur_radius.max(0.0).round() as u32;
+        if self
+            .key
+            .as_ref()
+            .is_some_and(|(path, r)| path == source.path && *r == radius)
+        {
+            return;
+        }
+        self.key = Some((source.path.to_string(), radius));
+
+        if radius == 0 {
+            self.bind_group = Some(self.texture_bind_group(device, source.view));
+            return;
+        }
+
+        let width = (source.width / 2).max(1);
+        let height = (source.height / 2).max(1);
+        let target = |label| {
+            device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: Some(label),
+                    size: wgpu::Extent3d {
+                        width,
+                        height,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: BLUR_FORMAT,
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                        | wgpu::TextureUsages::TEXTURE_BINDING,
+                    view_formats: &[],
+                })
+                .create_view(&wgpu::TextureViewDescriptor::default())
+        };
+        let horizontal = target("Gameplay BG Blur H");
+        let blurred = target("Gameplay BG Blur V");
+
+        // The radius is in pixels of the half-resolution texture.
+        let tap_step = radius as f32 / BLUR_TAPS;
+        let passes = [
+            (source.view, &horizontal, [tap_step / width as f32, 0.0]),
+            (&horizontal, &blurred, [0.0, tap_step / height as f32]),
+        ];
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Gameplay BG Blur Encoder"),
+        });
+        for (input, output, step) in passes {
+            let (params, params_group) =
+                params_buffer(device, &self.params_layout, "Gameplay BG Blur");
+            queue.write_buffer(
+                &params,
+                0,
+                bytemuck::cast_slice(&[step[0], step[1], 0.0, 0.0]),
+            );
+            let input_group = self.texture_bind_group(device, input);
+
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Gameplay BG Blur Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: output,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                    depth_slice: None,
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_pipeline(&self.blur_pipeline);
+            pass.set_bind_group(0, &input_group, &[]);
+            pass.set_bind_group(1, &params_group, &[]);
+            pass.draw(0..6, 0..1);
+        }
+        queue.submit(Some(encoder.finish()));
+
+        self.bind_group = Some(self.texture_bind_group(device, &blurred));
+        log::info!("RENDER: Gameplay background blurred (radius {})", radius);
+    }
+
+    /// Draws the prepared background, or clears to black if there is none.
+    pub fn draw(&self, queue: &Queue, encoder: &mut CommandEncoder, view: &TextureView, dim: f32) {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Gameplay Background"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+                depth_slice: None,
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        let Some(bind_group) = &self.bind_group else {
+            return;
+        };
+        // Fully dimmed: the clear is enough.
+        if dim >= 1.0 {
+            return;
+        }
+        queue.write_buffer(
+            &self.dim_buffer,
+            0,
+            bytemuck::cast_slice(&[0.0f32, 0.0, dim.clamp(0.0, 1.0), 0.0]),
+        );
+        pass.set_pipeline(&self.dim_pipeline);
+        pass.set_bind_group(0, bind_group, &[]);
+        pass.set_bind_group(1, &self.dim_bind_group, &[]);
+        pass.draw(0..6, 0..1);
+    }
+
+    fn texture_bind_group(&self, device: &Device, view: &TextureView) -> BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Gameplay BG Texture"),
+            layout: &self.texture_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+        })
+    }
+}
+
+/// Uniform buffer for the shader `Params` (vec2 step, dim, padding).
+fn params_buffer(device: &Device, layout: &BindGroupLayout, label: &str) -> (Buffer, BindGroup) {
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some(label),
+        size: 16,
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some(label),
+        layout,
+        entries: &[wgpu::BindGroupEntry {
+            binding: 0,
+            resource: buffer.as_entire_binding(),
+        }],
+    });
+    (buffer, bind_group)
+}
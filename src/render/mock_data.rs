@@ -1,9 +1,11 @@
 use crate::database::models::{Beatmap, BeatmapRating, BeatmapWithRatings, Beatmapset};
 use crate::models::engine::NoteData;
+use crate::models::settings::LaneCover;
 use crate::models::stats::{HitStats, Judgement};
-use crate::shared::snapshot::{GameplaySnapshot, RenderState};
+use crate::shared::snapshot::{GameplaySnapshot, NoteWindow, RenderState};
 use crate::state::{GameResultData, MenuState};
 use crate::views::components::editor::layout::EditorScene;
+use std::sync::Arc;
 use std::time::Instant;
 
 /// Génère un état de rendu factice basé sur la scène sélectionnée dans l'éditeur.
@@ -47,12 +49,15 @@ fn create_mock_gameplay(key_count: usize) -> RenderState {
         timestamp: Instant::now(),
         rate: 1.0,
         scroll_speed: 650.0,
-        visible_notes: notes,
+        scroll_map: None,
+        audio_path: None,
+        visible_notes: NoteWindow::from_notes(notes),
         keys_held: vec![false; key_count], // Aucune touche pressée
+        recent_presses: Vec::new(),
         score: 125000,
         accuracy: 98.45,
         combo: 124,
-        hit_stats: HitStats {
+        hit_stats: Arc::new(HitStats {
             marv: 100,
             perfect: 20,
             great: 4,
@@ -60,14 +65,24 @@ fn create_mock_gameplay(key_count: usize) -> RenderState {
             bad: 0,
             miss: 0,
             ghost_tap: 0,
-        },
+            columns: Vec::new(),
+        }),
         remaining_notes: 50,
         last_hit_judgement: Some(Judgement::Marv), // Affiche un jugement pour tester la position
         last_hit_timing: Some(-4.5),
+        recent_hit_offsets: vec![-4.5, 2.0, -7.5, -3.0],
         nps: 12.5,
         practice_mode: false,
+        autoplay: false,
+        lane_cover: LaneCover::default(),
+        column_order: Vec::new(),
+        ghost: None,
+        personal_best: None,
         checkpoints: vec![],
         map_duration: 120000.0,
+        health: None,
+        debug: None,
+        versus: None,
     })
 }
 
@@ -81,6 +96,14 @@ fn create_mock_menu() -> RenderState {
         image_path: None, // Le renderer utilisera le background par défaut
         artist: Some(String::from("Camellia")),
         title: Some(String::from("Ghost")),
+        artist_unicode: None,
+        title_unicode: None,
+        creator: Some(String::from("Mock Mapper")),
+        archived: false,
+        pack: None,
+        tags: None,
+        source: None,
+        edited: false,
     };
 
     let bm1 = Beatmap {
@@ -91,6 +114,8 @@ fn create_mock_menu() -> RenderState {
         note_count: 1540,
         duration_ms: 180000,
         nps: 15.4,
+        load_error: None,
+        pattern_tags: Some(String::from("js,hs")),
     };
 
     let ratings = vec![BeatmapRating {
@@ -124,6 +149,7 @@ fn create_mock_result() -> RenderState {
             bad: 0,
             miss: 1,
             ghost_tap: 5,
+            columns: Vec::new(),
         },
         replay_data: crate::models::replay::ReplayData::empty(),
         replay_result: crate::models::replay::ReplayResult::new(), // Vide pour l'instant (graphes vides)
@@ -131,8 +157,13 @@ fn create_mock_result() -> RenderState {
         accuracy: 99.12,
         max_combo: 850,
         beatmap_hash: Some(String::from("mock_hash")),
+        map_title: String::from("Mock Artist - Mock Song [Insane]"),
         rate: 1.1,
         judge_text: String::from("OD 8.5"),
         show_settings: false,
+        drift_stats: None,
+        course: None,
+        personal_best: None,
+        incomplete: false,
     })
 }
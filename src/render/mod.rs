@@ -1,8 +1,18 @@
 pub mod app;
+pub mod background_loader;
 pub mod context;
+pub mod display;
 pub mod draw;
+pub mod feedback;
+pub mod fonts;
+pub mod gameplay_background;
 pub mod mock_data; // Ajouté
+pub mod recorder;
 pub mod renderer;
 pub mod resources;
+pub mod screenshot;
+pub mod skin_watcher;
+pub mod sprite_atlas;
+pub mod theme;
 pub mod ui;
 pub mod utils;
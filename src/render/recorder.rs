@@ -0,0 +1,407 @@
+//! Experimental recording of plays to video.
+//!
+//! Each gameplay frame is copied into one of a few readback buffers in the
+//! submission that draws it. An encoder thread maps the buffers in order and
+//! pipes the pixels to an `ffmpeg` process, then hands the buffers back.
+//! When every buffer is still in flight the frame is dropped, and the next
+//! one is repeated to fill the gap, so the render loop never waits on the
+//! encoder. Once the play ends, the video is muxed with the chart's music.
+//!
+//! Frames are timed from the audio clock, not the wall clock: pauses do not
+//! show up in the video, and it stays in sync with the music.
+
+use crate::render::screenshot::{
+    copy_to_buffer, padded_bytes_per_row, read_pixels, readback_format,
+};
+use crate::shared::locale::tr_args;
+use crate::shared::snapshot::GameplaySnapshot;
+use crossbeam_channel::{Receiver, Sender, unbounded};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::Arc;
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Folder where recordings are written.
+const RECORDING_DIR: &str = "recordings";
+
+/// Readback buffers in flight at most.
+const RING_SIZE: usize = 3;
+
+/// A jump back of the audio clock larger than this is a retry or a
+/// practice rewind, which starts a new recording.
+const REWIND_THRESHOLD_MS: f64 = 500.0;
+
+/// A frame copied into a buffer, waiting for the submission to complete.
+pub struct Frame {
+    buffer: wgpu::Buffer,
+    /// Times the frame is written, to cover the dropped frames before it.
+    repeat: u64,
+}
+
+/// Recording of one play, from its first frame to a rewind or its end.
+struct Session {
+    frames: Sender<Frame>,
+    free: Receiver<wgpu::Buffer>,
+    buffers: usize,
+    width: u32,
+    height: u32,
+    fps: u32,
+    /// Audio time of the first frame.
+    first_audio_time: f64,
+    last_audio_time: f64,
+    /// Index of the next frame the video needs.
+    next_index: u64,
+}
+
+/// What the encoder thread needs to finish the file.
+struct Job {
+    ffmpeg: Child,
+    width: u32,
+    height: u32,
+    video_path: PathBuf,
+    output_path: PathBuf,
+    audio: Option<AudioTrack>,
+}
+
+/// Part of the music heard during the recording.
+struct AudioTrack {
+    path: Arc<Path>,
+    /// Audio time of the first frame, in ms (negative during the pre-roll).
+    start_ms: f64,
+    rate: f64,
+}
+
+pub struct Recorder {
+    session: Option<Session>,
+    /// Set when a recording could not start, so a play reports it once.
+    failed: bool,
+    done: Sender<Result<PathBuf, String>>,
+    results: Receiver<Result<PathBuf, String>>,
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        let (done, results) = unbounded();
+        Self {
+            session: None,
+            failed: false,
+            done,
+            results,
+        }
+    }
+
+    /// Records the copy of `texture` into `encoder` when the play needs a
+    /// new frame. Hand the result to [`Recorder::submit`] once submitted.
+    pub fn capture(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        texture: &wgpu::Texture,
+        snapshot: &GameplaySnapshot,
+        fps: u32,
+    ) -> Option<Frame> {
+        let restart = self.session.as_ref().is_some_and(|session| {
+            snapshot.audio_time < session.last_audio_time - REWIND_THRESHOLD_MS
+                || (session.width, session.height) != (texture.width(), texture.height())
+                || session.fps != fps
+        });
+        if restart {
+            self.stop();
+        }
+        if self.session.is_none() {
+            if self.failed {
+                return None;
+            }
+            match self.start(device, texture, snapshot, fps) {
+                Ok(session) => self.session = Some(session),
+                Err(e) => {
+                    self.failed = true;
+                    let _ = self.done.send(Err(e));
+                    return None;
+                }
+            }
+        }
+        let session = self.session.as_mut()?;
+        session.last_audio_time = snapshot.audio_time;
+
+        let elapsed_s =
+            (snapshot.audio_time - session.first_audio_time) / snapshot.rate.max(0.01) / 1000.0;
+        let index = (elapsed_s * session.fps as f64).max(0.0) as u64;
+        if index < session.next_index {
+            return None;
+        }
+        let buffer = match session.free.try_recv() {
+            Ok(buffer) => buffer,
+            Err(_) if session.buffers < RING_SIZE => {
+                session.buffers += 1;
+                device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("Recording Buffer"),
+                    size: padded_bytes_per_row(session.width) as u64 * session.height as u64,
+                    usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+                    mapped_at_creation: false,
+                })
+            }
+            // The encoder is behind: skip this frame, the next one covers it.
+            Err(_) => return None,
+        };
+        copy_to_buffer(encoder, texture, &buffer);
+        let repeat = index + 1 - session.next_index;
+        session.next_index = index + 1;
+        Some(Frame { buffer, repeat })
+    }
+
+    /// Queues a submitted frame for encoding.
+    pub fn submit(&mut self, frame: Frame) {
+        let Some(session) = &self.session else {
+            return;
+        };
+        if session.frames.send(frame).is_err() {
+            // The encoder thread stopped, its error is in the results.
+            self.session = None;
+            self.failed = true;
+        }
+    }
+
+    /// Ends the current recording, if any. The file is finished in the
+    /// background and reported by [`Recorder::finished`].
+    pub fn stop(&mut self) {
+        self.session = None;
+    }
+
+    /// Ends the current recording and allows the next play to try again
+    /// after a failure.
+    pub fn reset(&mut self) {
+        self.stop();
+        self.failed = false;
+    }
+
+    /// Toast messages for the recordings finished since the last call.
+    pub fn finished(&self) -> Vec<String> {
+        self.results
+            .try_iter()
+            .map(|result| match result {
+                Ok(path) => tr_args("notice.recording_saved", &[("path", &path.display())]),
+                Err(e) => {
+                    log::error!("RENDER: Recording failed: {}", e);
+                    tr_args("notice.recording_failed", &[("error", &e)])
+                }
+            })
+            .collect()
+    }
+
+    fn start(
+        &self,
+        device: &wgpu::Device,
+        texture: &wgpu::Texture,
+        snapshot: &GameplaySnapshot,
+        fps: u32,
+    ) -> Result<Session, String> {
+        let bgra = readback_format(texture)?;
+        let (width, height) = (texture.width(), texture.height());
+
+        std::fs::create_dir_all(RECORDING_DIR).map_err(|e| e.to_string())?;
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or(0);
+        let output_path = PathBuf::from(RECORDING_DIR).join(format!("recording-{}.mp4", millis));
+        let video_path = output_path.with_extension("video.mp4");
+
+        let ffmpeg = Command::new("ffmpeg")
+            .args(["-y", "-loglevel", "error", "-f", "rawvideo", "-pix_fmt"])
+            .arg(if bgra { "bgra" } else { "rgba" })
+            .arg("-s")
+            .arg(format!("{}x{}", width, height))
+            .arg("-r")
+            .arg(fps.to_string())
+            .args(["-i", "-"])
+            // x264 needs even dimensions.
+            .args(["-vf", "scale=trunc(iw/2)*2:trunc(ih/2)*2"])
+            .args(["-c:v", "libx264", "-preset", "veryfast", "-crf", "18"])
+            .args(["-pix_fmt", "yuv420p"])
+            .arg(&video_path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("could not start ffmpeg: {}", e))?;
+
+        let job = Job {
+            ffmpeg,
+            width,
+            height,
+            video_path,
+            output_path,
+            audio: snapshot.audio_path.clone().map(|path| AudioTrack {
+                path,
+                start_ms: snapshot.audio_time,
+                rate: snapshot.rate,
+            }),
+        };
+        let (frames, queued) = unbounded();
+        let (returned, free) = unbounded();
+        let device = device.clone();
+        let done = self.done.clone();
+        thread::Builder::new()
+            .name("recorder".to_string())
+            .spawn(move || {
+                let _ = done.send(encode(&device, job, queued, returned));
+            })
+            .map_err(|e| format!("could not start the encoder thread: {}", e))?;
+
+        log::info!("RENDER: Recording {}x{} at {} FPS", width, height, fps);
+        Ok(Session {
+            frames,
+            free,
+            buffers: 0,
+            width,
+            height,
+            fps,
+            first_audio_time: snapshot.audio_time,
+            last_audio_time: snapshot.audio_time,
+            next_index: 0,
+        })
+    }
+}
+
+/// Pipes the frames to ffmpeg until the session is dropped, then adds the
+/// music.
+fn encode(
+    device: &wgpu::Device,
+    mut job: Job,
+    frames: Receiver<Frame>,
+    free: Sender<wgpu::Buffer>,
+) -> Result<PathBuf, String> {
+    let mut stdin = job
+        .ffmpeg
+        .stdin
+        .take()
+        .ok_or_else(|| "ffmpeg has no input".to_string())?;
+    let mut written = 0u64;
+    for frame in frames.iter() {
+        let pixels = read_pixels(device, &frame.buffer, job.width, job.height)?;
+        let _ = free.send(frame.buffer);
+        for _ in 0..frame.repeat {
+            if let Err(e) = stdin.write_all(&pixels) {
+                // ffmpeg exited; its own message is more useful.
+                drop(stdin);
+                return Err(ffmpeg_error(job.ffmpeg, e.to_string()));
+            }
+        }
+        written += frame.repeat;
+    }
+    drop(stdin);
+
+    let output = job.ffmpeg.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(stderr_message(&output.stderr, "ffmpeg failed"));
+    }
+    if written == 0 {
+        let _ = std::fs::remove_file(&job.video_path);
+        return Err("no frame was recorded".to_string());
+    }
+
+    if let Some(audio) = &job.audio {
+        match mux(&job.video_path, audio, &job.output_path) {
+            Ok(()) => {
+                let _ = std::fs::remove_file(&job.video_path);
+                return Ok(job.output_path);
+            }
+            Err(e) => log::warn!("RENDER: Recording saved without music: {}", e),
+        }
+    }
+    std::fs::rename(&job.video_path, &job.output_path).map_err(|e| e.to_string())?;
+    Ok(job.output_path)
+}
+
+/// Adds the part of the music played during the recording to `video`.
+fn mux(video: &Path, audio: &AudioTrack, output: &Path) -> Result<(), String> {
+    let mut command = Command::new("ffmpeg");
+    command.args(["-y", "-loglevel", "error", "-i"]).arg(video);
+    if audio.start_ms > 0.0 {
+        command
+            .arg("-ss")
+            .arg(format!("{:.3}", audio.start_ms / 1000.0));
+    }
+    command.arg("-i").arg(audio.path.as_ref());
+
+    let filters = audio_filters(audio.start_ms, audio.rate);
+    if !filters.is_empty() {
+        command.arg("-af").arg(filters.join(","));
+    }
+    let result = command
+        .args(["-map", "0:v", "-map", "1:a", "-c:v", "copy", "-c:a", "aac"])
+        .arg("-shortest")
+        .arg(output)
+        .output()
+        .map_err(|e| e.to_string())?;
+    if result.status.success() {
+        Ok(())
+    } else {
+        Err(stderr_message(
+            &result.stderr,
+            "ffmpeg could not add the music",
+        ))
+    }
+}
+
+/// Filters lining the music up with a recording whose first frame was at
+/// `start_ms`, played at `rate`. The tempo changes but the pitch is kept,
+/// whatever the rate mode of the play.
+fn audio_filters(start_ms: f64, rate: f64) -> Vec<String> {
+    let mut filters = Vec::new();
+    // atempo accepts 0.5 to 2.0; larger changes are chained.
+    let mut tempo = rate;
+    while tempo > 2.0 {
+        filters.push("atempo=2.0".to_string());
+        tempo /= 2.0;
+    }
+    while tempo < 0.5 {
+        filters.push("atempo=0.5".to_string());
+        tempo /= 0.5;
+    }
+    if (tempo - 1.0).abs() > 1e-6 {
+        filters.push(format!("atempo={:.6}", tempo));
+    }
+    // The pre-roll is silent: the music starts after it, in video time.
+    if start_ms < 0.0 {
+        let delay = (-start_ms / rate).round() as u64;
+        filters.push(format!("adelay=delays={}:all=1", delay));
+    }
+    filters
+}
+
+fn ffmpeg_error(ffmpeg: Child, fallback: String) -> String {
+    match ffmpeg.wait_with_output() {
+        Ok(output) => stderr_message(&output.stderr, &fallback),
+        Err(_) => fallback,
+    }
+}
+
+fn stderr_message(stderr: &[u8], fallback: &str) -> String {
+    let message = String::from_utf8_lossy(stderr);
+    match message.lines().rfind(|line| !line.trim().is_empty()) {
+        Some(line) => line.trim().to_string(),
+        None => fallback.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn music_filters_follow_the_rate_and_the_pre_roll() {
+        assert!(audio_filters(1500.0, 1.0).is_empty());
+        assert_eq!(
+            audio_filters(-3000.0, 1.5),
+            vec!["atempo=1.500000", "adelay=delays=2000:all=1"]
+        );
+        assert_eq!(
+            audio_filters(0.0, 3.0),
+            vec!["atempo=2.0", "atempo=1.500000"]
+        );
+    }
+}
@@ -1,19 +1,31 @@
 //! Main renderer orchestrating all graphics operations.
 
 use crate::input::events::GameAction;
+use crate::models::skin::{Skin, package};
+use crate::models::tournament::SignedReplay;
 use crate::render::context::RenderContext;
+use crate::render::display::{AppliedDisplay, apply_display_mode, list_monitors};
 use crate::render::draw::draw_game;
 use crate::render::mock_data::create_mock_state;
+use crate::render::recorder::Recorder;
 use crate::render::resources::RenderResources;
+use crate::render::screenshot::Screenshots;
+use crate::render::theme::scope_of;
 use crate::render::ui::UiOverlay;
+use crate::shared::locale::{tr, tr_args};
+use crate::shared::notice::Notice;
 use crate::shared::snapshot::RenderState;
+use crate::views::components::common::toast::Toasts;
 use crate::views::components::editor::SkinEditorLayout;
 use crate::views::components::menu::result_screen::ResultScreen;
 use crate::views::components::menu::song_select::SongSelectScreen;
-use crate::views::settings::{SettingsSnapshot, render_settings_window};
+use crate::views::settings::{
+    LibraryOverview, MonitorInfo, SettingsSnapshot, render_settings_window,
+};
 use std::sync::Arc;
+use winit::dpi::PhysicalPosition;
 use winit::event::{ElementState, KeyEvent, WindowEvent};
-use winit::keyboard::PhysicalKey;
+use winit::keyboard::{KeyCode, PhysicalKey};
 use winit::window::Window;
 
 pub struct Renderer {
@@ -32,6 +44,12 @@ pub struct Renderer {
     song_select_screen: SongSelectScreen,
     result_screen: ResultScreen,
     skin_editor: SkinEditorLayout,
+    /// Notifications drawn over every screen.
+    pub toasts: Toasts,
+    /// Pending and finished screenshots (F12).
+    pub screenshots: Screenshots,
+    /// Experimental video recording of plays.
+    recorder: Recorder,
 
     // Offscreen Rendering (pour l'éditeur)
     offscreen_texture: Option<wgpu::Texture>,
@@ -44,6 +62,12 @@ pub struct Renderer {
     frame_count: u32,
     last_fps_update: std::time::Instant,
     current_fps: f64,
+    /// Earliest start of the next frame when an FPS cap is set.
+    next_frame_at: std::time::Instant,
+    /// Window mode currently applied (the app applies the saved one at startup).
+    applied_display: AppliedDisplay,
+    /// Monitor list, read when the settings window opens.
+    monitors: Option<Vec<MonitorInfo>>,
 }
 
 impl Renderer {
@@ -62,7 +86,8 @@ impl Renderer {
         // Positionnement initial des éléments
         resources.update_component_positions(ctx.config.width as f32, ctx.config.height as f32);
 
-        Self {
+        let applied_display = AppliedDisplay::from_settings(&resources.settings);
+        let mut renderer = Self {
             ctx,
             ui,
             offscreen_ui,
@@ -72,6 +97,9 @@ impl Renderer {
             song_select_screen: SongSelectScreen::new(),
             result_screen: ResultScreen::new(),
             skin_editor: SkinEditorLayout::new(),
+            toasts: Toasts::default(),
+            screenshots: Screenshots::new(),
+            recorder: Recorder::new(),
 
             offscreen_texture: None,
             offscreen_view: None,
@@ -82,6 +110,21 @@ impl Renderer {
             frame_count: 0,
             last_fps_update: std::time::Instant::now(),
             current_fps: 0.0,
+            next_frame_at: std::time::Instant::now(),
+            applied_display,
+            monitors: None,
+        };
+        Self::announce_skin_degradation(&renderer.resources.skin, &mut renderer.toasts);
+        renderer
+    }
+
+    /// Tells the player which features of the active skin are skipped.
+    fn announce_skin_degradation(skin: &Skin, toasts: &mut Toasts) {
+        for warning in skin.general.capabilities.unsupported() {
+            toasts.push(tr_args(
+                "notice.skin_warning",
+                &[("skin", &skin.general.name), ("warning", &warning)],
+            ));
         }
     }
 
@@ -101,6 +144,22 @@ impl Renderer {
         );
     }
 
+    /// Sleeps until the next frame is due when the FPS cap is set.
+    pub fn limit_frame_rate(&mut self) {
+        let cap = self.resources.settings.fps_cap;
+        if cap == 0 {
+            return;
+        }
+        let frame_time = std::time::Duration::from_secs_f64(1.0 / cap as f64);
+        let now = std::time::Instant::now();
+        if self.next_frame_at > now {
+            std::thread::sleep(self.next_frame_at - now);
+        }
+        // Scheduled from the previous deadline to hold the average rate,
+        // restarted from now after a slow frame.
+        self.next_frame_at = (self.next_frame_at + frame_time).max(std::time::Instant::now());
+    }
+
     pub fn handle_event(&mut self, window: &Window, event: &WindowEvent) -> bool {
         let handled = self.ui.handle_input(window, event);
 
@@ -114,14 +173,99 @@ impl Renderer {
             ..
         } = event
             && self.resources.settings.remapping_column.is_some()
+            && !self.resources.settings.remapping_pad
         {
-            let label = format!("{:?}", code);
-            self.resources.settings.push_keybind_key(label);
+            if *code == KeyCode::Escape {
+                self.resources.settings.cancel_keybind_capture();
+            } else {
+                let label = format!("{:?}", code);
+                self.resources.settings.push_keybind_key(label);
+            }
         }
 
         handled
     }
 
+    /// Feeds a pressed gamepad button to the keybind capture, if one is
+    /// waiting for gamepad buttons.
+    pub fn capture_pad_button(&mut self, label: &str) {
+        let settings = &mut self.resources.settings;
+        if settings.remapping_column.is_some() && settings.remapping_pad {
+            settings.push_keybind_key(label.to_string());
+        }
+    }
+
+    /// Installs a `.rvskin` package into `skins/`, reporting the outcome as a toast.
+    pub fn import_skin_package(archive: &std::path::Path, toasts: &mut Toasts) {
+        match package::import_skin(archive, std::path::Path::new("skins")) {
+            Ok(name) => {
+                log::info!("SKIN: Imported {:?} as {}", archive, name);
+                toasts.push(Notice::success(tr_args(
+                    "notice.skin_imported",
+                    &[("skin", &name)],
+                )));
+            }
+            Err(e) => {
+                log::error!("SKIN: Could not import {:?}: {}", archive, e);
+                toasts.push(Notice::error(tr_args(
+                    "notice.skin_import_failed",
+                    &[("error", &e)],
+                )));
+            }
+        }
+    }
+
+    /// Checks a signed replay against the tournament signing key, reporting
+    /// the outcome as a toast.
+    pub fn verify_signed_replay(&mut self, path: &std::path::Path) {
+        let Some(key) = &self.resources.settings.tournament.signing_key else {
+            self.toasts
+                .push(Notice::error(tr("notice.verify_needs_password")));
+            return;
+        };
+        match SignedReplay::verify(path, key) {
+            Ok(replay) => {
+                log::info!("REPLAY: Valid signature for {:?}", path);
+                self.toasts.push(Notice::success(tr_args(
+                    "notice.signed_replay_valid",
+                    &[
+                        ("score", &replay.score),
+                        ("accuracy", &format!("{:.2}", replay.accuracy)),
+                        ("rate", &format!("{:.2}", replay.replay.rate)),
+                    ],
+                )));
+            }
+            Err(e) => {
+                log::warn!("REPLAY: {:?} does not verify: {}", path, e);
+                self.toasts.push(Notice::error(tr_args(
+                    "notice.signed_replay_rejected",
+                    &[("error", &e)],
+                )));
+            }
+        }
+    }
+
+    /// Whether the skin editor is shown (it takes dropped image files).
+    pub fn is_editing_skin(&self) -> bool {
+        matches!(self.current_state, RenderState::Editor(_))
+    }
+
+    /// Column of the receptor under the cursor during gameplay, when mouse
+    /// input is enabled in the settings.
+    pub fn receptor_column_at(&self, cursor: PhysicalPosition<f64>) -> Option<usize> {
+        if !self.resources.settings.mouse_input
+            || !matches!(self.current_state, RenderState::InGame(_))
+        {
+            return None;
+        }
+        let x = (cursor.x as f32 / self.ctx.config.width as f32) * 2.0 - 1.0;
+        let y = 1.0 - (cursor.y as f32 / self.ctx.config.height as f32) * 2.0;
+        self.resources
+            .gameplay_view
+            .playfield_component()
+            .receptor_column_at(&self.resources.pixel_system, x, y)
+    }
+
     pub fn update_state(&mut self, new_state: RenderState) {
         if let RenderState::Menu(ref menu) = new_state
             && let Some((set, _)) = menu.get_selected_beatmapset()
@@ -129,6 +273,19 @@ impl Renderer {
         {
             self.resources
                 .load_background(&self.ctx.device, &self.ctx.queue, img_path);
+
+            // Decode the neighbours ahead so scrolling through the list
+            // finds them ready.
+            let index = menu.selected_index;
+            for neighbour in [index.wrapping_sub(1), index + 1] {
+                if let Some(path) = menu
+                    .beatmapsets
+                    .get(neighbour)
+                    .and_then(|(set, _)| set.image_path.as_deref())
+                {
+                    self.resources.prefetch_background(path);
+                }
+            }
         }
         self.current_state = new_state;
     }
@@ -178,6 +335,33 @@ impl Renderer {
     }
 
     pub fn render(&mut self, window: &Window) -> Result<Vec<GameAction>, wgpu::SurfaceError> {
+        self.resources
+            .poll_background(&self.ctx.device, &self.ctx.queue);
+
+        // Files edited outside the game show up right away. The skin editor
+        // holds its own unsaved copy, which a reload would overwrite.
+        if !matches!(self.current_state, RenderState::Editor(_))
+            && self.resources.skin_watcher.poll()
+        {
+            let egui_ctx = self.ui.ctx.clone();
+            match self.resources.reload_skin(&self.ctx, &egui_ctx) {
+                Ok(()) => {
+                    self.resources.update_component_positions(
+                        self.ctx.config.width as f32,
+                        self.ctx.config.height as f32,
+                    );
+                    self.toasts.push(tr_args(
+                        "notice.skin_reloaded",
+                        &[("skin", &self.resources.skin.general.name)],
+                    ));
+                }
+                Err(e) => self.toasts.push(Notice::error(tr_args(
+                    "notice.skin_not_reloaded",
+                    &[("error", &e)],
+                ))),
+            }
+        }
+
         // --- FPS Calculation ---
         self.frame_count += 1;
         let now = std::time::Instant::now();
@@ -188,6 +372,14 @@ impl Renderer {
             self.last_fps_update = now;
         }
 
+        self.ctx
+            .set_present_mode(self.resources.settings.present_mode);
+        let display = AppliedDisplay::from_settings(&self.resources.settings);
+        if display != self.applied_display {
+            apply_display_mode(window, &self.resources.settings);
+            self.applied_display = display;
+        }
+
         // Préparation de la frame
         let output = self.ctx.surface.get_current_texture()?;
         let swapchain_view = output
@@ -236,6 +428,9 @@ impl Renderer {
 
                 // 5. Rendu EGUI OFFSCREEN (Menus SongSelect / Result)
                 // C'est ici qu'on dessine l'UI du menu DANS la texture
+                self.offscreen_ui
+                    .ctx
+                    .set_style(self.resources.theme_styles.get(scope_of(&mock_state)));
                 match &mock_state {
                     RenderState::Menu(menu_state) => {
                         self.offscreen_ui.begin_frame(window); // Dummy inputs pour l'offscreen
@@ -287,6 +482,12 @@ impl Renderer {
                             &hit_win,
                             self.resources.settings.hit_window_mode,
                             self.resources.settings.hit_window_value,
+                            &self.resources.settings.leaderboard,
+                            self.resources.settings.chart_preview,
+                            self.resources.settings.tournament.enabled,
+                            &self.resources.settings.song_list_scroll,
+                            &self.resources.settings.presets,
+                            self.resources.settings.active_preset.as_deref(),
                             self.resources.song_button_texture.as_ref().map(|t| t.id()),
                             self.resources
                                 .song_button_selected_texture
@@ -302,6 +503,7 @@ impl Renderer {
                                 .map(|t| t.id()),
                             to_egui(menus.song_select.song_button.selected_border_color),
                             to_egui(menus.song_select.difficulty_button.selected_text_color),
+                            &menus.song_select.rating_colors,
                             &panel_textures,
                         );
 
@@ -314,7 +516,12 @@ impl Renderer {
                         let ctx_off = self.offscreen_ui.ctx.clone();
                         let hit_win = crate::models::engine::hit_window::HitWindow::new();
 
-                        self.result_screen.render(&ctx_off, data, &hit_win);
+                        self.result_screen.render(
+                            &ctx_off,
+                            data,
+                            &hit_win,
+                            &self.resources.settings.share_template,
+                        );
 
                         self.offscreen_ui
                             .end_frame_and_draw(&self.ctx, &mut encoder, target_view);
@@ -345,6 +552,11 @@ impl Renderer {
         // 2. UI LAYER PRINCIPALE (SWAPCHAIN)
         // =================================================================================
 
+        self.ui.ctx.set_style(
+            self.resources
+                .theme_styles
+                .get(scope_of(&self.current_state)),
+        );
         self.ui.begin_frame(window);
         let ctx_egui = self.ui.ctx.clone();
 
@@ -354,8 +566,20 @@ impl Renderer {
                 if menu_state.show_settings {
                     let (snapshot, result) = {
                         let settings = &mut self.resources.settings;
+                        let monitors = self.monitors.get_or_insert_with(|| list_monitors(window));
                         let snapshot = SettingsSnapshot::capture(settings);
-                        let result = render_settings_window(&ctx_egui, settings, &snapshot);
+                        let result = render_settings_window(
+                            &ctx_egui,
+                            settings,
+                            &snapshot,
+                            &menu_state.profiles,
+                            Some(LibraryOverview {
+                                stats: menu_state.library_stats.as_ref().as_ref(),
+                                progress: menu_state.library_progress,
+                                maintenance: menu_state.maintenance.as_ref(),
+                            }),
+                            monitors,
+                        );
                         (snapshot, result)
                     };
 
@@ -366,18 +590,56 @@ impl Renderer {
                             self.ctx.config.width as f32,
                             self.ctx.config.height as f32,
                         );
+                        Self::announce_skin_degradation(&self.resources.skin, &mut self.toasts);
                     }
 
                     if let Some(volume) = result.volume_changed {
                         actions_to_send.push(GameAction::UpdateVolume(volume));
                     }
+                    if let Some(speed) = result.scroll_speed_changed {
+                        actions_to_send.push(GameAction::UpdateScrollSpeed(speed));
+                    }
+                    if let Some(speeds) = result.keymode_scroll_speeds_changed {
+                        actions_to_send.push(GameAction::UpdateKeymodeScrollSpeeds(speeds));
+                    }
+                    if let Some(cover) = result.lane_cover_changed {
+                        actions_to_send.push(GameAction::UpdateLaneCover(cover));
+                    }
                     if let Some((mode, value)) = result.hit_window_changed {
                         actions_to_send.push(GameAction::UpdateHitWindow { mode, value });
                     }
                     if result.keybinds_updated {
                         actions_to_send.push(GameAction::ReloadKeybinds);
                     }
+                    if let Some(enabled) = result.discord_presence_changed {
+                        actions_to_send.push(GameAction::SetDiscordPresence(enabled));
+                    }
+                    if let Some(enabled) = result.unicode_metadata_changed {
+                        actions_to_send.push(GameAction::SetUnicodeMetadata(enabled));
+                    }
+                    if let Some(tournament) = result.tournament_changed {
+                        actions_to_send.push(GameAction::UpdateTournament(tournament));
+                    }
+                    if let Some(profile_id) = result.profile_switched {
+                        actions_to_send.push(GameAction::SwitchProfile(profile_id));
+                    }
+                    if let Some(name) = result.profile_created {
+                        actions_to_send.push(GameAction::CreateProfile(name));
+                    }
+                    if result.library_stats_requested {
+                        actions_to_send.push(GameAction::LoadLibraryStats);
+                    }
+                    if let Some(roots) = result.library_roots_changed {
+                        actions_to_send.push(GameAction::SetLibraryRoots(roots));
+                    }
+                    if let Some(request) = result.maintenance_requested {
+                        actions_to_send.push(GameAction::LibraryMaintenance(request));
+                    }
+                    if let Some(archive) = result.skin_package_picked {
+                        Self::import_skin_package(&archive, &mut self.toasts);
+                    }
                     if result.request_toggle {
+                        self.monitors = None;
                         actions_to_send.push(GameAction::ToggleSettings);
                     }
                 }
@@ -391,18 +653,10 @@ impl Renderer {
                         (c[3] * 255.) as u8,
                     )
                 };
-                let hit_window = match self.resources.settings.hit_window_mode {
-                    crate::models::settings::HitWindowMode::OsuOD => {
-                        crate::models::engine::hit_window::HitWindow::from_osu_od(
-                            self.resources.settings.hit_window_value,
-                        )
-                    }
-                    crate::models::settings::HitWindowMode::EtternaJudge => {
-                        crate::models::engine::hit_window::HitWindow::from_etterna_judge(
-                            self.resources.settings.hit_window_value as u8,
-                        )
-                    }
-                };
+                let hit_window = crate::models::engine::hit_window::HitWindow::from_mode(
+                    self.resources.settings.hit_window_mode,
+                    self.resources.settings.hit_window_value,
+                );
                 let panel_textures = crate::views::components::menu::song_select::UIPanelTextures {
                     beatmap_info_bg: self
                         .resources
@@ -422,7 +676,7 @@ impl Renderer {
                         .map(|t| t.id()),
                 };
 
-                let (action_opt, result_data, search_request, calculator_changed) =
+                let (action_opt, result_data, search_request, calculator_changed, panel_actions) =
                     self.song_select_screen.render(
                         &ctx_egui,
                         menu_state,
@@ -432,6 +686,12 @@ impl Renderer {
                         &hit_window,
                         self.resources.settings.hit_window_mode,
                         self.resources.settings.hit_window_value,
+                        &self.resources.settings.leaderboard,
+                        self.resources.settings.chart_preview,
+                        self.resources.settings.tournament.enabled,
+                        &self.resources.settings.song_list_scroll,
+                        &self.resources.settings.presets,
+                        self.resources.settings.active_preset.as_deref(),
                         self.resources.song_button_texture.as_ref().map(|t| t.id()),
                         self.resources
                             .song_button_selected_texture
@@ -447,6 +707,7 @@ impl Renderer {
                             .map(|t| t.id()),
                         to_egui(menus.song_select.song_button.selected_border_color),
                         to_egui(menus.song_select.difficulty_button.selected_text_color),
+                        &menus.song_select.rating_colors,
                         &panel_textures,
                     );
 
@@ -467,6 +728,7 @@ impl Renderer {
                         GameAction::ToggleSettings => {
                             actions_to_send.push(GameAction::ToggleSettings)
                         }
+                        GameAction::SetArchived { .. } => actions_to_send.push(a),
                         _ => {}
                     }
                 }
@@ -478,6 +740,17 @@ impl Renderer {
                 if let Some(filters) = search_request {
                     actions_to_send.push(GameAction::ApplySearch(filters));
                 }
+
+                // Keep our copy in sync so a later save doesn't revert it.
+                for action in &panel_actions {
+                    if let GameAction::UpdateLeaderboardOptions(options) = action {
+                        self.resources.settings.leaderboard = *options;
+                    }
+                    if let GameAction::ApplyGameplayPreset(name) = action {
+                        self.resources.settings.apply_preset(name);
+                    }
+                }
+                actions_to_send.extend(panel_actions);
             }
 
             RenderState::Editor(_snapshot) => {
@@ -487,8 +760,10 @@ impl Renderer {
                     .skin_editor
                     .show(&ctx_egui, &mut self.resources.skin, self.offscreen_id)
                 {
-                    let s = self.resources.skin.clone();
-                    self.resources.reload_textures(&self.ctx, &ctx_egui, &s);
+                    self.resources.apply_skin_edit(&self.ctx, &ctx_egui);
+                }
+                if let Some(notice) = self.skin_editor.take_notice() {
+                    self.toasts.push(notice);
                 }
 
                 // MISE À JOUR TEMPS RÉEL DES POSITIONS
@@ -513,8 +788,16 @@ impl Renderer {
                 if data.show_settings {
                     let (snapshot, result) = {
                         let settings = &mut self.resources.settings;
+                        let monitors = self.monitors.get_or_insert_with(|| list_monitors(window));
                         let snapshot = SettingsSnapshot::capture(settings);
-                        let result = render_settings_window(&ctx_egui, settings, &snapshot);
+                        let result = render_settings_window(
+                            &ctx_egui,
+                            settings,
+                            &snapshot,
+                            &[],
+                            None,
+                            monitors,
+                        );
                         (snapshot, result)
                     };
 
@@ -525,11 +808,21 @@ impl Renderer {
                             self.ctx.config.width as f32,
                             self.ctx.config.height as f32,
                         );
+                        Self::announce_skin_degradation(&self.resources.skin, &mut self.toasts);
                     }
 
                     if let Some(volume) = result.volume_changed {
                         actions_to_send.push(GameAction::UpdateVolume(volume));
                     }
+                    if let Some(speed) = result.scroll_speed_changed {
+                        actions_to_send.push(GameAction::UpdateScrollSpeed(speed));
+                    }
+                    if let Some(speeds) = result.keymode_scroll_speeds_changed {
+                        actions_to_send.push(GameAction::UpdateKeymodeScrollSpeeds(speeds));
+                    }
+                    if let Some(cover) = result.lane_cover_changed {
+                        actions_to_send.push(GameAction::UpdateLaneCover(cover));
+                    }
                     if let Some((mode, value)) = result.hit_window_changed {
                         actions_to_send.push(GameAction::UpdateHitWindow { mode, value });
                         hit_window_updated = true;
@@ -537,7 +830,20 @@ impl Renderer {
                     if result.keybinds_updated {
                         actions_to_send.push(GameAction::ReloadKeybinds);
                     }
+                    if let Some(enabled) = result.discord_presence_changed {
+                        actions_to_send.push(GameAction::SetDiscordPresence(enabled));
+                    }
+                    if let Some(enabled) = result.unicode_metadata_changed {
+                        actions_to_send.push(GameAction::SetUnicodeMetadata(enabled));
+                    }
+                    if let Some(tournament) = result.tournament_changed {
+                        actions_to_send.push(GameAction::UpdateTournament(tournament));
+                    }
+                    if let Some(archive) = result.skin_package_picked {
+                        Self::import_skin_package(&archive, &mut self.toasts);
+                    }
                     if result.request_toggle {
+                        self.monitors = None;
                         actions_to_send.push(GameAction::ToggleSettings);
                     }
                 }
@@ -545,7 +851,12 @@ impl Renderer {
                 // Only render result screen if settings didn't just trigger a re-judge
                 // (though technically concurrent rendering is fine, this follows Menu pattern)
                 let hit_win = crate::models::engine::hit_window::HitWindow::new();
-                if self.result_screen.render(&ctx_egui, data, &hit_win) {
+                if self.result_screen.render(
+                    &ctx_egui,
+                    data,
+                    &hit_win,
+                    &self.resources.settings.share_template,
+                ) {
                     actions_to_send.push(GameAction::Back);
                 }
             }
@@ -560,7 +871,55 @@ impl Renderer {
                                 snapshot.audio_time,
                                 snapshot.map_duration,
                                 &snapshot.checkpoints,
+                                snapshot.rate,
+                                self.ctx.config.width as f32,
+                            );
+                        });
+                }
+                if snapshot.autoplay {
+                    egui::Area::new(egui::Id::new("autoplay_overlay"))
+                        .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 12.0))
+                        .show(&ctx_egui, |ui| {
+                            ui.label(
+                                egui::RichText::new(tr("gameplay.autoplay"))
+                                    .size(18.0)
+                                    .strong()
+                                    .color(egui::Color32::from_rgb(255, 200, 50)),
+                            );
+                        });
+                }
+                if let Some(delta) = &snapshot.ghost {
+                    egui::Area::new(egui::Id::new("ghost_overlay"))
+                        .fixed_pos(egui::pos2(0.0, 0.0))
+                        .show(&ctx_egui, |ui| {
+                            crate::views::components::GhostOverlay::render(
+                                ui,
+                                delta,
+                                self.ctx.config.height as f32,
+                            );
+                        });
+                }
+                if let Some(health) = snapshot.health {
+                    egui::Area::new(egui::Id::new("health_overlay"))
+                        .fixed_pos(egui::pos2(0.0, 0.0))
+                        .show(&ctx_egui, |ui| {
+                            crate::views::components::HealthOverlay::render(
+                                ui,
+                                health,
                                 self.ctx.config.width as f32,
+                                self.ctx.config.height as f32,
+                            );
+                        });
+                }
+                if let Some(stats) = &snapshot.debug {
+                    egui::Area::new(egui::Id::new("debug_overlay"))
+                        .fixed_pos(egui::pos2(0.0, 0.0))
+                        .show(&ctx_egui, |ui| {
+                            crate::views::components::DebugOverlay::render(
+                                ui,
+                                stats,
+                                self.current_fps,
+                                self.ctx.config.height as f32,
                             );
                         });
                 }
@@ -568,9 +927,42 @@ impl Renderer {
             _ => {}
         }
 
+        for message in self.screenshots.finished() {
+            self.toasts.push(message);
+        }
+        for message in self.recorder.finished() {
+            self.toasts.push(message);
+        }
+        self.toasts
+            .render(&ctx_egui, &self.resources.skin.menus.panels);
+
         self.ui
             .end_frame_and_draw(&self.ctx, &mut encoder, &swapchain_view);
+        let capture = self
+            .screenshots
+            .capture(&self.ctx.device, &mut encoder, &output.texture);
+        let frame = match &self.current_state {
+            RenderState::InGame(snapshot) if self.resources.settings.record_gameplay => {
+                self.recorder.capture(
+                    &self.ctx.device,
+                    &mut encoder,
+                    &output.texture,
+                    snapshot,
+                    self.resources.settings.record_fps,
+                )
+            }
+            _ => {
+                self.recorder.reset();
+                None
+            }
+        };
         self.ctx.queue.submit(std::iter::once(encoder.finish()));
+        if let Some(capture) = capture {
+            self.screenshots.save(&self.ctx.device, capture);
+        }
+        if let Some(frame) = frame {
+            self.recorder.submit(frame);
+        }
         output.present();
 
         Ok(actions_to_send)
@@ -3,21 +3,83 @@
 use crate::models::engine::{InstanceRaw, NUM_COLUMNS, PixelSystem, PlayfieldConfig};
 use crate::models::settings::SettingsState;
 use crate::models::skin::Skin;
+use crate::models::skin::hud::HudLayout;
+use crate::render::background_loader::{BackgroundLoader, Decoded};
 use crate::render::context::RenderContext;
+use crate::render::feedback::{self, MissFeedback};
+use crate::render::gameplay_background::GameplayBackground;
+use crate::render::skin_watcher::SkinWatcher;
+use crate::render::sprite_atlas::SpriteAtlas;
+use crate::render::theme::ThemeStyles;
 use crate::render::utils::*;
 use crate::shaders::constants::{BACKGROUND_SHADER_SRC, PROGRESS_SHADER_SRC, QUAD_SHADER_SRC};
 use crate::views::components::common::primitives::ProgressInstance; // From primitives
 use crate::views::components::{
-    AccuracyDisplay, ComboDisplay, HitBarDisplay, JudgementFlash, JudgementPanel,
-    NotesRemainingDisplay, NpsDisplay, PlayfieldDisplay, ScoreDisplay, ScrollSpeedDisplay,
+    AccuracyDisplay, ColumnJudgementsDisplay, ComboDisplay, EarlyLateDisplay, HitBarDisplay,
+    JudgementFlash, JudgementPanel, KeyOverlayDisplay, NotesRemainingDisplay, NpsDisplay,
+    PbDeltaDisplay, PlayfieldDisplay, ScoreDisplay, ScriptWidgetDisplay, ScrollSpeedDisplay,
     TimeLeftDisplay,
 };
 use crate::views::gameplay::GameplayView;
 use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// HUD sprites drawn per frame: the judgement flash and the skin digits.
+pub const MAX_HUD_SPRITES: usize = 64;
+
+/// Image file with its modification time, so replacing a file under the
+/// same name still counts as a change.
+type FileKey = Option<(PathBuf, Option<SystemTime>)>;
+
+fn file_key(path: Option<PathBuf>) -> FileKey {
+    let path = path?;
+    let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+    Some((path, modified))
+}
+
+/// What the loaded GPU / egui textures were built from. Comparing it after a
+/// skin editor change tells which of them are stale; positions, sizes and
+/// HUD colors are read from the skin every frame and never need a reload.
+pub struct SkinAssets {
+    sprites: Vec<FileKey>,
+    /// Receptor and note colors, used by the atlas for missing images.
+    sprite_colors: [[f32; 4]; 2],
+    menu: Vec<FileKey>,
+}
+
+impl SkinAssets {
+    pub fn capture(skin: &Skin) -> Self {
+        Self {
+            sprites: SpriteAtlas::sources(skin)
+                .into_iter()
+                .map(file_key)
+                .collect(),
+            sprite_colors: [
+                skin.gameplay.receptors.color,
+                skin.gameplay.notes.note.color,
+            ],
+            menu: [
+                skin.get_song_button_image(),
+                skin.get_song_button_selected_image(),
+                skin.get_difficulty_button_image(),
+                skin.get_difficulty_button_selected_image(),
+                skin.get_beatmap_info_background_image(),
+                skin.get_search_panel_background_image(),
+                skin.get_search_bar_image(),
+                skin.get_leaderboard_background_image(),
+            ]
+            .into_iter()
+            .map(file_key)
+            .collect(),
+        }
+    }
+}
 
 pub struct RenderResources {
     pub render_pipeline: wgpu::RenderPipeline,
     pub bind_group_layout: wgpu::BindGroupLayout, // NEW: Persist for reloads
+    /// Shake and vignette of the skin on misses.
+    pub miss_feedback: MissFeedback,
     pub background_pipeline: wgpu::RenderPipeline,
     pub quad_pipeline: wgpu::RenderPipeline,
     pub progress_pipeline: wgpu::RenderPipeline,
@@ -25,22 +87,24 @@ pub struct RenderResources {
     pub instance_buffer: wgpu::Buffer,
     pub receptor_buffer: wgpu::Buffer,
     pub quad_buffer: wgpu::Buffer,
+    pub sprite_buffer: wgpu::Buffer,
     pub progress_buffer: wgpu::Buffer, // NEW
 
-    pub note_bind_groups: Vec<wgpu::BindGroup>,
-    pub receptor_bind_groups: Vec<wgpu::BindGroup>,
-    pub receptor_pressed_bind_groups: Vec<wgpu::BindGroup>,
-
-    // Special note type bind groups
-    pub mine_bind_group: Option<wgpu::BindGroup>,
-    pub hold_body_bind_group: Option<wgpu::BindGroup>,
-    pub hold_end_bind_group: Option<wgpu::BindGroup>,
-    pub burst_body_bind_group: Option<wgpu::BindGroup>,
-    pub burst_end_bind_group: Option<wgpu::BindGroup>,
+    /// Notes, receptors and note parts of the skin, in one texture.
+    pub sprite_atlas: SpriteAtlas,
+    /// Sources of the loaded textures, see [`RenderResources::apply_skin_edit`].
+    pub skin_assets: SkinAssets,
 
     pub background_bind_group: Option<wgpu::BindGroup>,
     pub background_sampler: wgpu::Sampler,
     pub current_background_path: Option<String>,
+    /// Background waiting for the decoder, shown as soon as it is ready.
+    pub pending_background_path: Option<String>,
+    pub background_loader: BackgroundLoader,
+    /// Loaded background texture and its size, for the in-game background.
+    pub background_texture: Option<(wgpu::TextureView, u32, u32)>,
+    /// Dimmed / blurred background drawn during gameplay.
+    pub gameplay_background: GameplayBackground,
 
     pub song_button_texture: Option<egui::TextureHandle>,
     pub song_button_selected_texture: Option<egui::TextureHandle>,
@@ -56,7 +120,11 @@ pub struct RenderResources {
     pub pixel_system: PixelSystem,
 
     pub skin: Skin,
+    /// Reports edits of the skin files, see [`RenderResources::reload_skin`].
+    pub skin_watcher: SkinWatcher,
     pub settings: SettingsState,
+    /// egui styles from the skin's `theme.toml`.
+    pub theme_styles: ThemeStyles,
 
     pub editor_status_text: Option<String>,
     pub editor_values_text: Option<String>,
@@ -75,15 +143,74 @@ pub struct RenderResources {
     pub notes_remaining_display: NotesRemainingDisplay,
     pub scroll_speed_display: ScrollSpeedDisplay,
     pub time_left_display: TimeLeftDisplay,
+    pub pb_delta_display: PbDeltaDisplay,
+    pub early_late_display: EarlyLateDisplay,
+    pub column_judgements_display: ColumnJudgementsDisplay,
+    pub key_overlay_display: KeyOverlayDisplay,
+    pub script_widgets: ScriptWidgetDisplay,
 }
 
 impl RenderResources {
     pub fn reload_textures(&mut self, ctx: &RenderContext, egui_ctx: &egui::Context, skin: &Skin) {
         self.reload_menu_assets(egui_ctx, skin);
         self.reload_gameplay_assets(ctx, skin);
+        self.script_widgets.load(skin);
+        self.skin_assets = SkinAssets::capture(skin);
+    }
+
+    /// Reads the active skin again from disk, after its files changed.
+    pub fn reload_skin(
+        &mut self,
+        ctx: &RenderContext,
+        egui_ctx: &egui::Context,
+    ) -> Result<(), String> {
+        let name = self
+            .skin
+            .base_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or("invalid skin folder")?
+            .to_string();
+        let mut skin = Skin::load(&name)?;
+        skin.load_key_mode(NUM_COLUMNS);
+        self.reload_textures(ctx, egui_ctx, &skin);
+        let font_path = skin
+            .get_font_path()
+            .unwrap_or(PathBuf::from("assets/font.ttf"));
+        self.text_brush = load_text_brush(
+            &ctx.device,
+            ctx.config.width,
+            ctx.config.height,
+            ctx.config.format,
+            Some(&font_path),
+        );
+        self.skin = skin;
+        log::info!("SKIN: Reloaded '{}' from disk", name);
+        Ok(())
+    }
+
+    /// Follows an edit of `self.skin` in the skin editor, reloading only the
+    /// textures whose source changed. A color or position tweak costs no GPU
+    /// upload beyond a few texels, so the preview keeps its framerate.
+    pub fn apply_skin_edit(&mut self, ctx: &RenderContext, egui_ctx: &egui::Context) {
+        let assets = SkinAssets::capture(&self.skin);
+        let skin = self.skin.clone();
+        if assets.sprites != self.skin_assets.sprites {
+            self.reload_gameplay_assets(ctx, &skin);
+        } else if assets.sprite_colors != self.skin_assets.sprite_colors {
+            self.sprite_atlas.update_colors(&ctx.queue, &skin);
+        }
+        if assets.menu != self.skin_assets.menu {
+            self.reload_menu_assets(egui_ctx, &skin);
+        } else {
+            self.theme_styles = ThemeStyles::from_skin(&skin, egui_ctx.theme());
+        }
+        self.skin_assets = assets;
     }
 
     fn reload_menu_assets(&mut self, egui_ctx: &egui::Context, skin: &Skin) {
+        self.theme_styles = ThemeStyles::from_skin(skin, egui_ctx.theme());
+
         let load_egui_tex = |path: Option<PathBuf>, name: &str| -> Option<egui::TextureHandle> {
             let p = path?;
             if !p.exists() {
@@ -122,143 +249,8 @@ impl RenderResources {
     }
 
     fn reload_gameplay_assets(&mut self, ctx: &RenderContext, skin: &Skin) {
-        let device = &ctx.device;
-        let queue = &ctx.queue;
-
-        let bind_group_layout = &self.bind_group_layout;
-        let sampler = create_sampler(device);
-
-        // Helper for single texture bind group
-        let create_bind_group_from_path =
-            |path: Option<PathBuf>, label: &str| -> Option<wgpu::BindGroup> {
-                let p = path?;
-                let (tex, _, _) = load_texture_from_path(device, queue, &p)?;
-                let view = tex.create_view(&Default::default());
-                Some(device.create_bind_group(&wgpu::BindGroupDescriptor {
-                    label: Some(label),
-                    layout: &bind_group_layout,
-                    entries: &[
-                        wgpu::BindGroupEntry {
-                            binding: 0,
-                            resource: wgpu::BindingResource::TextureView(&view),
-                        },
-                        wgpu::BindGroupEntry {
-                            binding: 1,
-                            resource: wgpu::BindingResource::Sampler(&sampler),
-                        },
-                    ],
-                }))
-            };
-
-        // Reload special notes
-        self.mine_bind_group =
-            create_bind_group_from_path(skin.get_mine_image(NUM_COLUMNS, 0), "Mine BG");
-        self.hold_body_bind_group =
-            create_bind_group_from_path(skin.get_hold_body_image(NUM_COLUMNS, 0), "Hold Body BG");
-        self.hold_end_bind_group =
-            create_bind_group_from_path(skin.get_hold_end_image(NUM_COLUMNS, 0), "Hold End BG");
-        self.burst_body_bind_group =
-            create_bind_group_from_path(skin.get_burst_body_image(NUM_COLUMNS, 0), "Burst Body BG");
-        self.burst_end_bind_group =
-            create_bind_group_from_path(skin.get_burst_end_image(NUM_COLUMNS, 0), "Burst End BG");
-
-        // Reload columns (Notes & Receptors)
-        self.receptor_bind_groups.clear();
-        self.receptor_pressed_bind_groups.clear();
-        self.note_bind_groups.clear();
-
-        let receptor_color = skin.gameplay.receptors.color;
-        let def_col_rec = [
-            (receptor_color[0] * 255.) as u8,
-            (receptor_color[1] * 255.) as u8,
-            (receptor_color[2] * 255.) as u8,
-            (receptor_color[3] * 255.) as u8,
-        ];
-
-        let note_color = skin.gameplay.notes.note.color;
-        let def_col_note = [
-            (note_color[0] * 255.) as u8,
-            (note_color[1] * 255.) as u8,
-            (note_color[2] * 255.) as u8,
-            (note_color[3] * 255.) as u8,
-        ];
-
-        for col in 0..NUM_COLUMNS {
-            // Receptor
-            let path = skin.get_receptor_image(NUM_COLUMNS, col);
-            let tex = path
-                .as_ref()
-                .and_then(|p| load_texture_from_path(device, queue, p).map(|(t, _, _)| t))
-                .unwrap_or_else(|| {
-                    create_default_texture(device, queue, def_col_rec, "Def Receptor")
-                });
-            let view = tex.create_view(&Default::default());
-            self.receptor_bind_groups
-                .push(device.create_bind_group(&wgpu::BindGroupDescriptor {
-                    label: Some("Receptor BG"),
-                    layout: &bind_group_layout,
-                    entries: &[
-                        wgpu::BindGroupEntry {
-                            binding: 0,
-                            resource: wgpu::BindingResource::TextureView(&view),
-                        },
-                        wgpu::BindGroupEntry {
-                            binding: 1,
-                            resource: wgpu::BindingResource::Sampler(&sampler),
-                        },
-                    ],
-                }));
-
-            // Pressed
-            let path_p = skin
-                .get_receptor_pressed_image(NUM_COLUMNS, col)
-                .or(path.clone());
-            let tex_p = path_p
-                .as_ref()
-                .and_then(|p| load_texture_from_path(device, queue, p).map(|(t, _, _)| t))
-                .unwrap_or_else(|| {
-                    create_default_texture(device, queue, def_col_rec, "Def Pressed")
-                });
-            let view_p = tex_p.create_view(&Default::default());
-            self.receptor_pressed_bind_groups
-                .push(device.create_bind_group(&wgpu::BindGroupDescriptor {
-                    label: Some("Pressed BG"),
-                    layout: &bind_group_layout,
-                    entries: &[
-                        wgpu::BindGroupEntry {
-                            binding: 0,
-                            resource: wgpu::BindingResource::TextureView(&view_p),
-                        },
-                        wgpu::BindGroupEntry {
-                            binding: 1,
-                            resource: wgpu::BindingResource::Sampler(&sampler),
-                        },
-                    ],
-                }));
-
-            // Note
-            let path_n = skin.get_note_image(NUM_COLUMNS, col);
-            let tex_n = path_n
-                .as_ref()
-                .and_then(|p| load_texture_from_path(device, queue, p).map(|(t, _, _)| t))
-                .unwrap_or_else(|| create_default_texture(device, queue, def_col_note, "Def Note"));
-            let view_n = tex_n.create_view(&Default::default());
-            self.note_bind_groups
-                .push(device.create_bind_group(&wgpu::BindGroupDescriptor {
-                    label: Some("Note BG"),
-                    layout: &bind_group_layout,
-                    entries: &[
-                        wgpu::BindGroupEntry {
-                            binding: 0,
-                            resource: wgpu::BindingResource::TextureView(&view_n),
-                        },
-                        wgpu::BindGroupEntry {
-                            binding: 1,
-                            resource: wgpu::BindingResource::Sampler(&sampler),
-                        },
-                    ],
-                }));
-        }
+        self.sprite_atlas =
+            SpriteAtlas::build(&ctx.device, &ctx.queue, &self.bind_group_layout, skin);
     }
 
     pub fn new(ctx: &RenderContext, egui_ctx: &egui::Context) -> Self {
@@ -306,8 +298,11 @@ impl RenderResources {
             load_egui_tex(skin.get_leaderboard_background_image(), "leaderboard_bg");
 
         let bind_group_layout = create_bind_group_layout(device);
-        let render_pipeline = create_render_pipeline(device, &bind_group_layout, config.format);
-        let sampler = create_sampler(device);
+        let feedback_layout = feedback::create_bind_group_layout(device);
+        let render_pipeline =
+            create_render_pipeline(device, &bind_group_layout, &feedback_layout, config.format);
+        let miss_feedback =
+            MissFeedback::new(device, &bind_group_layout, &feedback_layout, config.format);
 
         let bg_sampler = create_sampler(device);
         let bg_layout = create_bind_group_layout(device);
@@ -418,6 +413,12 @@ impl RenderResources {
             usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
             mapped_at_creation: false,
         });
+        let sprite_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("HUD Sprite Buffer"),
+            size: (MAX_HUD_SPRITES * std::mem::size_of::<InstanceRaw>()) as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
         let quad_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Quad Buffer"),
             size: (1000 * 32) as u64,
@@ -507,136 +508,8 @@ impl RenderResources {
             mapped_at_creation: false,
         });
 
-        let mut receptor_bind_groups = Vec::new();
-        let mut receptor_pressed_bind_groups = Vec::new();
-        let mut note_bind_groups = Vec::new();
-
-        // Get colors from new structure
-        let receptor_color = skin.gameplay.receptors.color;
-        let def_col = [
-            (receptor_color[0] * 255.) as u8,
-            (receptor_color[1] * 255.) as u8,
-            (receptor_color[2] * 255.) as u8,
-            (receptor_color[3] * 255.) as u8,
-        ];
-
-        for col in 0..NUM_COLUMNS {
-            let path = skin.get_receptor_image(NUM_COLUMNS, col);
-            let tex = path
-                .as_ref()
-                .and_then(|p| load_texture_from_path(device, &ctx.queue, p).map(|(t, _, _)| t))
-                .unwrap_or_else(|| {
-                    create_default_texture(device, &ctx.queue, def_col, "Def Receptor")
-                });
-            let view = tex.create_view(&Default::default());
-            receptor_bind_groups.push(device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: Some("Receptor BG"),
-                layout: &bind_group_layout,
-                entries: &[
-                    wgpu::BindGroupEntry {
-                        binding: 0,
-                        resource: wgpu::BindingResource::TextureView(&view),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 1,
-                        resource: wgpu::BindingResource::Sampler(&sampler),
-                    },
-                ],
-            }));
-
-            let path_p = skin
-                .get_receptor_pressed_image(NUM_COLUMNS, col)
-                .or(path.clone());
-            let tex_p = path_p
-                .as_ref()
-                .and_then(|p| load_texture_from_path(device, &ctx.queue, p).map(|(t, _, _)| t))
-                .unwrap_or_else(|| {
-                    create_default_texture(device, &ctx.queue, def_col, "Def Pressed")
-                });
-            let view_p = tex_p.create_view(&Default::default());
-            receptor_pressed_bind_groups.push(device.create_bind_group(
-                &wgpu::BindGroupDescriptor {
-                    label: Some("Pressed BG"),
-                    layout: &bind_group_layout,
-                    entries: &[
-                        wgpu::BindGroupEntry {
-                            binding: 0,
-                            resource: wgpu::BindingResource::TextureView(&view_p),
-                        },
-                        wgpu::BindGroupEntry {
-                            binding: 1,
-                            resource: wgpu::BindingResource::Sampler(&sampler),
-                        },
-                    ],
-                },
-            ));
-
-            let path_n = skin.get_note_image(NUM_COLUMNS, col);
-            let note_color = skin.gameplay.notes.note.color;
-            let tex_n = path_n
-                .as_ref()
-                .and_then(|p| load_texture_from_path(device, &ctx.queue, p).map(|(t, _, _)| t))
-                .unwrap_or_else(|| {
-                    create_default_texture(
-                        device,
-                        &ctx.queue,
-                        [
-                            (note_color[0] * 255.) as u8,
-                            (note_color[1] * 255.) as u8,
-                            (note_color[2] * 255.) as u8,
-                            (note_color[3] * 255.) as u8,
-                        ],
-                        "Def Note",
-                    )
-                });
-            let view_n = tex_n.create_view(&Default::default());
-            note_bind_groups.push(device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: Some("Note BG"),
-                layout: &bind_group_layout,
-                entries: &[
-                    wgpu::BindGroupEntry {
-                        binding: 0,
-                        resource: wgpu::BindingResource::TextureView(&view_n),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 1,
-                        resource: wgpu::BindingResource::Sampler(&sampler),
-                    },
-                ],
-            }));
-        }
-
-        let create_bind_group_from_path =
-            |path: Option<PathBuf>, label: &str| -> Option<wgpu::BindGroup> {
-                let p = path?;
-                let (tex, _, _) = load_texture_from_path(device, &ctx.queue, &p)?;
-                let view = tex.create_view(&Default::default());
-                Some(device.create_bind_group(&wgpu::BindGroupDescriptor {
-                    label: Some(label),
-                    layout: &bind_group_layout,
-                    entries: &[
-                        wgpu::BindGroupEntry {
-                            binding: 0,
-                            resource: wgpu::BindingResource::TextureView(&view),
-                        },
-                        wgpu::BindGroupEntry {
-                            binding: 1,
-                            resource: wgpu::BindingResource::Sampler(&sampler),
-                        },
-                    ],
-                }))
-            };
-
-        let mine_bind_group =
-            create_bind_group_from_path(skin.get_mine_image(NUM_COLUMNS, 0), "Mine BG");
-        let hold_body_bind_group =
-            create_bind_group_from_path(skin.get_hold_body_image(NUM_COLUMNS, 0), "Hold Body BG");
-        let hold_end_bind_group =
-            create_bind_group_from_path(skin.get_hold_end_image(NUM_COLUMNS, 0), "Hold End BG");
-        let burst_body_bind_group =
-            create_bind_group_from_path(skin.get_burst_body_image(NUM_COLUMNS, 0), "Burst Body BG");
-        let burst_end_bind_group =
-            create_bind_group_from_path(skin.get_burst_end_image(NUM_COLUMNS, 0), "Burst End BG");
+        let sprite_atlas = SpriteAtlas::build(device, &ctx.queue, &bind_group_layout, &skin);
+        let skin_assets = SkinAssets::capture(&skin);
 
         let font_path = skin
             .get_font_path()
@@ -667,19 +540,24 @@ impl RenderResources {
         let mut res = Self {
             render_pipeline,
             bind_group_layout, // NEW: Stored
+            miss_feedback,
             background_pipeline,
             quad_pipeline,
             progress_pipeline, // NEW
             instance_buffer,
             receptor_buffer,
             quad_buffer,
+            sprite_buffer,
             progress_buffer, // NEW
-            note_bind_groups: Vec::new(),
-            receptor_bind_groups: Vec::new(),
-            receptor_pressed_bind_groups: Vec::new(),
+            sprite_atlas,
+            skin_assets,
             background_bind_group: None,
             background_sampler: bg_sampler,
             current_background_path: None,
+            pending_background_path: None,
+            background_loader: BackgroundLoader::new(),
+            background_texture: None,
+            gameplay_background: GameplayBackground::new(device, config.format),
 
             song_button_texture: None,
             song_button_selected_texture: None,
@@ -691,14 +569,10 @@ impl RenderResources {
             search_bar_texture: None,
             leaderboard_bg_texture: None,
 
-            mine_bind_group: None,
-            hold_body_bind_group: None,
-            hold_end_bind_group: None,
-            burst_body_bind_group: None,
-            burst_end_bind_group: None,
-
             text_brush,
             pixel_system,
+            theme_styles: ThemeStyles::from_skin(&skin, egui_ctx.theme()),
+            skin_watcher: SkinWatcher::new(&skin.base_path),
             skin,
             settings,
 
@@ -719,6 +593,11 @@ impl RenderResources {
             notes_remaining_display: NotesRemainingDisplay::new(0., 0.),
             scroll_speed_display: ScrollSpeedDisplay::new(0., 0.),
             time_left_display: TimeLeftDisplay::new(0., 0.),
+            pb_delta_display: PbDeltaDisplay::new(0., 0.),
+            early_late_display: EarlyLateDisplay::new(0., 0.),
+            column_judgements_display: ColumnJudgementsDisplay::new(0., 0.),
+            key_overlay_display: KeyOverlayDisplay::new(0., 0.),
+            script_widgets: ScriptWidgetDisplay::new(),
         };
 
         let skin_clone = res.skin.clone();
@@ -729,7 +608,6 @@ impl RenderResources {
     }
 
     pub fn update_component_positions(&mut self, screen_width: f32, screen_height: f32) {
-        let hud = &self.skin.hud;
         let gameplay = &self.skin.gameplay;
 
         // 1. Mise à jour Playfield
@@ -741,6 +619,11 @@ impl RenderResources {
         pf.config.receptor_height_pixels = gameplay.playfield.receptor_size.y;
         pf.config.receptor_spacing_pixels = gameplay.playfield.receptor_spacing;
         pf.config.column_width_pixels = gameplay.playfield.column_width;
+        pf.config.note_opacity = gameplay.playfield.note_opacity;
+        pf.config.hold_body_opacity = gameplay.playfield.hold_body_opacity;
+        pf.config.receptor_opacity = gameplay.playfield.receptor_opacity;
+        pf.config.stretch_hold_body = gameplay.notes.hold.stretch_body;
+        pf.config.stretch_burst_body = gameplay.notes.burst.stretch_body;
 
         let playfield_width_px = pf.get_total_width_pixels();
         // Centrage: x = 640 est le centre de 1280.
@@ -750,20 +633,46 @@ impl RenderResources {
         pf.config.x_offset_pixels = x_offset;
         pf.config.y_offset_pixels = y_offset;
 
-        // 2. Mise à jour HUD
+        // 2. Ancrages : positions relatives converties en pixels écran
+        let layout = HudLayout::new(
+            screen_width,
+            screen_height,
+            gameplay.playfield.position.x,
+            playfield_width_px,
+        );
+        let hud = &self.skin.hud.resolved(&layout);
+        self.script_widgets.place(&layout);
+
+        // 3. Mise à jour HUD
         self.score_display
             .set_position(hud.score.position.x, hud.score.position.y);
         self.score_display.set_size(hud.score.scale);
+        self.score_display
+            .set_digit_layout(hud.score.digit_spacing, hud.score.digit_align);
 
         self.combo_display
             .set_position(hud.combo.position.x, hud.combo.position.y);
         self.combo_display.set_size(hud.combo.scale);
+        self.combo_display
+            .set_digit_layout(hud.combo.digit_spacing, hud.combo.digit_align);
 
         self.accuracy_panel
             .set_position(hud.accuracy.position.x, hud.accuracy.position.y);
         self.accuracy_panel.set_size(hud.accuracy.scale);
+        self.accuracy_panel
+            .set_digit_layout(hud.accuracy.digit_spacing, hud.accuracy.digit_align);
 
         // Judgement Panel - uses its OWN separate position from judgement_panel config
+        self.judgements_panel
+            .set_colors(crate::models::stats::JudgementColors {
+                marv: hud.judgement_panel.marv_color,
+                perfect: hud.judgement_panel.perfect_color,
+                great: hud.judgement_panel.great_color,
+                good: hud.judgement_panel.good_color,
+                bad: hud.judgement_panel.bad_color,
+                miss: hud.judgement_panel.miss_color,
+                ghost_tap: hud.judgement_panel.ghost_tap_color,
+            });
         self.judgements_panel.set_position(
             hud.judgement_panel.position.x,
             hud.judgement_panel.position.y,
@@ -782,6 +691,7 @@ impl RenderResources {
             hitbar_width,
             hud.hit_bar.scale,
         );
+        self.hit_bar.set_style(&hud.hit_bar);
 
         // Judgement Flash - uses the marv position as central flash position
         self.judgement_flash
@@ -789,6 +699,7 @@ impl RenderResources {
 
         // Set timing indicator option from skin config
         self.judgement_flash.show_timing = hud.judgement.show_timing;
+        self.judgement_flash.set_styles(&hud.judgement);
 
         // NEW: Notes Remaining display (separate from judgement panel)
         self.notes_remaining_display.set_position(
@@ -812,6 +723,40 @@ impl RenderResources {
             .set_format(hud.scroll_speed.format.clone());
         self.scroll_speed_display.visible = hud.scroll_speed.visible;
 
+        self.pb_delta_display
+            .set_position(hud.pb_delta.position.x, hud.pb_delta.position.y);
+        self.pb_delta_display.set_scale(hud.pb_delta.scale);
+        self.pb_delta_display
+            .set_colors(hud.pb_delta.ahead_color, hud.pb_delta.behind_color);
+        self.pb_delta_display
+            .set_format(hud.pb_delta.format.clone());
+        self.pb_delta_display.visible = hud.pb_delta.visible;
+
+        self.early_late_display
+            .set_position(hud.early_late.position.x, hud.early_late.position.y);
+        self.early_late_display.set_samples(hud.early_late.samples);
+        self.early_late_display.set_scale(hud.early_late.scale);
+        self.early_late_display
+            .set_colors(hud.early_late.early_color, hud.early_late.late_color);
+        self.early_late_display
+            .set_format(hud.early_late.format.clone());
+        self.early_late_display.visible = hud.early_late.visible;
+
+        self.column_judgements_display.set_position(
+            hud.column_judgements.position.x,
+            hud.column_judgements.position.y,
+        );
+        self.column_judgements_display
+            .set_scale(hud.column_judgements.scale);
+        self.column_judgements_display
+            .set_header_color(hud.column_judgements.header_color);
+        self.column_judgements_display.visible = hud.column_judgements.visible;
+
+        self.key_overlay_display
+            .set_position(hud.key_overlay.position.x, hud.key_overlay.position.y);
+        self.key_overlay_display.set_style(&hud.key_overlay);
+        self.key_overlay_display.visible = hud.key_overlay.visible;
+
         // NEW: Time Left display
         self.time_left_display
             .set_position(hud.time_left.position.x, hud.time_left.position.y);
@@ -821,10 +766,15 @@ impl RenderResources {
             .set_text_scale(hud.time_left.text_scale);
         self.time_left_display
             .set_text_color(hud.time_left.text_color);
+        // The bar is a quad, not text: its colors carry the opacity.
+        let faded = |mut color: [f32; 4]| {
+            color[3] *= hud.time_left.opacity;
+            color
+        };
         self.time_left_display
-            .set_progress_color(hud.time_left.progress_color);
+            .set_progress_color(faded(hud.time_left.progress_color));
         self.time_left_display
-            .set_background_color(hud.time_left.background_color);
+            .set_background_color(faded(hud.time_left.background_color));
         self.time_left_display
             .set_format(hud.time_left.format.clone());
         self.time_left_display.visible = hud.time_left.visible;
@@ -840,10 +790,16 @@ impl RenderResources {
         self.time_left_display.set_mode(display_mode);
     }
 
+    /// Switches to the background at `path_str`. The image is decoded on the
+    /// loader's threads; the current background stays until it is ready.
     pub fn load_background(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, path_str: &str) {
-        if let Some(current) = &self.current_background_path
-            && current == path_str
-        {
+        if self.current_background_path.as_deref() == Some(path_str) {
+            // Back to the shown background before the pending one was ready.
+            self.pending_background_path = None;
+            return;
+        }
+        if self.pending_background_path.as_deref() == Some(path_str) {
+            self.poll_background(device, queue);
             return;
         }
 
@@ -853,28 +809,54 @@ impl RenderResources {
             return;
         }
 
-        if let Some((texture, _, _)) = load_texture_from_path(device, queue, path) {
-            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-            let layout = self.background_pipeline.get_bind_group_layout(0);
-
-            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: Some("Background BG"),
-                layout: &layout,
-                entries: &[
-                    wgpu::BindGroupEntry {
-                        binding: 0,
-                        resource: wgpu::BindingResource::TextureView(&view),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 1,
-                        resource: wgpu::BindingResource::Sampler(&self.background_sampler),
-                    },
-                ],
-            });
+        self.background_loader.request(path_str);
+        self.pending_background_path = Some(path_str.to_string());
+        self.poll_background(device, queue);
+    }
 
-            self.background_bind_group = Some(bind_group);
-            self.current_background_path = Some(path_str.to_string());
-            log::info!("RENDER: Background loaded: {:?}", path);
-        }
+    /// Starts decoding a background that is likely to be shown soon.
+    pub fn prefetch_background(&mut self, path_str: &str) {
+        self.background_loader.request(path_str);
+    }
+
+    /// Uploads the pending background once the decoder is done with it.
+    pub fn poll_background(&mut self, device: &wgpu::Device, queue: &wgpu::Queue) {
+        let Some(path_str) = self.pending_background_path.clone() else {
+            return;
+        };
+        let image = match self.background_loader.poll(&path_str) {
+            Decoded::Ready(image) => image,
+            Decoded::Pending => return,
+            Decoded::Failed => {
+                self.pending_background_path = None;
+                return;
+            }
+        };
+        self.pending_background_path = None;
+
+        let (width, height) = image.dimensions();
+        let texture = texture_from_rgba(device, queue, Some(&path_str), &image);
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let layout = self.background_pipeline.get_bind_group_layout(0);
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Background BG"),
+            layout: &layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.background_sampler),
+                },
+            ],
+        });
+
+        self.background_bind_group = Some(bind_group);
+        self.background_texture = Some((view, width, height));
+        log::info!("RENDER: Background loaded: {:?}", path_str);
+        self.current_background_path = Some(path_str);
     }
 }
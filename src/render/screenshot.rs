@@ -0,0 +1,217 @@
+//! Screenshots of the swapchain (F12).
+//!
+//! The frame is copied into a buffer in the same submission that draws it.
+//! Mapping the buffer and encoding the PNG happen on a worker thread, which
+//! reports the saved path back to the render thread for a toast.
+
+use crate::shared::locale::tr_args;
+use crossbeam_channel::{Receiver, Sender, unbounded};
+use std::path::PathBuf;
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Folder where screenshots are written.
+const SCREENSHOT_DIR: &str = "screenshots";
+
+/// A frame copied into a buffer, waiting for the submission to complete.
+pub struct Capture {
+    buffer: wgpu::Buffer,
+    width: u32,
+    height: u32,
+    bgra: bool,
+}
+
+pub struct Screenshots {
+    requested: bool,
+    done: Sender<Result<PathBuf, String>>,
+    results: Receiver<Result<PathBuf, String>>,
+}
+
+impl Screenshots {
+    pub fn new() -> Self {
+        let (done, results) = unbounded();
+        Self {
+            requested: false,
+            done,
+            results,
+        }
+    }
+
+    /// Captures the next presented frame.
+    pub fn request(&mut self) {
+        self.requested = true;
+    }
+
+    /// Records the copy of `texture` into `encoder` when a screenshot was
+    /// requested. Hand the result to [`Screenshots::save`] once submitted.
+    pub fn capture(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        texture: &wgpu::Texture,
+    ) -> Option<Capture> {
+        if !std::mem::take(&mut self.requested) {
+            return None;
+        }
+
+        let bgra = match readback_format(texture) {
+            Ok(bgra) => bgra,
+            Err(e) => {
+                let _ = self.done.send(Err(e));
+                return None;
+            }
+        };
+
+        let (width, height) = (texture.width(), texture.height());
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Screenshot Buffer"),
+            size: padded_bytes_per_row(width) as u64 * height as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        copy_to_buffer(encoder, texture, &buffer);
+
+        Some(Capture {
+            buffer,
+            width,
+            height,
+            bgra,
+        })
+    }
+
+    /// Reads the submitted capture back and writes it as a PNG, off the render thread.
+    pub fn save(&self, device: &wgpu::Device, capture: Capture) {
+        let device = device.clone();
+        let done = self.done.clone();
+        let spawned = thread::Builder::new()
+            .name("screenshot".to_string())
+            .spawn(move || {
+                let _ = done.send(write_png(&device, capture));
+            });
+        if let Err(e) = spawned {
+            log::error!("RENDER: Could not start screenshot writer: {}", e);
+        }
+    }
+
+    /// Toast messages for the screenshots finished since the last call.
+    pub fn finished(&self) -> Vec<String> {
+        self.results
+            .try_iter()
+            .map(|result| match result {
+                Ok(path) => tr_args("notice.screenshot_saved", &[("path", &path.display())]),
+                Err(e) => {
+                    log::error!("RENDER: Screenshot failed: {}", e);
+                    tr_args("notice.screenshot_failed", &[("error", &e)])
+                }
+            })
+            .collect()
+    }
+}
+
+/// Whether `texture` can be copied to a buffer, and if its pixels are BGRA
+/// (`true`) or RGBA (`false`).
+pub(crate) fn readback_format(texture: &wgpu::Texture) -> Result<bool, String> {
+    let bgra = match texture.format() {
+        wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => true,
+        wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => false,
+        format => return Err(format!("unsupported surface format {:?}", format)),
+    };
+    if !texture.usage().contains(wgpu::TextureUsages::COPY_SRC) {
+        return Err("the surface cannot be read back".to_string());
+    }
+    Ok(bgra)
+}
+
+/// Row stride of a copy of a `width` pixels wide texture.
+pub(crate) fn padded_bytes_per_row(width: u32) -> u32 {
+    (width * 4).div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT
+}
+
+/// Records the copy of the whole `texture` into `buffer`, one row every
+/// [`padded_bytes_per_row`].
+pub(crate) fn copy_to_buffer(
+    encoder: &mut wgpu::CommandEncoder,
+    texture: &wgpu::Texture,
+    buffer: &wgpu::Buffer,
+) {
+    let (width, height) = (texture.width(), texture.height());
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::TexelCopyBufferInfo {
+            buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(padded_bytes_per_row(width)),
+                rows_per_image: Some(height),
+            },
+        },
+        wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+    );
+}
+
+/// Waits for a submitted copy into `buffer` and returns its pixels without
+/// the row padding. Blocks, so call it off the render thread.
+pub(crate) fn read_pixels(
+    device: &wgpu::Device,
+    buffer: &wgpu::Buffer,
+    width: u32,
+    height: u32,
+) -> Result<Vec<u8>, String> {
+    let slice = buffer.slice(..);
+    let (mapped_tx, mapped_rx) = std::sync::mpsc::channel();
+    slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = mapped_tx.send(result);
+    });
+    device
+        .poll(wgpu::PollType::wait_indefinitely())
+        .map_err(|e| e.to_string())?;
+    mapped_rx
+        .recv()
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+
+    let row_len = width as usize * 4;
+    let mut pixels = Vec::with_capacity(row_len * height as usize);
+    {
+        let data = slice.get_mapped_range();
+        for row in data.chunks(padded_bytes_per_row(width) as usize) {
+            pixels.extend_from_slice(&row[..row_len]);
+        }
+    }
+    buffer.unmap();
+    Ok(pixels)
+}
+
+fn write_png(device: &wgpu::Device, capture: Capture) -> Result<PathBuf, String> {
+    // Drop the row padding required by the copy, and swap BGRA to RGBA.
+    let mut pixels = read_pixels(device, &capture.buffer, capture.width, capture.height)?;
+    if capture.bgra {
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+    }
+    // The swapchain alpha is meaningless once the frame is on screen.
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel[3] = 255;
+    }
+
+    std::fs::create_dir_all(SCREENSHOT_DIR).map_err(|e| e.to_string())?;
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let path = PathBuf::from(SCREENSHOT_DIR).join(format!("screenshot-{}.png", millis));
+    image::save_buffer(
+        &path,
+        &pixels,
+        capture.width,
+        capture.height,
+        image::ColorType::Rgba8,
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(path)
+}
@@ -0,0 +1,73 @@
+//! Watches the active skin folder for changes made outside the game.
+//!
+//! The folder is polled for modification times at a short interval: skins
+//! are a few dozen files, and polling behaves the same on every platform
+//! and filesystem, network shares included.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Delay between two scans of the skin folder.
+const SCAN_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Subfolder depth scanned (`conf/`, `widgets/`, image folders).
+const MAX_DEPTH: usize = 2;
+
+type Stamp = (PathBuf, Option<SystemTime>);
+
+pub struct SkinWatcher {
+    root: PathBuf,
+    stamps: Vec<Stamp>,
+    last_scan: Instant,
+}
+
+impl SkinWatcher {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            stamps: scan(root),
+            last_scan: Instant::now(),
+        }
+    }
+
+    /// Whether a file of the skin was added, removed or modified since the
+    /// last change was reported.
+    pub fn poll(&mut self) -> bool {
+        if self.last_scan.elapsed() < SCAN_INTERVAL {
+            return false;
+        }
+        self.last_scan = Instant::now();
+        let stamps = scan(&self.root);
+        if stamps == self.stamps {
+            return false;
+        }
+        self.stamps = stamps;
+        true
+    }
+}
+
+fn scan(root: &Path) -> Vec<Stamp> {
+    let mut stamps = Vec::new();
+    scan_dir(root, 0, &mut stamps);
+    stamps.sort();
+    stamps
+}
+
+fn scan_dir(dir: &Path, depth: usize, stamps: &mut Vec<Stamp>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            if depth < MAX_DEPTH {
+                scan_dir(&entry.path(), depth + 1, stamps);
+            }
+        } else {
+            stamps.push((entry.path(), metadata.modified().ok()));
+        }
+    }
+}
@@ -0,0 +1,441 @@
+//! Gameplay sprites packed into a single texture.
+//!
+//! Notes, receptors and hold / burst / mine parts used to get one texture and
+//! one bind group each, so a frame switched bind groups and issued a draw call
+//! per column and per note type. At skin load all of them are packed into one
+//! atlas instead; instances carry the UV rectangle of their sprite and the
+//! whole playfield is drawn with the same bind group.
+
+use crate::models::engine::NUM_COLUMNS;
+use crate::models::skin::Skin;
+use crate::models::skin::hud::DIGIT_GLYPHS;
+use crate::models::stats::Judgement;
+use crate::render::utils::{create_sampler, texture_from_rgba};
+use image::{Rgba, RgbaImage};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// UV rectangle `[u, v, width, height]` of a sprite in the atlas.
+pub type UvRect = [f32; 4];
+
+/// Preferred atlas width; wider only if a sprite or the GPU limit requires it.
+const ATLAS_WIDTH: u32 = 2048;
+/// Transparent gap between sprites, so linear filtering never picks up a
+/// neighbour.
+const PADDING: u32 = 2;
+
+pub struct SpriteAtlas {
+    texture: wgpu::Texture,
+    pub bind_group: wgpu::BindGroup,
+    /// Per skin column.
+    pub notes: Vec<UvRect>,
+    pub receptors: Vec<UvRect>,
+    pub receptors_pressed: Vec<UvRect>,
+    /// Special notes; skins without the image use the first note sprite.
+    pub mine: UvRect,
+    pub hold_body: UvRect,
+    pub hold_end: UvRect,
+    pub burst_body: UvRect,
+    pub burst_end: UvRect,
+    /// Height / width ratio of the body images, the length of one repeat.
+    pub hold_body_aspect: f32,
+    pub burst_body_aspect: f32,
+    /// Judgement flash images, in [`Judgement::ALL`] order.
+    judgements: Vec<Option<UvRect>>,
+    digits: Option<DigitSprites>,
+    /// Texels of the color sprites standing in for missing receptor / note
+    /// images, rewritten in place when the skin colors change.
+    receptor_fills: Vec<(u32, u32)>,
+    note_fills: Vec<(u32, u32)>,
+}
+
+/// Number sprites of a skin that has all ten digits.
+pub struct DigitSprites {
+    /// Character, sprite and width / height ratio.
+    glyphs: Vec<(char, UvRect, f32)>,
+}
+
+impl DigitSprites {
+    /// Sprite and aspect ratio of a character, if the skin has an image for it.
+    pub fn glyph(&self, glyph: char) -> Option<(UvRect, f32)> {
+        self.glyphs
+            .iter()
+            .find(|(c, _, _)| *c == glyph)
+            .map(|&(_, uv, aspect)| (uv, aspect))
+    }
+}
+
+/// Images to pack, each loaded once even when several sprites share a file.
+struct Sprites {
+    images: Vec<RgbaImage>,
+    by_path: HashMap<PathBuf, usize>,
+    receptor_fills: Vec<usize>,
+    note_fills: Vec<usize>,
+}
+
+impl Sprites {
+    fn load(&mut self, path: Option<PathBuf>) -> Option<usize> {
+        let path = path?;
+        if let Some(&index) = self.by_path.get(&path) {
+            return Some(index);
+        }
+        let image = match image::open(&path) {
+            Ok(image) => image.to_rgba8(),
+            Err(e) => {
+                log::warn!("Failed to load texture {:?}: {}", path, e);
+                return None;
+            }
+        };
+        self.images.push(image);
+        self.by_path.insert(path, self.images.len() - 1);
+        Some(self.images.len() - 1)
+    }
+
+    /// One-pixel sprite of a skin color, for missing images.
+    fn color(&mut self, color: [f32; 4]) -> usize {
+        self.images
+            .push(RgbaImage::from_pixel(1, 1, Rgba(color_texel(color))));
+        self.images.len() - 1
+    }
+}
+
+impl SpriteAtlas {
+    pub fn build(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        layout: &wgpu::BindGroupLayout,
+        skin: &Skin,
+    ) -> Self {
+        let mut sprites = Sprites {
+            images: Vec::new(),
+            by_path: HashMap::new(),
+            receptor_fills: Vec::new(),
+            note_fills: Vec::new(),
+        };
+
+        let receptor_color = skin.gameplay.receptors.color;
+        let note_color = skin.gameplay.notes.note.color;
+        let mut notes = Vec::with_capacity(NUM_COLUMNS);
+        let mut receptors = Vec::with_capacity(NUM_COLUMNS);
+        let mut receptors_pressed = Vec::with_capacity(NUM_COLUMNS);
+        for col in 0..NUM_COLUMNS {
+            let receptor = sprites
+                .load(skin.get_receptor_image(NUM_COLUMNS, col))
+                .unwrap_or_else(|| {
+                    let fill = sprites.color(receptor_color);
+                    sprites.receptor_fills.push(fill);
+                    fill
+                });
+            let pressed = sprites
+                .load(skin.get_receptor_pressed_image(NUM_COLUMNS, col))
+                .unwrap_or(receptor);
+            let note = sprites
+                .load(skin.get_note_image(NUM_COLUMNS, col))
+                .unwrap_or_else(|| {
+                    let fill = sprites.color(note_color);
+                    sprites.note_fills.push(fill);
+                    fill
+                });
+            receptors.push(receptor);
+            receptors_pressed.push(pressed);
+            notes.push(note);
+        }
+
+        let fallback = notes[0];
+        let mine = sprites
+            .load(skin.get_mine_image(NUM_COLUMNS, 0))
+            .unwrap_or(fallback);
+        let hold_body = sprites
+            .load(skin.get_hold_body_image(NUM_COLUMNS, 0))
+            .unwrap_or(fallback);
+        let hold_end = sprites
+            .load(skin.get_hold_end_image(NUM_COLUMNS, 0))
+            .unwrap_or(fallback);
+        let burst_body = sprites
+            .load(skin.get_burst_body_image(NUM_COLUMNS, 0))
+            .unwrap_or(fallback);
+        let burst_end = sprites
+            .load(skin.get_burst_end_image(NUM_COLUMNS, 0))
+            .unwrap_or(fallback);
+        let judgements: Vec<Option<usize>> = Judgement::ALL
+            .iter()
+            .map(|&judgement| sprites.load(skin.get_judgement_image(judgement)))
+            .collect();
+        let digits: Vec<(char, usize)> = DIGIT_GLYPHS
+            .iter()
+            .filter_map(|&glyph| Some((glyph, sprites.load(skin.get_digit_image(glyph))?)))
+            .collect();
+        let has_digits = digits.iter().filter(|(c, _)| c.is_ascii_digit()).count() == 10;
+
+        let max_size = device.limits().max_texture_dimension_2d;
+        let mut images = sprites.images;
+        let Packing {
+            positions,
+            width,
+            height,
+        } = loop {
+            let sizes: Vec<(u32, u32)> = images.iter().map(|i| i.dimensions()).collect();
+            let packed = pack(&sizes, ATLAS_WIDTH.min(max_size), max_size);
+            if let Some(packed) = packed {
+                break packed;
+            }
+            // Only absurdly large skins get here: halve everything until it fits.
+            log::warn!("RENDER: Skin sprites do not fit in one texture, downscaling them");
+            images = images
+                .iter()
+                .map(|image| {
+                    image::imageops::resize(
+                        image,
+                        (image.width() / 2).max(1),
+                        (image.height() / 2).max(1),
+                        image::imageops::FilterType::Triangle,
+                    )
+                })
+                .collect();
+        };
+
+        let mut atlas = RgbaImage::new(width, height);
+        for (image, &(x, y)) in images.iter().zip(&positions) {
+            image::imageops::replace(&mut atlas, image, x as i64, y as i64);
+        }
+
+        // Half a texel inside each sprite, so sampling stays clear of its edges.
+        let uv = |index: usize| -> UvRect {
+            let (x, y) = positions[index];
+            let (w, h) = images[index].dimensions();
+            [
+                (x as f32 + 0.5) / width as f32,
+                (y as f32 + 0.5) / height as f32,
+                (w as f32 - 1.0) / width as f32,
+                (h as f32 - 1.0) / height as f32,
+            ]
+        };
+
+        let aspect = |index: usize| {
+            let (w, h) = images[index].dimensions();
+            h as f32 / w.max(1) as f32
+        };
+
+        let texture = texture_from_rgba(device, queue, Some("Sprite Atlas"), &atlas);
+        let view = texture.create_view(&Default::default());
+        let sampler = create_sampler(device);
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Sprite Atlas BG"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+
+        log::info!(
+            "RENDER: Packed {} sprites into a {}x{} atlas",
+            images.len(),
+            width,
+            height
+        );
+
+        Self {
+            texture,
+            bind_group,
+            notes: notes.into_iter().map(uv).collect(),
+            receptors: receptors.into_iter().map(uv).collect(),
+            receptors_pressed: receptors_pressed.into_iter().map(uv).collect(),
+            mine: uv(mine),
+            hold_body: uv(hold_body),
+            hold_end: uv(hold_end),
+            burst_body: uv(burst_body),
+            burst_end: uv(burst_end),
+            hold_body_aspect: aspect(hold_body),
+            burst_body_aspect: aspect(burst_body),
+            judgements: judgements.into_iter().map(|i| i.map(uv)).collect(),
+            digits: has_digits.then(|| DigitSprites {
+                glyphs: digits
+                    .iter()
+                    .map(|&(glyph, i)| {
+                        let (w, h) = images[i].dimensions();
+                        (glyph, uv(i), w as f32 / h as f32)
+                    })
+                    .collect(),
+            }),
+            receptor_fills: sprites
+                .receptor_fills
+                .iter()
+                .map(|&i| positions[i])
+                .collect(),
+            note_fills: sprites.note_fills.iter().map(|&i| positions[i]).collect(),
+        }
+    }
+
+    /// Applies new receptor / note colors to the color sprites, without
+    /// rebuilding the atlas.
+    pub fn update_colors(&self, queue: &wgpu::Queue, skin: &Skin) {
+        let fills = [
+            (&self.receptor_fills, skin.gameplay.receptors.color),
+            (&self.note_fills, skin.gameplay.notes.note.color),
+        ];
+        for (texels, color) in fills {
+            let texel = color_texel(color);
+            for &(x, y) in texels {
+                queue.write_texture(
+                    wgpu::TexelCopyTextureInfo {
+                        texture: &self.texture,
+                        mip_level: 0,
+                        origin: wgpu::Origin3d { x, y, z: 0 },
+                        aspect: wgpu::TextureAspect::All,
+                    },
+                    &texel,
+                    wgpu::TexelCopyBufferLayout {
+                        offset: 0,
+                        bytes_per_row: Some(4),
+                        rows_per_image: Some(1),
+                    },
+                    wgpu::Extent3d {
+                        width: 1,
+                        height: 1,
+                        depth_or_array_layers: 1,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Image files the atlas is built from, to tell whether a skin edit
+    /// needs a rebuild.
+    pub fn sources(skin: &Skin) -> Vec<Option<PathBuf>> {
+        let mut paths = Vec::with_capacity(3 * NUM_COLUMNS + 5);
+        for col in 0..NUM_COLUMNS {
+            paths.push(skin.get_receptor_image(NUM_COLUMNS, col));
+            paths.push(skin.get_receptor_pressed_image(NUM_COLUMNS, col));
+            paths.push(skin.get_note_image(NUM_COLUMNS, col));
+        }
+        paths.push(skin.get_mine_image(NUM_COLUMNS, 0));
+        paths.push(skin.get_hold_body_image(NUM_COLUMNS, 0));
+        paths.push(skin.get_hold_end_image(NUM_COLUMNS, 0));
+        paths.push(skin.get_burst_body_image(NUM_COLUMNS, 0));
+        paths.push(skin.get_burst_end_image(NUM_COLUMNS, 0));
+        paths.extend(
+            Judgement::ALL
+                .iter()
+                .map(|&judgement| skin.get_judgement_image(judgement)),
+        );
+        paths.extend(
+            DIGIT_GLYPHS
+                .iter()
+                .map(|&glyph| skin.get_digit_image(glyph)),
+        );
+        paths
+    }
+
+    /// Note sprite of a skin column.
+    pub fn note(&self, col: usize) -> UvRect {
+        self.notes.get(col).copied().unwrap_or(self.notes[0])
+    }
+
+    /// Flash image of a judgement, when the skin has one.
+    pub fn judgement(&self, judgement: Judgement) -> Option<UvRect> {
+        self.judgements[judgement as usize]
+    }
+
+    /// Number sprites, when the skin replaces the font with them.
+    pub fn digits(&self) -> Option<&DigitSprites> {
+        self.digits.as_ref()
+    }
+
+    /// Receptor sprite of a skin column.
+    pub fn receptor(&self, col: usize, pressed: bool) -> UvRect {
+        let sprites = if pressed {
+            &self.receptors_pressed
+        } else {
+            &self.receptors
+        };
+        sprites.get(col).copied().unwrap_or(sprites[0])
+    }
+}
+
+fn color_texel(color: [f32; 4]) -> [u8; 4] {
+    color.map(|c| (c * 255.) as u8)
+}
+
+/// Shelf packing: sprites sorted by height are laid out in rows of at most
+/// `width` pixels (more if a sprite is wider). Returns the position of every
+/// sprite and the atlas size, or `None` when it would exceed `max_size`.
+/// Sprite positions and atlas size.
+struct Packing {
+    positions: Vec<(u32, u32)>,
+    width: u32,
+    height: u32,
+}
+
+fn pack(sizes: &[(u32, u32)], width: u32, max_size: u32) -> Option<Packing> {
+    let widest = sizes.iter().map(|&(w, _)| w).max().unwrap_or(1);
+    let width = width.max(widest + PADDING);
+    if width > max_size {
+        return None;
+    }
+
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(sizes[i].1));
+
+    let mut positions = vec![(0, 0); sizes.len()];
+    let (mut x, mut y, mut shelf_height) = (0, 0, 0);
+    for i in order {
+        let (w, h) = sizes[i];
+        if x + w > width {
+            x = 0;
+            y += shelf_height + PADDING;
+            shelf_height = 0;
+        }
+        positions[i] = (x, y);
+        x += w + PADDING;
+        shelf_height = shelf_height.max(h);
+    }
+
+    let height = (y + shelf_height).max(1);
+    (height <= max_size).then_some(Packing {
+        positions,
+        width,
+        height,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packed_sprites_do_not_overlap() {
+        let sizes = [
+            (128, 128),
+            (300, 64),
+            (1, 1),
+            (128, 40),
+            (700, 100),
+            (64, 128),
+        ];
+        let Packing {
+            positions,
+            width,
+            height,
+        } = pack(&sizes, 512, 8192).unwrap();
+        assert_eq!(width, 702);
+
+        for (i, (&(x, y), &(w, h))) in positions.iter().zip(&sizes).enumerate() {
+            assert!(x + w <= width && y + h <= height);
+            for (&(ox, oy), &(ow, oh)) in positions.iter().zip(&sizes).skip(i + 1) {
+                assert!(x + w <= ox || ox + ow <= x || y + h <= oy || oy + oh <= y);
+            }
+        }
+    }
+
+    #[test]
+    fn too_large_for_the_gpu() {
+        assert!(pack(&[(4096, 4096), (4096, 4096)], 2048, 4096).is_none());
+    }
+}
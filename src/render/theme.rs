@@ -0,0 +1,113 @@
+//! Conversion of the skin's `theme.toml` into egui styles.
+
+use crate::models::skin::{EguiStyleConfig, Skin, ThemeScope};
+use crate::shared::snapshot::RenderState;
+use egui::{Color32, CornerRadius, Stroke, Style, Theme};
+use std::sync::Arc;
+
+/// Screen whose theme section applies to a render state.
+pub fn scope_of(state: &RenderState) -> ThemeScope {
+    match state {
+        RenderState::Empty | RenderState::Menu(_) => ThemeScope::Menu,
+        RenderState::InGame(_) => ThemeScope::Gameplay,
+        RenderState::Editor(_) => ThemeScope::Editor,
+        RenderState::Result(_) => ThemeScope::Result,
+    }
+}
+
+fn to_color32(c: [f32; 4]) -> Color32 {
+    Color32::from_rgba_unmultiplied(
+        (c[0] * 255.) as u8,
+        (c[1] * 255.) as u8,
+        (c[2] * 255.) as u8,
+        (c[3] * 255.) as u8,
+    )
+}
+
+/// Applies the set parameters on top of `style`.
+fn apply(config: &EguiStyleConfig, style: &mut Style) {
+    let visuals = &mut style.visuals;
+
+    if let Some(r) = config.rounding {
+        let radius = CornerRadius::same(r.clamp(0.0, 255.0) as u8);
+        visuals.window_corner_radius = radius;
+        visuals.menu_corner_radius = radius;
+        for widget in [
+            &mut visuals.widgets.noninteractive,
+            &mut visuals.widgets.inactive,
+            &mut visuals.widgets.hovered,
+            &mut visuals.widgets.active,
+            &mut visuals.widgets.open,
+        ] {
+            widget.corner_radius = radius;
+        }
+    }
+    if let Some(c) = config.accent {
+        let accent = to_color32(c);
+        visuals.selection.bg_fill = accent;
+        visuals.hyperlink_color = accent;
+        visuals.widgets.active.bg_fill = accent;
+        visuals.widgets.active.weak_bg_fill = accent;
+        visuals.widgets.hovered.bg_stroke = Stroke::new(1.0, accent);
+    }
+    if let Some(c) = config.panel_fill {
+        visuals.panel_fill = to_color32(c);
+    }
+    if let Some(c) = config.window_fill {
+        visuals.window_fill = to_color32(c);
+    }
+    if let Some(c) = config.window_stroke {
+        visuals.window_stroke = Stroke::new(visuals.window_stroke.width, to_color32(c));
+    }
+    if let Some(c) = config.extreme_bg {
+        visuals.extreme_bg_color = to_color32(c);
+        visuals.faint_bg_color = to_color32(c);
+    }
+    if let Some(c) = config.widget_fill {
+        visuals.widgets.inactive.bg_fill = to_color32(c);
+        visuals.widgets.inactive.weak_bg_fill = to_color32(c);
+    }
+    if let Some(c) = config.widget_hover_fill {
+        visuals.widgets.hovered.bg_fill = to_color32(c);
+        visuals.widgets.hovered.weak_bg_fill = to_color32(c);
+    }
+    if let Some(c) = config.text {
+        visuals.override_text_color = Some(to_color32(c));
+    }
+}
+
+/// egui styles of every screen, built once per skin load.
+pub struct ThemeStyles {
+    menu: Arc<Style>,
+    result: Arc<Style>,
+    editor: Arc<Style>,
+    gameplay: Arc<Style>,
+}
+
+impl ThemeStyles {
+    /// Builds the styles from the skin's theme, on top of egui's default
+    /// style for `theme` (dark or light).
+    pub fn from_skin(skin: &Skin, theme: Theme) -> Self {
+        let build = |scope| {
+            let mut style = theme.default_style();
+            apply(&skin.theme.resolve(scope), &mut style);
+            Arc::new(style)
+        };
+        Self {
+            menu: build(ThemeScope::Menu),
+            result: build(ThemeScope::Result),
+            editor: build(ThemeScope::Editor),
+            gameplay: build(ThemeScope::Gameplay),
+        }
+    }
+
+    /// Style of a screen.
+    pub fn get(&self, scope: ThemeScope) -> Arc<Style> {
+        match scope {
+            ThemeScope::Menu => self.menu.clone(),
+            ThemeScope::Result => self.result.clone(),
+            ThemeScope::Editor => self.editor.clone(),
+            ThemeScope::Gameplay => self.gameplay.clone(),
+        }
+    }
+}
@@ -16,6 +16,7 @@ pub struct UiOverlay {
 impl UiOverlay {
     pub fn new(window: Arc<Window>, device: &Device, output_format: TextureFormat) -> Self {
         let ctx = egui::Context::default();
+        crate::render::fonts::install(&ctx);
         let state = EguiState::new(
             ctx.clone(),
             egui::ViewportId::ROOT,
@@ -6,20 +6,13 @@ use wgpu_text::{BrushBuilder, TextBrush};
 
 // --- GESTION DES TEXTURES ---
 
-pub fn load_texture_from_path(
+/// Uploads an already decoded image.
+pub fn texture_from_rgba(
     device: &Device,
     queue: &Queue,
-    path: &Path,
-) -> Option<(Texture, u32, u32)> {
-    let img = match image::open(path) {
-        Ok(i) => i,
-        Err(e) => {
-            log::warn!("Failed to load texture {:?}: {}", path, e);
-            return None;
-        }
-    };
-
-    let rgba = img.to_rgba8();
+    label: Option<&str>,
+    rgba: &image::RgbaImage,
+) -> Texture {
     let (width, height) = rgba.dimensions();
 
     let texture_size = wgpu::Extent3d {
@@ -29,7 +22,7 @@ pub fn load_texture_from_path(
     };
 
     let texture = device.create_texture(&wgpu::TextureDescriptor {
-        label: path.to_str(),
+        label,
         size: texture_size,
         mip_level_count: 1,
         sample_count: 1,
@@ -46,7 +39,7 @@ pub fn load_texture_from_path(
             origin: wgpu::Origin3d::ZERO,
             aspect: wgpu::TextureAspect::All,
         },
-        &rgba,
+        rgba,
         wgpu::TexelCopyBufferLayout {
             offset: 0,
             bytes_per_row: Some(4 * width),
@@ -55,50 +48,6 @@ pub fn load_texture_from_path(
         texture_size,
     );
 
-    Some((texture, width, height))
-}
-
-pub fn create_default_texture(
-    device: &Device,
-    queue: &Queue,
-    color: [u8; 4],
-    label: &str,
-) -> Texture {
-    let texture = device.create_texture(&wgpu::TextureDescriptor {
-        label: Some(label),
-        size: wgpu::Extent3d {
-            width: 1,
-            height: 1,
-            depth_or_array_layers: 1,
-        },
-        mip_level_count: 1,
-        sample_count: 1,
-        dimension: wgpu::TextureDimension::D2,
-        format: wgpu::TextureFormat::Rgba8UnormSrgb,
-        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-        view_formats: &[],
-    });
-
-    queue.write_texture(
-        wgpu::TexelCopyTextureInfo {
-            texture: &texture,
-            mip_level: 0,
-            origin: wgpu::Origin3d::ZERO,
-            aspect: wgpu::TextureAspect::All,
-        },
-        &color,
-        wgpu::TexelCopyBufferLayout {
-            offset: 0,
-            bytes_per_row: Some(4),
-            rows_per_image: Some(1),
-        },
-        wgpu::Extent3d {
-            width: 1,
-            height: 1,
-            depth_or_array_layers: 1,
-        },
-    );
-
     texture
 }
 
@@ -143,6 +92,7 @@ pub fn create_sampler(device: &Device) -> Sampler {
 pub fn create_render_pipeline(
     device: &Device,
     bind_group_layout: &BindGroupLayout,
+    feedback_layout: &BindGroupLayout,
     format: TextureFormat,
 ) -> RenderPipeline {
     let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
@@ -164,12 +114,32 @@ pub fn create_render_pipeline(
                 shader_location: 6,
                 format: wgpu::VertexFormat::Float32x2,
             }, // Scale
+            wgpu::VertexAttribute {
+                offset: 16,
+                shader_location: 7,
+                format: wgpu::VertexFormat::Float32x4,
+            }, // UV rect
+            wgpu::VertexAttribute {
+                offset: 32,
+                shader_location: 8,
+                format: wgpu::VertexFormat::Float32,
+            }, // Alpha
+            wgpu::VertexAttribute {
+                offset: 36,
+                shader_location: 9,
+                format: wgpu::VertexFormat::Float32,
+            }, // Tile
+            wgpu::VertexAttribute {
+                offset: 40,
+                shader_location: 10,
+                format: wgpu::VertexFormat::Float32,
+            }, // Clip y
         ],
     };
 
     let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
         label: Some("Render Pipeline Layout"),
-        bind_group_layouts: &[bind_group_layout],
+        bind_group_layouts: &[bind_group_layout, feedback_layout],
         push_constant_ranges: &[],
     });
 
@@ -202,6 +172,9 @@ pub fn create_render_pipeline(
 
 // --- GESTION DU TEXTE ---
 
+/// Police par défaut, utilisée quand celle du skin manque.
+const DEFAULT_FONT: &[u8] = include_bytes!("../../assets/font.ttf");
+
 pub fn load_text_brush(
     device: &Device,
     width: u32,
@@ -220,15 +193,17 @@ pub fn load_text_brush(
         None
     };
 
-    // Fallback si la police n'est pas trouvée
+    // Police du skin, sinon celle incluse dans l'exécutable
     let final_font = font.unwrap_or_else(|| {
         log::warn!(
-            "Font not found or failed to load, using default (Hack: Empty Font might panic if used)"
+            "RENDER: Font {:?} not found or invalid, using the default one",
+            font_path
         );
-        // Pour éviter le panic, idéalement on devrait avoir une police par défaut incluse en bytes (include_bytes!)
-        // Ici on retourne une police vide qui risque de ne rien afficher, mais évite le crash immédiat si géré par wgpu_text
-        FontArc::try_from_vec(vec![]).unwrap_or_else(|_| panic!("Fatal: No font available"))
+        FontArc::try_from_slice(DEFAULT_FONT).expect("the bundled default font is valid")
     });
 
-    BrushBuilder::using_font(final_font).build(device, width, height, format)
+    // Les polices de secours suivent, pour les titres CJK
+    let mut fonts = vec![final_font];
+    fonts.extend(crate::render::fonts::brush_fallbacks());
+    BrushBuilder::using_fonts(fonts).build(device, width, height, format)
 }
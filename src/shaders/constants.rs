@@ -2,3 +2,4 @@ pub const BACKGROUND_SHADER_SRC: &str = include_str!("background_shader.wgsl");
 pub const QUAD_SHADER_SRC: &str = include_str!("quad_shader.wgsl");
 pub const PROGRESS_SHADER_SRC: &str = include_str!("progress_shader.wgsl");
 pub const MAIN_SHADER_SRC: &str = include_str!("shader.wgsl");
+pub const GAMEPLAY_BACKGROUND_SHADER_SRC: &str = include_str!("gameplay_background.wgsl");
@@ -0,0 +1,54 @@
+// In-game background: separable gaussian blur passes and the dimmed draw.
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) in_vertex_index: u32) -> VertexOutput {
+    var pos = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, 1.0), vec2<f32>(-1.0, -1.0), vec2<f32>(1.0, -1.0),
+        vec2<f32>(-1.0, 1.0), vec2<f32>(1.0, -1.0), vec2<f32>(1.0, 1.0)
+    );
+
+    var uvs = array<vec2<f32>, 6>(
+        vec2<f32>(0.0, 0.0), vec2<f32>(0.0, 1.0), vec2<f32>(1.0, 1.0),
+        vec2<f32>(0.0, 0.0), vec2<f32>(1.0, 1.0), vec2<f32>(1.0, 0.0)
+    );
+
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(pos[in_vertex_index], 0.0, 1.0);
+    out.tex_coords = uvs[in_vertex_index];
+    return out;
+}
+
+struct Params {
+    // UV offset between two blur taps (horizontal or vertical pass).
+    step: vec2<f32>,
+    // 0.0 = full brightness, 1.0 = black.
+    dim: f32,
+    _padding: f32,
+};
+
+@group(0) @binding(0) var t_source: texture_2d<f32>;
+@group(0) @binding(1) var s_source: sampler;
+@group(1) @binding(0) var<uniform> params: Params;
+
+@fragment
+fn fs_blur(in: VertexOutput) -> @location(0) vec4<f32> {
+    var weights = array<f32, 5>(0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216);
+    var color = textureSample(t_source, s_source, in.tex_coords) * weights[0];
+    for (var i = 1; i < 5; i++) {
+        let offset = params.step * f32(i);
+        color += textureSample(t_source, s_source, in.tex_coords + offset) * weights[i];
+        color += textureSample(t_source, s_source, in.tex_coords - offset) * weights[i];
+    }
+    return color;
+}
+
+@fragment
+fn fs_dim(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(t_source, s_source, in.tex_coords);
+    return vec4<f32>(color.rgb * (1.0 - params.dim), color.a);
+}
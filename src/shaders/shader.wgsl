@@ -1,11 +1,33 @@
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
-    @location(0) tex_coords: vec2<f32>,
+    // Position in the quad; y counts texture repeats when tiled.
+    @location(0) local: vec2<f32>,
+    @location(1) alpha: f32,
+    @location(2) @interpolate(flat) uv: vec4<f32>,
+    @location(3) @interpolate(flat) tile: f32,
+    // Height above the clip line, negative where the quad is cut.
+    @location(4) above_clip: f32,
 };
 
+// Miss feedback (render/feedback.rs): offset of the shaking playfield in
+// clip space, and the color of the edge flash.
+struct Feedback {
+    offset: vec2<f32>,
+    vignette: vec4<f32>,
+};
+
+@group(1) @binding(0) var<uniform> feedback: Feedback;
+
 struct InstanceInput {
     @location(5) offset: vec2<f32>,
     @location(6) scale: vec2<f32>,
+    // Sprite rectangle in the atlas: xy = origin, zw = size.
+    @location(7) uv: vec4<f32>,
+    @location(8) alpha: f32,
+    // Repeats of the texture down the quad (0 = stretched once).
+    @location(9) tile: f32,
+    // Clip-space height under which the quad is cut.
+    @location(10) clip_y: f32,
 };
 
 @vertex
@@ -23,11 +45,17 @@ fn vs_main(
         vec2<f32>(0.0, 0.0), vec2<f32>(1.0, 1.0), vec2<f32>(1.0, 0.0)
     );
 
-    let world_pos = (pos[in_vertex_index] * instance.scale) + instance.offset;
+    let quad_pos = (pos[in_vertex_index] * instance.scale) + instance.offset;
+    let world_pos = quad_pos + feedback.offset;
 
     var out: VertexOutput;
     out.clip_position = vec4<f32>(world_pos, 0.0, 1.0);
-    out.tex_coords = uvs[in_vertex_index];
+    out.local = uvs[in_vertex_index] * vec2<f32>(1.0, max(instance.tile, 1.0));
+    out.alpha = instance.alpha;
+    out.uv = instance.uv;
+    out.tile = instance.tile;
+    // Before the shake, so the cut stays on the receptors.
+    out.above_clip = quad_pos.y - instance.clip_y;
     return out;
 }
 
@@ -36,6 +64,39 @@ fn vs_main(
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    return textureSample(t_diffuse, s_diffuse, in.tex_coords);
+    if in.above_clip < 0.0 {
+        discard;
+    }
+    var v = in.local.y;
+    if in.tile > 0.0 {
+        v = fract(v);
+    }
+    let tex_coords = in.uv.xy + vec2<f32>(in.local.x, v) * in.uv.zw;
+    // Explicit level: fract() breaks the derivatives at each tile seam.
+    let color = textureSampleLevel(t_diffuse, s_diffuse, tex_coords, 0.0);
+    return vec4<f32>(color.rgb, color.a * in.alpha);
+}
+
+
+struct VignetteOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) ndc: vec2<f32>,
+};
+
+// One triangle covering the screen.
+@vertex
+fn vs_vignette(@builtin(vertex_index) in_vertex_index: u32) -> VignetteOutput {
+    let uv = vec2<f32>(f32((in_vertex_index << 1u) & 2u), f32(in_vertex_index & 2u));
+    let ndc = uv * 2.0 - 1.0;
+
+    var out: VignetteOutput;
+    out.clip_position = vec4<f32>(ndc, 0.0, 1.0);
+    out.ndc = ndc;
+    return out;
 }
 
+@fragment
+fn fs_vignette(in: VignetteOutput) -> @location(0) vec4<f32> {
+    let edge = smoothstep(0.5, 1.4, length(in.ndc));
+    return vec4<f32>(feedback.vignette.rgb, feedback.vignette.a * edge);
+}
@@ -0,0 +1,281 @@
+//! Audio clock shared between the audio thread and the game logic.
+//!
+//! The output device pulls the music in buffers, one per period. The
+//! output callback stamps the time of each pull, and the first song sample
+//! pulled after it publishes where in the song that block starts. A block
+//! is heard about one period after it was pulled, so the audible position
+//! at any instant is the block start, plus the time elapsed since, minus
+//! the period, at the playback speed. It never runs past the samples the
+//! device was given: the last block is assumed as long as the one before.
+//! The game reads it without waiting on the audio thread, instead of
+//! polling a sample counter that moves in block-sized jumps.
+//!
+//! The block is published under a sequence lock: writers make the sequence
+//! odd while they update it, and readers retry when it changed under them.
+
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering, fence};
+use std::time::Instant;
+
+/// Longest gap between two blocks counted as a device period (a longer one
+/// is a pause or a stall, not the latency).
+const MAX_PERIOD_NS: u64 = 100_000_000;
+
+/// Playback position of the music, written by the audio thread.
+pub struct AudioClock {
+    /// Reference point of the block times.
+    epoch: Instant,
+    /// When the output callback last pulled a buffer, in ns since `epoch`.
+    pull_ns: AtomicU64,
+
+    /// Even when the block below is consistent, odd while it is written.
+    sequence: AtomicU64,
+    /// Source being played: blocks of a replaced one are ignored.
+    generation: AtomicU64,
+    /// Source sample (interleaved) the last block starts at.
+    block_sample: AtomicU64,
+    /// Source samples of the block before, 0 if there is none.
+    block_len: AtomicU64,
+    /// When the device pulled the last block, in ns since `epoch`. 0 until
+    /// a block was pulled since playback (re)started.
+    block_ns: AtomicU64,
+    /// Time between the last two blocks, in ns: the latency estimate.
+    period_ns: AtomicU64,
+
+    sample_rate: AtomicU32,
+    channels: AtomicU32,
+    /// Playback speed, stored as `f32` bits.
+    speed: AtomicU32,
+    playing: AtomicBool,
+    /// Seeks sent to the audio thread and not done yet.
+    pending_seeks: AtomicU32,
+}
+
+/// Consistent copy of the last block.
+#[derive(Debug, Clone, Copy)]
+struct Block {
+    sample: u64,
+    len: u64,
+    ns: u64,
+    period_ns: u64,
+}
+
+impl AudioClock {
+    pub fn new() -> Self {
+        Self {
+            epoch: Instant::now(),
+            pull_ns: AtomicU64::new(0),
+            sequence: AtomicU64::new(0),
+            generation: AtomicU64::new(0),
+            block_sample: AtomicU64::new(0),
+            block_len: AtomicU64::new(0),
+            block_ns: AtomicU64::new(0),
+            period_ns: AtomicU64::new(0),
+            sample_rate: AtomicU32::new(44100),
+            channels: AtomicU32::new(2),
+            speed: AtomicU32::new(1.0f32.to_bits()),
+            playing: AtomicBool::new(false),
+            pending_seeks: AtomicU32::new(0),
+        }
+    }
+
+    /// Format of the source about to be played.
+    pub fn set_format(&self, sample_rate: u32, channels: u16) {
+        self.sample_rate.store(sample_rate, Ordering::Relaxed);
+        self.channels.store(channels as u32, Ordering::Relaxed);
+    }
+
+    pub fn set_speed(&self, speed: f32) {
+        self.speed.store(speed.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Called before the sink starts or stops. The clock runs again from
+    /// the first block pulled after a start.
+    pub fn set_playing(&self, playing: bool) {
+        if playing {
+            self.write(|| self.block_ns.store(0, Ordering::Relaxed));
+        }
+        self.playing.store(playing, Ordering::Relaxed);
+    }
+
+    /// Starts a new source at `sample`. Returns the generation its blocks
+    /// are published with.
+    pub fn restart_at(&self, sample: u64) -> u64 {
+        let mut generation = 0;
+        self.write(|| {
+            generation = self.generation.load(Ordering::Relaxed) + 1;
+            self.generation.store(generation, Ordering::Relaxed);
+            self.block_sample.store(sample, Ordering::Relaxed);
+            self.block_ns.store(0, Ordering::Relaxed);
+        });
+        generation
+    }
+
+    /// Called by the output callback, once per buffer, before filling it.
+    pub fn device_pull(&self, at: Instant) {
+        // Never 0, which means no pull.
+        self.pull_ns.store(self.nanos(at).max(1), Ordering::Relaxed);
+    }
+
+    /// Time of the last buffer pull, to spot the first sample of a block.
+    pub fn last_pull_ns(&self) -> u64 {
+        self.pull_ns.load(Ordering::Relaxed)
+    }
+
+    /// The block pulled at `pull_ns` starts at source `sample`.
+    pub fn start_block(&self, generation: u64, sample: u64, pull_ns: u64) {
+        self.write(|| {
+            if self.generation.load(Ordering::Relaxed) != generation {
+                return;
+            }
+            let previous = self.block_ns.load(Ordering::Relaxed);
+            if previous != 0 {
+                let period = pull_ns.saturating_sub(previous);
+                if period <= MAX_PERIOD_NS {
+                    self.period_ns.store(period, Ordering::Relaxed);
+                }
+                let previous_sample = self.block_sample.load(Ordering::Relaxed);
+                self.block_len
+                    .store(sample.saturating_sub(previous_sample), Ordering::Relaxed);
+            } else {
+                self.block_len.store(0, Ordering::Relaxed);
+            }
+            self.block_sample.store(sample, Ordering::Relaxed);
+            self.block_ns.store(pull_ns.max(1), Ordering::Relaxed);
+        });
+    }
+
+    /// A seek was sent to the audio thread: the position is unknown until
+    /// it is done.
+    pub fn begin_seek(&self) {
+        self.pending_seeks.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn end_seek(&self) {
+        let _ = self
+            .pending_seeks
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1));
+    }
+
+    /// Start of the last block pulled, in seconds of the source.
+    pub fn position_secs(&self) -> f64 {
+        self.read().sample as f64 / self.samples_per_sec()
+    }
+
+    /// Position heard at `now`, in ms of the source. `None` while nothing
+    /// plays: before the first block, while paused or seeking.
+    pub fn audible_ms(&self, now: Instant) -> Option<f64> {
+        if !self.playing.load(Ordering::Relaxed) || self.pending_seeks.load(Ordering::Relaxed) > 0 {
+            return None;
+        }
+        let block = self.read();
+        if block.ns == 0 {
+            return None;
+        }
+        let samples_per_ms = self.samples_per_sec() / 1000.0;
+        let elapsed_ms = (self.nanos(now) as f64 - block.ns as f64).max(0.0) / 1e6;
+        let latency_ms = block.period_ns as f64 / 1e6;
+        let speed = f32::from_bits(self.speed.load(Ordering::Relaxed)) as f64;
+        let start_ms = block.sample as f64 / samples_per_ms;
+        // The device has nothing past the end of the last block.
+        let end_ms = (block.sample + block.len) as f64 / samples_per_ms;
+        Some((start_ms + (elapsed_ms - latency_ms) * speed).min(end_ms))
+    }
+
+    fn nanos(&self, at: Instant) -> u64 {
+        at.saturating_duration_since(self.epoch).as_nanos() as u64
+    }
+
+    fn samples_per_sec(&self) -> f64 {
+        let sample_rate = self.sample_rate.load(Ordering::Relaxed).max(1) as f64;
+        let channels = self.channels.load(Ordering::Relaxed).max(1) as f64;
+        sample_rate * channels
+    }
+
+    /// Updates the block. Both the audio callback and the audio thread
+    /// write it, so writers take turns on the odd sequence.
+    fn write(&self, update: impl FnOnce()) {
+        let mut sequence = self.sequence.load(Ordering::Relaxed);
+        loop {
+            if sequence.is_multiple_of(2) {
+                match self.sequence.compare_exchange_weak(
+                    sequence,
+                    sequence + 1,
+                    Ordering::Acquire,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => break,
+                    Err(current) => sequence = current,
+                }
+            } else {
+                std::hint::spin_loop();
+                sequence = self.sequence.load(Ordering::Relaxed);
+            }
+        }
+        fence(Ordering::Release);
+        update();
+        self.sequence.store(sequence + 2, Ordering::Release);
+    }
+
+    fn read(&self) -> Block {
+        loop {
+            let before = self.sequence.load(Ordering::Acquire);
+            let block = Block {
+                sample: self.block_sample.load(Ordering::Relaxed),
+                len: self.block_len.load(Ordering::Relaxed),
+                ns: self.block_ns.load(Ordering::Relaxed),
+                period_ns: self.period_ns.load(Ordering::Relaxed),
+            };
+            fence(Ordering::Acquire);
+            if before.is_multiple_of(2) && self.sequence.load(Ordering::Relaxed) == before {
+                return block;
+            }
+            std::hint::spin_loop();
+        }
+    }
+}
+
+impl Default for AudioClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn audible_position_runs_from_the_last_block_minus_the_period() {
+        let clock = AudioClock::new();
+        clock.set_format(1000, 1);
+        let generation = clock.restart_at(0);
+        clock.set_playing(true);
+        let t0 = Instant::now();
+        let at = |ms: u64| clock.nanos(t0 + Duration::from_millis(ms));
+        assert_eq!(clock.audible_ms(t0), None);
+
+        clock.start_block(generation, 0, at(0));
+        clock.start_block(generation, 10, at(10));
+        let audible = clock.audible_ms(t0 + Duration::from_millis(15)).unwrap();
+        assert!((audible - 5.0).abs() < 1e-6, "{}", audible);
+
+        clock.set_speed(2.0);
+        let audible = clock.audible_ms(t0 + Duration::from_millis(15)).unwrap();
+        assert!((audible - 0.0).abs() < 1e-6, "{}", audible);
+
+        // Without new blocks, the clock stops at the end of the last one.
+        clock.set_speed(1.0);
+        let audible = clock.audible_ms(t0 + Duration::from_secs(5)).unwrap();
+        assert!((audible - 20.0).abs() < 1e-6, "{}", audible);
+
+        // Blocks of a replaced source and pending seeks don't move the clock.
+        clock.restart_at(500);
+        clock.start_block(generation, 20, at(20));
+        assert_eq!(clock.audible_ms(t0 + Duration::from_millis(25)), None);
+        clock.begin_seek();
+        assert_eq!(clock.audible_ms(t0 + Duration::from_millis(25)), None);
+        clock.end_seek();
+        assert_eq!(clock.position_secs(), 0.5);
+    }
+}
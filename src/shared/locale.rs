@@ -0,0 +1,178 @@
+//! Translations of the interface strings.
+//!
+//! Each language is a TOML file of `locales/` named after its code
+//! (`fr.toml`). Nested tables give dotted keys, so
+//!
+//! ```toml
+//! [meta]
+//! name = "Français"
+//!
+//! [settings]
+//! title = "Paramètres"
+//! ```
+//!
+//! is looked up with `tr("settings.title")`. English is built into the
+//! executable and fills in every key a translation lacks; a key missing
+//! there too is shown as is, so a typo is visible rather than blank.
+//! `{name}` placeholders are filled by [`tr_args`].
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::Path;
+use std::sync::{LazyLock, RwLock};
+
+/// Folder holding the translation files.
+const LOCALE_DIR: &str = "locales";
+
+/// Language of the strings built into the executable.
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+static ENGLISH: LazyLock<Locale> = LazyLock::new(|| {
+    Locale::parse(include_str!("../../locales/en.toml"))
+        .expect("the built-in English strings are valid TOML")
+});
+
+/// Language picked in the settings, `None` for English.
+static ACTIVE: RwLock<Option<Locale>> = RwLock::new(None);
+
+struct Locale {
+    strings: HashMap<String, String>,
+}
+
+impl Locale {
+    fn parse(source: &str) -> Result<Self, String> {
+        let table: toml::Table = source.parse().map_err(|e| format!("{}", e))?;
+        let mut strings = HashMap::new();
+        flatten("", &table, &mut strings);
+        Ok(Self { strings })
+    }
+
+    fn load(code: &str) -> Result<Self, String> {
+        let path = Path::new(LOCALE_DIR).join(format!("{}.toml", code));
+        let source = std::fs::read_to_string(&path).map_err(|e| format!("{:?}: {}", path, e))?;
+        Self::parse(&source)
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.strings.get(key).map(String::as_str)
+    }
+}
+
+fn flatten(prefix: &str, table: &toml::Table, strings: &mut HashMap<String, String>) {
+    for (name, value) in table {
+        let key = if prefix.is_empty() {
+            name.clone()
+        } else {
+            format!("{}.{}", prefix, name)
+        };
+        match value {
+            toml::Value::String(text) => {
+                strings.insert(key, text.clone());
+            }
+            toml::Value::Table(inner) => flatten(&key, inner, strings),
+            _ => log::warn!("RENDER: Ignoring non-text translation {}", key),
+        }
+    }
+}
+
+/// Switches the interface language. An unknown or unreadable language
+/// falls back to English.
+pub fn set_language(code: &str) {
+    let locale = if code == DEFAULT_LANGUAGE {
+        None
+    } else {
+        match Locale::load(code) {
+            Ok(locale) => Some(locale),
+            Err(e) => {
+                log::warn!(
+                    "RENDER: Language '{}' unavailable, using English: {}",
+                    code,
+                    e
+                );
+                None
+            }
+        }
+    };
+    if let Ok(mut active) = ACTIVE.write() {
+        *active = locale;
+    }
+}
+
+/// Languages found in `locales/` as `(code, name)`, English first.
+pub fn available() -> Vec<(String, String)> {
+    let mut languages: Vec<(String, String)> = std::fs::read_dir(LOCALE_DIR)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != "toml" {
+                return None;
+            }
+            let code = path.file_stem()?.to_str()?.to_string();
+            if code == DEFAULT_LANGUAGE {
+                return None;
+            }
+            let name = Locale::load(&code)
+                .ok()
+                .and_then(|locale| locale.get("meta.name").map(str::to_string))
+                .unwrap_or_else(|| code.clone());
+            Some((code, name))
+        })
+        .collect();
+    languages.sort_by(|a, b| a.1.cmp(&b.1));
+    let english = ENGLISH.get("meta.name").unwrap_or("English").to_string();
+    languages.insert(0, (DEFAULT_LANGUAGE.to_string(), english));
+    languages
+}
+
+/// Text of `key` in the active language.
+pub fn tr(key: &str) -> String {
+    let active = ACTIVE.read().ok();
+    active
+        .as_ref()
+        .and_then(|active| active.as_ref()?.get(key))
+        .or_else(|| ENGLISH.get(key))
+        .unwrap_or(key)
+        .to_string()
+}
+
+/// Text of `key` with its `{name}` placeholders replaced by `args`.
+pub fn tr_args(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut text = tr(key);
+    for (name, value) in args {
+        text = text.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_tables_become_dotted_keys() {
+        let locale = Locale::parse(
+            "[meta]\nname = \"Test\"\n[settings.category]\naudio = \"Son\"\ncount = 3\n",
+        )
+        .unwrap();
+        assert_eq!(locale.get("meta.name"), Some("Test"));
+        assert_eq!(locale.get("settings.category.audio"), Some("Son"));
+        assert_eq!(locale.get("settings.category.count"), None);
+    }
+
+    #[test]
+    fn missing_keys_fall_back_to_english_then_the_key() {
+        assert_eq!(tr("settings.title"), "Settings");
+        assert_eq!(tr("no.such.key"), "no.such.key");
+        assert_eq!(
+            tr_args("song_select.beatmap_count", &[("count", &12)]),
+            "Beatmaps: 12"
+        );
+        // Every key of the French strings exists in English.
+        let french = Locale::parse(include_str!("../../locales/fr.toml")).unwrap();
+        for key in french.strings.keys() {
+            assert!(ENGLISH.get(key).is_some(), "{} is not an English key", key);
+        }
+    }
+}
@@ -1 +1,4 @@
+pub mod audio_clock;
+pub mod locale;
+pub mod notice;
 pub mod snapshot;
@@ -4,9 +4,14 @@
 //! to the render thread. This decouples game logic from rendering.
 
 use crate::input::events::{EditMode, EditorTarget};
-use crate::models::engine::NoteData;
+use crate::models::engine::{NoteData, ScrollMap};
+use crate::models::ghost::GhostDelta;
+use crate::models::settings::LaneCover;
 use crate::models::stats::{HitStats, Judgement};
 use crate::state::{GameResultData, MenuState};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
 use std::time::Instant;
 
 /// High-level render state representing the current game mode.
@@ -52,11 +57,17 @@ pub struct GameplaySnapshot {
     pub rate: f64,
     /// Scroll speed in milliseconds.
     pub scroll_speed: f64,
+    /// Scroll positions from BPM scaling (mmod) and SV; `None` for constant scroll.
+    pub scroll_map: Option<Arc<ScrollMap>>,
+    /// Music of the chart; `None` when the play is silent.
+    pub audio_path: Option<Arc<Path>>,
 
     /// Notes currently visible on screen.
-    pub visible_notes: Vec<NoteData>,
+    pub visible_notes: NoteWindow,
     /// Per-column key held state.
     pub keys_held: Vec<bool>,
+    /// Chart times (ms) of the key presses of the last second, oldest first.
+    pub recent_presses: Vec<f64>,
 
     /// Current score.
     pub score: u32,
@@ -64,8 +75,8 @@ pub struct GameplaySnapshot {
     pub accuracy: f64,
     /// Current combo.
     pub combo: u32,
-    /// Hit statistics.
-    pub hit_stats: HitStats,
+    /// Hit statistics, shared with the previous snapshots until they change.
+    pub hit_stats: Arc<HitStats>,
     /// Number of remaining notes.
     pub remaining_notes: usize,
 
@@ -73,14 +84,137 @@ pub struct GameplaySnapshot {
     pub last_hit_judgement: Option<Judgement>,
     /// Last hit timing offset in ms.
     pub last_hit_timing: Option<f64>,
+    /// Offsets of the latest judged hits in ms, oldest first.
+    pub recent_hit_offsets: Vec<f64>,
 
     /// Current notes per second.
     pub nps: f64,
 
     /// Whether practice mode is enabled.
     pub practice_mode: bool,
+    /// Whether the engine is playing the chart itself.
+    pub autoplay: bool,
+    /// Sudden / hidden cover heights drawn over the notes.
+    pub lane_cover: LaneCover,
+    /// Lane of each chart column (empty = chart order); receptors and notes
+    /// keep the skin of their chart column.
+    pub column_order: Vec<usize>,
+    /// Difference with the raced replay, when racing one.
+    pub ghost: Option<GhostDelta>,
+    /// Difference with the personal best on this map and rate, if any.
+    pub personal_best: Option<GhostDelta>,
     /// Timestamps of placed checkpoints.
     pub checkpoints: Vec<f64>,
     /// Total map duration (for progress graph).
     pub map_duration: f64,
+    /// Remaining HP while playing a course, `None` otherwise.
+    pub health: Option<f64>,
+    /// Timing diagnostics, only filled while the debug overlay is shown.
+    pub debug: Option<Box<DebugOverlayStats>>,
+    /// Opponent playfield of a versus race, drawn on the right half.
+    pub versus: Option<Box<GameplaySnapshot>>,
+}
+
+/// Notes that may be on screen, without copying them every tick.
+///
+/// The chart is shared with the renderer once per play; a snapshot only
+/// carries the range of it in view and the notes of that range whose state
+/// the play changed since (hit, held, burst hits). The render thread
+/// rebuilds the list with [`NoteWindow::collect_into`] into a buffer it
+/// keeps, so neither side allocates per note.
+#[derive(Clone, Debug, Default)]
+pub struct NoteWindow {
+    /// The chart as it was when the play started.
+    pub chart: Arc<[NoteData]>,
+    /// Indices of `chart` in view.
+    pub range: Range<usize>,
+    /// Current version of the notes of `range` that differ from `chart`,
+    /// by ascending index.
+    pub changed: Vec<(usize, NoteData)>,
+    /// Notes with a duration that ended before this chart time (ms) are
+    /// left out.
+    pub min_end_ms: f64,
+}
+
+impl NoteWindow {
+    /// Window showing all of `notes` (for mock data and tests).
+    pub fn from_notes(notes: Vec<NoteData>) -> Self {
+        Self {
+            range: 0..notes.len(),
+            chart: notes.into(),
+            changed: Vec::new(),
+            min_end_ms: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Writes the notes to draw into `out`, hit notes left out.
+    pub fn collect_into(&self, out: &mut Vec<NoteData>) {
+        out.clear();
+        let Some(base) = self.chart.get(self.range.clone()) else {
+            return;
+        };
+        let mut changed = self.changed.iter().peekable();
+        for (index, note) in self.range.clone().zip(base) {
+            let note = match changed.peek() {
+                Some((changed_index, current)) if *changed_index == index => {
+                    changed.next();
+                    current
+                }
+                _ => note,
+            };
+            if note.hit || (note.has_duration() && note.end_time_ms() <= self.min_end_ms) {
+                continue;
+            }
+            out.push(note.clone());
+        }
+    }
+}
+
+/// Real-time timing diagnostics for the debug overlay (F9).
+#[derive(Clone, Debug, Default)]
+pub struct DebugOverlayStats {
+    /// Latency of the last judged key press (window event to judgement), in ms.
+    pub input_latency_ms: Option<f64>,
+    /// Rolling average of recent key press latencies, in ms.
+    pub mean_input_latency_ms: Option<f64>,
+    /// Last measured audio clock drift, in ms.
+    pub last_drift_ms: f64,
+    /// Largest audio clock drift so far, in ms.
+    pub max_drift_ms: f64,
+    /// Logic thread ticks per second.
+    pub logic_tps: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::engine::NoteType;
+
+    #[test]
+    fn note_window_applies_changes_and_drops_hit_and_ended_notes() {
+        let chart = vec![
+            NoteData::tap(1000.0, 0),
+            NoteData::hold(1100.0, 1, 200.0),
+            NoteData::tap(1200.0, 2),
+            NoteData::hold(500.0, 3, 100.0),
+        ];
+        let mut hit = chart[0].clone();
+        hit.hit = true;
+        let mut held = chart[1].clone();
+        held.note_type = NoteType::Hold {
+            duration_ms: 200.0,
+            start_time: Some(1100.0),
+            is_held: true,
+        };
+        let window = NoteWindow {
+            chart: chart.clone().into(),
+            range: 0..4,
+            changed: vec![(0, hit), (1, held.clone())],
+            min_end_ms: 900.0,
+        };
+
+        let mut notes = vec![NoteData::tap(0.0, 0)];
+        window.collect_into(&mut notes);
+        assert_eq!(notes, vec![held, chart[2].clone()]);
+    }
 }
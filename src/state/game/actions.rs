@@ -4,8 +4,11 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 use super::GameEngine;
 use crate::database::SaveReplayCommand;
-use crate::models::replay::simulate_replay;
-use crate::models::settings::HitWindowMode;
+use crate::models::autosave::Autosave;
+use crate::models::engine::NoteData;
+use crate::models::replay::{ReplayResult, simulate_replay};
+use crate::models::settings::SettingsState;
+use crate::models::tournament::SignedReplay;
 use crate::shared::snapshot::GameplaySnapshot;
 use crate::state::GameResultData;
 use crate::state::traits::{Snapshot, Transition, Update, UpdateContext};
@@ -28,51 +31,99 @@ impl Update for GameEngine {
 
         // Check if game is finished
         if !self.is_finished() {
+            self.autosave_if_due();
             return None;
         }
+        Autosave::remove();
 
         // Game finished - build results and save replay
         let chart = self.get_chart();
         let replay_result = simulate_replay(&self.replay_data, &chart, &self.hit_window);
-        let accuracy = replay_result.accuracy;
 
-        // Save replay to database
-        if let Some(payload) = build_replay_payload(self, accuracy) {
+        // Save replay to database (a course stage cut short by HP is not a full
+        // play, and autoplay or a replay is not the player's)
+        if !self.is_failed()
+            && !self.plays_itself()
+            && let Some(payload) = build_replay_payload(self, &replay_result)
+        {
+            if ctx.settings.tournament.enabled {
+                save_signed_replay(&payload, ctx.settings.tournament.signing_key.as_deref());
+            }
             ctx.db_manager.save_replay(payload);
         }
 
-        // Format judge text from settings
-        let judge_text =
-            format_hit_window_text(ctx.settings.hit_window_mode, ctx.settings.hit_window_value);
+        let drift = &self.drift_stats;
+        log::info!(
+            "GAME: Audio drift - mean {:.2}ms, max {:.1}ms over {} ticks",
+            drift.mean_abs_drift_ms(),
+            drift.max_abs_drift_ms,
+            drift.samples
+        );
 
-        // Build result data
-        let result = GameResultData {
+        Some(Transition::ToResult(
+            self.build_result(replay_result, ctx.settings),
+        ))
+    }
+}
+
+impl GameEngine {
+    /// Results of a run quit before the end, judged up to the quit point.
+    /// They are marked incomplete and never saved. `None` when nothing has
+    /// been judged yet.
+    pub fn partial_result(&self, settings: &SettingsState) -> Option<GameResultData> {
+        // Notes still ahead of the receptors (or being held) are left out
+        // rather than counted as misses.
+        let chart: Vec<NoteData> = self
+            .get_chart()
+            .into_iter()
+            .filter(|n| n.hit && n.is_judged())
+            .collect();
+        if chart.is_empty() {
+            return None;
+        }
+        log::info!(
+            "GAME: Quit at {:.0}ms, {} of {} notes judged",
+            self.get_time(),
+            chart.len(),
+            self.judged_notes
+        );
+
+        let replay_result = simulate_replay(&self.replay_data, &chart, &self.hit_window);
+        let mut result = self.build_result(replay_result, settings);
+        result.incomplete = true;
+        Some(result)
+    }
+
+    fn build_result(
+        &self,
+        replay_result: ReplayResult,
+        settings: &SettingsState,
+    ) -> GameResultData {
+        GameResultData {
             hit_stats: replay_result.hit_stats.clone(),
             replay_data: self.replay_data.clone(),
+            accuracy: replay_result.accuracy,
             replay_result,
-            score: self.score,
-            accuracy,
-            max_combo: self.max_combo,
+            score: self.tally.score,
+            max_combo: self.tally.max_combo,
             beatmap_hash: self.beatmap_hash.clone(),
+            map_title: String::new(),
             rate: self.rate,
-            judge_text,
+            judge_text: settings.hit_window_mode.label(settings.hit_window_value),
             show_settings: false,
-        };
-
-        Some(Transition::ToResult(result))
-    }
-}
-
-/// Formats the hit window mode and value as a display string.
-fn format_hit_window_text(mode: HitWindowMode, value: f64) -> String {
-    match mode {
-        HitWindowMode::OsuOD => format!("OD {:.1}", value),
-        HitWindowMode::EtternaJudge => format!("Judge {:.0}", value),
+            drift_stats: Some(Box::new(self.drift_stats.clone())),
+            course: None,
+            personal_best: None,
+            incomplete: false,
+        }
     }
 }
 
 /// Converts gameplay stats into a DB command for replay persistence.
-fn build_replay_payload(engine: &GameEngine, accuracy: f64) -> Option<SaveReplayCommand> {
+fn build_replay_payload(
+    engine: &GameEngine,
+    replay_result: &ReplayResult,
+) -> Option<SaveReplayCommand> {
     let hash = match engine.beatmap_hash.clone() {
         Some(h) => h,
         None => {
@@ -89,10 +140,31 @@ fn build_replay_payload(engine: &GameEngine, accuracy: f64) -> Option<SaveReplay
     Some(SaveReplayCommand {
         beatmap_hash: hash,
         timestamp,
-        score: engine.score.min(i32::MAX as u32) as i32,
-        accuracy,
-        max_combo: engine.max_combo.min(i32::MAX as u32) as i32,
+        score: engine.tally.score.min(i32::MAX as u32) as i32,
+        accuracy: replay_result.accuracy,
+        max_combo: engine.tally.max_combo.min(i32::MAX as u32) as i32,
         rate: engine.rate,
         data: engine.replay_data.clone(),
+        columns: replay_result.column_stats(),
     })
 }
+
+/// Writes the tournament copy of a finished play.
+fn save_signed_replay(payload: &SaveReplayCommand, key: Option<&[u8]>) {
+    let Some(key) = key else {
+        log::error!("REPLAY: No signing key, enter the organizer password again");
+        return;
+    };
+    let signed = SignedReplay {
+        beatmap_hash: payload.beatmap_hash.clone(),
+        timestamp: payload.timestamp,
+        score: payload.score,
+        accuracy: payload.accuracy,
+        max_combo: payload.max_combo,
+        replay: payload.data.clone(),
+    };
+    match signed.save(key) {
+        Ok(path) => log::info!("REPLAY: Signed replay written to {:?}", path),
+        Err(e) => log::error!("REPLAY: Could not write the signed replay: {}", e),
+    }
+}
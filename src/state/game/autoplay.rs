@@ -0,0 +1,95 @@
+//! Autoplay - synthesizes perfect inputs from the chart (skin previewing)
+
+use super::GameEngine;
+use crate::input::events::GameAction;
+use crate::models::engine::note::NoteType;
+
+/// How long autoplay keeps a key down after a tap, so receptors light up.
+const AUTOPLAY_TAP_HOLD_MS: f64 = 40.0;
+
+impl GameEngine {
+    /// Turns autoplay on or off. Keys still held by autoplay are released.
+    ///
+    /// Plays with autoplay are never saved.
+    pub fn set_autoplay(&mut self, enabled: bool) {
+        self.autoplay = enabled;
+        if !enabled {
+            for column in 0..self.autoplay_release_at.len() {
+                if self.autoplay_release_at[column].take().is_some() {
+                    self.handle_input(GameAction::Release {
+                        column,
+                        timestamp: None,
+                    });
+                }
+            }
+        }
+        log::info!("AUTOPLAY: {}", if enabled { "Enabled" } else { "Disabled" });
+    }
+
+    /// Presses every note that reached the receptors and releases the keys
+    /// whose note is done. Called each tick before the miss handling.
+    pub(crate) fn update_autoplay(&mut self) {
+        let now = self.audio_clock;
+
+        for column in 0..self.autoplay_release_at.len() {
+            if let Some(release_time) = self.autoplay_release_at[column]
+                && release_time <= now
+            {
+                self.autoplay_release_at[column] = None;
+                self.input_at(
+                    release_time,
+                    GameAction::Release {
+                        column,
+                        timestamp: None,
+                    },
+                );
+            }
+        }
+
+        // (press time, column, release time)
+        let mut presses = Vec::new();
+        for note in self.chart.iter().skip(self.head_index) {
+            if note.timestamp_ms > now {
+                break;
+            }
+            if note.hit || !note.should_hit() {
+                continue;
+            }
+            match note.note_type {
+                NoteType::Tap => presses.push((
+                    note.timestamp_ms,
+                    note.column,
+                    note.timestamp_ms + AUTOPLAY_TAP_HOLD_MS,
+                )),
+                NoteType::Hold {
+                    start_time: None, ..
+                } => presses.push((note.timestamp_ms, note.column, note.end_time_ms())),
+                // One hit per tick until the burst is complete.
+                NoteType::Burst { .. } => presses.push((now, note.column, now)),
+                _ => {}
+            }
+        }
+
+        for (press_time, column, release_time) in presses {
+            self.input_at(
+                press_time,
+                GameAction::Hit {
+                    column,
+                    timestamp: None,
+                },
+            );
+            if let Some(slot) = self.autoplay_release_at.get_mut(column) {
+                *slot = Some(slot.map_or(release_time, |t| t.max(release_time)));
+            }
+        }
+    }
+
+    /// Feeds an input as if it happened at `time_ms`, so judgements and the
+    /// replay get the exact note timing instead of the tick's.
+    pub(super) fn input_at(&mut self, time_ms: f64, action: GameAction) {
+        let clock = self.audio_clock;
+        self.audio_clock = time_ms;
+        self.handle_input(action);
+        self.audio_clock = clock;
+    }
+}
@@ -0,0 +1,38 @@
+//! Periodic copy of the play in progress, recovered after a crash.
+
+use super::GameEngine;
+use crate::models::autosave::{AUTOSAVE_INTERVAL, Autosave};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+impl GameEngine {
+    /// Writes the inputs so far to the autosave once [`AUTOSAVE_INTERVAL`]
+    /// has passed since the last write. Plays that would not be saved
+    /// (practice, autoplay, replays, debug charts) are skipped.
+    pub(crate) fn autosave_if_due(&mut self) {
+        if !self.has_audio
+            || self.practice_mode
+            || self.plays_itself()
+            || self.replay_data.inputs.is_empty()
+            || self.last_autosave.elapsed() < AUTOSAVE_INTERVAL
+        {
+            return;
+        }
+        let Some(beatmap_hash) = self.beatmap_hash.clone() else {
+            return;
+        };
+        self.last_autosave = Instant::now();
+
+        let autosave = Autosave {
+            beatmap_hash,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs() as i64,
+            reached_ms: self.audio_clock,
+            replay: self.replay_data.clone(),
+        };
+        if let Err(e) = autosave.write() {
+            log::warn!("REPLAY: Could not autosave the play: {}", e);
+        }
+    }
+}
@@ -0,0 +1,78 @@
+//! Debug counters for the latency overlay - input latency and logic tick rate
+
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use super::GameEngine;
+use crate::shared::snapshot::DebugOverlayStats;
+
+/// Number of latency samples kept for the rolling average.
+const LATENCY_WINDOW: usize = 64;
+
+/// Timing measurements shown by the debug overlay.
+#[derive(Clone, Debug)]
+pub struct DebugCounters {
+    /// Recent key-press-to-judgement latencies in ms (newest last).
+    latencies_ms: VecDeque<f64>,
+    /// Logic ticks counted since `window_start`.
+    ticks: u32,
+    window_start: Instant,
+    /// Logic ticks per second over the last full second.
+    tps: f64,
+}
+
+impl DebugCounters {
+    pub fn new() -> Self {
+        Self {
+            latencies_ms: VecDeque::with_capacity(LATENCY_WINDOW),
+            ticks: 0,
+            window_start: Instant::now(),
+            tps: 0.0,
+        }
+    }
+
+    /// Records the latency of one judged key press.
+    pub fn record_latency(&mut self, latency_ms: f64) {
+        if self.latencies_ms.len() == LATENCY_WINDOW {
+            self.latencies_ms.pop_front();
+        }
+        self.latencies_ms.push_back(latency_ms);
+    }
+
+    /// Counts one logic tick and refreshes the TPS once per second.
+    pub fn tick(&mut self) {
+        self.ticks += 1;
+        let elapsed = self.window_start.elapsed().as_secs_f64();
+        if elapsed >= 1.0 {
+            self.tps = self.ticks as f64 / elapsed;
+            self.ticks = 0;
+            self.window_start = Instant::now();
+        }
+    }
+}
+
+impl Default for DebugCounters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GameEngine {
+    /// Gathers the values displayed by the debug overlay.
+    pub fn debug_overlay_stats(&self) -> DebugOverlayStats {
+        let latencies = &self.debug_counters.latencies_ms;
+        let mean_input_latency_ms = if latencies.is_empty() {
+            None
+        } else {
+            Some(latencies.iter().sum::<f64>() / latencies.len() as f64)
+        };
+
+        DebugOverlayStats {
+            input_latency_ms: latencies.back().copied(),
+            mean_input_latency_ms,
+            last_drift_ms: self.drift_stats.last_drift_ms,
+            max_drift_ms: self.drift_stats.max_abs_drift_ms,
+            logic_tps: self.debug_counters.tps,
+        }
+    }
+}
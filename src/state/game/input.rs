@@ -1,36 +1,45 @@
 //! Input handling for GameEngine - process_hit, process_release, handle_input
 
 use super::GameEngine;
+use super::practice::PRACTICE_RATE_STEP;
 use crate::input::events::GameAction;
-use crate::models::engine::note::NoteType;
+use crate::models::judge::{self, Press};
 use crate::models::stats::Judgement;
+use std::time::Instant;
+
+/// Furthest an input is moved from the tick clock (a few ticks), so a
+/// stalled logic thread cannot push it anywhere absurd.
+const MAX_INPUT_SHIFT_MS: f64 = 25.0;
 
 impl GameEngine {
     /// Handles a gameplay input action.
     pub fn handle_input(&mut self, action: GameAction) {
         match action {
-            GameAction::Hit { column } => {
+            GameAction::Hit { column, timestamp } => {
                 if column < self.keys_held.len() {
                     self.keys_held[column] = true;
                 }
+                let time = self.input_time(timestamp);
 
                 // Record the raw PRESS input in the replay
-                self.replay_data.add_press(self.audio_clock, column);
+                self.replay_data.add_press(time, self.chart_column(column));
 
                 // Record input timestamp for NPS calculation
-                self.input_timestamps.push_back(self.audio_clock);
-                self.process_hit(column);
+                self.input_timestamps.push_back(time);
+                self.process_hit(column, time);
             }
-            GameAction::Release { column } => {
+            GameAction::Release { column, timestamp } => {
                 if column < self.keys_held.len() {
                     self.keys_held[column] = false;
                 }
+                let time = self.input_time(timestamp);
 
                 // Record the raw RELEASE input in the replay
-                self.replay_data.add_release(self.audio_clock, column);
+                self.replay_data
+                    .add_release(time, self.chart_column(column));
 
                 // Check if releasing a hold note
-                self.process_release(column);
+                self.process_release(column, time);
             }
             GameAction::TogglePause => { /* TODO */ }
             GameAction::PracticeCheckpoint => {
@@ -43,142 +52,98 @@ impl GameEngine {
                     self.goto_checkpoint();
                 }
             }
+            // Same keys as the song select rate controls.
+            GameAction::TabNext if self.practice_mode => {
+                self.adjust_practice_rate(PRACTICE_RATE_STEP);
+            }
+            GameAction::TabPrev if self.practice_mode => {
+                self.adjust_practice_rate(-PRACTICE_RATE_STEP);
+            }
             _ => {}
         }
     }
 
-    /// Processes a hit input on the given column.
-    ///
-    /// Finds the closest unhit note within the hit window and applies
-    /// the appropriate judgement based on note type.
-    pub(crate) fn process_hit(&mut self, column: usize) {
-        let current_time = self.audio_clock;
-        let mut best_note_idx = None;
-        let mut min_diff = f64::MAX;
-        let search_limit = current_time + self.hit_window.miss_ms;
+    /// Audio time an input happened at. Inputs stamped by the input thread
+    /// get the clock of the last tick moved by the wall time elapsed since
+    /// that tick, instead of falling into the tick's 5 ms bucket.
+    fn input_time(&self, timestamp: Option<Instant>) -> f64 {
+        let Some(timestamp) = timestamp else {
+            return self.audio_clock;
+        };
+        let shift_ms = if timestamp >= self.clock_updated_at {
+            timestamp
+                .duration_since(self.clock_updated_at)
+                .as_secs_f64()
+                * 1000.0
+        } else {
+            -self
+                .clock_updated_at
+                .duration_since(timestamp)
+                .as_secs_f64()
+                * 1000.0
+        };
+        self.audio_clock + shift_ms.clamp(-MAX_INPUT_SHIFT_MS, MAX_INPUT_SHIFT_MS) * self.rate
+    }
 
-        // Find the best matching note (immutable borrow)
-        for (i, note) in self.chart.iter().enumerate().skip(self.head_index) {
-            if note.timestamp_ms > search_limit {
-                break;
-            }
-            if note.column == column && !note.hit {
-                let diff = (note.timestamp_ms - current_time).abs();
-                if diff <= self.hit_window.miss_ms && diff < min_diff {
-                    min_diff = diff;
-                    best_note_idx = Some(i);
-                }
-            }
-        }
+    /// Processes a hit input on the given column, at `current_time`.
+    ///
+    /// Finds the closest unhit note within the hit window and applies the
+    /// judging rules of its note type.
+    pub(crate) fn process_hit(&mut self, column: usize, current_time: f64) {
+        let best_note_idx = judge::find_note(
+            &self.chart,
+            self.head_index,
+            column,
+            current_time,
+            &self.hit_window,
+            |i| !self.chart[i].hit && self.chart[i].is_judged(),
+        );
 
-        // Apply judgement based on note type
         if let Some(idx) = best_note_idx {
             let diff = self.chart[idx].timestamp_ms - current_time;
-
-            match &mut self.chart[idx].note_type {
-                NoteType::Tap => {
-                    let (judgement, _) = self.hit_window.judge(diff);
-                    self.chart[idx].hit = true;
-                    self.last_hit_timing = Some(diff);
+            match judge::press(&mut self.chart[idx], current_time, &self.hit_window) {
+                Press::Hit(judgement) => {
+                    self.record_hit_offset(diff);
                     self.last_hit_judgement = Some(judgement);
-                    self.apply_judgement(judgement);
+                    self.apply_judgement(column, judgement);
                 }
-
-                NoteType::Hold {
-                    start_time,
-                    is_held,
-                    ..
-                } => {
-                    // Start holding - judgement comes when hold is complete
-                    let (judgement, _) = self.hit_window.judge(diff);
-                    *start_time = Some(current_time);
-                    *is_held = true;
-                    self.last_hit_timing = Some(diff);
+                Press::HoldStart(judgement) => {
+                    // Judged when the hold ends: the press is only shown.
+                    self.record_hit_offset(diff);
                     self.last_hit_judgement = Some(judgement);
-                    // Don't mark as hit yet - wait for release/completion
                 }
-
-                NoteType::Mine => {
-                    // Hit a mine = bad!
-                    self.chart[idx].hit = true;
+                Press::Mine => {
                     self.last_hit_timing = Some(diff);
                     self.last_hit_judgement = Some(Judgement::Miss);
-                    self.apply_judgement(Judgement::Miss);
-                }
-
-                NoteType::Burst {
-                    current_hits,
-                    required_hits,
-                    ..
-                } => {
-                    // Increment hit count
-                    *current_hits += 1;
-                    if *current_hits >= *required_hits {
-                        // Burst complete!
-                        self.chart[idx].hit = true;
-                        let (judgement, _) = self.hit_window.judge(diff);
-                        self.last_hit_timing = Some(diff);
-                        self.last_hit_judgement = Some(judgement);
-                        self.apply_judgement(judgement);
-                    }
+                    self.apply_judgement(column, Judgement::Miss);
                 }
+                Press::BurstStep => {}
             }
         } else {
             self.last_hit_timing = None;
             self.last_hit_judgement = Some(Judgement::GhostTap);
-            self.apply_judgement(Judgement::GhostTap);
+            self.apply_judgement(column, Judgement::GhostTap);
         }
     }
 
-    /// Processes a release input on the given column (for hold notes).
-    pub(crate) fn process_release(&mut self, column: usize) {
-        let current_time = self.audio_clock;
-
-        // Find active hold in this column
-        for note in self.chart.iter_mut().skip(self.head_index) {
-            if note.column != column || note.hit {
-                continue;
-            }
-
-            if let NoteType::Hold {
-                duration_ms,
-                start_time: Some(start),
-                is_held,
-                ..
-            } = &mut note.note_type
-            {
-                if !*is_held {
-                    continue;
-                }
-
-                let end_time = note.timestamp_ms + *duration_ms;
-                let hold_duration = current_time - *start;
-                let expected_duration = end_time - note.timestamp_ms;
-
-                *is_held = false;
-                note.hit = true;
-
-                // Calculate how well they held (percentage of required duration)
-                let hold_ratio = hold_duration / expected_duration;
-
-                let judgement = if hold_ratio >= 0.9 {
-                    Judgement::Marv
-                } else if hold_ratio >= 0.8 {
-                    Judgement::Perfect
-                } else if hold_ratio >= 0.6 {
-                    Judgement::Great
-                } else if hold_ratio >= 0.4 {
-                    Judgement::Good
-                } else if hold_ratio >= 0.2 {
-                    Judgement::Bad
-                } else {
-                    Judgement::Miss
-                };
+    /// Stores the offset of a judged hit, for the hit error display and the
+    /// early/late trend.
+    fn record_hit_offset(&mut self, diff: f64) {
+        self.last_hit_timing = Some(diff);
+        self.recent_hit_offsets.push_back(diff);
+        if self.recent_hit_offsets.len() > Self::RECENT_HIT_OFFSETS {
+            self.recent_hit_offsets.pop_front();
+        }
+    }
 
-                self.last_hit_judgement = Some(judgement);
-                self.apply_judgement(judgement);
-                break;
-            }
+    /// Processes a release input on the given column (for hold notes), at
+    /// `current_time`.
+    pub(crate) fn process_release(&mut self, column: usize, current_time: f64) {
+        if let Some((_, judgement)) =
+            judge::release(&mut self.chart, self.head_index, column, current_time)
+        {
+            self.last_hit_judgement = Some(judgement);
+            self.apply_judgement(column, judgement);
         }
     }
 }
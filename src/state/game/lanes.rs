@@ -0,0 +1,41 @@
+//! Custom lane order - chart columns played on other lanes.
+
+use super::GameEngine;
+use crate::models::engine::NoteData;
+
+impl GameEngine {
+    /// Moves chart column `c` to lane `order[c]`. Call once, right after the
+    /// chart is loaded.
+    ///
+    /// Replays keep recording chart columns, so they can still be compared
+    /// with plays made under another order.
+    pub fn set_column_order(&mut self, order: Vec<usize>) {
+        for note in &mut self.chart {
+            if let Some(&lane) = order.get(note.column) {
+                note.column = lane;
+            }
+        }
+        log::info!("GAME: Lane order {:?}", order);
+        self.column_order = order;
+        self.snapshot_cache.chart_changed();
+    }
+
+    /// Chart column played on `lane`.
+    pub(crate) fn chart_column(&self, lane: usize) -> usize {
+        self.column_order
+            .iter()
+            .position(|&l| l == lane)
+            .unwrap_or(lane)
+    }
+
+    /// The chart with its original columns, as replays are recorded.
+    pub(crate) fn chart_in_chart_order(&self) -> Vec<NoteData> {
+        self.chart
+            .iter()
+            .map(|note| NoteData {
+                column: self.chart_column(note.column),
+                ..note.clone()
+            })
+            .collect()
+    }
+}
@@ -5,24 +5,43 @@
 //! - Score and combo tracking
 //! - Audio synchronization
 //! - Practice mode with checkpoints
+//! - Autoplay (skin previewing)
+//! - Versus races against a replay
 
+mod autoplay;
+mod autosave;
+mod debug;
 mod input;
+mod lanes;
 mod notes;
 mod practice;
+#[cfg(test)]
+mod simulation;
 mod snapshot;
+mod versus;
 
 pub mod actions;
 
+pub use debug::DebugCounters;
+use snapshot::SnapshotCache;
+
+use crate::database::chart_storage;
 use crate::input::events::GameAction;
 use crate::logic::audio::AudioManager;
-use crate::models::engine::{HitWindow, NUM_COLUMNS, NoteData, load_map};
+use crate::models::engine::{
+    HitWindow, MapLoadError, NUM_COLUMNS, NoteData, ScrollMap, TimingData, load_map,
+};
+use crate::models::ghost::GhostRun;
+use crate::models::judge::Tally;
 use crate::models::replay::{CHECKPOINT_MIN_INTERVAL_MS, ReplayData};
-use crate::models::settings::HitWindowMode;
-use crate::models::stats::{HitStats, Judgement};
+use crate::models::settings::{ChartRules, HitWindowMode, LaneCover, ScrollSpeedMode};
+use crate::models::stats::{DriftStats, Judgement};
 use crate::shared::snapshot::GameplaySnapshot;
 use crate::system::bus::SystemBus;
 use std::collections::VecDeque;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
 
 /// Offset applied when retrying from a checkpoint (in ms).
 /// The player starts 1 second before the checkpoint to prepare.
@@ -33,11 +52,7 @@ pub(crate) const CHECKPOINT_RETRY_OFFSET_MS: f64 = 1000.0;
 pub(crate) struct CheckpointState {
     pub timestamp_ms: f64,
     pub head_index: usize,
-    pub score: u32,
-    pub combo: u32,
-    pub max_combo: u32,
-    pub hit_stats: HitStats,
-    pub notes_passed: u32,
+    pub tally: Tally,
     /// Hit state of each note at checkpoint time.
     pub note_hit_states: Vec<bool>,
 }
@@ -46,19 +61,13 @@ pub(crate) struct CheckpointState {
 pub struct GameEngine {
     /// The chart data (all notes in the map).
     pub chart: Vec<NoteData>,
+    /// Number of notes that take part in judging (fake notes excluded).
+    pub judged_notes: usize,
     /// Index of the first unhit note to check.
     pub head_index: usize,
 
-    /// Current score.
-    pub score: u32,
-    /// Current combo count.
-    pub combo: u32,
-    /// Maximum combo achieved.
-    pub max_combo: u32,
-    /// Hit statistics (marv, perfect, etc.).
-    pub hit_stats: HitStats,
-    /// Number of notes that have been judged.
-    pub notes_passed: u32,
+    /// Score, combo and hit statistics so far.
+    pub tally: Tally,
 
     /// Currently held keys per column.
     pub keys_held: Vec<bool>,
@@ -66,18 +75,32 @@ pub struct GameEngine {
     pub last_hit_timing: Option<f64>,
     /// Judgement of the last hit.
     pub last_hit_judgement: Option<Judgement>,
+    /// Offsets of the latest judged hits, oldest first (early/late trend).
+    pub(crate) recent_hit_offsets: VecDeque<f64>,
 
     /// Audio manager for music playback.
     pub audio_manager: AudioManager,
-    /// Smoothed audio clock in milliseconds.
+    /// Game clock in milliseconds: the audible position of the music while
+    /// it plays.
     pub audio_clock: f64,
+    /// When `audio_clock` was last advanced, to place timestamped inputs
+    /// between two ticks.
+    pub(crate) clock_updated_at: Instant,
+    /// Whether the last tick took the clock from the audio device.
+    pub(crate) following_audio: bool,
     /// Whether audio is loaded (false for debug mode).
     pub(crate) has_audio: bool,
+    /// Music file of the chart (`None` for debug mode).
+    pub(crate) audio_path: Option<Arc<Path>>,
 
     /// Playback rate multiplier.
     pub rate: f64,
     /// Scroll speed in milliseconds (time visible on screen).
     pub scroll_speed_ms: f64,
+    /// BPM changes of the chart.
+    pub timing: TimingData,
+    /// Time to scroll position mapping (mmod / SV); `None` scrolls at a constant speed.
+    pub scroll_map: Option<Arc<ScrollMap>>,
     /// Hit window configuration.
     pub hit_window: HitWindow,
     /// Hit window mode (osu! OD or Etterna judge).
@@ -87,6 +110,8 @@ pub struct GameEngine {
 
     /// Replay data for recording inputs.
     pub replay_data: ReplayData,
+    /// When the inputs were last written to the crash autosave.
+    pub(crate) last_autosave: Instant,
     /// Hash of the beatmap being played.
     pub beatmap_hash: Option<String>,
     /// Whether audio has started playing.
@@ -103,37 +128,72 @@ pub struct GameEngine {
     pub(crate) checkpoint_state: Option<CheckpointState>,
     /// Timestamp of the last checkpoint (for cooldown enforcement).
     pub(crate) last_checkpoint_time: f64,
+
+    /// Leaderboard replay raced against, advanced with the audio clock.
+    pub ghost: Option<GhostRun>,
+    /// Best local replay on this map and rate, raced like the ghost.
+    pub personal_best: Option<GhostRun>,
+    /// Whether the engine plays the chart itself (player input is ignored).
+    pub autoplay: bool,
+    /// Per column, when autoplay lets go of the key it pressed.
+    pub(crate) autoplay_release_at: Vec<Option<f64>>,
+    /// Replay played back instead of the player's input.
+    pub(crate) playback: Option<versus::ReplayFeed>,
+    /// Replay raced on a second playfield.
+    pub(crate) versus: Option<Box<versus::VersusOpponent>>,
+    /// Sudden / hidden cover heights, adjustable while playing.
+    pub lane_cover: LaneCover,
+    /// Lane of each chart column (empty = chart order).
+    pub column_order: Vec<usize>,
+
+    /// Remaining HP in `[0, 1]`; only tracked while playing a course.
+    pub health: Option<f64>,
+    /// Score carried over from previous course stages (display only).
+    pub score_offset: u32,
+
+    /// Drift measurements for this play.
+    pub drift_stats: DriftStats,
+    /// Latency and tick rate counters for the debug overlay.
+    pub debug_counters: DebugCounters,
+    /// Chart and hit stats shared with the render snapshots.
+    pub(crate) snapshot_cache: SnapshotCache,
 }
 
 impl GameEngine {
     /// Pre-roll time before the first note (in ms).
     const PRE_ROLL_MS: f64 = 3000.0;
+    /// Hit offsets kept for the early/late trend (the HUD averages fewer).
+    pub(crate) const RECENT_HIT_OFFSETS: usize = 100;
 
-    /// Creates a new `GameEngine` by loading the map from a file.
-    /// Returns `None` if the map cannot be loaded.
+    /// Creates a new `GameEngine` by loading the map from a file, read with
+    /// `rules`. Returns why the map cannot be played if it fails to load.
     pub fn new(
         bus: &SystemBus,
         map_path: PathBuf,
+        rules: ChartRules,
         rate: f64,
         beatmap_hash: Option<String>,
         hit_window_mode: HitWindowMode,
         hit_window_value: f64,
-    ) -> Option<Self> {
-        match load_map(map_path.clone()) {
-            Ok((audio_path, chart)) => Some(Self::from_cached(
-                bus,
-                chart,
-                audio_path,
-                rate,
-                beatmap_hash,
-                hit_window_mode,
-                hit_window_value,
-            )),
-            Err(e) => {
-                log::error!("ENGINE: Failed to load map {:?}: {}", map_path, e);
-                None
-            }
-        }
+    ) -> Result<Self, MapLoadError> {
+        let loaded = match beatmap_hash.as_deref() {
+            Some(hash) => chart_storage::load_chart(hash, &map_path, rules),
+            None => load_map(map_path.clone(), rules),
+        };
+        let (audio_path, chart, timing) = loaded.inspect_err(|e| {
+            log::error!("ENGINE: Failed to load map {:?}: {}", map_path, e);
+        })?;
+        let mut engine = Self::from_cached(
+            bus,
+            chart,
+            audio_path,
+            rate,
+            beatmap_hash,
+            hit_window_mode,
+            hit_window_value,
+        );
+        engine.timing = timing;
+        Ok(engine)
     }
 
     /// Creates a `GameEngine` from pre-loaded chart and audio path.
@@ -152,30 +212,33 @@ impl GameEngine {
         audio_manager.load_music(&audio_path);
         audio_manager.set_speed(rate as f32);
 
-        let hit_window = match hit_window_mode {
-            HitWindowMode::OsuOD => HitWindow::from_osu_od(hit_window_value),
-            HitWindowMode::EtternaJudge => HitWindow::from_etterna_judge(hit_window_value as u8),
-        };
+        let hit_window = HitWindow::from_mode(hit_window_mode, hit_window_value);
+
+        let judged_notes = chart.iter().filter(|n| n.is_judged()).count();
 
         Self {
             chart,
+            judged_notes,
             head_index: 0,
-            score: 0,
-            combo: 0,
-            max_combo: 0,
-            hit_stats: HitStats::new(),
-            notes_passed: 0,
+            tally: Tally::new(),
             keys_held: vec![false; NUM_COLUMNS],
             last_hit_timing: None,
             last_hit_judgement: None,
+            recent_hit_offsets: VecDeque::new(),
             audio_manager,
             audio_clock: -Self::PRE_ROLL_MS,
+            clock_updated_at: Instant::now(),
+            following_audio: false,
             has_audio: true,
+            audio_path: Some(Arc::from(audio_path.as_path())),
             replay_data: ReplayData::new(rate, hit_window_mode, hit_window_value),
+            last_autosave: Instant::now(),
             beatmap_hash,
             started_audio: false,
             rate,
             scroll_speed_ms: 500.0,
+            timing: TimingData::default(),
+            scroll_map: None,
             hit_window,
             hit_window_mode,
             hit_window_value,
@@ -185,6 +248,19 @@ impl GameEngine {
             practice_mode: false,
             checkpoint_state: None,
             last_checkpoint_time: f64::NEG_INFINITY,
+            ghost: None,
+            personal_best: None,
+            autoplay: false,
+            autoplay_release_at: vec![None; NUM_COLUMNS],
+            playback: None,
+            versus: None,
+            lane_cover: LaneCover::default(),
+            column_order: Vec::new(),
+            health: None,
+            score_offset: 0,
+            drift_stats: DriftStats::default(),
+            debug_counters: DebugCounters::new(),
+            snapshot_cache: SnapshotCache::default(),
         }
     }
 
@@ -198,30 +274,32 @@ impl GameEngine {
         let audio_manager = AudioManager::new(bus);
         // No audio loaded - we'll run in silent mode
 
-        let hit_window = match hit_window_mode {
-            HitWindowMode::OsuOD => HitWindow::from_osu_od(hit_window_value),
-            HitWindowMode::EtternaJudge => HitWindow::from_etterna_judge(hit_window_value as u8),
-        };
+        let hit_window = HitWindow::from_mode(hit_window_mode, hit_window_value);
+        let judged_notes = chart.iter().filter(|n| n.is_judged()).count();
 
         Self {
             chart,
+            judged_notes,
             head_index: 0,
-            score: 0,
-            combo: 0,
-            max_combo: 0,
-            hit_stats: HitStats::new(),
-            notes_passed: 0,
+            tally: Tally::new(),
             keys_held: vec![false; NUM_COLUMNS],
             last_hit_timing: None,
             last_hit_judgement: None,
+            recent_hit_offsets: VecDeque::new(),
             audio_manager,
             audio_clock: -Self::PRE_ROLL_MS,
+            clock_updated_at: Instant::now(),
+            following_audio: false,
             has_audio: false, // Debug mode - no audio
+            audio_path: None,
             replay_data: ReplayData::new(1.0, hit_window_mode, hit_window_value),
+            last_autosave: Instant::now(),
             beatmap_hash: Some("debug_map".to_string()),
             started_audio: true, // No audio, but consider it "started" for gameplay
             rate: 1.0,
             scroll_speed_ms: 500.0,
+            timing: TimingData::default(),
+            scroll_map: None,
             hit_window,
             hit_window_mode,
             hit_window_value,
@@ -231,19 +309,50 @@ impl GameEngine {
             practice_mode: false,
             checkpoint_state: None,
             last_checkpoint_time: f64::NEG_INFINITY,
+            ghost: None,
+            personal_best: None,
+            autoplay: false,
+            autoplay_release_at: vec![None; NUM_COLUMNS],
+            playback: None,
+            versus: None,
+            lane_cover: LaneCover::default(),
+            column_order: Vec::new(),
+            health: None,
+            score_offset: 0,
+            drift_stats: DriftStats::default(),
+            debug_counters: DebugCounters::new(),
+            snapshot_cache: SnapshotCache::default(),
         }
     }
 
+    /// Configures scrolling: constant (cmod) or BPM-scaled (mmod), with or
+    /// without the chart's SV.
+    ///
+    /// Must be called after `timing` is set. Without BPM scaling or SV, notes
+    /// scroll at a constant speed.
+    pub fn configure_scroll(&mut self, mode: ScrollSpeedMode, use_sv: bool) {
+        let reference_bpm = match mode {
+            ScrollSpeedMode::CMod => None,
+            ScrollSpeedMode::MMod => self.timing.dominant_bpm(self.get_map_duration()),
+        };
+        let use_sv = use_sv && !self.timing.sv_changes.is_empty();
+        self.scroll_map = (reference_bpm.is_some() || use_sv)
+            .then(|| Arc::new(ScrollMap::new(&self.timing, reference_bpm, use_sv)));
+    }
+
     /// Updates the game state for one tick.
     ///
     /// This method:
     /// 1. Advances the audio clock
-    /// 2. Synchronizes with the audio device
+    /// 2. Reads the audible position from the audio device
     /// 3. Processes missed notes
     /// 4. Updates NPS tracking
     pub fn update(&mut self, dt_seconds: f64) {
-        // 1. Advance the smoothed clock
+        self.debug_counters.tick();
+
+        // 1. Advance the clock by the tick
         self.audio_clock += dt_seconds * 1000.0 * self.rate;
+        self.clock_updated_at = Instant::now();
 
         if !self.started_audio {
             if self.audio_clock >= 0.0 {
@@ -254,20 +363,27 @@ impl GameEngine {
             }
         }
 
-        // 2. Re-synchronize with the audio device if drifted
-        // Skip sync if audio is seeking (loading in background) or no audio (debug mode)
-        if self.has_audio && !self.audio_manager.is_seeking() {
-            let raw_audio_time = self.audio_manager.get_position_seconds() * 1000.0;
-            let drift = raw_audio_time - self.audio_clock;
-
-            if drift.abs() > 80.0 {
-                self.audio_clock = raw_audio_time;
-            } else if drift.abs() > 5.0 {
-                // Use a much smaller correction factor to avoid "sawtooth" velocity changes
-                // causing visual stutter
-                self.audio_clock += drift * 0.05;
+        // 2. Take the position heard from the audio device while the music
+        // plays. Before its first block, while it seeks, or without audio
+        // (debug mode), the tick-advanced clock stands in.
+        let audible_ms = if self.has_audio {
+            self.audio_manager.audible_ms(self.clock_updated_at)
+        } else {
+            None
+        };
+        if let Some(audible_ms) = audible_ms {
+            // The switch from the tick clock is the device latency, not drift.
+            if self.following_audio {
+                self.drift_stats.record(audible_ms - self.audio_clock);
             }
+            self.audio_clock = audible_ms;
         }
+        self.following_audio = audible_ms.is_some();
+
+        if self.autoplay {
+            self.update_autoplay();
+        }
+        self.update_playback();
 
         let current_time = self.audio_clock;
 
@@ -276,6 +392,33 @@ impl GameEngine {
 
         // 4. Update NPS tracking
         self.update_nps();
+
+        // 5. Keep the ghost at the same point of the map
+        if let Some(ghost) = self.ghost.as_mut() {
+            ghost.advance(current_time);
+        }
+        if let Some(best) = self.personal_best.as_mut() {
+            best.advance(current_time);
+        }
+        self.update_versus();
+    }
+
+    /// Races a previous play: its judgements are replayed as the map goes.
+    pub fn set_ghost(&mut self, replay: &ReplayData) {
+        self.ghost = Some(self.race(replay));
+    }
+
+    /// Compares the run with the player's best replay (HUD delta).
+    pub fn set_personal_best(&mut self, replay: &ReplayData) {
+        self.personal_best = Some(self.race(replay));
+    }
+
+    fn race(&self, replay: &ReplayData) -> GhostRun {
+        if self.column_order.is_empty() {
+            GhostRun::new(replay, &self.chart)
+        } else {
+            GhostRun::new(replay, &self.chart_in_chart_order())
+        }
     }
 
     /// Updates the notes-per-second tracking.
@@ -301,23 +444,36 @@ impl GameEngine {
         self.audio_clock
     }
 
-    /// Returns `true` if the map has finished (2 seconds after last note).
+    /// Returns `true` if the map has finished (2 seconds after last note) or HP ran out.
     pub fn is_finished(&self) -> bool {
-        self.chart
-            .last()
-            .is_none_or(|n| self.audio_clock > n.timestamp_ms + 2000.0)
+        self.is_failed()
+            || self
+                .chart
+                .last()
+                .is_none_or(|n| self.audio_clock > n.timestamp_ms + 2000.0)
+    }
+
+    /// Returns `true` if HP ran out during a course stage.
+    pub fn is_failed(&self) -> bool {
+        self.health.is_some_and(|hp| hp <= 0.0)
     }
 
     /// Updates the hit window configuration.
     pub fn update_hit_window(&mut self, mode: HitWindowMode, value: f64) {
-        self.hit_window = match mode {
-            HitWindowMode::OsuOD => HitWindow::from_osu_od(value),
-            HitWindowMode::EtternaJudge => HitWindow::from_etterna_judge(value as u8),
-        };
+        self.hit_window = HitWindow::from_mode(mode, value);
         self.hit_window_mode = mode;
         self.hit_window_value = value;
     }
 
+    /// Number of columns the chart uses.
+    pub fn key_count(&self) -> usize {
+        self.chart
+            .iter()
+            .map(|note| note.column + 1)
+            .max()
+            .unwrap_or(NUM_COLUMNS)
+    }
+
     /// Returns a copy of the chart (for replay simulation).
     pub fn get_chart(&self) -> Vec<NoteData> {
         self.chart.clone()
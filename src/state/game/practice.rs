@@ -2,6 +2,12 @@
 
 use super::{CHECKPOINT_RETRY_OFFSET_MS, CheckpointState, GameEngine};
 use crate::models::replay::CHECKPOINT_MIN_INTERVAL_MS;
+use std::time::Instant;
+
+/// Rate change per practice rate keypress.
+pub(crate) const PRACTICE_RATE_STEP: f64 = 0.05;
+const PRACTICE_RATE_MIN: f64 = 0.5;
+const PRACTICE_RATE_MAX: f64 = 2.0;
 
 impl GameEngine {
     /// Enables practice mode (called at engine creation).
@@ -33,11 +39,7 @@ impl GameEngine {
         self.checkpoint_state = Some(CheckpointState {
             timestamp_ms: current_time,
             head_index: self.head_index,
-            score: self.score,
-            combo: self.combo,
-            max_combo: self.max_combo,
-            hit_stats: self.hit_stats.clone(),
-            notes_passed: self.notes_passed,
+            tally: self.tally.clone(),
             note_hit_states,
         });
 
@@ -65,10 +67,7 @@ impl GameEngine {
 
         // Restore game state
         self.head_index = state.head_index;
-        self.score = state.score;
-        self.combo = state.combo;
-        self.hit_stats = state.hit_stats;
-        self.notes_passed = state.notes_passed;
+        self.tally = state.tally;
 
         log::info!(
             "PRACTICE: Restoring {} notes state",
@@ -107,6 +106,8 @@ impl GameEngine {
 
         // Seek audio (async)
         self.audio_clock = retry_time;
+        self.clock_updated_at = Instant::now();
+        self.following_audio = false;
         let seek_seconds = retry_time / 1000.0;
         self.audio_manager.seek(seek_seconds as f32);
 
@@ -115,6 +116,7 @@ impl GameEngine {
         // Reset held keys
         self.keys_held.fill(false);
         self.input_timestamps.clear();
+        self.recent_hit_offsets.clear();
         self.current_nps = 0.0;
 
         log::info!(
@@ -125,6 +127,24 @@ impl GameEngine {
         true
     }
 
+    /// Changes the playback rate without leaving the map.
+    ///
+    /// The audio clock is kept in chart time, so only its speed changes: the
+    /// next ticks advance it at the new rate and the audio sink follows. The
+    /// replay takes the new rate too, so the saved run is labelled with the
+    /// rate it ended on like its result.
+    pub fn adjust_practice_rate(&mut self, delta: f64) {
+        let rate = ((self.rate + delta) / PRACTICE_RATE_STEP).round() * PRACTICE_RATE_STEP;
+        let rate = rate.clamp(PRACTICE_RATE_MIN, PRACTICE_RATE_MAX);
+        if (rate - self.rate).abs() < f64::EPSILON {
+            return;
+        }
+        self.rate = rate;
+        self.replay_data.rate = rate;
+        self.audio_manager.set_speed(rate as f32);
+        log::info!("PRACTICE: Rate set to {:.2}x", rate);
+    }
+
     /// Returns the timestamps of all checkpoints for UI display.
     pub fn get_checkpoints(&self) -> &[f64] {
         &self.replay_data.checkpoints
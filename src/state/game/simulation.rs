@@ -0,0 +1,262 @@
+//! Deterministic playback of raw input streams, for regression tests of the
+//! judging.
+//!
+//! Raw events go through the same `InputManager` mapping as in the input
+//! thread, then to a `GameEngine` driven like the logic thread, but on a
+//! virtual clock: ticks are exactly `TICK_MS` apart and every event carries
+//! the instant the input thread would have stamped it with. Running the same
+//! stream twice gives the same judgements, offsets included.
+
+use super::GameEngine;
+use crate::input::events::{InputSource, RawInputEvent};
+use crate::input::manager::InputManager;
+use crate::models::engine::{NUM_COLUMNS, NoteData};
+use crate::models::settings::HitWindowMode;
+use crate::models::stats::{HitStats, Judgement};
+use crate::system::bus::SystemBus;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use winit::event::ElementState;
+use winit::keyboard::KeyCode;
+
+/// Logic tick length, as in the logic thread (200 TPS).
+const TICK_MS: f64 = 5.0;
+
+/// Time simulated after the last note, so its miss is judged.
+const TAIL_MS: f64 = 1000.0;
+
+/// A raw event and the chart time (ms) the input thread received it at.
+#[derive(Debug, Clone, Copy)]
+pub struct TimedInput {
+    pub time_ms: f64,
+    pub event: RawInputEvent,
+}
+
+impl TimedInput {
+    pub fn key(time_ms: f64, key: KeyCode, pressed: bool) -> Self {
+        Self {
+            time_ms,
+            event: RawInputEvent {
+                source: InputSource::Key(key),
+                state: if pressed {
+                    ElementState::Pressed
+                } else {
+                    ElementState::Released
+                },
+            },
+        }
+    }
+}
+
+/// One judgement given by the engine.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JudgementRecord {
+    /// Logic tick it was given on.
+    pub tick: u64,
+    pub judgement: Judgement,
+    /// Hit offset in ms, for judgements given on an input.
+    pub offset_ms: Option<f64>,
+}
+
+/// Plays `inputs` on `chart` with the columns bound to D F J K, the way the
+/// settings bind them, and returns every judgement in order.
+pub fn run(chart: Vec<NoteData>, inputs: &[TimedInput]) -> Vec<JudgementRecord> {
+    play(chart, inputs).1
+}
+
+/// Same as [`run`], also returning the engine at the end of the play.
+fn play(chart: Vec<NoteData>, inputs: &[TimedInput]) -> (GameEngine, Vec<JudgementRecord>) {
+    let end_ms = chart
+        .iter()
+        .map(|note| note.end_time_ms())
+        .fold(0.0, f64::max)
+        + TAIL_MS;
+    let bus = SystemBus::new();
+    let mut engine = GameEngine::from_debug_chart(&bus, chart, HitWindowMode::OsuOD, 8.0);
+    let mut manager = InputManager::with_default_bindings();
+    let keys = ["KeyD", "KeyF", "KeyJ", "KeyK"].map(String::from).to_vec();
+    manager.reload_keybinds(&HashMap::from([(NUM_COLUMNS.to_string(), keys)]));
+
+    // The debug engine plays at 1.0x: chart time maps to wall time one to one.
+    let epoch = Instant::now();
+    let instant_at = |time_ms: f64| {
+        epoch + Duration::from_secs_f64((time_ms + GameEngine::PRE_ROLL_MS) / 1000.0)
+    };
+    engine.clock_updated_at = instant_at(engine.audio_clock);
+
+    let mut inputs = inputs.to_vec();
+    inputs.sort_by(|a, b| a.time_ms.total_cmp(&b.time_ms));
+    let mut pending = inputs.into_iter().peekable();
+
+    let mut records = Vec::new();
+    let mut tick = 0;
+    while engine.audio_clock <= end_ms {
+        // Events received before the next tick are drained first, as the
+        // logic loop does.
+        let next_tick = engine.audio_clock + TICK_MS;
+        while let Some(input) = pending.next_if(|input| input.time_ms <= next_tick) {
+            let Some(action) = manager.process(input.event, instant_at(input.time_ms)) else {
+                continue;
+            };
+            let before = engine.tally.hit_stats.clone();
+            engine.handle_input(action);
+            record(&mut records, tick, &before, &engine, engine.last_hit_timing);
+        }
+
+        let before = engine.tally.hit_stats.clone();
+        engine.update(TICK_MS / 1000.0);
+        engine.clock_updated_at = instant_at(engine.audio_clock);
+        record(&mut records, tick, &before, &engine, None);
+        tick += 1;
+    }
+    (engine, records)
+}
+
+/// Appends the judgements counted since `before`.
+fn record(
+    records: &mut Vec<JudgementRecord>,
+    tick: u64,
+    before: &HitStats,
+    engine: &GameEngine,
+    offset_ms: Option<f64>,
+) {
+    for judgement in Judgement::ALL {
+        let added = count(&engine.tally.hit_stats, judgement) - count(before, judgement);
+        for _ in 0..added {
+            records.push(JudgementRecord {
+                tick,
+                judgement,
+                offset_ms,
+            });
+        }
+    }
+}
+
+fn count(stats: &HitStats, judgement: Judgement) -> u32 {
+    match judgement {
+        Judgement::Marv => stats.marv,
+        Judgement::Perfect => stats.perfect,
+        Judgement::Great => stats.great,
+        Judgement::Good => stats.good,
+        Judgement::Bad => stats.bad,
+        Judgement::Miss => stats.miss,
+        Judgement::GhostTap => stats.ghost_tap,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::judge;
+
+    const KEYS: [KeyCode; 4] = [KeyCode::KeyD, KeyCode::KeyF, KeyCode::KeyJ, KeyCode::KeyK];
+
+    fn stream() -> (Vec<NoteData>, Vec<TimedInput>) {
+        let mut chart = Vec::new();
+        let mut inputs = Vec::new();
+        for i in 0..32 {
+            let time = 1000.0 + i as f64 * 137.0;
+            let column = (i * 7) % 4;
+            chart.push(NoteData::tap(time, column));
+            // Offsets spread over the windows, never on a tick boundary.
+            let offset = ((i * 37) % 90) as f64 - 45.0 + 0.3;
+            inputs.push(TimedInput::key(time + offset, KEYS[column], true));
+            inputs.push(TimedInput::key(time + offset + 40.0, KEYS[column], false));
+        }
+        chart.push(NoteData::hold(6000.0, 1, 500.0));
+        inputs.push(TimedInput::key(6002.5, KEYS[1], true));
+        inputs.push(TimedInput::key(6498.0, KEYS[1], false));
+        // Ghost tap and a missed note.
+        inputs.push(TimedInput::key(7000.0, KEYS[3], true));
+        chart.push(NoteData::tap(7500.0, 2));
+        (chart, inputs)
+    }
+
+    #[test]
+    fn same_stream_same_judgements() {
+        let (chart, inputs) = stream();
+        let first = run(chart.clone(), &inputs);
+        let second = run(chart, &inputs);
+        assert!(first.len() >= 34);
+        assert_eq!(first, second);
+        assert!(first.iter().any(|r| r.judgement == Judgement::GhostTap));
+        assert_eq!(first.last().unwrap().judgement, Judgement::Miss);
+    }
+
+    #[test]
+    fn replay_simulation_judges_like_the_engine() {
+        let chart = vec![
+            NoteData::hold(1000.0, 0, 400.0),
+            NoteData::hold(1200.0, 1, 600.0),
+            NoteData::mine(1300.0, 2),
+            NoteData::tap(1500.0, 3),
+            NoteData::mine(1700.0, 3),
+            NoteData::hold(2000.0, 2, 300.0),
+            NoteData::tap(2600.0, 0),
+        ];
+        let inputs = [
+            // Held to the end.
+            TimedInput::key(1012.0, KEYS[0], true),
+            TimedInput::key(1450.0, KEYS[0], false),
+            // Let go halfway.
+            TimedInput::key(1193.0, KEYS[1], true),
+            TimedInput::key(1520.0, KEYS[1], false),
+            // Mine set off.
+            TimedInput::key(1301.0, KEYS[2], true),
+            TimedInput::key(1340.0, KEYS[2], false),
+            TimedInput::key(1521.0, KEYS[3], true),
+            TimedInput::key(1560.0, KEYS[3], false),
+            // The mine at 1700 is let through, the last hold never pressed
+            // and the last tap missed.
+        ];
+
+        let (engine, _) = play(chart.clone(), &inputs);
+        let replayed = judge::simulate(&chart, &engine.replay_data.inputs, &engine.hit_window);
+
+        let stats = &engine.tally.hit_stats;
+        for judgement in Judgement::ALL {
+            assert_eq!(
+                count(&replayed.hit_stats, judgement),
+                count(stats, judgement),
+                "{:?}",
+                judgement
+            );
+        }
+        assert_eq!(
+            (stats.marv, stats.good, stats.miss),
+            (1, 1, 3),
+            "{:?}",
+            stats
+        );
+        assert_eq!(replayed.score, engine.tally.score);
+        assert_eq!(replayed.max_combo, engine.tally.max_combo);
+    }
+
+    #[test]
+    fn practice_rate_change_reaches_the_replay() {
+        let bus = SystemBus::new();
+        let chart = vec![NoteData::tap(1000.0, 0)];
+        let mut engine = GameEngine::from_debug_chart(&bus, chart, HitWindowMode::OsuOD, 8.0);
+        engine.enable_practice_mode();
+        engine.adjust_practice_rate(0.05);
+        engine.adjust_practice_rate(0.05);
+        assert!((engine.rate - 1.1).abs() < 1e-9, "{}", engine.rate);
+        assert_eq!(engine.replay_data.rate, engine.rate);
+    }
+
+    #[test]
+    fn presses_are_judged_between_ticks() {
+        let chart = vec![NoteData::tap(1000.0, 0), NoteData::tap(1500.0, 1)];
+        let inputs = [
+            TimedInput::key(1002.3, KeyCode::KeyD, true),
+            TimedInput::key(1498.9, KeyCode::KeyF, true),
+        ];
+        let offsets: Vec<f64> = run(chart, &inputs)
+            .iter()
+            .filter_map(|r| r.offset_ms)
+            .collect();
+        assert_eq!(offsets.len(), 2);
+        assert!((offsets[0] - -2.3).abs() < 1e-6, "{:?}", offsets);
+        assert!((offsets[1] - 1.1).abs() < 1e-6, "{:?}", offsets);
+    }
+}
@@ -2,54 +2,141 @@
 
 use super::GameEngine;
 use crate::models::engine::NoteData;
-use crate::shared::snapshot::GameplaySnapshot;
+use crate::models::stats::HitStats;
+use crate::shared::snapshot::{GameplaySnapshot, NoteWindow};
+use std::sync::{Arc, Mutex};
+
+/// Data the snapshots share with the renderer instead of copying it every
+/// tick: the chart, sent again only when its generation changes, and the hit
+/// stats, sent again only when they change.
+#[derive(Default)]
+pub(crate) struct SnapshotCache {
+    /// Bumped whenever the engine chart is replaced or reordered.
+    chart_generation: u64,
+    /// Shared chart and the generation it was copied at.
+    chart: Mutex<Option<(u64, Arc<[NoteData]>)>>,
+    hit_stats: Mutex<Option<Arc<HitStats>>>,
+}
+
+impl SnapshotCache {
+    /// Shares the chart again at the next snapshot. Call after replacing or
+    /// reordering it (lane order): per-note state changes are sent anyway.
+    pub(crate) fn chart_changed(&mut self) {
+        self.chart_generation += 1;
+    }
+
+    fn chart(&self, chart: &[NoteData]) -> Arc<[NoteData]> {
+        let mut shared = self.chart.lock().unwrap_or_else(|e| e.into_inner());
+        match shared.as_ref() {
+            Some((generation, shared)) if *generation == self.chart_generation => shared.clone(),
+            _ => shared
+                .insert((self.chart_generation, Arc::from(chart)))
+                .1
+                .clone(),
+        }
+    }
+
+    fn hit_stats(&self, stats: &HitStats) -> Arc<HitStats> {
+        let mut shared = self.hit_stats.lock().unwrap_or_else(|e| e.into_inner());
+        match shared.as_ref() {
+            Some(shared) if **shared == *stats => shared.clone(),
+            _ => shared.insert(Arc::new(stats.clone())).clone(),
+        }
+    }
+}
 
 impl GameEngine {
     /// Creates a snapshot of the current game state for rendering.
     pub fn get_snapshot(&self) -> GameplaySnapshot {
-        let effective_speed = self.scroll_speed_ms * self.rate;
-        let max_visible_time = self.audio_clock + effective_speed;
-
-        // For notes with duration (Hold/Burst), we need to keep them visible
-        // until their end time has passed, not just their start time
-        let visible_notes: Vec<NoteData> = self
-            .chart
-            .iter()
-            .skip(self.head_index)
-            .take_while(|n| n.timestamp_ms <= max_visible_time + 2000.0)
-            .filter(|n| {
-                if n.hit {
-                    return false;
-                }
-                // For notes with duration, keep visible until end time passes
-                if n.note_type.has_duration() {
-                    // Keep visible if end hasn't passed yet
-                    n.end_time_ms() > self.audio_clock - 100.0
-                } else {
-                    true
-                }
-            })
-            .cloned()
-            .collect();
-
         GameplaySnapshot {
             audio_time: self.audio_clock,
             timestamp: std::time::Instant::now(),
             rate: self.rate,
             scroll_speed: self.scroll_speed_ms,
-            visible_notes,
+            scroll_map: self.scroll_map.clone(),
+            audio_path: self.audio_path.clone(),
+            visible_notes: self.note_window(),
             keys_held: self.keys_held.clone(),
-            score: self.score,
-            accuracy: self.hit_stats.calculate_accuracy(),
-            combo: self.combo,
-            hit_stats: self.hit_stats.clone(),
-            remaining_notes: self.chart.len().saturating_sub(self.notes_passed as usize),
+            recent_presses: self.input_timestamps.iter().copied().collect(),
+            score: self.tally.score + self.score_offset,
+            accuracy: self.tally.hit_stats.calculate_accuracy(),
+            combo: self.tally.combo,
+            hit_stats: self.snapshot_cache.hit_stats(&self.tally.hit_stats),
+            remaining_notes: self
+                .judged_notes
+                .saturating_sub(self.tally.notes_passed as usize),
             last_hit_judgement: self.last_hit_judgement,
             last_hit_timing: self.last_hit_timing,
+            recent_hit_offsets: self.recent_hit_offsets.iter().copied().collect(),
             nps: self.current_nps,
             practice_mode: self.practice_mode,
+            autoplay: self.autoplay,
+            lane_cover: self.lane_cover,
+            column_order: self.column_order.clone(),
+            ghost: self.ghost.as_ref().map(|g| g.delta(&self.tally)),
+            personal_best: self.personal_best.as_ref().map(|pb| pb.delta(&self.tally)),
             checkpoints: self.replay_data.checkpoints.clone(),
             map_duration: self.get_map_duration(),
+            health: self.health,
+            debug: None,
+            versus: self
+                .versus_engine()
+                .map(|opponent| Box::new(opponent.get_snapshot())),
         }
     }
+
+    /// Range of the chart in view and the notes of it changed since the
+    /// chart was shared.
+    fn note_window(&self) -> NoteWindow {
+        let effective_speed = self.scroll_speed_ms * self.rate;
+        // With mmod, the visible window is measured in scroll position, not time.
+        let position_of = |time_ms: f64| match &self.scroll_map {
+            Some(map) => map.position_at(time_ms),
+            None => time_ms,
+        };
+        let max_visible_position = position_of(self.audio_clock) + effective_speed;
+
+        let start = self.head_index.min(self.chart.len());
+        let end = start
+            + self.chart[start..]
+                .iter()
+                .take_while(|n| position_of(n.timestamp_ms) <= max_visible_position + 2000.0)
+                .count();
+
+        let chart = self.snapshot_cache.chart(&self.chart);
+        let changed = (start..end)
+            .filter(|&i| self.chart[i] != chart[i])
+            .map(|i| (i, self.chart[i].clone()))
+            .collect();
+
+        NoteWindow {
+            chart,
+            range: start..end,
+            changed,
+            // For notes with duration (Hold/Burst), keep them visible until
+            // their end time has passed, not just their start time
+            min_end_ms: self.audio_clock - 100.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chart_is_shared_until_its_generation_changes() {
+        let mut cache = SnapshotCache::default();
+        let chart = vec![NoteData::tap(1000.0, 0), NoteData::tap(1500.0, 1)];
+        let first = cache.chart(&chart);
+
+        // Same length, other layout: still the shared copy until bumped.
+        let reordered = vec![NoteData::tap(1000.0, 1), NoteData::tap(1500.0, 0)];
+        assert!(Arc::ptr_eq(&first, &cache.chart(&reordered)));
+
+        cache.chart_changed();
+        let shared = cache.chart(&reordered);
+        assert_eq!(shared[0].column, 1);
+        assert!(Arc::ptr_eq(&shared, &cache.chart(&reordered)));
+    }
 }
@@ -0,0 +1,138 @@
+//! Versus races - replays played on split playfields
+//!
+//! The opponent replay runs on its own engine, without audio, and follows
+//! the clock of the main engine so both sides stay on the same point of the
+//! map. The main engine is either played live or plays a replay back.
+
+use super::GameEngine;
+use crate::input::events::GameAction;
+use crate::models::replay::{ReplayData, ReplayInput};
+use crate::system::bus::SystemBus;
+
+/// Inputs of a replay, fed to an engine as the clock reaches them.
+#[derive(Debug, Clone)]
+pub struct ReplayFeed {
+    inputs: Vec<ReplayInput>,
+    cursor: usize,
+}
+
+impl ReplayFeed {
+    pub fn new(replay: &ReplayData) -> Self {
+        let mut inputs = replay.inputs.clone();
+        inputs.sort_by_key(|input| input.timestamp_ms);
+        Self { inputs, cursor: 0 }
+    }
+
+    /// Inputs up to `time_ms` not fed yet: `(time, chart column, is_press)`.
+    fn due(&mut self, time_ms: f64) -> Vec<(f64, usize, bool)> {
+        let mut due = Vec::new();
+        while let Some(input) = self.inputs.get(self.cursor) {
+            if input.timestamp_ms as f64 > time_ms {
+                break;
+            }
+            let (column, is_press) = input.unpack();
+            due.push((input.timestamp_ms as f64, column, is_press));
+            self.cursor += 1;
+        }
+        due
+    }
+}
+
+/// Replay raced on the second playfield.
+pub struct VersusOpponent {
+    engine: GameEngine,
+    feed: ReplayFeed,
+}
+
+impl GameEngine {
+    /// Plays `replay` back instead of listening to the player.
+    pub fn set_playback(&mut self, replay: &ReplayData) {
+        self.update_hit_window(replay.hit_window_mode, replay.hit_window_value);
+        self.replay_data.hit_window_mode = replay.hit_window_mode;
+        self.replay_data.hit_window_value = replay.hit_window_value;
+        self.playback = Some(ReplayFeed::new(replay));
+    }
+
+    /// Whether the engine presses the keys itself (autoplay or a replay).
+    pub fn plays_itself(&self) -> bool {
+        self.autoplay || self.playback.is_some()
+    }
+
+    /// Races `replay` on a second playfield, judged with its own hit window.
+    pub fn set_versus(&mut self, bus: &SystemBus, replay: &ReplayData) {
+        let chart = self
+            .chart_in_chart_order()
+            .iter()
+            .map(|note| note.reset())
+            .collect();
+        let mut engine = GameEngine::from_debug_chart(
+            bus,
+            chart,
+            replay.hit_window_mode,
+            replay.hit_window_value,
+        );
+        engine.rate = self.rate;
+        engine.beatmap_hash = self.beatmap_hash.clone();
+        engine.scroll_speed_ms = self.scroll_speed_ms;
+        engine.scroll_map = self.scroll_map.clone();
+        engine.lane_cover = self.lane_cover;
+        if !self.column_order.is_empty() {
+            engine.set_column_order(self.column_order.clone());
+        }
+        self.versus = Some(Box::new(VersusOpponent {
+            engine,
+            feed: ReplayFeed::new(replay),
+        }));
+    }
+
+    /// Feeds the replay inputs reached by the clock. Called each tick.
+    pub(crate) fn update_playback(&mut self) {
+        let Some(feed) = self.playback.as_mut() else {
+            return;
+        };
+        for (time, column, is_press) in feed.due(self.audio_clock) {
+            self.feed_input(time, column, is_press);
+        }
+    }
+
+    /// Moves the opponent to the main engine's clock. Called each tick.
+    pub(crate) fn update_versus(&mut self) {
+        let clock = self.audio_clock;
+        let Some(opponent) = self.versus.as_mut() else {
+            return;
+        };
+        let engine = &mut opponent.engine;
+        for (time, column, is_press) in opponent.feed.due(clock) {
+            engine.feed_input(time, column, is_press);
+        }
+        engine.audio_clock = clock;
+        engine.update_notes(clock);
+        engine.update_nps();
+    }
+
+    fn feed_input(&mut self, time_ms: f64, chart_column: usize, is_press: bool) {
+        // Replays record chart columns.
+        let column = self
+            .column_order
+            .get(chart_column)
+            .copied()
+            .unwrap_or(chart_column);
+        let action = if is_press {
+            GameAction::Hit {
+                column,
+                timestamp: None,
+            }
+        } else {
+            GameAction::Release {
+                column,
+                timestamp: None,
+            }
+        };
+        self.input_at(time_ms, action);
+    }
+
+    /// The opponent engine, while racing one.
+    pub fn versus_engine(&self) -> Option<&GameEngine> {
+        self.versus.as_ref().map(|opponent| &opponent.engine)
+    }
+}
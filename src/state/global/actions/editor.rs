@@ -59,22 +59,14 @@ pub fn apply(
             editor.save_requested = true;
             None
         }
-        GameAction::UpdateVolume(value) => {
-            state.settings.master_volume = *value;
-            editor.engine.audio_manager.set_volume(*value);
-            state.persist_settings();
+        GameAction::ToggleAutoplay => {
+            let enabled = !editor.engine.autoplay;
+            editor.engine.set_autoplay(enabled);
             None
         }
-        GameAction::Hit { column } => {
-            editor
-                .engine
-                .handle_input(GameAction::Hit { column: *column });
-            None
-        }
-        GameAction::Release { column } => {
-            editor
-                .engine
-                .handle_input(GameAction::Release { column: *column });
+        GameAction::Hit { .. } | GameAction::Release { .. } if editor.engine.autoplay => None,
+        GameAction::Hit { .. } | GameAction::Release { .. } => {
+            editor.engine.handle_input(action.clone());
             None
         }
         _ => None,
@@ -1,9 +1,11 @@
 use crate::input::events::GameAction;
+use crate::models::autosave::Autosave;
 use crate::models::engine::hit_window::HitWindow;
-use crate::models::settings::HitWindowMode;
-use crate::state::GameEngine;
+use crate::models::settings::LaneCover;
 use crate::state::global::GlobalState;
+use crate::state::global::actions::menu::relaunch;
 use crate::state::global::app_state::AppState;
+use crate::state::{GameEngine, GameResultData};
 
 pub fn apply(
     state: &mut GlobalState,
@@ -13,38 +15,87 @@ pub fn apply(
     match action {
         GameAction::Back => {
             engine.audio_manager.stop();
+            Autosave::remove();
+            if let Some(result) = partial_result(state, engine) {
+                return Some(AppState::Result(result));
+            }
+            state.active_course = None;
             state.requested_leaderboard_hash = None;
             let menu = state.saved_menu_state.clone();
             let request_hash = menu.get_selected_beatmap_hash();
             state.request_leaderboard_for_hash(request_hash);
             Some(AppState::Menu(menu))
         }
-        GameAction::UpdateVolume(value) => {
-            state.settings.master_volume = *value;
-            engine.audio_manager.set_volume(*value);
+        // Course stages and versus races are not restarted on their own.
+        GameAction::Restart
+            if state.active_course.is_none()
+                && engine.versus.is_none()
+                && engine.playback.is_none()
+                && engine.beatmap_hash.is_some()
+                && engine.beatmap_hash == state.saved_menu_state.get_selected_beatmap_hash() =>
+        {
+            engine.audio_manager.stop();
+            Autosave::remove();
+            let practice = engine.practice_mode;
+            // A chart that no longer loads sends the player back to the menu,
+            // the load error is reported there.
+            relaunch(state, engine.rate, practice)
+                .or_else(|| apply(state, engine, &GameAction::Back))
+        }
+        GameAction::Restart => None,
+        GameAction::ReloadKeybinds => None,
+        // Up / Down move the sudden cover, Left / Right the hidden one.
+        GameAction::Navigation { x, y } => {
+            engine
+                .lane_cover
+                .adjust(*y as f32 * LaneCover::STEP, *x as f32 * LaneCover::STEP);
+            state.settings.lane_cover = engine.lane_cover;
             state.persist_settings();
             None
         }
-        GameAction::ReloadKeybinds => None,
         GameAction::UpdateHitWindow { mode, value } => {
             state.settings.hit_window_mode = *mode;
             state.settings.hit_window_value = *value;
             state.persist_settings();
 
-            let hw = match mode {
-                HitWindowMode::OsuOD => HitWindow::from_osu_od(*value),
-                HitWindowMode::EtternaJudge => HitWindow::from_etterna_judge(*value as u8),
-            };
-
-            engine.hit_window = hw;
+            engine.hit_window = HitWindow::from_mode(*mode, *value);
             engine.replay_data.hit_window_mode = *mode;
             engine.replay_data.hit_window_value = *value;
 
             None
         }
+        // The engine presses the keys itself.
+        GameAction::Hit { .. } | GameAction::Release { .. } if engine.plays_itself() => None,
+        GameAction::Hit { .. } => {
+            engine.handle_input(action.clone());
+            engine
+                .debug_counters
+                .record_latency(state.bus.key_press_age_ms());
+            None
+        }
         _ => {
             engine.handle_input(action.clone());
             None
         }
     }
 }
+
+/// Results up to the quit point of a run left midway, if the player wants
+/// them. Course stages, practice and autoplay are left as before.
+fn partial_result(state: &GlobalState, engine: &GameEngine) -> Option<GameResultData> {
+    if !state.settings.partial_results
+        || state.active_course.is_some()
+        || engine.practice_mode
+        || engine.plays_itself()
+    {
+        return None;
+    }
+    let mut result = engine.partial_result(&state.settings)?;
+    if let Some(hash) = &result.beatmap_hash {
+        result.map_title = state
+            .saved_menu_state
+            .beatmap_label(hash)
+            .unwrap_or_default();
+    }
+    Some(result)
+}
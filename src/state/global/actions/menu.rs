@@ -1,8 +1,15 @@
+use crate::database::replay_storage;
 use crate::input::events::GameAction;
+use crate::models::ghost::VersusChoice;
+use crate::models::personal_best::{best_for, judge_key};
+use crate::models::skin::UiSound;
+use crate::shared::locale::tr;
+use crate::shared::notice::Notice;
 use crate::state::global::GlobalState;
 use crate::state::global::app_state::AppState;
 use crate::state::global::helpers::create_debug_chart;
 use crate::state::{GameEngine, MenuState};
+use std::path::{Path, PathBuf};
 
 pub fn apply(
     state: &mut GlobalState,
@@ -10,12 +17,26 @@ pub fn apply(
     action: &GameAction,
 ) -> Option<AppState> {
     match action {
-        GameAction::Navigation { x, y } => handle_navigation(state, menu, *x, *y),
+        GameAction::Navigation { x, y } => {
+            state.play_ui_sound(UiSound::MenuMove);
+            handle_navigation(state, menu, *x, *y)
+        }
         GameAction::SetSelection(idx) => handle_set_selection(state, menu, *idx),
         GameAction::SetDifficulty(idx) => handle_set_difficulty(state, menu, *idx),
-        GameAction::Confirm => handle_confirm(state, menu),
+        GameAction::Confirm => {
+            state.play_ui_sound(UiSound::MenuConfirm);
+            handle_confirm(state, menu)
+        }
         GameAction::LaunchPractice => handle_launch_practice(state, menu),
         GameAction::ToggleEditor => handle_toggle_editor(state, menu),
+        GameAction::TabNext if state.settings.tournament.enabled => {
+            menu.rate = state.settings.tournament.step_rate(menu.rate, 1);
+            None
+        }
+        GameAction::TabPrev if state.settings.tournament.enabled => {
+            menu.rate = state.settings.tournament.step_rate(menu.rate, -1);
+            None
+        }
         GameAction::TabNext => {
             menu.increase_rate();
             None
@@ -27,20 +48,54 @@ pub fn apply(
         GameAction::ToggleSettings => {
             menu.show_settings = !menu.show_settings;
             if menu.show_settings {
-                menu.ensure_chart_cache();
+                menu.ensure_chart_cache(state.settings.chart_rules());
             }
             None
         }
-        GameAction::UpdateVolume(value) => {
-            state.settings.master_volume = *value;
-            state.persist_settings();
+        GameAction::ToggleAutoplay => {
+            menu.autoplay = !menu.autoplay;
             None
         }
+        GameAction::RandomMap => {
+            if menu.pick_random() {
+                state.play_ui_sound(UiSound::MenuMove);
+                if menu.show_settings {
+                    menu.ensure_chart_cache(state.settings.chart_rules());
+                }
+                state.request_leaderboard_for_hash(menu.get_selected_beatmap_hash());
+            }
+            None
+        }
+        GameAction::RecommendMap => {
+            // Picked once the DB answers, see `sync_db_to_menu`.
+            state
+                .db_manager
+                .fetch_recent_rating(menu.active_calculator.clone());
+            None
+        }
+        GameAction::SetGhost(choice) => {
+            menu.ghost = choice.clone();
+            None
+        }
+        GameAction::StartVersus(choice) => handle_start_versus(state, menu, choice),
         GameAction::Rescan => {
+            // The settings window may have changed the chart rules.
+            state.reload_settings();
             state.db_manager.rescan();
             state.last_db_version = u64::MAX;
             None
         }
+        GameAction::ImportArchive(path) => {
+            state.reload_settings();
+            state.db_manager.import_archive(path.clone());
+            None
+        }
+        GameAction::SetLibraryRoots(roots) => {
+            state.reload_settings();
+            state.db_manager.set_library_roots(roots.clone());
+            state.last_db_version = u64::MAX;
+            None
+        }
         GameAction::ApplySearch(filters) => {
             menu.search_filters = filters.clone();
             state.db_manager.search(filters.clone());
@@ -48,6 +103,67 @@ pub fn apply(
             state.last_leaderboard_version = 0;
             None
         }
+        GameAction::SetArchived {
+            beatmapset_id,
+            archived,
+        } => {
+            state
+                .db_manager
+                .set_archived(*beatmapset_id, *archived, menu.search_filters.clone());
+            state.requested_leaderboard_hash = None;
+            None
+        }
+        GameAction::EditMetadata {
+            beatmapset_id,
+            metadata,
+        } => {
+            state.db_manager.set_metadata(
+                *beatmapset_id,
+                metadata.clone(),
+                menu.search_filters.clone(),
+            );
+            None
+        }
+        GameAction::CreateGoal(draft) => {
+            state.db_manager.create_goal(draft.clone());
+            None
+        }
+        GameAction::DeleteGoal(goal_id) => {
+            state.db_manager.delete_goal(*goal_id);
+            None
+        }
+        GameAction::RecoverAutosave => {
+            state.recover_autosave(menu);
+            None
+        }
+        GameAction::DiscardAutosave => {
+            state.discard_autosave(menu);
+            None
+        }
+        GameAction::CreateCourse(draft) => {
+            state.db_manager.create_course(draft.clone());
+            None
+        }
+        GameAction::DeleteCourse(course_id) => {
+            state.db_manager.delete_course(*course_id);
+            None
+        }
+        GameAction::StartCourse(course_id) => {
+            let course = menu
+                .courses
+                .iter()
+                .find(|c| c.course.id == *course_id)
+                .cloned()?;
+            if missing_signing_key(state) {
+                return None;
+            }
+            let rate = if state.settings.tournament.enabled {
+                state.settings.tournament.snap_rate(menu.rate)
+            } else {
+                menu.rate
+            };
+            state.start_course(course, rate)
+        }
         GameAction::SetCalculator(calc_id) => {
             menu.set_calculator(calc_id);
             menu.ensure_difficulty_calculated();
@@ -59,7 +175,70 @@ pub fn apply(
             state.persist_settings();
             None
         }
-        GameAction::SetResult(result_data) => Some(AppState::Result(result_data.clone())),
+        GameAction::UpdateLeaderboardOptions(options) => {
+            // The renderer may have saved other settings since our last read.
+            state.reload_settings();
+            state.settings.leaderboard = *options;
+            state.persist_settings();
+            None
+        }
+        GameAction::ApplyGameplayPreset(name) => {
+            // The renderer may have saved other settings since our last read.
+            state.reload_settings();
+            if state.settings.apply_preset(name) {
+                log::info!("MENU: Gameplay preset '{}' loaded", name);
+                state.persist_settings();
+            }
+            None
+        }
+        GameAction::SwitchProfile(profile_id) => {
+            // The settings window already swapped the keybinds and saved them.
+            state.reload_settings();
+            state.forward_keybinds();
+            state.db_manager.set_profile(*profile_id);
+            state.requested_leaderboard_hash = None;
+            state.request_leaderboard_for_hash(menu.get_selected_beatmap_hash());
+            None
+        }
+        GameAction::CreateProfile(name) => {
+            state.db_manager.create_profile(name.clone());
+            None
+        }
+        GameAction::LoadLibraryStats => {
+            state
+                .db_manager
+                .load_library_stats(menu.active_calculator.clone());
+            None
+        }
+        GameAction::LibraryMaintenance(request) => {
+            state.db_manager.maintenance(request.clone());
+            None
+        }
+        GameAction::SearchMirror(query) => {
+            state.reload_settings();
+            state
+                .downloader
+                .search(&state.settings.mirror_url, query.clone());
+            None
+        }
+        GameAction::DownloadBeatmapset(set_id) => {
+            if let Some(set) = menu.downloads.results.iter().find(|set| set.id == *set_id) {
+                state.reload_settings();
+                state.downloader.download(
+                    &state.settings.mirror_url,
+                    state.settings.import_root(),
+                    set.clone(),
+                );
+            }
+            None
+        }
+        GameAction::SetResult(result_data) => {
+            let mut result = result_data.clone();
+            if let Some(hash) = &result.beatmap_hash {
+                result.map_title = menu.beatmap_label(hash).unwrap_or_default();
+            }
+            Some(AppState::Result(result))
+        }
         GameAction::LaunchDebugMap => handle_launch_debug_map(state),
         _ => None,
     }
@@ -84,7 +263,7 @@ fn handle_navigation(
         menu.next_difficulty();
     }
     if menu.show_settings {
-        menu.ensure_chart_cache();
+        menu.ensure_chart_cache(state.settings.chart_rules());
     }
     let request_hash = menu.get_selected_beatmap_hash();
     state.request_leaderboard_for_hash(request_hash);
@@ -96,19 +275,9 @@ fn handle_set_selection(
     menu: &mut MenuState,
     idx: usize,
 ) -> Option<AppState> {
-    if idx < menu.beatmapsets.len() {
-        menu.selected_index = idx;
-        menu.selected_difficulty_index = 0;
-        if idx < menu.start_index {
-            menu.start_index = idx;
-            menu.end_index = (menu.start_index + menu.visible_count).min(menu.beatmapsets.len());
-        } else if idx >= menu.end_index {
-            menu.end_index = (idx + 1).min(menu.beatmapsets.len());
-            menu.start_index = menu.end_index.saturating_sub(menu.visible_count);
-        }
-    }
+    menu.select_beatmapset(idx);
     if menu.show_settings {
-        menu.ensure_chart_cache();
+        menu.ensure_chart_cache(state.settings.chart_rules());
     }
     let request_hash = menu.get_selected_beatmap_hash();
     state.request_leaderboard_for_hash(request_hash);
@@ -122,7 +291,7 @@ fn handle_set_difficulty(
 ) -> Option<AppState> {
     menu.selected_difficulty_index = idx;
     if menu.show_settings {
-        menu.ensure_chart_cache();
+        menu.ensure_chart_cache(state.settings.chart_rules());
     }
     let request_hash = menu.get_selected_beatmap_hash();
     state.request_leaderboard_for_hash(request_hash);
@@ -131,7 +300,14 @@ fn handle_set_difficulty(
 
 fn handle_confirm(state: &mut GlobalState, menu: &mut MenuState) -> Option<AppState> {
     state.reload_settings();
-    menu.ensure_chart_cache();
+    if missing_signing_key(state) {
+        return None;
+    }
+    menu.ensure_chart_cache(state.settings.chart_rules());
+    let tournament = state.settings.tournament.enabled;
+    if tournament {
+        menu.rate = state.settings.tournament.snap_rate(menu.rate);
+    }
 
     let engine = if let Some(cache) = menu.get_cached_chart() {
         let chart: Vec<_> = cache.chart.iter().map(|n| n.reset()).collect();
@@ -142,7 +318,7 @@ fn handle_confirm(state: &mut GlobalState, menu: &mut MenuState) -> Option<AppSt
             chart.len(),
             beatmap_hash
         );
-        GameEngine::from_cached(
+        let mut engine = GameEngine::from_cached(
             &state.bus,
             chart,
             cache.audio_path.clone(),
@@ -150,40 +326,41 @@ fn handle_confirm(state: &mut GlobalState, menu: &mut MenuState) -> Option<AppSt
             beatmap_hash,
             state.settings.hit_window_mode,
             state.settings.hit_window_value,
-        )
+        );
+        engine.timing = cache.timing.clone();
+        engine
     } else if let Some(path) = menu.get_selected_beatmap_path() {
         let beatmap_hash = menu.get_selected_beatmap_hash();
         log::info!(
             "GAME: Loading chart from file (no cache), hash: {:?}",
             beatmap_hash
         );
-        if let Some(e) = GameEngine::new(
-            &state.bus,
-            path,
-            menu.rate,
-            beatmap_hash,
-            state.settings.hit_window_mode,
-            state.settings.hit_window_value,
-        ) {
-            e
-        } else {
-            return None;
-        }
+        load_selected(state, menu, path, menu.rate, beatmap_hash)?
     } else {
         return None;
     };
 
     let mut engine = engine;
-    engine.scroll_speed_ms = state.settings.scroll_speed;
-    engine
-        .audio_manager
-        .set_volume(state.settings.master_volume);
+    state.prepare_engine(&mut engine);
+    if menu.autoplay {
+        engine.set_autoplay(true);
+    }
+    // The leaderboard is hidden in tournament mode, so are the scores it holds.
+    if !tournament {
+        attach_ghost(&mut engine, menu);
+        attach_personal_best(&mut engine, menu);
+    }
+    clear_load_error(state, menu);
     Some(AppState::Game(engine))
 }
 
 fn handle_launch_practice(state: &mut GlobalState, menu: &mut MenuState) -> Option<AppState> {
     state.reload_settings();
-    menu.ensure_chart_cache();
+    if state.settings.tournament.enabled {
+        log::info!("PRACTICE: Disabled in tournament mode");
+        return None;
+    }
+    menu.ensure_chart_cache(state.settings.chart_rules());
 
     let engine = if let Some(cache) = menu.get_cached_chart() {
         let chart: Vec<_> = cache.chart.iter().map(|n| n.reset()).collect();
@@ -194,7 +371,7 @@ fn handle_launch_practice(state: &mut GlobalState, menu: &mut MenuState) -> Opti
             chart.len(),
             beatmap_hash
         );
-        GameEngine::from_cached(
+        let mut engine = GameEngine::from_cached(
             &state.bus,
             chart,
             cache.audio_path.clone(),
@@ -202,47 +379,166 @@ fn handle_launch_practice(state: &mut GlobalState, menu: &mut MenuState) -> Opti
             beatmap_hash,
             state.settings.hit_window_mode,
             state.settings.hit_window_value,
-        )
+        );
+        engine.timing = cache.timing.clone();
+        engine
     } else if let Some(path) = menu.get_selected_beatmap_path() {
         let beatmap_hash = menu.get_selected_beatmap_hash();
         log::info!(
             "PRACTICE: Loading chart from file (no cache), hash: {:?}",
             beatmap_hash
         );
-        if let Some(e) = GameEngine::new(
-            &state.bus,
-            path,
-            menu.rate,
-            beatmap_hash,
-            state.settings.hit_window_mode,
-            state.settings.hit_window_value,
-        ) {
-            e
-        } else {
-            return None;
-        }
+        load_selected(state, menu, path, menu.rate, beatmap_hash)?
     } else {
         return None;
     };
 
     let mut engine = engine;
-    engine.scroll_speed_ms = state.settings.scroll_speed;
-    engine
-        .audio_manager
-        .set_volume(state.settings.master_volume);
+    state.prepare_engine(&mut engine);
     engine.enable_practice_mode();
+    attach_ghost(&mut engine, menu);
+    attach_personal_best(&mut engine, menu);
+    clear_load_error(state, menu);
+    Some(AppState::Game(engine))
+}
+
+/// Starts the chart of the last launch again at `rate`, with the same
+/// autoplay, ghost and practice setup, for a quick retry.
+pub(super) fn relaunch(state: &mut GlobalState, rate: f64, practice: bool) -> Option<AppState> {
+    let mut menu = state.saved_menu_state.clone();
+    menu.rate = rate;
+    log::info!("GAME: Retrying at {:.2}x", rate);
+    if practice {
+        handle_launch_practice(state, &mut menu)
+    } else {
+        handle_confirm(state, &mut menu)
+    }
+}
+
+/// Loads the selected chart from its file, reporting why it cannot be played.
+fn load_selected(
+    state: &GlobalState,
+    menu: &MenuState,
+    path: PathBuf,
+    rate: f64,
+    beatmap_hash: Option<String>,
+) -> Option<GameEngine> {
+    let loaded = GameEngine::new(
+        &state.bus,
+        path,
+        state.settings.chart_rules(),
+        rate,
+        beatmap_hash,
+        state.settings.hit_window_mode,
+        state.settings.hit_window_value,
+    );
+    match loaded {
+        Ok(engine) => Some(engine),
+        Err(e) => {
+            state.report_load_error(
+                menu.get_selected_beatmap_hash(),
+                &e,
+                menu.search_filters.clone(),
+            );
+            None
+        }
+    }
+}
+
+/// Clears the broken flag of the selected chart now that it loaded.
+/// In tournament mode after a restart, plays could not be signed until the
+/// organizer enters the password again, so none is started.
+fn missing_signing_key(state: &GlobalState) -> bool {
+    let tournament = &state.settings.tournament;
+    if !tournament.enabled || tournament.signing_key.is_some() {
+        return false;
+    }
+    log::warn!("GAME: Tournament mode without signing key, play refused");
+    let _ = state
+        .bus
+        .toast_tx
+        .send(Notice::error(tr("notice.tournament_password_needed")));
+    true
+}
+
+fn clear_load_error(state: &GlobalState, menu: &MenuState) {
+    if menu.get_selected_load_error().is_some()
+        && let Some(hash) = menu.get_selected_beatmap_hash()
+    {
+        state
+            .db_manager
+            .set_load_error(hash, None, menu.search_filters.clone());
+    }
+}
+
+/// Races a leaderboard replay on split playfields, against the player or
+/// another replay. Both sides play at the rate of the left one.
+fn handle_start_versus(
+    state: &mut GlobalState,
+    menu: &mut MenuState,
+    choice: &VersusChoice,
+) -> Option<AppState> {
+    if menu.get_selected_beatmap_hash().as_deref() != Some(choice.beatmap_hash.as_str()) {
+        return None;
+    }
+    let rate = menu.rate;
+    let autoplay = menu.autoplay;
+    menu.rate = choice.player.as_ref().unwrap_or(&choice.opponent).rate;
+    menu.autoplay = false;
+    let launched = handle_confirm(state, menu);
+    menu.rate = rate;
+    menu.autoplay = autoplay;
+
+    let Some(AppState::Game(mut engine)) = launched else {
+        return launched;
+    };
+    if let Some(replay) = &choice.player {
+        engine.set_playback(replay);
+    }
+    engine.set_versus(&state.bus, &choice.opponent);
+    log::info!("GAME: Versus race at {:.2}x", engine.rate);
     Some(AppState::Game(engine))
 }
 
+/// Races the leaderboard replay picked for this chart, if any.
+fn attach_ghost(engine: &mut GameEngine, menu: &MenuState) {
+    if let Some(choice) = &menu.ghost
+        && engine.beatmap_hash.as_ref() == Some(&choice.beatmap_hash)
+    {
+        engine.set_ghost(&choice.replay_data);
+    }
+}
+
+/// Loads the best leaderboard replay at the engine's rate and judge for the
+/// HUD delta.
+fn attach_personal_best(engine: &mut GameEngine, menu: &MenuState) {
+    if menu.leaderboard_hash.is_none() || menu.leaderboard_hash != engine.beatmap_hash {
+        return;
+    }
+    let judge = judge_key(
+        engine.replay_data.hit_window_mode,
+        engine.replay_data.hit_window_value,
+    );
+    let best = best_for(&menu.leaderboard_scores, engine.rate, &judge)
+        .and_then(|score| replay_storage::load_replay_from_path(Path::new(&score.file_path)).ok());
+    if let Some(replay) = best {
+        engine.set_personal_best(&replay);
+    }
+}
+
 fn handle_toggle_editor(state: &mut GlobalState, menu: &mut MenuState) -> Option<AppState> {
     use crate::state::editor::EditorState;
 
     state.reload_settings();
-    menu.ensure_chart_cache();
+    if state.settings.tournament.enabled {
+        log::info!("EDITOR: Disabled in tournament mode");
+        return None;
+    }
+    menu.ensure_chart_cache(state.settings.chart_rules());
 
     let engine = if let Some(cache) = menu.get_cached_chart() {
         let chart: Vec<_> = cache.chart.iter().map(|n| n.reset()).collect();
-        GameEngine::from_cached(
+        let mut engine = GameEngine::from_cached(
             &state.bus,
             chart,
             cache.audio_path.clone(),
@@ -250,35 +546,31 @@ fn handle_toggle_editor(state: &mut GlobalState, menu: &mut MenuState) -> Option
             None,
             state.settings.hit_window_mode,
             state.settings.hit_window_value,
-        )
+        );
+        engine.timing = cache.timing.clone();
+        engine
     } else if let Some(path) = menu.get_selected_beatmap_path() {
-        if let Some(e) = GameEngine::new(
-            &state.bus,
-            path,
-            1.0,
-            None,
-            state.settings.hit_window_mode,
-            state.settings.hit_window_value,
-        ) {
-            e
-        } else {
-            return None;
-        }
+        load_selected(state, menu, path, 1.0, None)?
     } else {
         return None;
     };
 
     let mut engine = engine;
-    engine.scroll_speed_ms = state.settings.scroll_speed;
-    engine
-        .audio_manager
-        .set_volume(state.settings.master_volume);
+    state.prepare_engine(&mut engine);
+    if menu.autoplay {
+        engine.set_autoplay(true);
+    }
 
+    clear_load_error(state, menu);
     Some(AppState::Editor(EditorState::new(engine)))
 }
 
 fn handle_launch_debug_map(state: &mut GlobalState) -> Option<AppState> {
     state.reload_settings();
+    if state.settings.tournament.enabled {
+        log::info!("GAME: Debug map disabled in tournament mode");
+        return None;
+    }
     let chart = create_debug_chart();
     let engine = GameEngine::from_debug_chart(
         &state.bus,
@@ -287,6 +579,6 @@ fn handle_launch_debug_map(state: &mut GlobalState) -> Option<AppState> {
         state.settings.hit_window_value,
     );
     let mut engine = engine;
-    engine.scroll_speed_ms = state.settings.scroll_speed;
+    state.prepare_engine(&mut engine);
     Some(AppState::Game(engine))
 }
@@ -1,8 +1,8 @@
 use crate::input::events::GameAction;
 use crate::models::replay::simulate_replay;
-use crate::models::settings::HitWindowMode;
 use crate::state::GameResultData;
 use crate::state::global::GlobalState;
+use crate::state::global::actions::menu::relaunch;
 use crate::state::global::app_state::AppState;
 
 pub fn apply(
@@ -18,6 +18,14 @@ pub fn apply(
             state.request_leaderboard_for_hash(request_hash);
             Some(AppState::Menu(menu))
         }
+        // Course results span several charts, there is no single one to retry.
+        GameAction::Restart
+            if result.course.is_none()
+                && result.beatmap_hash.is_some()
+                && result.beatmap_hash == state.saved_menu_state.get_selected_beatmap_hash() =>
+        {
+            relaunch(state, result.rate, result.replay_data.is_practice_mode)
+        }
         GameAction::ToggleSettings => {
             result.show_settings = !result.show_settings;
             None
@@ -29,16 +37,13 @@ pub fn apply(
 
             result.replay_data.hit_window_mode = *mode;
             result.replay_data.hit_window_value = *value;
-            result.judge_text = match state.settings.hit_window_mode {
-                HitWindowMode::OsuOD => format!("OD {:.1}", state.settings.hit_window_value),
-                HitWindowMode::EtternaJudge => {
-                    format!("Judge {:.0}", state.settings.hit_window_value)
-                }
-            };
+            result.judge_text = mode.label(*value);
 
+            // Course results span several charts, so there is no single chart to re-judge.
             let chart_opt = state
                 .saved_menu_state
                 .get_cached_chart()
+                .filter(|_| result.course.is_none())
                 .map(|c| c.chart.iter().map(|n| n.reset()).collect::<Vec<_>>());
 
             if let Some(chart) = chart_opt {
@@ -0,0 +1,248 @@
+//! Course runs: charts played back to back with score and HP carried over.
+
+use std::path::PathBuf;
+
+use crate::database::models::{CourseChart, CourseWithCharts};
+use crate::models::course::COURSE_START_HEALTH;
+use crate::models::goal::CourseStagePlay;
+use crate::models::replay::{HitTiming, ReplayData, ReplayResult};
+use crate::models::skin::UiSound;
+use crate::models::stats::{DriftStats, HitStats};
+use crate::state::global::GlobalState;
+use crate::state::global::app_state::AppState;
+use crate::state::{CourseStageResult, CourseSummary, GameEngine, GameResultData};
+
+/// Gap inserted between stages on the combined timeline graph (ms).
+const STAGE_GAP_MS: f64 = 2000.0;
+
+/// Progress of the course currently being played.
+pub(crate) struct CourseRun {
+    course: CourseWithCharts,
+    rate: f64,
+    health: f64,
+    stages: Vec<CourseStageResult>,
+    hit_stats: HitStats,
+    score: u32,
+    max_combo: u32,
+    hit_timings: Vec<HitTiming>,
+    /// Timeline offset applied to the next stage's hit timings.
+    time_offset_ms: f64,
+    replay_data: ReplayData,
+    drift_stats: DriftStats,
+}
+
+impl CourseRun {
+    pub(super) fn new(course: CourseWithCharts, rate: f64) -> Self {
+        Self {
+            course,
+            rate,
+            health: COURSE_START_HEALTH,
+            stages: Vec::new(),
+            hit_stats: HitStats::new(),
+            score: 0,
+            max_combo: 0,
+            hit_timings: Vec::new(),
+            time_offset_ms: 0.0,
+            replay_data: ReplayData::empty(),
+            drift_stats: DriftStats::default(),
+        }
+    }
+
+    /// Chart to play next, if any remain.
+    fn current_chart(&self) -> Option<&CourseChart> {
+        self.course.charts.get(self.stages.len())
+    }
+
+    /// Folds a finished stage into the running totals.
+    fn record_stage(&mut self, result: &GameResultData, health: f64, failed: bool) {
+        let label = self
+            .current_chart()
+            .map(CourseChart::label)
+            .unwrap_or_default();
+
+        self.stages.push(CourseStageResult {
+            label,
+            score: result.score,
+            accuracy: result.accuracy,
+            max_combo: result.max_combo,
+            failed,
+        });
+
+        let stats = &result.hit_stats;
+        self.hit_stats.marv += stats.marv;
+        self.hit_stats.perfect += stats.perfect;
+        self.hit_stats.great += stats.great;
+        self.hit_stats.good += stats.good;
+        self.hit_stats.bad += stats.bad;
+        self.hit_stats.miss += stats.miss;
+        self.hit_stats.ghost_tap += stats.ghost_tap;
+
+        self.score += result.score;
+        self.max_combo = self.max_combo.max(result.max_combo);
+        self.health = health;
+
+        let note_offset = self.hit_timings.len();
+        let mut stage_end = self.time_offset_ms;
+        for hit in &result.replay_result.hit_timings {
+            let mut hit = hit.clone();
+            hit.note_index += note_offset;
+            hit.note_timestamp_ms += self.time_offset_ms;
+            stage_end = stage_end.max(hit.note_timestamp_ms);
+            self.hit_timings.push(hit);
+        }
+        self.time_offset_ms = stage_end + STAGE_GAP_MS;
+        self.replay_data = result.replay_data.clone();
+        if let Some(drift) = &result.drift_stats {
+            self.drift_stats.merge(drift);
+        }
+    }
+
+    /// Stages played so far with the chart each was, for course goals.
+    fn stage_plays(&self) -> Vec<CourseStagePlay> {
+        self.stages
+            .iter()
+            .zip(&self.course.charts)
+            .map(|(stage, chart)| CourseStagePlay {
+                beatmap_hash: chart.beatmap_hash.clone(),
+                accuracy: stage.accuracy,
+                score: stage.score as i64,
+                passed: !stage.failed,
+            })
+            .collect()
+    }
+
+    /// Builds the combined result screen data for the whole course.
+    fn into_result(self, judge_text: String) -> GameResultData {
+        let accuracy = self.hit_stats.calculate_accuracy();
+        let replay_result = ReplayResult {
+            hit_stats: self.hit_stats.clone(),
+            accuracy,
+            score: self.score,
+            max_combo: self.max_combo,
+            hit_timings: self.hit_timings,
+            ghost_taps: Vec::new(),
+        };
+
+        GameResultData {
+            hit_stats: self.hit_stats,
+            replay_data: self.replay_data,
+            replay_result,
+            score: self.score,
+            accuracy,
+            max_combo: self.max_combo,
+            beatmap_hash: None,
+            map_title: self.course.course.name.clone(),
+            rate: self.rate,
+            judge_text,
+            show_settings: false,
+            drift_stats: Some(Box::new(self.drift_stats)),
+            course: Some(Box::new(CourseSummary {
+                name: self.course.course.name,
+                stages: self.stages,
+                total_stages: self.course.charts.len(),
+                final_health: self.health,
+            })),
+            personal_best: None,
+            incomplete: false,
+        }
+    }
+}
+
+impl GlobalState {
+    /// Starts a course from its first chart.
+    pub(super) fn start_course(&mut self, course: CourseWithCharts, rate: f64) -> Option<AppState> {
+        if !course.is_playable() {
+            log::warn!(
+                "COURSE: '{}' references charts missing from the library",
+                course.course.name
+            );
+            return None;
+        }
+
+        let run = CourseRun::new(course, rate);
+        let engine = self.build_course_engine(&run)?;
+        log::info!(
+            "COURSE: Starting '{}' ({} charts)",
+            run.course.course.name,
+            run.course.charts.len()
+        );
+        self.active_course = Some(run);
+        Some(AppState::Game(engine))
+    }
+
+    /// Records the finished stage and either loads the next chart or shows the
+    /// combined result.
+    pub(super) fn advance_course(&mut self, result: GameResultData) {
+        let Some(mut run) = self.active_course.take() else {
+            self.current_state = AppState::Result(result);
+            return;
+        };
+
+        let (health, failed) = match &mut self.current_state {
+            AppState::Game(engine) => {
+                engine.audio_manager.stop();
+                (engine.health.unwrap_or(0.0), engine.is_failed())
+            }
+            _ => (0.0, true),
+        };
+
+        run.record_stage(&result, health, failed);
+        if failed {
+            self.play_ui_sound(UiSound::SectionFail);
+        }
+
+        if !failed && run.current_chart().is_some() {
+            if let Some(engine) = self.build_course_engine(&run) {
+                log::info!(
+                    "COURSE: Stage {}/{}",
+                    run.stages.len() + 1,
+                    run.course.charts.len()
+                );
+                self.active_course = Some(run);
+                self.current_state = AppState::Game(engine);
+                self.play_ui_sound(UiSound::SectionPass);
+                return;
+            }
+            log::error!("COURSE: Failed to load next chart, ending course");
+        }
+
+        self.db_manager
+            .record_course_run(run.stage_plays(), run.rate);
+        self.current_state = AppState::Result(run.into_result(result.judge_text));
+        if !failed {
+            self.play_ui_sound(UiSound::Applause);
+        }
+    }
+
+    /// Loads the run's current chart with HP and score carried over.
+    fn build_course_engine(&mut self, run: &CourseRun) -> Option<GameEngine> {
+        let chart = run.current_chart()?;
+        let path = PathBuf::from(chart.path.as_ref()?);
+
+        self.reload_settings();
+        let loaded = GameEngine::new(
+            &self.bus,
+            path,
+            self.settings.chart_rules(),
+            run.rate,
+            Some(chart.beatmap_hash.clone()),
+            self.settings.hit_window_mode,
+            self.settings.hit_window_value,
+        );
+        let mut engine = match loaded {
+            Ok(engine) => engine,
+            Err(e) => {
+                self.report_load_error(
+                    Some(chart.beatmap_hash.clone()),
+                    &e,
+                    self.saved_menu_state.search_filters.clone(),
+                );
+                return None;
+            }
+        };
+        self.prepare_engine(&mut engine);
+        engine.health = Some(run.health);
+        engine.score_offset = run.score;
+        Some(engine)
+    }
+}
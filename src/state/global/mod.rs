@@ -2,21 +2,35 @@
 
 mod actions;
 mod app_state;
+mod course;
 mod helpers;
+mod presence;
+mod recovery;
+mod sounds;
 
 use actions::editor::apply as apply_to_editor;
 use actions::game::apply as apply_to_game;
 use actions::menu::apply as apply_to_menu;
 use actions::result::apply as apply_to_result;
 use app_state::AppState;
+use course::CourseRun;
 
 use crate::database::{DbManager, DbStatus};
+use crate::downloader::Downloader;
 use crate::input::events::{GameAction, InputCommand};
+use crate::models::autosave::Autosave;
+use crate::models::engine::{MapLoadError, NUM_COLUMNS};
+use crate::models::personal_best::{PbResult, best_for, judge_key};
+use crate::models::search::MenuSearchFilters;
 use crate::models::settings::SettingsState;
+use crate::models::skin::{SkinSounds, UiSound};
+use crate::presence::Presence;
+use crate::shared::locale::{tr, tr_args};
+use crate::shared::notice::Notice;
 use crate::shared::snapshot::{EditorSnapshot, RenderState};
-use crate::state::MenuState;
 use crate::state::traits::{Snapshot, Transition, Update, UpdateContext};
-use crate::system::bus::SystemBus;
+use crate::state::{GameEngine, GameResultData, MenuState};
+use crate::system::bus::{AudioCommand, SystemBus};
 use crossbeam_channel::Sender;
 use std::sync::Arc;
 
@@ -25,40 +39,98 @@ pub struct GlobalState {
     pub(super) current_state: AppState,
     pub(super) saved_menu_state: MenuState,
     pub(super) db_manager: DbManager,
+    pub(super) downloader: Downloader,
     pub(super) last_db_version: u64,
     pub(super) last_leaderboard_version: u64,
+    pub(super) last_goals_version: u64,
+    pub(super) last_courses_version: u64,
+    pub(super) last_profiles_version: u64,
+    pub(super) last_library_version: u64,
+    pub(super) last_maintenance_version: u64,
+    pub(super) last_download_version: u64,
+    pub(super) last_recent_rating_version: u64,
+    /// Scan progress last forwarded to the window title.
+    pub(super) last_scan_progress: Option<(usize, usize)>,
     pub(super) requested_leaderboard_hash: Option<String>,
     pub(super) settings: SettingsState,
     pub(super) input_cmd_tx: Sender<InputCommand>,
     pub(super) bus: SystemBus,
+    /// Course being played, if gameplay is part of one.
+    pub(super) active_course: Option<CourseRun>,
+    /// Whether gameplay snapshots carry the debug overlay stats.
+    pub(super) show_debug_overlay: bool,
+    /// `[sounds]` of the skin they were read from.
+    pub(super) skin_sounds: Option<(String, SkinSounds)>,
+    /// Discord Rich Presence publisher.
+    pub(super) presence: Presence,
+    /// What the published presence describes, `None` when cleared.
+    pub(super) presence_key: Option<(u8, u64)>,
 }
 
 impl GlobalState {
     /// Creates a new state machine with default menu/settings and DB plumbing.
-    pub fn new(db_manager: DbManager, input_cmd_tx: Sender<InputCommand>, bus: SystemBus) -> Self {
+    pub fn new(
+        db_manager: DbManager,
+        downloader: Downloader,
+        input_cmd_tx: Sender<InputCommand>,
+        bus: SystemBus,
+    ) -> Self {
         log::info!("LOGIC: Initializing Global State");
         let settings = SettingsState::load();
-        let menu = MenuState::new();
+        let mut menu = MenuState::new();
+        menu.unicode_metadata = settings.unicode_metadata;
+        menu.autosave = Autosave::load().map(Arc::new);
+        if let Some(autosave) = &menu.autosave {
+            log::info!(
+                "LOGIC: Found an interrupted play on {}",
+                autosave.beatmap_hash
+            );
+        }
+        db_manager.set_profile(settings.profile_id);
+        // UI sounds play before any chart sets the volumes.
+        let _ = bus
+            .audio_cmd_tx
+            .send(AudioCommand::SetVolume(settings.volumes()));
 
         Self {
             saved_menu_state: menu.clone(),
             current_state: AppState::Menu(menu),
             db_manager,
+            downloader,
             last_db_version: 0,
+            last_scan_progress: None,
             last_leaderboard_version: 0,
+            last_goals_version: 0,
+            last_courses_version: 0,
+            last_profiles_version: 0,
+            last_library_version: 0,
+            last_maintenance_version: 0,
+            last_download_version: 0,
+            last_recent_rating_version: 0,
             requested_leaderboard_hash: None,
             settings,
             input_cmd_tx,
             bus,
+            active_course: None,
+            show_debug_overlay: false,
+            skin_sounds: None,
+            presence: Presence::new(),
+            presence_key: None,
         }
     }
 
     pub fn resize(&mut self, _w: u32, _h: u32) {}
-    pub fn shutdown(&mut self) {}
+    pub fn shutdown(&mut self) {
+        // Closing the game mid-play is not a crash: nothing to recover.
+        if matches!(self.current_state, AppState::Game(_)) {
+            Autosave::remove();
+        }
+    }
 
     /// Ticks the active state and processes end-of-run transitions.
     pub fn update(&mut self, dt: f64) {
         self.sync_db_to_menu();
+        self.sync_downloads_to_menu();
 
         // Create the update context with shared resources
         let mut ctx = UpdateContext {
@@ -80,26 +152,90 @@ impl GlobalState {
         };
 
         // Apply any transition
-        if let Some(Transition::ToResult(result)) = transition {
-            self.current_state = AppState::Result(result);
+        if let Some(Transition::ToResult(mut result)) = transition {
+            if self.active_course.is_some() {
+                self.advance_course(result);
+            } else {
+                if let Some(hash) = &result.beatmap_hash {
+                    result.map_title = self
+                        .saved_menu_state
+                        .beatmap_label(hash)
+                        .unwrap_or_default();
+                }
+                result.personal_best = self.compare_with_personal_best(&result);
+                self.current_state = AppState::Result(result);
+                self.play_ui_sound(UiSound::Applause);
+            }
         }
+
+        self.update_presence();
+    }
+
+    /// How a finished play compares to the best score at its rate and judge,
+    /// from the leaderboard loaded before the play (the new score is not in
+    /// it yet). `None` when the play is not saved or the leaderboard is for
+    /// another chart.
+    fn compare_with_personal_best(&self, result: &GameResultData) -> Option<PbResult> {
+        let AppState::Game(engine) = &self.current_state else {
+            return None;
+        };
+        if engine.is_failed() || engine.plays_itself() || result.replay_data.is_practice_mode {
+            return None;
+        }
+        let menu = &self.saved_menu_state;
+        let hash = result.beatmap_hash.as_ref()?;
+        if menu.leaderboard_hash.as_ref() != Some(hash) {
+            return None;
+        }
+        let judge = judge_key(
+            result.replay_data.hit_window_mode,
+            result.replay_data.hit_window_value,
+        );
+        let previous = best_for(&menu.leaderboard_scores, result.rate, &judge);
+        Some(PbResult::compare(previous, result.score, result.accuracy))
     }
 
     /// Mirrors database snapshots into the menu whenever new data is available.
     fn sync_db_to_menu(&mut self) {
         let db_state_arc = self.db_manager.get_state();
-        if let Ok(guard) = db_state_arc.try_lock()
-            && matches!(guard.status, DbStatus::Idle)
-        {
+        let Ok(mut guard) = db_state_arc.try_lock() else {
+            return;
+        };
+        for notice in guard.notices.drain(..) {
+            let _ = self.bus.toast_tx.send(notice);
+        }
+        let scan_progress = match guard.status {
+            DbStatus::Scanning { current, total } if total > 0 => Some((current, total)),
+            _ => None,
+        };
+        if let AppState::Menu(menu) = &mut self.current_state {
+            menu.scan_progress = scan_progress;
+        }
+        if scan_progress != self.last_scan_progress {
+            self.last_scan_progress = scan_progress;
+            let _ = self.bus.scan_progress_tx.send(scan_progress);
+        }
+
+        if matches!(guard.status, DbStatus::Idle) {
             if guard.version != self.last_db_version {
                 let mut request_hash = None;
                 let mut cache = None;
+                let focus = guard.focus_set_path.take();
                 if let AppState::Menu(menu) = &mut self.current_state {
                     menu.beatmapsets = Arc::new(guard.beatmapsets.clone());
                     menu.start_index = 0;
                     menu.end_index = menu.visible_count.min(menu.beatmapsets.len());
                     menu.selected_index = 0;
                     menu.selected_difficulty_index = 0;
+                    menu.highlight = None;
+                    // Jump to a freshly imported set.
+                    if let Some(idx) = focus.and_then(|path| {
+                        menu.beatmapsets
+                            .iter()
+                            .position(|(set, _)| set.path == path)
+                    }) {
+                        menu.select_beatmapset(idx);
+                    }
                     request_hash = menu.get_selected_beatmap_hash();
                     cache = Some(menu.clone());
                 }
@@ -126,7 +262,116 @@ impl GlobalState {
                     self.requested_leaderboard_hash = None;
                 }
             }
+
+            // Goals are also kept on the saved menu so they survive gameplay.
+            if guard.goals_version != self.last_goals_version {
+                let goals = Arc::new(guard.goals.clone());
+                if let AppState::Menu(menu) = &mut self.current_state {
+                    menu.goals = Arc::clone(&goals);
+                }
+                self.saved_menu_state.goals = goals;
+                self.last_goals_version = guard.goals_version;
+            }
+
+            if guard.courses_version != self.last_courses_version {
+                let courses = Arc::new(guard.courses.clone());
+                if let AppState::Menu(menu) = &mut self.current_state {
+                    menu.courses = Arc::clone(&courses);
+                }
+                self.saved_menu_state.courses = courses;
+                self.last_courses_version = guard.courses_version;
+            }
+
+            if guard.profiles_version != self.last_profiles_version {
+                let profiles = Arc::new(guard.profiles.clone());
+                if let AppState::Menu(menu) = &mut self.current_state {
+                    menu.profiles = Arc::clone(&profiles);
+                }
+                self.saved_menu_state.profiles = profiles;
+                self.last_profiles_version = guard.profiles_version;
+            }
+
+            if guard.library_version != self.last_library_version {
+                let stats = Arc::new(guard.library_stats.clone());
+                if let AppState::Menu(menu) = &mut self.current_state {
+                    menu.library_stats = Arc::clone(&stats);
+                    menu.library_progress = guard.library_progress;
+                }
+                self.saved_menu_state.library_stats = stats;
+                self.saved_menu_state.library_progress = guard.library_progress;
+                self.last_library_version = guard.library_version;
+            }
+
+            if guard.maintenance_version != self.last_maintenance_version {
+                if let AppState::Menu(menu) = &mut self.current_state {
+                    menu.maintenance = guard.maintenance.clone();
+                }
+                self.saved_menu_state.maintenance = guard.maintenance.clone();
+                self.last_maintenance_version = guard.maintenance_version;
+            }
+
+            if guard.recent_rating_version != self.last_recent_rating_version {
+                self.last_recent_rating_version = guard.recent_rating_version;
+                self.recommend_map(guard.recent_rating);
+            }
+        }
+    }
+
+    /// Jumps to a chart rated near `recent_rating`, the player's recent average.
+    fn recommend_map(&mut self, recent_rating: Option<f64>) {
+        let AppState::Menu(menu) = &mut self.current_state else {
+            return;
+        };
+        let Some(target) = recent_rating else {
+            let _ = self
+                .bus
+                .toast_tx
+                .send(Notice::info(tr("notice.no_recent_scores")));
+            return;
+        };
+        let Some(rating) = menu.pick_recommended(target) else {
+            let _ = self
+                .bus
+                .toast_tx
+                .send(Notice::info(tr("notice.no_rated_charts")));
+            return;
+        };
+        if menu.show_settings {
+            menu.ensure_chart_cache(self.settings.chart_rules());
         }
+        let request_hash = menu.get_selected_beatmap_hash();
+        let menu = menu.clone();
+        let _ = self.bus.toast_tx.send(Notice::info(tr_args(
+            "notice.recommended",
+            &[
+                ("rating", &format!("{:.2}", rating)),
+                ("average", &format!("{:.2}", target)),
+            ],
+        )));
+        self.play_ui_sound(UiSound::MenuMove);
+        self.cache_menu_state(menu);
+        self.request_leaderboard_for_hash(request_hash);
+    }
+
+    /// Imports freshly downloaded sets and mirrors the download list into the menu.
+    fn sync_downloads_to_menu(&mut self) {
+        for folder in self.downloader.take_imported() {
+            self.db_manager.import_folder(folder);
+        }
+
+        let state_arc = self.downloader.get_state();
+        let Ok(guard) = state_arc.try_lock() else {
+            return;
+        };
+        if guard.version == self.last_download_version {
+            return;
+        }
+        let downloads = Arc::new(guard.clone());
+        if let AppState::Menu(menu) = &mut self.current_state {
+            menu.downloads = Arc::clone(&downloads);
+        }
+        self.saved_menu_state.downloads = downloads;
+        self.last_download_version = guard.version;
     }
 
     /// Asks the DB thread to refresh leaderboard data for a beatmap hash.
@@ -139,6 +384,24 @@ impl GlobalState {
         }
     }
 
+    /// Tells the player why a chart cannot be played and flags it in the
+    /// library, where it can be filtered out.
+    pub(super) fn report_load_error(
+        &self,
+        beatmap_hash: Option<String>,
+        error: &MapLoadError,
+        filters: MenuSearchFilters,
+    ) {
+        let _ = self.bus.toast_tx.send(Notice::error(tr_args(
+            "notice.cannot_play",
+            &[("error", &error)],
+        )));
+        if let Some(hash) = beatmap_hash {
+            self.db_manager
+                .set_load_error(hash, Some(error.to_string()), filters);
+        }
+    }
+
     /// Persists the last known menu state so that leaving gameplay restores it.
     pub(super) fn cache_menu_state(&mut self, menu: MenuState) {
         self.saved_menu_state = menu;
@@ -149,20 +412,72 @@ impl GlobalState {
         self.settings.save();
     }
 
+    /// Sends the volumes to the audio thread, which every state shares.
+    fn apply_volumes(&self) {
+        let _ = self
+            .bus
+            .audio_cmd_tx
+            .send(AudioCommand::SetVolume(self.settings.volumes()));
+    }
+
+    /// Gives the running chart the scroll speed of its keymode.
+    fn apply_scroll_speed(&mut self) {
+        let engine = match &mut self.current_state {
+            AppState::Game(engine) => engine,
+            AppState::Editor(editor) => &mut editor.engine,
+            _ => return,
+        };
+        engine.scroll_speed_ms = self.settings.scroll_speed_for(engine.key_count());
+    }
+
+    /// Applies the gameplay settings to a freshly loaded chart: scroll
+    /// speed and mode, lane cover and order, volumes and time-stretch.
+    pub(super) fn prepare_engine(&self, engine: &mut GameEngine) {
+        engine.scroll_speed_ms = self.settings.scroll_speed_for(engine.key_count());
+        engine.lane_cover = self.settings.lane_cover;
+        if let Some(order) = self.settings.lane_order(NUM_COLUMNS) {
+            engine.set_column_order(order);
+        }
+        engine.configure_scroll(self.settings.scroll_speed_mode, !self.settings.disable_sv);
+        engine.audio_manager.set_volume(self.settings.volumes());
+        engine
+            .audio_manager
+            .set_time_stretch(self.settings.rate_mode.is_stretch());
+    }
+
     /// Reloads settings from disk (to sync with renderer's changes).
     pub(super) fn reload_settings(&mut self) {
+        // The signing key is never saved: keep it while the lock is the same.
+        let tournament = std::mem::take(&mut self.settings.tournament);
+        let scan = self.settings.scan_options();
         self.settings = SettingsState::load();
+        if self.settings.tournament.password_hash == tournament.password_hash {
+            self.settings.tournament.signing_key = tournament.signing_key;
+        }
+        if self.settings.scan_options() != scan {
+            self.db_manager
+                .set_scan_options(self.settings.scan_options());
+        }
     }
 
     /// Reloads bindings from disk and forwards them to the input thread.
     fn reload_keybinds_from_disk(&mut self) {
         let disk_settings = SettingsState::load();
-        self.settings.keybinds = disk_settings.keybinds.clone();
-        if let Err(e) = self
-            .input_cmd_tx
-            .send(InputCommand::ReloadKeybinds(self.settings.keybinds.clone()))
-        {
-            log::error!("LOGIC: Failed to forward keybinds to input thread: {}", e);
+        self.settings.keybinds = disk_settings.keybinds;
+        self.settings.pad_keybinds = disk_settings.pad_keybinds;
+        self.forward_keybinds();
+    }
+
+    /// Sends the current bindings to the input thread.
+    pub(super) fn forward_keybinds(&self) {
+        let commands = [
+            InputCommand::ReloadKeybinds(self.settings.keybinds.clone()),
+            InputCommand::ReloadPadBinds(self.settings.pad_keybinds.clone()),
+        ];
+        for cmd in commands {
+            if let Err(e) = self.input_cmd_tx.send(cmd) {
+                log::error!("LOGIC: Failed to forward keybinds to input thread: {}", e);
+            }
         }
     }
 
@@ -172,6 +487,72 @@ impl GlobalState {
             self.reload_keybinds_from_disk();
             return;
         }
+        if let GameAction::ToggleDebugOverlay = action {
+            self.show_debug_overlay = !self.show_debug_overlay;
+            return;
+        }
+        if let GameAction::SetDiscordPresence(enabled) = action {
+            // Saved by the settings window when it closes.
+            self.settings.discord_presence = enabled;
+            return;
+        }
+        if let GameAction::SetUnicodeMetadata(enabled) = action {
+            // Saved by the settings window when it closes.
+            self.settings.unicode_metadata = enabled;
+            if let AppState::Menu(menu) = &mut self.current_state {
+                menu.unicode_metadata = enabled;
+            }
+            self.saved_menu_state.unicode_metadata = enabled;
+            return;
+        }
+        if let GameAction::UpdateTournament(tournament) = action {
+            // Saved by the settings window when it closes.
+            if tournament.enabled
+                && let AppState::Menu(menu) = &mut self.current_state
+            {
+                menu.rate = tournament.snap_rate(menu.rate);
+            }
+            self.settings.tournament = tournament;
+            return;
+        }
+        if let GameAction::UpdateScrollSpeed(speed) = action {
+            // Saved by the settings window when it closes.
+            self.settings.scroll_speed = speed;
+            self.apply_scroll_speed();
+            return;
+        }
+        if let GameAction::UpdateKeymodeScrollSpeeds(speeds) = action {
+            // Saved by the settings window when it closes.
+            self.settings.scroll_speeds = speeds;
+            self.apply_scroll_speed();
+            return;
+        }
+        if let GameAction::UpdateVolume(volumes) = action {
+            self.settings.set_volumes(volumes);
+            self.apply_volumes();
+            self.persist_settings();
+            return;
+        }
+        if let GameAction::AdjustVolume { channel, delta } = action {
+            let volume = self.settings.volume_mut(channel);
+            *volume = (*volume + delta).clamp(0.0, 1.0);
+            let percent = (*volume * 100.0).round();
+            self.apply_volumes();
+            self.persist_settings();
+            let _ = self.bus.toast_tx.send(Notice::info(tr_args(
+                "notice.volume",
+                &[("channel", &channel.label()), ("percent", &percent)],
+            )));
+            return;
+        }
+        if let GameAction::UpdateLaneCover(cover) = action {
+            // Saved by the settings window when it closes.
+            self.settings.lane_cover = cover;
+            if let AppState::Game(engine) = &mut self.current_state {
+                engine.lane_cover = cover;
+            }
+            return;
+        }
 
         let mut current_state =
             std::mem::replace(&mut self.current_state, AppState::Menu(MenuState::new()));
@@ -203,7 +584,13 @@ impl GlobalState {
     pub fn create_snapshot(&mut self) -> RenderState {
         match &mut self.current_state {
             AppState::Menu(menu) => RenderState::Menu(Snapshot::create_snapshot(menu)),
-            AppState::Game(engine) => RenderState::InGame(Snapshot::create_snapshot(engine)),
+            AppState::Game(engine) => {
+                let mut snapshot = Snapshot::create_snapshot(engine);
+                if self.show_debug_overlay {
+                    snapshot.debug = Some(Box::new(engine.debug_overlay_stats()));
+                }
+                RenderState::InGame(snapshot)
+            }
             AppState::Editor(editor) => {
                 let modification = if let (Some(t), Some((dx, dy))) =
                     (editor.target.as_ref(), editor.modification_buffer.as_ref())
@@ -218,12 +605,17 @@ impl GlobalState {
                     editor.modification_buffer = None;
                 }
 
-                let status_text = if let Some(t) = editor.target.as_ref() {
+                let mut status_text = if let Some(t) = editor.target.as_ref() {
                     format!("EDIT: {:?} [{}]", t, editor.mode)
                 } else {
                     "SELECT: W(Note) X(Rec) C(Cmb) V(Scr) B(Acc) N(Judg) K(Bar) | S(Save)"
                         .to_string()
                 };
+                status_text.push_str(if editor.engine.autoplay {
+                    " | F7(Autoplay: on)"
+                } else {
+                    " | F7(Autoplay: off)"
+                });
 
                 RenderState::Editor(EditorSnapshot {
                     game: Snapshot::create_snapshot(&editor.engine),
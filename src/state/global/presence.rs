@@ -0,0 +1,70 @@
+//! Discord Rich Presence updates following the state machine.
+
+use super::GlobalState;
+use super::app_state::AppState;
+use crate::presence::Activity;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+impl GlobalState {
+    /// Publishes the current activity when it changed since the last call.
+    pub(super) fn update_presence(&mut self) {
+        let key = self
+            .settings
+            .discord_presence
+            .then(|| match &self.current_state {
+                AppState::Menu(_) => (0, 0),
+                AppState::Game(engine) => (1, engine.rate.to_bits()),
+                AppState::Result(result) => (2, result.accuracy.to_bits()),
+                AppState::Editor(_) => (3, 0),
+            });
+        if key == self.presence_key {
+            return;
+        }
+        self.presence_key = key;
+        let activity = key.map(|_| self.current_activity());
+        self.presence.set(activity);
+    }
+
+    fn current_activity(&self) -> Activity {
+        match &self.current_state {
+            AppState::Menu(_) => Activity {
+                details: "Browsing songs".to_string(),
+                state: None,
+                started_at: None,
+            },
+            AppState::Game(engine) => {
+                let title = engine
+                    .beatmap_hash
+                    .as_deref()
+                    .and_then(|hash| self.saved_menu_state.beatmap_label(hash))
+                    .unwrap_or_default();
+                let state = if engine.autoplay {
+                    "Autoplay"
+                } else if engine.practice_mode {
+                    "Practicing"
+                } else {
+                    "Playing"
+                };
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .ok();
+                Activity {
+                    details: format!("{} {:.2}x", title, engine.rate),
+                    state: Some(state.to_string()),
+                    started_at: now,
+                }
+            }
+            AppState::Result(result) => Activity {
+                details: format!("{} {:.2}x", result.map_title, result.rate),
+                state: Some(format!("{:.2}%", result.accuracy)),
+                started_at: None,
+            },
+            AppState::Editor(_) => Activity {
+                details: "In the editor".to_string(),
+                state: None,
+                started_at: None,
+            },
+        }
+    }
+}
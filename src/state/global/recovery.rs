@@ -0,0 +1,115 @@
+//! Recovery of a play interrupted by a crash (see [`crate::models::autosave`]).
+
+use super::GlobalState;
+use crate::database::{SaveReplayCommand, chart_storage};
+use crate::models::autosave::Autosave;
+use crate::models::engine::NoteData;
+use crate::models::replay::simulate_replay;
+use crate::shared::locale::{tr, tr_args};
+use crate::shared::notice::Notice;
+use crate::state::MenuState;
+
+impl GlobalState {
+    /// Saves the play offered for recovery as an incomplete score. The offer
+    /// stays up when its chart cannot be loaded.
+    pub(super) fn recover_autosave(&mut self, menu: &mut MenuState) {
+        let Some(autosave) = menu.autosave.clone() else {
+            return;
+        };
+        let Some(path) = menu.beatmap_path(&autosave.beatmap_hash) else {
+            let _ = self
+                .bus
+                .toast_tx
+                .send(Notice::error(tr("notice.autosave_chart_missing")));
+            return;
+        };
+        let chart = match chart_storage::load_chart(
+            &autosave.beatmap_hash,
+            &path,
+            self.settings.chart_rules(),
+        ) {
+            Ok((_, chart, _)) => chart,
+            Err(e) => {
+                log::error!("REPLAY: Cannot recover the autosave: {}", e);
+                let _ = self.bus.toast_tx.send(Notice::error(tr_args(
+                    "notice.play_not_recovered",
+                    &[("error", &e)],
+                )));
+                return;
+            }
+        };
+
+        log::info!(
+            "REPLAY: Recovering play on {} up to {:.0}ms",
+            autosave.beatmap_hash,
+            autosave.reached_ms
+        );
+        self.db_manager
+            .save_replay(recovered_score(&autosave, &chart));
+        self.discard_autosave(menu);
+    }
+
+    /// Drops the play offered for recovery.
+    pub(super) fn discard_autosave(&mut self, menu: &mut MenuState) {
+        Autosave::remove();
+        menu.autosave = None;
+    }
+}
+
+/// Score of an interrupted play, judged on the notes it got past.
+fn recovered_score(autosave: &Autosave, chart: &[NoteData]) -> SaveReplayCommand {
+    let mut data = autosave.replay.clone();
+    data.incomplete = true;
+    let hit_window = data.build_hit_window();
+    // Later notes may not have been judged yet when the play stopped.
+    let reached: Vec<NoteData> = chart
+        .iter()
+        .filter(|n| n.is_judged() && n.end_time_ms() + hit_window.miss_ms <= autosave.reached_ms)
+        .cloned()
+        .collect();
+    let result = simulate_replay(&data, &reached, &hit_window);
+
+    SaveReplayCommand {
+        beatmap_hash: autosave.beatmap_hash.clone(),
+        timestamp: autosave.timestamp,
+        score: result.score.min(i32::MAX as u32) as i32,
+        accuracy: result.accuracy,
+        max_combo: result.max_combo.min(i32::MAX as u32) as i32,
+        rate: data.rate,
+        columns: result.column_stats(),
+        data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::replay::ReplayData;
+    use crate::models::settings::HitWindowMode;
+
+    #[test]
+    fn recovered_score_only_judges_the_notes_reached() {
+        let chart = vec![
+            NoteData::tap(1000.0, 0),
+            NoteData::tap(2000.0, 1),
+            NoteData::tap(10000.0, 2),
+        ];
+        let mut replay = ReplayData::new(1.0, HitWindowMode::EtternaJudge, 4.0);
+        for (time, column) in [(1000.0, 0), (2000.0, 1)] {
+            replay.add_press(time, column);
+            replay.add_release(time + 50.0, column);
+        }
+        let autosave = Autosave {
+            beatmap_hash: "abc".to_string(),
+            timestamp: 1_700_000_000,
+            reached_ms: 3000.0,
+            replay,
+        };
+
+        let payload = recovered_score(&autosave, &chart);
+        assert!(payload.data.incomplete);
+        assert_eq!(payload.accuracy, 100.0);
+        assert_eq!(payload.max_combo, 2);
+        assert_eq!(payload.timestamp, autosave.timestamp);
+    }
+}
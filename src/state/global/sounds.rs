@@ -0,0 +1,30 @@
+//! Skin sound effects triggered by menu actions and state transitions.
+
+use super::GlobalState;
+use crate::models::skin::{SkinGeneral, UiSound, load_toml};
+use crate::system::bus::AudioCommand;
+use std::path::Path;
+
+impl GlobalState {
+    /// Plays `sound` from the current skin, if the skin provides it. The
+    /// skin's `[sounds]` table is read again when the selected skin changes.
+    pub(super) fn play_ui_sound(&mut self, sound: UiSound) {
+        let skin_path = Path::new("skins").join(&self.settings.current_skin);
+        let stale = self
+            .skin_sounds
+            .as_ref()
+            .is_none_or(|(skin, _)| *skin != self.settings.current_skin);
+        if stale {
+            let general: SkinGeneral =
+                load_toml(&skin_path.join("conf").join("general.toml")).unwrap_or_default();
+            self.skin_sounds = Some((self.settings.current_skin.clone(), general.sounds));
+        }
+
+        let Some((_, sounds)) = &self.skin_sounds else {
+            return;
+        };
+        if let Some(path) = sounds.resolve(&skin_path, sound) {
+            let _ = self.bus.audio_cmd_tx.send(AudioCommand::PlaySound { path });
+        }
+    }
+}
@@ -15,10 +15,16 @@ impl Snapshot for MenuState {
 
 // MenuState performs cache updates during update().
 impl Update for MenuState {
-    fn update(&mut self, _dt: f64, _ctx: &mut UpdateContext) -> Option<Transition> {
+    fn update(&mut self, _dt: f64, ctx: &mut UpdateContext) -> Option<Transition> {
         // Ensure caches are up-to-date
         self.ensure_selected_rate_cache();
-        self.ensure_chart_cache();
+        for (hash, tags) in self.new_pattern_tags.drain(..) {
+            ctx.db_manager.set_pattern_tags(hash, tags);
+        }
+        self.ensure_chart_cache(ctx.settings.chart_rules());
+        if self.uses_script_calculator() {
+            self.ensure_difficulty_calculated();
+        }
         None
     }
 }
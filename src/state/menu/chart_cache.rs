@@ -1,6 +1,6 @@
 //! Cache de chart pour le menu.
 
-use crate::models::engine::NoteData;
+use crate::models::engine::{NoteData, TimingData};
 use std::path::PathBuf;
 
 /// Cache de la chart actuellement sélectionnée.
@@ -13,8 +13,32 @@ pub struct ChartCache {
     pub beatmap_hash: String,
     /// Notes de la chart.
     pub chart: Vec<NoteData>,
+    /// Changements de BPM de la chart.
+    pub timing: TimingData,
     /// Chemin vers le fichier audio.
     pub audio_path: PathBuf,
     /// Chemin vers le fichier .osu.
     pub map_path: PathBuf,
+    /// Notes par seconde, une valeur par seconde depuis la première note (rate 1.0).
+    pub density: Vec<f32>,
+}
+
+/// Calcule la densité (NPS) de la chart par tranches d'une seconde.
+/// Les mines et notes factices ne comptent pas.
+pub fn note_density(chart: &[NoteData]) -> Vec<f32> {
+    let mut notes = chart.iter().filter(|n| n.should_hit());
+    let Some(first) = notes.next() else {
+        return Vec::new();
+    };
+    let start = first.timestamp_ms;
+
+    let mut density = vec![1.0];
+    for note in notes {
+        let second = ((note.timestamp_ms - start) / 1000.0).max(0.0) as usize;
+        if second >= density.len() {
+            density.resize(second + 1, 0.0);
+        }
+        density[second] += 1.0;
+    }
+    density
 }
@@ -19,20 +19,31 @@ pub use chart_cache::ChartCache;
 pub use difficulty_cache::DifficultyCache;
 pub use rate_cache::RateCacheEntry;
 
-use crate::database::models::Replay;
+use crate::database::maintenance::MaintenanceReport;
+use crate::database::models::{CourseWithCharts, GoalWithCharts, LibraryStats, Profile, Replay};
 use crate::database::{BeatmapRating, BeatmapWithRatings, Beatmapset, Database};
-use crate::difficulty::{self, BeatmapSsr};
+use crate::difficulty::{self, BeatmapSsr, pattern_tags};
+use crate::downloader::DownloadState;
+use crate::models::autosave::Autosave;
+use crate::models::ghost::GhostChoice;
 use crate::models::search::MenuSearchFilters;
+use crate::models::settings::ChartRules;
 use crate::state::result::GameResultData;
 use crate::views::components::menu::song_select::CalculatorOption;
+use rand::Rng;
+use rand::seq::IndexedRandom;
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
 /// Constants for pagination
 pub const PAGE_SIZE: usize = 50;
 pub const PRELOAD_MARGIN: usize = 10;
 
+/// Recommendations pick among this many charts closest to the target rating.
+const RECOMMEND_CANDIDATES: usize = 5;
+
 /// Main state for the song selection menu.
 #[derive(Clone, Debug)]
 pub struct MenuState {
@@ -59,6 +70,12 @@ pub struct MenuState {
 
     // Playback rate
     pub rate: f64,
+    // Next play (or skin editor session) is played by the engine
+    pub autoplay: bool,
+    // Titles and artists shown in their original script
+    pub unicode_metadata: bool,
+    // Leaderboard replay raced against on its chart
+    pub ghost: Option<GhostChoice>,
 
     // Result screen
     pub last_result: Option<GameResultData>,
@@ -69,6 +86,10 @@ pub struct MenuState {
 
     // Failed rate calculations (to avoid retrying)
     pub failed_rate_hashes: HashSet<String>,
+    // (hash, pattern tags) found by the rate analysis, waiting to be saved
+    pub new_pattern_tags: Vec<(String, String)>,
+    // (hash, calculator) pairs that failed on-demand rating (to avoid retrying)
+    pub failed_difficulty: HashSet<(String, String)>,
 
     // On-demand difficulty cache (in RAM only!)
     pub difficulty_cache: DifficultyCache,
@@ -88,6 +109,26 @@ pub struct MenuState {
 
     // Chart cache for gameplay - Arc for O(1) clones
     pub chart_cache: Arc<Option<ChartCache>>,
+
+    // Goals mirrored from the DB - Arc for O(1) clones
+    pub goals: Arc<Vec<GoalWithCharts>>,
+    // Courses mirrored from the DB
+    pub courses: Arc<Vec<CourseWithCharts>>,
+    // Player profiles mirrored from the DB
+    pub profiles: Arc<Vec<Profile>>,
+    // Library overview mirrored from the DB, with the rating job's progress
+    pub library_stats: Arc<Option<LibraryStats>>,
+    pub library_progress: Option<(usize, usize)>,
+    // Last vacuum / replay prune, shown in the Library settings
+    pub maintenance: Option<MaintenanceReport>,
+    // Mirror search results and downloads
+    pub downloads: Arc<DownloadState>,
+    // `(done, total)` while an archive import or incremental scan runs
+    pub scan_progress: Option<(usize, usize)>,
+    // Play interrupted by a crash, offered for recovery at startup
+    pub autosave: Option<Arc<Autosave>>,
+    // Set picked by the random / recommend keys, flashed in the song list
+    pub highlight: Option<(usize, Instant)>,
 }
 
 impl MenuState {
@@ -106,27 +147,49 @@ impl MenuState {
             show_result: false,
             show_settings: false,
             rate: 1.0,
+            autoplay: false,
+            unicode_metadata: false,
+            ghost: None,
             last_result: None,
             should_close_result: false,
             rate_cache: Arc::new(HashMap::new()),
             failed_rate_hashes: HashSet::new(),
+            new_pattern_tags: Vec::new(),
+            failed_difficulty: HashSet::new(),
             difficulty_cache: DifficultyCache::new(),
             active_calculator: "etterna".to_string(),
             available_calculators: vec![
                 CalculatorOption::new("etterna", "Etterna"),
                 CalculatorOption::new("osu", "osu!"),
-            ],
+            ]
+            .into_iter()
+            .chain(
+                difficulty::script::script_calculators()
+                    .iter()
+                    .map(|script| CalculatorOption::new(&script.id, &script.display_name)),
+            )
+            .collect(),
             search_filters: MenuSearchFilters::default(),
             leaderboard_scores: Vec::new(),
             leaderboard_hash: None,
             chart_cache: Arc::new(None),
+            goals: Arc::new(Vec::new()),
+            courses: Arc::new(Vec::new()),
+            profiles: Arc::new(Vec::new()),
+            library_stats: Arc::new(None),
+            library_progress: None,
+            maintenance: None,
+            downloads: Arc::new(DownloadState::default()),
+            scan_progress: None,
+            autosave: None,
+            highlight: None,
         }
     }
 
     /// Loads the currently selected beatmap's chart into cache.
     ///
     /// Returns `true` if a new chart was loaded, `false` if already cached.
-    pub fn ensure_chart_cache(&mut self) -> bool {
+    pub fn ensure_chart_cache(&mut self, rules: ChartRules) -> bool {
         let selected = match self.get_selected_beatmap() {
             Some(bm) => bm,
             None => return false,
@@ -141,23 +204,26 @@ impl MenuState {
             }
         }
 
-        match crate::models::engine::load_map_safe(&beatmap_path) {
-            Some((audio_path, chart)) => {
+        match crate::database::chart_storage::load_chart(&beatmap_hash, &beatmap_path, rules) {
+            Ok((audio_path, chart, timing)) => {
                 log::info!(
                     "MENU: Chart cached for {} ({} notes)",
                     beatmap_hash,
                     chart.len()
                 );
+                let density = chart_cache::note_density(&chart);
                 self.chart_cache = Arc::new(Some(ChartCache {
                     beatmap_hash,
                     chart,
+                    timing,
                     audio_path,
                     map_path: beatmap_path,
+                    density,
                 }));
                 true
             }
-            None => {
-                log::error!("MENU: Failed to load chart for caching");
+            Err(e) => {
+                log::error!("MENU: Failed to load chart for caching: {}", e);
                 self.chart_cache = Arc::new(None);
                 false
             }
@@ -171,7 +237,7 @@ impl MenuState {
     pub fn get_cached_chart_note_count(&self) -> usize {
         (*self.chart_cache)
             .as_ref()
-            .map(|c| c.chart.len())
+            .map(|c| c.chart.iter().filter(|n| n.is_judged()).count())
             .unwrap_or(0)
     }
 
@@ -188,12 +254,17 @@ impl MenuState {
         if let Some(cached) = self.difficulty_cache.get(&beatmap_hash, &calculator, rate) {
             return Some(cached.clone());
         }
+        let failure_key = (beatmap_hash.clone(), calculator.clone());
+        if self.failed_difficulty.contains(&failure_key) {
+            return None;
+        }
 
         // Load and calculate
         let map = match rosu_map::Beatmap::from_path(&beatmap_path) {
             Ok(map) => map,
             Err(err) => {
                 log::error!("MENU: Failed to load beatmap for difficulty calc: {}", err);
+                self.failed_difficulty.insert(failure_key);
                 return None;
             }
         };
@@ -206,6 +277,7 @@ impl MenuState {
             }
             Err(err) => {
                 log::error!("MENU: Failed to calculate difficulty: {}", err);
+                self.failed_difficulty.insert(failure_key);
                 None
             }
         }
@@ -285,6 +357,7 @@ impl MenuState {
 
             match difficulty::analyze_all_rates(&map) {
                 Ok(rate_data) => {
+                    self.tag_selected_beatmap(&rate_data);
                     let entry = RateCacheEntry::from_analysis(&beatmap_hash, rate_data);
                     let adjusted_rate = entry.closest_rate(self.rate);
                     if let Some(rate) = adjusted_rate {
@@ -308,6 +381,42 @@ impl MenuState {
         self.rate_cache.get(&beatmap_hash)
     }
 
+    /// Tags the selected chart with its dominant patterns at 1.0x, queuing
+    /// the tags for the database when they changed.
+    fn tag_selected_beatmap(&mut self, analysis: &difficulty::RateDifficultyCache) {
+        let Some(ssr) = analysis
+            .ratings_by_rate
+            .iter()
+            .find(|(rate, _)| (rate - 1.0).abs() < 0.001)
+            .and_then(|(_, values)| values.iter().find(|value| value.name == "etterna"))
+            .map(|value| &value.ssr)
+        else {
+            return;
+        };
+        let tags = pattern_tags::to_column(&pattern_tags::classify(ssr));
+
+        let set_index = self.selected_index;
+        let Some((_, beatmaps)) = self.beatmapsets.get(set_index) else {
+            return;
+        };
+        let difficulty_index = self
+            .selected_difficulty_index
+            .min(beatmaps.len().saturating_sub(1));
+        let Some(beatmap) = beatmaps.get(difficulty_index) else {
+            return;
+        };
+        if beatmap.beatmap.pattern_tags.as_deref() == Some(tags.as_str()) {
+            return;
+        }
+        let hash = beatmap.beatmap.hash.clone();
+        if let Some((_, beatmaps)) = Arc::make_mut(&mut self.beatmapsets).get_mut(set_index)
+            && let Some(beatmap) = beatmaps.get_mut(difficulty_index)
+        {
+            beatmap.beatmap.pattern_tags = Some(tags.clone());
+        }
+        self.new_pattern_tags.push((hash, tags));
+    }
+
     pub async fn load_from_db(
         menu_state: Arc<Mutex<Self>>,
         db: &Database,
@@ -325,6 +434,7 @@ impl MenuState {
             state.start_index = 0;
             Arc::make_mut(&mut state.rate_cache).clear();
             state.failed_rate_hashes.clear();
+            state.failed_difficulty.clear();
             state.difficulty_cache.clear();
             state.rate = 1.0;
             state.search_filters = MenuSearchFilters::default();
@@ -355,6 +465,69 @@ impl MenuState {
         }
     }
 
+    /// Selects the beatmapset at `idx` and scrolls it into the visible window.
+    pub fn select_beatmapset(&mut self, idx: usize) {
+        if idx >= self.beatmapsets.len() {
+            return;
+        }
+        self.selected_index = idx;
+        self.selected_difficulty_index = 0;
+        if idx < self.start_index {
+            self.start_index = idx;
+            self.end_index = (self.start_index + self.visible_count).min(self.beatmapsets.len());
+        } else if idx >= self.end_index {
+            self.end_index = (idx + 1).min(self.beatmapsets.len());
+            self.start_index = self.end_index.saturating_sub(self.visible_count);
+        }
+    }
+
+    /// Selects a random set (other than the current one when possible).
+    pub fn pick_random(&mut self) -> bool {
+        let count = self.beatmapsets.len();
+        if count == 0 {
+            return false;
+        }
+        let mut rng = rand::rng();
+        let mut idx = rng.random_range(0..count);
+        if count > 1 && idx == self.selected_index {
+            idx = (idx + rng.random_range(1..count)) % count;
+        }
+        self.select_picked(idx, 0);
+        true
+    }
+
+    /// Selects one of the charts whose 1.0x rating with the active calculator
+    /// is closest to `target`, and returns that rating.
+    pub fn pick_recommended(&mut self, target: f64) -> Option<f64> {
+        let calculator = &self.active_calculator;
+        let mut candidates: Vec<(usize, usize, f64)> = self
+            .beatmapsets
+            .iter()
+            .enumerate()
+            .flat_map(|(set_idx, (_, beatmaps))| {
+                beatmaps
+                    .iter()
+                    .enumerate()
+                    .filter_map(move |(diff_idx, bm)| {
+                        let rating = bm.ratings.iter().find(|r| &r.name == calculator)?;
+                        Some((set_idx, diff_idx, rating.overall))
+                    })
+            })
+            .collect();
+        candidates.sort_by(|a, b| (a.2 - target).abs().total_cmp(&(b.2 - target).abs()));
+        candidates.truncate(RECOMMEND_CANDIDATES);
+
+        let &(set_idx, diff_idx, rating) = candidates.choose(&mut rand::rng())?;
+        self.select_picked(set_idx, diff_idx);
+        Some(rating)
+    }
+
+    fn select_picked(&mut self, set_idx: usize, diff_idx: usize) {
+        self.select_beatmapset(set_idx);
+        self.selected_difficulty_index = diff_idx;
+        self.highlight = Some((set_idx, Instant::now()));
+    }
+
     pub fn move_up(&mut self) {
         if self.beatmapsets.is_empty() {
             return;
@@ -401,11 +574,25 @@ impl MenuState {
         })
     }
 
+    /// Why the selected chart failed to load last time, if it did.
+    pub fn get_selected_load_error(&self) -> Option<&str> {
+        self.get_selected_beatmap()
+            .and_then(|bm| bm.beatmap.load_error.as_deref())
+    }
+
     pub fn get_selected_beatmap_path(&self) -> Option<PathBuf> {
         self.get_selected_beatmap()
             .map(|bm| PathBuf::from(&bm.beatmap.path))
     }
 
+    /// File of a loaded beatmap.
+    pub fn beatmap_path(&self, hash: &str) -> Option<PathBuf> {
+        self.beatmapsets.iter().find_map(|(_, beatmaps)| {
+            let bm = beatmaps.iter().find(|bm| bm.beatmap.hash == hash)?;
+            Some(PathBuf::from(&bm.beatmap.path))
+        })
+    }
+
     pub fn next_difficulty(&mut self) {
         if let Some((_, beatmaps)) = self.get_selected_beatmapset() {
             if beatmaps.is_empty() {
@@ -439,6 +626,24 @@ impl MenuState {
             .map(|bm| bm.beatmap.hash.clone())
     }
 
+    /// "Artist - Title [Difficulty]" of a loaded beatmap, for display/sharing.
+    pub fn beatmap_label(&self, hash: &str) -> Option<String> {
+        self.beatmapsets.iter().find_map(|(set, beatmaps)| {
+            let bm = beatmaps.iter().find(|bm| bm.beatmap.hash == hash)?;
+            let mut label = format!(
+                "{} - {}",
+                set.display_artist(self.unicode_metadata)
+                    .unwrap_or("Unknown"),
+                set.display_title(self.unicode_metadata)
+                    .unwrap_or("Unknown")
+            );
+            if let Some(diff) = &bm.beatmap.difficulty_name {
+                label.push_str(&format!(" [{}]", diff));
+            }
+            Some(label)
+        })
+    }
+
     pub fn set_leaderboard(&mut self, hash: Option<String>, scores: Vec<Replay>) {
         self.leaderboard_hash = hash;
         self.leaderboard_scores = scores;
@@ -453,6 +658,14 @@ impl MenuState {
         }
     }
 
+    /// Whether the active calculator is a community script, rated on demand
+    /// instead of through the rate cache.
+    pub fn uses_script_calculator(&self) -> bool {
+        difficulty::script::script_calculators()
+            .iter()
+            .any(|script| script.id == self.active_calculator)
+    }
+
     /// Gets the available calculators.
     pub fn available_calculators(&self) -> Vec<(&'static str, &'static str)> {
         vec![("etterna", "Etterna (MinaCalc)"), ("osu", "osu! (rosu-pp)")]
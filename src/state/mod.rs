@@ -20,5 +20,5 @@ pub use editor::EditorState;
 pub use game::GameEngine;
 pub use global::GlobalState;
 pub use menu::{ChartCache, DifficultyCache, MenuState, RateCacheEntry};
-pub use result::GameResultData;
+pub use result::{CourseStageResult, CourseSummary, GameResultData};
 pub use traits::{ActionContext, HandleAction, Snapshot, Transition, Update};
@@ -2,8 +2,9 @@
 
 pub mod actions;
 
+use crate::models::personal_best::PbResult;
 use crate::models::replay::{ReplayData, ReplayResult};
-use crate::models::stats::HitStats;
+use crate::models::stats::{DriftStats, HitStats};
 
 /// Données complètes d'un résultat de partie.
 #[derive(Clone, Debug, PartialEq)]
@@ -17,7 +18,76 @@ pub struct GameResultData {
     pub accuracy: f64,
     pub max_combo: u32,
     pub beatmap_hash: Option<String>,
+    /// "Artist - Title [Difficulty]" (course name for courses), empty if unknown.
+    pub map_title: String,
     pub rate: f64,
     pub judge_text: String,
     pub show_settings: bool,
+    /// Audio drift measured during the play (`None` for stored replays).
+    pub drift_stats: Option<Box<DriftStats>>,
+    /// Per-stage breakdown when this result aggregates a course.
+    pub course: Option<Box<CourseSummary>>,
+    /// Comparison with the previous best at this rate and judge (`None` for
+    /// stored replays, courses and plays that are not saved).
+    pub personal_best: Option<PbResult>,
+    /// Run quit before the end, judged up to the quit point and not saved.
+    pub incomplete: bool,
+}
+
+impl GameResultData {
+    /// Fills a share template (see `SettingsState::share_template`).
+    ///
+    /// Placeholders: `{map}`, `{rate}`, `{judge}`, `{score}`, `{acc}`,
+    /// `{combo}` and `{judgements}` (marv/perf/great/good/bad/miss).
+    pub fn share_text(&self, template: &str) -> String {
+        let judge = self
+            .replay_data
+            .hit_window_mode
+            .label(self.replay_data.hit_window_value);
+        let s = &self.hit_stats;
+        let judgements = format!(
+            "{}/{}/{}/{}/{}/{}",
+            s.marv, s.perfect, s.great, s.good, s.bad, s.miss
+        );
+        let map = if self.map_title.is_empty() {
+            "Unknown map"
+        } else {
+            &self.map_title
+        };
+        template
+            .replace("{map}", map)
+            .replace("{rate}", &format!("{:.2}", self.rate))
+            .replace("{judge}", &judge)
+            .replace("{score}", &self.score.to_string())
+            .replace("{acc}", &format!("{:.2}", self.accuracy))
+            .replace("{combo}", &self.max_combo.to_string())
+            .replace("{judgements}", &judgements)
+    }
+}
+
+/// Aggregated outcome of a course run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CourseSummary {
+    pub name: String,
+    pub stages: Vec<CourseStageResult>,
+    /// Number of charts in the course (stages may stop early on fail).
+    pub total_stages: usize,
+    pub final_health: f64,
+}
+
+impl CourseSummary {
+    /// A course is cleared when every chart was played without running out of HP.
+    pub fn is_cleared(&self) -> bool {
+        self.stages.len() == self.total_stages && self.stages.iter().all(|s| !s.failed)
+    }
+}
+
+/// Result of a single chart inside a course.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CourseStageResult {
+    pub label: String,
+    pub score: u32,
+    pub accuracy: f64,
+    pub max_combo: u32,
+    pub failed: bool,
 }
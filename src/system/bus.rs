@@ -4,11 +4,15 @@
 //! in the application, using lock-free channels for high-performance message passing.
 
 use crate::input::events::{GameAction, InputCommand, RawInputEvent};
+use crate::models::settings::Volumes;
+use crate::shared::audio_clock::AudioClock;
+use crate::shared::notice::Notice;
 use crate::shared::snapshot::RenderState;
 use crossbeam_channel::{Receiver, Sender, bounded, unbounded};
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::sync::atomic::AtomicU64;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
 
 /// System-level events broadcast to all threads.
 #[derive(Debug, Clone)]
@@ -38,8 +42,13 @@ pub enum AudioCommand {
     Seek { position_secs: f32 },
     /// Change playback speed.
     SetSpeed { speed: f32 },
-    /// Change volume level.
-    SetVolume { volume: f32 },
+    /// Change the master, music and sound effect volumes.
+    SetVolume(Volumes),
+    /// Keep the original pitch when the speed changes (time stretch)
+    /// instead of resampling.
+    SetTimeStretch { enabled: bool },
+    /// Play a short UI sound (skin sound effect) over the music.
+    PlaySound { path: PathBuf },
 }
 
 /// Aggregates the cross-thread communication channels.
@@ -53,10 +62,24 @@ pub enum AudioCommand {
 /// - Audio commands to the audio thread
 #[derive(Clone)]
 pub struct SystemBus {
-    /// Main → Input: raw keyboard events.
+    /// Main / Gamepad → Input: raw key, mouse and gamepad events.
     pub raw_input_tx: Sender<RawInputEvent>,
     pub raw_input_rx: Receiver<RawInputEvent>,
 
+    /// Gamepad → Main: labels of pressed gamepad buttons, for keybind
+    /// capture in the settings window.
+    pub pad_press_tx: Sender<&'static str>,
+    pub pad_press_rx: Receiver<&'static str>,
+
+    /// Any thread → Main: messages shown to the player as toasts.
+    pub toast_tx: Sender<Notice>,
+    pub toast_rx: Receiver<Notice>,
+
+    /// Logic → Main: library scan or import progress as `(done, total)`,
+    /// `None` once finished. Shown in the window title.
+    pub scan_progress_tx: Sender<Option<(usize, usize)>>,
+    pub scan_progress_rx: Receiver<Option<(usize, usize)>>,
+
     /// Commands sent to the input thread.
     pub input_cmd_tx: Sender<InputCommand>,
     pub input_cmd_rx: Receiver<InputCommand>,
@@ -77,21 +100,24 @@ pub struct SystemBus {
     pub audio_cmd_tx: Sender<AudioCommand>,
     pub audio_cmd_rx: Receiver<AudioCommand>,
 
-    /// Shared audio position in samples.
+    /// Playback position of the music.
     /// Written by the audio thread, read by the logic thread.
-    pub audio_position: Arc<AtomicU64>,
-
-    /// Current audio sample rate.
-    pub audio_sample_rate: Arc<AtomicU64>,
+    pub audio_clock: Arc<AudioClock>,
 
-    /// Number of audio channels.
-    pub audio_channels: Arc<AtomicU64>,
+    /// Reference point for `last_key_press_ns`.
+    pub epoch: Instant,
+    /// When the window last received a key press, in ns since `epoch`.
+    /// Written by the main thread, read by the logic thread for latency display.
+    pub last_key_press_ns: Arc<AtomicU64>,
 }
 
 impl SystemBus {
     /// Creates a new system bus with all channels initialized.
     pub fn new() -> Self {
         let (raw_input_tx, raw_input_rx) = unbounded();
+        let (pad_press_tx, pad_press_rx) = unbounded();
+        let (toast_tx, toast_rx) = unbounded();
+        let (scan_progress_tx, scan_progress_rx) = unbounded();
         let (input_cmd_tx, input_cmd_rx) = unbounded();
         let (action_tx, action_rx) = unbounded();
 
@@ -104,6 +130,12 @@ impl SystemBus {
         Self {
             raw_input_tx,
             raw_input_rx,
+            pad_press_tx,
+            pad_press_rx,
+            toast_tx,
+            toast_rx,
+            scan_progress_tx,
+            scan_progress_rx,
             input_cmd_tx,
             input_cmd_rx,
             action_tx,
@@ -114,11 +146,24 @@ impl SystemBus {
             sys_rx,
             audio_cmd_tx,
             audio_cmd_rx,
-            audio_position: Arc::new(AtomicU64::new(0)),
-            audio_sample_rate: Arc::new(AtomicU64::new(44100)),
-            audio_channels: Arc::new(AtomicU64::new(2)),
+            audio_clock: Arc::new(AudioClock::new()),
+            epoch: Instant::now(),
+            last_key_press_ns: Arc::new(AtomicU64::new(0)),
         }
     }
+
+    /// Records that a key was pressed just now.
+    pub fn stamp_key_press(&self) {
+        let ns = self.epoch.elapsed().as_nanos() as u64;
+        self.last_key_press_ns.store(ns, Ordering::Relaxed);
+    }
+
+    /// Milliseconds elapsed since the last recorded key press.
+    pub fn key_press_age_ms(&self) -> f64 {
+        let pressed_ns = self.last_key_press_ns.load(Ordering::Relaxed);
+        let now_ns = self.epoch.elapsed().as_nanos() as u64;
+        now_ns.saturating_sub(pressed_ns) as f64 / 1_000_000.0
+    }
 }
 
 impl Default for SystemBus {
@@ -1 +1,2 @@
 pub mod bus;
+pub mod supervisor;
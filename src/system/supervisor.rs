@@ -0,0 +1,132 @@
+//! Worker thread supervision.
+//!
+//! The bus keeps both ends of every channel alive, so a dead worker is not
+//! noticed by the threads talking to it: its messages simply pile up. The
+//! supervisor watches the join handles instead. When a watched thread panics
+//! it logs the panic, spawns a fresh instance (up to `MAX_RESTARTS` times)
+//! and tells the player through a toast.
+
+use crate::shared::locale::tr_args;
+use crate::shared::notice::Notice;
+use crate::system::bus::SystemBus;
+use std::any::Any;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Restarts allowed per thread before giving up on it.
+const MAX_RESTARTS: u32 = 3;
+/// How often the handles are checked.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Spawns one instance of a worker thread.
+type Spawner = Box<dyn Fn() -> JoinHandle<()> + Send>;
+
+struct Watched {
+    name: &'static str,
+    spawn: Spawner,
+    handle: Option<JoinHandle<()>>,
+    restarts: u32,
+}
+
+/// Collects the threads to watch, then runs the watch loop on its own thread.
+pub struct Supervisor {
+    bus: SystemBus,
+    watched: Vec<Watched>,
+}
+
+impl Supervisor {
+    pub fn new(bus: SystemBus) -> Self {
+        Self {
+            bus,
+            watched: Vec::new(),
+        }
+    }
+
+    /// Starts a thread with `spawn` and restarts it the same way if it panics.
+    pub fn watch(
+        &mut self,
+        name: &'static str,
+        spawn: impl Fn() -> JoinHandle<()> + Send + 'static,
+    ) {
+        let handle = spawn();
+        self.watched.push(Watched {
+            name,
+            spawn: Box::new(spawn),
+            handle: Some(handle),
+            restarts: 0,
+        });
+    }
+
+    /// Moves the supervisor to its own thread.
+    pub fn start(mut self) {
+        thread::Builder::new()
+            .name("Supervisor Thread".to_string())
+            .spawn(move || {
+                loop {
+                    for watched in &mut self.watched {
+                        check(watched, &self.bus);
+                    }
+                    thread::sleep(POLL_INTERVAL);
+                }
+            })
+            .expect("Failed to spawn Supervisor thread");
+    }
+}
+
+/// Restarts `watched` if its thread died from a panic.
+fn check(watched: &mut Watched, bus: &SystemBus) {
+    if !watched.handle.as_ref().is_some_and(|h| h.is_finished()) {
+        return;
+    }
+    let Some(handle) = watched.handle.take() else {
+        return;
+    };
+    let payload = match handle.join() {
+        Ok(()) => {
+            log::info!("SUPERVISOR: {} thread exited", watched.name);
+            return;
+        }
+        Err(payload) => payload,
+    };
+
+    log::error!(
+        "SUPERVISOR: {} thread panicked: {}",
+        watched.name,
+        panic_message(payload.as_ref())
+    );
+    if watched.restarts >= MAX_RESTARTS {
+        log::error!(
+            "SUPERVISOR: {} thread crashed {} times, not restarting",
+            watched.name,
+            watched.restarts + 1
+        );
+        let _ = bus.toast_tx.send(Notice::error(tr_args(
+            "notice.thread_stopped",
+            &[("thread", &watched.name)],
+        )));
+        return;
+    }
+
+    watched.restarts += 1;
+    watched.handle = Some((watched.spawn)());
+    log::warn!(
+        "SUPERVISOR: {} thread restarted ({}/{})",
+        watched.name,
+        watched.restarts,
+        MAX_RESTARTS
+    );
+    let _ = bus.toast_tx.send(Notice::error(tr_args(
+        "notice.thread_restarted",
+        &[("thread", &watched.name)],
+    )));
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s
+    } else {
+        "unknown panic"
+    }
+}
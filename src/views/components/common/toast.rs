@@ -0,0 +1,109 @@
+//! Short-lived notifications stacked in the top-right corner.
+
+use crate::models::skin::common::Color;
+use crate::models::skin::menus::PanelStyleConfig;
+use crate::shared::notice::{Notice, NoticeLevel};
+use egui::{Align2, Color32, RichText, Stroke};
+use std::time::{Duration, Instant};
+
+/// How long a toast stays on screen.
+const TOAST_DURATION: Duration = Duration::from_secs(6);
+/// Errors stay longer, they usually ask the player to do something.
+const ERROR_DURATION: Duration = Duration::from_secs(10);
+/// Fade-out at the end of the toast's duration.
+const TOAST_FADE: Duration = Duration::from_millis(800);
+/// Toasts on screen at once; the oldest go first.
+const MAX_TOASTS: usize = 5;
+
+/// Queue of messages shown over every screen.
+#[derive(Default)]
+pub struct Toasts {
+    items: Vec<(Notice, Instant)>,
+}
+
+impl Toasts {
+    pub fn push(&mut self, notice: impl Into<Notice>) {
+        let notice = notice.into();
+        // A repeated message restarts its toast instead of stacking a copy.
+        self.items.retain(|(shown, _)| *shown != notice);
+        self.items.push((notice, Instant::now()));
+        if self.items.len() > MAX_TOASTS {
+            self.items.drain(..self.items.len() - MAX_TOASTS);
+        }
+    }
+
+    /// Draws the live toasts with the skin's panel colors and drops the
+    /// expired ones.
+    pub fn render(&mut self, ctx: &egui::Context, style: &PanelStyleConfig) {
+        self.items
+            .retain(|(notice, shown_at)| shown_at.elapsed() < duration(notice.level));
+        if self.items.is_empty() {
+            return;
+        }
+
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(Align2::RIGHT_TOP, [-16.0, 16.0])
+            .interactable(false)
+            .show(ctx, |ui| {
+                for (notice, shown_at) in &self.items {
+                    let left = duration(notice.level).saturating_sub(shown_at.elapsed());
+                    let alpha = (left.as_secs_f32() / TOAST_FADE.as_secs_f32()).min(1.0);
+                    let border = match notice.level {
+                        NoticeLevel::Info => style.accent,
+                        NoticeLevel::Success => style.success,
+                        NoticeLevel::Error => style.error,
+                    };
+                    egui::Frame::default()
+                        .fill(faded(style.background, alpha))
+                        .stroke(Stroke::new(1.0, faded(border, alpha)))
+                        .corner_radius(6.0)
+                        .inner_margin(10.0)
+                        .show(ui, |ui| {
+                            ui.set_max_width(320.0);
+                            ui.label(
+                                RichText::new(&notice.text).color(faded(style.text_primary, alpha)),
+                            );
+                        });
+                    ui.add_space(6.0);
+                }
+            });
+    }
+}
+
+fn duration(level: NoticeLevel) -> Duration {
+    match level {
+        NoticeLevel::Error => ERROR_DURATION,
+        _ => TOAST_DURATION,
+    }
+}
+
+fn faded(c: Color, alpha: f32) -> Color32 {
+    Color32::from_rgba_unmultiplied(
+        (c[0] * 255.0) as u8,
+        (c[1] * 255.0) as u8,
+        (c[2] * 255.0) as u8,
+        (c[3] * alpha * 255.0) as u8,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_newest_toasts_once() {
+        let mut toasts = Toasts::default();
+        for i in 0..MAX_TOASTS + 2 {
+            toasts.push(format!("notice {}", i));
+        }
+        toasts.push(format!("notice {}", MAX_TOASTS));
+
+        let texts: Vec<_> = toasts.items.iter().map(|(n, _)| n.text.as_str()).collect();
+        assert_eq!(texts.len(), MAX_TOASTS);
+        assert_eq!(texts.first(), Some(&"notice 2"));
+        assert_eq!(
+            texts.last(),
+            Some(&format!("notice {}", MAX_TOASTS).as_str())
+        );
+    }
+}
@@ -0,0 +1,130 @@
+//! Historique annuler / rétablir de l'éditeur de skin.
+//!
+//! Chaque modification d'un élément (position, taille, couleur, image...)
+//! est enregistrée comme une commande contenant l'état du skin avant et
+//! après. Un glissement ou un DragValue modifie le skin à chaque frame : les
+//! changements sont regroupés en une seule commande jusqu'au relâchement de
+//! la souris.
+
+use crate::models::skin::Skin;
+
+/// Nombre maximal de commandes conservées.
+const MAX_HISTORY: usize = 100;
+
+/// Une modification d'un élément du skin.
+pub struct SkinEdit {
+    /// Élément modifié (id de l'inspecteur).
+    pub element: String,
+    before: Skin,
+    after: Skin,
+}
+
+pub struct EditHistory {
+    undo: Vec<SkinEdit>,
+    redo: Vec<SkinEdit>,
+    /// État du skin après la dernière commande enregistrée.
+    baseline: Option<Skin>,
+    /// Élément en cours de modification, pas encore enregistré.
+    pending: Option<String>,
+}
+
+impl EditHistory {
+    pub fn new() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            baseline: None,
+            pending: None,
+        }
+    }
+
+    /// Signale que `element` vient d'être modifié.
+    pub fn touch(&mut self, element: &str) {
+        if self.pending.is_none() {
+            self.pending = Some(element.to_string());
+        }
+    }
+
+    /// Enregistre la modification en cours une fois l'interaction terminée.
+    pub fn settle(&mut self, skin: &Skin, interacting: bool) {
+        if self.baseline.is_none() {
+            self.baseline = Some(skin.clone());
+        }
+        if !interacting {
+            self.commit(skin);
+        }
+    }
+
+    fn commit(&mut self, skin: &Skin) {
+        let Some(element) = self.pending.take() else {
+            return;
+        };
+        let before = self
+            .baseline
+            .replace(skin.clone())
+            .unwrap_or_else(|| skin.clone());
+        self.undo.push(SkinEdit {
+            element,
+            before,
+            after: skin.clone(),
+        });
+        if self.undo.len() > MAX_HISTORY {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// Annule la dernière commande. Renvoie `true` si le skin a changé.
+    pub fn undo(&mut self, skin: &mut Skin) -> bool {
+        self.commit(skin);
+        let Some(edit) = self.undo.pop() else {
+            return false;
+        };
+        *skin = edit.before.clone();
+        self.baseline = Some(edit.before.clone());
+        self.redo.push(edit);
+        true
+    }
+
+    /// Rétablit la dernière commande annulée. Renvoie `true` si le skin a changé.
+    pub fn redo(&mut self, skin: &mut Skin) -> bool {
+        self.commit(skin);
+        let Some(edit) = self.redo.pop() else {
+            return false;
+        };
+        *skin = edit.after.clone();
+        self.baseline = Some(edit.after.clone());
+        self.undo.push(edit);
+        true
+    }
+
+    /// Annule ou rétablit jusqu'à ce que `applied` commandes soient appliquées.
+    pub fn jump_to(&mut self, applied: usize, skin: &mut Skin) -> bool {
+        let mut changed = false;
+        while self.undo.len() > applied && self.undo(skin) {
+            changed = true;
+        }
+        while self.undo.len() < applied && self.redo(skin) {
+            changed = true;
+        }
+        changed
+    }
+
+    /// Commandes appliquées, de la plus ancienne à la plus récente.
+    pub fn applied(&self) -> &[SkinEdit] {
+        &self.undo
+    }
+
+    /// Commandes annulées, la prochaine à rétablir en dernier.
+    pub fn undone(&self) -> &[SkinEdit] {
+        &self.redo
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty() || self.pending.is_some()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
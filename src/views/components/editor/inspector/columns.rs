@@ -4,6 +4,7 @@
 
 use super::common::*;
 use crate::models::skin::Skin;
+use crate::shared::locale::{tr, tr_args};
 use egui::Ui;
 
 /// Edit columns for a specific keymode
@@ -14,7 +15,10 @@ pub fn edit_columns(ui: &mut Ui, skin: &mut Skin, keymode: usize) -> bool {
     // Get or create the keymode config from the HashMap
     let km_config = skin.key_modes.entry(keymode).or_default();
 
-    section_header(ui, &format!("🎹 {}K Column Configuration", keymode));
+    section_header(
+        ui,
+        &tr_args("editor.section.column_configuration", &[("keys", &keymode)]),
+    );
 
     // Ensure we have enough entries for each column
     while km_config.notes.len() < keymode {
@@ -26,40 +30,51 @@ pub fn edit_columns(ui: &mut Ui, skin: &mut Skin, keymode: usize) -> bool {
 
     // Edit each column
     for col in 0..keymode {
-        let col_name = format!("Column {} ({}K)", col + 1, keymode);
+        let col_name = tr_args(
+            "editor.field.column_name",
+            &[("column", &(col + 1)), ("keys", &keymode)],
+        );
 
         ui.collapsing(&col_name, |ui| {
             // Note image
-            section_header(ui, "🎵 Note");
+            section_header(ui, &tr("editor.section.note"));
             if let Some(note_cfg) = km_config.notes.get_mut(col) {
-                changed |=
-                    image_picker(ui, "Note Image", &mut note_cfg.image, Some(&skin.base_path));
-                changed |= color_edit(ui, "Note Color", &mut note_cfg.color);
+                changed |= image_picker(
+                    ui,
+                    &tr("editor.field.note_image"),
+                    &mut note_cfg.image,
+                    Some(&skin.base_path),
+                );
+                changed |= color_edit(ui, &tr("editor.field.note_color"), &mut note_cfg.color);
             }
 
             // Receptor images
-            section_header(ui, "⬇️ Receptor");
+            section_header(ui, &tr("editor.section.receptor"));
             if let Some(rec_cfg) = km_config.receptors.get_mut(col) {
                 changed |= image_picker(
                     ui,
-                    "Normal Image",
+                    &tr("editor.field.normal_image"),
                     &mut rec_cfg.image,
                     Some(&skin.base_path),
                 );
                 changed |= image_picker(
                     ui,
-                    "Pressed Image",
+                    &tr("editor.field.pressed_image"),
                     &mut rec_cfg.pressed_image,
                     Some(&skin.base_path),
                 );
-                changed |= color_edit(ui, "Normal Color", &mut rec_cfg.color);
-                changed |= color_edit(ui, "Pressed Color", &mut rec_cfg.pressed_color);
+                changed |= color_edit(ui, &tr("editor.field.normal_color"), &mut rec_cfg.color);
+                changed |= color_edit(
+                    ui,
+                    &tr("editor.field.pressed_color"),
+                    &mut rec_cfg.pressed_color,
+                );
             }
         });
     }
 
     ui.add_space(10.0);
-    hint(ui, "Each column can have different images and colors");
+    hint(ui, &tr("editor.hint.per_column"));
 
     changed
 }
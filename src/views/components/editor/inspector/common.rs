@@ -1,6 +1,17 @@
 //! Inspector submodule - common utilities for element editing
 
-use egui::{Color32, DragValue, RichText, Ui};
+use crate::models::skin::hud::{Anchor, DigitAlign, PositionUnits};
+use crate::shared::locale::tr;
+use egui::{Color32, Context, DragValue, Id, RichText, Stroke, StrokeKind, Ui};
+use std::path::{Path, PathBuf};
+
+/// Image formats accepted by the skin loader.
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg"];
+
+/// Frame on which a picker took the dropped image.
+const DROP_CLAIMED_ID: &str = "inspector_drop_claimed";
+/// Image dropped outside of any picker, handed to the first picker next frame.
+const DROP_PENDING_ID: &str = "inspector_drop_pending";
 
 /// Helper to edit a color
 pub fn color_edit(ui: &mut Ui, label: &str, color: &mut [f32; 4]) -> bool {
@@ -11,25 +22,83 @@ pub fn color_edit(ui: &mut Ui, label: &str, color: &mut [f32; 4]) -> bool {
     .inner
 }
 
+/// Helper to edit an opacity (0 = hidden, 1 = opaque)
+pub fn opacity_edit(ui: &mut Ui, opacity: &mut f32) -> bool {
+    ui.horizontal(|ui| {
+        ui.label(tr("editor.field.opacity"));
+        ui.add(egui::Slider::new(opacity, 0.0..=1.0)).changed()
+    })
+    .inner
+}
+
+/// Helper to edit the spacing and alignment of digit sprites
+pub fn digit_layout_edit(ui: &mut Ui, spacing: &mut f32, align: &mut DigitAlign) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label(tr("editor.field.spacing"));
+        changed |= ui.add(DragValue::new(spacing).speed(0.5)).changed();
+    });
+    egui::ComboBox::from_label(tr("editor.field.align"))
+        .selected_text(format!("{:?}", align))
+        .show_ui(ui, |ui| {
+            for option in [DigitAlign::Left, DigitAlign::Center, DigitAlign::Right] {
+                changed |= ui
+                    .selectable_value(align, option, format!("{:?}", option))
+                    .changed();
+            }
+        });
+    hint(ui, &tr("editor.hint.digit_sprites"));
+    changed
+}
+
 /// Helper to edit position X/Y
 pub fn position_edit(ui: &mut Ui, x: &mut f32, y: &mut f32) -> bool {
     let mut changed = false;
     ui.horizontal(|ui| {
-        ui.label("X");
+        ui.label(tr("editor.field.x"));
         changed |= ui.add(DragValue::new(x).speed(1.0).suffix("px")).changed();
-        ui.label("Y");
+        ui.label(tr("editor.field.y"));
         changed |= ui.add(DragValue::new(y).speed(1.0).suffix("px")).changed();
     });
     changed
 }
 
+/// Helper to edit the anchor and units of a position
+pub fn anchor_edit(ui: &mut Ui, anchor: &mut Anchor, units: &mut PositionUnits) -> bool {
+    let mut changed = false;
+    egui::ComboBox::from_label(tr("editor.field.anchor"))
+        .selected_text(anchor.label())
+        .show_ui(ui, |ui| {
+            for option in Anchor::ALL {
+                changed |= ui
+                    .selectable_value(anchor, option, option.label())
+                    .changed();
+            }
+        });
+    ui.horizontal(|ui| {
+        ui.label(tr("editor.field.units"));
+        changed |= ui
+            .selectable_value(units, PositionUnits::Pixels, tr("editor.field.pixels"))
+            .changed();
+        changed |= ui
+            .selectable_value(
+                units,
+                PositionUnits::Percent,
+                tr("editor.field.percent_of_screen"),
+            )
+            .changed();
+    });
+    hint(ui, &tr("editor.hint.anchor_offset"));
+    changed
+}
+
 /// Helper to edit size W/H
 pub fn size_edit(ui: &mut Ui, w: &mut f32, h: &mut f32) -> bool {
     let mut changed = false;
     ui.horizontal(|ui| {
-        ui.label("W");
+        ui.label(tr("editor.field.w"));
         changed |= ui.add(DragValue::new(w).speed(1.0)).changed();
-        ui.label("H");
+        ui.label(tr("editor.field.h"));
         changed |= ui.add(DragValue::new(h).speed(1.0)).changed();
     });
     changed
@@ -43,34 +112,21 @@ pub fn image_picker(
     dest_folder: Option<&std::path::Path>,
 ) -> bool {
     let mut changed = false;
-    ui.horizontal(|ui| {
-        if ui.button("📂").clicked() {
-            if let Some(path) = rfd::FileDialog::new()
-                .add_filter("Images", &["png", "jpg", "jpeg"])
+    let row = ui.horizontal(|ui| {
+        if ui.button("📂").clicked()
+            && let Some(path) = rfd::FileDialog::new()
+                .add_filter(tr("editor.field.images"), IMAGE_EXTENSIONS)
                 .pick_file()
-            {
-                // Get just the filename
-                if let Some(filename) = path.file_name() {
-                    let filename_str = filename.to_string_lossy().to_string();
-
-                    // Copy file if destination is provided
-                    if let Some(dest) = dest_folder {
-                        let dest_path = dest.join(filename);
-                        if let Err(e) = std::fs::copy(&path, &dest_path) {
-                            eprintln!("Failed to copy image: {}", e);
-                        }
-                    }
-
-                    *image = Some(filename_str);
-                    changed = true;
-                }
-            }
+            && let Some(filename) = import_file(&path, dest_folder)
+        {
+            *image = Some(filename);
+            changed = true;
         }
 
         let display = image
             .as_ref()
             .map(|s| s.split(['/', '\\']).last().unwrap_or(s).to_string())
-            .unwrap_or_else(|| "None".to_string());
+            .unwrap_or_else(|| tr("editor.field.none"));
 
         ui.label(format!("{}: {}", label, display));
 
@@ -79,9 +135,88 @@ pub fn image_picker(
             changed = true;
         }
     });
+
+    // Drag-and-drop from the file explorer
+    let over_row = ui.rect_contains_pointer(row.response.rect);
+    if over_row && ui.ctx().input(|i| !i.raw.hovered_files.is_empty()) {
+        ui.painter().rect_stroke(
+            row.response.rect.expand(2.0),
+            2.0,
+            Stroke::new(1.5, Color32::LIGHT_BLUE),
+            StrokeKind::Outside,
+        );
+    }
+    if let Some(path) = take_dropped_image(ui.ctx(), over_row)
+        && let Some(filename) = import_file(&path, dest_folder)
+    {
+        *image = Some(filename);
+        changed = true;
+    }
+
     changed
 }
 
+/// Copies `source` into `dest_folder` (when given) and returns the file name
+/// to store in the skin config.
+fn import_file(source: &Path, dest_folder: Option<&Path>) -> Option<String> {
+    let filename = source.file_name()?;
+    if let Some(dest) = dest_folder {
+        let dest_path = dest.join(filename);
+        // Copying a file onto itself would truncate it.
+        let same_file = matches!(
+            (source.canonicalize(), dest_path.canonicalize()),
+            (Ok(a), Ok(b)) if a == b
+        );
+        if !same_file && let Err(e) = std::fs::copy(source, &dest_path) {
+            eprintln!("Failed to copy file: {}", e);
+            return None;
+        }
+    }
+    Some(filename.to_string_lossy().to_string())
+}
+
+/// Image file dropped on the window this frame, if any.
+fn dropped_image(ctx: &Context) -> Option<PathBuf> {
+    ctx.input(|i| {
+        i.raw
+            .dropped_files
+            .iter()
+            .filter_map(|file| file.path.clone())
+            .find(|path| {
+                path.extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| {
+                        IMAGE_EXTENSIONS
+                            .iter()
+                            .any(|known| ext.eq_ignore_ascii_case(known))
+                    })
+            })
+    })
+}
+
+/// Takes the dropped image for a picker: the one dropped on its row, or one
+/// dropped elsewhere on the previous frame if no other picker took it yet.
+fn take_dropped_image(ctx: &Context, over_row: bool) -> Option<PathBuf> {
+    let pass = ctx.cumulative_pass_nr();
+    if over_row && let Some(path) = dropped_image(ctx) {
+        ctx.data_mut(|d| d.insert_temp(Id::new(DROP_CLAIMED_ID), pass));
+        return Some(path);
+    }
+    ctx.data_mut(|d| d.remove_temp::<PathBuf>(Id::new(DROP_PENDING_ID)))
+}
+
+/// Called once the selected element is drawn. An image dropped outside of
+/// every picker goes to the element's first image next frame (the pointer
+/// position is not always known while dragging files in).
+pub fn defer_unclaimed_drop(ctx: &Context) {
+    let pass = ctx.cumulative_pass_nr();
+    ctx.data_mut(|d| d.remove::<PathBuf>(Id::new(DROP_PENDING_ID)));
+    let claimed = ctx.data(|d| d.get_temp::<u64>(Id::new(DROP_CLAIMED_ID))) == Some(pass);
+    if !claimed && let Some(path) = dropped_image(ctx) {
+        ctx.data_mut(|d| d.insert_temp(Id::new(DROP_PENDING_ID), path));
+    }
+}
+
 /// Section header
 pub fn section_header(ui: &mut Ui, title: &str) {
     ui.add_space(8.0);
@@ -131,7 +266,7 @@ pub fn file_picker(
         let display = path_str
             .as_ref()
             .map(|s| s.split(['/', '\\']).last().unwrap_or(s).to_string())
-            .unwrap_or_else(|| "None".to_string());
+            .unwrap_or_else(|| tr("editor.field.none"));
 
         ui.label(format!("{}: {}", label, display));
 
@@ -2,41 +2,87 @@
 
 use super::common::*;
 use crate::models::skin::Skin;
+use crate::shared::locale::tr;
 use egui::Ui;
 
 pub fn edit_skin_info(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
 
-    section_header(ui, "ℹ️ Skin Information");
+    section_header(ui, &tr("editor.section.skin_information"));
     ui.horizontal(|ui| {
-        ui.label("Name");
+        ui.label(tr("editor.field.name"));
         changed |= ui.text_edit_singleline(&mut skin.general.name).changed();
     });
     ui.horizontal(|ui| {
-        ui.label("Version");
+        ui.label(tr("editor.field.version"));
         changed |= ui.text_edit_singleline(&mut skin.general.version).changed();
     });
     ui.horizontal(|ui| {
-        ui.label("Author");
+        ui.label(tr("editor.field.author"));
         changed |= ui.text_edit_singleline(&mut skin.general.author).changed();
     });
 
     changed
 }
 
+pub fn edit_capabilities(ui: &mut Ui, skin: &mut Skin) -> bool {
+    let mut changed = false;
+    let caps = &mut skin.general.capabilities;
+
+    section_header(ui, &tr("editor.section.capabilities"));
+    changed |= ui
+        .checkbox(&mut caps.menus, tr("editor.field.styles_menus"))
+        .changed();
+    changed |= ui
+        .checkbox(&mut caps.hud, tr("editor.field.styles_hud"))
+        .changed();
+    changed |= ui
+        .checkbox(
+            &mut caps.uses_animations,
+            tr("editor.field.uses_animations"),
+        )
+        .changed();
+    changed |= ui
+        .checkbox(
+            &mut caps.uses_custom_shaders,
+            tr("editor.field.uses_custom_shaders"),
+        )
+        .changed();
+
+    let mut min_version = caps.min_engine_version.clone().unwrap_or_default();
+    ui.horizontal(|ui| {
+        ui.label(tr("editor.field.min_game_version"));
+        if ui.text_edit_singleline(&mut min_version).changed() {
+            let trimmed = min_version.trim();
+            caps.min_engine_version = (!trimmed.is_empty()).then(|| trimmed.to_string());
+            changed = true;
+        }
+    });
+    hint(ui, &tr("editor.hint.unstyled_panels"));
+
+    for warning in caps.unsupported() {
+        ui.colored_label(
+            egui::Color32::from_rgb(255, 170, 60),
+            format!("⚠ {}", warning),
+        );
+    }
+
+    changed
+}
+
 pub fn edit_font(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
 
-    section_header(ui, "🔤 Font");
+    section_header(ui, &tr("editor.section.font"));
     changed |= file_picker(
         ui,
-        "Font File (.ttf)",
+        &tr("editor.field.font_file"),
         &mut skin.general.font,
         Some(&skin.base_path),
-        "Fonts",
+        &tr("editor.field.fonts"),
         &["ttf", "otf"],
     );
-    hint(ui, "Place font file in the skin folder");
+    hint(ui, &tr("editor.hint.font_location"));
 
     changed
 }
@@ -2,50 +2,64 @@
 
 use super::common::*;
 use crate::models::skin::Skin;
+use crate::shared::locale::tr;
 use egui::{DragValue, Ui};
 
 pub fn edit_score(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
 
-    section_header(ui, "📍 Position");
+    section_header(ui, &tr("editor.section.position"));
     changed |= position_edit(
         ui,
         &mut skin.hud.score.position.x,
         &mut skin.hud.score.position.y,
     );
+    changed |= anchor_edit(ui, &mut skin.hud.score.anchor, &mut skin.hud.score.units);
 
-    section_header(ui, "📐 Size & Scale");
+    section_header(ui, &tr("editor.section.size_scale"));
     changed |= size_edit(ui, &mut skin.hud.score.size.x, &mut skin.hud.score.size.y);
     ui.horizontal(|ui| {
-        ui.label("Text Scale");
+        ui.label(tr("editor.field.text_scale"));
         changed |= ui
             .add(DragValue::new(&mut skin.hud.score.scale).speed(0.5))
             .changed();
     });
 
-    section_header(ui, "🎨 Colors");
-    changed |= color_edit(ui, "Text Color", &mut skin.hud.score.color);
+    section_header(ui, &tr("editor.section.colors"));
+    changed |= color_edit(
+        ui,
+        &tr("editor.field.text_color"),
+        &mut skin.hud.score.color,
+    );
 
-    section_header(ui, "📝 Format");
+    section_header(ui, &tr("editor.section.format"));
     ui.horizontal(|ui| {
-        ui.label("Format");
+        ui.label(tr("editor.field.format"));
         changed |= ui
             .text_edit_singleline(&mut skin.hud.score.format)
             .changed();
     });
-    hint(ui, "Use {score} as placeholder");
+    hint(ui, &tr("editor.hint.score_placeholder"));
 
-    section_header(ui, "🖼️ Image (Optional)");
+    section_header(ui, &tr("editor.section.image_optional"));
     changed |= image_picker(
         ui,
-        "Background",
+        &tr("editor.field.background"),
         &mut skin.hud.score.image,
         Some(&skin.base_path),
     );
 
-    section_header(ui, "👁️ Visibility");
+    section_header(ui, &tr("editor.section.digit_sprites"));
+    changed |= digit_layout_edit(
+        ui,
+        &mut skin.hud.score.digit_spacing,
+        &mut skin.hud.score.digit_align,
+    );
+
+    section_header(ui, &tr("editor.section.visibility"));
+    changed |= opacity_edit(ui, &mut skin.hud.score.opacity);
     changed |= ui
-        .checkbox(&mut skin.hud.score.visible, "Visible")
+        .checkbox(&mut skin.hud.score.visible, tr("editor.field.visible"))
         .changed();
 
     changed
@@ -54,45 +68,58 @@ pub fn edit_score(ui: &mut Ui, skin: &mut Skin) -> bool {
 pub fn edit_combo(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
 
-    section_header(ui, "📍 Position");
+    section_header(ui, &tr("editor.section.position"));
     changed |= position_edit(
         ui,
         &mut skin.hud.combo.position.x,
         &mut skin.hud.combo.position.y,
     );
+    changed |= anchor_edit(ui, &mut skin.hud.combo.anchor, &mut skin.hud.combo.units);
 
-    section_header(ui, "📐 Size & Scale");
+    section_header(ui, &tr("editor.section.size_scale"));
     changed |= size_edit(ui, &mut skin.hud.combo.size.x, &mut skin.hud.combo.size.y);
     ui.horizontal(|ui| {
-        ui.label("Text Scale");
+        ui.label(tr("editor.field.text_scale"));
         changed |= ui
             .add(DragValue::new(&mut skin.hud.combo.scale).speed(0.5))
             .changed();
     });
 
-    section_header(ui, "🎨 Colors");
-    changed |= color_edit(ui, "Text Color", &mut skin.hud.combo.color);
+    section_header(ui, &tr("editor.section.colors"));
+    changed |= color_edit(
+        ui,
+        &tr("editor.field.text_color"),
+        &mut skin.hud.combo.color,
+    );
 
-    section_header(ui, "📝 Format");
+    section_header(ui, &tr("editor.section.format"));
     ui.horizontal(|ui| {
-        ui.label("Format");
+        ui.label(tr("editor.field.format"));
         changed |= ui
             .text_edit_singleline(&mut skin.hud.combo.format)
             .changed();
     });
-    hint(ui, "Use {combo} as placeholder");
+    hint(ui, &tr("editor.hint.combo_placeholder"));
 
-    section_header(ui, "🖼️ Image (Optional)");
+    section_header(ui, &tr("editor.section.image_optional"));
     changed |= image_picker(
         ui,
-        "Background",
+        &tr("editor.field.background"),
         &mut skin.hud.combo.image,
         Some(&skin.base_path),
     );
 
-    section_header(ui, "👁️ Visibility");
+    section_header(ui, &tr("editor.section.digit_sprites"));
+    changed |= digit_layout_edit(
+        ui,
+        &mut skin.hud.combo.digit_spacing,
+        &mut skin.hud.combo.digit_align,
+    );
+
+    section_header(ui, &tr("editor.section.visibility"));
+    changed |= opacity_edit(ui, &mut skin.hud.combo.opacity);
     changed |= ui
-        .checkbox(&mut skin.hud.combo.visible, "Visible")
+        .checkbox(&mut skin.hud.combo.visible, tr("editor.field.visible"))
         .changed();
 
     changed
@@ -101,49 +128,66 @@ pub fn edit_combo(ui: &mut Ui, skin: &mut Skin) -> bool {
 pub fn edit_accuracy(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
 
-    section_header(ui, "📍 Position");
+    section_header(ui, &tr("editor.section.position"));
     changed |= position_edit(
         ui,
         &mut skin.hud.accuracy.position.x,
         &mut skin.hud.accuracy.position.y,
     );
+    changed |= anchor_edit(
+        ui,
+        &mut skin.hud.accuracy.anchor,
+        &mut skin.hud.accuracy.units,
+    );
 
-    section_header(ui, "📐 Size & Scale");
+    section_header(ui, &tr("editor.section.size_scale"));
     changed |= size_edit(
         ui,
         &mut skin.hud.accuracy.size.x,
         &mut skin.hud.accuracy.size.y,
     );
     ui.horizontal(|ui| {
-        ui.label("Text Scale");
+        ui.label(tr("editor.field.text_scale"));
         changed |= ui
             .add(DragValue::new(&mut skin.hud.accuracy.scale).speed(0.5))
             .changed();
     });
 
-    section_header(ui, "🎨 Colors");
-    changed |= color_edit(ui, "Text Color", &mut skin.hud.accuracy.color);
+    section_header(ui, &tr("editor.section.colors"));
+    changed |= color_edit(
+        ui,
+        &tr("editor.field.text_color"),
+        &mut skin.hud.accuracy.color,
+    );
 
-    section_header(ui, "📝 Format");
+    section_header(ui, &tr("editor.section.format"));
     ui.horizontal(|ui| {
-        ui.label("Format");
+        ui.label(tr("editor.field.format"));
         changed |= ui
             .text_edit_singleline(&mut skin.hud.accuracy.format)
             .changed();
     });
-    hint(ui, "Use {accuracy} as placeholder");
+    hint(ui, &tr("editor.hint.accuracy_placeholder"));
 
-    section_header(ui, "🖼️ Image (Optional)");
+    section_header(ui, &tr("editor.section.image_optional"));
     changed |= image_picker(
         ui,
-        "Background",
+        &tr("editor.field.background"),
         &mut skin.hud.accuracy.image,
         Some(&skin.base_path),
     );
 
-    section_header(ui, "👁️ Visibility");
+    section_header(ui, &tr("editor.section.digit_sprites"));
+    changed |= digit_layout_edit(
+        ui,
+        &mut skin.hud.accuracy.digit_spacing,
+        &mut skin.hud.accuracy.digit_align,
+    );
+
+    section_header(ui, &tr("editor.section.visibility"));
+    changed |= opacity_edit(ui, &mut skin.hud.accuracy.opacity);
     changed |= ui
-        .checkbox(&mut skin.hud.accuracy.visible, "Visible")
+        .checkbox(&mut skin.hud.accuracy.visible, tr("editor.field.visible"))
         .changed();
 
     changed
@@ -152,42 +196,46 @@ pub fn edit_accuracy(ui: &mut Ui, skin: &mut Skin) -> bool {
 pub fn edit_nps(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
 
-    section_header(ui, "📍 Position");
+    section_header(ui, &tr("editor.section.position"));
     changed |= position_edit(
         ui,
         &mut skin.hud.nps.position.x,
         &mut skin.hud.nps.position.y,
     );
+    changed |= anchor_edit(ui, &mut skin.hud.nps.anchor, &mut skin.hud.nps.units);
 
-    section_header(ui, "📐 Size & Scale");
+    section_header(ui, &tr("editor.section.size_scale"));
     changed |= size_edit(ui, &mut skin.hud.nps.size.x, &mut skin.hud.nps.size.y);
     ui.horizontal(|ui| {
-        ui.label("Text Scale");
+        ui.label(tr("editor.field.text_scale"));
         changed |= ui
             .add(DragValue::new(&mut skin.hud.nps.scale).speed(0.5))
             .changed();
     });
 
-    section_header(ui, "🎨 Colors");
-    changed |= color_edit(ui, "Text Color", &mut skin.hud.nps.color);
+    section_header(ui, &tr("editor.section.colors"));
+    changed |= color_edit(ui, &tr("editor.field.text_color"), &mut skin.hud.nps.color);
 
-    section_header(ui, "📝 Format");
+    section_header(ui, &tr("editor.section.format"));
     ui.horizontal(|ui| {
-        ui.label("Format");
+        ui.label(tr("editor.field.format"));
         changed |= ui.text_edit_singleline(&mut skin.hud.nps.format).changed();
     });
-    hint(ui, "Use {nps} as placeholder");
+    hint(ui, &tr("editor.hint.nps_placeholder"));
 
-    section_header(ui, "🖼️ Image (Optional)");
+    section_header(ui, &tr("editor.section.image_optional"));
     changed |= image_picker(
         ui,
-        "Background",
+        &tr("editor.field.background"),
         &mut skin.hud.nps.image,
         Some(&skin.base_path),
     );
 
-    section_header(ui, "👁️ Visibility");
-    changed |= ui.checkbox(&mut skin.hud.nps.visible, "Visible").changed();
+    section_header(ui, &tr("editor.section.visibility"));
+    changed |= opacity_edit(ui, &mut skin.hud.nps.opacity);
+    changed |= ui
+        .checkbox(&mut skin.hud.nps.visible, tr("editor.field.visible"))
+        .changed();
 
     changed
 }
@@ -2,13 +2,15 @@
 
 use super::common::*;
 use crate::models::skin::Skin;
+use crate::models::skin::hud::FlashAnimation;
+use crate::shared::locale::{tr, tr_args};
 use egui::{DragValue, Ui};
 
 /// Edit ALL judgement flashes at once (position + size for all)
 pub fn edit_flash_all(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
 
-    section_header(ui, "📍 Default Position (applies to ALL flashes)");
+    section_header(ui, &tr("editor.section.default_position"));
 
     // Use marv as reference
     let ref_x = skin.hud.judgement.marv.position.x;
@@ -22,8 +24,13 @@ pub fn edit_flash_all(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut new_h = ref_h;
 
     let pos_changed = position_edit(ui, &mut new_x, &mut new_y);
+    changed |= anchor_edit(
+        ui,
+        &mut skin.hud.judgement.anchor,
+        &mut skin.hud.judgement.units,
+    );
 
-    section_header(ui, "📐 Default Size (applies to ALL flashes)");
+    section_header(ui, &tr("editor.section.default_size"));
     let size_changed = size_edit(ui, &mut new_w, &mut new_h);
 
     if pos_changed {
@@ -67,13 +74,19 @@ pub fn edit_flash_all(ui: &mut Ui, skin: &mut Skin) -> bool {
         changed = true;
     }
 
-    section_header(ui, "⏱️ Timing Indicator");
+    section_header(ui, &tr("editor.section.timing_indicator"));
     changed |= ui
-        .checkbox(&mut skin.hud.judgement.show_timing, "Show +/- (early/late)")
+        .checkbox(
+            &mut skin.hud.judgement.show_timing,
+            tr("editor.field.show_timing"),
+        )
         .changed();
-    hint(ui, "- = early hit, + = late hit");
+    hint(ui, &tr("editor.hint.timing_sign"));
+
+    hint(ui, &tr("editor.hint.all_flashes"));
 
-    hint(ui, "This moves/resizes all judgement flashes together");
+    section_header(ui, &tr("editor.section.visibility"));
+    changed |= opacity_edit(ui, &mut skin.hud.judgement.opacity);
 
     changed
 }
@@ -90,32 +103,73 @@ fn edit_judgement_flash(
     size_y: &mut f32,
     visible: &mut bool,
     image: &mut Option<String>,
+    animation: &mut FlashAnimation,
     dest_folder: Option<&std::path::Path>,
 ) -> bool {
     let mut changed = false;
 
-    section_header(ui, "📝 Label");
+    section_header(ui, &tr("editor.section.label"));
     ui.horizontal(|ui| {
-        ui.label("Text");
+        ui.label(tr("editor.field.text"));
         changed |= ui.text_edit_singleline(label).changed();
     });
-    hint(ui, &format!("Default: \"{}\"", name));
+    hint(
+        ui,
+        &tr_args("editor.hint.default_label", &[("label", &name)]),
+    );
 
-    section_header(ui, "📍 Position");
+    section_header(ui, &tr("editor.section.position"));
     changed |= position_edit(ui, pos_x, pos_y);
 
-    section_header(ui, "📐 Size");
+    section_header(ui, &tr("editor.section.size"));
     changed |= size_edit(ui, size_x, size_y);
 
-    section_header(ui, "🎨 Color");
-    changed |= color_edit(ui, "Flash Color", color);
+    section_header(ui, &tr("editor.section.color"));
+    changed |= color_edit(ui, &tr("editor.field.flash_color"), color);
+
+    section_header(ui, &tr("editor.section.image_optional"));
+    changed |= image_picker(
+        ui,
+        &tr("editor.field.replace_with_image"),
+        image,
+        dest_folder,
+    );
+    hint(ui, &tr("editor.hint.image_replaces_text"));
 
-    section_header(ui, "🖼️ Image (Optional)");
-    changed |= image_picker(ui, "Replace with image", image, dest_folder);
-    hint(ui, "If set, image replaces text");
+    section_header(ui, &tr("editor.section.animation"));
+    ui.horizontal(|ui| {
+        ui.label(tr("editor.field.scale"));
+        changed |= ui
+            .add(
+                DragValue::new(&mut animation.scale)
+                    .speed(0.01)
+                    .range(0.1..=5.0),
+            )
+            .changed();
+        ui.label(tr("editor.field.pop"));
+        changed |= ui
+            .add(
+                DragValue::new(&mut animation.pop)
+                    .speed(0.01)
+                    .range(0.1..=5.0),
+            )
+            .changed();
+    });
+    ui.horizontal(|ui| {
+        ui.label(tr("editor.field.fade"));
+        changed |= ui
+            .add(
+                DragValue::new(&mut animation.fade_ms)
+                    .speed(5.0)
+                    .range(0.0..=2000.0)
+                    .suffix(" ms"),
+            )
+            .changed();
+    });
+    hint(ui, &tr("editor.hint.flash_animation"));
 
-    section_header(ui, "👁️ Visibility");
-    changed |= ui.checkbox(visible, "Visible").changed();
+    section_header(ui, &tr("editor.section.visibility"));
+    changed |= ui.checkbox(visible, tr("editor.field.visible")).changed();
 
     changed
 }
@@ -132,6 +186,7 @@ pub fn edit_marvelous(ui: &mut Ui, skin: &mut Skin) -> bool {
         &mut skin.hud.judgement.marv.size.y,
         &mut skin.hud.judgement.marv.visible,
         &mut skin.hud.judgement.marv.image,
+        &mut skin.hud.judgement.marv.animation,
         Some(&skin.base_path),
     )
 }
@@ -148,6 +203,7 @@ pub fn edit_perfect(ui: &mut Ui, skin: &mut Skin) -> bool {
         &mut skin.hud.judgement.perfect.size.y,
         &mut skin.hud.judgement.perfect.visible,
         &mut skin.hud.judgement.perfect.image,
+        &mut skin.hud.judgement.perfect.animation,
         Some(&skin.base_path),
     )
 }
@@ -164,6 +220,7 @@ pub fn edit_great(ui: &mut Ui, skin: &mut Skin) -> bool {
         &mut skin.hud.judgement.great.size.y,
         &mut skin.hud.judgement.great.visible,
         &mut skin.hud.judgement.great.image,
+        &mut skin.hud.judgement.great.animation,
         Some(&skin.base_path),
     )
 }
@@ -180,6 +237,7 @@ pub fn edit_good(ui: &mut Ui, skin: &mut Skin) -> bool {
         &mut skin.hud.judgement.good.size.y,
         &mut skin.hud.judgement.good.visible,
         &mut skin.hud.judgement.good.image,
+        &mut skin.hud.judgement.good.animation,
         Some(&skin.base_path),
     )
 }
@@ -196,6 +254,7 @@ pub fn edit_bad(ui: &mut Ui, skin: &mut Skin) -> bool {
         &mut skin.hud.judgement.bad.size.y,
         &mut skin.hud.judgement.bad.visible,
         &mut skin.hud.judgement.bad.image,
+        &mut skin.hud.judgement.bad.animation,
         Some(&skin.base_path),
     )
 }
@@ -212,6 +271,7 @@ pub fn edit_miss(ui: &mut Ui, skin: &mut Skin) -> bool {
         &mut skin.hud.judgement.miss.size.y,
         &mut skin.hud.judgement.miss.visible,
         &mut skin.hud.judgement.miss.image,
+        &mut skin.hud.judgement.miss.animation,
         Some(&skin.base_path),
     )
 }
@@ -228,6 +288,7 @@ pub fn edit_ghost_tap(ui: &mut Ui, skin: &mut Skin) -> bool {
         &mut skin.hud.judgement.ghost_tap.size.y,
         &mut skin.hud.judgement.ghost_tap.visible,
         &mut skin.hud.judgement.ghost_tap.image,
+        &mut skin.hud.judgement.ghost_tap.animation,
         Some(&skin.base_path),
     )
 }
@@ -237,29 +298,37 @@ pub fn edit_judgement_panel(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
     let panel = &mut skin.hud.judgement_panel;
 
-    section_header(ui, "📍 Position");
+    section_header(ui, &tr("editor.section.position"));
     changed |= position_edit(ui, &mut panel.position.x, &mut panel.position.y);
+    changed |= anchor_edit(ui, &mut panel.anchor, &mut panel.units);
 
-    section_header(ui, "📐 Size");
+    section_header(ui, &tr("editor.section.size"));
     changed |= size_edit(ui, &mut panel.size.x, &mut panel.size.y);
     ui.horizontal(|ui| {
-        ui.label("Text Scale");
+        ui.label(tr("editor.field.text_scale"));
         changed |= ui
             .add(DragValue::new(&mut panel.text_scale).speed(0.5))
             .changed();
     });
 
-    section_header(ui, "🎨 Panel Colors (separate from Flash!)");
-    changed |= color_edit(ui, "Marvelous", &mut panel.marv_color);
-    changed |= color_edit(ui, "Perfect", &mut panel.perfect_color);
-    changed |= color_edit(ui, "Great", &mut panel.great_color);
-    changed |= color_edit(ui, "Good", &mut panel.good_color);
-    changed |= color_edit(ui, "Bad", &mut panel.bad_color);
-    changed |= color_edit(ui, "Miss", &mut panel.miss_color);
-    changed |= color_edit(ui, "Ghost Tap", &mut panel.ghost_tap_color);
+    section_header(ui, &tr("editor.section.flash_panel_colors"));
+    changed |= color_edit(ui, &tr("editor.field.marvelous"), &mut panel.marv_color);
+    changed |= color_edit(ui, &tr("editor.field.perfect"), &mut panel.perfect_color);
+    changed |= color_edit(ui, &tr("editor.field.great"), &mut panel.great_color);
+    changed |= color_edit(ui, &tr("editor.field.good"), &mut panel.good_color);
+    changed |= color_edit(ui, &tr("editor.field.bad"), &mut panel.bad_color);
+    changed |= color_edit(ui, &tr("editor.field.miss"), &mut panel.miss_color);
+    changed |= color_edit(
+        ui,
+        &tr("editor.field.ghost_tap"),
+        &mut panel.ghost_tap_color,
+    );
 
-    section_header(ui, "👁️ Visibility");
-    changed |= ui.checkbox(&mut panel.visible, "Visible").changed();
+    section_header(ui, &tr("editor.section.visibility"));
+    changed |= opacity_edit(ui, &mut panel.opacity);
+    changed |= ui
+        .checkbox(&mut panel.visible, tr("editor.field.visible"))
+        .changed();
 
     changed
 }
@@ -269,28 +338,32 @@ pub fn edit_notes_remaining(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
     let cfg = &mut skin.hud.notes_remaining;
 
-    section_header(ui, "📍 Position");
+    section_header(ui, &tr("editor.section.position"));
     changed |= position_edit(ui, &mut cfg.position.x, &mut cfg.position.y);
+    changed |= anchor_edit(ui, &mut cfg.anchor, &mut cfg.units);
 
-    section_header(ui, "📐 Size & Scale");
+    section_header(ui, &tr("editor.section.size_scale"));
     changed |= size_edit(ui, &mut cfg.size.x, &mut cfg.size.y);
     ui.horizontal(|ui| {
-        ui.label("Text Scale");
+        ui.label(tr("editor.field.text_scale"));
         changed |= ui.add(DragValue::new(&mut cfg.scale).speed(0.5)).changed();
     });
 
-    section_header(ui, "🎨 Color");
-    changed |= color_edit(ui, "Text Color", &mut cfg.color);
+    section_header(ui, &tr("editor.section.color"));
+    changed |= color_edit(ui, &tr("editor.field.text_color"), &mut cfg.color);
 
-    section_header(ui, "📝 Format");
+    section_header(ui, &tr("editor.section.format"));
     ui.horizontal(|ui| {
-        ui.label("Format");
+        ui.label(tr("editor.field.format"));
         changed |= ui.text_edit_singleline(&mut cfg.format).changed();
     });
-    hint(ui, "Use {remaining} as placeholder");
+    hint(ui, &tr("editor.hint.remaining_placeholder"));
 
-    section_header(ui, "👁️ Visibility");
-    changed |= ui.checkbox(&mut cfg.visible, "Visible").changed();
+    section_header(ui, &tr("editor.section.visibility"));
+    changed |= opacity_edit(ui, &mut cfg.opacity);
+    changed |= ui
+        .checkbox(&mut cfg.visible, tr("editor.field.visible"))
+        .changed();
 
     changed
 }
@@ -300,28 +373,32 @@ pub fn edit_scroll_speed(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
     let cfg = &mut skin.hud.scroll_speed;
 
-    section_header(ui, "📍 Position");
+    section_header(ui, &tr("editor.section.position"));
     changed |= position_edit(ui, &mut cfg.position.x, &mut cfg.position.y);
+    changed |= anchor_edit(ui, &mut cfg.anchor, &mut cfg.units);
 
-    section_header(ui, "📐 Size & Scale");
+    section_header(ui, &tr("editor.section.size_scale"));
     changed |= size_edit(ui, &mut cfg.size.x, &mut cfg.size.y);
     ui.horizontal(|ui| {
-        ui.label("Text Scale");
+        ui.label(tr("editor.field.text_scale"));
         changed |= ui.add(DragValue::new(&mut cfg.scale).speed(0.5)).changed();
     });
 
-    section_header(ui, "🎨 Color");
-    changed |= color_edit(ui, "Text Color", &mut cfg.color);
+    section_header(ui, &tr("editor.section.color"));
+    changed |= color_edit(ui, &tr("editor.field.text_color"), &mut cfg.color);
 
-    section_header(ui, "📝 Format");
+    section_header(ui, &tr("editor.section.format"));
     ui.horizontal(|ui| {
-        ui.label("Format");
+        ui.label(tr("editor.field.format"));
         changed |= ui.text_edit_singleline(&mut cfg.format).changed();
     });
-    hint(ui, "Use {speed} as placeholder");
+    hint(ui, &tr("editor.hint.speed_placeholder"));
 
-    section_header(ui, "👁️ Visibility");
-    changed |= ui.checkbox(&mut cfg.visible, "Visible").changed();
+    section_header(ui, &tr("editor.section.visibility"));
+    changed |= opacity_edit(ui, &mut cfg.opacity);
+    changed |= ui
+        .checkbox(&mut cfg.visible, tr("editor.field.visible"))
+        .changed();
 
     changed
 }
@@ -333,18 +410,18 @@ pub fn edit_time_left(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
     let cfg = &mut skin.hud.time_left;
 
-    section_header(ui, "🎛️ Display Mode");
-    egui::ComboBox::from_label("Mode")
+    section_header(ui, &tr("editor.section.display_mode"));
+    egui::ComboBox::from_label(tr("editor.field.mode"))
         .selected_text(match cfg.mode {
-            TimeDisplayMode::Bar => "Bar",
-            TimeDisplayMode::Circle => "Circle",
-            TimeDisplayMode::Text => "Text",
+            TimeDisplayMode::Bar => tr("editor.field.bar"),
+            TimeDisplayMode::Circle => tr("editor.field.circle"),
+            TimeDisplayMode::Text => tr("editor.field.text"),
         })
         .show_ui(ui, |ui| {
             if ui
                 .selectable_label(
                     cfg.mode == TimeDisplayMode::Bar,
-                    "Bar (horizontal progress)",
+                    tr("editor.field.mode_bar"),
                 )
                 .clicked()
             {
@@ -352,14 +429,20 @@ pub fn edit_time_left(ui: &mut Ui, skin: &mut Skin) -> bool {
                 changed = true;
             }
             if ui
-                .selectable_label(cfg.mode == TimeDisplayMode::Circle, "Circle (watch-like)")
+                .selectable_label(
+                    cfg.mode == TimeDisplayMode::Circle,
+                    tr("editor.field.mode_circle"),
+                )
                 .clicked()
             {
                 cfg.mode = TimeDisplayMode::Circle;
                 changed = true;
             }
             if ui
-                .selectable_label(cfg.mode == TimeDisplayMode::Text, "Text (minutes:seconds)")
+                .selectable_label(
+                    cfg.mode == TimeDisplayMode::Text,
+                    tr("editor.field.mode_text"),
+                )
                 .clicked()
             {
                 cfg.mode = TimeDisplayMode::Text;
@@ -367,89 +450,101 @@ pub fn edit_time_left(ui: &mut Ui, skin: &mut Skin) -> bool {
             }
         });
 
-    section_header(ui, "📍 Position");
+    section_header(ui, &tr("editor.section.position"));
     changed |= position_edit(ui, &mut cfg.position.x, &mut cfg.position.y);
+    changed |= anchor_edit(ui, &mut cfg.anchor, &mut cfg.units);
 
-    section_header(ui, "📐 Size");
+    section_header(ui, &tr("editor.section.size"));
     changed |= size_edit(ui, &mut cfg.size.x, &mut cfg.size.y);
 
     match cfg.mode {
         TimeDisplayMode::Bar => {
-            section_header(ui, "🎨 Bar Colors");
-            changed |= color_edit(ui, "Progress", &mut cfg.progress_color);
-            changed |= color_edit(ui, "Background", &mut cfg.background_color);
-            changed |= color_edit(ui, "Border", &mut cfg.border_color);
+            section_header(ui, &tr("editor.section.bar_colors"));
+            changed |= color_edit(ui, &tr("editor.field.progress"), &mut cfg.progress_color);
+            changed |= color_edit(
+                ui,
+                &tr("editor.field.background"),
+                &mut cfg.background_color,
+            );
+            changed |= color_edit(ui, &tr("editor.field.border"), &mut cfg.border_color);
 
             ui.horizontal(|ui| {
-                ui.label("Border Width");
+                ui.label(tr("editor.field.border_width"));
                 changed |= ui
                     .add(egui::DragValue::new(&mut cfg.border_width).speed(0.5))
                     .changed();
             });
 
-            section_header(ui, "🖼️ Images (Optional)");
+            section_header(ui, &tr("editor.section.images_optional"));
             changed |= image_picker(
                 ui,
-                "Background",
+                &tr("editor.field.background"),
                 &mut cfg.background_image,
                 Some(&skin.base_path),
             );
             changed |= image_picker(
                 ui,
-                "Progress Fill",
+                &tr("editor.field.progress_fill"),
                 &mut cfg.progress_image,
                 Some(&skin.base_path),
             );
         }
         TimeDisplayMode::Circle => {
-            section_header(ui, "🎨 Circle Colors");
-            changed |= color_edit(ui, "Progress", &mut cfg.progress_color);
-            changed |= color_edit(ui, "Background", &mut cfg.background_color);
-            changed |= color_edit(ui, "Border", &mut cfg.border_color);
+            section_header(ui, &tr("editor.section.circle_colors"));
+            changed |= color_edit(ui, &tr("editor.field.progress"), &mut cfg.progress_color);
+            changed |= color_edit(
+                ui,
+                &tr("editor.field.background"),
+                &mut cfg.background_color,
+            );
+            changed |= color_edit(ui, &tr("editor.field.border"), &mut cfg.border_color);
 
             ui.horizontal(|ui| {
-                ui.label("Radius");
+                ui.label(tr("editor.field.radius"));
                 changed |= ui
                     .add(egui::DragValue::new(&mut cfg.circle_radius).speed(1.0))
                     .changed();
             });
             ui.horizontal(|ui| {
-                ui.label("Border Width");
+                ui.label(tr("editor.field.border_width"));
                 changed |= ui
                     .add(egui::DragValue::new(&mut cfg.border_width).speed(0.5))
                     .changed();
             });
 
-            section_header(ui, "🖼️ Images (Optional)");
+            section_header(ui, &tr("editor.section.images_optional"));
             changed |= image_picker(
                 ui,
-                "Circle Image",
+                &tr("editor.field.circle_image"),
                 &mut cfg.circle_image,
                 Some(&skin.base_path),
             );
         }
         TimeDisplayMode::Text => {
-            section_header(ui, "🎨 Text Color");
-            changed |= color_edit(ui, "Text", &mut cfg.text_color);
+            section_header(ui, &tr("editor.section.text_color"));
+            changed |= color_edit(ui, &tr("editor.field.text"), &mut cfg.text_color);
 
             ui.horizontal(|ui| {
-                ui.label("Text Scale");
+                ui.label(tr("editor.field.text_scale"));
                 changed |= ui
                     .add(egui::DragValue::new(&mut cfg.text_scale).speed(0.5))
                     .changed();
             });
 
-            section_header(ui, "📝 Format");
+            section_header(ui, &tr("editor.section.format"));
             ui.horizontal(|ui| {
-                ui.label("Format");
+                ui.label(tr("editor.field.format"));
                 changed |= ui.text_edit_singleline(&mut cfg.format).changed();
             });
-            hint(ui, "Use {elapsed}, {remaining}, {total}, {percent}");
+            hint(ui, &tr("editor.hint.time_placeholders"));
         }
     }
 
-    section_header(ui, "👁️ Visibility");
-    changed |= ui.checkbox(&mut cfg.visible, "Visible").changed();
+    section_header(ui, &tr("editor.section.visibility"));
+    changed |= opacity_edit(ui, &mut cfg.opacity);
+    changed |= ui
+        .checkbox(&mut cfg.visible, tr("editor.field.visible"))
+        .changed();
 
     changed
 }
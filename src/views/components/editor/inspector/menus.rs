@@ -2,39 +2,40 @@
 
 use super::common::*;
 use crate::models::skin::Skin;
+use crate::shared::locale::tr;
 use egui::Ui;
 
 pub fn edit_song_button(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
 
-    section_header(ui, "📐 Size");
+    section_header(ui, &tr("editor.section.size"));
     changed |= size_edit(
         ui,
         &mut skin.menus.song_select.song_button.size.x,
         &mut skin.menus.song_select.song_button.size.y,
     );
 
-    section_header(ui, "🎨 Normal State");
+    section_header(ui, &tr("editor.section.normal_state"));
     changed |= color_edit(
         ui,
-        "Background",
+        &tr("editor.field.background"),
         &mut skin.menus.song_select.song_button.background_color,
     );
     changed |= color_edit(
         ui,
-        "Text",
+        &tr("editor.field.text"),
         &mut skin.menus.song_select.song_button.text_color,
     );
     changed |= color_edit(
         ui,
-        "Border",
+        &tr("editor.field.border"),
         &mut skin.menus.song_select.song_button.border_color,
     );
 
-    section_header(ui, "🖼️ Image");
+    section_header(ui, &tr("editor.section.image"));
     changed |= image_picker(
         ui,
-        "Button Image",
+        &tr("editor.field.button_image"),
         &mut skin.menus.song_select.song_button.image,
         Some(&skin.base_path),
     );
@@ -45,34 +46,34 @@ pub fn edit_song_button(ui: &mut Ui, skin: &mut Skin) -> bool {
 pub fn edit_song_button_selected(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
 
-    section_header(ui, "🎨 Selected State");
+    section_header(ui, &tr("editor.section.selected_state"));
     changed |= color_edit(
         ui,
-        "Background",
+        &tr("editor.field.background"),
         &mut skin.menus.song_select.song_button.selected_background_color,
     );
     changed |= color_edit(
         ui,
-        "Text",
+        &tr("editor.field.text"),
         &mut skin.menus.song_select.song_button.selected_text_color,
     );
     changed |= color_edit(
         ui,
-        "Border",
+        &tr("editor.field.border"),
         &mut skin.menus.song_select.song_button.selected_border_color,
     );
 
-    section_header(ui, "🎨 Hover State");
+    section_header(ui, &tr("editor.section.hover_state"));
     changed |= color_edit(
         ui,
-        "Background",
+        &tr("editor.field.background"),
         &mut skin.menus.song_select.song_button.hover_background_color,
     );
 
-    section_header(ui, "🖼️ Image");
+    section_header(ui, &tr("editor.section.image"));
     changed |= image_picker(
         ui,
-        "Selected Image",
+        &tr("editor.field.selected_image"),
         &mut skin.menus.song_select.song_button.selected_image,
         Some(&skin.base_path),
     );
@@ -83,29 +84,29 @@ pub fn edit_song_button_selected(ui: &mut Ui, skin: &mut Skin) -> bool {
 pub fn edit_difficulty_button(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
 
-    section_header(ui, "📐 Size");
+    section_header(ui, &tr("editor.section.size"));
     changed |= size_edit(
         ui,
         &mut skin.menus.song_select.difficulty_button.size.x,
         &mut skin.menus.song_select.difficulty_button.size.y,
     );
 
-    section_header(ui, "🎨 Normal State");
+    section_header(ui, &tr("editor.section.normal_state"));
     changed |= color_edit(
         ui,
-        "Background",
+        &tr("editor.field.background"),
         &mut skin.menus.song_select.difficulty_button.background_color,
     );
     changed |= color_edit(
         ui,
-        "Text",
+        &tr("editor.field.text"),
         &mut skin.menus.song_select.difficulty_button.text_color,
     );
 
-    section_header(ui, "🎨 Selected State");
+    section_header(ui, &tr("editor.section.selected_state"));
     changed |= color_edit(
         ui,
-        "Background",
+        &tr("editor.field.background"),
         &mut skin
             .menus
             .song_select
@@ -114,20 +115,20 @@ pub fn edit_difficulty_button(ui: &mut Ui, skin: &mut Skin) -> bool {
     );
     changed |= color_edit(
         ui,
-        "Text",
+        &tr("editor.field.text"),
         &mut skin.menus.song_select.difficulty_button.selected_text_color,
     );
 
-    section_header(ui, "🖼️ Images");
+    section_header(ui, &tr("editor.section.images"));
     changed |= image_picker(
         ui,
-        "Button Image",
+        &tr("editor.field.button_image"),
         &mut skin.menus.song_select.difficulty_button.image,
         Some(&skin.base_path),
     );
     changed |= image_picker(
         ui,
-        "Selected Image",
+        &tr("editor.field.selected_image"),
         &mut skin.menus.song_select.difficulty_button.selected_image,
         Some(&skin.base_path),
     );
@@ -138,49 +139,49 @@ pub fn edit_difficulty_button(ui: &mut Ui, skin: &mut Skin) -> bool {
 pub fn edit_search_bar(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
 
-    section_header(ui, "📐 Size");
+    section_header(ui, &tr("editor.section.size"));
     changed |= size_edit(
         ui,
         &mut skin.menus.song_select.search_bar.size.x,
         &mut skin.menus.song_select.search_bar.size.y,
     );
 
-    section_header(ui, "🎨 Colors");
+    section_header(ui, &tr("editor.section.colors"));
     changed |= color_edit(
         ui,
-        "Background",
+        &tr("editor.field.background"),
         &mut skin.menus.song_select.search_bar.background_color,
     );
     changed |= color_edit(
         ui,
-        "Active BG",
+        &tr("editor.field.active_bg"),
         &mut skin.menus.song_select.search_bar.active_background_color,
     );
     changed |= color_edit(
         ui,
-        "Text",
+        &tr("editor.field.text"),
         &mut skin.menus.song_select.search_bar.text_color,
     );
     changed |= color_edit(
         ui,
-        "Placeholder",
+        &tr("editor.field.placeholder"),
         &mut skin.menus.song_select.search_bar.placeholder_color,
     );
     changed |= color_edit(
         ui,
-        "Border",
+        &tr("editor.field.border"),
         &mut skin.menus.song_select.search_bar.border_color,
     );
     changed |= color_edit(
         ui,
-        "Active Border",
+        &tr("editor.field.active_border"),
         &mut skin.menus.song_select.search_bar.active_border_color,
     );
 
-    section_header(ui, "🖼️ Image");
+    section_header(ui, &tr("editor.section.image"));
     changed |= image_picker(
         ui,
-        "Background",
+        &tr("editor.field.background"),
         &mut skin.menus.song_select.search_bar.image,
         Some(&skin.base_path),
     );
@@ -191,29 +192,29 @@ pub fn edit_search_bar(ui: &mut Ui, skin: &mut Skin) -> bool {
 pub fn edit_search_panel(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
 
-    section_header(ui, "📐 Size");
+    section_header(ui, &tr("editor.section.size"));
     changed |= size_edit(
         ui,
         &mut skin.menus.song_select.search_panel.size.x,
         &mut skin.menus.song_select.search_panel.size.y,
     );
 
-    section_header(ui, "🎨 Colors");
+    section_header(ui, &tr("editor.section.colors"));
     changed |= color_edit(
         ui,
-        "Background",
+        &tr("editor.field.background"),
         &mut skin.menus.song_select.search_panel.background_color,
     );
     changed |= color_edit(
         ui,
-        "Border",
+        &tr("editor.field.border"),
         &mut skin.menus.song_select.search_panel.border_color,
     );
 
-    section_header(ui, "🖼️ Image");
+    section_header(ui, &tr("editor.section.image"));
     changed |= image_picker(
         ui,
-        "Background Image",
+        &tr("editor.field.background_image"),
         &mut skin.menus.song_select.search_panel.background_image,
         Some(&skin.base_path),
     );
@@ -224,34 +225,34 @@ pub fn edit_search_panel(ui: &mut Ui, skin: &mut Skin) -> bool {
 pub fn edit_beatmap_info(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
 
-    section_header(ui, "📐 Size");
+    section_header(ui, &tr("editor.section.size"));
     changed |= size_edit(
         ui,
         &mut skin.menus.song_select.beatmap_info.size.x,
         &mut skin.menus.song_select.beatmap_info.size.y,
     );
 
-    section_header(ui, "🎨 Colors");
+    section_header(ui, &tr("editor.section.colors"));
     changed |= color_edit(
         ui,
-        "Background",
+        &tr("editor.field.background"),
         &mut skin.menus.song_select.beatmap_info.background_color,
     );
     changed |= color_edit(
         ui,
-        "Text",
+        &tr("editor.field.text"),
         &mut skin.menus.song_select.beatmap_info.text_color,
     );
     changed |= color_edit(
         ui,
-        "Secondary Text",
+        &tr("editor.field.secondary_text"),
         &mut skin.menus.song_select.beatmap_info.secondary_text_color,
     );
 
-    section_header(ui, "🖼️ Image");
+    section_header(ui, &tr("editor.section.image"));
     changed |= image_picker(
         ui,
-        "Background Image",
+        &tr("editor.field.background_image"),
         &mut skin.menus.song_select.beatmap_info.background_image,
         Some(&skin.base_path),
     );
@@ -262,39 +263,39 @@ pub fn edit_beatmap_info(ui: &mut Ui, skin: &mut Skin) -> bool {
 pub fn edit_leaderboard(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
 
-    section_header(ui, "📐 Size");
+    section_header(ui, &tr("editor.section.size"));
     changed |= size_edit(
         ui,
         &mut skin.menus.song_select.leaderboard.size.x,
         &mut skin.menus.song_select.leaderboard.size.y,
     );
 
-    section_header(ui, "🎨 Colors");
+    section_header(ui, &tr("editor.section.colors"));
     changed |= color_edit(
         ui,
-        "Background",
+        &tr("editor.field.background"),
         &mut skin.menus.song_select.leaderboard.background_color,
     );
     changed |= color_edit(
         ui,
-        "Text",
+        &tr("editor.field.text"),
         &mut skin.menus.song_select.leaderboard.text_color,
     );
     changed |= color_edit(
         ui,
-        "Entry BG",
+        &tr("editor.field.entry_bg"),
         &mut skin.menus.song_select.leaderboard.entry_background_color,
     );
     changed |= color_edit(
         ui,
-        "Entry Selected",
+        &tr("editor.field.entry_selected"),
         &mut skin.menus.song_select.leaderboard.entry_selected_color,
     );
 
-    section_header(ui, "🖼️ Image");
+    section_header(ui, &tr("editor.section.image"));
     changed |= image_picker(
         ui,
-        "Background Image",
+        &tr("editor.field.background_image"),
         &mut skin.menus.song_select.leaderboard.background_image,
         Some(&skin.base_path),
     );
@@ -305,17 +306,57 @@ pub fn edit_leaderboard(ui: &mut Ui, skin: &mut Skin) -> bool {
 pub fn edit_panel_style(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
 
-    section_header(ui, "🎨 Panel Colors");
-    changed |= color_edit(ui, "Background", &mut skin.menus.panels.background);
-    changed |= color_edit(ui, "Secondary", &mut skin.menus.panels.secondary);
-    changed |= color_edit(ui, "Border", &mut skin.menus.panels.border);
-    changed |= color_edit(ui, "Accent", &mut skin.menus.panels.accent);
-    changed |= color_edit(ui, "Accent Dim", &mut skin.menus.panels.accent_dim);
-
-    section_header(ui, "📝 Text Colors");
-    changed |= color_edit(ui, "Primary", &mut skin.menus.panels.text_primary);
-    changed |= color_edit(ui, "Secondary", &mut skin.menus.panels.text_secondary);
-    changed |= color_edit(ui, "Muted", &mut skin.menus.panels.text_muted);
+    section_header(ui, &tr("editor.section.panel_colors"));
+    changed |= color_edit(
+        ui,
+        &tr("editor.field.background"),
+        &mut skin.menus.panels.background,
+    );
+    changed |= color_edit(
+        ui,
+        &tr("editor.field.secondary"),
+        &mut skin.menus.panels.secondary,
+    );
+    changed |= color_edit(
+        ui,
+        &tr("editor.field.border"),
+        &mut skin.menus.panels.border,
+    );
+    changed |= color_edit(
+        ui,
+        &tr("editor.field.accent"),
+        &mut skin.menus.panels.accent,
+    );
+    changed |= color_edit(
+        ui,
+        &tr("editor.field.accent_dim"),
+        &mut skin.menus.panels.accent_dim,
+    );
+
+    section_header(ui, &tr("editor.section.text_colors"));
+    changed |= color_edit(
+        ui,
+        &tr("editor.field.primary"),
+        &mut skin.menus.panels.text_primary,
+    );
+    changed |= color_edit(
+        ui,
+        &tr("editor.field.secondary"),
+        &mut skin.menus.panels.text_secondary,
+    );
+    changed |= color_edit(
+        ui,
+        &tr("editor.field.muted"),
+        &mut skin.menus.panels.text_muted,
+    );
+
+    section_header(ui, &tr("editor.section.notification_colors"));
+    changed |= color_edit(
+        ui,
+        &tr("editor.field.success"),
+        &mut skin.menus.panels.success,
+    );
+    changed |= color_edit(ui, &tr("editor.field.error"), &mut skin.menus.panels.error);
 
     changed
 }
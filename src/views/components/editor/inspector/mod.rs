@@ -6,7 +6,7 @@
 //! - hud: score, combo, accuracy, nps  
 //! - judgement: flash levels (all + individual)
 //! - menus: song select elements
-//! - general: skin info, font
+//! - general: skin info, capabilities, font
 //! - columns: per-column editing (4K, 5K, 6K, 7K)
 
 mod columns;
@@ -17,8 +17,10 @@ mod judgement;
 mod menus;
 mod playfield;
 
+use super::browser::element_name;
 use super::layout::SkinEditorState;
 use crate::models::skin::Skin;
+use crate::shared::locale::tr;
 use egui::{Color32, RichText, Ui};
 
 pub struct ElementInspector;
@@ -32,17 +34,23 @@ impl ElementInspector {
         let mut changed = false;
 
         if let Some(id) = &state.selected_element_id.clone() {
-            ui.label(RichText::new(format!("✏️ {}", id)).strong().size(16.0));
+            ui.label(
+                RichText::new(format!("✏️ {}", element_name(id)))
+                    .strong()
+                    .size(16.0),
+            );
             ui.add_space(8.0);
 
+            common::hint(ui, &tr("editor.hint.drop_image"));
             egui::ScrollArea::vertical().show(ui, |ui| {
                 changed |= self.edit_element(ui, &id, skin);
             });
+            common::defer_unclaimed_drop(ui.ctx());
         } else {
             ui.vertical_centered(|ui| {
                 ui.add_space(50.0);
-                ui.label(RichText::new("No Selection").color(Color32::GRAY));
-                ui.label("Select an element in the browser\nor click on the preview.");
+                ui.label(RichText::new(tr("editor.field.no_selection")).color(Color32::GRAY));
+                ui.label(tr("editor.field.select_prompt"));
             });
         }
 
@@ -100,10 +108,11 @@ impl ElementInspector {
 
             // ========== GENERAL ==========
             "Skin Info" => general::edit_skin_info(ui, skin),
+            "Capabilities" => general::edit_capabilities(ui, skin),
             "Font" => general::edit_font(ui, skin),
 
             _ => {
-                ui.label("Select an element to edit its properties.");
+                ui.label(tr("editor.field.unknown_element"));
                 false
             }
         }
@@ -2,25 +2,31 @@
 
 use super::common::*;
 use crate::models::skin::Skin;
+use crate::shared::locale::tr;
 use egui::{DragValue, Ui};
 
 pub fn edit_notes_default(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
 
-    section_header(ui, "🎨 Colors");
-    changed |= color_edit(ui, "Note Color", &mut skin.gameplay.notes.note.color);
+    section_header(ui, &tr("editor.section.colors"));
+    changed |= color_edit(
+        ui,
+        &tr("editor.field.note_color"),
+        &mut skin.gameplay.notes.note.color,
+    );
+    changed |= opacity_edit(ui, &mut skin.gameplay.playfield.note_opacity);
 
-    section_header(ui, "📐 Size");
+    section_header(ui, &tr("editor.section.size"));
     changed |= size_edit(
         ui,
         &mut skin.gameplay.playfield.note_size.x,
         &mut skin.gameplay.playfield.note_size.y,
     );
 
-    section_header(ui, "🖼️ Image");
+    section_header(ui, &tr("editor.section.image"));
     changed |= image_picker(
         ui,
-        "Image",
+        &tr("editor.field.image"),
         &mut skin.gameplay.notes.note.image,
         Some(&skin.base_path),
     );
@@ -31,24 +37,35 @@ pub fn edit_notes_default(ui: &mut Ui, skin: &mut Skin) -> bool {
 pub fn edit_hold_body(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
 
-    section_header(ui, "🎨 Colors");
-    changed |= color_edit(ui, "Body Color", &mut skin.gameplay.notes.hold.color);
+    section_header(ui, &tr("editor.section.colors"));
+    changed |= color_edit(
+        ui,
+        &tr("editor.field.body_color"),
+        &mut skin.gameplay.notes.hold.color,
+    );
+    changed |= opacity_edit(ui, &mut skin.gameplay.playfield.hold_body_opacity);
 
-    section_header(ui, "📐 Size");
+    section_header(ui, &tr("editor.section.size"));
     ui.horizontal(|ui| {
-        ui.label("Width");
+        ui.label(tr("editor.field.width"));
         changed |= ui
             .add(DragValue::new(&mut skin.gameplay.notes.hold.body_width).speed(1.0))
             .changed();
     });
 
-    section_header(ui, "🖼️ Image");
+    section_header(ui, &tr("editor.section.image"));
     changed |= image_picker(
         ui,
-        "Body Image",
+        &tr("editor.field.body_image"),
         &mut skin.gameplay.notes.hold.body_image,
         Some(&skin.base_path),
     );
+    changed |= ui
+        .checkbox(
+            &mut skin.gameplay.notes.hold.stretch_body,
+            tr("editor.field.stretch_body"),
+        )
+        .changed();
 
     changed
 }
@@ -56,17 +73,17 @@ pub fn edit_hold_body(ui: &mut Ui, skin: &mut Skin) -> bool {
 pub fn edit_hold_end(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
 
-    section_header(ui, "📐 Size");
+    section_header(ui, &tr("editor.section.size"));
     changed |= size_edit(
         ui,
         &mut skin.gameplay.notes.hold.end_size.x,
         &mut skin.gameplay.notes.hold.end_size.y,
     );
 
-    section_header(ui, "🖼️ Image");
+    section_header(ui, &tr("editor.section.image"));
     changed |= image_picker(
         ui,
-        "End Image",
+        &tr("editor.field.end_image"),
         &mut skin.gameplay.notes.hold.end_image,
         Some(&skin.base_path),
     );
@@ -77,23 +94,48 @@ pub fn edit_hold_end(ui: &mut Ui, skin: &mut Skin) -> bool {
 pub fn edit_burst_body(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
 
-    section_header(ui, "🎨 Colors");
-    changed |= color_edit(ui, "Body Color", &mut skin.gameplay.notes.burst.color);
+    section_header(ui, &tr("editor.section.colors"));
+    changed |= color_edit(
+        ui,
+        &tr("editor.field.body_color"),
+        &mut skin.gameplay.notes.burst.color,
+    );
 
-    section_header(ui, "📐 Size");
+    section_header(ui, &tr("editor.section.size"));
     changed |= size_edit(
         ui,
         &mut skin.gameplay.notes.burst.body_size.x,
         &mut skin.gameplay.notes.burst.body_size.y,
     );
 
-    section_header(ui, "🖼️ Image");
+    section_header(ui, &tr("editor.section.image"));
     changed |= image_picker(
         ui,
-        "Body Image",
+        &tr("editor.field.body_image"),
         &mut skin.gameplay.notes.burst.body_image,
         Some(&skin.base_path),
     );
+    changed |= ui
+        .checkbox(
+            &mut skin.gameplay.notes.burst.stretch_body,
+            tr("editor.field.stretch_body"),
+        )
+        .changed();
+
+    let burst = &mut skin.gameplay.notes.burst;
+    section_header(ui, &tr("editor.section.hit_counter"));
+    changed |= ui
+        .checkbox(&mut burst.show_counter, tr("editor.field.visible"))
+        .changed();
+    ui.horizontal(|ui| {
+        ui.label(tr("editor.field.text_scale"));
+        changed |= ui
+            .add(DragValue::new(&mut burst.counter_scale).speed(0.5))
+            .changed();
+    });
+    changed |= color_edit(ui, &tr("editor.field.text_color"), &mut burst.counter_color);
+    changed |= position_edit(ui, &mut burst.counter_offset.x, &mut burst.counter_offset.y);
+    hint(ui, &tr("editor.hint.burst_counter_offset"));
 
     changed
 }
@@ -101,17 +143,17 @@ pub fn edit_burst_body(ui: &mut Ui, skin: &mut Skin) -> bool {
 pub fn edit_burst_end(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
 
-    section_header(ui, "📐 Size");
+    section_header(ui, &tr("editor.section.size"));
     changed |= size_edit(
         ui,
         &mut skin.gameplay.notes.burst.end_size.x,
         &mut skin.gameplay.notes.burst.end_size.y,
     );
 
-    section_header(ui, "🖼️ Image");
+    section_header(ui, &tr("editor.section.image"));
     changed |= image_picker(
         ui,
-        "End Image",
+        &tr("editor.field.end_image"),
         &mut skin.gameplay.notes.burst.end_image,
         Some(&skin.base_path),
     );
@@ -122,20 +164,24 @@ pub fn edit_burst_end(ui: &mut Ui, skin: &mut Skin) -> bool {
 pub fn edit_mines(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
 
-    section_header(ui, "🎨 Colors");
-    changed |= color_edit(ui, "Mine Color", &mut skin.gameplay.notes.mine.color);
+    section_header(ui, &tr("editor.section.colors"));
+    changed |= color_edit(
+        ui,
+        &tr("editor.field.mine_color"),
+        &mut skin.gameplay.notes.mine.color,
+    );
 
-    section_header(ui, "📐 Size");
+    section_header(ui, &tr("editor.section.size"));
     changed |= size_edit(
         ui,
         &mut skin.gameplay.notes.mine.size.x,
         &mut skin.gameplay.notes.mine.size.y,
     );
 
-    section_header(ui, "🖼️ Image");
+    section_header(ui, &tr("editor.section.image"));
     changed |= image_picker(
         ui,
-        "Mine Image",
+        &tr("editor.field.mine_image"),
         &mut skin.gameplay.notes.mine.image,
         Some(&skin.base_path),
     );
@@ -146,37 +192,42 @@ pub fn edit_mines(ui: &mut Ui, skin: &mut Skin) -> bool {
 pub fn edit_receptors_default(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
 
-    section_header(ui, "🎨 Colors");
-    changed |= color_edit(ui, "Receptor Color", &mut skin.gameplay.receptors.color);
+    section_header(ui, &tr("editor.section.colors"));
     changed |= color_edit(
         ui,
-        "Pressed Color",
+        &tr("editor.field.receptor_color"),
+        &mut skin.gameplay.receptors.color,
+    );
+    changed |= color_edit(
+        ui,
+        &tr("editor.field.pressed_color"),
         &mut skin.gameplay.receptors.pressed_color,
     );
+    changed |= opacity_edit(ui, &mut skin.gameplay.playfield.receptor_opacity);
 
-    section_header(ui, "📐 Size");
+    section_header(ui, &tr("editor.section.size"));
     changed |= size_edit(
         ui,
         &mut skin.gameplay.playfield.receptor_size.x,
         &mut skin.gameplay.playfield.receptor_size.y,
     );
     ui.horizontal(|ui| {
-        ui.label("Spacing");
+        ui.label(tr("editor.field.spacing"));
         changed |= ui
             .add(DragValue::new(&mut skin.gameplay.playfield.receptor_spacing).speed(0.5))
             .changed();
     });
 
-    section_header(ui, "🖼️ Images");
+    section_header(ui, &tr("editor.section.images"));
     changed |= image_picker(
         ui,
-        "Normal Image",
+        &tr("editor.field.normal_image"),
         &mut skin.gameplay.receptors.image,
         Some(&skin.base_path),
     );
     changed |= image_picker(
         ui,
-        "Pressed Image",
+        &tr("editor.field.pressed_image"),
         &mut skin.gameplay.receptors.pressed_image,
         Some(&skin.base_path),
     );
@@ -187,33 +238,101 @@ pub fn edit_receptors_default(ui: &mut Ui, skin: &mut Skin) -> bool {
 pub fn edit_hit_bar(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
 
-    section_header(ui, "📍 Position");
+    section_header(ui, &tr("editor.section.position"));
     changed |= position_edit(
         ui,
         &mut skin.hud.hit_bar.position.x,
         &mut skin.hud.hit_bar.position.y,
     );
+    changed |= anchor_edit(
+        ui,
+        &mut skin.hud.hit_bar.anchor,
+        &mut skin.hud.hit_bar.units,
+    );
 
-    section_header(ui, "📐 Size");
+    section_header(ui, &tr("editor.section.size"));
     changed |= size_edit(
         ui,
         &mut skin.hud.hit_bar.size.x,
         &mut skin.hud.hit_bar.size.y,
     );
     ui.horizontal(|ui| {
-        ui.label("Scale");
+        ui.label(tr("editor.field.scale"));
         changed |= ui
             .add(DragValue::new(&mut skin.hud.hit_bar.scale).speed(0.5))
             .changed();
     });
 
-    section_header(ui, "🎨 Colors");
-    changed |= color_edit(ui, "Bar Color", &mut skin.hud.hit_bar.bar_color);
-    changed |= color_edit(ui, "Indicator Color", &mut skin.hud.hit_bar.indicator_color);
+    section_header(ui, &tr("editor.section.colors"));
+    changed |= color_edit(
+        ui,
+        &tr("editor.field.bar_color"),
+        &mut skin.hud.hit_bar.bar_color,
+    );
+    changed |= color_edit(
+        ui,
+        &tr("editor.field.indicator_color"),
+        &mut skin.hud.hit_bar.indicator_color,
+    );
+
+    section_header(ui, &tr("editor.section.ticks"));
+    let cfg = &mut skin.hud.hit_bar;
+    ui.horizontal(|ui| {
+        ui.label(tr("editor.field.max_ticks"));
+        changed |= ui
+            .add(DragValue::new(&mut cfg.max_ticks).range(1..=200))
+            .changed();
+    });
+    ui.horizontal(|ui| {
+        ui.label(tr("editor.field.thickness"));
+        changed |= ui
+            .add(
+                DragValue::new(&mut cfg.tick_width)
+                    .speed(0.1)
+                    .range(0.5..=20.0),
+            )
+            .changed();
+    });
+    ui.horizontal(|ui| {
+        ui.label(tr("editor.field.lifetime_ms"));
+        changed |= ui
+            .add(
+                DragValue::new(&mut cfg.tick_lifetime_ms)
+                    .speed(10.0)
+                    .range(0.0..=10000.0),
+            )
+            .changed();
+    });
+    ui.horizontal(|ui| {
+        ui.label(tr("editor.field.fade_ms"));
+        changed |= ui
+            .add(
+                DragValue::new(&mut cfg.tick_fade_ms)
+                    .speed(10.0)
+                    .range(0.0..=10000.0),
+            )
+            .changed();
+    });
+    hint(ui, &tr("editor.hint.tick_lifetime"));
+    changed |= ui
+        .checkbox(&mut cfg.mirror, tr("editor.field.mirror"))
+        .changed();
+
+    section_header(ui, &tr("editor.section.average_marker"));
+    changed |= ui
+        .checkbox(&mut cfg.average_marker, tr("editor.field.show"))
+        .changed();
+    ui.horizontal(|ui| {
+        ui.label(tr("editor.field.window_hits"));
+        changed |= ui
+            .add(DragValue::new(&mut cfg.average_window).range(1..=200))
+            .changed();
+    });
 
-    section_header(ui, "👁️ Visibility");
+    section_header(ui, &tr("editor.section.visibility"));
+    changed |= opacity_edit(ui, &mut skin.hud.hit_bar.opacity);
     changed |= ui
-        .checkbox(&mut skin.hud.hit_bar.visible, "Visible")
+        .checkbox(&mut skin.hud.hit_bar.visible, tr("editor.field.visible"))
         .changed();
 
     changed
@@ -222,16 +341,16 @@ pub fn edit_hit_bar(ui: &mut Ui, skin: &mut Skin) -> bool {
 pub fn edit_playfield_position(ui: &mut Ui, skin: &mut Skin) -> bool {
     let mut changed = false;
 
-    section_header(ui, "📍 Position");
+    section_header(ui, &tr("editor.section.position"));
     changed |= position_edit(
         ui,
         &mut skin.gameplay.playfield.position.x,
         &mut skin.gameplay.playfield.position.y,
     );
 
-    section_header(ui, "📐 Column Settings");
+    section_header(ui, &tr("editor.section.column_settings"));
     ui.horizontal(|ui| {
-        ui.label("Column Width");
+        ui.label(tr("editor.field.column_width"));
         changed |= ui
             .add(DragValue::new(&mut skin.gameplay.playfield.column_width).speed(1.0))
             .changed();
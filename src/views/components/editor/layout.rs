@@ -1,8 +1,26 @@
-use super::browser::AssetBrowser;
+use super::browser::{AssetBrowser, element_name};
+use super::history::EditHistory;
 use super::inspector::ElementInspector;
 use super::viewport::GamePreviewViewport;
-use crate::models::skin::Skin;
-use egui::{CentralPanel, Color32, Context, DragValue, RichText, SidePanel, TopBottomPanel};
+use crate::models::skin::{Skin, package};
+use crate::shared::locale::{tr, tr_args};
+use egui::{
+    CentralPanel, Color32, ComboBox, Context, DragValue, Key, KeyboardShortcut, Modifiers,
+    RichText, SidePanel, TopBottomPanel,
+};
+
+/// Résolutions prédéfinies pour la prévisualisation (nom, largeur, hauteur).
+const RESOLUTION_PRESETS: &[(&str, u32, u32)] = &[
+    ("720p (16:9)", 1280, 720),
+    ("1080p (16:9)", 1920, 1080),
+    ("1440p (16:9)", 2560, 1440),
+    ("4K (16:9)", 3840, 2160),
+    ("Ultrawide 1080p (21:9)", 2560, 1080),
+    ("Ultrawide 1440p (21:9)", 3440, 1440),
+    ("Super Ultrawide (32:9)", 5120, 1440),
+    ("XGA (4:3)", 1024, 768),
+    ("SXGA (5:4)", 1280, 1024),
+];
 
 /// État global de l'éditeur de skin.
 pub struct SkinEditorState {
@@ -15,6 +33,10 @@ pub struct SkinEditorState {
     /// Résolution de la prévisualisation.
     pub preview_width: u32,
     pub preview_height: u32,
+    /// Historique annuler / rétablir des modifications.
+    pub history: EditHistory,
+    /// Message à afficher en toast (résultat d'un export...).
+    pub notice: Option<String>,
 }
 
 impl SkinEditorState {
@@ -25,12 +47,22 @@ impl SkinEditorState {
             game_texture_id: None,
             preview_width: 1280,
             preview_height: 720,
+            history: EditHistory::new(),
+            notice: None,
         }
     }
 
     pub fn target_aspect_ratio(&self) -> f32 {
         self.preview_width as f32 / self.preview_height as f32
     }
+
+    /// Nom du preset correspondant à la résolution actuelle, s'il y en a un.
+    fn preset_name(&self) -> Option<&'static str> {
+        RESOLUTION_PRESETS
+            .iter()
+            .find(|(_, w, h)| *w == self.preview_width && *h == self.preview_height)
+            .map(|(name, _, _)| *name)
+    }
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -42,13 +74,13 @@ pub enum EditorScene {
 }
 
 impl EditorScene {
-    pub fn name(&self) -> &'static str {
-        match self {
-            EditorScene::Gameplay4K => "Gameplay (4K)",
-            EditorScene::Gameplay7K => "Gameplay (7K)",
-            EditorScene::SongSelect => "Song Select",
-            EditorScene::ResultScreen => "Result Screen",
-        }
+    pub fn name(&self) -> String {
+        tr(match self {
+            EditorScene::Gameplay4K => "editor.scene.gameplay_4k",
+            EditorScene::Gameplay7K => "editor.scene.gameplay_7k",
+            EditorScene::SongSelect => "editor.scene.song_select",
+            EditorScene::ResultScreen => "editor.scene.result_screen",
+        })
     }
 }
 
@@ -79,17 +111,64 @@ impl SkinEditorLayout {
         self.state.game_texture_id = game_texture;
         let mut any_change = false;
 
+        // Raccourcis annuler / rétablir (sauf pendant la saisie d'un texte).
+        if !ctx.wants_keyboard_input() {
+            let redo = ctx.input_mut(|i| {
+                i.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND, Key::Y))
+                    || i.consume_shortcut(&KeyboardShortcut::new(
+                        Modifiers::COMMAND | Modifiers::SHIFT,
+                        Key::Z,
+                    ))
+            });
+            let undo = ctx.input_mut(|i| {
+                i.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND, Key::Z))
+            });
+            if redo {
+                any_change |= self.state.history.redo(skin);
+            } else if undo {
+                any_change |= self.state.history.undo(skin);
+            }
+        }
+
         // 1. Barre de Menu (Top)
         TopBottomPanel::top("editor_top_bar").show(ctx, |ui| {
             ui.horizontal(|ui| {
-                ui.label(RichText::new("🛠 Skin Editor").strong());
+                ui.label(RichText::new(format!("🛠 {}", tr("editor.title"))).strong());
                 ui.separator();
 
-                ui.label("Resolution:");
+                ui.label(tr("editor.resolution"));
+                ComboBox::from_id_salt("editor_resolution_preset")
+                    .selected_text(
+                        self.state
+                            .preset_name()
+                            .map(str::to_string)
+                            .unwrap_or_else(|| tr("editor.custom_resolution")),
+                    )
+                    .show_ui(ui, |ui| {
+                        for (name, w, h) in RESOLUTION_PRESETS {
+                            let selected =
+                                self.state.preview_width == *w && self.state.preview_height == *h;
+                            if ui.selectable_label(selected, *name).clicked() {
+                                self.state.preview_width = *w;
+                                self.state.preview_height = *h;
+                            }
+                        }
+                        ui.separator();
+                        // Taille réelle de la fenêtre, en pixels physiques.
+                        if ui
+                            .selectable_label(false, tr("editor.match_window"))
+                            .clicked()
+                        {
+                            let size = ctx.viewport_rect().size() * ctx.pixels_per_point();
+                            self.state.preview_width = (size.x.round() as u32).clamp(320, 5120);
+                            self.state.preview_height = (size.y.round() as u32).clamp(240, 2160);
+                        }
+                    });
+
                 ui.add(
                     DragValue::new(&mut self.state.preview_width)
                         .speed(1.0)
-                        .range(320..=3840)
+                        .range(320..=5120)
                         .suffix("px"),
                 );
                 ui.label("x");
@@ -99,10 +178,37 @@ impl SkinEditorLayout {
                         .range(240..=2160)
                         .suffix("px"),
                 );
+                ui.label(
+                    RichText::new(format!("{:.2}:1", self.state.target_aspect_ratio()))
+                        .color(Color32::GRAY),
+                );
 
                 ui.separator();
 
-                if ui.button("💾 Save Skin").clicked() {
+                if ui
+                    .add_enabled(
+                        self.state.history.can_undo(),
+                        egui::Button::new(format!("↶ {}", tr("editor.undo"))),
+                    )
+                    .on_hover_text("Ctrl+Z")
+                    .clicked()
+                {
+                    any_change |= self.state.history.undo(skin);
+                }
+                if ui
+                    .add_enabled(
+                        self.state.history.can_redo(),
+                        egui::Button::new(format!("↷ {}", tr("editor.redo"))),
+                    )
+                    .on_hover_text("Ctrl+Y")
+                    .clicked()
+                {
+                    any_change |= self.state.history.redo(skin);
+                }
+
+                ui.separator();
+
+                if ui.button(format!("💾 {}", tr("editor.save"))).clicked() {
                     println!("DEBUG: Save Skin button clicked!");
                     if let Err(e) = skin.save() {
                         eprintln!("Error saving skin config: {}", e);
@@ -110,7 +216,14 @@ impl SkinEditorLayout {
                         println!("DEBUG: Skin save returned successfully.");
                     }
                 }
-                if ui.button("🚪 Exit Editor").clicked() {
+                if ui
+                    .button(format!("📦 {}", tr("editor.export")))
+                    .on_hover_text(tr("editor.export_hint"))
+                    .clicked()
+                {
+                    self.state.notice = Some(export_skin(skin));
+                }
+                if ui.button(format!("🚪 {}", tr("editor.exit"))).clicked() {
                     // TODO: Close event
                 }
             });
@@ -122,11 +235,16 @@ impl SkinEditorLayout {
             .default_width(320.0)
             .show(ctx, |ui| {
                 ui.add_space(4.0);
-                ui.heading("Inspector");
+                ui.heading(tr("editor.inspector"));
                 ui.separator();
                 self.inspector.show(ui, &mut self.state, skin)
             });
 
+        if inspector_res.inner
+            && let Some(id) = &self.state.selected_element_id
+        {
+            self.state.history.touch(id);
+        }
         any_change |= inspector_res.inner;
 
         // 3. Panneau de Droite (Browser & Scène)
@@ -135,9 +253,17 @@ impl SkinEditorLayout {
             .default_width(280.0)
             .show(ctx, |ui| {
                 ui.add_space(4.0);
-                ui.heading("Context");
+                ui.heading(tr("editor.context"));
                 ui.separator();
                 self.browser.show(ui, &mut self.state, skin);
+
+                ui.add_space(8.0);
+                egui::CollapsingHeader::new(tr("editor.history"))
+                    .id_salt("editor_history_header")
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        any_change |= self.show_history(ui, skin);
+                    });
             });
 
         // 4. Zone Centrale (Viewport "Dézoomé")
@@ -145,10 +271,88 @@ impl SkinEditorLayout {
             egui::Frame::canvas(ui.style())
                 .fill(Color32::from_rgb(20, 20, 20))
                 .show(ui, |ui| {
-                    self.viewport.show(ui, &mut self.state, skin);
+                    if self.viewport.show(ui, &mut self.state, skin)
+                        && let Some(id) = &self.state.selected_element_id
+                    {
+                        self.state.history.touch(id);
+                    }
                 });
         });
 
+        // Un glissement n'est enregistré qu'une fois la souris relâchée.
+        let interacting = ctx.input(|i| i.pointer.any_down());
+        self.state.history.settle(skin, interacting);
+
         any_change
     }
+
+    /// Message en attente pour le joueur, s'il y en a un.
+    pub fn take_notice(&mut self) -> Option<String> {
+        self.state.notice.take()
+    }
+
+    /// Liste des modifications ; cliquer une entrée revient à cet état.
+    fn show_history(&mut self, ui: &mut egui::Ui, skin: &mut Skin) -> bool {
+        let history = &self.state.history;
+        if history.applied().is_empty() && history.undone().is_empty() {
+            ui.label(RichText::new(tr("editor.no_changes")).color(Color32::GRAY));
+            return false;
+        }
+
+        let applied = history.applied().len();
+        let mut target = None;
+        egui::ScrollArea::vertical()
+            .id_salt("editor_history")
+            .max_height(200.0)
+            .show(ui, |ui| {
+                if ui
+                    .selectable_label(applied == 0, tr("editor.opened_skin"))
+                    .clicked()
+                {
+                    target = Some(0);
+                }
+                for (i, edit) in history.applied().iter().enumerate() {
+                    if ui
+                        .selectable_label(i + 1 == applied, element_name(&edit.element))
+                        .clicked()
+                    {
+                        target = Some(i + 1);
+                    }
+                }
+                // Commandes annulées, grisées, dans l'ordre où elles seraient rétablies.
+                for (i, edit) in history.undone().iter().rev().enumerate() {
+                    let label = RichText::new(element_name(&edit.element)).color(Color32::GRAY);
+                    if ui.selectable_label(false, label).clicked() {
+                        target = Some(applied + i + 1);
+                    }
+                }
+            });
+
+        target.is_some_and(|applied| self.state.history.jump_to(applied, skin))
+    }
+}
+
+/// Enregistre le skin puis l'exporte dans le fichier choisi. Renvoie le message pour le joueur.
+fn export_skin(skin: &Skin) -> String {
+    let folder_name = skin
+        .base_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "skin".to_string());
+    let Some(archive) = rfd::FileDialog::new()
+        .add_filter("Skin package", &[package::EXTENSION])
+        .set_file_name(format!("{}.{}", folder_name, package::EXTENSION))
+        .save_file()
+    else {
+        return tr("editor.export_cancelled");
+    };
+
+    // Les modifications non enregistrées font partie de l'export.
+    if let Err(e) = skin.save() {
+        return tr_args("editor.export_save_failed", &[("error", &e)]);
+    }
+    match package::export_skin(&skin.base_path, &archive) {
+        Ok(()) => tr_args("editor.exported", &[("path", &archive.display())]),
+        Err(e) => tr_args("editor.export_failed", &[("error", &e)]),
+    }
 }
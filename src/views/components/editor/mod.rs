@@ -1,4 +1,5 @@
 pub mod browser;
+pub mod history;
 pub mod inspector;
 pub mod layout;
 pub mod viewport;
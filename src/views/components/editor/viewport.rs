@@ -1,5 +1,7 @@
 use super::layout::SkinEditorState;
+use crate::models::skin::hud::{HudLayout, PositionUnits};
 use crate::models::skin::{Skin, Vec2Conf};
+use crate::shared::locale::tr;
 use egui::{
     Align2, Color32, FontId, Id, PointerButton, Pos2, Rect, Sense, Stroke, StrokeKind, Ui, Vec2,
 };
@@ -11,7 +13,9 @@ impl GamePreviewViewport {
         Self
     }
 
-    pub fn show(&mut self, ui: &mut Ui, state: &mut SkinEditorState, skin: &mut Skin) {
+    /// Renvoie `true` si un élément a été déplacé.
+    pub fn show(&mut self, ui: &mut Ui, state: &mut SkinEditorState, skin: &mut Skin) -> bool {
+        let mut moved = false;
         let available_size = ui.available_size();
 
         let target_aspect = state.target_aspect_ratio();
@@ -39,7 +43,7 @@ impl GamePreviewViewport {
             painter.text(
                 viewport_rect.center(),
                 Align2::CENTER_CENTER,
-                "No Preview",
+                tr("editor.no_preview"),
                 FontId::proportional(20.0),
                 Color32::from_gray(100),
             );
@@ -53,6 +57,7 @@ impl GamePreviewViewport {
 
         let scale_x = viewport_rect.width() / state.preview_width as f32;
         let scale_y = viewport_rect.height() / state.preview_height as f32;
+        let layout = Self::layout(state, skin);
 
         // All selectable elements
         let element_ids = [
@@ -81,8 +86,14 @@ impl GamePreviewViewport {
             if let Some(mouse_pos) = response.interact_pointer_pos() {
                 let mut found = None;
                 for id in element_ids.iter() {
-                    let rect =
-                        self.calculate_element_rect(id, skin, viewport_rect, scale_x, scale_y);
+                    let rect = self.calculate_element_rect(
+                        id,
+                        skin,
+                        &layout,
+                        viewport_rect,
+                        scale_x,
+                        scale_y,
+                    );
                     if rect.contains(mouse_pos) {
                         found = Some(id.to_string());
                         break;
@@ -96,8 +107,14 @@ impl GamePreviewViewport {
 
         // Gizmo for selected element
         if let Some(selected_id) = &state.selected_element_id {
-            let gizmo_rect =
-                self.calculate_element_rect(selected_id, skin, viewport_rect, scale_x, scale_y);
+            let gizmo_rect = self.calculate_element_rect(
+                selected_id,
+                skin,
+                &layout,
+                viewport_rect,
+                scale_x,
+                scale_y,
+            );
 
             if gizmo_rect != Rect::NOTHING {
                 painter.rect_stroke(
@@ -129,15 +146,64 @@ impl GamePreviewViewport {
 
                 if gizmo_response.dragged() {
                     let delta = gizmo_response.drag_delta();
-                    self.apply_movement(selected_id, skin, delta.x / scale_x, delta.y / scale_y);
+                    if delta != Vec2::ZERO {
+                        // Les éléments en pourcentage bougent en pourcentage
+                        let (dx, dy) = layout.pixels_to_offset(
+                            Self::element_units(selected_id, skin),
+                            delta.x / scale_x,
+                            delta.y / scale_y,
+                        );
+                        self.apply_movement(selected_id, skin, dx, dy);
+                        moved = true;
+                    }
                 }
             }
         }
+
+        moved
     }
 
-    fn calculate_element_rect(&self, id: &str, skin: &Skin, vp: Rect, sx: f32, sy: f32) -> Rect {
-        let gameplay = &skin.gameplay;
+    /// Géométrie de l'aperçu, pour résoudre les ancrages du HUD.
+    fn layout(state: &SkinEditorState, skin: &Skin) -> HudLayout {
+        let playfield = &skin.gameplay.playfield;
+        let total_w = (4.0 * playfield.column_width) + (3.0 * playfield.receptor_spacing);
+        HudLayout::new(
+            state.preview_width as f32,
+            state.preview_height as f32,
+            playfield.position.x,
+            total_w,
+        )
+    }
+
+    /// Unité de la position de l'élément `id`.
+    fn element_units(id: &str, skin: &Skin) -> PositionUnits {
         let hud = &skin.hud;
+        match id {
+            "📊 Hit Bar" => hud.hit_bar.units,
+            "Score Display" => hud.score.units,
+            "Combo Counter" => hud.combo.units,
+            "Accuracy" => hud.accuracy.units,
+            "NPS Display" => hud.nps.units,
+            "📋 Judgement Panel" => hud.judgement_panel.units,
+            "📝 Notes Remaining" => hud.notes_remaining.units,
+            "⚡ Scroll Speed" => hud.scroll_speed.units,
+            "⏱️ Time Left" => hud.time_left.units,
+            id if id.starts_with("Flash - ") => hud.judgement.units,
+            _ => PositionUnits::Pixels,
+        }
+    }
+
+    fn calculate_element_rect(
+        &self,
+        id: &str,
+        skin: &Skin,
+        layout: &HudLayout,
+        vp: Rect,
+        sx: f32,
+        sy: f32,
+    ) -> Rect {
+        let gameplay = &skin.gameplay;
+        let hud = &skin.hud.resolved(layout);
 
         let to_screen = |pos: Vec2Conf, size: Vec2| -> Rect {
             let x = vp.min.x + (pos.x * sx);
@@ -1,11 +1,17 @@
 //! Accuracy display showing current percentage with smoothing.
 
+use crate::models::engine::InstanceRaw;
+use crate::models::skin::hud::DigitAlign;
+use crate::render::sprite_atlas::DigitSprites;
+use crate::views::components::gameplay::digits::digit_sprites;
 use wgpu_text::glyph_brush::{Section, Text};
 
 pub struct AccuracyDisplay {
     position: (f32, f32),
     text_size: f32, // Configurable font size.
     text_buffer: String,
+    digit_spacing: f32,
+    digit_align: DigitAlign,
 }
 
 impl AccuracyDisplay {
@@ -14,6 +20,8 @@ impl AccuracyDisplay {
             position: (x, y),
             text_size: 20.0,
             text_buffer: String::new(),
+            digit_spacing: 0.0,
+            digit_align: DigitAlign::Left,
         }
     }
 
@@ -23,6 +31,10 @@ impl AccuracyDisplay {
     pub fn set_size(&mut self, size: f32) {
         self.text_size = size;
     }
+    pub fn set_digit_layout(&mut self, spacing: f32, align: DigitAlign) {
+        self.digit_spacing = spacing;
+        self.digit_align = align;
+    }
 
     pub fn render(
         &mut self,
@@ -45,4 +57,25 @@ impl AccuracyDisplay {
             ..Default::default()
         }]
     }
+
+    /// The percentage with the skin digit sprites, without the label.
+    pub fn render_sprites(
+        &self,
+        accuracy: f64,
+        sprites: &DigitSprites,
+        screen_width: f32,
+        screen_height: f32,
+    ) -> Vec<InstanceRaw> {
+        let scale_ratio = screen_height / 1080.0;
+        digit_sprites(
+            sprites,
+            &format!("{:.2}%", accuracy),
+            self.position,
+            self.text_size * scale_ratio,
+            self.digit_spacing * scale_ratio,
+            self.digit_align,
+            screen_width,
+            screen_height,
+        )
+    }
 }
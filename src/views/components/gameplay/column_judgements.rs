@@ -0,0 +1,107 @@
+//! Per-column judgement counts component
+//! A small table of the judgements of each lane, to spot a weak finger
+//! during the play
+
+use crate::models::stats::{HitStats, Judgement, JudgementColors};
+use wgpu_text::glyph_brush::{Section, Text};
+
+/// Judgements shown, one row each (ghost taps are left out).
+const ROWS: usize = 6;
+
+pub struct ColumnJudgementsDisplay {
+    position: (f32, f32),
+    scale: f32,
+    header_color: [f32; 4],
+    /// Texts of the cells, rebuilt every frame (the sections borrow them).
+    cells: Vec<String>,
+    pub visible: bool,
+}
+
+impl ColumnJudgementsDisplay {
+    pub fn new(x: f32, y: f32) -> Self {
+        Self {
+            position: (x, y),
+            scale: 16.0,
+            header_color: [0.8, 0.8, 0.8, 1.0],
+            cells: Vec::new(),
+            visible: false,
+        }
+    }
+
+    pub fn set_position(&mut self, x: f32, y: f32) {
+        self.position = (x, y);
+    }
+
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+    }
+
+    pub fn set_header_color(&mut self, color: [f32; 4]) {
+        self.header_color = color;
+    }
+
+    pub fn render(
+        &mut self,
+        stats: &HitStats,
+        colors: &JudgementColors,
+        screen_width: f32,
+        screen_height: f32,
+    ) -> Vec<Section<'_>> {
+        if !self.visible || stats.columns.is_empty() {
+            return Vec::new();
+        }
+
+        let scale_ratio = screen_height / 1080.0;
+        let font_scale = self.scale * scale_ratio;
+        // Room for four digits per cell.
+        let cell_width = font_scale * 2.6;
+        let row_height = font_scale * 1.2;
+
+        // Header row first, then the rows of each judgement.
+        self.cells.clear();
+        for lane in 0..stats.columns.len() {
+            self.cells.push((lane + 1).to_string());
+        }
+        for row in 0..ROWS {
+            for counts in &stats.columns {
+                self.cells.push(counts[row].to_string());
+            }
+        }
+
+        let lanes = stats.columns.len();
+        let row_colors: Vec<[f32; 4]> = Judgement::ALL[..ROWS]
+            .iter()
+            .map(|&j| judgement_color(colors, j))
+            .collect();
+        let (x, y) = self.position;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, text)| {
+                let (row, lane) = (i / lanes, i % lanes);
+                let color = match row {
+                    0 => self.header_color,
+                    _ => row_colors[row - 1],
+                };
+                Section {
+                    screen_position: (x + lane as f32 * cell_width, y + row as f32 * row_height),
+                    bounds: (screen_width, screen_height),
+                    text: vec![Text::new(text).with_scale(font_scale).with_color(color)],
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+}
+
+fn judgement_color(colors: &JudgementColors, judgement: Judgement) -> [f32; 4] {
+    match judgement {
+        Judgement::Marv => colors.marv,
+        Judgement::Perfect => colors.perfect,
+        Judgement::Great => colors.great,
+        Judgement::Good => colors.good,
+        Judgement::Bad => colors.bad,
+        Judgement::Miss => colors.miss,
+        Judgement::GhostTap => colors.ghost_tap,
+    }
+}
@@ -1,9 +1,15 @@
+use crate::models::engine::InstanceRaw;
+use crate::models::skin::hud::DigitAlign;
+use crate::render::sprite_atlas::DigitSprites;
+use crate::views::components::gameplay::digits::digit_sprites;
 use wgpu_text::glyph_brush::{Section, Text};
 
 pub struct ComboDisplay {
     position: (f32, f32),
     text_size: f32, // Nouveau
     text_buffer: String,
+    digit_spacing: f32,
+    digit_align: DigitAlign,
 }
 
 impl ComboDisplay {
@@ -12,6 +18,8 @@ impl ComboDisplay {
             position: (x, y),
             text_size: 48.0,
             text_buffer: String::new(),
+            digit_spacing: 0.0,
+            digit_align: DigitAlign::Center,
         }
     }
 
@@ -21,6 +29,10 @@ impl ComboDisplay {
     pub fn set_size(&mut self, size: f32) {
         self.text_size = size;
     }
+    pub fn set_digit_layout(&mut self, spacing: f32, align: DigitAlign) {
+        self.digit_spacing = spacing;
+        self.digit_align = align;
+    }
 
     pub fn render(
         &mut self,
@@ -47,4 +59,25 @@ impl ComboDisplay {
             ..Default::default()
         }]
     }
+
+    /// Le combo avec les chiffres du skin au lieu de la police
+    pub fn render_sprites(
+        &self,
+        combo: u32,
+        sprites: &DigitSprites,
+        screen_width: f32,
+        screen_height: f32,
+    ) -> Vec<InstanceRaw> {
+        let scale_ratio = screen_height / 1080.0;
+        digit_sprites(
+            sprites,
+            &combo.to_string(),
+            self.position,
+            self.text_size * scale_ratio,
+            self.digit_spacing * scale_ratio,
+            self.digit_align,
+            screen_width,
+            screen_height,
+        )
+    }
 }
@@ -0,0 +1,44 @@
+//! Debug overlay - input latency, audio drift, FPS and TPS (toggled with F9).
+
+use egui::{Align2, Color32, FontId, Pos2, Rect, Ui, Vec2};
+
+use crate::shared::snapshot::DebugOverlayStats;
+
+/// Small timing readout drawn in the bottom-left corner during gameplay.
+pub struct DebugOverlay;
+
+impl DebugOverlay {
+    /// Renders the overlay; `fps` comes from the render thread.
+    pub fn render(ui: &mut Ui, stats: &DebugOverlayStats, fps: f64, screen_height: f32) {
+        let fmt_ms = |value: Option<f64>| match value {
+            Some(ms) => format!("{:.1} ms", ms),
+            None => "-".to_string(),
+        };
+
+        let lines = [
+            format!("Input latency  {}", fmt_ms(stats.input_latency_ms)),
+            format!("  avg          {}", fmt_ms(stats.mean_input_latency_ms)),
+            format!("Audio drift    {:+.1} ms", stats.last_drift_ms),
+            format!("  max          {:.1} ms", stats.max_drift_ms),
+            format!("Render         {:.0} FPS", fps),
+            format!("Logic          {:.0} TPS", stats.logic_tps),
+        ];
+
+        let line_height = 16.0;
+        let size = Vec2::new(240.0, line_height * lines.len() as f32 + 12.0);
+        let rect = Rect::from_min_size(Pos2::new(10.0, screen_height - size.y - 10.0), size);
+
+        let painter = ui.painter();
+        painter.rect_filled(rect, 4.0, Color32::from_rgba_unmultiplied(0, 0, 0, 190));
+
+        for (i, line) in lines.iter().enumerate() {
+            painter.text(
+                Pos2::new(rect.min.x + 8.0, rect.min.y + 6.0 + i as f32 * line_height),
+                Align2::LEFT_TOP,
+                line,
+                FontId::monospace(13.0),
+                Color32::from_rgb(180, 255, 180),
+            );
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Numbers drawn with the skin digit sprites instead of the font.
+
+use crate::models::engine::InstanceRaw;
+use crate::models::skin::hud::DigitAlign;
+use crate::render::sprite_atlas::DigitSprites;
+
+/// Sprites of `text`, `height` pixels tall, with its top at `position.1`
+/// and anchored horizontally at `position.0`. Characters the skin has no
+/// image for are skipped.
+#[allow(clippy::too_many_arguments)]
+pub fn digit_sprites(
+    sprites: &DigitSprites,
+    text: &str,
+    position: (f32, f32),
+    height: f32,
+    spacing: f32,
+    align: DigitAlign,
+    screen_width: f32,
+    screen_height: f32,
+) -> Vec<InstanceRaw> {
+    let glyphs: Vec<_> = text.chars().filter_map(|c| sprites.glyph(c)).collect();
+    if glyphs.is_empty() {
+        return Vec::new();
+    }
+    let total_width = glyphs
+        .iter()
+        .map(|(_, aspect)| aspect * height)
+        .sum::<f32>()
+        + spacing * (glyphs.len() - 1) as f32;
+    let mut x = match align {
+        DigitAlign::Left => position.0,
+        DigitAlign::Center => position.0 - total_width / 2.0,
+        DigitAlign::Right => position.0 - total_width,
+    };
+    let center_y = position.1 + height / 2.0;
+
+    glyphs
+        .into_iter()
+        .map(|(uv, aspect)| {
+            let width = aspect * height;
+            let mut instance = InstanceRaw::new(
+                [
+                    (x + width / 2.0) / screen_width * 2.0 - 1.0,
+                    1.0 - center_y / screen_height * 2.0,
+                ],
+                [width / screen_width * 2.0, height / screen_height * 2.0],
+            );
+            instance.uv = uv;
+            x += width + spacing;
+            instance
+        })
+        .collect()
+}
@@ -0,0 +1,102 @@
+//! Early / late trend component
+//! Shows the mean offset of the last hits, so a drifting offset is visible
+//! before it costs judgements
+
+use wgpu_text::glyph_brush::{Section, Text};
+
+pub struct EarlyLateDisplay {
+    position: (f32, f32),
+    samples: usize,
+    scale: f32,
+    early_color: [f32; 4],
+    late_color: [f32; 4],
+    format: String,
+    text_buffer: String,
+    pub visible: bool,
+}
+
+impl EarlyLateDisplay {
+    pub fn new(x: f32, y: f32) -> Self {
+        Self {
+            position: (x, y),
+            samples: 20,
+            scale: 20.0,
+            early_color: [0.4, 0.7, 1.0, 1.0],
+            late_color: [1.0, 0.6, 0.3, 1.0],
+            format: "{arrow} {offset}".to_string(),
+            text_buffer: String::new(),
+            visible: true,
+        }
+    }
+
+    pub fn set_position(&mut self, x: f32, y: f32) {
+        self.position = (x, y);
+    }
+
+    pub fn set_samples(&mut self, samples: usize) {
+        self.samples = samples.max(1);
+    }
+
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+    }
+
+    pub fn set_colors(&mut self, early: [f32; 4], late: [f32; 4]) {
+        self.early_color = early;
+        self.late_color = late;
+    }
+
+    pub fn set_format(&mut self, format: String) {
+        self.format = format;
+    }
+
+    /// Mean of the last `samples` offsets (positive = early), if any.
+    fn mean_offset(&self, recent_offsets: &[f64]) -> Option<f64> {
+        let start = recent_offsets.len().saturating_sub(self.samples);
+        let window = &recent_offsets[start..];
+        (!window.is_empty()).then(|| window.iter().sum::<f64>() / window.len() as f64)
+    }
+
+    /// `recent_offsets` holds the latest hit offsets, oldest first.
+    pub fn render(
+        &mut self,
+        recent_offsets: &[f64],
+        screen_width: f32,
+        screen_height: f32,
+    ) -> Vec<Section<'_>> {
+        if !self.visible {
+            return Vec::new();
+        }
+        let Some(mean) = self.mean_offset(recent_offsets) else {
+            return Vec::new();
+        };
+
+        let scale_ratio = screen_height / 1080.0;
+        let font_scale = self.scale * scale_ratio;
+
+        // Offsets are note time minus hit time: hitting early is positive.
+        let (arrow, color) = if mean > 0.0 {
+            ("<", self.early_color)
+        } else {
+            (">", self.late_color)
+        };
+        self.text_buffer = self
+            .format
+            .replace("{arrow}", arrow)
+            .replace("{offset}", &format!("{:.1}ms", mean.abs()));
+
+        let text_width = self.text_buffer.len() as f32 * 0.6 * font_scale;
+        let cx = self.position.0 - (text_width / 2.0);
+
+        vec![Section {
+            screen_position: (cx, self.position.1),
+            bounds: (screen_width, screen_height),
+            text: vec![
+                Text::new(&self.text_buffer)
+                    .with_scale(font_scale)
+                    .with_color(color),
+            ],
+            ..Default::default()
+        }]
+    }
+}
@@ -0,0 +1,75 @@
+//! Replay ghost overlay: how far ahead or behind the raced replay the player is.
+
+use egui::{Align2, Color32, FontId, Pos2, Rect, Ui, Vec2};
+
+use crate::models::ghost::GhostDelta;
+use crate::shared::locale::{tr, tr_args};
+
+const AHEAD: Color32 = Color32::from_rgb(100, 255, 150);
+const BEHIND: Color32 = Color32::from_rgb(255, 110, 110);
+const EVEN: Color32 = Color32::from_rgb(220, 220, 220);
+
+/// Score / accuracy / combo deltas, drawn at the left edge.
+pub struct GhostOverlay;
+
+impl GhostOverlay {
+    pub fn render(ui: &mut Ui, delta: &GhostDelta, screen_height: f32) {
+        let origin = Pos2::new(30.0, screen_height * 0.4);
+        let painter = ui.painter();
+        painter.rect_filled(
+            Rect::from_min_size(origin - Vec2::new(8.0, 8.0), Vec2::new(150.0, 84.0)),
+            4.0,
+            Color32::from_rgba_unmultiplied(0, 0, 0, 160),
+        );
+
+        painter.text(
+            origin,
+            Align2::LEFT_TOP,
+            tr("gameplay.ghost"),
+            FontId::proportional(12.0),
+            Color32::from_rgb(255, 200, 50),
+        );
+        let rows = [
+            (
+                tr_args(
+                    "gameplay.ghost_score",
+                    &[("delta", &format!("{:+}", delta.score))],
+                ),
+                delta.score.signum() as f64,
+            ),
+            (
+                tr_args(
+                    "gameplay.ghost_accuracy",
+                    &[("delta", &format!("{:+.2}", delta.accuracy))],
+                ),
+                delta.accuracy,
+            ),
+            (
+                tr_args(
+                    "gameplay.ghost_combo",
+                    &[("delta", &format!("{:+}", delta.combo))],
+                ),
+                delta.combo.signum() as f64,
+            ),
+        ];
+        for (i, (text, sign)) in rows.into_iter().enumerate() {
+            painter.text(
+                origin + Vec2::new(0.0, 18.0 + i as f32 * 18.0),
+                Align2::LEFT_TOP,
+                text,
+                FontId::monospace(14.0),
+                delta_color(sign),
+            );
+        }
+    }
+}
+
+fn delta_color(delta: f64) -> Color32 {
+    if delta > 0.005 {
+        AHEAD
+    } else if delta < -0.005 {
+        BEHIND
+    } else {
+        EVEN
+    }
+}
@@ -0,0 +1,43 @@
+//! Course mode HP bar overlay.
+
+use egui::{Color32, Pos2, Rect, Stroke, Ui, Vec2};
+
+/// Vertical HP bar drawn on the right edge while playing a course.
+pub struct HealthOverlay;
+
+impl HealthOverlay {
+    /// Renders the bar; `health` is in `[0, 1]`.
+    pub fn render(ui: &mut Ui, health: f64, screen_width: f32, screen_height: f32) {
+        let bar_width = 12.0;
+        let bar_height = screen_height * 0.5;
+        let bar_x = screen_width - bar_width - 30.0;
+        let bar_y = (screen_height - bar_height) / 2.0;
+
+        let bar_rect =
+            Rect::from_min_size(Pos2::new(bar_x, bar_y), Vec2::new(bar_width, bar_height));
+
+        let painter = ui.painter();
+        painter.rect_filled(bar_rect, 4.0, Color32::from_rgba_unmultiplied(0, 0, 0, 180));
+
+        // Fill from the bottom up, turning red when low.
+        let ratio = health.clamp(0.0, 1.0) as f32;
+        let fill_height = bar_height * ratio;
+        let fill_rect = Rect::from_min_size(
+            Pos2::new(bar_x, bar_y + bar_height - fill_height),
+            Vec2::new(bar_width, fill_height),
+        );
+        let color = if ratio < 0.25 {
+            Color32::from_rgb(255, 80, 80)
+        } else {
+            Color32::from_rgb(100, 255, 150)
+        };
+        painter.rect_filled(fill_rect, 4.0, color);
+
+        painter.rect_stroke(
+            bar_rect,
+            4.0,
+            Stroke::new(1.5, Color32::from_rgb(150, 150, 150)),
+            egui::StrokeKind::Outside,
+        );
+    }
+}
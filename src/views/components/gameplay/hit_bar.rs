@@ -1,17 +1,28 @@
-use crate::models::stats::Judgement;
-use wgpu_text::glyph_brush::{Section, Text};
+use crate::models::skin::hud::HitBarConfig;
+use crate::models::stats::{Judgement, JudgementColors, JudgementPalette};
+use crate::views::components::common::primitives::{QuadInstance, quad_from_rect};
+use std::collections::VecDeque;
+use std::time::Instant;
+
+/// Upper bound on the ticks kept, whatever the skin asks for.
+const MAX_TICKS: usize = 200;
 
 #[derive(Clone)]
 struct HitMarker {
     timing: f64,
     judgement: Judgement,
+    at: Instant,
 }
 
 pub struct HitBarDisplay {
     position: (f32, f32),
     size: (f32, f32),
-    last_hits: Vec<HitMarker>,
-    max_history: usize,
+    last_hits: VecDeque<HitMarker>,
+    style: HitBarConfig,
+    /// Moving average of the hit offsets, for the average marker.
+    average: Option<f64>,
+    colors: JudgementColors,
+    palette: JudgementPalette,
 }
 
 impl HitBarDisplay {
@@ -19,8 +30,11 @@ impl HitBarDisplay {
         Self {
             position: (x_pixels, y_pixels),
             size: (width_pixels, height_pixels),
-            last_hits: Vec::with_capacity(10),
-            max_history: 10,
+            last_hits: VecDeque::with_capacity(10),
+            style: HitBarConfig::default(),
+            average: None,
+            colors: Self::default_colors(),
+            palette: JudgementPalette::Skin,
         }
     }
 
@@ -35,18 +49,47 @@ impl HitBarDisplay {
         self.size = (width_pixels, height_pixels);
     }
 
+    /// Tick, average and mirroring options of the skin.
+    pub fn set_style(&mut self, style: &HitBarConfig) {
+        self.style = style.clone();
+    }
+
+    /// Tick colors the player picked over the default ones.
+    pub fn set_palette(&mut self, palette: JudgementPalette) {
+        if palette != self.palette {
+            self.palette = palette;
+            self.colors = Self::default_colors().with_palette(palette);
+        }
+    }
+
+    /// Forgets the ticks and the average, for a new play.
+    pub fn clear(&mut self) {
+        self.last_hits.clear();
+        self.average = None;
+    }
+
     fn push_hit(&mut self, timing: f64, judgement: Judgement) {
         let is_new = self
             .last_hits
-            .last()
+            .back()
             .map(|hit| hit.timing != timing || hit.judgement != judgement)
             .unwrap_or(true);
 
         if is_new {
-            self.last_hits.push(HitMarker { timing, judgement });
-            if self.last_hits.len() > self.max_history {
-                self.last_hits.remove(0);
+            self.last_hits.push_back(HitMarker {
+                timing,
+                judgement,
+                at: Instant::now(),
+            });
+            while self.last_hits.len() > self.style.max_ticks.min(MAX_TICKS) {
+                self.last_hits.pop_front();
             }
+
+            let weight = 2.0 / (self.style.average_window.max(1) as f64 + 1.0);
+            self.average = Some(match self.average {
+                Some(average) => average + (timing - average) * weight,
+                None => timing,
+            });
         }
     }
 
@@ -55,60 +98,99 @@ impl HitBarDisplay {
         let center_x = self.position.0 + (width / 2.0);
         let max_timing = 200.0;
         let ratio = (timing_ms / max_timing).clamp(-1.0, 1.0) as f32;
-        center_x - (ratio * (width / 2.0))
+        if self.style.mirror {
+            center_x + (ratio * (width / 2.0))
+        } else {
+            center_x - (ratio * (width / 2.0))
+        }
     }
 
-    #[inline]
-    fn judgement_color(judgement: Judgement) -> [f32; 4] {
-        match judgement {
-            Judgement::Marv => [0.0, 1.0, 1.0, 1.0],
-            Judgement::Perfect => [1.0, 1.0, 0.0, 1.0],
-            Judgement::Great => [0.0, 1.0, 0.0, 1.0],
-            Judgement::Good => [0.0, 0.0, 1.0, 1.0],
-            Judgement::Bad => [1.0, 0.0, 1.0, 1.0],
-            Judgement::Miss => [1.0, 0.0, 0.0, 1.0],
-            Judgement::GhostTap => [0.5, 0.5, 0.5, 1.0],
+    /// Opacity of a tick `age_ms` after its hit, `None` once it expired.
+    fn tick_alpha(&self, age_ms: f32) -> Option<f32> {
+        let lifetime = self.style.tick_lifetime_ms;
+        if lifetime <= 0.0 {
+            return Some(1.0);
+        }
+        let left = lifetime - age_ms;
+        if left <= 0.0 {
+            return None;
         }
+        let fade = self.style.tick_fade_ms.min(lifetime);
+        Some(if fade > 0.0 {
+            (left / fade).min(1.0)
+        } else {
+            1.0
+        })
     }
 
+    fn default_colors() -> JudgementColors {
+        JudgementColors {
+            marv: [0.0, 1.0, 1.0, 1.0],
+            perfect: [1.0, 1.0, 0.0, 1.0],
+            great: [0.0, 1.0, 0.0, 1.0],
+            good: [0.0, 0.0, 1.0, 1.0],
+            bad: [1.0, 0.0, 1.0, 1.0],
+            miss: [1.0, 0.0, 0.0, 1.0],
+            ghost_tap: [0.5, 0.5, 0.5, 1.0],
+        }
+    }
+
+    /// Center line, hit ticks and average marker.
     pub fn render(
         &mut self,
         latest_hit: Option<(f64, Judgement)>,
         screen_width: f32,
         screen_height: f32,
-    ) -> Vec<Section<'_>> {
+    ) -> Vec<QuadInstance> {
         if let Some((timing, judgement)) = latest_hit {
             self.push_hit(timing, judgement);
         }
 
-        let mut sections = Vec::new();
         let (width, height) = self.size;
+        let tick_width = self.style.tick_width;
         let center_x = self.position.0 + (width / 2.0);
+        let mut quads = Vec::with_capacity(self.last_hits.len() + 2);
 
-        sections.push(Section {
-            screen_position: (center_x, self.position.1),
-            bounds: (screen_width, screen_height),
-            text: vec![
-                Text::new("|")
-                    .with_scale(height)
-                    .with_color([1.0, 1.0, 1.0, 1.0]),
-            ],
-            ..Default::default()
-        });
+        quads.push(quad_from_rect(
+            center_x - tick_width / 2.0,
+            self.position.1,
+            tick_width,
+            height,
+            self.style.indicator_color,
+            screen_width,
+            screen_height,
+        ));
 
         for hit in &self.last_hits {
-            sections.push(Section {
-                screen_position: (self.timing_to_x(hit.timing), self.position.1),
-                bounds: (screen_width, screen_height),
-                text: vec![
-                    Text::new("|")
-                        .with_scale(height * 0.9)
-                        .with_color(Self::judgement_color(hit.judgement)),
-                ],
-                ..Default::default()
-            });
+            let Some(alpha) = self.tick_alpha(hit.at.elapsed().as_secs_f32() * 1000.0) else {
+                continue;
+            };
+            let mut color = self.colors.get(hit.judgement);
+            color[3] *= alpha;
+            quads.push(quad_from_rect(
+                self.timing_to_x(hit.timing) - tick_width / 2.0,
+                self.position.1 + height * 0.05,
+                tick_width,
+                height * 0.9,
+                color,
+                screen_width,
+                screen_height,
+            ));
+        }
+
+        if let Some(average) = self.average.filter(|_| self.style.average_marker) {
+            let marker_width = tick_width * 3.0;
+            quads.push(quad_from_rect(
+                self.timing_to_x(average) - marker_width / 2.0,
+                self.position.1 + height,
+                marker_width,
+                height * 0.3,
+                self.style.indicator_color,
+                screen_width,
+                screen_height,
+            ));
         }
 
-        sections
+        quads
     }
 }
@@ -1,6 +1,10 @@
 //! Displays judgement panels, combo text, and the center flash overlay.
-use crate::models::skin::JudgementLabels;
-use crate::models::stats::{HitStats, Judgement, JudgementColors};
+use crate::models::engine::InstanceRaw;
+use crate::models::skin::hud::{FlashAnimation, JudgementFlashSet};
+use crate::models::skin::{JudgementLabels, Vec2Conf};
+use crate::models::stats::{HitStats, Judgement, JudgementColors, JudgementPalette};
+use crate::render::sprite_atlas::UvRect;
+use std::time::Instant;
 use wgpu_text::glyph_brush::{Section, Text};
 
 /// The Judgement Panel displays stats (Marvelous: 100, Perfect: 50, etc.)
@@ -8,7 +12,10 @@ use wgpu_text::glyph_brush::{Section, Text};
 pub struct JudgementPanel {
     position: (f32, f32),
     text_size: f32,
+    /// Skin colors, with the player's palette applied.
     colors: JudgementColors,
+    skin_colors: JudgementColors,
+    palette: JudgementPalette,
     judgement_lines: [String; 7],
 }
 
@@ -17,7 +24,9 @@ impl JudgementPanel {
         Self {
             position: (x, y),
             text_size: 16.0,
-            colors,
+            colors: colors.clone(),
+            skin_colors: colors,
+            palette: JudgementPalette::Skin,
             judgement_lines: std::array::from_fn(|_| String::new()),
         }
     }
@@ -28,6 +37,16 @@ impl JudgementPanel {
     pub fn set_size(&mut self, size: f32) {
         self.text_size = size;
     }
+    pub fn set_colors(&mut self, colors: JudgementColors) {
+        self.colors = colors.with_palette(self.palette);
+        self.skin_colors = colors;
+    }
+    pub fn set_palette(&mut self, palette: JudgementPalette) {
+        if palette != self.palette {
+            self.palette = palette;
+            self.colors = self.skin_colors.with_palette(palette);
+        }
+    }
 
     /// Render ONLY the judgement counts, NO notes/speed (those are separate now)
     pub fn render(
@@ -86,12 +105,18 @@ impl JudgementPanel {
     }
 }
 
-/// The Judgement Flash displays a centered text when hitting notes
+/// The Judgement Flash displays a centered text (or the skin's image) when
+/// hitting notes
 pub struct JudgementFlash {
     position: (f32, f32),
     text_buffer: String,
     /// If true, show +/- timing indicator (early = "-", late = "+")
     pub show_timing: bool,
+    /// Image size and animation of each flash, in `Judgement::ALL` order.
+    styles: [(Vec2Conf, FlashAnimation); 7],
+    /// Judgements counted so far; a change restarts the animation.
+    judged: u32,
+    judged_at: Instant,
 }
 
 impl JudgementFlash {
@@ -100,12 +125,70 @@ impl JudgementFlash {
             position: (x, y),
             text_buffer: String::new(),
             show_timing: false,
+            styles: [(Vec2Conf::new(200.0, 50.0), FlashAnimation::default()); 7],
+            judged: 0,
+            judged_at: Instant::now(),
         }
     }
     pub fn set_position(&mut self, x: f32, y: f32) {
         self.position = (x, y);
     }
 
+    pub fn set_styles(&mut self, flashes: &JudgementFlashSet) {
+        self.styles = Judgement::ALL.map(|judgement| {
+            let (_, size, animation) = flashes.flash(judgement);
+            (size, animation)
+        });
+    }
+
+    /// Restarts the animation when a new judgement came in.
+    pub fn advance(&mut self, stats: &HitStats) {
+        let judged = stats.marv
+            + stats.perfect
+            + stats.great
+            + stats.good
+            + stats.bad
+            + stats.miss
+            + stats.ghost_tap;
+        if judged != self.judged {
+            self.judged = judged;
+            self.judged_at = Instant::now();
+        }
+    }
+
+    /// Scale and opacity of the flash, `None` once it faded out.
+    fn animation(&self, judgement: Judgement) -> Option<(f32, f32)> {
+        let elapsed_ms = self.judged_at.elapsed().as_secs_f32() * 1000.0;
+        self.styles[judgement as usize].1.at(elapsed_ms)
+    }
+
+    /// Image of the flash, centered on the flash position.
+    pub fn sprite(
+        &self,
+        judgement: Judgement,
+        uv: UvRect,
+        screen_width: f32,
+        screen_height: f32,
+    ) -> Option<InstanceRaw> {
+        let (scale, alpha) = self.animation(judgement)?;
+        let (size, _) = self.styles[judgement as usize];
+        let scale_ratio = screen_height / 1080.0;
+        let center_y = self.position.1 + size.y * scale_ratio / 2.0;
+        let mut instance = InstanceRaw::new(
+            [
+                self.position.0 / screen_width * 2.0 - 1.0,
+                1.0 - center_y / screen_height * 2.0,
+            ],
+            [
+                size.x * scale_ratio * scale / screen_width * 2.0,
+                size.y * scale_ratio * scale / screen_height * 2.0,
+            ],
+        );
+        instance.uv = uv;
+        instance.alpha = alpha;
+        Some(instance)
+    }
+
     /// Render the flash with optional timing indicator
     /// timing_ms: negative = early, positive = late (in milliseconds from perfect hit)
     pub fn render(
@@ -120,6 +203,9 @@ impl JudgementFlash {
         let Some(judgement) = last_judgement else {
             return Vec::new();
         };
+        let Some((scale, alpha)) = self.animation(judgement) else {
+            return Vec::new();
+        };
 
         let (label, color) = match judgement {
             Judgement::Marv => (labels.marv.as_str(), colors.marv),
@@ -132,7 +218,7 @@ impl JudgementFlash {
         };
 
         let scale_ratio = screen_height / 1080.0;
-        let font_scale = 48.0 * scale_ratio;
+        let font_scale = 48.0 * scale_ratio * scale;
         self.text_buffer.clear();
 
         // Add timing indicator if enabled
@@ -170,7 +256,7 @@ impl JudgementFlash {
             text: vec![
                 Text::new(&self.text_buffer)
                     .with_scale(font_scale)
-                    .with_color(color),
+                    .with_color([color[0], color[1], color[2], color[3] * alpha]),
             ],
             ..Default::default()
         }]
@@ -0,0 +1,135 @@
+//! Key overlay component
+//! Lit squares for the held keys and a keys-per-second meter with its peak,
+//! for players streaming or recording their plays
+
+use crate::models::skin::hud::KeyOverlayConfig;
+use crate::views::components::common::primitives::{QuadInstance, quad_from_rect};
+use wgpu_text::glyph_brush::{Section, Text};
+
+pub struct KeyOverlayDisplay {
+    position: (f32, f32),
+    key_size: (f32, f32),
+    spacing: f32,
+    idle_color: [f32; 4],
+    pressed_color: [f32; 4],
+    text_color: [f32; 4],
+    scale: f32,
+    format: String,
+    text_buffer: String,
+    /// Highest keys per second of the current play.
+    peak: u32,
+    /// Chart time of the last frame, to notice a restart.
+    last_time: f64,
+    pub visible: bool,
+}
+
+impl KeyOverlayDisplay {
+    pub fn new(x: f32, y: f32) -> Self {
+        Self {
+            position: (x, y),
+            key_size: (36.0, 36.0),
+            spacing: 6.0,
+            idle_color: [1.0, 1.0, 1.0, 0.15],
+            pressed_color: [1.0, 1.0, 1.0, 0.85],
+            text_color: [1.0, 1.0, 1.0, 1.0],
+            scale: 20.0,
+            format: "{kps} KPS (max {peak})".to_string(),
+            text_buffer: String::new(),
+            peak: 0,
+            last_time: f64::NEG_INFINITY,
+            visible: false,
+        }
+    }
+
+    pub fn set_position(&mut self, x: f32, y: f32) {
+        self.position = (x, y);
+    }
+
+    pub fn set_style(&mut self, config: &KeyOverlayConfig) {
+        self.key_size = (config.key_size.x, config.key_size.y);
+        self.spacing = config.spacing;
+        self.idle_color = config.idle_color;
+        self.pressed_color = config.pressed_color;
+        self.text_color = config.text_color;
+        self.scale = config.scale;
+        self.format = config.format.clone();
+    }
+
+    /// One square per lane, lit while its key is held.
+    pub fn render_keys(
+        &self,
+        keys_held: &[bool],
+        screen_width: f32,
+        screen_height: f32,
+    ) -> Vec<QuadInstance> {
+        if !self.visible {
+            return Vec::new();
+        }
+        let (width, height) = self.key_size;
+        keys_held
+            .iter()
+            .enumerate()
+            .map(|(lane, &held)| {
+                let color = if held {
+                    self.pressed_color
+                } else {
+                    self.idle_color
+                };
+                quad_from_rect(
+                    self.position.0 + lane as f32 * (width + self.spacing),
+                    self.position.1,
+                    width,
+                    height,
+                    color,
+                    screen_width,
+                    screen_height,
+                )
+            })
+            .collect()
+    }
+
+    /// Keys-per-second meter under the keys. `recent_presses` holds the chart
+    /// times of the presses of the last second of the chart, which lasts
+    /// `1 / rate` seconds of real time.
+    pub fn render(
+        &mut self,
+        recent_presses: &[f64],
+        audio_time: f64,
+        rate: f64,
+        screen_width: f32,
+        screen_height: f32,
+    ) -> Vec<Section<'_>> {
+        // Retry or practice checkpoint: the clock went back.
+        if audio_time < self.last_time {
+            self.peak = 0;
+        }
+        self.last_time = audio_time;
+        if !self.visible {
+            return Vec::new();
+        }
+
+        let window_start = audio_time - 1000.0;
+        let presses = recent_presses.iter().filter(|&&t| t > window_start).count();
+        let kps = (presses as f64 * rate).round() as u32;
+        self.peak = self.peak.max(kps);
+        self.text_buffer = self
+            .format
+            .replace("{kps}", &kps.to_string())
+            .replace("{peak}", &self.peak.to_string());
+
+        let font_scale = self.scale * screen_height / 1080.0;
+        vec![Section {
+            screen_position: (
+                self.position.0,
+                self.position.1 + self.key_size.1 + self.spacing,
+            ),
+            bounds: (screen_width, screen_height),
+            text: vec![
+                Text::new(&self.text_buffer)
+                    .with_scale(font_scale)
+                    .with_color(self.text_color),
+            ],
+            ..Default::default()
+        }]
+    }
+}
@@ -1,12 +1,21 @@
 pub mod accuracy;
+pub mod column_judgements;
 pub mod combo;
+pub mod debug_overlay;
+pub mod digits;
+pub mod early_late;
+pub mod ghost;
+pub mod health;
 pub mod hit_bar;
 pub mod judgement;
+pub mod key_overlay;
 pub mod notes_remaining;
 pub mod nps;
+pub mod pb_delta;
 pub mod playfield;
 pub mod practice;
 pub mod score;
+pub mod script_widget;
 pub mod scroll_speed;
 pub mod time_left;
 
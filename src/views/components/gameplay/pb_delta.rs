@@ -0,0 +1,83 @@
+//! Personal best delta component
+//! Shows how far ahead or behind the best local replay the player is
+
+use wgpu_text::glyph_brush::{Section, Text};
+
+use crate::models::ghost::GhostDelta;
+
+pub struct PbDeltaDisplay {
+    position: (f32, f32),
+    scale: f32,
+    ahead_color: [f32; 4],
+    behind_color: [f32; 4],
+    format: String,
+    text_buffer: String,
+    pub visible: bool,
+}
+
+impl PbDeltaDisplay {
+    pub fn new(x: f32, y: f32) -> Self {
+        Self {
+            position: (x, y),
+            scale: 16.0,
+            ahead_color: [0.4, 1.0, 0.6, 1.0],
+            behind_color: [1.0, 0.45, 0.45, 1.0],
+            format: "PB {score} ({acc})".to_string(),
+            text_buffer: String::new(),
+            visible: true,
+        }
+    }
+
+    pub fn set_position(&mut self, x: f32, y: f32) {
+        self.position = (x, y);
+    }
+
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+    }
+
+    pub fn set_colors(&mut self, ahead: [f32; 4], behind: [f32; 4]) {
+        self.ahead_color = ahead;
+        self.behind_color = behind;
+    }
+
+    pub fn set_format(&mut self, format: String) {
+        self.format = format;
+    }
+
+    /// Nothing is drawn without a personal best to compare with.
+    pub fn render(
+        &mut self,
+        delta: Option<&GhostDelta>,
+        screen_width: f32,
+        screen_height: f32,
+    ) -> Vec<Section<'_>> {
+        let Some(delta) = delta.filter(|_| self.visible) else {
+            return Vec::new();
+        };
+
+        let scale_ratio = screen_height / 1080.0;
+        let font_scale = self.scale * scale_ratio;
+
+        self.text_buffer = self
+            .format
+            .replace("{score}", &format!("{:+}", delta.score))
+            .replace("{acc}", &format!("{:+.2}%", delta.accuracy));
+        let color = if delta.score < 0 {
+            self.behind_color
+        } else {
+            self.ahead_color
+        };
+
+        vec![Section {
+            screen_position: self.position,
+            bounds: (screen_width, screen_height),
+            text: vec![
+                Text::new(&self.text_buffer)
+                    .with_scale(font_scale)
+                    .with_color(color),
+            ],
+            ..Default::default()
+        }]
+    }
+}
@@ -1,7 +1,9 @@
 use crate::models::engine::{
-    HIT_LINE_Y, InstanceRaw, NUM_COLUMNS, NoteData, NoteType, PixelSystem, PlayfieldConfig,
-    VISIBLE_DISTANCE,
+    HIT_LINE_Y, InstanceRaw, NO_CLIP, NUM_COLUMNS, NoteData, NoteType, PixelSystem,
+    PlayfieldConfig, ScrollMap, VISIBLE_DISTANCE,
 };
+use crate::models::settings::LaneCover;
+use crate::views::components::common::primitives::QuadInstance;
 
 /// Type of visual element to render.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -10,14 +12,16 @@ pub enum NoteVisual {
     Tap,
     /// Mine (different texture)
     Mine,
-    /// Hold note body (stretched)
+    /// Hold note body (texture repeated down its length)
     HoldBody,
     /// Hold note end cap
     HoldEnd,
-    /// Burst note body (stretched)
+    /// Burst note body (texture repeated down its length)
     BurstBody,
     /// Burst note end cap
     BurstEnd,
+    /// Hits still needed by a burst, drawn as text over its head
+    BurstCounter { remaining: u8 },
 }
 
 /// A renderable note instance with its visual type.
@@ -60,21 +64,35 @@ impl PlayfieldDisplay {
         pixel_system: &PixelSystem,
     ) -> Vec<(usize, InstanceRaw)> {
         // Convert typed instances to simple format for backward compatibility
-        self.render_notes_typed(visible_notes, song_time, scroll_speed_ms, pixel_system)
-            .into_iter()
-            .filter(|n| n.visual == NoteVisual::Tap) // Only tap notes for old system
-            .map(|n| (n.column, n.instance))
-            .collect()
+        self.render_notes_typed(
+            visible_notes,
+            song_time,
+            scroll_speed_ms,
+            None,
+            pixel_system,
+        )
+        .into_iter()
+        .filter(|n| n.visual == NoteVisual::Tap) // Only tap notes for old system
+        .map(|n| (n.column, n.instance))
+        .collect()
     }
 
     /// Calcule la position de chaque note visible avec le type visuel.
+    /// `scroll_map` (mmod) remplace le temps brut par la position de défilement.
     pub fn render_notes_typed(
         &self,
         visible_notes: &[NoteData],
         song_time: f64,
         scroll_speed_ms: f64,
+        scroll_map: Option<&ScrollMap>,
         pixel_system: &PixelSystem,
     ) -> Vec<NoteInstance> {
+        let position_of = |time_ms: f64| match scroll_map {
+            Some(map) => map.position_at(time_ms),
+            None => time_ms,
+        };
+        let song_position = position_of(song_time);
+
         let (playfield_left_x, _) = self.get_bounds(pixel_system);
 
         // Conversion pixels -> normalisé GPU
@@ -104,7 +122,7 @@ impl PlayfieldDisplay {
                 playfield_left_x + col_offset + (column_width_norm / 2.0) + x_offset_norm;
 
             // Physique de défilement : Distance = Temps / Vitesse
-            let time_to_hit = note.timestamp_ms - song_time;
+            let time_to_hit = position_of(note.timestamp_ms) - song_position;
             let progress = time_to_hit / scroll_speed_ms;
 
             let y_pos = (HIT_LINE_Y as f64
@@ -116,10 +134,10 @@ impl PlayfieldDisplay {
                     instances.push(NoteInstance {
                         column: note.column,
                         visual: NoteVisual::Tap,
-                        instance: InstanceRaw {
-                            offset: [center_x, y_pos],
-                            scale: [note_width_norm, note_height_norm],
-                        },
+                        instance: InstanceRaw::new(
+                            [center_x, y_pos],
+                            [note_width_norm, note_height_norm],
+                        ),
                     });
                 }
 
@@ -127,10 +145,10 @@ impl PlayfieldDisplay {
                     instances.push(NoteInstance {
                         column: note.column,
                         visual: NoteVisual::Mine,
-                        instance: InstanceRaw {
-                            offset: [center_x, y_pos],
-                            scale: [note_width_norm, note_height_norm],
-                        },
+                        instance: InstanceRaw::new(
+                            [center_x, y_pos],
+                            [note_width_norm, note_height_norm],
+                        ),
                     });
                 }
 
@@ -140,7 +158,7 @@ impl PlayfieldDisplay {
                     ..
                 } => {
                     let end_time = note.timestamp_ms + duration_ms;
-                    let end_progress = (end_time - song_time) / scroll_speed_ms;
+                    let end_progress = (position_of(end_time) - song_position) / scroll_speed_ms;
                     let end_y_pos = (HIT_LINE_Y as f64
                         + y_offset_norm as f64
                         + (VISIBLE_DISTANCE as f64 * end_progress))
@@ -153,18 +171,23 @@ impl PlayfieldDisplay {
                     } else {
                         y_pos
                     };
+                    // Tenue : la queue est coupée à son tour sur les récepteurs
+                    let clip_y = if *is_held { hit_line_y } else { NO_CLIP };
 
                     let body_height = (end_y_pos - clamped_y_pos).abs();
                     let body_center_y = (clamped_y_pos + end_y_pos) / 2.0;
 
-                    // Body (stretched, 95% width)
+                    // Body (repeated, 95% width)
                     if body_height > 0.001 {
                         instances.push(NoteInstance {
                             column: note.column,
                             visual: NoteVisual::HoldBody,
                             instance: InstanceRaw {
-                                offset: [center_x, body_center_y],
-                                scale: [ln_width_norm, body_height],
+                                clip_y,
+                                ..InstanceRaw::new(
+                                    [center_x, body_center_y],
+                                    [ln_width_norm, body_height],
+                                )
                             },
                         });
                     }
@@ -174,10 +197,10 @@ impl PlayfieldDisplay {
                         instances.push(NoteInstance {
                             column: note.column,
                             visual: NoteVisual::Tap,
-                            instance: InstanceRaw {
-                                offset: [center_x, y_pos],
-                                scale: [note_width_norm, note_height_norm],
-                            },
+                            instance: InstanceRaw::new(
+                                [center_x, y_pos],
+                                [note_width_norm, note_height_norm],
+                            ),
                         });
                     }
 
@@ -186,19 +209,22 @@ impl PlayfieldDisplay {
                         column: note.column,
                         visual: NoteVisual::HoldEnd,
                         instance: InstanceRaw {
-                            offset: [center_x, end_y_pos],
-                            scale: [ln_width_norm, note_height_norm],
+                            clip_y,
+                            ..InstanceRaw::new(
+                                [center_x, end_y_pos],
+                                [ln_width_norm, note_height_norm],
+                            )
                         },
                     });
                 }
 
                 NoteType::Burst {
                     duration_ms,
+                    required_hits,
                     current_hits,
-                    ..
                 } => {
                     let end_time = note.timestamp_ms + duration_ms;
-                    let end_progress = (end_time - song_time) / scroll_speed_ms;
+                    let end_progress = (position_of(end_time) - song_position) / scroll_speed_ms;
                     let end_y_pos = (HIT_LINE_Y as f64
                         + y_offset_norm as f64
                         + (VISIBLE_DISTANCE as f64 * end_progress))
@@ -212,18 +238,22 @@ impl PlayfieldDisplay {
                     } else {
                         y_pos
                     };
+                    let clip_y = if started { hit_line_y } else { NO_CLIP };
 
                     let body_height = (end_y_pos - clamped_y_pos).abs();
                     let body_center_y = (clamped_y_pos + end_y_pos) / 2.0;
 
-                    // Body (stretched, 95% width)
+                    // Body (repeated, 95% width)
                     if body_height > 0.001 {
                         instances.push(NoteInstance {
                             column: note.column,
                             visual: NoteVisual::BurstBody,
                             instance: InstanceRaw {
-                                offset: [center_x, body_center_y],
-                                scale: [ln_width_norm, body_height],
+                                clip_y,
+                                ..InstanceRaw::new(
+                                    [center_x, body_center_y],
+                                    [ln_width_norm, body_height],
+                                )
                             },
                         });
                     }
@@ -233,10 +263,10 @@ impl PlayfieldDisplay {
                         instances.push(NoteInstance {
                             column: note.column,
                             visual: NoteVisual::Tap,
-                            instance: InstanceRaw {
-                                offset: [center_x, y_pos],
-                                scale: [note_width_norm, note_height_norm],
-                            },
+                            instance: InstanceRaw::new(
+                                [center_x, y_pos],
+                                [note_width_norm, note_height_norm],
+                            ),
                         });
                     }
 
@@ -245,9 +275,24 @@ impl PlayfieldDisplay {
                         column: note.column,
                         visual: NoteVisual::BurstEnd,
                         instance: InstanceRaw {
-                            offset: [center_x, end_y_pos],
-                            scale: [ln_width_norm, note_height_norm],
+                            clip_y,
+                            ..InstanceRaw::new(
+                                [center_x, end_y_pos],
+                                [ln_width_norm, note_height_norm],
+                            )
+                        },
+                    });
+
+                    // Compteur de frappes restantes, sur la tête
+                    instances.push(NoteInstance {
+                        column: note.column,
+                        visual: NoteVisual::BurstCounter {
+                            remaining: required_hits.saturating_sub(*current_hits),
                         },
+                        instance: InstanceRaw::new(
+                            [center_x, clamped_y_pos],
+                            [note_width_norm, note_height_norm],
+                        ),
                     });
                 }
             }
@@ -277,11 +322,57 @@ impl PlayfieldDisplay {
                 playfield_left_x + col_offset + (column_width_norm / 2.0) + x_offset_norm;
             let center_y = HIT_LINE_Y + y_offset_norm;
 
-            instances.push(InstanceRaw {
-                offset: [center_x, center_y],
-                scale: [receptor_width_norm, receptor_height_norm],
-            });
+            instances.push(InstanceRaw::new(
+                [center_x, center_y],
+                [receptor_width_norm, receptor_height_norm],
+            ));
         }
         instances
     }
+
+    /// Quads des caches sudden (haut) / hidden (bas), en clip space.
+    /// La zone couverte va de la ligne de frappe au haut de l'écran.
+    pub fn render_lane_covers(
+        &self,
+        pixel_system: &PixelSystem,
+        cover: &LaneCover,
+        color: [f32; 4],
+    ) -> Vec<QuadInstance> {
+        let (playfield_left_x, width_norm) = self.get_bounds(pixel_system);
+        let x_offset_norm = pixel_system.x_pixels_to_normalized(self.config.x_offset_pixels);
+        let y_offset_norm = pixel_system.y_pixels_to_normalized(self.config.y_offset_pixels);
+        let center_x = playfield_left_x + x_offset_norm + width_norm / 2.0;
+
+        let bottom = HIT_LINE_Y + y_offset_norm;
+        let top = (bottom + VISIBLE_DISTANCE).min(1.0);
+        let lane_height = (top - bottom).max(0.0);
+
+        let mut quads = Vec::with_capacity(2);
+        if cover.sudden > 0.0 {
+            let height = lane_height * cover.sudden;
+            quads.push(QuadInstance {
+                center: [center_x, top - height / 2.0],
+                size: [width_norm, height],
+                color,
+            });
+        }
+        if cover.hidden > 0.0 {
+            let height = lane_height * cover.hidden;
+            quads.push(QuadInstance {
+                center: [center_x, bottom + height / 2.0],
+                size: [width_norm, height],
+                color,
+            });
+        }
+        quads
+    }
+
+    /// Column whose receptor contains the point `(x, y)`, in clip space
+    /// (-1..1, y up), the space the receptors are drawn in.
+    pub fn receptor_column_at(&self, pixel_system: &PixelSystem, x: f32, y: f32) -> Option<usize> {
+        self.render_receptors(pixel_system).iter().position(|r| {
+            (x - r.offset[0]).abs() <= r.scale[0] / 2.0
+                && (y - r.offset[1]).abs() <= r.scale[1] / 2.0
+        })
+    }
 }
@@ -1,5 +1,6 @@
 //! Practice Mode UI overlay - progress bar with checkpoints.
 
+use crate::shared::locale::{tr, tr_args};
 use egui::{Color32, Pos2, Rect, Stroke, Ui, Vec2};
 
 /// Affiche l'overlay du mode Practice avec le graphe de progression et les checkpoints.
@@ -11,11 +12,13 @@ impl PracticeOverlay {
     /// - `current_time`: temps actuel en ms
     /// - `map_duration`: durée totale de la map en ms
     /// - `checkpoints`: timestamps des checkpoints en ms
+    /// - `rate`: rate de lecture actuel (modifiable en cours de map)
     pub fn render(
         ui: &mut Ui,
         current_time: f64,
         map_duration: f64,
         checkpoints: &[f64],
+        rate: f64,
         screen_width: f32,
     ) {
         // Position en haut de l'écran
@@ -82,13 +85,13 @@ impl PracticeOverlay {
         painter.text(
             label_pos,
             egui::Align2::CENTER_TOP,
-            "PRACTICE MODE",
+            tr_args("gameplay.practice", &[("rate", &format!("{:.2}", rate))]),
             egui::FontId::proportional(14.0),
             Color32::from_rgb(255, 200, 50),
         );
 
         // Instructions (touches)
-        let instructions = "[  Checkpoint    ]  Retry    P  Toggle";
+        let instructions = tr("gameplay.practice_keys");
         let instr_pos = Pos2::new(bar_x + bar_width / 2.0, bar_y + bar_height + 28.0);
         painter.text(
             instr_pos,
@@ -1,5 +1,9 @@
 //! Simple score label shown during gameplay.
 
+use crate::models::engine::InstanceRaw;
+use crate::models::skin::hud::DigitAlign;
+use crate::render::sprite_atlas::DigitSprites;
+use crate::views::components::gameplay::digits::digit_sprites;
 use wgpu_text::glyph_brush::{Section, Text};
 
 pub struct ScoreDisplay {
@@ -7,6 +11,8 @@ pub struct ScoreDisplay {
     text_size: f32, // Configurable text size.
     current_score: u32,
     score_text: String,
+    digit_spacing: f32,
+    digit_align: DigitAlign,
 }
 
 impl ScoreDisplay {
@@ -16,6 +22,8 @@ impl ScoreDisplay {
             text_size: 24.0,
             current_score: 0,
             score_text: String::new(),
+            digit_spacing: 0.0,
+            digit_align: DigitAlign::Left,
         }
     }
 
@@ -25,31 +33,25 @@ impl ScoreDisplay {
     pub fn set_size(&mut self, size: f32) {
         self.text_size = size;
     }
+    pub fn set_digit_layout(&mut self, spacing: f32, align: DigitAlign) {
+        self.digit_spacing = spacing;
+        self.digit_align = align;
+    }
     pub fn set_score(&mut self, value: u32) {
         self.current_score = value;
     }
 
     pub fn render(&mut self, screen_width: f32, screen_height: f32) -> Vec<Section<'_>> {
-        let scale_ratio = screen_height / 1080.0;
-        let font_scale = self.text_size * scale_ratio;
-        let spacing = font_scale * 1.1;
+        let font_scale = self.text_size * screen_height / 1080.0;
+        let number_y = self.number_y(font_scale);
 
         self.score_text.clear();
         self.score_text.push_str(&self.current_score.to_string());
 
         vec![
+            Self::label(self.position, font_scale, screen_width, screen_height),
             Section {
-                screen_position: self.position,
-                bounds: (screen_width, screen_height),
-                text: vec![
-                    Text::new("Score")
-                        .with_scale(font_scale * 0.8)
-                        .with_color([1.0, 1.0, 1.0, 1.0]),
-                ],
-                ..Default::default()
-            },
-            Section {
-                screen_position: (self.position.0, self.position.1 + spacing),
+                screen_position: (self.position.0, number_y),
                 bounds: (screen_width, screen_height),
                 text: vec![
                     Text::new(&self.score_text)
@@ -60,4 +62,58 @@ impl ScoreDisplay {
             },
         ]
     }
+
+    /// The "Score" label alone, for skins drawing the number with sprites.
+    pub fn render_label(&self, screen_width: f32, screen_height: f32) -> Vec<Section<'static>> {
+        let font_scale = self.text_size * screen_height / 1080.0;
+        vec![Self::label(
+            self.position,
+            font_scale,
+            screen_width,
+            screen_height,
+        )]
+    }
+
+    /// The score with the skin digit sprites, where the text would be.
+    pub fn render_sprites(
+        &self,
+        sprites: &DigitSprites,
+        screen_width: f32,
+        screen_height: f32,
+    ) -> Vec<InstanceRaw> {
+        let scale_ratio = screen_height / 1080.0;
+        let font_scale = self.text_size * scale_ratio;
+        digit_sprites(
+            sprites,
+            &self.current_score.to_string(),
+            (self.position.0, self.number_y(font_scale)),
+            font_scale,
+            self.digit_spacing * scale_ratio,
+            self.digit_align,
+            screen_width,
+            screen_height,
+        )
+    }
+
+    fn number_y(&self, font_scale: f32) -> f32 {
+        self.position.1 + font_scale * 1.1
+    }
+
+    fn label(
+        position: (f32, f32),
+        font_scale: f32,
+        screen_width: f32,
+        screen_height: f32,
+    ) -> Section<'static> {
+        Section {
+            screen_position: position,
+            bounds: (screen_width, screen_height),
+            text: vec![
+                Text::new("Score")
+                    .with_scale(font_scale * 0.8)
+                    .with_color([1.0, 1.0, 1.0, 1.0]),
+            ],
+            ..Default::default()
+        }
+    }
 }
@@ -0,0 +1,303 @@
+//! HUD widgets defined by skin scripts.
+//!
+//! Each widget is a Rhai script defining any of
+//!
+//! ```rhai
+//! fn text(hud) { `${hud.remaining} notes` }    // text, `()` hides it
+//! fn color(hud) { [1.0, 0.5, 0.5, 1.0] }       // text and bar color, `()` for the skin color
+//! fn fill(hud) { hud.time / hud.duration }     // bar filled from 0.0 to 1.0
+//! ```
+//!
+//! `hud` is a read-only map of the snapshot: `time`, `duration` (ms),
+//! `rate`, `score`, `accuracy`, `combo`, `remaining`, `nps`,
+//! `scroll_speed`, `marv` .. `miss`, `health` (`()` outside courses),
+//! `practice` and `autoplay`. Scripts cannot import modules and are cut off
+//! after a small number of operations; a script that fails is disabled for
+//! the rest of the session.
+
+use crate::models::skin::Skin;
+use crate::models::skin::common::{Color, Vec2Conf};
+use crate::models::skin::hud::{HudLayout, ScriptWidgetConfig};
+use crate::shared::snapshot::GameplaySnapshot;
+use crate::views::components::common::primitives::{QuadInstance, quad_from_rect};
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{AST, Array, CallFnOptions, Dynamic, Engine, Map, Scope};
+use std::time::Instant;
+use wgpu_text::glyph_brush::{Section, Text};
+
+/// Operations a widget may spend per function call.
+const MAX_OPERATIONS: u64 = 100_000;
+
+struct ScriptWidget {
+    config: ScriptWidgetConfig,
+    /// Screen position of the widget, its anchor resolved.
+    position: Vec2Conf,
+    ast: AST,
+    has_text: bool,
+    has_color: bool,
+    has_fill: bool,
+    text: String,
+    color: Color,
+    fill: Option<f32>,
+    failed: bool,
+}
+
+pub struct ScriptWidgetDisplay {
+    engine: Engine,
+    widgets: Vec<ScriptWidget>,
+    /// Snapshot the widgets were last evaluated for.
+    evaluated_at: Option<Instant>,
+    layout: Option<HudLayout>,
+}
+
+impl ScriptWidgetDisplay {
+    pub fn new() -> Self {
+        let mut engine = Engine::new();
+        engine.set_module_resolver(DummyModuleResolver::new());
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.set_max_call_levels(16);
+        engine.set_max_string_size(1024);
+        engine.set_max_array_size(1024);
+        engine.set_max_map_size(256);
+        Self {
+            engine,
+            widgets: Vec::new(),
+            evaluated_at: None,
+            layout: None,
+        }
+    }
+
+    /// Compiles the widgets of `skin`, skipping the scripts that fail to.
+    pub fn load(&mut self, skin: &Skin) {
+        self.widgets.clear();
+        self.evaluated_at = None;
+        for config in &skin.hud.widgets {
+            let path = skin.base_path.join(&config.script);
+            let ast = match self.engine.compile_file(path.clone()) {
+                Ok(ast) => ast,
+                Err(e) => {
+                    log::error!("SKIN: Widget {:?} not loaded: {}", path, e);
+                    continue;
+                }
+            };
+            let defines = |name: &str| ast.iter_functions().any(|f| f.name == name);
+            let (has_text, has_color, has_fill) =
+                (defines("text"), defines("color"), defines("fill"));
+            if !has_text && !has_fill {
+                log::error!(
+                    "SKIN: Widget {:?} defines neither `text(hud)` nor `fill(hud)`",
+                    path
+                );
+                continue;
+            }
+            self.widgets.push(ScriptWidget {
+                config: config.clone(),
+                position: config.position,
+                ast,
+                has_text,
+                has_color,
+                has_fill,
+                text: String::new(),
+                color: config.color,
+                fill: None,
+                failed: false,
+            });
+        }
+        if let Some(layout) = self.layout {
+            self.place(&layout);
+        }
+    }
+
+    /// Resolves the widget anchors against the current screen and playfield.
+    pub fn place(&mut self, layout: &HudLayout) {
+        self.layout = Some(*layout);
+        for widget in &mut self.widgets {
+            let config = &widget.config;
+            widget.position = layout.resolve(config.position, config.anchor, config.units);
+        }
+    }
+
+    /// Runs the scripts against `snapshot`, once per snapshot.
+    pub fn update(&mut self, snapshot: &GameplaySnapshot) {
+        if self.widgets.is_empty() || self.evaluated_at == Some(snapshot.timestamp) {
+            return;
+        }
+        self.evaluated_at = Some(snapshot.timestamp);
+
+        let hud = Dynamic::from_map(hud_values(snapshot));
+        for widget in self
+            .widgets
+            .iter_mut()
+            .filter(|w| w.config.visible && !w.failed)
+        {
+            if let Err(e) = widget.evaluate(&self.engine, &hud) {
+                log::error!("SKIN: Widget '{}' disabled: {}", widget.config.script, e);
+                widget.failed = true;
+            }
+        }
+    }
+
+    pub fn render(&self, screen_width: f32, screen_height: f32) -> Vec<Section<'_>> {
+        let font_scale_ratio = screen_height / 1080.0;
+        self.active()
+            .filter(|widget| !widget.text.is_empty())
+            .map(|widget| Section {
+                screen_position: (widget.position.x, widget.position.y),
+                bounds: (screen_width, screen_height),
+                text: vec![
+                    Text::new(&widget.text)
+                        .with_scale(widget.config.scale * font_scale_ratio)
+                        .with_color(widget.color),
+                ],
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    /// Bars of the widgets with a `fill` function, below their text.
+    pub fn quads(&self, screen_width: f32, screen_height: f32) -> Vec<QuadInstance> {
+        let mut quads = Vec::new();
+        for widget in self.active() {
+            let Some(fill) = widget.fill else {
+                continue;
+            };
+            let config = &widget.config;
+            let x = widget.position.x;
+            let y = widget.position.y
+                + if widget.text.is_empty() {
+                    0.0
+                } else {
+                    config.scale * screen_height / 1080.0
+                };
+            if let Some(background) = config.background {
+                quads.push(quad_from_rect(
+                    x,
+                    y,
+                    config.size.x,
+                    config.size.y,
+                    background,
+                    screen_width,
+                    screen_height,
+                ));
+            }
+            if fill > 0.0 {
+                quads.push(quad_from_rect(
+                    x,
+                    y,
+                    config.size.x * fill,
+                    config.size.y,
+                    widget.color,
+                    screen_width,
+                    screen_height,
+                ));
+            }
+        }
+        quads
+    }
+
+    fn active(&self) -> impl Iterator<Item = &ScriptWidget> {
+        self.widgets
+            .iter()
+            .filter(|widget| widget.config.visible && !widget.failed)
+    }
+}
+
+impl Default for ScriptWidgetDisplay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScriptWidget {
+    fn evaluate(&mut self, engine: &Engine, hud: &Dynamic) -> Result<(), String> {
+        if self.has_text {
+            let value = self.call(engine, "text", hud)?;
+            self.text = if value.is_unit() {
+                String::new()
+            } else {
+                value.to_string()
+            };
+        }
+        self.color = if self.has_color {
+            let value = self.call(engine, "color", hud)?;
+            if value.is_unit() {
+                self.config.color
+            } else {
+                color_from(value).ok_or("`color` must return [r, g, b] or [r, g, b, a]")?
+            }
+        } else {
+            self.config.color
+        };
+        if self.has_fill {
+            let value = self.call(engine, "fill", hud)?;
+            self.fill = if value.is_unit() {
+                None
+            } else {
+                let fill = number(&value).ok_or("`fill` must return a number")?;
+                Some((fill as f32).clamp(0.0, 1.0))
+            };
+        }
+        Ok(())
+    }
+
+    fn call(&self, engine: &Engine, name: &str, hud: &Dynamic) -> Result<Dynamic, String> {
+        // Only the function runs, not the script's top-level statements.
+        let options = CallFnOptions::new().eval_ast(false);
+        engine
+            .call_fn_with_options(options, &mut Scope::new(), &self.ast, name, (hud.clone(),))
+            .map_err(|e| format!("{}: {}", name, e))
+    }
+}
+
+/// The values scripts can read.
+fn hud_values(snapshot: &GameplaySnapshot) -> Map {
+    let stats = &snapshot.hit_stats;
+    let mut hud = Map::new();
+    let mut set = |key: &str, value: Dynamic| {
+        hud.insert(key.into(), value);
+    };
+    set("time", Dynamic::from_float(snapshot.audio_time));
+    set("duration", Dynamic::from_float(snapshot.map_duration));
+    set("rate", Dynamic::from_float(snapshot.rate));
+    set("score", Dynamic::from_int(snapshot.score as i64));
+    set("accuracy", Dynamic::from_float(snapshot.accuracy));
+    set("combo", Dynamic::from_int(snapshot.combo as i64));
+    set(
+        "remaining",
+        Dynamic::from_int(snapshot.remaining_notes as i64),
+    );
+    set("nps", Dynamic::from_float(snapshot.nps));
+    set("scroll_speed", Dynamic::from_float(snapshot.scroll_speed));
+    set("marv", Dynamic::from_int(stats.marv as i64));
+    set("perfect", Dynamic::from_int(stats.perfect as i64));
+    set("great", Dynamic::from_int(stats.great as i64));
+    set("good", Dynamic::from_int(stats.good as i64));
+    set("bad", Dynamic::from_int(stats.bad as i64));
+    set("miss", Dynamic::from_int(stats.miss as i64));
+    set(
+        "health",
+        snapshot.health.map_or(Dynamic::UNIT, Dynamic::from_float),
+    );
+    set("practice", Dynamic::from_bool(snapshot.practice_mode));
+    set("autoplay", Dynamic::from_bool(snapshot.autoplay));
+    hud
+}
+
+fn number(value: &Dynamic) -> Option<f64> {
+    value
+        .as_float()
+        .ok()
+        .or_else(|| value.as_int().ok().map(|i| i as f64))
+}
+
+fn color_from(value: Dynamic) -> Option<Color> {
+    let channels = value.try_cast::<Array>()?;
+    if !(3..=4).contains(&channels.len()) {
+        return None;
+    }
+    let mut color = [1.0; 4];
+    for (slot, channel) in color.iter_mut().zip(&channels) {
+        *slot = number(channel)? as f32;
+    }
+    Some(color)
+}
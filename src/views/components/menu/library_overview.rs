@@ -0,0 +1,201 @@
+//! Library overview: how many charts the library has per difficulty and keymode,
+//! and how the player does on each lane.
+
+use crate::database::models::{LaneStats, LibraryStats};
+use crate::shared::locale::{tr, tr_args};
+use egui::{Color32, CornerRadius, Pos2, Rect, Stroke, Ui, Vec2};
+
+const GRAPH_HEIGHT: f32 = 120.0;
+
+/// Renders the overview section. `key_mode` is the selected keymode filter
+/// (`None` = every keymode). Returns `true` when a (re)computation is requested.
+pub fn render_library_overview(
+    ui: &mut Ui,
+    stats: Option<&LibraryStats>,
+    progress: Option<(usize, usize)>,
+    key_mode: &mut Option<i32>,
+) -> bool {
+    let mut requested = false;
+
+    ui.horizontal(|ui| {
+        if let Some((done, total)) = progress {
+            ui.spinner();
+            ui.label(tr_args(
+                "settings.overview.rating",
+                &[("done", &done), ("total", &total)],
+            ));
+        } else {
+            let label = if stats.is_some() {
+                tr("settings.overview.refresh")
+            } else {
+                tr("settings.overview.compute")
+            };
+            requested = ui
+                .button(label)
+                .on_hover_text(tr("settings.overview.compute_hint"))
+                .clicked();
+        }
+    });
+
+    let Some(stats) = stats else {
+        return requested;
+    };
+
+    egui::ComboBox::from_label(tr("settings.overview.keymode"))
+        .selected_text(key_mode_label(*key_mode))
+        .show_ui(ui, |ui| {
+            ui.selectable_value(key_mode, None, key_mode_label(None));
+            for (key_count, _) in &stats.key_modes {
+                ui.selectable_value(key_mode, Some(*key_count), key_mode_label(Some(*key_count)));
+            }
+        });
+
+    let counts = bucket_counts(stats, *key_mode);
+    let rated: u32 = counts.iter().sum();
+    let unit = if stats.calculator_id == "osu" {
+        "★"
+    } else {
+        "MSD"
+    };
+    ui.label(tr_args(
+        "settings.overview.rated",
+        &[
+            ("rated", &rated),
+            ("calculator", &stats.calculator_id),
+            ("unrated", &stats.unrated),
+        ],
+    ));
+    if rated > 0 {
+        render_histogram(ui, &counts, stats.bucket_width, unit);
+    }
+
+    if let Some(key_count) = *key_mode {
+        let lanes: Vec<&LaneStats> = stats
+            .lanes
+            .iter()
+            .filter(|lane| lane.key_count == key_count)
+            .collect();
+        if !lanes.is_empty() {
+            ui.add_space(8.0);
+            render_lanes(ui, &lanes);
+        }
+    }
+
+    requested
+}
+
+/// Accuracy, judgements and mean offset of each lane over the saved plays.
+fn render_lanes(ui: &mut Ui, lanes: &[&LaneStats]) {
+    ui.label(tr("settings.overview.lanes"));
+    egui::Grid::new("library_lanes")
+        .striped(true)
+        .num_columns(4)
+        .show(ui, |ui| {
+            ui.label(tr("settings.overview.lane"));
+            ui.label(tr("settings.overview.accuracy"));
+            ui.label(tr("settings.overview.judgements"));
+            ui.label(tr("settings.overview.mean_offset"));
+            ui.end_row();
+            for lane in lanes {
+                let s = &lane.hit_stats;
+                ui.label(format!("{}", lane.column + 1));
+                ui.label(format!("{:.2}%", s.calculate_accuracy()));
+                ui.label(format!(
+                    "{} / {} / {} / {} / {} / {}",
+                    s.marv, s.perfect, s.great, s.good, s.bad, s.miss
+                ));
+                ui.label(format!("{:+.2}ms", lane.mean_offset_ms));
+                ui.end_row();
+            }
+        });
+}
+
+fn key_mode_label(key_mode: Option<i32>) -> String {
+    match key_mode {
+        None => tr("settings.overview.all_keymodes"),
+        Some(0) => tr("settings.overview.unknown_keymode"),
+        Some(k) => format!("{}K", k),
+    }
+}
+
+/// Chart count per bucket for one keymode, or summed over all of them.
+fn bucket_counts(stats: &LibraryStats, key_mode: Option<i32>) -> Vec<u32> {
+    let mut counts: Vec<u32> = Vec::new();
+    for (key_count, buckets) in &stats.key_modes {
+        if key_mode.is_some_and(|k| k != *key_count) {
+            continue;
+        }
+        if counts.len() < buckets.len() {
+            counts.resize(buckets.len(), 0);
+        }
+        for (total, count) in counts.iter_mut().zip(buckets) {
+            *total += count;
+        }
+    }
+    counts
+}
+
+fn render_histogram(ui: &mut Ui, counts: &[u32], bucket_width: f64, unit: &str) {
+    let (rect, response) = ui.allocate_exact_size(
+        Vec2::new(ui.available_width(), GRAPH_HEIGHT),
+        egui::Sense::hover(),
+    );
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(
+        rect,
+        CornerRadius::same(4),
+        Color32::from_rgba_unmultiplied(0, 0, 0, 90),
+    );
+
+    let peak = counts.iter().copied().max().unwrap_or(0).max(1) as f32;
+    let label_space = 14.0;
+    let plot_height = GRAPH_HEIGHT - label_space - 4.0;
+    let step = rect.width() / counts.len() as f32;
+    let accent = ui.visuals().selection.bg_fill;
+    let text_color = ui.visuals().weak_text_color();
+
+    // Label roughly every 60 px.
+    let label_every = ((60.0 / step).ceil() as usize).max(1);
+    for (i, &count) in counts.iter().enumerate() {
+        let left = rect.left() + i as f32 * step;
+        let top = rect.bottom() - label_space - (count as f32 / peak) * plot_height;
+        painter.rect_filled(
+            Rect::from_min_max(
+                Pos2::new(left + 1.0, top),
+                Pos2::new(left + step - 1.0, rect.bottom() - label_space),
+            ),
+            0.0,
+            accent,
+        );
+        if i % label_every == 0 {
+            painter.text(
+                Pos2::new(left, rect.bottom() - 2.0),
+                egui::Align2::LEFT_BOTTOM,
+                format!("{}", i as f64 * bucket_width),
+                egui::FontId::proportional(10.0),
+                text_color,
+            );
+        }
+    }
+    painter.line_segment(
+        [
+            Pos2::new(rect.left(), rect.bottom() - label_space),
+            Pos2::new(rect.right(), rect.bottom() - label_space),
+        ],
+        Stroke::new(1.0, text_color),
+    );
+
+    if let Some(pos) = response.hover_pos() {
+        let bucket = (((pos.x - rect.left()) / step) as usize).min(counts.len() - 1);
+        let low = bucket as f64 * bucket_width;
+        response.on_hover_text(tr_args(
+            "settings.overview.bucket",
+            &[
+                ("low", &low),
+                ("high", &(low + bucket_width)),
+                ("unit", &unit),
+                ("count", &counts[bucket]),
+            ],
+        ));
+    }
+}
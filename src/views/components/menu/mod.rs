@@ -1,2 +1,3 @@
+pub mod library_overview;
 pub mod result_screen;
 pub mod song_select;
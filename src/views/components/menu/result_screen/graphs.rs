@@ -1,10 +1,11 @@
 use crate::models::engine::hit_window::HitWindow;
-use crate::models::replay::ReplayResult;
-use egui::{Align2, Color32, FontId, Painter, Pos2, Rect, Stroke, Ui, Vec2};
+use crate::models::replay::{ColumnStats, DeviationStats, ReplayResult};
+use crate::shared::locale::{tr, tr_args};
+use egui::{Align2, Color32, FontId, Painter, Pos2, Rect, RichText, Stroke, Ui, Vec2};
 
 pub fn render_graphs(ui: &mut Ui, replay_result: &ReplayResult, hit_window: &HitWindow) {
     ui.vertical(|ui| {
-        ui.label(egui::RichText::new("Hit Deviation Distribution").strong());
+        ui.label(egui::RichText::new(tr("result.deviation")).strong());
         egui::Frame::canvas(ui.style())
             .fill(Color32::from_black_alpha(50))
             .stroke(Stroke::new(1.0, Color32::from_gray(60)))
@@ -14,7 +15,7 @@ pub fn render_graphs(ui: &mut Ui, replay_result: &ReplayResult, hit_window: &Hit
                 render_hit_histogram(&painter, &response.rect, replay_result, hit_window);
             });
         ui.add_space(20.0);
-        ui.label(egui::RichText::new("Hit Timeline").strong());
+        ui.label(egui::RichText::new(tr("result.timeline")).strong());
         egui::Frame::canvas(ui.style())
             .fill(Color32::from_black_alpha(50))
             .stroke(Stroke::new(1.0, Color32::from_gray(60)))
@@ -23,9 +24,123 @@ pub fn render_graphs(ui: &mut Ui, replay_result: &ReplayResult, hit_window: &Hit
                     .allocate_painter(Vec2::new(ui.available_width(), 200.0), egui::Sense::hover());
                 render_timeline_graph(&painter, &response.rect, replay_result, hit_window);
             });
+
+        let deviation = replay_result.deviation_stats();
+        ui.add_space(8.0);
+        render_deviation_readout(ui, &deviation);
+
+        let columns = replay_result.column_stats();
+        if !columns.is_empty() {
+            ui.add_space(16.0);
+            ui.label(RichText::new(tr("result.per_column")).strong());
+            render_column_breakdown(ui, &columns);
+        }
+    });
+}
+
+/// Mean / standard deviation / early-late summary under the timeline.
+fn render_deviation_readout(ui: &mut Ui, deviation: &DeviationStats) {
+    let text_color = Color32::from_gray(200);
+    ui.horizontal(|ui| {
+        ui.label(
+            RichText::new(tr_args(
+                "result.mean",
+                &[("ms", &format!("{:+.2}", deviation.mean_ms))],
+            ))
+            .monospace()
+            .color(text_color),
+        );
+        ui.separator();
+        ui.label(
+            RichText::new(format!("σ {:.2}ms", deviation.std_dev_ms))
+                .monospace()
+                .color(text_color),
+        );
+        ui.separator();
+        let early = deviation.early_ratio() * 100.0;
+        ui.label(
+            RichText::new(tr_args(
+                "result.early_late",
+                &[
+                    ("early", &format!("{:.0}", early)),
+                    ("late", &format!("{:.0}", 100.0 - early)),
+                ],
+            ))
+            .monospace()
+            .color(text_color),
+        );
     });
 }
 
+/// One accuracy bar per column, with the column's mean offset on the right.
+fn render_column_breakdown(ui: &mut Ui, columns: &[ColumnStats]) {
+    let font_id = FontId::monospace(11.0);
+    let worst = columns
+        .iter()
+        .filter(|c| c.deviation.count > 0)
+        .map(|c| c.hit_stats.calculate_accuracy())
+        .fold(f64::INFINITY, f64::min);
+
+    for stats in columns {
+        let accuracy = stats.hit_stats.calculate_accuracy();
+        let (rect, response) =
+            ui.allocate_exact_size(Vec2::new(ui.available_width(), 18.0), egui::Sense::hover());
+        let s = &stats.hit_stats;
+        response.on_hover_text(tr_args(
+            "result.column_hint",
+            &[
+                (
+                    "judgements",
+                    &format!(
+                        "{} / {} / {} / {} / {} / {}",
+                        s.marv, s.perfect, s.great, s.good, s.bad, s.miss
+                    ),
+                ),
+                ("ghost_taps", &s.ghost_tap),
+                ("deviation", &format!("{:.2}", stats.deviation.std_dev_ms)),
+            ],
+        ));
+        let painter = ui.painter();
+
+        let label_width = 36.0;
+        let value_width = 150.0;
+        let bar_rect = Rect::from_min_max(
+            Pos2::new(rect.left() + label_width, rect.top() + 3.0),
+            Pos2::new(rect.right() - value_width, rect.bottom() - 3.0),
+        );
+
+        painter.text(
+            Pos2::new(rect.left(), rect.center().y),
+            Align2::LEFT_CENTER,
+            format!("C{}", stats.column + 1),
+            font_id.clone(),
+            Color32::from_gray(180),
+        );
+
+        // Highlight the weakest column so it stands out at a glance.
+        let color = if columns.len() > 1 && accuracy <= worst {
+            Color32::from_rgb(255, 105, 180)
+        } else {
+            Color32::from_rgb(0, 255, 255)
+        };
+        painter.rect_filled(bar_rect, 2.0, color.linear_multiply(0.15));
+        let ratio = (accuracy / 100.0).clamp(0.0, 1.0) as f32;
+        let filled = Rect::from_min_size(
+            bar_rect.min,
+            Vec2::new(bar_rect.width() * ratio, bar_rect.height()),
+        );
+        painter.rect_filled(filled, 2.0, color.linear_multiply(0.8));
+
+        painter.text(
+            Pos2::new(rect.right(), rect.center().y),
+            Align2::RIGHT_CENTER,
+            format!("{:6.2}%  {:+6.2}ms", accuracy, stats.deviation.mean_ms),
+            font_id.clone(),
+            Color32::from_gray(200),
+        );
+    }
+}
+
 fn render_hit_histogram(
     painter: &Painter,
     rect: &Rect,
@@ -211,6 +326,17 @@ fn render_timeline_graph(
         .unwrap_or(1.0);
     let time_range = (max_time - min_time).max(1.0);
 
+    // Mean offset (same inverted axis as the points below)
+    let mean = replay_result.deviation_stats().mean_ms;
+    let mean_y = center_y - mean as f32 * scale_y;
+    painter.line_segment(
+        [
+            Pos2::new(graph_rect.left(), mean_y),
+            Pos2::new(graph_rect.right(), mean_y),
+        ],
+        Stroke::new(1.0, Color32::from_rgb(255, 165, 0).linear_multiply(0.6)),
+    );
+
     for hit in &replay_result.hit_timings {
         let x_ratio = (hit.note_timestamp_ms - min_time) as f32 / time_range as f32;
         let x = graph_rect.left() + x_ratio * width;
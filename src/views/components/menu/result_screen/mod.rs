@@ -4,21 +4,32 @@ pub mod graphs;
 pub mod stats;
 
 use crate::models::engine::hit_window::HitWindow;
+use crate::shared::locale::tr;
 use crate::state::GameResultData;
 use egui::{Color32, Key, RichText};
+use std::time::{Duration, Instant};
 
-pub struct ResultScreen;
+/// How long the "Copied!" confirmation stays visible.
+const COPIED_FEEDBACK: Duration = Duration::from_secs(2);
+
+pub struct ResultScreen {
+    /// When the result text was last copied to the clipboard.
+    copied_at: Option<Instant>,
+}
 
 impl ResultScreen {
     pub fn new() -> Self {
-        Self
+        Self { copied_at: None }
     }
 
+    /// Draws the result screen. `share_template` is the text format used by
+    /// the "Copy result" button. Returns `true` when the screen should close.
     pub fn render(
         &mut self,
         ctx: &egui::Context,
         data: &GameResultData,
         hit_window: &HitWindow,
+        share_template: &str,
     ) -> bool {
         let mut should_close = false;
 
@@ -36,12 +47,24 @@ impl ResultScreen {
             .show(ctx, |ui| {
                 ui.vertical_centered(|ui| {
                     ui.add_space(10.0);
+                    let title = if data.course.is_some() {
+                        tr("result.course_title")
+                    } else {
+                        tr("result.title")
+                    };
                     ui.label(
-                        RichText::new("RESULTS")
+                        RichText::new(title)
                             .size(32.0)
                             .strong()
                             .color(Color32::WHITE),
                     );
+                    if data.incomplete {
+                        ui.label(
+                            RichText::new(tr("result.incomplete"))
+                                .size(16.0)
+                                .color(Color32::from_rgb(255, 170, 60)),
+                        );
+                    }
                     ui.add_space(30.0);
                 });
 
@@ -79,7 +102,7 @@ impl ResultScreen {
                 ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
                     ui.add_space(10.0);
                     let btn = ui.add(
-                        egui::Button::new(RichText::new("CONTINUE (Press Enter)").size(16.0))
+                        egui::Button::new(RichText::new(tr("result.continue")).size(16.0))
                             .fill(Color32::from_white_alpha(20))
                             .stroke(egui::Stroke::NONE),
                     );
@@ -87,6 +110,30 @@ impl ResultScreen {
                     if btn.clicked() {
                         should_close = true;
                     }
+
+                    ui.add_space(6.0);
+                    let copied = self
+                        .copied_at
+                        .is_some_and(|at| at.elapsed() < COPIED_FEEDBACK);
+                    let label = if copied {
+                        tr("result.copied")
+                    } else {
+                        tr("result.copy")
+                    };
+                    let copy = ui
+                        .add(
+                            egui::Button::new(RichText::new(label).size(14.0))
+                                .fill(Color32::from_white_alpha(10))
+                                .stroke(egui::Stroke::NONE),
+                        )
+                        .on_hover_text(data.share_text(share_template));
+                    if copy.clicked() {
+                        ctx.copy_text(data.share_text(share_template));
+                        self.copied_at = Some(Instant::now());
+                    }
+                    if copied {
+                        ctx.request_repaint_after(COPIED_FEEDBACK);
+                    }
                 });
             });
 
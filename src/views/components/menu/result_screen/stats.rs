@@ -1,5 +1,8 @@
 //! Stats panel for the result screen (score, accuracy, judgement bars).
-use crate::state::GameResultData;
+use crate::models::personal_best::PbResult;
+use crate::models::stats::{DriftStats, SyncQuality};
+use crate::shared::locale::{tr, tr_args};
+use crate::state::{CourseSummary, GameResultData};
 use egui::{Align2, Color32, FontId, Pos2, Rect, RichText, Ui, Vec2};
 
 pub fn render_stats(ui: &mut Ui, data: &GameResultData) {
@@ -50,12 +53,28 @@ pub fn render_stats(ui: &mut Ui, data: &GameResultData) {
                 .inner_margin(6.0)
                 .show(ui, |ui| {
                     ui.label(
-                        RichText::new(format!("{}  •  {:.1}x Rate", data.judge_text, data.rate))
-                            .size(16.0)
-                            .strong()
-                            .color(Color32::from_gray(220)),
+                        RichText::new(tr_args(
+                            "result.judge_rate",
+                            &[
+                                ("judge", &data.judge_text),
+                                ("rate", &format!("{:.1}", data.rate)),
+                            ],
+                        ))
+                        .size(16.0)
+                        .strong()
+                        .color(Color32::from_gray(220)),
                     );
                 });
+
+            if let Some(pb) = &data.personal_best {
+                ui.add_space(6.0);
+                render_personal_best(ui, pb, data.rate);
+            }
+
+            if let Some(drift) = &data.drift_stats {
+                ui.add_space(6.0);
+                render_sync_quality(ui, drift);
+            }
         });
 
         ui.add_space(30.0);
@@ -169,12 +188,127 @@ pub fn render_stats(ui: &mut Ui, data: &GameResultData) {
         // Ghost taps summary at the bottom.
         ui.add_space(10.0);
         ui.horizontal(|ui| {
-            ui.label(RichText::new("Ghost Taps:").color(Color32::GRAY));
+            ui.label(RichText::new(tr("result.ghost_taps")).color(Color32::GRAY));
             ui.label(
                 RichText::new(data.hit_stats.ghost_tap.to_string())
                     .strong()
                     .color(Color32::WHITE),
             );
         });
+
+        if let Some(course) = &data.course {
+            ui.add_space(20.0);
+            render_course_stages(ui, course);
+        }
     });
 }
+
+/// Per-chart breakdown shown under the totals when the result is a course.
+fn render_course_stages(ui: &mut Ui, course: &CourseSummary) {
+    ui.horizontal(|ui| {
+        ui.label(
+            RichText::new(&course.name)
+                .size(18.0)
+                .strong()
+                .color(Color32::WHITE),
+        );
+        let (status, color) = if course.is_cleared() {
+            (tr("result.cleared"), Color32::GOLD)
+        } else {
+            (tr("result.failed"), Color32::from_rgb(255, 80, 80))
+        };
+        ui.label(RichText::new(status).size(14.0).strong().color(color));
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ui.label(
+                RichText::new(format!("HP {:.0}%", course.final_health * 100.0))
+                    .size(14.0)
+                    .color(Color32::GRAY),
+            );
+        });
+    });
+
+    for (i, stage) in course.stages.iter().enumerate() {
+        ui.horizontal(|ui| {
+            let color = if stage.failed {
+                Color32::from_rgb(255, 80, 80)
+            } else {
+                Color32::from_gray(220)
+            };
+            ui.label(
+                RichText::new(format!("{}. {}", i + 1, stage.label))
+                    .size(14.0)
+                    .color(color),
+            );
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(
+                    RichText::new(format!(
+                        "{:07}  {:.2}%  {}x",
+                        stage.score, stage.accuracy, stage.max_combo
+                    ))
+                    .size(14.0)
+                    .color(Color32::from_gray(180)),
+                );
+            });
+        });
+    }
+
+    // Charts never reached because HP ran out.
+    for i in course.stages.len()..course.total_stages {
+        ui.label(
+            RichText::new(format!("{}. —", i + 1))
+                .size(14.0)
+                .color(Color32::DARK_GRAY),
+        );
+    }
+}
+
+/// New personal best banner, or how far the play is from the best, at the
+/// play's rate and judge.
+fn render_personal_best(ui: &mut Ui, pb: &PbResult, rate: f64) {
+    let (text, color) = match pb {
+        PbResult::First => (
+            tr_args("result.pb_first", &[("rate", &format!("{:.2}", rate))]),
+            Color32::from_rgb(100, 255, 100),
+        ),
+        PbResult::Improved { score, accuracy } => (
+            tr_args(
+                "result.pb_improved",
+                &[
+                    ("score", &score),
+                    ("accuracy", &format!("{:+.2}", accuracy)),
+                ],
+            ),
+            Color32::from_rgb(100, 255, 100),
+        ),
+        PbResult::Behind { score, accuracy } => (
+            tr_args(
+                "result.pb_behind",
+                &[
+                    ("score", &format!("{:+}", score)),
+                    ("accuracy", &format!("{:+.2}", accuracy)),
+                ],
+            ),
+            Color32::from_rgb(255, 140, 100),
+        ),
+    };
+    ui.label(RichText::new(text).size(16.0).strong().color(color))
+        .on_hover_text(tr("result.pb_hint"));
+}
+
+/// One-line audio sync indicator, to help spot driver/clock issues.
+fn render_sync_quality(ui: &mut Ui, drift: &DriftStats) {
+    let (label, color) = match drift.quality() {
+        SyncQuality::Good => ("result.sync_good", Color32::from_rgb(100, 255, 100)),
+        SyncQuality::Fair => ("result.sync_fair", Color32::from_rgb(255, 200, 80)),
+        SyncQuality::Poor => ("result.sync_poor", Color32::from_rgb(255, 80, 80)),
+    };
+
+    ui.label(RichText::new(tr(label)).size(14.0).strong().color(color))
+        .on_hover_text(tr_args(
+            "result.drift",
+            &[
+                ("mean", &format!("{:.2}", drift.mean_abs_drift_ms())),
+                ("max", &format!("{:.1}", drift.max_abs_drift_ms)),
+            ],
+        ));
+}
@@ -8,6 +8,7 @@ use egui::{
 use crate::database::models::{BeatmapRating, BeatmapWithRatings, Beatmapset};
 use crate::difficulty::BeatmapSsr;
 use crate::models::settings::HitWindowMode;
+use crate::shared::locale::{tr, tr_args};
 
 /// UI color configuration for the beatmap info panel.
 #[derive(Clone)]
@@ -71,6 +72,10 @@ pub struct BeatmapInfo {
     colors: BeatmapInfoColors,
     /// Whether the pattern breakdown section is expanded
     pattern_breakdown_expanded: bool,
+    /// Mapper name clicked this frame, consumed by the song select screen
+    creator_clicked: Option<String>,
+    /// Pack name clicked this frame, consumed by the song select screen
+    pack_clicked: Option<String>,
 }
 
 impl BeatmapInfo {
@@ -78,6 +83,8 @@ impl BeatmapInfo {
         Self {
             colors: BeatmapInfoColors::default(),
             pattern_breakdown_expanded: false,
+            creator_clicked: None,
+            pack_clicked: None,
         }
     }
 
@@ -86,15 +93,26 @@ impl BeatmapInfo {
         self.colors = colors;
     }
 
+    /// Returns the mapper the user clicked on since the last call, if any.
+    pub fn take_creator_click(&mut self) -> Option<String> {
+        self.creator_clicked.take()
+    }
+
+    /// Returns the pack the user clicked on since the last call, if any.
+    pub fn take_pack_click(&mut self) -> Option<String> {
+        self.pack_clicked.take()
+    }
+
     /// Renders the beatmap info panel.
     ///
     /// `active_calculator` - the currently selected calculator ID from MenuState
     /// `current_ssr` - the calculated SSR for the active calculator (from difficulty_cache)
+    /// `density` - per-second NPS of the cached chart, drawn under the badges
     /// Returns the new calculator ID if the user changed it via dropdown
     pub fn render(
         &mut self,
         ui: &mut Ui,
-        _beatmapset: &Beatmapset,
+        beatmapset: &Beatmapset,
         beatmap: Option<&BeatmapWithRatings>,
         rate: f64,
         hit_window_mode: HitWindowMode,
@@ -104,6 +122,7 @@ impl BeatmapInfo {
         available_calculators: &[CalculatorOption],
         active_calculator: &str,
         current_ssr: Option<&BeatmapSsr>,
+        density: Option<&[f32]>,
     ) -> Option<String> {
         let colors = self.colors.clone();
         let rounding = CornerRadius::same(12);
@@ -197,6 +216,61 @@ impl BeatmapInfo {
                             background_texture.is_some(),
                         );
 
+                        if let Some(density) = density.filter(|d| !d.is_empty()) {
+                            ui.add_space(6.0);
+                            self.render_density_graph(ui, density, rate, &colors);
+                        }
+
+                        if let Some(creator) = &beatmapset.creator {
+                            ui.add_space(4.0);
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    RichText::new(tr("song_select.info.mapped_by"))
+                                        .size(11.0)
+                                        .color(colors.text_muted),
+                                );
+                                let link = ui
+                                    .link(RichText::new(creator).size(11.0).color(colors.accent))
+                                    .on_hover_text(tr("song_select.info.mapper_hint"));
+                                if link.clicked() {
+                                    self.creator_clicked = Some(creator.clone());
+                                }
+                            });
+                        }
+
+                        if let Some(pack) = &beatmapset.pack {
+                            ui.add_space(4.0);
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    RichText::new(tr("song_select.info.pack"))
+                                        .size(11.0)
+                                        .color(colors.text_muted),
+                                );
+                                let link = ui
+                                    .link(RichText::new(pack).size(11.0).color(colors.accent))
+                                    .on_hover_text(tr("song_select.info.pack_hint"));
+                                if link.clicked() {
+                                    self.pack_clicked = Some(pack.clone());
+                                }
+                            });
+                        }
+
+                        if let Some(source) = &beatmapset.source {
+                            ui.add_space(4.0);
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    RichText::new(tr("song_select.info.source"))
+                                        .size(11.0)
+                                        .color(colors.text_muted),
+                                );
+                                ui.label(
+                                    RichText::new(source)
+                                        .size(11.0)
+                                        .color(colors.text_secondary),
+                                );
+                            });
+                        }
+
                         ui.add_space(10.0);
 
                         // Calculator dropdown + Rate display on same line
@@ -273,7 +347,7 @@ impl BeatmapInfo {
                             ui.add_space(12.0);
                             ui.centered_and_justified(|ui| {
                                 ui.label(
-                                    RichText::new("No rating data")
+                                    RichText::new(tr("song_select.info.no_rating"))
                                         .size(13.0)
                                         .italics()
                                         .color(colors.text_muted),
@@ -342,6 +416,86 @@ impl BeatmapInfo {
             });
     }
 
+    /// Sparkline of notes per second over the chart, scaled by the rate.
+    fn render_density_graph(
+        &self,
+        ui: &mut Ui,
+        density: &[f32],
+        rate: f64,
+        colors: &BeatmapInfoColors,
+    ) {
+        let height = 36.0;
+        let (rect, response) = ui.allocate_exact_size(
+            Vec2::new(ui.available_width(), height),
+            egui::Sense::hover(),
+        );
+        let rate = rate as f32;
+        let peak = density.iter().copied().fold(0.0_f32, f32::max) * rate;
+        if peak <= 0.0 {
+            return;
+        }
+
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(
+            rect,
+            CornerRadius::same(4),
+            Color32::from_rgba_unmultiplied(0, 0, 0, 90),
+        );
+
+        let step = rect.width() / density.len() as f32;
+        let point = |i: usize, nps: f32| {
+            Pos2::new(
+                rect.left() + (i as f32 + 0.5) * step,
+                rect.bottom() - (nps * rate / peak) * (height - 4.0),
+            )
+        };
+        let fill = Color32::from_rgba_unmultiplied(
+            colors.accent.r(),
+            colors.accent.g(),
+            colors.accent.b(),
+            60,
+        );
+        for (i, &nps) in density.iter().enumerate() {
+            let top = point(i, nps).y;
+            painter.rect_filled(
+                Rect::from_min_max(
+                    Pos2::new(rect.left() + i as f32 * step, top),
+                    Pos2::new(rect.left() + (i + 1) as f32 * step, rect.bottom()),
+                ),
+                0.0,
+                fill,
+            );
+        }
+        let line: Vec<Pos2> = density
+            .iter()
+            .enumerate()
+            .map(|(i, &nps)| point(i, nps))
+            .collect();
+        painter.add(egui::Shape::line(line, Stroke::new(1.5, colors.accent)));
+
+        painter.text(
+            rect.left_top() + Vec2::new(4.0, 2.0),
+            egui::Align2::LEFT_TOP,
+            tr_args(
+                "song_select.info.peak_nps",
+                &[("nps", &format!("{:.0}", peak))],
+            ),
+            egui::FontId::proportional(10.0),
+            colors.text_secondary,
+        );
+
+        if let Some(pos) = response.hover_pos() {
+            let second = (((pos.x - rect.left()) / step) as usize).min(density.len() - 1);
+            let elapsed = (second as f32 / rate) as u32;
+            response.on_hover_text(format!(
+                "{}:{:02} — {:.1} NPS",
+                elapsed / 60,
+                elapsed % 60,
+                density[second] * rate
+            ));
+        }
+    }
+
     fn render_calculator_dropdown(
         &self,
         ui: &mut Ui,
@@ -404,10 +558,7 @@ impl BeatmapInfo {
         colors: &BeatmapInfoColors,
         has_bg: bool,
     ) {
-        let hit_window_text = match hit_window_mode {
-            HitWindowMode::OsuOD => format!("OD {:.1}", hit_window_value),
-            HitWindowMode::EtternaJudge => format!("J{}", hit_window_value as u8),
-        };
+        let hit_window_text = hit_window_mode.label(hit_window_value);
 
         let bg = if has_bg {
             Color32::from_rgba_unmultiplied(0, 0, 0, 120)
@@ -477,7 +628,7 @@ impl BeatmapInfo {
     fn render_overall_value(&self, ui: &mut Ui, overall: f64, colors: &BeatmapInfoColors) {
         ui.horizontal(|ui| {
             ui.label(
-                RichText::new("Overall")
+                RichText::new(tr("song_select.info.overall"))
                     .size(13.0)
                     .color(colors.text_secondary),
             );
@@ -571,7 +722,7 @@ impl BeatmapInfo {
                     ui.label(RichText::new(arrow).size(10.0).color(colors.accent));
                     ui.add_space(4.0);
                     ui.label(
-                        RichText::new("Pattern Breakdown")
+                        RichText::new(tr("song_select.info.pattern_breakdown"))
                             .size(11.0)
                             .color(colors.text_secondary),
                     );
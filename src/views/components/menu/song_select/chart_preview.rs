@@ -0,0 +1,144 @@
+//! Mini playfield auto-playing the selected chart in song select.
+//!
+//! Drawn with the egui painter from the cached chart, so it costs nothing
+//! more than the notes on screen. The preview keeps its own clock (advanced
+//! at the selected rate) and loops over the chart.
+
+use egui::{Color32, CornerRadius, Pos2, Rect, Stroke, Ui, Vec2};
+
+use crate::state::ChartCache;
+
+/// Chart time visible above the receptors, at 1.0x.
+const VISIBLE_MS: f64 = 1200.0;
+/// Lead-in before the first note and pause after the last one.
+const LOOP_PADDING_MS: f64 = 1000.0;
+/// Holds starting this long before the current time are still looked up.
+const HOLD_LOOKBACK_MS: f64 = 10_000.0;
+const COLUMN_WIDTH: f32 = 26.0;
+const NOTE_HEIGHT: f32 = 8.0;
+
+pub struct ChartPreview {
+    beatmap_hash: Option<String>,
+    time_ms: f64,
+}
+
+impl ChartPreview {
+    pub fn new() -> Self {
+        Self {
+            beatmap_hash: None,
+            time_ms: 0.0,
+        }
+    }
+
+    /// Draws the preview centered in the available space and advances its clock.
+    pub fn render(&mut self, ui: &mut Ui, cache: &ChartCache, rate: f64) {
+        let Some(first) = cache.chart.first() else {
+            return;
+        };
+        let start_ms = first.timestamp_ms - LOOP_PADDING_MS;
+        let end_ms = cache
+            .chart
+            .iter()
+            .map(|n| n.end_time_ms())
+            .fold(first.timestamp_ms, f64::max)
+            + LOOP_PADDING_MS;
+
+        if self.beatmap_hash.as_deref() != Some(cache.beatmap_hash.as_str()) {
+            self.beatmap_hash = Some(cache.beatmap_hash.clone());
+            self.time_ms = start_ms;
+        } else {
+            let dt = ui.input(|i| i.stable_dt).min(0.1) as f64;
+            self.time_ms += dt * 1000.0 * rate;
+            if self.time_ms > end_ms {
+                self.time_ms = start_ms;
+            }
+        }
+        ui.ctx().request_repaint();
+
+        let columns = cache.chart.iter().map(|n| n.column + 1).max().unwrap_or(1);
+        let available = ui.available_rect_before_wrap();
+        let width = (columns as f32 * COLUMN_WIDTH).min(available.width() - 20.0);
+        let height = (available.height() * 0.7).min(520.0);
+        let rect = Rect::from_center_size(available.center(), Vec2::new(width, height));
+        ui.allocate_rect(available, egui::Sense::hover());
+
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(
+            rect,
+            CornerRadius::same(6),
+            Color32::from_rgba_unmultiplied(0, 0, 0, 200),
+        );
+
+        let column_width = rect.width() / columns as f32;
+        let receptor_y = rect.bottom() - NOTE_HEIGHT * 2.0;
+        // Pixels per chart millisecond; a higher rate scrolls faster, like in game.
+        let scale = (receptor_y - rect.top()) as f64 / VISIBLE_MS;
+        let y_at = |timestamp_ms: f64| receptor_y - ((timestamp_ms - self.time_ms) * scale) as f32;
+
+        for column in 0..columns {
+            let left = rect.left() + column as f32 * column_width;
+            painter.rect_stroke(
+                Rect::from_min_size(
+                    Pos2::new(left + 1.0, receptor_y - NOTE_HEIGHT / 2.0),
+                    Vec2::new(column_width - 2.0, NOTE_HEIGHT),
+                ),
+                2.0,
+                Stroke::new(1.0, Color32::from_gray(140)),
+                egui::StrokeKind::Inside,
+            );
+        }
+
+        let first_visible = cache
+            .chart
+            .partition_point(|n| n.timestamp_ms < self.time_ms - HOLD_LOOKBACK_MS);
+        for note in &cache.chart[first_visible..] {
+            if note.timestamp_ms > self.time_ms + VISIBLE_MS {
+                break;
+            }
+            // Auto-play: notes disappear once they reach the receptors.
+            if note.end_time_ms() < self.time_ms {
+                continue;
+            }
+
+            let left = rect.left() + note.column as f32 * column_width + 1.0;
+            let color = if note.note_type.is_mine() {
+                Color32::from_rgb(230, 70, 70)
+            } else {
+                column_color(note.column, columns)
+            };
+            let head_y = y_at(note.timestamp_ms.max(self.time_ms));
+
+            if note.note_type.has_duration() {
+                let tail_y = y_at(note.end_time_ms()).max(rect.top());
+                painter.rect_filled(
+                    Rect::from_min_max(
+                        Pos2::new(left + column_width * 0.2, tail_y),
+                        Pos2::new(left + column_width * 0.8 - 2.0, head_y),
+                    ),
+                    0.0,
+                    color.gamma_multiply(0.55),
+                );
+            }
+            painter.rect_filled(
+                Rect::from_min_size(
+                    Pos2::new(left, head_y - NOTE_HEIGHT / 2.0),
+                    Vec2::new(column_width - 2.0, NOTE_HEIGHT),
+                ),
+                2.0,
+                color,
+            );
+        }
+    }
+}
+
+/// Mirrored column colors (outer white, inner blue, odd middle gold).
+fn column_color(column: usize, columns: usize) -> Color32 {
+    let mirrored = column.min(columns - 1 - column);
+    if !columns.is_multiple_of(2) && column == columns / 2 {
+        Color32::from_rgb(240, 200, 80)
+    } else if mirrored.is_multiple_of(2) {
+        Color32::from_rgb(235, 235, 235)
+    } else {
+        Color32::from_rgb(100, 170, 255)
+    }
+}
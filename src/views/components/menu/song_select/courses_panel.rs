@@ -0,0 +1,223 @@
+//! Courses tab: chart lists played back to back with shared score and HP.
+
+use std::collections::HashMap;
+
+use egui::{Color32, CornerRadius, Frame, Margin, RichText, ScrollArea};
+
+use crate::database::models::CourseWithCharts;
+use crate::models::course::CourseDraft;
+use crate::shared::locale::{tr, tr_args};
+use crate::state::MenuState;
+use crate::views::components::menu::song_select::goals_panel::{build_chart_labels, chart_label};
+
+/// Message emitted by the courses panel.
+pub enum CoursesPanelEvent {
+    None,
+    Create(CourseDraft),
+    Delete(i64),
+    Start(i64),
+}
+
+/// Stateful course list plus the "new course" form.
+pub struct CoursesPanel {
+    draft: CourseDraft,
+    form_expanded: bool,
+}
+
+impl CoursesPanel {
+    pub fn new() -> Self {
+        Self {
+            draft: CourseDraft::default(),
+            form_expanded: false,
+        }
+    }
+
+    /// Draws the course list and returns an event when a course is created, removed or started.
+    pub fn render(&mut self, ui: &mut egui::Ui, menu_state: &MenuState) -> CoursesPanelEvent {
+        let mut event = CoursesPanelEvent::None;
+
+        egui::Frame::default()
+            .corner_radius(5.0)
+            .outer_margin(10.0)
+            .inner_margin(5.0)
+            .fill(Color32::from_rgba_unmultiplied(38, 38, 38, 230))
+            .show(ui, |ui| {
+                ui.set_width(ui.available_rect_before_wrap().width());
+                ui.set_height(ui.available_rect_before_wrap().height());
+
+                ui.horizontal(|ui| {
+                    ui.heading(tr("song_select.courses.title"));
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.label(
+                            RichText::new(format!("{:.2}x", menu_state.rate))
+                                .color(Color32::from_rgb(255, 200, 100)),
+                        );
+                    });
+                });
+                ui.separator();
+
+                if let Some(draft) = self.render_form(ui, menu_state) {
+                    event = CoursesPanelEvent::Create(draft);
+                }
+
+                ui.add_space(6.0);
+
+                if menu_state.courses.is_empty() {
+                    ui.centered_and_justified(|ui| {
+                        ui.label(tr("song_select.courses.empty"));
+                    });
+                    return;
+                }
+
+                ScrollArea::vertical()
+                    .id_salt("courses_scroll")
+                    .auto_shrink([false; 2])
+                    .show(ui, |ui| {
+                        for course in menu_state.courses.iter() {
+                            match render_course_card(ui, course) {
+                                CoursesPanelEvent::None => {}
+                                e => event = e,
+                            }
+                            ui.add_space(6.0);
+                        }
+                    });
+            });
+
+        event
+    }
+
+    /// Collapsible creation form; returns a draft when the user confirms it.
+    fn render_form(&mut self, ui: &mut egui::Ui, menu_state: &MenuState) -> Option<CourseDraft> {
+        let arrow = if self.form_expanded { "▼" } else { "▶" };
+        if ui
+            .selectable_label(
+                self.form_expanded,
+                format!("{} {}", arrow, tr("song_select.courses.new")),
+            )
+            .clicked()
+        {
+            self.form_expanded = !self.form_expanded;
+        }
+
+        if !self.form_expanded {
+            return None;
+        }
+
+        let chart_labels: HashMap<String, String> = build_chart_labels(menu_state);
+        let mut created = None;
+
+        ui.horizontal(|ui| {
+            ui.label(tr("song_select.courses.name"));
+            ui.text_edit_singleline(&mut self.draft.name);
+        });
+
+        let mut remove_idx = None;
+        for (i, hash) in self.draft.beatmap_hashes.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(
+                    RichText::new(format!("{}. {}", i + 1, chart_label(&chart_labels, hash)))
+                        .size(12.0),
+                );
+                if ui.small_button("✕").clicked() {
+                    remove_idx = Some(i);
+                }
+            });
+        }
+        if let Some(i) = remove_idx {
+            self.draft.beatmap_hashes.remove(i);
+        }
+
+        ui.horizontal(|ui| {
+            // The same chart may appear several times in a course.
+            let selected = menu_state.get_selected_beatmap_hash();
+            if ui
+                .add_enabled(
+                    selected.is_some(),
+                    egui::Button::new(tr("song_select.courses.add_chart")),
+                )
+                .clicked()
+                && let Some(hash) = selected
+            {
+                self.draft.beatmap_hashes.push(hash);
+            }
+
+            if ui
+                .add_enabled(
+                    self.draft.is_valid(),
+                    egui::Button::new(tr("song_select.courses.create")),
+                )
+                .clicked()
+            {
+                let mut draft = std::mem::take(&mut self.draft);
+                if draft.name.trim().is_empty() {
+                    draft.name = tr_args(
+                        "song_select.courses.default_name",
+                        &[("count", &draft.beatmap_hashes.len())],
+                    );
+                }
+                created = Some(draft);
+                self.form_expanded = false;
+            }
+        });
+
+        ui.separator();
+        created
+    }
+}
+
+/// Draws one course card; returns the action picked on it, if any.
+fn render_course_card(ui: &mut egui::Ui, course: &CourseWithCharts) -> CoursesPanelEvent {
+    let mut event = CoursesPanelEvent::None;
+    let playable = course.is_playable();
+
+    Frame::default()
+        .inner_margin(Margin::symmetric(12, 8))
+        .corner_radius(CornerRadius::same(8))
+        .fill(Color32::from_rgba_unmultiplied(45, 45, 50, 240))
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(&course.course.name).size(15.0).strong());
+                ui.label(
+                    RichText::new(tr_args(
+                        "song_select.courses.chart_count",
+                        &[("count", &course.charts.len())],
+                    ))
+                    .size(12.0)
+                    .color(Color32::from_gray(160)),
+                );
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui
+                        .small_button("🗑")
+                        .on_hover_text(tr("song_select.courses.delete"))
+                        .clicked()
+                    {
+                        event = CoursesPanelEvent::Delete(course.course.id);
+                    }
+                    let play = ui
+                        .add_enabled(playable, egui::Button::new(tr("song_select.courses.play")))
+                        .on_disabled_hover_text(tr("song_select.courses.missing_charts"));
+                    if play.clicked() {
+                        event = CoursesPanelEvent::Start(course.course.id);
+                    }
+                });
+            });
+
+            for (i, chart) in course.charts.iter().enumerate() {
+                let color = if chart.path.is_some() {
+                    Color32::from_gray(200)
+                } else {
+                    Color32::from_rgb(255, 80, 80)
+                };
+                ui.label(
+                    RichText::new(format!("{}. {}", i + 1, chart.label()))
+                        .size(12.0)
+                        .color(color),
+                );
+            }
+        });
+
+    event
+}
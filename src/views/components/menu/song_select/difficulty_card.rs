@@ -5,6 +5,7 @@ use egui::{
 };
 
 use crate::database::models::BeatmapWithRatings;
+use crate::shared::locale::tr;
 
 pub struct DifficultyCard;
 
@@ -88,16 +89,23 @@ impl DifficultyCard {
         let mut content_ui =
             ui.new_child(UiBuilder::new().max_rect(card_rect).layout(*ui.layout()));
 
+        // Charts that failed to load are dimmed in red.
+        let text_color = if beatmap.beatmap.load_error.is_some() {
+            Color32::from_rgb(230, 110, 110)
+        } else {
+            Color32::WHITE
+        };
         content_ui.vertical(|ui| {
             ui.centered_and_justified(|ui| {
                 if let Some(diff_name) = &beatmap.beatmap.difficulty_name {
                     ui.add(
-                        Label::new(RichText::new(diff_name).size(16.0).color(Color32::WHITE))
+                        Label::new(RichText::new(diff_name).size(16.0).color(text_color))
                             .selectable(false),
                     );
                 } else {
                     ui.add(
-                        Label::new(RichText::new("Unknown").size(16.0).weak()).selectable(false),
+                        Label::new(RichText::new(tr("song_select.unknown")).size(16.0).weak())
+                            .selectable(false),
                     );
                 }
             });
@@ -106,6 +114,9 @@ impl DifficultyCard {
         // Add spacing between rows of difficulties.
         ui.add_space(0.0);
 
-        response
+        match &beatmap.beatmap.load_error {
+            Some(error) => response.on_hover_text(error),
+            None => response,
+        }
     }
 }
@@ -0,0 +1,179 @@
+//! "Get songs" tab: search a beatmap mirror and download sets into `songs/`.
+
+use egui::{Color32, RichText, ScrollArea};
+
+use crate::downloader::JobStatus;
+use crate::downloader::mirror::MirrorSet;
+use crate::shared::locale::{tr, tr_args};
+use crate::state::MenuState;
+
+/// Message emitted by the download panel.
+pub enum DownloadPanelEvent {
+    None,
+    Search(String),
+    Download(i64),
+}
+
+/// Search field plus the result and download lists.
+pub struct DownloadPanel {
+    query: String,
+}
+
+impl DownloadPanel {
+    pub fn new() -> Self {
+        Self {
+            query: String::new(),
+        }
+    }
+
+    /// Draws the panel and returns an event when a search or download is requested.
+    pub fn render(&mut self, ui: &mut egui::Ui, menu_state: &MenuState) -> DownloadPanelEvent {
+        let mut event = DownloadPanelEvent::None;
+        let downloads = &menu_state.downloads;
+
+        egui::Frame::default()
+            .corner_radius(5.0)
+            .outer_margin(10.0)
+            .inner_margin(5.0)
+            .fill(Color32::from_rgba_unmultiplied(38, 38, 38, 230))
+            .show(ui, |ui| {
+                ui.set_width(ui.available_rect_before_wrap().width());
+                ui.set_height(ui.available_rect_before_wrap().height());
+
+                ui.heading(tr("song_select.download.title"));
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut self.query)
+                            .hint_text(tr("song_select.download.search_hint"))
+                            .desired_width(ui.available_width() - 70.0),
+                    );
+                    let submitted =
+                        response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if (ui.button(tr("song_select.download.search")).clicked() || submitted)
+                        && !downloads.searching
+                    {
+                        event = DownloadPanelEvent::Search(self.query.trim().to_string());
+                    }
+                });
+
+                if downloads.searching {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(tr("song_select.download.searching"));
+                    });
+                }
+                if let Some(error) = &downloads.search_error {
+                    ui.colored_label(Color32::from_rgb(255, 120, 120), error);
+                }
+
+                if !downloads.jobs.is_empty() {
+                    ui.add_space(4.0);
+                    ui.label(RichText::new(tr("song_select.download.downloads")).strong());
+                    for job in downloads.jobs.iter().rev() {
+                        ui.horizontal(|ui| {
+                            ui.label(&job.label);
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    ui.label(job_status_text(&job.status));
+                                },
+                            );
+                        });
+                    }
+                    ui.separator();
+                }
+
+                ScrollArea::vertical()
+                    .id_salt("mirror_results")
+                    .show(ui, |ui| {
+                        for set in &downloads.results {
+                            let job = downloads.jobs.iter().rev().find(|j| j.set_id == set.id);
+                            ui.horizontal(|ui| {
+                                ui.vertical(|ui| {
+                                    ui.label(RichText::new(set.label()).strong())
+                                        .on_hover_text(difficulty_list(set));
+                                    ui.label(
+                                        RichText::new(tr_args(
+                                            "song_select.download.by",
+                                            &[
+                                                ("creator", &set.creator),
+                                                ("difficulties", &difficulty_summary(set)),
+                                            ],
+                                        ))
+                                        .small()
+                                        .color(Color32::GRAY),
+                                    );
+                                });
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| {
+                                        let can_download = job.is_none_or(|j| {
+                                            matches!(j.status, JobStatus::Failed(_))
+                                        });
+                                        if ui
+                                            .add_enabled(
+                                                can_download,
+                                                egui::Button::new(tr(
+                                                    "song_select.download.download",
+                                                )),
+                                            )
+                                            .clicked()
+                                        {
+                                            event = DownloadPanelEvent::Download(set.id);
+                                        }
+                                    },
+                                );
+                            });
+                            ui.separator();
+                        }
+                    });
+            });
+
+        event
+    }
+}
+
+fn job_status_text(status: &JobStatus) -> String {
+    match status {
+        JobStatus::Downloading {
+            received,
+            total: Some(total),
+        } if *total > 0 => format!("{:.0}%", *received as f64 / *total as f64 * 100.0),
+        JobStatus::Downloading { received, .. } => {
+            format!("{:.1} MB", *received as f64 / 1_048_576.0)
+        }
+        JobStatus::Extracting => tr("song_select.download.extracting"),
+        JobStatus::Done => tr("song_select.download.imported"),
+        JobStatus::AlreadyInstalled => tr("song_select.download.already_installed"),
+        JobStatus::Failed(e) => tr_args("song_select.download.failed", &[("error", e)]),
+    }
+}
+
+/// One line per mania difficulty: "Hard (4K, 3.21★)".
+fn difficulty_list(set: &MirrorSet) -> String {
+    set.mania_difficulties()
+        .map(|d| format!("{} ({}K, {:.2}★)", d.version, d.keys as u32, d.stars))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// "4K 7K - 1.2-4.5★" style summary of a set's mania difficulties.
+fn difficulty_summary(set: &MirrorSet) -> String {
+    let mut keys: Vec<u32> = set.mania_difficulties().map(|d| d.keys as u32).collect();
+    keys.sort_unstable();
+    keys.dedup();
+    let keys = keys
+        .iter()
+        .map(|k| format!("{}K", k))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let stars = set.mania_difficulties().map(|d| d.stars);
+    let (min, max) = stars.fold((f64::MAX, f64::MIN), |(lo, hi), s| (lo.min(s), hi.max(s)));
+    if min > max {
+        return tr("song_select.download.no_mania");
+    }
+    format!("{} - {:.1}-{:.1}★", keys, min, max)
+}
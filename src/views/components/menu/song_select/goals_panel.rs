@@ -0,0 +1,371 @@
+//! Goals tab: lists user goals with progress and lets the player create new ones.
+
+use std::collections::HashMap;
+
+use egui::{Color32, CornerRadius, DragValue, Frame, Margin, ProgressBar, RichText, ScrollArea};
+
+use crate::database::models::GoalWithCharts;
+use crate::models::goal::GoalDraft;
+use crate::shared::locale::{tr, tr_args};
+use crate::state::MenuState;
+
+/// Message emitted by the goals panel.
+pub enum GoalsPanelEvent {
+    None,
+    Create(GoalDraft),
+    Delete(i64),
+}
+
+/// Stateful goal list plus the "new goal" form.
+pub struct GoalsPanel {
+    draft: GoalDraft,
+    form_expanded: bool,
+}
+
+impl GoalsPanel {
+    pub fn new() -> Self {
+        Self {
+            draft: GoalDraft::new(),
+            form_expanded: false,
+        }
+    }
+
+    /// Draws the goal list and returns an event when a goal is created or removed.
+    pub fn render(&mut self, ui: &mut egui::Ui, menu_state: &MenuState) -> GoalsPanelEvent {
+        let mut event = GoalsPanelEvent::None;
+        let chart_labels = build_chart_labels(menu_state);
+
+        egui::Frame::default()
+            .corner_radius(5.0)
+            .outer_margin(10.0)
+            .inner_margin(5.0)
+            .fill(Color32::from_rgba_unmultiplied(38, 38, 38, 230))
+            .show(ui, |ui| {
+                ui.set_width(ui.available_rect_before_wrap().width());
+                ui.set_height(ui.available_rect_before_wrap().height());
+
+                ui.horizontal(|ui| {
+                    ui.heading(tr("song_select.goals.title"));
+                    let completed = menu_state.goals.iter().filter(|g| g.is_completed()).count();
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.label(
+                            RichText::new(format!("{}/{}", completed, menu_state.goals.len()))
+                                .color(Color32::from_rgb(255, 215, 0)),
+                        );
+                    });
+                });
+                ui.separator();
+
+                if let Some(draft) = self.render_form(ui, menu_state, &chart_labels) {
+                    event = GoalsPanelEvent::Create(draft);
+                }
+
+                ui.add_space(6.0);
+
+                if menu_state.goals.is_empty() {
+                    ui.centered_and_justified(|ui| {
+                        ui.label(tr("song_select.goals.empty"));
+                    });
+                    return;
+                }
+
+                ScrollArea::vertical()
+                    .id_salt("goals_scroll")
+                    .auto_shrink([false; 2])
+                    .show(ui, |ui| {
+                        for goal in menu_state.goals.iter() {
+                            if render_goal_card(ui, goal, &chart_labels) {
+                                event = GoalsPanelEvent::Delete(goal.goal.id);
+                            }
+                            ui.add_space(6.0);
+                        }
+                    });
+            });
+
+        event
+    }
+
+    /// Collapsible creation form; returns a draft when the user confirms it.
+    fn render_form(
+        &mut self,
+        ui: &mut egui::Ui,
+        menu_state: &MenuState,
+        chart_labels: &HashMap<String, String>,
+    ) -> Option<GoalDraft> {
+        let arrow = if self.form_expanded { "▼" } else { "▶" };
+        if ui
+            .selectable_label(
+                self.form_expanded,
+                format!("{} {}", arrow, tr("song_select.goals.new")),
+            )
+            .clicked()
+        {
+            self.form_expanded = !self.form_expanded;
+        }
+
+        if !self.form_expanded {
+            return None;
+        }
+
+        let mut created = None;
+
+        ui.horizontal(|ui| {
+            ui.label(tr("song_select.goals.name"));
+            ui.text_edit_singleline(&mut self.draft.name);
+        });
+
+        ui.horizontal(|ui| {
+            let mut use_acc = self.draft.target_accuracy.is_some();
+            if ui
+                .checkbox(&mut use_acc, tr("song_select.goals.accuracy_at_least"))
+                .changed()
+            {
+                self.draft.target_accuracy = use_acc.then_some(96.0);
+            }
+            if let Some(acc) = self.draft.target_accuracy.as_mut() {
+                ui.add(
+                    DragValue::new(acc)
+                        .range(0.0..=100.0)
+                        .speed(0.05)
+                        .suffix("%"),
+                );
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let mut use_score = self.draft.target_score.is_some();
+            if ui
+                .checkbox(&mut use_score, tr("song_select.goals.score_at_least"))
+                .changed()
+            {
+                self.draft.target_score = use_score.then_some(900_000);
+            }
+            if let Some(score) = self.draft.target_score.as_mut() {
+                ui.add(DragValue::new(score).range(0..=1_000_000).speed(1000.0));
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr("song_select.goals.min_rate"));
+            ui.add(
+                DragValue::new(&mut self.draft.min_rate)
+                    .range(0.5..=2.0)
+                    .speed(0.05)
+                    .suffix("x"),
+            );
+        });
+
+        // Chart list (one chart = plain goal, several = played back to back in a course).
+        let mut remove_idx = None;
+        for (i, hash) in self.draft.beatmap_hashes.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(
+                    RichText::new(format!("{}. {}", i + 1, chart_label(chart_labels, hash)))
+                        .size(12.0),
+                );
+                if ui.small_button("✕").clicked() {
+                    remove_idx = Some(i);
+                }
+            });
+        }
+        if let Some(i) = remove_idx {
+            self.draft.beatmap_hashes.remove(i);
+        }
+
+        ui.horizontal(|ui| {
+            let selected = menu_state.get_selected_beatmap_hash();
+            let can_add = selected
+                .as_ref()
+                .is_some_and(|h| !self.draft.beatmap_hashes.contains(h));
+            if ui
+                .add_enabled(
+                    can_add,
+                    egui::Button::new(tr("song_select.goals.add_chart")),
+                )
+                .clicked()
+                && let Some(hash) = selected
+            {
+                self.draft.beatmap_hashes.push(hash);
+            }
+
+            if ui
+                .add_enabled(
+                    self.draft.is_valid(),
+                    egui::Button::new(tr("song_select.goals.create")),
+                )
+                .clicked()
+            {
+                let mut draft = std::mem::take(&mut self.draft);
+                if draft.name.trim().is_empty() {
+                    draft.name = chart_label(chart_labels, &draft.beatmap_hashes[0]);
+                    if draft.beatmap_hashes.len() > 1 {
+                        draft.name =
+                            format!("{} (+{})", draft.name, draft.beatmap_hashes.len() - 1);
+                    }
+                }
+                created = Some(draft);
+                self.form_expanded = false;
+            }
+        });
+
+        ui.separator();
+        created
+    }
+}
+
+/// Draws one goal; returns `true` when its delete button was clicked.
+fn render_goal_card(
+    ui: &mut egui::Ui,
+    goal: &GoalWithCharts,
+    chart_labels: &HashMap<String, String>,
+) -> bool {
+    let mut delete = false;
+    let completed = goal.is_completed();
+    let bg = if completed {
+        Color32::from_rgba_unmultiplied(80, 70, 30, 240)
+    } else {
+        Color32::from_rgba_unmultiplied(45, 45, 50, 240)
+    };
+
+    Frame::default()
+        .inner_margin(Margin::symmetric(12, 8))
+        .corner_radius(CornerRadius::same(8))
+        .fill(bg)
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(&goal.goal.name).size(15.0).strong());
+
+                if completed {
+                    Frame::default()
+                        .inner_margin(Margin::symmetric(6, 2))
+                        .corner_radius(CornerRadius::same(4))
+                        .fill(Color32::from_rgb(255, 215, 0))
+                        .show(ui, |ui| {
+                            ui.label(
+                                RichText::new(tr("song_select.goals.cleared"))
+                                    .size(10.0)
+                                    .strong()
+                                    .color(Color32::BLACK),
+                            );
+                        });
+                } else if goal.charts.len() > 1 {
+                    Frame::default()
+                        .inner_margin(Margin::symmetric(6, 2))
+                        .corner_radius(CornerRadius::same(4))
+                        .fill(Color32::from_rgb(180, 100, 255))
+                        .show(ui, |ui| {
+                            ui.label(
+                                RichText::new(tr("song_select.goals.course"))
+                                    .size(10.0)
+                                    .strong()
+                                    .color(Color32::WHITE),
+                            );
+                        })
+                        .response
+                        .on_hover_text(tr("song_select.goals.course_hint"));
+                }
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui
+                        .small_button("🗑")
+                        .on_hover_text(tr("song_select.goals.delete"))
+                        .clicked()
+                    {
+                        delete = true;
+                    }
+                });
+            });
+
+            ui.label(
+                RichText::new(format_targets(goal))
+                    .size(12.0)
+                    .color(Color32::from_rgb(255, 200, 100)),
+            );
+
+            ui.add(
+                ProgressBar::new(goal.progress())
+                    .desired_height(6.0)
+                    .fill(if completed {
+                        Color32::from_rgb(255, 215, 0)
+                    } else {
+                        Color32::from_rgb(100, 200, 255)
+                    }),
+            );
+
+            for chart in &goal.charts {
+                ui.horizontal(|ui| {
+                    let (mark, color) = if chart.cleared {
+                        ("✔", Color32::from_rgb(100, 255, 100))
+                    } else {
+                        ("○", Color32::from_gray(150))
+                    };
+                    ui.label(RichText::new(mark).color(color));
+                    ui.label(
+                        RichText::new(format!(
+                            "{}. {}",
+                            chart.position + 1,
+                            chart_label(chart_labels, &chart.beatmap_hash)
+                        ))
+                        .size(12.0),
+                    );
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let best = if goal.goal.target_score.is_some() {
+                            format!("{:.2}% · {}", chart.best_accuracy, chart.best_score)
+                        } else {
+                            format!("{:.2}%", chart.best_accuracy)
+                        };
+                        ui.label(
+                            RichText::new(best)
+                                .size(12.0)
+                                .color(Color32::from_gray(180)),
+                        );
+                    });
+                });
+            }
+        });
+
+    delete
+}
+
+fn format_targets(goal: &GoalWithCharts) -> String {
+    let mut parts = Vec::new();
+    if let Some(acc) = goal.goal.target_accuracy {
+        parts.push(format!("≥ {:.2}%", acc));
+    }
+    if let Some(score) = goal.goal.target_score {
+        parts.push(tr_args(
+            "song_select.goals.score_target",
+            &[("score", &score)],
+        ));
+    }
+    parts.push(format!("@ {:.2}x+", goal.goal.min_rate));
+    parts.join("  ")
+}
+
+/// Maps beatmap hashes to "Title [Difficulty]" for the currently loaded sets.
+pub(super) fn build_chart_labels(menu_state: &MenuState) -> HashMap<String, String> {
+    let mut labels = HashMap::new();
+    for (set, beatmaps) in menu_state.beatmapsets.iter() {
+        let title = set
+            .display_title(menu_state.unicode_metadata)
+            .map(str::to_string)
+            .unwrap_or_else(|| tr("song_select.unknown"));
+        for bm in beatmaps {
+            let label = match &bm.beatmap.difficulty_name {
+                Some(diff) => format!("{} [{}]", title, diff),
+                None => title.clone(),
+            };
+            labels.insert(bm.beatmap.hash.clone(), label);
+        }
+    }
+    labels
+}
+
+pub(super) fn chart_label(labels: &HashMap<String, String>, hash: &str) -> String {
+    labels
+        .get(hash)
+        .cloned()
+        .unwrap_or_else(|| hash.chars().take(8).collect())
+}
@@ -3,32 +3,41 @@
 use std::path::Path;
 
 use crate::database::replay_storage;
+use crate::input::events::GameAction;
 use crate::models::engine::NoteData;
 use crate::models::engine::hit_window::HitWindow;
-use crate::models::replay::{ReplayData, ReplayResult, simulate_replay};
+use crate::models::ghost::{GhostChoice, VersusChoice};
+use crate::models::rejudge::{ReplayRejudger, ScoreId};
+use crate::models::replay::{ReplayData, ReplayResult};
+use crate::models::settings::{HitWindowMode, LeaderboardOptions, LeaderboardSort};
 use crate::models::stats::HitStats;
+use crate::shared::locale::tr;
 use crate::state::GameResultData;
-use crate::views::components::menu::song_select::leaderboard_card::LeaderboardCard;
-use egui::{Color32, ScrollArea};
+use crate::views::components::menu::song_select::leaderboard_card::{LeaderboardCard, RunKind};
+use egui::{Color32, ComboBox, ScrollArea};
 
 #[derive(Clone)]
 pub struct ScoreCard {
     pub timestamp: i64,
     pub rate: f64,
+    /// Hit window the score was set with.
+    pub hit_window_mode: HitWindowMode,
+    pub hit_window_value: f64,
     pub replay_data: ReplayData,
     pub total_notes: usize,
     pub score: i32,
     pub accuracy: f64,
     pub max_combo: i32,
     pub beatmap_hash: String,
-    /// Résultat de simulation (recalculé avec la chart cachée).
-    pub cached_result: Option<ReplayResult>,
+    /// Best score of its rate and judge.
+    pub personal_best: bool,
 }
 
 impl ScoreCard {
     pub fn from_replay(
         replay: &crate::database::models::Replay,
         total_notes: usize,
+        personal_best: bool,
     ) -> Option<Self> {
         // Load replay data from compressed file (binary)
         let replay_data = replay_storage::load_replay_from_path(Path::new(&replay.file_path))
@@ -37,52 +46,164 @@ impl ScoreCard {
         Some(ScoreCard {
             timestamp: replay.timestamp,
             rate: replay.rate,
+            hit_window_mode: replay_data.hit_window_mode,
+            hit_window_value: replay_data.hit_window_value,
             replay_data,
             total_notes,
             score: replay.score,
             accuracy: replay.accuracy,
             max_combo: replay.max_combo,
             beatmap_hash: replay.beatmap_hash.clone(),
-            cached_result: None,
+            personal_best,
         })
     }
 
-    /// Simule le replay avec la chart et le hit window donnés.
-    /// Met à jour le cache de résultat.
-    pub fn simulate_with_chart(&mut self, chart: &[NoteData], hit_window: &HitWindow) {
-        let result = simulate_replay(&self.replay_data, chart, hit_window);
-        self.cached_result = Some(result);
+    fn id(&self) -> ScoreId {
+        (self.beatmap_hash.clone(), self.timestamp)
+    }
+
+    /// Whether the score was set with the given hit window.
+    fn matches_judge(&self, mode: HitWindowMode, value: f64) -> bool {
+        self.hit_window_mode == mode && (self.hit_window_value - value).abs() < 1e-6
     }
 }
 
 pub struct Leaderboard {
     scores: Vec<ScoreCard>,
+    /// Replays simulated at the current hit window.
+    rejudger: ReplayRejudger,
 }
 
 impl Leaderboard {
     pub fn new() -> Self {
-        Self { scores: Vec::new() }
+        Self {
+            scores: Vec::new(),
+            rejudger: ReplayRejudger::new(),
+        }
     }
 
     pub fn update_scores(&mut self, scores: Vec<ScoreCard>) {
         self.scores = scores;
     }
 
-    /// Simule tous les replays avec la chart et le hit window donnés.
-    pub fn simulate_all(&mut self, chart: &[NoteData], hit_window: &HitWindow) {
-        for score in &mut self.scores {
-            score.simulate_with_chart(chart, hit_window);
+    /// Accuracy shown on the card: re-judged if available, stored otherwise.
+    fn display_accuracy(&self, card: &ScoreCard, hit_window: &HitWindow) -> f64 {
+        self.rejudger
+            .get(&card.id(), hit_window)
+            .map_or(card.accuracy, |r| r.accuracy)
+    }
+
+    /// Indices of the scores to show, filtered and sorted per `options`.
+    fn visible_scores(
+        &self,
+        options: &LeaderboardOptions,
+        rate: f64,
+        hit_window: &HitWindow,
+        hit_window_mode: HitWindowMode,
+        hit_window_value: f64,
+    ) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..self.scores.len())
+            .filter(|&i| {
+                let card = &self.scores[i];
+                (!options.current_rate_only || (card.rate - rate).abs() < 0.001)
+                    && (!options.current_judge_only
+                        || card.matches_judge(hit_window_mode, hit_window_value))
+            })
+            .collect();
+
+        let scores = &self.scores;
+        match options.sort {
+            LeaderboardSort::Score => {
+                indices.sort_by(|&a, &b| scores[b].score.cmp(&scores[a].score))
+            }
+            LeaderboardSort::Accuracy => indices.sort_by(|&a, &b| {
+                self.display_accuracy(&scores[b], hit_window)
+                    .total_cmp(&self.display_accuracy(&scores[a], hit_window))
+            }),
+            LeaderboardSort::Date => {
+                indices.sort_by(|&a, &b| scores[b].timestamp.cmp(&scores[a].timestamp))
+            }
         }
+        indices
+    }
+
+    /// Draws the filter / sort controls; returns the new options if changed.
+    fn render_options(
+        ui: &mut egui::Ui,
+        options: &LeaderboardOptions,
+    ) -> Option<LeaderboardOptions> {
+        let mut edited = *options;
+
+        ui.horizontal(|ui| {
+            ui.checkbox(
+                &mut edited.current_rate_only,
+                tr("song_select.leaderboard.this_rate"),
+            );
+            ui.checkbox(
+                &mut edited.current_judge_only,
+                tr("song_select.leaderboard.this_judge"),
+            );
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ComboBox::from_id_salt("leaderboard_sort")
+                    .selected_text(sort_label(edited.sort))
+                    .width(90.0)
+                    .show_ui(ui, |ui| {
+                        for sort in [
+                            LeaderboardSort::Score,
+                            LeaderboardSort::Accuracy,
+                            LeaderboardSort::Date,
+                        ] {
+                            ui.selectable_value(&mut edited.sort, sort, sort_label(sort));
+                        }
+                    });
+            });
+        });
+
+        (edited != *options).then_some(edited)
     }
 
+    /// Renders the leaderboard; returns the clicked score (if any), the
+    /// filter / sort options when the user changed them and a `SetGhost`
+    /// action when a score is picked (or dropped) as the replay ghost, or a
+    /// `StartVersus` action when a versus race is started.
+    ///
+    /// Scores are shown re-judged at `hit_window` once their replays went
+    /// through the rejudge thread; scores set at another judge also show
+    /// their original accuracy.
     pub fn render(
-        &self,
+        &mut self,
         ui: &mut egui::Ui,
         _difficulty_name: Option<&str>,
         hit_window: &HitWindow,
         chart: Option<&[NoteData]>,
-    ) -> Option<GameResultData> {
+        options: &LeaderboardOptions,
+        rate: f64,
+        hit_window_mode: HitWindowMode,
+        hit_window_value: f64,
+        ghost: Option<&GhostChoice>,
+    ) -> (
+        Option<GameResultData>,
+        Option<LeaderboardOptions>,
+        Option<GameAction>,
+    ) {
         let mut clicked_result = None;
+        let mut options_changed = None;
+        let mut ghost_action = None;
+
+        self.rejudger.poll();
+        if let Some(chart) = chart {
+            self.rejudger.request(
+                chart,
+                *hit_window,
+                self.scores
+                    .iter()
+                    .map(|card| (card.id(), &card.replay_data)),
+            );
+        }
+        if self.rejudger.is_busy() {
+            ui.ctx().request_repaint();
+        }
 
         egui::Frame::default()
             .corner_radius(5.0)
@@ -93,37 +214,47 @@ impl Leaderboard {
                 ui.set_width(ui.available_rect_before_wrap().width());
                 ui.set_height(ui.available_rect_before_wrap().height());
 
-                ui.heading("Top Scores");
+                ui.heading(tr("song_select.leaderboard.title"));
+                options_changed = Self::render_options(ui, options);
                 ui.separator();
 
+                let options = options_changed.as_ref().unwrap_or(options);
+                let visible = self.visible_scores(
+                    options,
+                    rate,
+                    hit_window,
+                    hit_window_mode,
+                    hit_window_value,
+                );
+
                 if self.scores.is_empty() {
                     ui.centered_and_justified(|ui| {
-                        ui.label("No Score Set");
+                        ui.label(tr("song_select.leaderboard.no_score"));
+                    });
+                } else if visible.is_empty() {
+                    ui.centered_and_justified(|ui| {
+                        ui.label(tr("song_select.leaderboard.no_match"));
                     });
                 } else {
                     ScrollArea::vertical()
                         .auto_shrink([false; 2])
                         .show(ui, |ui| {
-                            for (i, card) in self.scores.iter().take(10).enumerate() {
-                                // Utiliser le résultat simulé si disponible, sinon recalculer à la volée
+                            for (i, card) in visible
+                                .iter()
+                                .take(10)
+                                .map(|&idx| &self.scores[idx])
+                                .enumerate()
+                            {
+                                // Résultat re-jugé au hit window courant s'il est prêt
+                                let rejudged = self.rejudger.get(&card.id(), hit_window);
                                 let (hit_stats, accuracy, max_combo, replay_result) =
-                                    if let Some(ref result) = card.cached_result {
+                                    if let Some(result) = rejudged {
                                         (
                                             result.hit_stats.clone(),
                                             result.accuracy,
                                             result.max_combo as i32,
                                             result.clone(),
                                         )
-                                    } else if let Some(chart) = chart {
-                                        // Simuler à la volée si on a la chart
-                                        let result =
-                                            simulate_replay(&card.replay_data, chart, hit_window);
-                                        (
-                                            result.hit_stats.clone(),
-                                            result.accuracy,
-                                            result.max_combo as i32,
-                                            result,
-                                        )
                                     } else {
                                         // Fallback: utiliser les données stockées
                                         (
@@ -135,7 +266,12 @@ impl Leaderboard {
                                     };
 
                                 // Détecte si c'est un score practice depuis le replay_data
-                                let is_practice = card.replay_data.is_practice_mode;
+                                let kind = RunKind::of(&card.replay_data);
+                                let is_practice = kind == RunKind::Practice;
+                                // Score set at another judge: keep its own accuracy in view.
+                                let original_accuracy = (rejudged.is_some()
+                                    && !card.matches_judge(hit_window_mode, hit_window_value))
+                                .then_some(card.accuracy);
 
                                 let response = LeaderboardCard::render(
                                     ui,
@@ -145,14 +281,95 @@ impl Leaderboard {
                                     card.timestamp,
                                     max_combo,
                                     &hit_stats,
-                                    is_practice,
+                                    &card.hit_window_mode.label(card.hit_window_value),
+                                    original_accuracy,
+                                    kind,
+                                    card.personal_best,
                                 );
 
+                                response.context_menu(|ui| {
+                                    let exportable = !replay_result.hit_timings.is_empty();
+                                    if ui
+                                        .add_enabled(
+                                            exportable,
+                                            egui::Button::new(tr(
+                                                "song_select.leaderboard.export_csv",
+                                            )),
+                                        )
+                                        .on_disabled_hover_text(tr(
+                                            "song_select.leaderboard.chart_not_loaded",
+                                        ))
+                                        .clicked()
+                                    {
+                                        export_judgements(card, &replay_result);
+                                        ui.close();
+                                    }
+
+                                    let is_ghost = ghost.is_some_and(|g| {
+                                        g.beatmap_hash == card.beatmap_hash
+                                            && g.replay_data == card.replay_data
+                                    });
+                                    if is_ghost {
+                                        if ui
+                                            .button(tr("song_select.leaderboard.stop_ghost"))
+                                            .clicked()
+                                        {
+                                            ghost_action = Some(GameAction::SetGhost(None));
+                                            ui.close();
+                                        }
+                                    } else if ui
+                                        .button(tr("song_select.leaderboard.race_ghost"))
+                                        .on_hover_text(tr(
+                                            "song_select.leaderboard.race_ghost_hint",
+                                        ))
+                                        .clicked()
+                                    {
+                                        ghost_action =
+                                            Some(GameAction::SetGhost(Some(GhostChoice {
+                                                beatmap_hash: card.beatmap_hash.clone(),
+                                                replay_data: card.replay_data.clone(),
+                                                accuracy,
+                                            })));
+                                        ui.close();
+                                    }
+
+                                    ui.separator();
+                                    if ui
+                                        .button(tr("song_select.leaderboard.versus"))
+                                        .on_hover_text(tr("song_select.leaderboard.versus_hint"))
+                                        .clicked()
+                                    {
+                                        ghost_action =
+                                            Some(GameAction::StartVersus(VersusChoice {
+                                                beatmap_hash: card.beatmap_hash.clone(),
+                                                opponent: card.replay_data.clone(),
+                                                player: None,
+                                            }));
+                                        ui.close();
+                                    }
+                                    let raced = ghost.filter(|g| {
+                                        g.beatmap_hash == card.beatmap_hash && !is_ghost
+                                    });
+                                    if let Some(raced) = raced
+                                        && ui
+                                            .button(tr("song_select.leaderboard.versus_raced"))
+                                            .clicked()
+                                    {
+                                        ghost_action =
+                                            Some(GameAction::StartVersus(VersusChoice {
+                                                beatmap_hash: card.beatmap_hash.clone(),
+                                                opponent: card.replay_data.clone(),
+                                                player: Some(raced.replay_data.clone()),
+                                            }));
+                                        ui.close();
+                                    }
+                                });
+
                                 if response.clicked() {
                                     let judge_text = if is_practice {
-                                        "Practice Replay".to_string()
+                                        tr("song_select.leaderboard.practice_replay")
                                     } else {
-                                        "Replay View".to_string()
+                                        tr("song_select.leaderboard.replay_view")
                                     };
 
                                     clicked_result = Some(GameResultData {
@@ -163,13 +380,18 @@ impl Leaderboard {
                                         accuracy,
                                         max_combo: max_combo as u32,
                                         beatmap_hash: Some(card.beatmap_hash.clone()),
+                                        map_title: String::new(),
                                         rate: card.rate,
                                         judge_text,
                                         show_settings: false,
+                                        drift_stats: None,
+                                        course: None,
+                                        personal_best: None,
+                                        incomplete: card.replay_data.incomplete,
                                     });
                                 }
 
-                                if i < self.scores.len().min(10).saturating_sub(1) {
+                                if i < visible.len().min(10).saturating_sub(1) {
                                     ui.add_space(6.0);
                                 }
                             }
@@ -177,6 +399,35 @@ impl Leaderboard {
                 }
             });
 
-        clicked_result
+        (clicked_result, options_changed, ghost_action)
     }
 }
+
+/// Asks where to save the per-note judgement table of a score, then writes it.
+fn export_judgements(card: &ScoreCard, result: &ReplayResult) {
+    let file_name = format!(
+        "{}_{}_{:.2}x.csv",
+        card.beatmap_hash.get(..8).unwrap_or(&card.beatmap_hash),
+        card.timestamp,
+        card.rate
+    );
+    let Some(path) = rfd::FileDialog::new()
+        .add_filter("CSV", &["csv"])
+        .set_file_name(file_name)
+        .save_file()
+    else {
+        return;
+    };
+    match std::fs::write(&path, result.judgement_csv()) {
+        Ok(()) => log::info!("LEADERBOARD: Judgements exported to {:?}", path),
+        Err(e) => log::error!("LEADERBOARD: Failed to export judgements: {}", e),
+    }
+}
+
+fn sort_label(sort: LeaderboardSort) -> String {
+    tr(match sort {
+        LeaderboardSort::Score => "song_select.leaderboard.sort_score",
+        LeaderboardSort::Accuracy => "song_select.leaderboard.sort_accuracy",
+        LeaderboardSort::Date => "song_select.leaderboard.sort_date",
+    })
+}
@@ -1,8 +1,31 @@
+use crate::models::replay::ReplayData;
 use crate::models::stats::HitStats;
+use crate::shared::locale::{tr, tr_args};
 use egui::{Color32, CornerRadius, RichText, Sense, Stroke, Vec2};
 
 pub struct LeaderboardCard;
 
+/// Kind of run a score comes from, shown as a badge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunKind {
+    Full,
+    Practice,
+    /// Recovered after a crash, judged up to where it stopped.
+    Incomplete,
+}
+
+impl RunKind {
+    pub fn of(data: &ReplayData) -> Self {
+        if data.is_practice_mode {
+            RunKind::Practice
+        } else if data.incomplete {
+            RunKind::Incomplete
+        } else {
+            RunKind::Full
+        }
+    }
+}
+
 impl LeaderboardCard {
     pub fn render(
         ui: &mut egui::Ui,
@@ -12,7 +35,10 @@ impl LeaderboardCard {
         timestamp: i64,
         max_combo: i32,
         hit_stats: &HitStats,
-        is_practice: bool,
+        judge: &str,
+        original_accuracy: Option<f64>,
+        kind: RunKind,
+        is_personal_best: bool,
     ) -> egui::Response {
         let available_width = ui.available_width();
 
@@ -37,7 +63,7 @@ impl LeaderboardCard {
         };
 
         // Practice mode a une teinte différente
-        let card_bg = if is_practice {
+        let card_bg = if kind == RunKind::Practice {
             Color32::from_rgba_unmultiplied(50, 40, 60, 240) // Purple tint
         } else {
             bg_color
@@ -65,21 +91,58 @@ impl LeaderboardCard {
                             .color(rank_color),
                     );
 
-                    // Practice badge
-                    if is_practice {
+                    // Meilleur score de son rate et de son judge
+                    if is_personal_best {
                         ui.add_space(8.0);
                         egui::Frame::default()
                             .inner_margin(egui::Margin::symmetric(6, 2))
                             .corner_radius(CornerRadius::same(4))
-                            .fill(Color32::from_rgb(180, 100, 255))
+                            .fill(Color32::from_rgb(60, 170, 90))
+                            .show(ui, |ui| {
+                                ui.label(
+                                    RichText::new(tr("song_select.card.pb"))
+                                        .size(10.0)
+                                        .strong()
+                                        .color(Color32::WHITE),
+                                );
+                            })
+                            .response
+                            .on_hover_text(tr_args(
+                                "song_select.card.pb_hint",
+                                &[("rate", &format!("{:.2}", rate)), ("judge", &judge)],
+                            ));
+                    }
+
+                    // Badge practice / incomplet
+                    let badge = match kind {
+                        RunKind::Full => None,
+                        RunKind::Practice => Some((
+                            tr("song_select.card.practice"),
+                            Color32::from_rgb(180, 100, 255),
+                        )),
+                        RunKind::Incomplete => Some((
+                            tr("song_select.card.incomplete"),
+                            Color32::from_rgb(220, 130, 40),
+                        )),
+                    };
+                    if let Some((text, color)) = badge {
+                        ui.add_space(8.0);
+                        let response = egui::Frame::default()
+                            .inner_margin(egui::Margin::symmetric(6, 2))
+                            .corner_radius(CornerRadius::same(4))
+                            .fill(color)
                             .show(ui, |ui| {
                                 ui.label(
-                                    RichText::new("PRACTICE")
+                                    RichText::new(text)
                                         .size(10.0)
                                         .strong()
                                         .color(Color32::WHITE),
                                 );
-                            });
+                            })
+                            .response;
+                        if kind == RunKind::Incomplete {
+                            response.on_hover_text(tr("song_select.card.incomplete_hint"));
+                        }
                     }
 
                     // Accuracy (right aligned)
@@ -91,6 +154,19 @@ impl LeaderboardCard {
                                 .strong()
                                 .color(acc_color),
                         );
+
+                        // Re-judged score: the accuracy it was set with
+                        if let Some(original) = original_accuracy {
+                            ui.label(
+                                RichText::new(tr_args(
+                                    "song_select.card.original_accuracy",
+                                    &[("accuracy", &format!("{:.2}", original)), ("judge", &judge)],
+                                ))
+                                .size(12.0)
+                                .color(Color32::from_rgb(150, 150, 160)),
+                            )
+                            .on_hover_text(tr("song_select.card.original_accuracy_hint"));
+                        }
                     });
                 });
 
@@ -114,6 +190,15 @@ impl LeaderboardCard {
                             .color(Color32::from_rgb(100, 200, 255)),
                     );
 
+                    ui.add_space(12.0);
+
+                    // Hit window the score was set with
+                    ui.label(
+                        RichText::new(judge)
+                            .size(13.0)
+                            .color(Color32::from_rgb(180, 180, 190)),
+                    );
+
                     // Date (right aligned)
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                         let date_str = format_date(timestamp);
@@ -208,14 +293,14 @@ fn format_date(timestamp: i64) -> String {
     let diff = now - timestamp;
 
     if diff < 60 {
-        "Just now".to_string()
+        tr("song_select.card.just_now")
     } else if diff < 3600 {
-        format!("{}m ago", diff / 60)
+        tr_args("song_select.card.minutes_ago", &[("count", &(diff / 60))])
     } else if diff < 86400 {
-        format!("{}h ago", diff / 3600)
+        tr_args("song_select.card.hours_ago", &[("count", &(diff / 3600))])
     } else if diff < 604800 {
-        format!("{}d ago", diff / 86400)
+        tr_args("song_select.card.days_ago", &[("count", &(diff / 86400))])
     } else {
-        format!("{}w ago", diff / 604800)
+        tr_args("song_select.card.weeks_ago", &[("count", &(diff / 604800))])
     }
 }
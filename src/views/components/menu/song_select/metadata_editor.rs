@@ -0,0 +1,116 @@
+//! Window editing the title, artist, tags and source of a set. The edit is
+//! only stored in the database; the chart files are never rewritten.
+
+use crate::database::Beatmapset;
+use crate::database::models::MetadataEdit;
+use crate::shared::locale::tr;
+
+/// Message emitted by the metadata editor.
+pub enum MetadataEditorEvent {
+    None,
+    Save(i64, MetadataEdit),
+    /// Drop the edit and go back to the values of the files.
+    Reset(i64),
+}
+
+/// Set being edited, with the form values.
+struct Editing {
+    beatmapset_id: i64,
+    label: String,
+    draft: MetadataEdit,
+    edited: bool,
+}
+
+pub struct MetadataEditor {
+    editing: Option<Editing>,
+}
+
+impl MetadataEditor {
+    pub fn new() -> Self {
+        Self { editing: None }
+    }
+
+    /// Opens the window on `set`, filled with the metadata shown for it.
+    pub fn open(&mut self, set: &Beatmapset, unicode_metadata: bool) {
+        let field = |value: Option<&str>| value.unwrap_or_default().to_string();
+        self.editing = Some(Editing {
+            beatmapset_id: set.id,
+            label: set.path.clone(),
+            draft: MetadataEdit {
+                title: field(set.display_title(unicode_metadata)),
+                artist: field(set.display_artist(unicode_metadata)),
+                tags: field(set.tags.as_deref()),
+                source: field(set.source.as_deref()),
+            },
+            edited: set.edited,
+        });
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context) -> MetadataEditorEvent {
+        let Some(editing) = &mut self.editing else {
+            return MetadataEditorEvent::None;
+        };
+
+        let mut event = MetadataEditorEvent::None;
+        let mut close = false;
+        egui::Window::new(tr("song_select.metadata.window"))
+            .id(egui::Id::new("metadata_editor"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.weak(&editing.label);
+                ui.add_space(4.0);
+                egui::Grid::new("metadata_editor_fields")
+                    .num_columns(2)
+                    .spacing([8.0, 6.0])
+                    .show(ui, |ui| {
+                        let draft = &mut editing.draft;
+                        for (name, value, hint) in [
+                            ("title", &mut draft.title, None),
+                            ("artist", &mut draft.artist, None),
+                            ("source", &mut draft.source, Some("source_hint")),
+                            ("tags", &mut draft.tags, Some("tags_hint")),
+                        ] {
+                            ui.label(tr(&format!("song_select.metadata.{}", name)));
+                            let hint = hint
+                                .map(|hint| tr(&format!("song_select.metadata.{}", hint)))
+                                .unwrap_or_default();
+                            ui.add(
+                                egui::TextEdit::singleline(value)
+                                    .hint_text(hint)
+                                    .desired_width(280.0),
+                            );
+                            ui.end_row();
+                        }
+                    });
+                ui.weak(tr("song_select.metadata.empty_hint"));
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    if ui.button(tr("song_select.metadata.save")).clicked() {
+                        event =
+                            MetadataEditorEvent::Save(editing.beatmapset_id, editing.draft.clone());
+                        close = true;
+                    }
+                    if ui
+                        .add_enabled(
+                            editing.edited,
+                            egui::Button::new(tr("song_select.metadata.restore")),
+                        )
+                        .clicked()
+                    {
+                        event = MetadataEditorEvent::Reset(editing.beatmapset_id);
+                        close = true;
+                    }
+                    if ui.button(tr("song_select.metadata.cancel")).clicked() {
+                        close = true;
+                    }
+                });
+            });
+
+        if close {
+            self.editing = None;
+        }
+        event
+    }
+}
@@ -3,12 +3,18 @@
 #![allow(clippy::too_many_arguments)]
 
 pub(super) mod beatmap_info;
+pub(super) mod chart_preview;
+pub(super) mod courses_panel;
 pub(super) mod difficulty_card;
+pub(super) mod download_panel;
+pub(super) mod goals_panel;
 pub(super) mod leaderboard;
 pub(super) mod leaderboard_card;
+pub(super) mod metadata_editor;
 pub(super) mod search_panel;
 pub(super) mod song_card;
 pub(super) mod song_list;
+pub(super) mod thumbnails;
 
 // Re-export CalculatorOption for use in MenuState
 pub use beatmap_info::CalculatorOption;
@@ -21,10 +27,21 @@ use wgpu::TextureView;
 use winit::dpi::PhysicalSize;
 
 use crate::input::events::GameAction;
+use crate::models::personal_best::personal_bests;
 use crate::models::search::MenuSearchFilters;
+use crate::shared::locale::{tr, tr_args};
 use crate::state::{GameResultData, MenuState};
 use crate::views::components::menu::song_select::beatmap_info::BeatmapInfo;
+use crate::views::components::menu::song_select::chart_preview::ChartPreview;
+use crate::views::components::menu::song_select::courses_panel::{CoursesPanel, CoursesPanelEvent};
+use crate::views::components::menu::song_select::download_panel::{
+    DownloadPanel, DownloadPanelEvent,
+};
+use crate::views::components::menu::song_select::goals_panel::{GoalsPanel, GoalsPanelEvent};
 use crate::views::components::menu::song_select::leaderboard::{Leaderboard, ScoreCard};
+use crate::views::components::menu::song_select::metadata_editor::{
+    MetadataEditor, MetadataEditorEvent,
+};
 use crate::views::components::menu::song_select::search_panel::{SearchPanel, SearchPanelEvent};
 use crate::views::components::menu::song_select::song_list::SongList;
 
@@ -52,10 +69,35 @@ impl Default for UIPanelTextures {
     }
 }
 
+/// Everything the song select screen can emit in one frame:
+/// (UIAction, GameResultData, SearchFilters, CalculatorChanged, PanelActions).
+pub type SongSelectOutput = (
+    Option<GameAction>,
+    Option<GameResultData>,
+    Option<MenuSearchFilters>,
+    Option<String>,
+    Vec<GameAction>,
+);
+
+/// Tabs sharing the lower-left panel of the song select screen.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum LeftPanelTab {
+    Scores,
+    Goals,
+    Courses,
+    Download,
+}
+
 pub struct SongSelectScreen {
     song_list: SongList,
     leaderboard: Leaderboard,
+    goals_panel: GoalsPanel,
+    courses_panel: CoursesPanel,
+    download_panel: DownloadPanel,
+    metadata_editor: MetadataEditor,
+    left_tab: LeftPanelTab,
     beatmap_info: BeatmapInfo,
+    chart_preview: ChartPreview,
     search_panel: SearchPanel,
     current_background_image: Option<CurrentBackground>,
     current_beatmap_hash: Option<String>,
@@ -66,7 +108,13 @@ impl SongSelectScreen {
         Self {
             song_list: SongList::new(),
             leaderboard: Leaderboard::new(),
+            goals_panel: GoalsPanel::new(),
+            courses_panel: CoursesPanel::new(),
+            download_panel: DownloadPanel::new(),
+            metadata_editor: MetadataEditor::new(),
+            left_tab: LeftPanelTab::Scores,
             beatmap_info: BeatmapInfo::new(),
+            chart_preview: ChartPreview::new(),
             search_panel: SearchPanel::new(),
             current_background_image: None,
             current_beatmap_hash: None,
@@ -102,12 +150,13 @@ impl SongSelectScreen {
         replays: Vec<crate::database::models::Replay>,
         note_count_map: std::collections::HashMap<String, i32>,
     ) {
+        let bests = personal_bests(&replays);
         let scores: Vec<ScoreCard> = replays
             .iter()
             .filter_map(|r| {
                 let total_notes =
                     note_count_map.get(&r.beatmap_hash).copied().unwrap_or(0) as usize;
-                ScoreCard::from_replay(r, total_notes)
+                ScoreCard::from_replay(r, total_notes, bests.contains(&r.hash))
             })
             .collect();
         self.leaderboard.update_scores(scores);
@@ -120,7 +169,7 @@ impl SongSelectScreen {
     pub fn on_resize(&mut self, _new_size: &PhysicalSize<u32>) {}
 
     // Signature extended to optionally bubble up GameResultData.
-    // Returns: (UIAction, GameResultData, SearchFilters, CalculatorChanged)
+    // Panel actions are emitted by side panels (goals...) and forwarded as-is.
     pub fn render(
         &mut self,
         ctx: &egui::Context,
@@ -131,25 +180,28 @@ impl SongSelectScreen {
         hit_window: &crate::models::engine::hit_window::HitWindow,
         hit_window_mode: crate::models::settings::HitWindowMode,
         hit_window_value: f64,
+        leaderboard_options: &crate::models::settings::LeaderboardOptions,
+        show_chart_preview: bool,
+        hide_scores: bool,
+        song_list_scroll: &crate::models::settings::SongListScroll,
+        presets: &[crate::models::settings::GameplayPreset],
+        active_preset: Option<&str>,
         btn_tex: Option<TextureId>,
         btn_sel_tex: Option<TextureId>,
         diff_tex: Option<TextureId>,
         diff_sel_tex: Option<TextureId>,
         song_sel_color: Color32,
         diff_sel_color: Color32,
+        rating_colors: &crate::models::skin::menus::song_select::RatingColorsConfig,
         panel_textures: &UIPanelTextures,
-    ) -> (
-        Option<GameAction>,
-        Option<GameResultData>,
-        Option<MenuSearchFilters>,
-        Option<String>, // Calculator changed
-    ) {
+    ) -> SongSelectOutput {
         self.song_list.set_current(menu_state.selected_index);
 
         let mut action_triggered = None;
         let mut result_data_triggered = None;
         let mut search_request = None;
         let mut calculator_changed = None;
+        let mut panel_actions = Vec::new();
 
         egui::CentralPanel::default()
             .frame(egui::Frame::NONE)
@@ -191,6 +243,16 @@ impl SongSelectScreen {
                                 // Get current difficulty from cache (for custom calculators)
                                 let current_ssr = menu_state.get_current_difficulty();
 
+                                // Density graph, once the selected chart is cached.
+                                let density = menu_state
+                                    .get_cached_chart()
+                                    .filter(|cache| {
+                                        beatmap
+                                            .as_ref()
+                                            .is_some_and(|bm| bm.beatmap.hash == cache.beatmap_hash)
+                                    })
+                                    .map(|cache| cache.density.as_slice());
+
                                 if let Some(new_calc) = self.beatmap_info.render(
                                     ui,
                                     bs,
@@ -203,30 +265,131 @@ impl SongSelectScreen {
                                     &menu_state.available_calculators,
                                     &menu_state.active_calculator,
                                     current_ssr,
+                                    density,
                                 ) {
                                     calculator_changed = Some(new_calc);
                                 }
+                                if let Some(creator) = self.beatmap_info.take_creator_click() {
+                                    let mut filters = menu_state.search_filters.clone();
+                                    filters.creator = Some(creator);
+                                    search_request = Some(filters);
+                                }
+                                if let Some(pack) = self.beatmap_info.take_pack_click() {
+                                    let mut filters = menu_state.search_filters.clone();
+                                    filters.pack = Some(pack);
+                                    search_request = Some(filters);
+                                }
                                 ui.add_space(10.0);
                             }
 
-                            // Capture the leaderboard click result if any.
-                            // Passer la chart cachée pour permettre le recalcul des replays.
-                            let cached_chart =
-                                menu_state.get_cached_chart().map(|c| c.chart.as_slice());
-
-                            let clicked_result = self.leaderboard.render(
-                                ui,
-                                diff_name.as_deref(),
-                                hit_window,
-                                cached_chart,
-                            );
+                            ui.horizontal(|ui| {
+                                ui.add_space(10.0);
+                                ui.selectable_value(
+                                    &mut self.left_tab,
+                                    LeftPanelTab::Scores,
+                                    tr("song_select.tab.scores"),
+                                );
+                                ui.selectable_value(
+                                    &mut self.left_tab,
+                                    LeftPanelTab::Goals,
+                                    tr("song_select.tab.goals"),
+                                );
+                                ui.selectable_value(
+                                    &mut self.left_tab,
+                                    LeftPanelTab::Courses,
+                                    tr("song_select.tab.courses"),
+                                );
+                                ui.selectable_value(
+                                    &mut self.left_tab,
+                                    LeftPanelTab::Download,
+                                    tr("song_select.tab.download"),
+                                );
+                            });
 
-                            if let Some(result_data) = clicked_result {
-                                result_data_triggered = Some(result_data);
+                            match self.left_tab {
+                                LeftPanelTab::Scores if hide_scores => {
+                                    ui.add_space(10.0);
+                                    ui.label(tr("song_select.scores_hidden"));
+                                }
+                                LeftPanelTab::Scores => {
+                                    // Capture the leaderboard click result if any.
+                                    // Passer la chart cachée pour permettre le recalcul des replays.
+                                    let cached_chart =
+                                        menu_state.get_cached_chart().map(|c| c.chart.as_slice());
+
+                                    let (clicked_result, options_changed, ghost_action) =
+                                        self.leaderboard.render(
+                                            ui,
+                                            diff_name.as_deref(),
+                                            hit_window,
+                                            cached_chart,
+                                            leaderboard_options,
+                                            rate,
+                                            hit_window_mode,
+                                            hit_window_value,
+                                            menu_state.ghost.as_ref(),
+                                        );
+
+                                    if let Some(result_data) = clicked_result {
+                                        result_data_triggered = Some(result_data);
+                                    }
+                                    panel_actions.extend(ghost_action);
+                                    if let Some(options) = options_changed {
+                                        panel_actions
+                                            .push(GameAction::UpdateLeaderboardOptions(options));
+                                    }
+                                }
+                                LeftPanelTab::Goals => {
+                                    match self.goals_panel.render(ui, menu_state) {
+                                        GoalsPanelEvent::Create(draft) => {
+                                            panel_actions.push(GameAction::CreateGoal(draft));
+                                        }
+                                        GoalsPanelEvent::Delete(goal_id) => {
+                                            panel_actions.push(GameAction::DeleteGoal(goal_id));
+                                        }
+                                        GoalsPanelEvent::None => {}
+                                    }
+                                }
+                                LeftPanelTab::Courses => {
+                                    match self.courses_panel.render(ui, menu_state) {
+                                        CoursesPanelEvent::Create(draft) => {
+                                            panel_actions.push(GameAction::CreateCourse(draft));
+                                        }
+                                        CoursesPanelEvent::Delete(course_id) => {
+                                            panel_actions.push(GameAction::DeleteCourse(course_id));
+                                        }
+                                        CoursesPanelEvent::Start(course_id) => {
+                                            panel_actions.push(GameAction::StartCourse(course_id));
+                                        }
+                                        CoursesPanelEvent::None => {}
+                                    }
+                                }
+                                LeftPanelTab::Download => {
+                                    match self.download_panel.render(ui, menu_state) {
+                                        DownloadPanelEvent::Search(query) => {
+                                            panel_actions.push(GameAction::SearchMirror(query));
+                                        }
+                                        DownloadPanelEvent::Download(set_id) => {
+                                            panel_actions
+                                                .push(GameAction::DownloadBeatmapset(set_id));
+                                        }
+                                        DownloadPanelEvent::None => {}
+                                    }
+                                }
                             }
                         });
 
-                        strip.empty();
+                        strip.cell(|ui| {
+                            // Mini playfield of the selected chart, once it is cached.
+                            let selected_hash = menu_state.get_selected_beatmap_hash();
+                            if show_chart_preview
+                                && let Some(cache) = menu_state.get_cached_chart().filter(|cache| {
+                                    Some(&cache.beatmap_hash) == selected_hash.as_ref()
+                                })
+                            {
+                                self.chart_preview.render(ui, cache, menu_state.rate);
+                            }
+                        });
 
                         strip.strip(|builder| {
                             builder
@@ -247,17 +410,82 @@ impl SongSelectScreen {
                                                 SearchPanelEvent::None => {}
                                             }
 
+                                            if let Some((done, total)) = menu_state.scan_progress {
+                                                ui.horizontal(|ui| {
+                                                    ui.spinner();
+                                                    ui.label(tr_args(
+                                                        "song_select.updating_library",
+                                                        &[("done", &done), ("total", &total)],
+                                                    ));
+                                                });
+                                            }
+
+                                            ui.horizontal(|ui| {
+                                                if ui.button(tr("song_select.random")).clicked() {
+                                                    panel_actions.push(GameAction::RandomMap);
+                                                }
+                                                if ui
+                                                    .button(tr("song_select.recommend"))
+                                                    .on_hover_text(tr("song_select.recommend_hint"))
+                                                    .clicked()
+                                                {
+                                                    panel_actions.push(GameAction::RecommendMap);
+                                                }
+                                                if !presets.is_empty() {
+                                                    egui::ComboBox::from_id_salt("gameplay_preset")
+                                                        .selected_text(
+                                                            active_preset
+                                                                .map(str::to_string)
+                                                                .unwrap_or_else(|| {
+                                                                    tr("song_select.preset")
+                                                                }),
+                                                        )
+                                                        .show_ui(ui, |ui| {
+                                                            for preset in presets {
+                                                                let active = active_preset
+                                                                    == Some(preset.name.as_str());
+                                                                if ui
+                                                                    .selectable_label(
+                                                                        active,
+                                                                        &preset.name,
+                                                                    )
+                                                                    .clicked()
+                                                                    && !active
+                                                                {
+                                                                    panel_actions.push(
+                                                                    GameAction::ApplyGameplayPreset(
+                                                                        preset.name.clone(),
+                                                                    ),
+                                                                );
+                                                                }
+                                                            }
+                                                        })
+                                                        .response
+                                                        .on_hover_text(tr(
+                                                            "song_select.preset_hint",
+                                                        ));
+                                                }
+                                            });
+
                                             ui.add_space(8.0);
                                             action_triggered = self.song_list.render(
                                                 ui,
                                                 menu_state,
+                                                song_list_scroll,
                                                 btn_tex,
                                                 btn_sel_tex,
                                                 diff_tex,
                                                 diff_sel_tex,
                                                 song_sel_color,
                                                 diff_sel_color,
+                                                rating_colors,
                                             );
+                                            if let Some(set) =
+                                                self.song_list.take_metadata_request()
+                                            {
+                                                self.metadata_editor
+                                                    .open(&set, menu_state.unicode_metadata);
+                                            }
                                         });
                                     });
 
@@ -282,11 +510,57 @@ impl SongSelectScreen {
                     })
             });
 
+        match self.metadata_editor.render(ctx) {
+            MetadataEditorEvent::Save(beatmapset_id, metadata) => {
+                panel_actions.push(GameAction::EditMetadata {
+                    beatmapset_id,
+                    metadata: Some(metadata),
+                });
+            }
+            MetadataEditorEvent::Reset(beatmapset_id) => {
+                panel_actions.push(GameAction::EditMetadata {
+                    beatmapset_id,
+                    metadata: None,
+                });
+            }
+            MetadataEditorEvent::None => {}
+        }
+
+        // Partie interrompue par un crash : proposer de la garder
+        if let Some(autosave) = &menu_state.autosave {
+            let chart = menu_state
+                .beatmap_label(&autosave.beatmap_hash)
+                .unwrap_or_else(|| tr("song_select.autosave.missing_chart"));
+            let reached_s = (autosave.reached_ms / 1000.0).max(0.0) as u64;
+            let time = format!("{}:{:02}", reached_s / 60, reached_s % 60);
+            egui::Window::new(tr("song_select.autosave.title"))
+                .id(egui::Id::new("autosave_recovery"))
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label(tr_args(
+                        "song_select.autosave.message",
+                        &[("chart", &chart), ("time", &time)],
+                    ));
+                    ui.label(tr("song_select.autosave.question"));
+                    ui.horizontal(|ui| {
+                        if ui.button(tr("song_select.autosave.save")).clicked() {
+                            panel_actions.push(GameAction::RecoverAutosave);
+                        }
+                        if ui.button(tr("song_select.autosave.discard")).clicked() {
+                            panel_actions.push(GameAction::DiscardAutosave);
+                        }
+                    });
+                });
+        }
+
         (
             action_triggered,
             result_data_triggered,
             search_request,
             calculator_changed,
+            panel_actions,
         )
     }
 
@@ -295,7 +569,19 @@ impl SongSelectScreen {
             egui::Layout::centered_and_justified(Direction::LeftToRight),
             |ui| {
                 let beatmap_count = menu_state.beatmapsets.len();
-                let text = format!("Beatmaps: {}", beatmap_count);
+                let mut text = tr_args("song_select.beatmap_count", &[("count", &beatmap_count)]);
+                if menu_state.autoplay {
+                    text.push_str(&format!("  |  {}", tr("song_select.autoplay")));
+                }
+                if let Some(ghost) = menu_state.ghost.as_ref().filter(|g| {
+                    menu_state.get_selected_beatmap_hash() == Some(g.beatmap_hash.clone())
+                }) {
+                    let accuracy = format!("{:.2}", ghost.accuracy);
+                    text.push_str(&format!(
+                        "  |  {}",
+                        tr_args("song_select.ghost", &[("accuracy", &accuracy)])
+                    ));
+                }
                 ui.add(Label::new(RichText::new(text).heading()).selectable(false));
             },
         );
@@ -308,10 +594,13 @@ impl SongSelectScreen {
         total_notes: usize,
     ) {
         if menu_state.leaderboard_hash.as_deref() == Some(beatmap_hash) {
+            let bests = personal_bests(&menu_state.leaderboard_scores);
             let cards = menu_state
                 .leaderboard_scores
                 .iter()
-                .filter_map(|replay| ScoreCard::from_replay(replay, total_notes))
+                .filter_map(|replay| {
+                    ScoreCard::from_replay(replay, total_notes, bests.contains(&replay.hash))
+                })
                 .collect();
             self.leaderboard.update_scores(cards);
         } else {
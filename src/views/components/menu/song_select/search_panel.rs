@@ -5,15 +5,29 @@ use egui::{
     StrokeKind, TextEdit, TextureId, Ui, Vec2,
 };
 
+use std::time::{Duration, Instant};
+
+use crate::difficulty::PatternTag;
 use crate::models::search::{MenuSearchFilters, RatingMetric, RatingSource};
+use crate::shared::locale::{tr, tr_args};
 use crate::state::MenuState;
 
+/// Delay after the last keystroke before the query is sent to the database.
+const QUERY_DEBOUNCE: Duration = Duration::from_millis(250);
+
 /// Message emitted by the search panel when the user applies filters.
 pub enum SearchPanelEvent {
     None,
     Apply(MenuSearchFilters),
 }
 
+/// What happened to the query text this frame.
+enum QueryEdit {
+    None,
+    Typed,
+    Submitted,
+}
+
 /// UI color configuration for the search panel.
 #[derive(Clone)]
 pub struct SearchPanelColors {
@@ -52,6 +66,12 @@ pub struct SearchPanel {
     source_metric_expanded: bool,
     /// Whether the filters section is expanded
     filters_expanded: bool,
+    /// Time of the last query edit not yet applied.
+    query_edited_at: Option<Instant>,
+    /// Filters last applied from this panel.
+    last_applied: Option<MenuSearchFilters>,
+    /// Menu filters seen on the previous frame, to detect outside changes.
+    seen_filters: MenuSearchFilters,
 }
 
 impl SearchPanel {
@@ -62,6 +82,9 @@ impl SearchPanel {
             colors: SearchPanelColors::default(),
             source_metric_expanded: false,
             filters_expanded: false,
+            query_edited_at: None,
+            last_applied: None,
+            seen_filters: MenuSearchFilters::default(),
         }
     }
 
@@ -84,7 +107,7 @@ impl SearchPanel {
 
         let available_rect = ui.available_rect_before_wrap();
         let margin = Margin::symmetric(0, 4);
-        let panel_height = 200.0;
+        let panel_height = 224.0;
         let panel_rect = Rect::from_min_size(
             available_rect.min + Vec2::new(margin.left as f32, margin.top as f32),
             Vec2::new(
@@ -125,7 +148,7 @@ impl SearchPanel {
                     ui.label(RichText::new("🔍").size(16.0).color(colors.accent));
                     ui.add_space(4.0);
                     ui.label(
-                        RichText::new("Search")
+                        RichText::new(tr("song_select.search.title"))
                             .size(14.0)
                             .strong()
                             .color(colors.text_primary),
@@ -146,10 +169,19 @@ impl SearchPanel {
 
                 ui.add_space(8.0);
 
-                // Search bar
-                should_apply |= self.render_search_bar(ui, &colors, search_bar_texture, has_bg);
+                // Search bar: typing is debounced, Enter applies right away.
+                match self.render_search_bar(ui, &colors, search_bar_texture, has_bg) {
+                    QueryEdit::None => {}
+                    QueryEdit::Typed => self.query_edited_at = Some(Instant::now()),
+                    QueryEdit::Submitted => should_apply = true,
+                }
+
+                ui.add_space(6.0);
 
-                ui.add_space(8.0);
+                // Filter chips
+                should_apply |= self.render_chips(ui, &colors);
+
+                ui.add_space(6.0);
 
                 // Collapsible: Source & Metric
                 should_apply |= self.render_collapsible_source_metric(ui, &colors, has_bg);
@@ -160,12 +192,31 @@ impl SearchPanel {
                 should_apply |= self.render_collapsible_filters(ui, &colors, has_bg);
             });
 
+        if let Some(edited_at) = self.query_edited_at {
+            let elapsed = edited_at.elapsed();
+            if elapsed >= QUERY_DEBOUNCE {
+                should_apply = true;
+            } else {
+                ui.ctx().request_repaint_after(QUERY_DEBOUNCE - elapsed);
+            }
+        }
+
+        // Adopt filters changed elsewhere (e.g. a creator link), but not the
+        // echo of our own apply, which may lag behind what is being typed.
+        if menu_state.search_filters != self.seen_filters {
+            self.seen_filters = menu_state.search_filters.clone();
+            if self.last_applied.as_ref() != Some(&menu_state.search_filters) {
+                self.form_filters = menu_state.search_filters.clone();
+                self.query_edited_at = None;
+                should_apply = false;
+            }
+        }
+
         if should_apply {
+            self.query_edited_at = None;
+            self.last_applied = Some(self.form_filters.clone());
             SearchPanelEvent::Apply(self.form_filters.clone())
         } else {
-            if self.form_filters != menu_state.search_filters {
-                self.form_filters = menu_state.search_filters.clone();
-            }
             SearchPanelEvent::None
         }
     }
@@ -176,7 +227,7 @@ impl SearchPanel {
         colors: &SearchPanelColors,
         search_bar_texture: Option<TextureId>,
         has_bg: bool,
-    ) -> bool {
+    ) -> QueryEdit {
         let available_width = ui.available_width();
         let bar_height = 32.0;
 
@@ -218,7 +269,7 @@ impl SearchPanel {
 
         let text_edit = TextEdit::singleline(&mut self.form_filters.query)
             .hint_text(
-                RichText::new("Artist, title...")
+                RichText::new(tr("song_select.search.hint"))
                     .color(colors.text_muted)
                     .size(11.0),
             )
@@ -226,7 +277,210 @@ impl SearchPanel {
             .frame(false)
             .desired_width(available_width - 40.0);
 
-        child_ui.add(text_edit).changed()
+        let response = child_ui.add(text_edit);
+        if response.lost_focus() && child_ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            QueryEdit::Submitted
+        } else if response.changed() {
+            QueryEdit::Typed
+        } else {
+            QueryEdit::None
+        }
+    }
+
+    fn render_chips(&mut self, ui: &mut Ui, colors: &SearchPanelColors) -> bool {
+        let mut changed = false;
+
+        ui.horizontal(|ui| {
+            let active = self.form_filters.show_archived;
+            let chip = Frame::default()
+                .corner_radius(CornerRadius::same(10))
+                .inner_margin(Margin::symmetric(8, 2))
+                .fill(if active {
+                    colors.accent
+                } else {
+                    colors.panel_secondary
+                })
+                .stroke(Stroke::new(1.0, colors.panel_border))
+                .show(ui, |ui| {
+                    ui.label(
+                        RichText::new(tr("song_select.search.archived"))
+                            .size(10.0)
+                            .color(if active {
+                                colors.panel_bg
+                            } else {
+                                colors.text_secondary
+                            }),
+                    );
+                })
+                .response;
+
+            if chip
+                .interact(egui::Sense::click())
+                .on_hover_text(tr("song_select.search.archived_hint"))
+                .clicked()
+            {
+                self.form_filters.show_archived = !active;
+                changed = true;
+            }
+
+            let active = self.form_filters.group_by_pack;
+            let chip = Frame::default()
+                .corner_radius(CornerRadius::same(10))
+                .inner_margin(Margin::symmetric(8, 2))
+                .fill(if active {
+                    colors.accent
+                } else {
+                    colors.panel_secondary
+                })
+                .stroke(Stroke::new(1.0, colors.panel_border))
+                .show(ui, |ui| {
+                    ui.label(
+                        RichText::new(tr("song_select.search.by_pack"))
+                            .size(10.0)
+                            .color(if active {
+                                colors.panel_bg
+                            } else {
+                                colors.text_secondary
+                            }),
+                    );
+                })
+                .response;
+
+            if chip
+                .interact(egui::Sense::click())
+                .on_hover_text(tr("song_select.search.by_pack_hint"))
+                .clicked()
+            {
+                self.form_filters.group_by_pack = !active;
+                changed = true;
+            }
+
+            let active = self.form_filters.hide_broken;
+            let chip = Frame::default()
+                .corner_radius(CornerRadius::same(10))
+                .inner_margin(Margin::symmetric(8, 2))
+                .fill(if active {
+                    colors.accent
+                } else {
+                    colors.panel_secondary
+                })
+                .stroke(Stroke::new(1.0, colors.panel_border))
+                .show(ui, |ui| {
+                    ui.label(
+                        RichText::new(tr("song_select.search.hide_broken"))
+                            .size(10.0)
+                            .color(if active {
+                                colors.panel_bg
+                            } else {
+                                colors.text_secondary
+                            }),
+                    );
+                })
+                .response;
+
+            if chip
+                .interact(egui::Sense::click())
+                .on_hover_text(tr("song_select.search.hide_broken_hint"))
+                .clicked()
+            {
+                self.form_filters.hide_broken = !active;
+                changed = true;
+            }
+
+            // Mapper filter set from the beatmap info panel; click to go back.
+            if let Some(creator) = self.form_filters.creator.clone() {
+                let chip = Frame::default()
+                    .corner_radius(CornerRadius::same(10))
+                    .inner_margin(Margin::symmetric(8, 2))
+                    .fill(colors.accent)
+                    .stroke(Stroke::new(1.0, colors.panel_border))
+                    .show(ui, |ui| {
+                        ui.label(
+                            RichText::new(tr_args(
+                                "song_select.search.mapper",
+                                &[("mapper", &creator)],
+                            ))
+                            .size(10.0)
+                            .color(colors.panel_bg),
+                        );
+                    })
+                    .response;
+
+                if chip
+                    .interact(egui::Sense::click())
+                    .on_hover_text(tr("song_select.search.clear_mapper"))
+                    .clicked()
+                {
+                    self.form_filters.creator = None;
+                    changed = true;
+                }
+            }
+
+            // Pack filter set from the beatmap info panel.
+            if let Some(pack) = self.form_filters.pack.clone() {
+                let chip = Frame::default()
+                    .corner_radius(CornerRadius::same(10))
+                    .inner_margin(Margin::symmetric(8, 2))
+                    .fill(colors.accent)
+                    .stroke(Stroke::new(1.0, colors.panel_border))
+                    .show(ui, |ui| {
+                        ui.label(
+                            RichText::new(tr_args("song_select.search.pack", &[("pack", &pack)]))
+                                .size(10.0)
+                                .color(colors.panel_bg),
+                        );
+                    })
+                    .response;
+
+                if chip
+                    .interact(egui::Sense::click())
+                    .on_hover_text(tr("song_select.search.clear_pack"))
+                    .clicked()
+                {
+                    self.form_filters.pack = None;
+                    changed = true;
+                }
+            }
+        });
+
+        // Pattern tags: a chart must have every selected one.
+        ui.horizontal_wrapped(|ui| {
+            for tag in PatternTag::ALL {
+                let active = self.form_filters.pattern_tags.contains(&tag);
+                let chip = Frame::default()
+                    .corner_radius(CornerRadius::same(10))
+                    .inner_margin(Margin::symmetric(8, 2))
+                    .fill(if active {
+                        colors.accent
+                    } else {
+                        colors.panel_secondary
+                    })
+                    .stroke(Stroke::new(1.0, colors.panel_border))
+                    .show(ui, |ui| {
+                        ui.label(RichText::new(tag.label()).size(10.0).color(if active {
+                            colors.panel_bg
+                        } else {
+                            colors.text_secondary
+                        }));
+                    })
+                    .response;
+
+                if chip
+                    .interact(egui::Sense::click())
+                    .on_hover_text(tr("song_select.search.pattern_hint"))
+                    .clicked()
+                {
+                    if active {
+                        self.form_filters.pattern_tags.retain(|t| *t != tag);
+                    } else {
+                        self.form_filters.pattern_tags.push(tag);
+                    }
+                    changed = true;
+                }
+            }
+        });
+
+        changed
     }
 
     fn render_collapsible_source_metric(
@@ -259,7 +513,7 @@ impl SearchPanel {
                     ui.label(RichText::new(arrow).size(9.0).color(colors.accent));
                     ui.add_space(3.0);
                     ui.label(
-                        RichText::new("Source & Metric")
+                        RichText::new(tr("song_select.search.source_metric"))
                             .size(11.0)
                             .color(colors.text_secondary),
                     );
@@ -321,7 +575,11 @@ impl SearchPanel {
                 .stroke(Stroke::new(1.0, colors.panel_border))
                 .show(ui, |ui| {
                     ui.horizontal(|ui| {
-                        ui.label(RichText::new("Source:").size(10.0).color(colors.text_muted));
+                        ui.label(
+                            RichText::new(tr("song_select.search.source"))
+                                .size(10.0)
+                                .color(colors.text_muted),
+                        );
 
                         ComboBox::from_id_salt("source_combo")
                             .selected_text(
@@ -358,7 +616,11 @@ impl SearchPanel {
                 .stroke(Stroke::new(1.0, colors.panel_border))
                 .show(ui, |ui| {
                     ui.horizontal(|ui| {
-                        ui.label(RichText::new("Metric:").size(10.0).color(colors.text_muted));
+                        ui.label(
+                            RichText::new(tr("song_select.search.metric"))
+                                .size(10.0)
+                                .color(colors.text_muted),
+                        );
 
                         ComboBox::from_id_salt("metric_combo")
                             .selected_text(
@@ -430,7 +692,7 @@ impl SearchPanel {
                     ui.label(RichText::new(arrow).size(9.0).color(colors.accent));
                     ui.add_space(3.0);
                     ui.label(
-                        RichText::new("Filters")
+                        RichText::new(tr("song_select.search.filters"))
                             .size(11.0)
                             .color(colors.text_secondary),
                     );
@@ -464,12 +726,16 @@ impl SearchPanel {
             ui.add_space(4.0);
 
             // Rating section
-            ui.label(RichText::new("Rating").size(10.0).color(colors.text_muted));
+            ui.label(
+                RichText::new(tr("song_select.search.rating"))
+                    .size(10.0)
+                    .color(colors.text_muted),
+            );
             ui.add_space(2.0);
 
             changed |= Self::toggle_slider_static(
                 ui,
-                "Min",
+                &tr("song_select.search.min"),
                 &mut self.form_filters.min_rating,
                 15.0,
                 0.0..=50.0,
@@ -479,7 +745,7 @@ impl SearchPanel {
             );
             changed |= Self::toggle_slider_static(
                 ui,
-                "Max",
+                &tr("song_select.search.max"),
                 &mut self.form_filters.max_rating,
                 30.0,
                 0.0..=50.0,
@@ -492,7 +758,7 @@ impl SearchPanel {
 
             // Duration section
             ui.label(
-                RichText::new("Duration")
+                RichText::new(tr("song_select.search.duration"))
                     .size(10.0)
                     .color(colors.text_muted),
             );
@@ -500,7 +766,7 @@ impl SearchPanel {
 
             changed |= Self::toggle_slider_static(
                 ui,
-                "Min",
+                &tr("song_select.search.min"),
                 &mut self.form_filters.min_duration_seconds,
                 60.0,
                 0.0..=600.0,
@@ -510,7 +776,7 @@ impl SearchPanel {
             );
             changed |= Self::toggle_slider_static(
                 ui,
-                "Max",
+                &tr("song_select.search.max"),
                 &mut self.form_filters.max_duration_seconds,
                 240.0,
                 0.0..=600.0,
@@ -1,20 +1,39 @@
 //! Renders a single beatmapset card inside the song list.
 
+use std::ops::Range;
+
+use egui::text::{LayoutJob, TextFormat};
 use egui::{
-    Color32, Label, Margin, Pos2, Rect, RichText, Sense, Stroke, StrokeKind, TextureId, UiBuilder,
-    Vec2,
+    Align2, Color32, FontId, Label, Margin, Pos2, Rect, Sense, Stroke, StrokeKind, TextureId,
+    UiBuilder, Vec2,
 };
 
-use crate::database::models::{BeatmapWithRatings, Beatmapset};
+use crate::database::models::Beatmapset;
+use crate::difficulty::PatternTag;
+use crate::models::skin::menus::song_select::RatingColorsConfig;
+
+/// Gap between the card border and its thumbnail.
+const THUMBNAIL_INSET: f32 = 6.0;
+/// Height of a pattern tag chip.
+const TAG_HEIGHT: f32 = 16.0;
 
 pub struct SongCard;
 
 impl SongCard {
     /// Renders a beatmapset row; returns the egui response for interaction.
+    /// Occurrences of `highlight` (the search query) in the title and artist
+    /// are underlaid with the selection color. Sets from a pack show its name
+    /// in the top-right corner. Sets with a background get its `thumbnail`
+    /// on the left, or an empty frame while it loads. The pattern `tags` of
+    /// its charts are drawn as chips in the bottom-right corner.
     pub fn render(
         ui: &mut egui::Ui,
         beatmapset: &Beatmapset,
-        _beatmaps: &[BeatmapWithRatings],
+        unicode_metadata: bool,
+        thumbnail: Option<TextureId>,
+        tags: &[PatternTag],
+        rating_colors: &RatingColorsConfig,
+        highlight: &str,
         is_selected: bool,
         texture_normal: Option<TextureId>,
         texture_selected: Option<TextureId>,
@@ -27,6 +46,14 @@ impl SongCard {
 
         let (rect, response) = ui.allocate_exact_size(size, Sense::click());
 
+        let thumbnail_rect = beatmapset.image_path.is_some().then(|| {
+            let height = card_height - 2.0 * THUMBNAIL_INSET;
+            Rect::from_min_size(
+                rect.min + Vec2::splat(THUMBNAIL_INSET),
+                Vec2::new(height * 16.0 / 9.0, height),
+            )
+        });
+
         if ui.is_rect_visible(rect) {
             let painter = ui.painter();
 
@@ -84,6 +111,54 @@ impl SongCard {
                     StrokeKind::Inside,
                 );
             }
+
+            if let Some(thumbnail_rect) = thumbnail_rect {
+                if let Some(tex_id) = thumbnail {
+                    painter.image(
+                        tex_id,
+                        thumbnail_rect,
+                        Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+                        Color32::WHITE,
+                    );
+                } else {
+                    painter.rect_filled(thumbnail_rect, 2.0, Color32::from_gray(30));
+                }
+            }
+
+            if let Some(pack) = &beatmapset.pack {
+                painter.text(
+                    rect.right_top() + Vec2::new(-10.0, 8.0),
+                    Align2::RIGHT_TOP,
+                    pack,
+                    FontId::proportional(12.0),
+                    Color32::GRAY,
+                );
+            }
+
+            let mut right = rect.right() - 10.0;
+            let bottom = rect.bottom() - 8.0;
+            for &tag in tags.iter().rev() {
+                let galley = painter.layout_no_wrap(
+                    tag.label().to_string(),
+                    FontId::proportional(11.0),
+                    Color32::BLACK,
+                );
+                let width = galley.size().x + 10.0;
+                let chip = Rect::from_min_max(
+                    Pos2::new(right - width, bottom - TAG_HEIGHT),
+                    Pos2::new(right, bottom),
+                );
+                let [r, g, b, a] = rating_colors.tag_color(tag);
+                let color = Color32::from_rgba_unmultiplied(
+                    (r * 255.0) as u8,
+                    (g * 255.0) as u8,
+                    (b * 255.0) as u8,
+                    (a * 255.0) as u8,
+                );
+                painter.rect_filled(chip, TAG_HEIGHT / 2.0, color);
+                painter.galley(chip.center() - galley.size() / 2.0, galley, Color32::BLACK);
+                right = chip.left() - 4.0;
+            }
         }
 
         // Narrower margins so the card spans the full row.
@@ -94,38 +169,50 @@ impl SongCard {
             bottom: 0,
         };
 
+        // The text starts after the thumbnail.
+        let left = thumbnail_rect.map_or(rect.min.x, |r| r.max.x) + card_margin.left as f32;
         let content_rect = Rect::from_min_max(
-            rect.min + Vec2::new(card_margin.left as f32, card_margin.top as f32),
+            Pos2::new(left, rect.min.y + card_margin.top as f32),
             rect.max - Vec2::new(card_margin.right as f32, card_margin.bottom as f32),
         );
 
         let mut content_ui =
             ui.new_child(UiBuilder::new().max_rect(content_rect).layout(*ui.layout()));
 
+        let highlight = highlight.trim();
+        let highlight_bg = Color32::from_rgba_unmultiplied(
+            selected_color.r(),
+            selected_color.g(),
+            selected_color.b(),
+            110,
+        );
+
         content_ui.vertical(|ui| {
-            if let Some(title) = &beatmapset.title {
+            if let Some(title) = beatmapset.display_title(unicode_metadata) {
                 ui.add(
-                    Label::new(
-                        RichText::new(title)
-                            .size(24.0)
-                            .strong()
-                            .color(Color32::WHITE),
-                    )
+                    Label::new(highlighted(
+                        title,
+                        highlight,
+                        24.0,
+                        Color32::WHITE,
+                        highlight_bg,
+                    ))
                     .selectable(false),
                 );
             }
 
-            let artist_creator = if let Some(artist) = &beatmapset.artist {
-                artist.to_string()
-            } else {
-                String::new()
-            };
+            let artist_creator = beatmapset
+                .display_artist(unicode_metadata)
+                .unwrap_or_default()
+                .to_string();
             ui.add(
-                Label::new(
-                    RichText::new(&artist_creator)
-                        .size(16.0)
-                        .color(Color32::LIGHT_GRAY),
-                )
+                Label::new(highlighted(
+                    &artist_creator,
+                    highlight,
+                    16.0,
+                    Color32::LIGHT_GRAY,
+                    highlight_bg,
+                ))
                 .selectable(false),
             );
         });
@@ -133,3 +220,41 @@ impl SongCard {
         response
     }
 }
+
+/// Byte ranges of `needle` in `text`, ignoring ASCII case like SQLite's `LOWER`.
+fn match_ranges(text: &str, needle: &str) -> Vec<Range<usize>> {
+    let (haystack, needle) = (text.as_bytes(), needle.as_bytes());
+    let mut ranges = Vec::new();
+    if needle.is_empty() {
+        return ranges;
+    }
+    let mut start = 0;
+    while start + needle.len() <= haystack.len() {
+        if haystack[start..start + needle.len()].eq_ignore_ascii_case(needle) {
+            ranges.push(start..start + needle.len());
+            start += needle.len();
+        } else {
+            start += 1;
+        }
+    }
+    ranges
+}
+
+/// Lays out `text` with the matches of `needle` on a colored background.
+fn highlighted(text: &str, needle: &str, size: f32, color: Color32, bg: Color32) -> LayoutJob {
+    let plain = TextFormat::simple(FontId::proportional(size), color);
+    let marked = TextFormat {
+        background: bg,
+        ..plain.clone()
+    };
+
+    let mut job = LayoutJob::default();
+    let mut cursor = 0;
+    for range in match_ranges(text, needle) {
+        job.append(&text[cursor..range.start], 0.0, plain.clone());
+        job.append(&text[range.clone()], 0.0, marked.clone());
+        cursor = range.end;
+    }
+    job.append(&text[cursor..], 0.0, plain);
+    job
+}
@@ -1,20 +1,59 @@
+use crate::database::{BeatmapWithRatings, Beatmapset};
+use crate::difficulty::PatternTag;
 use crate::input::events::GameAction;
+use crate::models::settings::SongListScroll;
+use crate::models::skin::menus::song_select::RatingColorsConfig;
+use crate::shared::locale::tr;
 use crate::state::MenuState;
 use crate::views::components::menu::song_select::difficulty_card::DifficultyCard;
 use crate::views::components::menu::song_select::song_card::SongCard;
-use egui::{Align, Color32, ScrollArea, TextureId, scroll_area::ScrollBarVisibility};
+use crate::views::components::menu::song_select::thumbnails::Thumbnails;
+use egui::scroll_area::{ScrollBarVisibility, ScrollSource};
+use egui::{Align2, Color32, FontId, Rect, ScrollArea, Stroke, StrokeKind, TextureId, Vec2};
+use std::time::{Duration, Instant};
 
 // Hauteur Carte (80) + Marge (8)
 const ROW_HEIGHT: f32 = 88.0;
 // Hauteur Diff (30) + Marge interne (4) + Espace (4)
 const DIFFICULTY_HEIGHT: f32 = 38.0;
+// Durée du clignotement d'une map tirée au hasard / recommandée
+const HIGHLIGHT_DURATION: Duration = Duration::from_millis(1500);
+// Lignes rendues en plus au-dessus et en dessous de la zone visible
+const OVERSCAN_ROWS: usize = 1;
+// Élan donné par la molette : vitesse (px/s) par pixel de défilement
+const WHEEL_IMPULSE: f32 = 12.0;
+// Freinage de l'élan (1/s) ; la distance parcourue vaut impulsion / freinage
+const WHEEL_FRICTION: f32 = 6.0;
+// En dessous de cette vitesse (px/s), la liste s'arrête
+const MIN_VELOCITY: f32 = 5.0;
+// Durée d'affichage de l'indicateur de position après un mouvement
+const INDICATOR_DURATION: Duration = Duration::from_millis(1200);
+
+/// Saut animé vers une position de la liste.
+struct Jump {
+    from: f32,
+    to: f32,
+    started: Instant,
+}
 
 pub struct SongList {
     current: usize,
     need_scroll_to: Option<usize>,
-    need_scroll_center: Option<usize>,
     min: usize,
     max: usize,
+    last_highlight: Option<Instant>,
+    thumbnails: Thumbnails,
+    /// Décalage vertical affiché, piloté à la main (la ScrollArea ne défile plus seule).
+    offset: f32,
+    /// Vitesse de l'élan de la molette (px/s).
+    velocity: f32,
+    jump: Option<Jump>,
+    /// Sélection pour laquelle la liste a été recentrée.
+    followed: Option<usize>,
+    viewport_height: f32,
+    last_motion: Option<Instant>,
+    /// Set dont l'édition des métadonnées a été demandée (clic droit).
+    metadata_request: Option<Beatmapset>,
 }
 
 impl SongList {
@@ -22,9 +61,17 @@ impl SongList {
         Self {
             current: 0,
             need_scroll_to: None,
-            need_scroll_center: None,
             min: 0,
             max: 0,
+            last_highlight: None,
+            thumbnails: Thumbnails::new(),
+            offset: 0.0,
+            velocity: 0.0,
+            jump: None,
+            followed: None,
+            viewport_height: 0.0,
+            last_motion: None,
+            metadata_request: None,
         }
     }
 
@@ -36,16 +83,23 @@ impl SongList {
         self.current = current;
     }
 
+    /// Set à ouvrir dans l'éditeur de métadonnées, une seule fois.
+    pub fn take_metadata_request(&mut self) -> Option<Beatmapset> {
+        self.metadata_request.take()
+    }
+
     pub fn render(
         &mut self,
         ui: &mut egui::Ui,
         menu_state: &MenuState, // Immutable !
+        scroll: &SongListScroll,
         btn_tex: Option<TextureId>,
         btn_sel_tex: Option<TextureId>,
         diff_tex: Option<TextureId>,
         diff_sel_tex: Option<TextureId>,
         song_sel_color: Color32,
         diff_sel_color: Color32,
+        rating_colors: &RatingColorsConfig,
     ) -> Option<GameAction> {
         let beatmapsets = &menu_state.beatmapsets;
         let current_from_state = menu_state.selected_index;
@@ -53,52 +107,88 @@ impl SongList {
         let mut action_triggered = None;
 
         self.current = current_from_state;
+        self.thumbnails.poll(ui.ctx());
 
+        // Position du haut de chaque ligne, calculée une seule fois par frame
+        let mut cumulative_heights = Vec::with_capacity(beatmapsets.len());
         let mut total_height = 0.0;
         for (i, (_, beatmaps)) in beatmapsets.iter().enumerate() {
+            cumulative_heights.push(total_height);
             total_height += ROW_HEIGHT;
             if i == current_from_state && beatmaps.len() > 1 {
                 total_height += DIFFICULTY_HEIGHT * (beatmaps.len() - 1) as f32;
             }
         }
+        let centered_on = |idx: usize, viewport_height: f32| {
+            cumulative_heights.get(idx).copied().unwrap_or(0.0) + ROW_HEIGHT / 2.0
+                - viewport_height / 2.0
+        };
 
-        ScrollArea::vertical()
-            .scroll_bar_visibility(ScrollBarVisibility::AlwaysHidden)
-            .show_viewport(ui, |ui, rect| {
-                ui.set_height(total_height);
-
-                let mut cumulative_heights = Vec::new();
-                let mut current_height = 0.0;
-                for (i, (_, beatmaps)) in beatmapsets.iter().enumerate() {
-                    cumulative_heights.push(current_height);
-                    current_height += ROW_HEIGHT;
-                    if i == current_from_state && beatmaps.len() > 1 {
-                        current_height += DIFFICULTY_HEIGHT * (beatmaps.len() - 1) as f32;
-                    }
+        // Nouvelle sélection (clavier, clic, recherche) : on la recentre
+        if self.viewport_height > 0.0 {
+            if let Some(need_scroll_to) = self.need_scroll_to.take()
+                && need_scroll_to < beatmapsets.len()
+            {
+                self.current = need_scroll_to;
+                self.followed = Some(need_scroll_to);
+                self.jump_to(centered_on(need_scroll_to, self.viewport_height), scroll);
+            }
+            if self.followed != Some(current_from_state) {
+                let target = centered_on(current_from_state, self.viewport_height);
+                // À l'ouverture, on arrive directement sur la sélection
+                if self.followed.is_none() {
+                    self.offset = target;
+                } else {
+                    self.jump_to(target, scroll);
                 }
+                self.followed = Some(current_from_state);
+            }
+            // Nouvelle map tirée : on la centre dans la liste
+            if let Some((idx, started)) = menu_state.highlight
+                && self.last_highlight != Some(started)
+                && idx < beatmapsets.len()
+            {
+                self.last_highlight = Some(started);
+                self.jump_to(centered_on(idx, self.viewport_height), scroll);
+            }
+        }
 
-                if let Some(need_scroll_to) = self.need_scroll_to.take()
-                    && need_scroll_to < beatmapsets.len()
-                {
-                    let current_y = cumulative_heights.get(self.current).copied().unwrap_or(0.0);
-                    let target_y = cumulative_heights
-                        .get(need_scroll_to)
-                        .copied()
-                        .unwrap_or(0.0);
-                    let scroll_y = target_y - current_y;
-                    self.current = need_scroll_to;
-                    ui.scroll_with_delta(egui::Vec2::new(0.0, -scroll_y));
-                    self.need_scroll_center = Some(need_scroll_to);
+        // La molette donne de l'élan au lieu de sauter d'un cran
+        let list_rect = ui.available_rect_before_wrap();
+        if ui.rect_contains_pointer(list_rect) {
+            let wheel = ui.input(|i| i.raw_scroll_delta.y);
+            if wheel != 0.0 {
+                self.jump = None;
+                self.last_motion = Some(Instant::now());
+                if scroll.smooth {
+                    self.velocity -= wheel * WHEEL_IMPULSE;
+                } else {
+                    self.offset -= wheel;
                 }
+            }
+        }
+
+        let max_offset = (total_height - self.viewport_height).max(0.0);
+        let moving = self.advance(ui.input(|i| i.stable_dt).min(0.1), scroll, max_offset);
+        if moving {
+            ui.ctx().request_repaint();
+        }
 
+        let output = ScrollArea::vertical()
+            .scroll_bar_visibility(ScrollBarVisibility::AlwaysHidden)
+            .scroll_source(ScrollSource::NONE)
+            .vertical_scroll_offset(self.offset)
+            .show_viewport(ui, |ui, rect| {
+                ui.set_height(total_height);
+
+                // Première ligne dont le bas dépasse le haut de la zone visible,
+                // dernière ligne dont le haut est avant son bas
                 let min_row = cumulative_heights
-                    .iter()
-                    .position(|&h| h >= rect.min.y)
-                    .unwrap_or(0);
-                let max_row = cumulative_heights
-                    .iter()
-                    .position(|&h| h > rect.max.y)
-                    .unwrap_or(beatmapsets.len());
+                    .partition_point(|&h| h <= rect.min.y)
+                    .saturating_sub(1 + OVERSCAN_ROWS);
+                let max_row = (cumulative_heights.partition_point(|&h| h < rect.max.y)
+                    + OVERSCAN_ROWS)
+                    .min(beatmapsets.len());
 
                 let fill_top = cumulative_heights.get(min_row).copied().unwrap_or(0.0);
                 egui::Frame::NONE.show(ui, |ui| {
@@ -112,30 +202,57 @@ impl SongList {
                     if let Some((beatmapset, beatmaps)) = beatmapsets.get(i) {
                         let id = i;
                         let is_selected = self.current == id;
+                        // Seules les cartes visibles demandent leur miniature
+                        let thumbnail = beatmapset
+                            .image_path
+                            .as_deref()
+                            .and_then(|path| self.thumbnails.get(path));
 
                         let response = SongCard::render(
                             ui,
                             beatmapset,
-                            beatmaps,
+                            menu_state.unicode_metadata,
+                            thumbnail,
+                            &set_tags(beatmaps),
+                            rating_colors,
+                            &menu_state.search_filters.query,
                             is_selected,
                             btn_tex,
                             btn_sel_tex,
                             song_sel_color,
                         );
 
-                        let sense = response.interact(egui::Sense::click());
-
-                        if let Some(need_scroll_center) = self.need_scroll_center
-                            && id == need_scroll_center
+                        if let Some((idx, started)) = menu_state.highlight
+                            && idx == id
                         {
-                            response.scroll_to_me(Some(Align::Center));
-                            let _ = self.need_scroll_center.take();
+                            Self::paint_highlight(ui, response.rect, started, song_sel_color);
                         }
 
+                        let sense = response.interact(egui::Sense::click());
+
+                        // Right-click: archive / restore the set, edit its metadata.
+                        sense.context_menu(|ui| {
+                            if ui.button(tr("song_select.metadata.edit")).clicked() {
+                                self.metadata_request = Some(beatmapset.clone());
+                                ui.close();
+                            }
+                            let label = if beatmapset.archived {
+                                tr("song_select.unarchive")
+                            } else {
+                                tr("song_select.archive")
+                            };
+                            if ui.button(label).clicked() {
+                                action_triggered = Some(GameAction::SetArchived {
+                                    beatmapset_id: beatmapset.id,
+                                    archived: !beatmapset.archived,
+                                });
+                                ui.close();
+                            }
+                        });
+
                         if sense.clicked() || sense.double_clicked() {
                             // On émet l'action au lieu de changer l'état
                             action_triggered = Some(GameAction::SetSelection(id));
-                            response.scroll_to_me(Some(Align::Center));
 
                             // IMPORTANT : On rend le focus pour que les touches (E, Espace) remarchent tout de suite
                             ui.ctx().memory_mut(|m| m.surrender_focus(response.id));
@@ -172,6 +289,139 @@ impl SongList {
                 self.max = max_row;
             });
 
+        self.viewport_height = output.inner_rect.height();
+        self.paint_position(
+            ui,
+            output.inner_rect,
+            total_height,
+            beatmapsets.len(),
+            song_sel_color,
+        );
+
         action_triggered
     }
+
+    /// Lance un saut animé vers `target` (immédiat sans défilement fluide).
+    fn jump_to(&mut self, target: f32, scroll: &SongListScroll) {
+        self.velocity = 0.0;
+        self.last_motion = Some(Instant::now());
+        if scroll.smooth {
+            self.jump = Some(Jump {
+                from: self.offset,
+                to: target,
+                started: Instant::now(),
+            });
+        } else {
+            self.jump = None;
+            self.offset = target;
+        }
+    }
+
+    /// Avance le saut ou l'élan en cours ; renvoie si la liste bouge encore.
+    fn advance(&mut self, dt: f32, scroll: &SongListScroll, max_offset: f32) -> bool {
+        let mut moving = false;
+        if let Some(jump) = &self.jump {
+            let duration = scroll.duration_ms.max(1.0) / 1000.0;
+            let t = jump.started.elapsed().as_secs_f32() / duration;
+            self.offset = jump.from + (jump.to - jump.from) * scroll.easing.apply(t);
+            if t >= 1.0 {
+                self.jump = None;
+            } else {
+                moving = true;
+            }
+        } else if self.velocity != 0.0 {
+            self.offset += self.velocity * dt;
+            self.velocity *= (-WHEEL_FRICTION * dt).exp();
+            if self.velocity.abs() < MIN_VELOCITY {
+                self.velocity = 0.0;
+            } else {
+                moving = true;
+            }
+        }
+
+        let clamped = self.offset.clamp(0.0, max_offset);
+        if clamped != self.offset {
+            // Butée : l'élan s'arrête net
+            self.offset = clamped;
+            self.velocity = 0.0;
+        }
+        moving
+    }
+
+    /// Indicateur de position à droite de la liste (la barre de défilement est cachée),
+    /// avec le numéro de la map sélectionnée tant que la liste bouge.
+    fn paint_position(
+        &self,
+        ui: &egui::Ui,
+        rect: Rect,
+        total_height: f32,
+        count: usize,
+        color: Color32,
+    ) {
+        if total_height <= rect.height() || count == 0 {
+            return;
+        }
+        let track = Rect::from_min_max(
+            egui::pos2(rect.right() - 3.0, rect.top()),
+            rect.right_bottom(),
+        );
+        let thumb_height = (rect.height() / total_height * track.height()).max(16.0);
+        let progress = self.offset / (total_height - rect.height());
+        let thumb_top = track.top() + progress.clamp(0.0, 1.0) * (track.height() - thumb_height);
+        let thumb = Rect::from_min_size(
+            egui::pos2(track.left(), thumb_top),
+            Vec2::new(track.width(), thumb_height),
+        );
+
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(track, 1.5, Color32::from_white_alpha(20));
+        painter.rect_filled(thumb, 1.5, color.gamma_multiply(0.8));
+
+        let Some(last_motion) = self.last_motion else {
+            return;
+        };
+        let elapsed = last_motion.elapsed();
+        if elapsed >= INDICATOR_DURATION {
+            return;
+        }
+        let alpha = 1.0 - elapsed.as_secs_f32() / INDICATOR_DURATION.as_secs_f32();
+        painter.text(
+            egui::pos2(track.left() - 6.0, thumb.center().y),
+            Align2::RIGHT_CENTER,
+            format!("{} / {}", self.current + 1, count),
+            FontId::proportional(14.0),
+            Color32::WHITE.gamma_multiply(alpha),
+        );
+        ui.ctx().request_repaint();
+    }
+
+    /// Pulsing outline fading out over `HIGHLIGHT_DURATION`.
+    fn paint_highlight(ui: &egui::Ui, rect: egui::Rect, started: Instant, color: Color32) {
+        let elapsed = started.elapsed();
+        if elapsed >= HIGHLIGHT_DURATION {
+            return;
+        }
+        let t = elapsed.as_secs_f32();
+        let fade = 1.0 - t / HIGHLIGHT_DURATION.as_secs_f32();
+        let pulse = 0.6 + 0.4 * (t * std::f32::consts::TAU * 2.0).cos();
+        let alpha = fade * pulse;
+        ui.painter()
+            .rect_filled(rect, 6.0, color.gamma_multiply(alpha * 0.25));
+        ui.painter().rect_stroke(
+            rect,
+            6.0,
+            Stroke::new(3.0, color.gamma_multiply(alpha)),
+            StrokeKind::Inside,
+        );
+        ui.ctx().request_repaint();
+    }
+}
+
+/// Tags d'un set : ceux de ses difficultés, sans doublon, dans l'ordre des skillsets
+fn set_tags(beatmaps: &[BeatmapWithRatings]) -> Vec<PatternTag> {
+    let tags: Vec<PatternTag> = beatmaps.iter().flat_map(|bm| bm.beatmap.tags()).collect();
+    PatternTag::ALL
+        .into_iter()
+        .filter(|tag| tags.contains(tag))
+        .collect()
 }
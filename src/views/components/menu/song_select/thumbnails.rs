@@ -0,0 +1,111 @@
+//! Thumbnails of the song cards, loaded as the list scrolls.
+//!
+//! Only the visible cards ask for their thumbnail. A worker thread reads it
+//! from the thumbnail cache (building it if the set was scanned before the
+//! cache existed) and the render thread uploads it. Textures are kept in a
+//! small LRU, so memory stays bounded however long the library is.
+
+use crate::database::thumbnail_cache::ensure_thumbnail;
+use crossbeam_channel::{Receiver, Sender, unbounded};
+use egui::{ColorImage, TextureHandle, TextureId};
+use std::collections::HashSet;
+use std::thread;
+
+/// Thumbnails kept uploaded, a few screens worth of cards.
+const CACHE_SIZE: usize = 64;
+
+pub struct Thumbnails {
+    requests: Sender<String>,
+    results: Receiver<(String, Option<ColorImage>)>,
+    /// Least recently used first.
+    cache: Vec<(String, TextureHandle)>,
+    in_flight: HashSet<String>,
+    failed: HashSet<String>,
+}
+
+impl Thumbnails {
+    pub fn new() -> Self {
+        let (requests, jobs) = unbounded::<String>();
+        let (done, results) = unbounded();
+        let spawned = thread::Builder::new()
+            .name("thumbnail-loader".to_string())
+            .spawn(move || {
+                for path in jobs.iter() {
+                    if done.send((path.clone(), load(&path))).is_err() {
+                        break;
+                    }
+                }
+            });
+        if let Err(e) = spawned {
+            log::error!("MENU: Could not start thumbnail loader: {}", e);
+        }
+
+        Self {
+            requests,
+            results,
+            cache: Vec::new(),
+            in_flight: HashSet::new(),
+            failed: HashSet::new(),
+        }
+    }
+
+    /// Uploads the thumbnails loaded since the last frame.
+    pub fn poll(&mut self, ctx: &egui::Context) {
+        for (path, image) in self.results.try_iter() {
+            self.in_flight.remove(&path);
+            match image {
+                Some(image) => {
+                    let texture = ctx.load_texture(&path, image, Default::default());
+                    self.cache.push((path, texture));
+                    if self.cache.len() > CACHE_SIZE {
+                        // Dropping the handle frees the texture.
+                        self.cache.drain(..1);
+                    }
+                }
+                None => {
+                    self.failed.insert(path);
+                }
+            }
+        }
+        if !self.in_flight.is_empty() {
+            ctx.request_repaint();
+        }
+    }
+
+    /// The thumbnail of the background `image_path`, or `None` while it is
+    /// loading or if it cannot be made.
+    pub fn get(&mut self, image_path: &str) -> Option<TextureId> {
+        if let Some(index) = self.cache.iter().position(|(p, _)| p == image_path) {
+            let entry = self.cache.remove(index);
+            let id = entry.1.id();
+            self.cache.push(entry);
+            return Some(id);
+        }
+        if !self.in_flight.contains(image_path)
+            && !self.failed.contains(image_path)
+            && self.requests.send(image_path.to_string()).is_ok()
+        {
+            self.in_flight.insert(image_path.to_string());
+        }
+        None
+    }
+}
+
+fn load(image_path: &str) -> Option<ColorImage> {
+    let image = ensure_thumbnail(image_path)
+        .and_then(|path| image::open(&path).map_err(|e| format!("{:?}: {}", path, e)));
+    match image {
+        Ok(image) => {
+            let image = image.to_rgba8();
+            let size = [image.width() as usize, image.height() as usize];
+            Some(ColorImage::from_rgba_unmultiplied(
+                size,
+                image.as_flat_samples().as_slice(),
+            ))
+        }
+        Err(e) => {
+            log::warn!("MENU: No thumbnail for {}: {}", image_path, e);
+            None
+        }
+    }
+}
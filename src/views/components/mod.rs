@@ -5,14 +5,22 @@ pub mod menu;
 
 pub use gameplay::{
     accuracy::AccuracyDisplay,
+    column_judgements::ColumnJudgementsDisplay,
     combo::ComboDisplay,
+    debug_overlay::DebugOverlay,
+    early_late::EarlyLateDisplay,
+    ghost::GhostOverlay,
+    health::HealthOverlay,
     hit_bar::HitBarDisplay,
     judgement::{JudgementFlash, JudgementPanel},
+    key_overlay::KeyOverlayDisplay,
     notes_remaining::NotesRemainingDisplay,
     nps::NpsDisplay,
+    pb_delta::PbDeltaDisplay,
     playfield::PlayfieldDisplay,
     practice::PracticeOverlay,
     score::ScoreDisplay,
+    script_widget::ScriptWidgetDisplay,
     scroll_speed::ScrollSpeedDisplay,
     time_left::TimeLeftDisplay,
 };
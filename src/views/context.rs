@@ -1,7 +1,9 @@
 //! Render context structures.
 
 use crate::models::engine::PixelSystem;
-use wgpu::{BindGroup, Buffer, Device, Queue, RenderPipeline, TextureView};
+use crate::render::feedback::MissFeedback;
+use crate::render::sprite_atlas::SpriteAtlas;
+use wgpu::{Buffer, Device, Queue, RenderPipeline, TextureView};
 use wgpu_text::TextBrush;
 
 /// Contains all resources needed to render a game frame.
@@ -13,24 +15,23 @@ pub struct GameplayRenderContext<'a> {
     // Pipelines & Buffers
     pub render_pipeline: &'a RenderPipeline,
     pub progress_pipeline: &'a RenderPipeline, // NEW
+    pub quad_pipeline: &'a RenderPipeline,
     pub instance_buffer: &'a Buffer,
     pub receptor_buffer: &'a Buffer,
     pub progress_buffer: &'a Buffer, // NEW
+    pub quad_buffer: &'a Buffer,
+    /// HUD sprites drawn over the playfield (judgement flash).
+    pub sprite_buffer: &'a Buffer,
 
-    // Bind Groups (Textures)
-    pub note_bind_groups: &'a [BindGroup],
-    pub receptor_bind_groups: &'a [BindGroup],
-    pub receptor_pressed_bind_groups: &'a [BindGroup],
-
-    // Special note type bind groups
-    pub mine_bind_group: Option<&'a BindGroup>,
-    pub hold_body_bind_group: Option<&'a BindGroup>,
-    pub hold_end_bind_group: Option<&'a BindGroup>,
-    pub burst_body_bind_group: Option<&'a BindGroup>,
-    pub burst_end_bind_group: Option<&'a BindGroup>,
+    /// Skin sprites (notes, receptors, note parts).
+    pub sprite_atlas: &'a SpriteAtlas,
+    /// Shake of the notes and receptors, vignette flash.
+    pub miss_feedback: &'a MissFeedback,
 
     pub view: &'a TextureView,
     pub pixel_system: &'a PixelSystem,
+    /// Skin color of the sudden / hidden covers.
+    pub lane_cover_color: [f32; 4],
 
     pub screen_width: f32,
     pub screen_height: f32,
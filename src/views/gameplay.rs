@@ -2,18 +2,24 @@
 
 use bytemuck;
 use wgpu::{
-    CommandEncoder, LoadOp, Operations, RenderPassColorAttachment, RenderPassDescriptor, StoreOp,
+    BindGroup, CommandEncoder, LoadOp, Operations, RenderPassColorAttachment, RenderPassDescriptor,
+    StoreOp,
 };
 use wgpu_text::glyph_brush::Section; // Import bytemuck
 
-use crate::models::engine::{InstanceRaw, NUM_COLUMNS};
-use crate::models::skin::JudgementLabels;
+use crate::models::engine::{InstanceRaw, NoteData};
+use crate::models::skin::gameplay::notes::BurstConfig;
+use crate::models::skin::{HudConfig, JudgementLabels};
 use crate::models::stats::JudgementColors;
+use crate::render::fonts;
+use crate::render::resources::MAX_HUD_SPRITES;
 use crate::shared::snapshot::GameplaySnapshot;
+use crate::views::components::common::primitives::QuadInstance;
 use crate::views::components::gameplay::playfield::NoteVisual;
 use crate::views::components::{
-    AccuracyDisplay, ComboDisplay, HitBarDisplay, JudgementFlash, JudgementPanel,
-    NotesRemainingDisplay, NpsDisplay, PlayfieldDisplay, ScoreDisplay, ScrollSpeedDisplay,
+    AccuracyDisplay, ColumnJudgementsDisplay, ComboDisplay, EarlyLateDisplay, HitBarDisplay,
+    JudgementFlash, JudgementPanel, KeyOverlayDisplay, NotesRemainingDisplay, NpsDisplay,
+    PbDeltaDisplay, PlayfieldDisplay, ScoreDisplay, ScriptWidgetDisplay, ScrollSpeedDisplay,
     TimeLeftDisplay,
 };
 use crate::views::context::GameplayRenderContext; // Import
@@ -21,30 +27,41 @@ use crate::views::context::GameplayRenderContext; // Import
 pub struct GameplayView {
     playfield_component: PlayfieldDisplay,
     instance_cache: Vec<InstanceRaw>,
-    column_instances_cache: Vec<Vec<InstanceRaw>>,
+    tap_instances: Vec<InstanceRaw>,
     mine_instances: Vec<InstanceRaw>,
     hold_body_instances: Vec<InstanceRaw>,
     hold_end_instances: Vec<InstanceRaw>,
     burst_body_instances: Vec<InstanceRaw>,
     burst_end_instances: Vec<InstanceRaw>,
+    /// Screen position (px) of the head of each visible burst, and the hits
+    /// it still needs.
+    burst_counters: Vec<((f32, f32), u8)>,
+    /// Text of each hit count, indexed by the count, built the first time a
+    /// burst shows it.
+    burst_counter_texts: Vec<String>,
+    /// FPS shown in the corner, rebuilt only when the rounded value changes.
+    fps_shown: Option<u32>,
+    fps_text: String,
+    /// Notes of the snapshot in view, rebuilt each frame from its window.
+    note_cache: Vec<NoteData>,
 }
 
 impl GameplayView {
     pub fn new(playfield_component: PlayfieldDisplay) -> Self {
-        let mut column_instances_cache = Vec::with_capacity(NUM_COLUMNS);
-        for _ in 0..NUM_COLUMNS {
-            column_instances_cache.push(Vec::with_capacity(100));
-        }
-
         Self {
             playfield_component,
             instance_cache: Vec::with_capacity(2000),
-            column_instances_cache,
+            tap_instances: Vec::with_capacity(400),
             mine_instances: Vec::with_capacity(50),
             hold_body_instances: Vec::with_capacity(50),
             hold_end_instances: Vec::with_capacity(50),
             burst_body_instances: Vec::with_capacity(50),
             burst_end_instances: Vec::with_capacity(50),
+            burst_counters: Vec::new(),
+            burst_counter_texts: Vec::new(),
+            fps_shown: None,
+            fps_text: String::new(),
+            note_cache: Vec::with_capacity(400),
         }
     }
 
@@ -72,171 +89,255 @@ impl GameplayView {
         notes_remaining_display: &mut NotesRemainingDisplay,
         scroll_speed_display: &mut ScrollSpeedDisplay,
         time_left_display: &mut TimeLeftDisplay,
+        pb_delta_display: &mut PbDeltaDisplay,
+        early_late_display: &mut EarlyLateDisplay,
+        column_judgements_display: &mut ColumnJudgementsDisplay,
+        key_overlay_display: &mut KeyOverlayDisplay,
+        script_widgets: &mut ScriptWidgetDisplay,
         colors: &JudgementColors,
         labels: &JudgementLabels,
+        hud: &HudConfig,
+        burst: &BurstConfig,
     ) -> Result<(), wgpu::SurfaceError> {
-        let effective_scroll_speed = snapshot.scroll_speed * snapshot.rate;
-
-        let now = std::time::Instant::now();
-        let delta_time_ms = now.duration_since(snapshot.timestamp).as_secs_f64() * 1000.0;
-        let clamped_delta = delta_time_ms.min(50.0);
-        let interpolated_time = snapshot.audio_time + (clamped_delta * snapshot.rate);
-
-        let typed_instances = self.playfield_component.render_notes_typed(
-            &snapshot.visible_notes,
-            interpolated_time,
-            effective_scroll_speed,
-            ctx.pixel_system,
-        );
+        let (receptor_count, cover_count) = self.prepare_playfield(ctx, snapshot);
 
-        self.instance_cache.clear();
-        for col_vec in &mut self.column_instances_cache {
-            col_vec.clear();
-        }
-        self.mine_instances.clear();
-        self.hold_body_instances.clear();
-        self.hold_end_instances.clear();
-        self.burst_body_instances.clear();
-        self.burst_end_instances.clear();
-
-        for note_instance in typed_instances {
-            match note_instance.visual {
-                NoteVisual::Tap => {
-                    if note_instance.column < self.column_instances_cache.len() {
-                        self.column_instances_cache[note_instance.column]
-                            .push(note_instance.instance);
-                    }
-                }
-                NoteVisual::Mine => self.mine_instances.push(note_instance.instance),
-                NoteVisual::HoldBody => self.hold_body_instances.push(note_instance.instance),
-                NoteVisual::HoldEnd => self.hold_end_instances.push(note_instance.instance),
-                NoteVisual::BurstBody => self.burst_body_instances.push(note_instance.instance),
-                NoteVisual::BurstEnd => self.burst_end_instances.push(note_instance.instance),
+        let mut text_sections = Vec::new();
+        if burst.show_counter {
+            let mut color = burst.counter_color;
+            color[3] *= self.playfield_component.config.note_opacity;
+            let layout = wgpu_text::glyph_brush::Layout::default()
+                .h_align(wgpu_text::glyph_brush::HorizontalAlign::Center)
+                .v_align(wgpu_text::glyph_brush::VerticalAlign::Center);
+            let scale = burst.counter_scale * ctx.screen_height / 1080.0;
+            for ((x, y), remaining) in &self.burst_counters {
+                text_sections.push(Section {
+                    screen_position: (x + burst.counter_offset.x, y + burst.counter_offset.y),
+                    bounds: (ctx.screen_width, ctx.screen_height),
+                    layout,
+                    text: vec![
+                        wgpu_text::glyph_brush::Text::new(
+                            &self.burst_counter_texts[*remaining as usize],
+                        )
+                        .with_scale(scale)
+                        .with_color(color),
+                    ],
+                });
             }
         }
 
-        let mut column_offsets: Vec<u64> = Vec::with_capacity(NUM_COLUMNS);
-        let mut total_instances = 0u64;
-
-        for col_instances in &self.column_instances_cache {
-            column_offsets.push(total_instances);
-            self.instance_cache.extend(col_instances.iter().copied());
-            total_instances += col_instances.len() as u64;
-        }
-
-        let mine_offset = total_instances;
-        self.instance_cache
-            .extend(self.mine_instances.iter().copied());
-        total_instances += self.mine_instances.len() as u64;
-
-        let hold_body_offset = total_instances;
-        self.instance_cache
-            .extend(self.hold_body_instances.iter().copied());
-        total_instances += self.hold_body_instances.len() as u64;
-
-        let hold_end_offset = total_instances;
-        self.instance_cache
-            .extend(self.hold_end_instances.iter().copied());
-        total_instances += self.hold_end_instances.len() as u64;
-
-        let burst_body_offset = total_instances;
-        self.instance_cache
-            .extend(self.burst_body_instances.iter().copied());
-        total_instances += self.burst_body_instances.len() as u64;
-
-        let burst_end_offset = total_instances;
-        self.instance_cache
-            .extend(self.burst_end_instances.iter().copied());
-
-        if !self.instance_cache.is_empty() {
-            ctx.queue.write_buffer(
-                ctx.instance_buffer,
-                0,
-                bytemuck::cast_slice(&self.instance_cache),
-            );
+        let fps = ctx.fps.round() as u32;
+        if self.fps_shown != Some(fps) {
+            self.fps_shown = Some(fps);
+            self.fps_text = fps.to_string();
         }
-
-        let mut text_sections = Vec::new();
-        let fps_text = format!("{:.0}", ctx.fps);
         text_sections.push(Section {
             screen_position: (ctx.screen_width - 60.0, 20.0),
             bounds: (ctx.screen_width, ctx.screen_height),
             text: vec![
-                wgpu_text::glyph_brush::Text::new(&fps_text)
+                wgpu_text::glyph_brush::Text::new(&self.fps_text)
                     .with_scale(24.0)
                     .with_color([1.0, 1.0, 1.0, 1.0]),
             ],
             ..Default::default()
         });
 
+        // Le flash et les chiffres du skin, dessinés avec l'atlas
+        let mut hud_sprites: Vec<InstanceRaw> = Vec::new();
+        let digits = ctx.sprite_atlas.digits();
+
         score_display.set_score(snapshot.score);
-        text_sections.extend(score_display.render(ctx.screen_width, ctx.screen_height));
+        if let Some(digits) = digits {
+            hud_sprites.extend(faded_sprites(
+                score_display.render_sprites(digits, ctx.screen_width, ctx.screen_height),
+                hud.score.opacity,
+            ));
+            text_sections.extend(faded(
+                score_display.render_label(ctx.screen_width, ctx.screen_height),
+                hud.score.opacity,
+            ));
+        } else {
+            text_sections.extend(faded(
+                score_display.render(ctx.screen_width, ctx.screen_height),
+                hud.score.opacity,
+            ));
+        }
 
-        text_sections.extend(accuracy_panel.render(
-            snapshot.accuracy,
-            ctx.screen_width,
-            ctx.screen_height,
-        ));
+        if let Some(digits) = digits {
+            hud_sprites.extend(faded_sprites(
+                accuracy_panel.render_sprites(
+                    snapshot.accuracy,
+                    digits,
+                    ctx.screen_width,
+                    ctx.screen_height,
+                ),
+                hud.accuracy.opacity,
+            ));
+        } else {
+            text_sections.extend(faded(
+                accuracy_panel.render(snapshot.accuracy, ctx.screen_width, ctx.screen_height),
+                hud.accuracy.opacity,
+            ));
+        }
 
         // PASSAGE DES LABELS AU PANEL (no more notes/speed - they're separate now)
-        text_sections.extend(judgements_panel.render(
-            &snapshot.hit_stats,
-            ctx.screen_width,
-            ctx.screen_height,
-            labels,
+        text_sections.extend(faded(
+            judgements_panel.render(
+                &snapshot.hit_stats,
+                ctx.screen_width,
+                ctx.screen_height,
+                labels,
+            ),
+            hud.judgement_panel.opacity,
         ));
 
-        text_sections.extend(combo_display.render(
-            snapshot.combo,
-            ctx.screen_width,
-            ctx.screen_height,
-        ));
+        if let Some(digits) = digits {
+            hud_sprites.extend(faded_sprites(
+                combo_display.render_sprites(
+                    snapshot.combo,
+                    digits,
+                    ctx.screen_width,
+                    ctx.screen_height,
+                ),
+                hud.combo.opacity,
+            ));
+        } else {
+            text_sections.extend(faded(
+                combo_display.render(snapshot.combo, ctx.screen_width, ctx.screen_height),
+                hud.combo.opacity,
+            ));
+        }
+
+        // Le flash est l'image du skin si elle existe, sinon le texte
+        judgement_flash.advance(&snapshot.hit_stats);
+        let flash_image = snapshot
+            .last_hit_judgement
+            .and_then(|judgement| Some((judgement, ctx.sprite_atlas.judgement(judgement)?)));
+        let flash_sprite = flash_image.and_then(|(judgement, uv)| {
+            judgement_flash.sprite(judgement, uv, ctx.screen_width, ctx.screen_height)
+        });
+        hud_sprites.extend(faded_sprites(flash_sprite, hud.judgement.opacity));
+        hud_sprites.truncate(MAX_HUD_SPRITES);
+        if !hud_sprites.is_empty() {
+            ctx.queue
+                .write_buffer(ctx.sprite_buffer, 0, bytemuck::cast_slice(&hud_sprites));
+        }
 
         // PASSAGE DES COULEURS ET LABELS AU FLASH avec timing pour +/-
-        text_sections.extend(judgement_flash.render(
-            snapshot.last_hit_judgement,
-            snapshot.last_hit_timing, // timing in ms for +/- indicator
+        if flash_image.is_none() {
+            text_sections.extend(faded(
+                judgement_flash.render(
+                    snapshot.last_hit_judgement,
+                    snapshot.last_hit_timing, // timing in ms for +/- indicator
+                    ctx.screen_width,
+                    ctx.screen_height,
+                    colors,
+                    labels,
+                ),
+                hud.judgement.opacity,
+            ));
+        }
+
+        text_sections.extend(faded(
+            early_late_display.render(
+                &snapshot.recent_hit_offsets,
+                ctx.screen_width,
+                ctx.screen_height,
+            ),
+            hud.early_late.opacity,
+        ));
+        text_sections.extend(faded(
+            column_judgements_display.render(
+                &snapshot.hit_stats,
+                colors,
+                ctx.screen_width,
+                ctx.screen_height,
+            ),
+            hud.column_judgements.opacity,
+        ));
+        // Squares before the meter, whose text stays borrowed until drawn.
+        let key_quads = key_overlay_display.render_keys(
+            &snapshot.keys_held,
             ctx.screen_width,
             ctx.screen_height,
-            colors,
-            labels,
+        );
+        text_sections.extend(faded(
+            key_overlay_display.render(
+                &snapshot.recent_presses,
+                snapshot.audio_time,
+                snapshot.rate,
+                ctx.screen_width,
+                ctx.screen_height,
+            ),
+            hud.key_overlay.opacity,
         ));
 
-        text_sections.extend(hit_bar.render(
+        // Pas encore de frappe : nouvelle partie, la barre repart de zéro
+        if snapshot.recent_hit_offsets.is_empty() {
+            hit_bar.clear();
+        }
+        let mut hud_quads = hit_bar.render(
             snapshot.last_hit_timing.zip(snapshot.last_hit_judgement),
             ctx.screen_width,
             ctx.screen_height,
+        );
+        for quad in &mut hud_quads {
+            quad.color[3] *= hud.hit_bar.opacity;
+        }
+        hud_quads.extend(key_quads.into_iter().map(|mut quad| {
+            quad.color[3] *= hud.key_overlay.opacity;
+            quad
+        }));
+        text_sections.extend(faded(
+            nps_display.render(snapshot.nps, ctx.screen_width, ctx.screen_height),
+            hud.nps.opacity,
         ));
-        text_sections.extend(nps_display.render(snapshot.nps, ctx.screen_width, ctx.screen_height));
 
         // NEW: Separate display components
-        text_sections.extend(notes_remaining_display.render(
-            snapshot.remaining_notes,
-            ctx.screen_width,
-            ctx.screen_height,
+        text_sections.extend(faded(
+            notes_remaining_display.render(
+                snapshot.remaining_notes,
+                ctx.screen_width,
+                ctx.screen_height,
+            ),
+            hud.notes_remaining.opacity,
         ));
-        text_sections.extend(scroll_speed_display.render(
-            snapshot.scroll_speed,
-            ctx.screen_width,
-            ctx.screen_height,
+        text_sections.extend(faded(
+            scroll_speed_display.render(snapshot.scroll_speed, ctx.screen_width, ctx.screen_height),
+            hud.scroll_speed.opacity,
         ));
-        text_sections.extend(time_left_display.render(
-            snapshot.audio_time,   // elapsed
-            snapshot.map_duration, // total
-            ctx.screen_width,
-            ctx.screen_height,
+        text_sections.extend(faded(
+            pb_delta_display.render(
+                snapshot.personal_best.as_ref(),
+                ctx.screen_width,
+                ctx.screen_height,
+            ),
+            hud.pb_delta.opacity,
         ));
+        text_sections.extend(faded(
+            time_left_display.render(
+                snapshot.audio_time,   // elapsed
+                snapshot.map_duration, // total
+                ctx.screen_width,
+                ctx.screen_height,
+            ),
+            hud.time_left.opacity,
+        ));
+
+        script_widgets.update(snapshot);
+        text_sections.extend(script_widgets.render(ctx.screen_width, ctx.screen_height));
 
+        let text_sections = fonts::with_fallback(text_sections, ctx.text_brush.fonts());
         ctx.text_brush
             .queue(ctx.device, ctx.queue, text_sections)
             .map_err(|_| wgpu::SurfaceError::Lost)?;
 
-        let receptor_instances = self.playfield_component.render_receptors(ctx.pixel_system);
-        if !receptor_instances.is_empty() {
+        // Hit bar and widget bars go after the lane covers in the quad buffer.
+        hud_quads.extend(script_widgets.quads(ctx.screen_width, ctx.screen_height));
+        if !hud_quads.is_empty() {
             ctx.queue.write_buffer(
-                ctx.receptor_buffer,
-                0,
-                bytemuck::cast_slice(&receptor_instances),
+                ctx.quad_buffer,
+                (cover_count * std::mem::size_of::<QuadInstance>()) as u64,
+                bytemuck::cast_slice(&hud_quads),
             );
         }
 
@@ -257,108 +358,29 @@ impl GameplayView {
                 occlusion_query_set: None,
             });
 
-            render_pass.set_pipeline(ctx.render_pipeline);
-
-            if !receptor_instances.is_empty() {
-                for (col, _) in receptor_instances.iter().enumerate() {
-                    if col < ctx.receptor_bind_groups.len() {
-                        let is_pressed = snapshot.keys_held.get(col).copied().unwrap_or(false);
-                        let bind_group =
-                            if is_pressed && col < ctx.receptor_pressed_bind_groups.len() {
-                                &ctx.receptor_pressed_bind_groups[col]
-                            } else {
-                                &ctx.receptor_bind_groups[col]
-                            };
-                        render_pass.set_bind_group(0, bind_group, &[]);
-                        let offset = (col * std::mem::size_of::<InstanceRaw>()) as u64;
-                        let size = std::mem::size_of::<InstanceRaw>() as u64;
-                        render_pass
-                            .set_vertex_buffer(0, ctx.receptor_buffer.slice(offset..offset + size));
-                        render_pass.draw(0..6, 0..1);
-                    }
-                }
-            }
-
-            let draw_special_instances =
-                |render_pass: &mut wgpu::RenderPass,
-                 bind_group: Option<&wgpu::BindGroup>,
-                 fallback_bind_group: &wgpu::BindGroup,
-                 offset: u64,
-                 count: usize,
-                 buffer: &wgpu::Buffer| {
-                    if count == 0 {
-                        return;
-                    }
-                    render_pass.set_bind_group(0, bind_group.unwrap_or(fallback_bind_group), &[]);
-                    let offset_bytes = offset * std::mem::size_of::<InstanceRaw>() as u64;
-                    let size_bytes = count as u64 * std::mem::size_of::<InstanceRaw>() as u64;
-                    render_pass.set_vertex_buffer(
-                        0,
-                        buffer.slice(offset_bytes..offset_bytes + size_bytes),
-                    );
-                    render_pass.draw(0..6, 0..count as u32);
-                };
-
-            let fallback = &ctx.note_bind_groups[0];
-
-            draw_special_instances(
+            self.draw_playfield(
                 &mut render_pass,
-                ctx.hold_body_bind_group,
-                fallback,
-                hold_body_offset,
-                self.hold_body_instances.len(),
-                ctx.instance_buffer,
-            );
-            draw_special_instances(
-                &mut render_pass,
-                ctx.burst_body_bind_group,
-                fallback,
-                burst_body_offset,
-                self.burst_body_instances.len(),
-                ctx.instance_buffer,
+                ctx,
+                ctx.miss_feedback.playfield_bind_group(),
+                receptor_count,
+                cover_count,
             );
+            ctx.miss_feedback.draw_vignette(&mut render_pass);
 
-            for (col, col_instances) in self.column_instances_cache.iter().enumerate() {
-                if col_instances.is_empty() || col >= ctx.note_bind_groups.len() {
-                    continue;
-                }
-                render_pass.set_bind_group(0, &ctx.note_bind_groups[col], &[]);
-                let offset_bytes = column_offsets[col] * std::mem::size_of::<InstanceRaw>() as u64;
-                let size_bytes =
-                    col_instances.len() as u64 * std::mem::size_of::<InstanceRaw>() as u64;
-                render_pass.set_vertex_buffer(
-                    0,
-                    ctx.instance_buffer
-                        .slice(offset_bytes..offset_bytes + size_bytes),
-                );
-                render_pass.draw(0..6, 0..col_instances.len() as u32);
+            if !hud_quads.is_empty() {
+                let first = cover_count as u32;
+                render_pass.set_pipeline(ctx.quad_pipeline);
+                render_pass.set_vertex_buffer(0, ctx.quad_buffer.slice(..));
+                render_pass.draw(0..4, first..first + hud_quads.len() as u32);
             }
 
-            draw_special_instances(
-                &mut render_pass,
-                ctx.hold_end_bind_group,
-                fallback,
-                hold_end_offset,
-                self.hold_end_instances.len(),
-                ctx.instance_buffer,
-            );
-            draw_special_instances(
-                &mut render_pass,
-                ctx.burst_end_bind_group,
-                fallback,
-                burst_end_offset,
-                self.burst_end_instances.len(),
-                ctx.instance_buffer,
-            );
-
-            draw_special_instances(
-                &mut render_pass,
-                ctx.mine_bind_group,
-                fallback,
-                mine_offset,
-                self.mine_instances.len(),
-                ctx.instance_buffer,
-            );
+            if !hud_sprites.is_empty() {
+                render_pass.set_pipeline(ctx.render_pipeline);
+                render_pass.set_bind_group(0, &ctx.sprite_atlas.bind_group, &[]);
+                render_pass.set_bind_group(1, ctx.miss_feedback.still_bind_group(), &[]);
+                render_pass.set_vertex_buffer(0, ctx.sprite_buffer.slice(..));
+                render_pass.draw(0..6, 0..hud_sprites.len() as u32);
+            }
 
             // Render TimeLeft progress (Bar/Circle)
             if let Some(instance) = time_left_display.get_progress_instance(
@@ -382,4 +404,282 @@ impl GameplayView {
 
         Ok(())
     }
+
+    /// Opponent side of a versus race: the playfield and a one-line summary
+    /// of the run above it, without the skin HUD.
+    pub fn render_opponent(
+        &mut self,
+        ctx: &mut GameplayRenderContext<'_>,
+        encoder: &mut CommandEncoder,
+        snapshot: &GameplaySnapshot,
+        label: &str,
+    ) -> Result<(), wgpu::SurfaceError> {
+        let (receptor_count, cover_count) = self.prepare_playfield(ctx, snapshot);
+
+        let center_x = ctx.screen_width / 2.0 + self.playfield_component.config.x_offset_pixels;
+        let summary = format!(
+            "{}\n{}  {:.2}%  {}x",
+            label, snapshot.score, snapshot.accuracy, snapshot.combo
+        );
+        let section = Section {
+            screen_position: (center_x, 20.0),
+            bounds: (ctx.screen_width, ctx.screen_height),
+            layout: wgpu_text::glyph_brush::Layout::default()
+                .h_align(wgpu_text::glyph_brush::HorizontalAlign::Center),
+            text: vec![
+                wgpu_text::glyph_brush::Text::new(&summary)
+                    .with_scale(28.0)
+                    .with_color([1.0, 1.0, 1.0, 1.0]),
+            ],
+        };
+        let sections = fonts::with_fallback(vec![section], ctx.text_brush.fonts());
+        ctx.text_brush
+            .queue(ctx.device, ctx.queue, sections)
+            .map_err(|_| wgpu::SurfaceError::Lost)?;
+
+        let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("Versus Opponent Pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: ctx.view,
+                resolve_target: None,
+                ops: Operations {
+                    load: LoadOp::Load,
+                    store: StoreOp::Store,
+                },
+                depth_slice: None,
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        self.draw_playfield(
+            &mut render_pass,
+            ctx,
+            ctx.miss_feedback.still_bind_group(),
+            receptor_count,
+            cover_count,
+        );
+        ctx.text_brush.draw(&mut render_pass);
+        Ok(())
+    }
+
+    /// Writes the notes, receptors and lane covers of `snapshot` to their
+    /// buffers. Returns the receptor and cover counts.
+    fn prepare_playfield(
+        &mut self,
+        ctx: &GameplayRenderContext<'_>,
+        snapshot: &GameplaySnapshot,
+    ) -> (usize, usize) {
+        let effective_scroll_speed = snapshot.scroll_speed * snapshot.rate;
+
+        let now = std::time::Instant::now();
+        let delta_time_ms = now.duration_since(snapshot.timestamp).as_secs_f64() * 1000.0;
+        let clamped_delta = delta_time_ms.min(50.0);
+        let interpolated_time = snapshot.audio_time + (clamped_delta * snapshot.rate);
+
+        snapshot.visible_notes.collect_into(&mut self.note_cache);
+        let typed_instances = self.playfield_component.render_notes_typed(
+            &self.note_cache,
+            interpolated_time,
+            effective_scroll_speed,
+            snapshot.scroll_map.as_deref(),
+            ctx.pixel_system,
+        );
+
+        // With a custom lane order, lanes keep the skin of their chart column.
+        let skin_column = |lane: usize| {
+            snapshot
+                .column_order
+                .iter()
+                .position(|&l| l == lane)
+                .unwrap_or(lane)
+        };
+        let atlas = ctx.sprite_atlas;
+        let config = &self.playfield_component.config;
+        let (note_opacity, hold_body_opacity) = (config.note_opacity, config.hold_body_opacity);
+
+        self.instance_cache.clear();
+        self.tap_instances.clear();
+        self.mine_instances.clear();
+        self.hold_body_instances.clear();
+        self.hold_end_instances.clear();
+        self.burst_body_instances.clear();
+        self.burst_end_instances.clear();
+        self.burst_counters.clear();
+
+        for note_instance in typed_instances {
+            let mut instance = note_instance.instance;
+            if let NoteVisual::BurstCounter { remaining } = note_instance.visual {
+                // Clip space -> pixels écran (y vers le bas)
+                let [x, y] = instance.offset;
+                let position = (
+                    (x + 1.0) / 2.0 * ctx.screen_width,
+                    (1.0 - y) / 2.0 * ctx.screen_height,
+                );
+                while self.burst_counter_texts.len() <= remaining as usize {
+                    let count = self.burst_counter_texts.len();
+                    self.burst_counter_texts.push(count.to_string());
+                }
+                self.burst_counters.push((position, remaining));
+                continue;
+            }
+            let (target, uv) = match note_instance.visual {
+                NoteVisual::Tap => (
+                    &mut self.tap_instances,
+                    atlas.note(skin_column(note_instance.column)),
+                ),
+                NoteVisual::Mine => (&mut self.mine_instances, atlas.mine),
+                NoteVisual::HoldBody => (&mut self.hold_body_instances, atlas.hold_body),
+                NoteVisual::HoldEnd => (&mut self.hold_end_instances, atlas.hold_end),
+                NoteVisual::BurstBody => (&mut self.burst_body_instances, atlas.burst_body),
+                NoteVisual::BurstEnd => (&mut self.burst_end_instances, atlas.burst_end),
+                NoteVisual::BurstCounter { .. } => continue,
+            };
+            instance.uv = uv;
+            instance.alpha = match note_instance.visual {
+                NoteVisual::HoldBody | NoteVisual::BurstBody => hold_body_opacity,
+                _ => note_opacity,
+            };
+            // Une répétition de l'image du corps fait sa hauteur à la largeur
+            // du corps ; l'ancrage en haut la garde collée à la queue.
+            let repeat = match note_instance.visual {
+                NoteVisual::HoldBody if !config.stretch_hold_body => Some(atlas.hold_body_aspect),
+                NoteVisual::BurstBody if !config.stretch_burst_body => {
+                    Some(atlas.burst_body_aspect)
+                }
+                _ => None,
+            };
+            if let Some(aspect) = repeat {
+                instance.tile =
+                    body_repeats(instance.scale, aspect, ctx.screen_width, ctx.screen_height);
+            }
+            target.push(instance);
+        }
+
+        // Every sprite is in the atlas, so the buffer order is the draw order:
+        // bodies under the heads, ends over them, mines on top.
+        for instances in [
+            &self.hold_body_instances,
+            &self.burst_body_instances,
+            &self.tap_instances,
+            &self.hold_end_instances,
+            &self.burst_end_instances,
+            &self.mine_instances,
+        ] {
+            self.instance_cache.extend(instances.iter().copied());
+        }
+
+        if !self.instance_cache.is_empty() {
+            ctx.queue.write_buffer(
+                ctx.instance_buffer,
+                0,
+                bytemuck::cast_slice(&self.instance_cache),
+            );
+        }
+
+        let mut receptor_instances = self.playfield_component.render_receptors(ctx.pixel_system);
+        for (col, instance) in receptor_instances.iter_mut().enumerate() {
+            let is_pressed = snapshot.keys_held.get(col).copied().unwrap_or(false);
+            instance.uv = atlas.receptor(skin_column(col), is_pressed);
+            instance.alpha = self.playfield_component.config.receptor_opacity;
+        }
+        if !receptor_instances.is_empty() {
+            ctx.queue.write_buffer(
+                ctx.receptor_buffer,
+                0,
+                bytemuck::cast_slice(&receptor_instances),
+            );
+        }
+
+        let cover_quads = self.playfield_component.render_lane_covers(
+            ctx.pixel_system,
+            &snapshot.lane_cover,
+            ctx.lane_cover_color,
+        );
+        if !cover_quads.is_empty() {
+            ctx.queue
+                .write_buffer(ctx.quad_buffer, 0, bytemuck::cast_slice(&cover_quads));
+        }
+
+        (receptor_instances.len(), cover_quads.len())
+    }
+
+    fn draw_playfield(
+        &self,
+        render_pass: &mut wgpu::RenderPass<'_>,
+        ctx: &GameplayRenderContext<'_>,
+        feedback: &BindGroup,
+        receptor_count: usize,
+        cover_count: usize,
+    ) {
+        render_pass.set_pipeline(ctx.render_pipeline);
+        render_pass.set_bind_group(0, &ctx.sprite_atlas.bind_group, &[]);
+        render_pass.set_bind_group(1, feedback, &[]);
+
+        if receptor_count > 0 {
+            render_pass.set_vertex_buffer(0, ctx.receptor_buffer.slice(..));
+            render_pass.draw(0..6, 0..receptor_count as u32);
+        }
+
+        if !self.instance_cache.is_empty() {
+            render_pass.set_vertex_buffer(0, ctx.instance_buffer.slice(..));
+            render_pass.draw(0..6, 0..self.instance_cache.len() as u32);
+        }
+
+        // Lane covers go over the notes but under the HUD.
+        if cover_count > 0 {
+            render_pass.set_pipeline(ctx.quad_pipeline);
+            render_pass.set_vertex_buffer(0, ctx.quad_buffer.slice(..));
+            render_pass.draw(0..4, 0..cover_count as u32);
+        }
+    }
+}
+
+/// Repeats of a body texture of `aspect` (height / width) down a quad of
+/// clip-space `scale`.
+fn body_repeats(scale: [f32; 2], aspect: f32, screen_width: f32, screen_height: f32) -> f32 {
+    let tile_height = scale[0] * screen_width * aspect;
+    if tile_height <= 0.0 {
+        return 0.0;
+    }
+    // Au moins un peu plus que zéro : 0 voudrait dire étiré
+    (scale[1] * screen_height / tile_height).max(f32::EPSILON)
+}
+
+/// Multiplies the alpha of every text of `sections` by `opacity`.
+fn faded(mut sections: Vec<Section<'_>>, opacity: f32) -> Vec<Section<'_>> {
+    if opacity < 1.0 {
+        for text in sections
+            .iter_mut()
+            .flat_map(|section| section.text.iter_mut())
+        {
+            text.extra.color[3] *= opacity;
+        }
+    }
+    sections
+}
+
+/// Same as [`faded`], for HUD sprites.
+fn faded_sprites(
+    sprites: impl IntoIterator<Item = InstanceRaw>,
+    opacity: f32,
+) -> impl Iterator<Item = InstanceRaw> {
+    sprites.into_iter().map(move |mut sprite| {
+        sprite.alpha *= opacity;
+        sprite
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn body_repeats_follow_the_image_aspect() {
+        // A 100 px wide body, 400 px long, with a square image: 4 repeats.
+        let scale = [2.0 * 100.0 / 1920.0, 2.0 * 400.0 / 1080.0];
+        assert!((body_repeats(scale, 1.0, 1920.0, 1080.0) - 4.0).abs() < 1e-4);
+        assert!((body_repeats(scale, 2.0, 1920.0, 1080.0) - 2.0).abs() < 1e-4);
+        assert_eq!(body_repeats([0.0, 1.0], 1.0, 1920.0, 1080.0), 0.0);
+    }
 }
@@ -1,12 +1,29 @@
-use crate::models::settings::{HitWindowMode, SettingsState};
+use crate::database::maintenance::{MaintenanceReport, MaintenanceRequest, format_size};
+use crate::database::models::{LibraryStats, Profile};
+use crate::models::engine::hit_window::HitWindow;
+use crate::models::settings::{
+    BmsScratchMode, DEFAULT_MIRROR_URL, DEFAULT_SHARE_TEMPLATE, DisplayMode, HitWindowMode,
+    LaneCover, OsuHitsound, PresentModeSetting, RateMode, ReplayPruneRules, ScrollEasing,
+    ScrollSpeedMode, SettingsCategory, SettingsState, TournamentSettings, VolumeChannel, Volumes,
+};
+use crate::models::skin::package;
+use crate::models::stats::JudgementPalette;
+use crate::presence::Presence;
+use crate::shared::locale::{self, tr, tr_args};
+use crate::views::components::menu::library_overview::render_library_overview;
+use egui::Ui;
 use log::info;
+use std::collections::HashMap;
 
 #[derive(Clone)]
 pub struct SettingsSnapshot {
     pub skin: String,
     pub hit_window_mode: HitWindowMode,
     pub hit_window_value: f64,
-    pub master_volume: f32,
+    pub volumes: Volumes,
+    pub scroll_speed: f64,
+    pub scroll_speeds: HashMap<String, f64>,
+    pub lane_cover: LaneCover,
 }
 
 impl SettingsSnapshot {
@@ -15,185 +32,1328 @@ impl SettingsSnapshot {
             skin: settings.current_skin.clone(),
             hit_window_mode: settings.hit_window_mode,
             hit_window_value: settings.hit_window_value,
-            master_volume: settings.master_volume,
+            volumes: settings.volumes(),
+            scroll_speed: settings.scroll_speed,
+            scroll_speeds: settings.scroll_speeds.clone(),
+            lane_cover: settings.lane_cover,
         }
     }
 }
 
+/// Library data shown in the "Library overview" section (menu only).
+pub struct LibraryOverview<'a> {
+    pub stats: Option<&'a LibraryStats>,
+    pub progress: Option<(usize, usize)>,
+    pub maintenance: Option<&'a MaintenanceReport>,
+}
+
+/// A connected monitor, for the display mode options.
+pub struct MonitorInfo {
+    pub name: String,
+    /// Supported resolutions, largest first.
+    pub resolutions: Vec<(u32, u32)>,
+}
+
+#[derive(Default)]
 pub struct SettingsWindowResult {
     pub request_toggle: bool,
-    pub volume_changed: Option<f32>,
+    pub volume_changed: Option<Volumes>,
+    pub scroll_speed_changed: Option<f64>,
+    pub keymode_scroll_speeds_changed: Option<HashMap<String, f64>>,
+    pub lane_cover_changed: Option<LaneCover>,
     pub keybinds_updated: bool,
     pub hit_window_changed: Option<(HitWindowMode, f64)>,
+    pub profile_switched: Option<i64>,
+    pub profile_created: Option<String>,
+    pub library_stats_requested: bool,
+    pub discord_presence_changed: Option<bool>,
+    pub unicode_metadata_changed: Option<bool>,
+    pub tournament_changed: Option<TournamentSettings>,
+    /// `.rvskin` package picked to be imported.
+    pub skin_package_picked: Option<std::path::PathBuf>,
+    /// Library folders after one was added or removed.
+    pub library_roots_changed: Option<Vec<std::path::PathBuf>>,
+    pub maintenance_requested: Option<MaintenanceRequest>,
+}
+
+/// Options of one settings page. While searching, options whose name does
+/// not match are skipped and the page title is drawn above the first match.
+struct Section<'q> {
+    query: &'q str,
+    heading: Option<String>,
+    separate: bool,
+    shown: bool,
+}
+
+impl<'q> Section<'q> {
+    fn new(query: &'q str, category: SettingsCategory, separate: bool) -> Self {
+        Self {
+            query,
+            heading: (!query.is_empty()).then(|| category.label()),
+            separate,
+            shown: false,
+        }
+    }
+
+    fn option(&mut self, ui: &mut Ui, name: &str, add: impl FnOnce(&mut Ui)) {
+        if !name.to_lowercase().contains(self.query) {
+            return;
+        }
+        if let Some(heading) = self.heading.take() {
+            if self.separate {
+                ui.separator();
+            }
+            ui.heading(heading);
+        } else if self.shown {
+            ui.add_space(6.0);
+        }
+        self.shown = true;
+        add(ui);
+    }
 }
 
 pub fn render_settings_window(
     ctx: &egui::Context,
     settings: &mut SettingsState,
     snapshot: &SettingsSnapshot,
+    profiles: &[Profile],
+    library: Option<LibraryOverview<'_>>,
+    monitors: &[MonitorInfo],
 ) -> SettingsWindowResult {
-    let mut request_toggle = false;
-    let mut volume_changed = None;
-    let mut hit_window_changed = None;
+    let mut result = SettingsWindowResult::default();
     let mut open = true;
-    let mut keybinds_updated = false;
+    let mut close_clicked = false;
 
-    egui::Window::new("Settings")
+    egui::Window::new(tr("settings.title"))
+        .id(egui::Id::new("settings_window"))
         .open(&mut open)
+        .default_size([620.0, 520.0])
         .show(ctx, |ui| {
-            ui.heading("Skin");
-            let mut skins = vec!["default".to_string()];
-            if let Ok(entries) = std::fs::read_dir("skins") {
-                for entry in entries.flatten() {
-                    if entry.path().is_dir()
-                        && let Some(name) = entry.file_name().to_str()
-                        && name != "default"
-                    {
-                        skins.push(name.to_string());
+            ui.add(
+                egui::TextEdit::singleline(&mut settings.settings_search)
+                    .hint_text(tr("settings.search_hint"))
+                    .desired_width(f32::INFINITY),
+            );
+            let query = settings.settings_search.trim().to_lowercase();
+            let searching = !query.is_empty();
+            ui.separator();
+
+            ui.horizontal_top(|ui| {
+                ui.vertical(|ui| {
+                    ui.set_width(100.0);
+                    for category in SettingsCategory::ALL {
+                        let selected = !searching && settings.settings_category == category;
+                        if ui.selectable_label(selected, category.label()).clicked() {
+                            settings.settings_category = category;
+                            settings.settings_search.clear();
+                        }
                     }
-                }
-            }
-            egui::ComboBox::from_label("Skin")
-                .selected_text(&settings.current_skin)
+                });
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .max_height(ui.available_height() - 40.0)
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        let categories = if searching {
+                            SettingsCategory::ALL.to_vec()
+                        } else {
+                            vec![settings.settings_category]
+                        };
+                        let mut any_shown = false;
+                        for category in categories {
+                            let mut section = Section::new(&query, category, any_shown);
+                            match category {
+                                SettingsCategory::Gameplay => {
+                                    gameplay_page(&mut section, ui, settings, profiles, &mut result)
+                                }
+                                SettingsCategory::Audio => audio_page(&mut section, ui, settings),
+                                SettingsCategory::Video => {
+                                    video_page(&mut section, ui, settings, monitors)
+                                }
+                                SettingsCategory::Input => input_page(&mut section, ui, settings),
+                                SettingsCategory::Skin => {
+                                    skin_page(&mut section, ui, settings, &mut result)
+                                }
+                                SettingsCategory::Library => library_page(
+                                    &mut section,
+                                    ui,
+                                    settings,
+                                    library.as_ref(),
+                                    &mut result,
+                                ),
+                            }
+                            any_shown |= section.shown;
+                        }
+                        if !any_shown {
+                            ui.label(tr("settings.no_match"));
+                        }
+                    });
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                close_clicked = ui.button(tr("settings.close")).clicked();
+                ui.label(tr("settings.saved_on_close"));
+            });
+        });
+
+    // Applied live; saved with the rest on close.
+    if settings.volumes() != snapshot.volumes {
+        result.volume_changed = Some(settings.volumes());
+    }
+    if (settings.scroll_speed - snapshot.scroll_speed).abs() > f64::EPSILON {
+        result.scroll_speed_changed = Some(settings.scroll_speed);
+    }
+    if settings.scroll_speeds != snapshot.scroll_speeds {
+        result.keymode_scroll_speeds_changed = Some(settings.scroll_speeds.clone());
+    }
+    if settings.lane_cover != snapshot.lane_cover {
+        result.lane_cover_changed = Some(settings.lane_cover);
+    }
+
+    if settings.hit_window_mode != snapshot.hit_window_mode
+        || (settings.hit_window_value - snapshot.hit_window_value).abs() > f64::EPSILON
+    {
+        info!(
+            "Settings: Hit window updated -> mode {:?}, value {:.2}",
+            settings.hit_window_mode, settings.hit_window_value
+        );
+        result.hit_window_changed = Some((settings.hit_window_mode, settings.hit_window_value));
+    }
+
+    if settings.keybinds_changed {
+        settings.keybinds_changed = false;
+        settings.save_keybinds();
+        info!("Settings: Keybinds applied");
+        result.keybinds_updated = true;
+    }
+
+    if !open || close_clicked {
+        settings.cancel_keybind_capture();
+        settings.settings_search.clear();
+        settings.save();
+        info!("Settings: Saved");
+
+        result.keybinds_updated = true;
+        result.request_toggle = true;
+    }
+
+    result
+}
+
+fn gameplay_page(
+    section: &mut Section,
+    ui: &mut Ui,
+    settings: &mut SettingsState,
+    profiles: &[Profile],
+    result: &mut SettingsWindowResult,
+) {
+    if !profiles.is_empty() {
+        section.option(ui, &tr("settings.option.profile"), |ui| {
+            let current = profiles
+                .iter()
+                .find(|p| p.id == settings.profile_id)
+                .map_or("?", |p| p.name.as_str());
+            let mut selected = settings.profile_id;
+            egui::ComboBox::from_label(tr("settings.option.profile"))
+                .selected_text(current)
                 .show_ui(ui, |ui| {
-                    for skin_name in skins {
-                        ui.selectable_value(
-                            &mut settings.current_skin,
-                            skin_name.clone(),
-                            skin_name,
-                        );
+                    for profile in profiles {
+                        ui.selectable_value(&mut selected, profile.id, &profile.name);
                     }
                 });
+            if selected != settings.profile_id {
+                settings.switch_profile(selected);
+                settings.save();
+                info!("Settings: Switched to profile {}", selected);
+                result.profile_switched = Some(selected);
+            }
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut settings.new_profile_name)
+                        .hint_text(tr("settings.text.new_profile"))
+                        .desired_width(160.0),
+                );
+                let name = settings.new_profile_name.trim();
+                let taken = profiles.iter().any(|p| p.name == name);
+                if ui
+                    .add_enabled(
+                        !name.is_empty() && !taken,
+                        egui::Button::new(tr("settings.text.create_profile")),
+                    )
+                    .clicked()
+                {
+                    result.profile_created = Some(name.to_string());
+                    settings.new_profile_name.clear();
+                }
+            });
+            ui.label(tr("settings.text.profile_note"));
+        });
+    }
 
-            ui.separator();
-            ui.heading("Audio");
+    section.option(ui, &tr("settings.option.scroll_speed"), |ui| {
+        ui.add(
+            egui::Slider::new(&mut settings.scroll_speed, 100.0..=2000.0)
+                .text(tr("settings.option.scroll_speed"))
+                .suffix(" ms")
+                .step_by(10.0),
+        )
+        .on_hover_text(tr("settings.hint.scroll_speed"));
+    });
+
+    section.option(ui, &tr("settings.option.scroll_speed_per_keymode"), |ui| {
+        let mut key_modes: Vec<usize> = settings
+            .keybinds
+            .keys()
+            .filter_map(|key| key.parse().ok())
+            .collect();
+        key_modes.sort_unstable();
+        for key_count in key_modes {
+            let key = key_count.to_string();
+            ui.horizontal(|ui| {
+                let mut own = settings.scroll_speeds.contains_key(&key);
+                if ui
+                    .checkbox(&mut own, format!("{}K", key_count))
+                    .on_hover_text(tr("settings.hint.keymode_scroll_speed"))
+                    .changed()
+                {
+                    if own {
+                        settings
+                            .scroll_speeds
+                            .insert(key.clone(), settings.scroll_speed);
+                    } else {
+                        settings.scroll_speeds.remove(&key);
+                    }
+                }
+                if let Some(speed) = settings.scroll_speeds.get_mut(&key) {
+                    ui.add(
+                        egui::Slider::new(speed, 100.0..=2000.0)
+                            .suffix(" ms")
+                            .step_by(10.0),
+                    );
+                } else {
+                    ui.weak(format!("{:.0} ms", settings.scroll_speed));
+                }
+            });
+        }
+    });
+
+    section.option(ui, &tr("settings.option.gameplay_presets"), |ui| {
+        ui.label(tr("settings.text.presets_note"));
+        let mut load = None;
+        let mut delete = None;
+        for preset in &settings.presets {
+            ui.horizontal(|ui| {
+                let active = settings.active_preset.as_deref() == Some(preset.name.as_str());
+                let name = if active {
+                    egui::RichText::new(&preset.name).strong()
+                } else {
+                    egui::RichText::new(&preset.name)
+                };
+                ui.label(name);
+                if ui.small_button(tr("settings.text.load")).clicked() {
+                    load = Some(preset.name.clone());
+                }
+                if ui.small_button(tr("settings.text.delete")).clicked() {
+                    delete = Some(preset.name.clone());
+                }
+            });
+        }
+        if settings.preset_modified() {
+            ui.weak(tr("settings.text.preset_modified"));
+        }
+        if let Some(name) = load {
+            settings.apply_preset(&name);
+            info!("Settings: Gameplay preset '{}' loaded", name);
+        }
+        if let Some(name) = delete {
+            settings.delete_preset(&name);
+        }
+        ui.horizontal(|ui| {
             ui.add(
-                egui::Slider::new(&mut settings.master_volume, 0.0..=1.0)
-                    .text("Master Volume")
-                    .step_by(0.01),
+                egui::TextEdit::singleline(&mut settings.new_preset_name)
+                    .hint_text(tr("settings.text.preset_name"))
+                    .desired_width(160.0),
             );
+            let name = settings.new_preset_name.trim().to_string();
+            let label = if settings.presets.iter().any(|p| p.name == name) {
+                tr("settings.text.overwrite")
+            } else {
+                tr("settings.text.save_current")
+            };
+            if ui
+                .add_enabled(!name.is_empty(), egui::Button::new(label))
+                .clicked()
+            {
+                settings.save_preset(&name);
+                settings.new_preset_name.clear();
+                info!("Settings: Gameplay preset '{}' saved", name);
+            }
+        });
+    });
 
-            if (settings.master_volume - snapshot.master_volume).abs() > f32::EPSILON {
-                volume_changed = Some(settings.master_volume);
+    section.option(ui, &tr("settings.option.scroll_speed_mode"), |ui| {
+        ui.horizontal(|ui| {
+            ui.label(tr("settings.text.scroll_speed_mode"));
+            ui.radio_value(
+                &mut settings.scroll_speed_mode,
+                ScrollSpeedMode::CMod,
+                tr("settings.text.cmod"),
+            );
+            ui.radio_value(
+                &mut settings.scroll_speed_mode,
+                ScrollSpeedMode::MMod,
+                tr("settings.text.mmod"),
+            );
+        })
+        .response
+        .on_hover_text(tr("settings.hint.scroll_speed_mode"));
+    });
+
+    section.option(ui, &tr("settings.option.disable_sv"), |ui| {
+        ui.checkbox(&mut settings.disable_sv, tr("settings.option.disable_sv"))
+            .on_hover_text(tr("settings.hint.disable_sv"));
+    });
+
+    section.option(ui, &tr("settings.option.partial_results_on_quit"), |ui| {
+        ui.checkbox(
+            &mut settings.partial_results,
+            tr("settings.option.partial_results_on_quit"),
+        )
+        .on_hover_text(tr("settings.hint.partial_results"));
+    });
+
+    section.option(ui, &tr("settings.option.lane_cover"), |ui| {
+        let percent =
+            |value: f64, _: std::ops::RangeInclusive<usize>| format!("{:.0}%", value * 100.0);
+        ui.add(
+            egui::Slider::new(&mut settings.lane_cover.sudden, 0.0..=LaneCover::MAX)
+                .text(tr("settings.text.sudden"))
+                .custom_formatter(percent),
+        )
+        .on_hover_text(tr("settings.hint.sudden"));
+        ui.add(
+            egui::Slider::new(&mut settings.lane_cover.hidden, 0.0..=LaneCover::MAX)
+                .text(tr("settings.text.hidden"))
+                .custom_formatter(percent),
+        )
+        .on_hover_text(tr("settings.hint.hidden"));
+        // Same limit as the in-game keys.
+        settings.lane_cover.adjust(0.0, 0.0);
+    });
+
+    section.option(ui, &tr("settings.option.background_dim"), |ui| {
+        ui.add(
+            egui::Slider::new(&mut settings.background_dim, 0.0..=1.0)
+                .text(tr("settings.option.background_dim"))
+                .custom_formatter(|value, _| format!("{:.0}%", value * 100.0)),
+        )
+        .on_hover_text(tr("settings.hint.background_dim"));
+    });
+
+    section.option(ui, &tr("settings.option.background_blur"), |ui| {
+        ui.add(
+            egui::Slider::new(&mut settings.background_blur, 0.0..=32.0)
+                .text(tr("settings.option.background_blur"))
+                .step_by(1.0),
+        )
+        .on_hover_text(tr("settings.hint.background_blur"));
+    });
+
+    section.option(ui, &tr("settings.option.judgement"), |ui| {
+        if settings.tournament.enabled {
+            ui.label(tr_args(
+                "settings.text.judgement_locked",
+                &[(
+                    "judge",
+                    &settings.hit_window_mode.label(settings.hit_window_value),
+                )],
+            ));
+            return;
+        }
+        egui::ComboBox::from_label(tr("settings.option.judgement"))
+            .selected_text(match settings.hit_window_mode {
+                HitWindowMode::OsuOD => tr("settings.text.osu_od"),
+                HitWindowMode::EtternaJudge => tr("settings.text.etterna_judge"),
+                HitWindowMode::Custom(_) => tr("settings.text.custom_windows"),
+            })
+            .show_ui(ui, |ui| {
+                ui.selectable_value(
+                    &mut settings.hit_window_mode,
+                    HitWindowMode::OsuOD,
+                    tr("settings.text.osu_od"),
+                );
+                ui.selectable_value(
+                    &mut settings.hit_window_mode,
+                    HitWindowMode::EtternaJudge,
+                    tr("settings.text.etterna_judge"),
+                );
+                let custom = matches!(settings.hit_window_mode, HitWindowMode::Custom(_));
+                if ui
+                    .selectable_label(custom, tr("settings.text.custom_windows"))
+                    .clicked()
+                    && !custom
+                {
+                    if settings.custom_hit_window.validate().is_err() {
+                        settings.custom_hit_window = HitWindow::new();
+                    }
+                    settings.hit_window_mode = HitWindowMode::Custom(settings.custom_hit_window);
+                }
+            });
+
+        match settings.hit_window_mode {
+            HitWindowMode::OsuOD => {
+                ui.add(
+                    egui::Slider::new(&mut settings.hit_window_value, 0.0..=12.0)
+                        .text(tr("settings.text.overall_difficulty"))
+                        .step_by(0.1),
+                );
             }
+            HitWindowMode::EtternaJudge => {
+                ui.add(
+                    egui::Slider::new(&mut settings.hit_window_value, 1.0..=15.0)
+                        .text(tr("settings.text.judge"))
+                        .step_by(1.0),
+                );
+                settings.hit_window_value = settings.hit_window_value.round();
+            }
+            HitWindowMode::Custom(_) => custom_hit_window_options(ui, settings),
+        }
+    });
 
-            ui.separator();
-            ui.heading("Judgement");
-            egui::ComboBox::from_label("Mode")
-                .selected_text(match settings.hit_window_mode {
-                    HitWindowMode::OsuOD => "Osu! Overall Diff",
-                    HitWindowMode::EtternaJudge => "Etterna Judge",
-                })
+    section.option(ui, &tr("settings.option.result_sharing"), |ui| {
+        ui.label(tr("settings.text.result_sharing"));
+        ui.add(
+            egui::TextEdit::singleline(&mut settings.share_template).desired_width(f32::INFINITY),
+        )
+        .on_hover_text(tr("settings.hint.share_template"));
+        if ui.button(tr("settings.text.reset_share_text")).clicked() {
+            settings.share_template = DEFAULT_SHARE_TEMPLATE.to_string();
+        }
+    });
+
+    section.option(ui, &tr("settings.option.discord_presence"), |ui| {
+        ui.add_enabled_ui(Presence::available(), |ui| {
+            if ui
+                .checkbox(
+                    &mut settings.discord_presence,
+                    tr("settings.text.discord_rich_presence"),
+                )
+                .on_hover_text(tr("settings.hint.discord_presence"))
+                .on_disabled_hover_text(tr("settings.hint.discord_unavailable"))
+                .changed()
+            {
+                result.discord_presence_changed = Some(settings.discord_presence);
+            }
+        });
+    });
+
+    section.option(ui, &tr("settings.option.tournament_mode"), |ui| {
+        if tournament_options(ui, &mut settings.tournament) {
+            result.tournament_changed = Some(settings.tournament.clone());
+        }
+    });
+}
+
+/// Per-judgement windows of the custom mode. Only valid windows (widening
+/// from Marvelous to Miss) are applied.
+fn custom_hit_window_options(ui: &mut Ui, settings: &mut SettingsState) {
+    let windows = &mut settings.custom_hit_window;
+    egui::Grid::new("custom_hit_windows")
+        .num_columns(2)
+        .show(ui, |ui| {
+            for (label, ms) in [
+                ("settings.text.marvelous", &mut windows.marv_ms),
+                ("settings.text.perfect", &mut windows.perfect_ms),
+                ("settings.text.great", &mut windows.great_ms),
+                ("settings.text.good", &mut windows.good_ms),
+                ("settings.text.bad", &mut windows.bad_ms),
+                ("settings.text.miss", &mut windows.miss_ms),
+            ] {
+                ui.label(tr(label));
+                ui.add(
+                    egui::DragValue::new(ms)
+                        .range(1.0..=1000.0)
+                        .speed(0.5)
+                        .suffix(" ms"),
+                );
+                ui.end_row();
+            }
+        });
+
+    match settings.custom_hit_window.validate() {
+        Ok(()) => settings.hit_window_mode = HitWindowMode::Custom(settings.custom_hit_window),
+        Err(e) => {
+            ui.colored_label(egui::Color32::LIGHT_RED, e);
+        }
+    }
+}
+
+/// Returns whether the tournament settings changed. Leaving tournament mode
+/// takes the organizer password it was locked with.
+fn tournament_options(ui: &mut Ui, tournament: &mut TournamentSettings) -> bool {
+    let mut changed = false;
+    // Le mot de passe saisi reste en mémoire egui, jamais dans les réglages.
+    let password_id = egui::Id::new("tournament_password");
+    let wrong_id = egui::Id::new("tournament_password_wrong");
+    let mut password = ui
+        .data_mut(|d| d.get_temp::<String>(password_id))
+        .unwrap_or_default();
+    let mut wrong = ui
+        .data_mut(|d| d.get_temp::<bool>(wrong_id))
+        .unwrap_or(false);
+
+    ui.horizontal(|ui| {
+        ui.label(tr("settings.text.organizer_password"));
+        ui.add(egui::TextEdit::singleline(&mut password).password(true))
+            .on_hover_text(tr("settings.hint.organizer_password"));
+
+        if !tournament.enabled {
+            if ui
+                .add_enabled(
+                    !password.is_empty(),
+                    egui::Button::new(tr("settings.text.lock")),
+                )
+                .clicked()
+            {
+                tournament.lock(&password);
+                password.clear();
+                wrong = false;
+                changed = true;
+            }
+        } else {
+            if ui.button(tr("settings.text.unlock")).clicked() {
+                wrong = !tournament.unlock(&password);
+                changed = !wrong;
+                password.clear();
+            }
+            if tournament.signing_key.is_none()
+                && ui
+                    .button(tr("settings.text.resume_signing"))
+                    .on_hover_text(tr("settings.hint.resume_signing"))
+                    .clicked()
+            {
+                wrong = !tournament.resume(&password);
+                changed = !wrong;
+                password.clear();
+            }
+        }
+    });
+    if wrong {
+        ui.colored_label(egui::Color32::LIGHT_RED, tr("settings.text.wrong_password"));
+    } else if tournament.enabled && tournament.signing_key.is_none() {
+        ui.colored_label(egui::Color32::YELLOW, tr("settings.text.replays_unsigned"));
+    }
+    ui.data_mut(|d| {
+        d.insert_temp(password_id, password);
+        d.insert_temp(wrong_id, wrong);
+    });
+
+    ui.add_enabled_ui(!tournament.enabled, |ui| {
+        ui.horizontal_wrapped(|ui| {
+            ui.label(tr("settings.text.allowed_rates"));
+            let removable = tournament.rates.len() > 1;
+            let mut removed = None;
+            for (index, rate) in tournament.rates.iter_mut().enumerate() {
+                changed |= ui
+                    .add(
+                        egui::DragValue::new(rate)
+                            .range(0.5..=2.0)
+                            .speed(0.01)
+                            .fixed_decimals(2)
+                            .suffix("x"),
+                    )
+                    .changed();
+                if removable && ui.small_button("✖").clicked() {
+                    removed = Some(index);
+                }
+            }
+            if let Some(index) = removed {
+                tournament.rates.remove(index);
+                changed = true;
+            }
+            if ui.small_button("+").clicked() {
+                let next = tournament.rates.iter().copied().fold(1.0, f64::max) + 0.1;
+                tournament.rates.push(next.min(2.0));
+                changed = true;
+            }
+        });
+    });
+    changed
+}
+
+fn audio_page(section: &mut Section, ui: &mut Ui, settings: &mut SettingsState) {
+    for (key, channel, keys) in [
+        (
+            "settings.option.master_volume",
+            VolumeChannel::Master,
+            "Ctrl+Up / Ctrl+Down",
+        ),
+        (
+            "settings.option.music_volume",
+            VolumeChannel::Music,
+            "Ctrl+Right / Ctrl+Left",
+        ),
+        (
+            "settings.option.effects_volume",
+            VolumeChannel::Effects,
+            "Ctrl+PageUp / Ctrl+PageDown",
+        ),
+    ] {
+        let name = tr(key);
+        section.option(ui, &name, |ui| {
+            ui.add(
+                egui::Slider::new(settings.volume_mut(channel), 0.0..=1.0)
+                    .text(&name)
+                    .step_by(0.01),
+            )
+            .on_hover_text(tr_args("settings.quick_adjust", &[("keys", &keys)]));
+        });
+    }
+
+    section.option(ui, &tr("settings.option.rate_changes"), |ui| {
+        ui.horizontal(|ui| {
+            ui.label(tr("settings.text.rate_changes"));
+            ui.radio_value(
+                &mut settings.rate_mode,
+                RateMode::Pitch,
+                tr("settings.text.shift_pitch"),
+            );
+            ui.radio_value(
+                &mut settings.rate_mode,
+                RateMode::Stretch,
+                tr("settings.text.keep_pitch"),
+            );
+        })
+        .response
+        .on_hover_text(tr("settings.hint.rate_changes"));
+    });
+}
+
+fn video_page(
+    section: &mut Section,
+    ui: &mut Ui,
+    settings: &mut SettingsState,
+    monitors: &[MonitorInfo],
+) {
+    section.option(ui, &tr("settings.option.language"), |ui| {
+        let languages = locale::available();
+        let current = languages
+            .iter()
+            .find(|(code, _)| *code == settings.language)
+            .map_or(settings.language.clone(), |(_, name)| name.clone());
+        egui::ComboBox::from_id_salt("settings_language")
+            .selected_text(current)
+            .show_ui(ui, |ui| {
+                for (code, name) in languages {
+                    if ui
+                        .selectable_value(&mut settings.language, code.clone(), name)
+                        .changed()
+                    {
+                        locale::set_language(&code);
+                    }
+                }
+            });
+    });
+
+    section.option(ui, &tr("settings.option.display_mode"), |ui| {
+        egui::ComboBox::from_label(tr("settings.option.display_mode"))
+            .selected_text(settings.display_mode.label())
+            .show_ui(ui, |ui| {
+                for mode in [
+                    DisplayMode::Windowed,
+                    DisplayMode::Borderless,
+                    DisplayMode::Exclusive,
+                ] {
+                    ui.selectable_value(&mut settings.display_mode, mode, mode.label());
+                }
+            });
+    });
+
+    if monitors.len() > 1 {
+        section.option(ui, &tr("settings.option.monitor"), |ui| {
+            let name = |index: usize| {
+                monitors.get(index).map_or_else(
+                    || tr_args("settings.text.monitor_number", &[("number", &(index + 1))]),
+                    |m| m.name.clone(),
+                )
+            };
+            egui::ComboBox::from_label(tr("settings.option.monitor"))
+                .selected_text(name(settings.monitor))
                 .show_ui(ui, |ui| {
-                    ui.selectable_value(
-                        &mut settings.hit_window_mode,
-                        HitWindowMode::OsuOD,
-                        "Osu! Overall Diff",
-                    );
-                    ui.selectable_value(
-                        &mut settings.hit_window_mode,
-                        HitWindowMode::EtternaJudge,
-                        "Etterna Judge",
-                    );
+                    for index in 0..monitors.len() {
+                        ui.selectable_value(&mut settings.monitor, index, name(index));
+                    }
                 });
+        });
+    }
 
-            match settings.hit_window_mode {
-                HitWindowMode::OsuOD => {
-                    ui.add(
-                        egui::Slider::new(&mut settings.hit_window_value, 0.0..=12.0)
-                            .text("Overall Difficulty")
-                            .step_by(0.1),
-                    );
+    if settings.display_mode != DisplayMode::Borderless {
+        section.option(ui, &tr("settings.option.resolution"), |ui| {
+            let (width, height) = settings.resolution;
+            let resolutions = monitors
+                .get(settings.monitor)
+                .or(monitors.first())
+                .map_or(&[][..], |m| m.resolutions.as_slice());
+            egui::ComboBox::from_label(tr("settings.option.resolution"))
+                .selected_text(format!("{}x{}", width, height))
+                .show_ui(ui, |ui| {
+                    for &(w, h) in resolutions {
+                        ui.selectable_value(
+                            &mut settings.resolution,
+                            (w, h),
+                            format!("{}x{}", w, h),
+                        );
+                    }
+                });
+        });
+    }
+
+    section.option(ui, &tr("settings.option.present_mode"), |ui| {
+        egui::ComboBox::from_label(tr("settings.text.present_mode"))
+            .selected_text(settings.present_mode.label())
+            .show_ui(ui, |ui| {
+                for mode in [
+                    PresentModeSetting::Immediate,
+                    PresentModeSetting::Mailbox,
+                    PresentModeSetting::VSync,
+                ] {
+                    ui.selectable_value(&mut settings.present_mode, mode, mode.label());
                 }
-                HitWindowMode::EtternaJudge => {
-                    ui.add(
-                        egui::Slider::new(&mut settings.hit_window_value, 1.0..=15.0)
-                            .text("Judge")
-                            .step_by(1.0),
-                    );
-                    settings.hit_window_value = settings.hit_window_value.round();
+            })
+            .response
+            .on_hover_text(tr("settings.hint.present_mode"));
+    });
+
+    section.option(ui, &tr("settings.option.fps_cap"), |ui| {
+        ui.add(
+            egui::Slider::new(&mut settings.fps_cap, 0..=1000)
+                .text(tr("settings.option.fps_cap"))
+                .custom_formatter(|value, _| {
+                    if value == 0.0 {
+                        tr("settings.text.unlimited")
+                    } else {
+                        format!("{:.0}", value)
+                    }
+                }),
+        )
+        .on_hover_text(tr("settings.hint.fps_cap"));
+    });
+
+    section.option(ui, &tr("settings.option.record_gameplay"), |ui| {
+        ui.checkbox(
+            &mut settings.record_gameplay,
+            tr("settings.text.record_plays"),
+        )
+        .on_hover_text(tr("settings.hint.record_gameplay"));
+        ui.add_enabled(
+            settings.record_gameplay,
+            egui::Slider::new(&mut settings.record_fps, 24..=120)
+                .text(tr("settings.text.recording_fps")),
+        );
+    });
+
+    section.option(ui, &tr("settings.option.chart_preview"), |ui| {
+        ui.checkbox(
+            &mut settings.chart_preview,
+            tr("settings.text.chart_preview"),
+        )
+        .on_hover_text(tr("settings.hint.chart_preview"));
+    });
+
+    section.option(ui, &tr("settings.option.judgement_colors"), |ui| {
+        egui::ComboBox::from_id_salt("settings_judgement_palette")
+            .selected_text(settings.judgement_palette.label())
+            .show_ui(ui, |ui| {
+                for palette in JudgementPalette::ALL {
+                    ui.selectable_value(&mut settings.judgement_palette, palette, palette.label());
                 }
-            }
+            })
+            .response
+            .on_hover_text(tr("settings.hint.judgement_colors"));
+    });
 
-            ui.separator();
-            ui.heading("Keybinds");
-            ui.label("Choose a keymode below, then press the required keys in order.");
-            let mut columns: Vec<_> = settings.keybinds.keys().cloned().collect();
-            columns.sort_by_key(|key| key.parse::<usize>().unwrap_or(0));
-            for column in columns {
-                let Ok(column_count) = column.parse::<usize>() else {
-                    continue;
+    section.option(ui, &tr("settings.option.song_list_scrolling"), |ui| {
+        let scroll = &mut settings.song_list_scroll;
+        ui.checkbox(&mut scroll.smooth, tr("settings.text.smooth_scrolling"))
+            .on_hover_text(tr("settings.hint.smooth_scrolling"));
+        ui.add_enabled_ui(scroll.smooth, |ui| {
+            ui.add(
+                egui::Slider::new(&mut scroll.duration_ms, 50.0..=1000.0)
+                    .text(tr("settings.text.jump_duration")),
+            );
+            egui::ComboBox::from_label(tr("settings.text.easing"))
+                .selected_text(scroll.easing.label())
+                .show_ui(ui, |ui| {
+                    for easing in [
+                        ScrollEasing::Linear,
+                        ScrollEasing::EaseOut,
+                        ScrollEasing::EaseInOut,
+                    ] {
+                        ui.selectable_value(&mut scroll.easing, easing, easing.label());
+                    }
+                });
+        });
+    });
+}
+
+fn input_page(section: &mut Section, ui: &mut Ui, settings: &mut SettingsState) {
+    section.option(ui, &tr("settings.option.mouse_input"), |ui| {
+        ui.checkbox(&mut settings.mouse_input, tr("settings.option.mouse_input"))
+            .on_hover_text(tr("settings.hint.mouse_input"));
+    });
+
+    section.option(ui, &tr("settings.option.keybinds"), |ui| {
+        ui.label(tr("settings.text.keybinds_note"));
+        let mut columns: Vec<_> = settings.keybinds.keys().cloned().collect();
+        columns.sort_by_key(|key| key.parse::<usize>().unwrap_or(0));
+        for column in columns {
+            let Ok(column_count) = column.parse::<usize>() else {
+                continue;
+            };
+            for pad in [false, true] {
+                let binds = if pad {
+                    &settings.pad_keybinds
+                } else {
+                    &settings.keybinds
                 };
-                let existing = settings
-                    .keybinds
-                    .get(&column)
-                    .cloned()
-                    .unwrap_or_default()
-                    .join(", ");
+                let existing = binds.get(&column).cloned().unwrap_or_default();
                 ui.horizontal(|ui| {
-                    ui.label(format!("{:>2}K", column_count));
-                    let label = if existing.is_empty() {
-                        "(no keys set)".to_string()
+                    if pad {
+                        ui.label(format!("   {}", tr("settings.text.pad")));
                     } else {
-                        existing.clone()
-                    };
-                    ui.label(label);
+                        ui.label(format!("{:>2}K", column_count));
+                    }
 
-                    if settings.remapping_column == Some(column_count) {
-                        ui.label(format!(
-                            "Listening... {}/{}",
-                            settings.remapping_buffer.len(),
-                            column_count
-                        ));
-                        if ui.button("Cancel").clicked() {
+                    let capturing = settings.remapping_column == Some(column_count)
+                        && settings.remapping_pad == pad;
+                    for slot in 0..column_count {
+                        let text = if capturing && settings.remapping_slot == Some(slot) {
+                            "...".to_string()
+                        } else {
+                            match existing.get(slot) {
+                                Some(label) if !label.is_empty() => label.clone(),
+                                _ => "-".to_string(),
+                            }
+                        };
+                        if ui
+                            .small_button(text)
+                            .on_hover_text(tr_args(
+                                "settings.hint.rebind_column",
+                                &[("column", &(slot + 1))],
+                            ))
+                            .clicked()
+                        {
+                            settings.begin_slot_capture(column_count, slot, pad);
+                        }
+                    }
+
+                    if capturing {
+                        if settings.remapping_slot.is_some() {
+                            ui.label(tr("settings.text.listening"));
+                        } else {
+                            ui.label(tr_args(
+                                "settings.text.listening_count",
+                                &[
+                                    ("done", &settings.remapping_buffer.len()),
+                                    ("total", &column_count),
+                                ],
+                            ));
+                        }
+                        if ui.button(tr("settings.text.cancel")).clicked() {
                             settings.cancel_keybind_capture();
                         }
-                    } else if ui.button("Rebind").clicked() {
-                        settings.begin_keybind_capture(column_count);
+                    } else if ui.button(tr("settings.text.rebind_all")).clicked() {
+                        if pad {
+                            settings.begin_pad_capture(column_count);
+                        } else {
+                            settings.begin_keybind_capture(column_count);
+                        }
                     }
                 });
             }
-            if ui.button("Reset keybinds to defaults").clicked() {
-                settings.reset_keybinds();
-                settings.cancel_keybind_capture();
-            }
+        }
+        for conflict in settings.keybind_conflicts() {
+            ui.colored_label(egui::Color32::from_rgb(255, 170, 60), conflict);
+        }
+        if ui.button(tr("settings.text.reset_keybinds")).clicked() {
+            settings.reset_keybinds();
+            settings.cancel_keybind_capture();
+        }
+    });
 
-            if ui.button("Save").clicked() {
-                settings.save();
+    section.option(ui, &tr("settings.option.lane_order"), |ui| {
+        ui.label(tr("settings.text.lane_order_note"));
+        let mut key_counts: Vec<usize> = settings
+            .keybinds
+            .keys()
+            .filter_map(|key| key.parse().ok())
+            .collect();
+        key_counts.sort_unstable();
+        for key_count in key_counts {
+            let order = settings
+                .lane_order(key_count)
+                .unwrap_or_else(|| (0..key_count).collect());
+            ui.horizontal(|ui| {
+                ui.label(format!("{:>2}K", key_count));
+                for lane in 0..key_count {
+                    let column = order.iter().position(|&l| l == lane).unwrap_or(lane);
+                    let picked = settings.lane_swap_from == Some((key_count, lane));
+                    if ui
+                        .selectable_label(picked, format!("{}", column + 1))
+                        .on_hover_text(tr_args(
+                            "settings.hint.lane",
+                            &[("lane", &(lane + 1)), ("column", &(column + 1))],
+                        ))
+                        .clicked()
+                    {
+                        // A second click on the picked lane just unpicks it.
+                        match settings.lane_swap_from.take() {
+                            Some((count, from)) if count == key_count => {
+                                if from != lane {
+                                    settings.swap_lanes(key_count, from, lane);
+                                }
+                            }
+                            _ => settings.lane_swap_from = Some((key_count, lane)),
+                        }
+                    }
+                }
+                if settings.lane_order(key_count).is_some()
+                    && ui.button(tr("settings.text.reset")).clicked()
+                {
+                    settings.lane_orders.remove(&key_count.to_string());
+                    settings.lane_swap_from = None;
+                }
+            });
+        }
+    });
+}
 
-                if settings.hit_window_mode != snapshot.hit_window_mode
-                    || (settings.hit_window_value - snapshot.hit_window_value).abs() > f64::EPSILON
+fn skin_page(
+    section: &mut Section,
+    ui: &mut Ui,
+    settings: &mut SettingsState,
+    result: &mut SettingsWindowResult,
+) {
+    section.option(ui, &tr("settings.option.skin"), |ui| {
+        let mut skins = vec!["default".to_string()];
+        if let Ok(entries) = std::fs::read_dir("skins") {
+            for entry in entries.flatten() {
+                // Hidden folders are imports still being extracted.
+                if entry.path().is_dir()
+                    && let Some(name) = entry.file_name().to_str()
+                    && name != "default"
+                    && !name.starts_with('.')
                 {
-                    info!(
-                        "Settings: Hit window updated -> mode {:?}, value {:.2}",
-                        settings.hit_window_mode, settings.hit_window_value
-                    );
-                    hit_window_changed =
-                        Some((settings.hit_window_mode, settings.hit_window_value));
+                    skins.push(name.to_string());
+                }
+            }
+        }
+        egui::ComboBox::from_label(tr("settings.option.skin"))
+            .selected_text(&settings.current_skin)
+            .show_ui(ui, |ui| {
+                for skin_name in skins {
+                    ui.selectable_value(&mut settings.current_skin, skin_name.clone(), skin_name);
+                }
+            });
+    });
+    section.option(ui, &tr("settings.option.import_skin_package"), |ui| {
+        if ui.button(tr("settings.text.import_skin")).clicked() {
+            result.skin_package_picked = rfd::FileDialog::new()
+                .add_filter(tr("settings.text.skin_package"), &[package::EXTENSION])
+                .pick_file();
+        }
+    });
+}
+
+fn library_page(
+    section: &mut Section,
+    ui: &mut Ui,
+    settings: &mut SettingsState,
+    library: Option<&LibraryOverview<'_>>,
+    result: &mut SettingsWindowResult,
+) {
+    section.option(ui, &tr("settings.option.bms_scratch"), |ui| {
+        let scratch_label = |mode: BmsScratchMode| match mode {
+            BmsScratchMode::Left => tr("settings.text.scratch_left"),
+            BmsScratchMode::Right => tr("settings.text.scratch_right"),
+            BmsScratchMode::Drop => tr("settings.text.scratch_drop"),
+        };
+        egui::ComboBox::from_label(tr("settings.text.bms_scratch"))
+            .selected_text(scratch_label(settings.bms_scratch))
+            .show_ui(ui, |ui| {
+                for mode in [
+                    BmsScratchMode::Left,
+                    BmsScratchMode::Right,
+                    BmsScratchMode::Drop,
+                ] {
+                    ui.selectable_value(&mut settings.bms_scratch, mode, scratch_label(mode));
                 }
+            })
+            .response
+            .on_hover_text(tr("settings.hint.bms_scratch"));
+    });
 
-                if (settings.master_volume - snapshot.master_volume).abs() > f32::EPSILON {
-                    info!(
-                        "Settings: Master volume updated -> {:.2}",
-                        settings.master_volume
-                    );
+    section.option(ui, &tr("settings.option.osu_mines_and_bursts"), |ui| {
+        ui.label(tr("settings.text.osu_conversion"));
+        let rules = &mut settings.osu_convert;
+        for (label, hitsound) in [
+            ("settings.text.taps_to_mines", &mut rules.mine_hitsound),
+            ("settings.text.holds_to_bursts", &mut rules.burst_hitsound),
+        ] {
+            egui::ComboBox::from_label(tr(label))
+                .selected_text(hitsound.label())
+                .show_ui(ui, |ui| {
+                    for option in OsuHitsound::ALL {
+                        ui.selectable_value(hitsound, option, option.label());
+                    }
+                });
+        }
+        ui.add_enabled(
+            rules.burst_hitsound != OsuHitsound::None,
+            egui::Slider::new(&mut rules.burst_hit_interval_ms, 50..=500)
+                .suffix(" ms")
+                .text(tr("settings.text.burst_interval")),
+        )
+        .on_hover_text(tr("settings.hint.burst_interval"));
+        ui.label(
+            egui::RichText::new(tr("settings.text.osu_applies"))
+                .small()
+                .weak(),
+        );
+    });
+
+    section.option(ui, &tr("settings.option.title_and_artist_language"), |ui| {
+        ui.horizontal(|ui| {
+            let before = settings.unicode_metadata;
+            ui.radio_value(
+                &mut settings.unicode_metadata,
+                false,
+                tr("settings.text.romanized"),
+            );
+            ui.radio_value(
+                &mut settings.unicode_metadata,
+                true,
+                tr("settings.text.original"),
+            )
+            .on_hover_text(tr("settings.hint.original_metadata"));
+            if settings.unicode_metadata != before {
+                result.unicode_metadata_changed = Some(settings.unicode_metadata);
+            }
+        });
+    });
+
+    section.option(ui, &tr("settings.option.library_folders"), |ui| {
+        ui.label(tr("settings.text.library_folders"));
+        let mut removed = None;
+        let removable = settings.library_roots.len() > 1;
+        for (i, root) in settings.library_roots.iter().enumerate() {
+            ui.horizontal(|ui| {
+                let label = if i == 0 {
+                    tr_args(
+                        "settings.text.downloads_folder",
+                        &[("folder", &root.display())],
+                    )
+                } else {
+                    root.display().to_string()
+                };
+                if !root.is_dir() {
+                    ui.colored_label(egui::Color32::LIGHT_RED, label)
+                        .on_hover_text(tr("settings.hint.missing_folder"));
+                } else {
+                    ui.label(label);
+                }
+                if ui
+                    .add_enabled(removable, egui::Button::new(tr("settings.text.remove")))
+                    .clicked()
+                {
+                    removed = Some(i);
                 }
+            });
+        }
+        let mut changed = false;
+        if let Some(i) = removed {
+            settings.library_roots.remove(i);
+            changed = true;
+        }
+        if ui
+            .button(tr("settings.text.add_folder"))
+            .on_hover_text(tr("settings.hint.add_folder"))
+            .clicked()
+            && let Some(folder) = rfd::FileDialog::new().pick_folder()
+            && !settings.library_roots.contains(&folder)
+        {
+            settings.library_roots.push(folder);
+            changed = true;
+        }
+        if changed {
+            settings.save();
+            info!("Settings: Library folders -> {:?}", settings.library_roots);
+            result.library_roots_changed = Some(settings.song_roots());
+        }
+    });
 
-                info!("Settings: Keybinds saved");
-                keybinds_updated = true;
+    section.option(ui, &tr("settings.option.scan_threads"), |ui| {
+        ui.horizontal(|ui| {
+            ui.label(tr("settings.text.scan_threads"));
+            let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+            ui.add(
+                egui::Slider::new(&mut settings.scan_workers, 0..=cores.max(1)).custom_formatter(
+                    |n, _| {
+                        if n == 0.0 {
+                            tr("settings.text.auto")
+                        } else {
+                            format!("{}", n)
+                        }
+                    },
+                ),
+            )
+            .on_hover_text(tr("settings.hint.scan_threads"));
+        });
+    });
 
-                request_toggle = true;
+    section.option(ui, &tr("settings.option.beatmap_mirror"), |ui| {
+        ui.horizontal(|ui| {
+            ui.label(tr("settings.text.beatmap_mirror"));
+            ui.text_edit_singleline(&mut settings.mirror_url)
+                .on_hover_text(tr("settings.hint.beatmap_mirror"));
+            if ui.button(tr("settings.text.default")).clicked() {
+                settings.mirror_url = DEFAULT_MIRROR_URL.to_string();
             }
         });
+    });
+
+    if let Some(library) = library {
+        section.option(ui, &tr("settings.option.library_overview"), |ui| {
+            egui::CollapsingHeader::new(tr("settings.option.library_overview")).show(ui, |ui| {
+                result.library_stats_requested = render_library_overview(
+                    ui,
+                    library.stats,
+                    library.progress,
+                    &mut settings.library_key_mode,
+                );
+            });
+        });
+
+        section.option(ui, &tr("settings.option.maintenance"), |ui| {
+            egui::CollapsingHeader::new(tr("settings.option.maintenance")).show(ui, |ui| {
+                result.maintenance_requested =
+                    maintenance_panel(ui, &mut settings.replay_prune, library.maintenance);
+            });
+        });
+    }
+}
+
+/// Vacuum and replay pruning, with the report of the last command. A prune
+/// is previewed first and only runs once confirmed.
+fn maintenance_panel(
+    ui: &mut Ui,
+    rules: &mut ReplayPruneRules,
+    report: Option<&MaintenanceReport>,
+) -> Option<MaintenanceRequest> {
+    let mut request = None;
+    let running = report == Some(&MaintenanceReport::Running);
+
+    ui.horizontal(|ui| {
+        if ui
+            .add_enabled(
+                !running,
+                egui::Button::new(tr("settings.text.compact_database")),
+            )
+            .on_hover_text(tr("settings.hint.compact_database"))
+            .clicked()
+        {
+            request = Some(MaintenanceRequest::Vacuum);
+        }
+    });
 
-    if !open {
-        request_toggle = true;
+    ui.separator();
+    ui.label(tr("settings.text.prune_replays"));
+    ui.add(
+        egui::Slider::new(&mut rules.below_accuracy, 0.0..=100.0)
+            .suffix("%")
+            .text(tr("settings.text.below_accuracy"))
+            .custom_formatter(|value, _| {
+                if value == 0.0 {
+                    tr("settings.text.off")
+                } else {
+                    format!("{:.1}", value)
+                }
+            }),
+    );
+    ui.add(
+        egui::Slider::new(&mut rules.older_than_days, 0..=730)
+            .text(tr("settings.text.older_than"))
+            .custom_formatter(|value, _| {
+                if value == 0.0 {
+                    tr("settings.text.off")
+                } else {
+                    format!("{:.0}", value)
+                }
+            }),
+    );
+    if ui
+        .add_enabled(
+            !running && rules.is_enabled(),
+            egui::Button::new(tr("settings.text.find_replays")),
+        )
+        .clicked()
+    {
+        request = Some(MaintenanceRequest::PreviewPrune(*rules));
     }
 
-    SettingsWindowResult {
-        request_toggle,
-        volume_changed,
-        keybinds_updated,
-        hit_window_changed,
+    match report {
+        None => {}
+        Some(MaintenanceReport::Running) => {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(tr("settings.text.working"));
+            });
+        }
+        Some(MaintenanceReport::PrunePreview { replays: 0, .. }) => {
+            ui.label(tr("settings.text.no_prune_match"));
+        }
+        Some(MaintenanceReport::PrunePreview {
+            rules: previewed,
+            replays,
+            bytes,
+        }) => {
+            ui.colored_label(
+                egui::Color32::from_rgb(230, 180, 80),
+                tr_args(
+                    "settings.text.prune_preview",
+                    &[("replays", replays), ("size", &format_size(*bytes))],
+                ),
+            );
+            ui.horizontal(|ui| {
+                if ui.button(tr("settings.text.delete")).clicked() {
+                    request = Some(MaintenanceRequest::Prune(*previewed));
+                }
+                if ui.button(tr("settings.text.cancel")).clicked() {
+                    request = Some(MaintenanceRequest::Dismiss);
+                }
+            });
+        }
+        Some(MaintenanceReport::Pruned { replays, bytes }) => {
+            ui.label(tr_args(
+                "settings.text.pruned",
+                &[("replays", replays), ("size", &format_size(*bytes))],
+            ));
+        }
+        Some(MaintenanceReport::Vacuumed { before, after }) => {
+            ui.label(tr_args(
+                "settings.text.vacuumed",
+                &[
+                    ("before", &format_size(*before)),
+                    ("after", &format_size(*after)),
+                    ("reclaimed", &format_size(before.saturating_sub(*after))),
+                ],
+            ));
+        }
+        Some(MaintenanceReport::Failed(e)) => {
+            ui.colored_label(egui::Color32::LIGHT_RED, e);
+        }
     }
+    request
 }